@@ -0,0 +1,26 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Audio.swift
+//  SwiftAppWindow
+//
+//  Audio hints.  macOS doesn't duck apps, but App Nap can throttle a backgrounded app's
+//  timers and threads enough to cause audio glitches.
+//
+import Foundation
+
+@MainActor private var backgroundAudioActivity: NSObjectProtocol?
+
+@_cdecl("SwiftAppWindow_SetBackgroundAudio") public func SetBackgroundAudio(enabled: Bool) {
+    MainActor.assumeIsolated {
+        if enabled {
+            guard backgroundAudioActivity == nil else { return }
+            backgroundAudioActivity = ProcessInfo.processInfo.beginActivity(options: [.userInitiated, .latencyCritical], reason: "Playing audio")
+        } else if let activity = backgroundAudioActivity {
+            ProcessInfo.processInfo.endActivity(activity)
+            backgroundAudioActivity = nil
+        }
+    }
+}
+#endif
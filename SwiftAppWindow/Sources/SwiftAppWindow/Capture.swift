@@ -0,0 +1,20 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Capture.swift
+//  SwiftAppWindow
+//
+//  Screen Recording permission.  Without it, captures only show the wallpaper and our own windows.
+//
+import CoreGraphics
+
+@_cdecl("SwiftAppWindow_CapturePreflight") public func CapturePreflight() -> Bool {
+    CGPreflightScreenCaptureAccess()
+}
+
+///Prompts the first time; afterwards the user has to go to System Settings
+@_cdecl("SwiftAppWindow_CaptureRequest") public func CaptureRequest() -> Bool {
+    CGRequestScreenCaptureAccess()
+}
+#endif
@@ -0,0 +1,15 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Clipboard.swift
+//  SwiftAppWindow
+//
+//  The pasteboard's change count, which Rust polls to notice clipboard changes.
+//
+import AppKit
+
+@_cdecl("SwiftAppWindow_PasteboardChangeCount") public func PasteboardChangeCount() -> Int {
+    NSPasteboard.general.changeCount
+}
+#endif
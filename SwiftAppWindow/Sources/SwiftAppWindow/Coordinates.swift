@@ -1,4 +1,5 @@
 //SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
 
 //
 //  Coordinates.swift
@@ -13,3 +14,15 @@ extension NSPoint {
         self.init(x: rustX, y: outerBounds.height - rustY)
     }
 }
+extension NSWindow {
+    ///The frame's origin as Rust positions windows: the inverse of `NSPoint(rustX:rustY:outerBounds:)` on the screen's frame.
+    var rustOrigin: NSPoint? {
+        rustOrigin(of: frame)
+    }
+    ///Where `frame` would put the window, as for `rustOrigin`.
+    func rustOrigin(of frame: NSRect) -> NSPoint? {
+        guard let screen else { return nil }
+        return NSPoint(x: frame.origin.x, y: screen.frame.height - frame.origin.y)
+    }
+}
+#endif
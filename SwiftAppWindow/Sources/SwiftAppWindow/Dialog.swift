@@ -0,0 +1,39 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Dialog.swift
+//  SwiftAppWindow
+//
+//  System dialogs.  The color picker is NSColorSampler, the same eyedropper the color panel uses.
+//
+import AppKit
+
+///Result codes shared with Rust (see sys/macos.rs)
+enum PickColorResult: Int32 {
+    case ok = 0
+    case cancelled = 1
+}
+
+///The sampler has to stay alive while it's on screen
+@MainActor private var activeSampler: NSColorSampler?
+
+@_cdecl("SwiftAppWindow_PickColor") public func PickColor(context: UInt64, ret: @convention(c) @Sendable (UInt64, Int32, Double, Double, Double) -> ()) {
+    Task {
+        await MainActor.run {
+            let sampler = NSColorSampler()
+            activeSampler = sampler
+            sampler.show { color in
+                MainActor.assumeIsolated {
+                    activeSampler = nil
+                }
+                guard let color = color?.usingColorSpace(.sRGB) else {
+                    ret(context, PickColorResult.cancelled.rawValue, 0, 0, 0)
+                    return
+                }
+                ret(context, PickColorResult.ok.rawValue, color.redComponent, color.greenComponent, color.blueComponent)
+            }
+        }
+    }
+}
+#endif
@@ -0,0 +1,110 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Drag.swift
+//  SwiftAppWindow
+//
+//  Outgoing drag-and-drop.
+//
+import AppKit
+import SwiftRs
+import UniformTypeIdentifiers
+
+///Result codes shared with Rust (see sys/macos.rs)
+enum StartDragResult: Int32 {
+    case ok = 0
+    case noPointerGrab = 1
+}
+
+final class DragSource: NSObject, NSDraggingSource {
+    let context: UInt64
+    ///Rust's current mask; the app can change it mid-drag
+    let operations: @convention(c) @Sendable (UInt64) -> Int
+    let ended: @convention(c) @Sendable (UInt64, Int) -> ()
+
+    init(context: UInt64, operations: @escaping @convention(c) @Sendable (UInt64) -> Int, ended: @escaping @convention(c) @Sendable (UInt64, Int) -> ()) {
+        self.context = context
+        self.operations = operations
+        self.ended = ended
+    }
+
+    func draggingSession(_ session: NSDraggingSession, sourceOperationMaskFor context: NSDraggingContext) -> NSDragOperation {
+        NSDragOperation(rawValue: UInt(operations(self.context)))
+    }
+
+    func draggingSession(_ session: NSDraggingSession, endedAt screenPoint: NSPoint, operation: NSDragOperation) {
+        ended(context, Int(operation.rawValue))
+        MainActor.assumeIsolated {
+            activeDragSource = nil
+        }
+    }
+}
+
+///The session doesn't keep its source alive, so we do until it ends
+@MainActor private var activeDragSource: DragSource?
+
+///Non-premultiplied RGBA8 rows, as sent from Rust
+func rgbaImage(rgba: [UInt8], width: Int) -> NSImage? {
+    guard width > 0 else { return nil }
+    let height = rgba.count / (width * 4)
+    guard let rep = NSBitmapImageRep(bitmapDataPlanes: nil, pixelsWide: width, pixelsHigh: height, bitsPerSample: 8, samplesPerPixel: 4, hasAlpha: true, isPlanar: false, colorSpaceName: .deviceRGB, bitmapFormat: [.alphaNonpremultiplied], bytesPerRow: width * 4, bitsPerPixel: 32), let bitmapData = rep.bitmapData else {
+        return nil
+    }
+    bitmapData.update(from: rgba, count: rgba.count)
+    let image = NSImage(size: NSSize(width: width, height: height))
+    image.addRepresentation(rep)
+    return image
+}
+
+extension Window {
+    @MainActor func startDrag(mime: String, data: Data, image: NSImage?, source: DragSource) -> StartDragResult {
+        guard let window = self.window, let view = window.contentView as? SurfaceView, let event = view.lastMouseEvent else {
+            return .noPointerGrab
+        }
+        var items: [NSDraggingItem] = []
+        if mime == "text/uri-list" {
+            //one item per file so Finder and friends see real file URLs
+            let list = String(decoding: data, as: UTF8.self)
+            for line in list.components(separatedBy: "\r\n") where !line.isEmpty && !line.hasPrefix("#") {
+                if let url = NSURL(string: line) {
+                    items.append(NSDraggingItem(pasteboardWriter: url))
+                }
+            }
+        } else {
+            let pasteboardItem = NSPasteboardItem()
+            let typeName = UTType(mimeType: mime)?.identifier ?? mime
+            pasteboardItem.setData(data, forType: NSPasteboard.PasteboardType(typeName))
+            items.append(NSDraggingItem(pasteboardWriter: pasteboardItem))
+        }
+        let location = view.convert(event.locationInWindow, from: nil)
+        let imageSize = image?.size ?? .zero
+        for item in items {
+            item.setDraggingFrame(NSRect(origin: location, size: imageSize), contents: image)
+        }
+        activeDragSource = source
+        view.beginDraggingSession(with: items, event: event, source: source)
+        return .ok
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowStartDrag") public func WindowStartDrag(context: UInt64, window: UnsafeMutableRawPointer, mime: SRString, payload: SRData, image: SRData, imageWidth: Int, endContext: UInt64, operations: @escaping @convention(c) @Sendable (UInt64) -> Int, ended: @escaping @convention(c) @Sendable (UInt64, Int) -> (), ret: @convention(c) @Sendable (UInt64, Int32) -> ()) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    //copy everything now; Rust frees its buffers once we return
+    let mime = mime.toString()
+    let data = Data(payload.toArray())
+    let rgba = image.toArray()
+    Task {
+        await MainActor.run {
+            let nsImage = rgbaImage(rgba: rgba, width: imageWidth)
+            let source = DragSource(context: endContext, operations: operations, ended: ended)
+            let result = window.startDrag(mime: mime, data: data, image: nsImage, source: source)
+            if result != .ok {
+                //no session, so no end; let Rust free its side
+                ended(endContext, 0)
+            }
+            ret(context, result.rawValue)
+        }
+    }
+}
+#endif
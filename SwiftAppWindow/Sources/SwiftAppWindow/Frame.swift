@@ -0,0 +1,45 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Frame.swift
+//  SwiftAppWindow
+//
+//  Frame callbacks for Window::request_redraw, from a display link on the window's view.
+//
+import AppKit
+import QuartzCore
+
+@MainActor final class FrameRequester: NSObject {
+    ///Called with how long until the frame is displayed, and the display's refresh interval, in seconds.
+    typealias Callback = @convention(c) @Sendable (UInt64, Double, Double) -> ()
+    private var link: CADisplayLink?
+    private var pending: [(context: UInt64, call: Callback)] = []
+
+    func request(view: NSView, context: UInt64, call: @escaping Callback) {
+        pending.append((context, call))
+        guard link == nil else { return }
+        //the view's link follows the window between displays
+        let link = view.displayLink(target: self, selector: #selector(tick))
+        link.add(to: .main, forMode: .common)
+        self.link = link
+    }
+
+    @objc func tick(_ link: CADisplayLink) {
+        guard !pending.isEmpty else {
+            //nobody asked for this frame, so stop until somebody does; this also lets go of us
+            link.invalidate()
+            self.link = nil
+            return
+        }
+        let pending = self.pending
+        self.pending = []
+        //Rust's clock isn't CACurrentMediaTime, so it gets the time remaining instead
+        let untilTarget = link.targetTimestamp - CACurrentMediaTime()
+        let interval = link.targetTimestamp - link.timestamp
+        for request in pending {
+            request.call(request.context, untilTarget, interval)
+        }
+    }
+}
+#endif
@@ -0,0 +1,81 @@
+//SPDX-License-Identifier: MPL-2.0
+
+//
+//  Notification.swift
+//  SwiftAppWindow
+//
+//  Notifications with UNUserNotificationCenter, on macOS and iOS alike.
+//
+import CoreGraphics
+import Foundation
+import ImageIO
+import SwiftRs
+import UniformTypeIdentifiers
+import UserNotifications
+
+///Result codes shared with Rust (see sys/macos.rs)
+enum PostNotificationResult: Int32 {
+    case ok = 0
+    case unsupported = 1
+    case denied = 2
+    case failed = 3
+}
+
+///Shows our notifications while the app is in front, which the system otherwise doesn't
+final class NotificationPresenter: NSObject, UNUserNotificationCenterDelegate, Sendable {
+    func userNotificationCenter(_ center: UNUserNotificationCenter, willPresent notification: UNNotification) async -> UNNotificationPresentationOptions {
+        [.banner, .list, .sound]
+    }
+}
+
+///The center only holds its delegate weakly
+private let presenter = NotificationPresenter()
+
+///Writes the image to a PNG for an attachment, which the center moves into its own store
+func notificationImageFile(rgba: [UInt8], width: Int) -> URL? {
+    guard width > 0, rgba.count >= width * 4, let space = CGColorSpace(name: CGColorSpace.sRGB) else { return nil }
+    let height = rgba.count / (width * 4)
+    guard let provider = CGDataProvider(data: Data(rgba) as CFData),
+          let image = CGImage(width: width, height: height, bitsPerComponent: 8, bitsPerPixel: 32, bytesPerRow: width * 4, space: space, bitmapInfo: CGBitmapInfo(rawValue: CGImageAlphaInfo.last.rawValue), provider: provider, decode: nil, shouldInterpolate: true, intent: .defaultIntent) else {
+        return nil
+    }
+    let url = FileManager.default.temporaryDirectory.appendingPathComponent("\(UUID().uuidString).png")
+    guard let destination = CGImageDestinationCreateWithURL(url as CFURL, UTType.png.identifier as CFString, 1, nil) else { return nil }
+    CGImageDestinationAddImage(destination, image, nil)
+    return CGImageDestinationFinalize(destination) ? url : nil
+}
+
+@_cdecl("SwiftAppWindow_PostNotification") public func PostNotification(context: UInt64, title: SRString, body: SRString, rgba: SRData, width: Int, ret: @escaping @convention(c) @Sendable (UInt64, Int32) -> ()) {
+    //the center raises outside an app bundle
+    guard Bundle.main.bundleIdentifier != nil else {
+        ret(context, PostNotificationResult.unsupported.rawValue)
+        return
+    }
+    let title = title.toString()
+    let body = body.toString()
+    //copy now; Rust frees its buffer once we return
+    let bytes = rgba.toArray()
+    Task {
+        let center = UNUserNotificationCenter.current()
+        if center.delegate == nil {
+            center.delegate = presenter
+        }
+        do {
+            //only asks the user the first time
+            guard try await center.requestAuthorization(options: [.alert, .sound]) else {
+                ret(context, PostNotificationResult.denied.rawValue)
+                return
+            }
+            let content = UNMutableNotificationContent()
+            content.title = title
+            content.body = body
+            if let url = notificationImageFile(rgba: bytes, width: width), let attachment = try? UNNotificationAttachment(identifier: "icon", url: url) {
+                content.attachments = [attachment]
+            }
+            try await center.add(UNNotificationRequest(identifier: UUID().uuidString, content: content, trigger: nil))
+            ret(context, PostNotificationResult.ok.rawValue)
+        } catch {
+            ret(context, PostNotificationResult.failed.rawValue)
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Performance.swift
+//  SwiftAppWindow
+//
+//  Performance hints, as an NSProcessInfo activity.  Any user-initiated activity keeps App Nap
+//  away; latency-critical ones also ask the system not to coalesce our timers.
+//
+//  On Macs with automatic graphics switching, creating the system default Metal device switches
+//  to the discrete GPU until it's released.
+//
+import Foundation
+import Metal
+
+@MainActor private var performanceActivity: NSObjectProtocol?
+@MainActor private var discreteGPU: (any MTLDevice)?
+
+@_cdecl("SwiftAppWindow_SetPreferDiscreteGPU") public func SetPreferDiscreteGPU(prefer: Bool) {
+    MainActor.assumeIsolated {
+        discreteGPU = prefer ? MTLCreateSystemDefaultDevice() : nil
+    }
+}
+
+@_cdecl("SwiftAppWindow_SetPerformanceActivity") public func SetPerformanceActivity(userInitiated: Bool, latencyCritical: Bool) {
+    MainActor.assumeIsolated {
+        if let activity = performanceActivity {
+            ProcessInfo.processInfo.endActivity(activity)
+            performanceActivity = nil
+        }
+        var options: ProcessInfo.ActivityOptions = []
+        if userInitiated {
+            options.insert(.userInitiatedAllowingIdleSystemSleep)
+        }
+        if latencyCritical {
+            options.insert(.latencyCritical)
+        }
+        guard !options.isEmpty else { return }
+        performanceActivity = ProcessInfo.processInfo.beginActivity(options: options, reason: "Performance hints")
+    }
+}
+#endif
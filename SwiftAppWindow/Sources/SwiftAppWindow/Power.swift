@@ -0,0 +1,59 @@
+//SPDX-License-Identifier: MPL-2.0
+
+//
+//  Power.swift
+//  SwiftAppWindow
+//
+//  Power notifications for app_window: Low Power Mode from NSProcessInfo, and on macOS,
+//  displays sleeping from NSWorkspace.
+//
+import Foundation
+#if os(macOS)
+import AppKit
+#endif
+
+/// 0 and 1 for the displays going off and on, 2 and 3 for Low Power Mode starting and stopping.
+typealias PowerCallback = @convention(c) @Sendable (Int) -> ()
+
+@MainActor private var powerObservers: [(NotificationCenter, NSObjectProtocol)] = []
+@MainActor private var lowPower = false
+
+@_cdecl("SwiftAppWindow_WatchPowerMain") public func WatchPowerMain(callback: @escaping PowerCallback) {
+    MainActor.assumeIsolated {
+        guard powerObservers.isEmpty else { return }
+        lowPower = ProcessInfo.processInfo.isLowPowerModeEnabled
+        if lowPower {
+            callback(2)
+        }
+        //posted on whichever thread noticed, so we ask for the main one
+        let processInfo = NotificationCenter.default.addObserver(forName: .NSProcessInfoPowerStateDidChange, object: nil, queue: .main) { _ in
+            MainActor.assumeIsolated {
+                let now = ProcessInfo.processInfo.isLowPowerModeEnabled
+                guard now != lowPower else { return }
+                lowPower = now
+                callback(now ? 2 : 3)
+            }
+        }
+        powerObservers.append((NotificationCenter.default, processInfo))
+        #if os(macOS)
+        let workspace = NSWorkspace.shared.notificationCenter
+        let sleep = workspace.addObserver(forName: NSWorkspace.screensDidSleepNotification, object: nil, queue: .main) { _ in
+            callback(0)
+        }
+        let wake = workspace.addObserver(forName: NSWorkspace.screensDidWakeNotification, object: nil, queue: .main) { _ in
+            callback(1)
+        }
+        powerObservers.append((workspace, sleep))
+        powerObservers.append((workspace, wake))
+        #endif
+    }
+}
+
+@_cdecl("SwiftAppWindow_UnwatchPowerMain") public func UnwatchPowerMain() {
+    MainActor.assumeIsolated {
+        for (center, observer) in powerObservers {
+            center.removeObserver(observer)
+        }
+        powerObservers = []
+    }
+}
@@ -1,4 +1,5 @@
 //SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
 
 //
 //  Surface.swift
@@ -11,6 +12,42 @@ import SwiftAppWindowC
 
 final class SurfaceView: NSView {
     var sizeNotify: ((CGFloat, CGFloat) -> ())?
+    ///Frames for Rust's Surface, separate from the window's own.
+    lazy var frames = FrameRequester()
+    ///The mouse event that's currently holding a button down, needed to start drags.
+    var lastMouseEvent: NSEvent?
+    ///Shown over the whole view; nil hides it.  See Window.setCursor.
+    var cursor: NSCursor? = .arrow
+
+    ///A transparent cursor, since NSCursor.hide() is app-wide.
+    var effectiveCursor: NSCursor {
+        cursor ?? NSCursor(image: NSImage(size: NSSize(width: 1, height: 1)), hotSpot: .zero)
+    }
+    override func resetCursorRects() {
+        addCursorRect(bounds, cursor: effectiveCursor)
+    }
+
+    ///The input method's text that isn't final yet.  See the NSTextInputClient conformance.
+    var markedText = ""
+
+    override var acceptsFirstResponder: Bool { true }
+    override func keyDown(with event: NSEvent) {
+        //hands the key to the input method, which calls back as NSTextInputClient
+        interpretKeyEvents([event])
+    }
+
+    override func mouseDown(with event: NSEvent) {
+        lastMouseEvent = event
+        super.mouseDown(with: event)
+    }
+    override func mouseDragged(with event: NSEvent) {
+        lastMouseEvent = event
+        super.mouseDragged(with: event)
+    }
+    override func mouseUp(with event: NSEvent) {
+        lastMouseEvent = nil
+        super.mouseUp(with: event)
+    }
     override func layout() {
         super.layout()
         let scale = window?.backingScaleFactor ?? 1.0
@@ -18,6 +55,70 @@ final class SurfaceView: NSView {
     }
 }
 
+///Reports text to Rust's input::text.  We have no text storage of our own, so ranges into it
+///are all empty.
+extension SurfaceView: NSTextInputClient {
+    private static func plain(_ string: Any) -> String {
+        (string as? NSAttributedString)?.string ?? (string as? String) ?? ""
+    }
+
+    func insertText(_ string: Any, replacementRange: NSRange) {
+        markedText = ""
+        Self.plain(string).withCString { raw_input_text_commit($0) }
+    }
+    func doCommand(by selector: Selector) {
+        //newlines, deletes and the like; apps see those as keys.  The default beeps.
+    }
+    func setMarkedText(_ string: Any, selectedRange: NSRange, replacementRange: NSRange) {
+        markedText = Self.plain(string)
+        if markedText.isEmpty {
+            raw_input_text_cancel()
+            return
+        }
+        //Rust wants UTF-8 offsets, not UTF-16
+        let utf16 = Array(markedText.utf16)
+        func utf8Offset(_ utf16Offset: Int) -> Int64 {
+            Int64(String(decoding: utf16.prefix(utf16Offset), as: UTF16.self).utf8.count)
+        }
+        let (start, end): (Int64, Int64)
+        if selectedRange.location == NSNotFound {
+            (start, end) = (-1, -1)
+        } else {
+            (start, end) = (utf8Offset(selectedRange.location), utf8Offset(NSMaxRange(selectedRange)))
+        }
+        markedText.withCString { raw_input_text_preedit($0, start, end) }
+    }
+    func unmarkText() {
+        //accept the marked text as it stands
+        let text = markedText
+        markedText = ""
+        text.withCString { raw_input_text_commit($0) }
+    }
+    func selectedRange() -> NSRange {
+        NSRange(location: 0, length: 0)
+    }
+    func markedRange() -> NSRange {
+        markedText.isEmpty ? NSRange(location: NSNotFound, length: 0) : NSRange(location: 0, length: markedText.utf16.count)
+    }
+    func hasMarkedText() -> Bool {
+        !markedText.isEmpty
+    }
+    func attributedSubstring(forProposedRange range: NSRange, actualRange: NSRangePointer?) -> NSAttributedString? {
+        nil
+    }
+    func validAttributesForMarkedText() -> [NSAttributedString.Key] {
+        []
+    }
+    func firstRect(forCharacterRange range: NSRange, actualRange: NSRangePointer?) -> NSRect {
+        //we don't know where the app draws its caret, so put candidates at the view's corner
+        guard let window else { return .zero }
+        return window.convertToScreen(convert(NSRect(origin: bounds.origin, size: .zero), to: nil))
+    }
+    func characterIndex(for point: NSPoint) -> Int {
+        NSNotFound
+    }
+}
+
 public final class Surface: Sendable {
     let view: SurfaceView
     
@@ -50,6 +151,25 @@ public final class Surface: Sendable {
             }
         }
     }
+    func requestFrame(context: UInt64, call: @escaping FrameRequester.Callback) {
+        Task {
+            await MainActor.run {
+                view.frames.request(view: view, context: context, call: call)
+            }
+        }
+    }
+    func damage(_ rect: CGRect) {
+        Task {
+            await MainActor.run {
+                //AppKit's origin is at the bottom-left unless the view is flipped
+                var rect = rect
+                if !view.isFlipped {
+                    rect.origin.y = view.bounds.height - rect.maxY
+                }
+                view.layer?.setNeedsDisplay(rect)
+            }
+        }
+    }
     
 }
 
@@ -75,8 +195,21 @@ public final class Surface: Sendable {
     Unmanaged<Surface>.fromOpaque(surface).release()
 }
 
+///Balanced by SurfaceFree.
+@_cdecl("SwiftAppWindow_SurfaceRetain") public func SurfaceRetain(surface: UnsafeMutableRawPointer) {
+    _ = Unmanaged<Surface>.fromOpaque(surface).retain()
+}
+
+@_cdecl("SwiftAppWindow_SurfaceRequestFrame") public func SurfaceRequestFrame(surface: UnsafeMutableRawPointer, context: UInt64, call: @escaping FrameRequester.Callback) {
+    Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue().requestFrame(context: context, call: call)
+}
+
 @_cdecl("SwiftAppWindow_SurfaceSizeUpdate") public func SurfaceSizeUpdate(ctx: UInt64, surface: UnsafeMutableRawPointer, notify: @Sendable @convention(c) (UInt64, CGFloat, CGFloat) -> ()) {
     Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue().sizeUpdate(notify: {
         notify(ctx, $0, $1)
     })
 }
+@_cdecl("SwiftAppWindow_SurfaceDamage") public func SurfaceDamage(surface: UnsafeMutableRawPointer, x: Double, y: Double, width: Double, height: Double) {
+    Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue().damage(CGRect(x: x, y: y, width: width, height: height))
+}
+#endif
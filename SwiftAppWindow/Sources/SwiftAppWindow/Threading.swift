@@ -1,4 +1,5 @@
 //SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
 
 //
 //  Threading.swift
@@ -46,6 +47,28 @@ public func StopMainThread() {
     }
 }
 
+///Set from Rust with application::set_activation_policy; windows reapply it when they open
+@MainActor var activationPolicy: NSApplication.ActivationPolicy = .regular
+
+@MainActor func applyActivationPolicy() -> Bool {
+    let ok = NSApplication.shared.setActivationPolicy(activationPolicy)
+    if activationPolicy != .prohibited {
+        NSApplication.shared.activate()
+    }
+    return ok
+}
+
+///Call on the main thread.
+@_cdecl("SwiftAppWindow_SetActivationPolicyMain") public func SetActivationPolicyMain(policy: Int) -> Bool {
+    MainActor.assumeIsolated {
+        guard let policy = NSApplication.ActivationPolicy(rawValue: policy) else {
+            return false
+        }
+        activationPolicy = policy
+        return applyActivationPolicy()
+    }
+}
+
 @_cdecl("SwiftAppWindow_OnMainThread")
 public func OnMainThread(context: UInt64, cfn: @escaping @convention(c) @Sendable (UInt64) -> Void) {
     Task {
@@ -54,3 +77,13 @@ public func OnMainThread(context: UInt64, cfn: @escaping @convention(c) @Sendabl
         }
     }
 }
+
+@_cdecl("SwiftAppWindow_OnMainThreadAfter")
+public func OnMainThreadAfter(seconds: Double, context: UInt64, cfn: @escaping @convention(c) @Sendable (UInt64) -> Void) {
+    //in the common modes, so it keeps firing while the run loop tracks a resize or a scroll
+    let timer = CFRunLoopTimerCreateWithHandler(kCFAllocatorDefault, CFAbsoluteTimeGetCurrent() + seconds, 0, 0, 0) { _ in
+        cfn(context)
+    }
+    CFRunLoopAddTimer(CFRunLoopGetMain(), timer, .commonModes)
+}
+#endif
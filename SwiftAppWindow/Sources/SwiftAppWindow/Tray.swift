@@ -0,0 +1,97 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
+
+//
+//  Tray.swift
+//  SwiftAppWindow
+//
+//  Status items in the menu bar, for app_window's tray icons.
+//
+import AppKit
+import SwiftRs
+
+@MainActor final class Tray: NSObject {
+    typealias Callback = @convention(c) @Sendable (UInt64, Int) -> ()
+    let item: NSStatusItem
+    let id: UInt64
+    let callback: Callback
+
+    init(id: UInt64, callback: @escaping Callback) {
+        self.item = NSStatusBar.system.statusItem(withLength: NSStatusItem.variableLength)
+        self.id = id
+        self.callback = callback
+        super.init()
+        item.button?.target = self
+        item.button?.action = #selector(clicked(_:))
+    }
+
+    @objc func clicked(_ sender: Any?) {
+        callback(id, -1)
+    }
+
+    @objc func chose(_ sender: NSMenuItem) {
+        callback(id, sender.tag)
+    }
+
+    /// `menu` has a line per item: `1` or `0` for enabled or not, then the label, or just `s`
+    /// for a separator.
+    func update(rgba: [UInt8], width: Int, tooltip: String, menu: String) {
+        if let image = rgbaImage(rgba: rgba, width: width) {
+            //the menu bar's height, less some room
+            let height: CGFloat = 18
+            image.size = NSSize(width: height * image.size.width / max(image.size.height, 1), height: height)
+            item.button?.image = image
+        }
+        item.button?.toolTip = tooltip
+        let lines = menu.split(separator: "\n", omittingEmptySubsequences: false)
+        if menu.isEmpty {
+            //clicks go to the button again
+            item.menu = nil
+            return
+        }
+        let built = NSMenu()
+        //we enable items ourselves
+        built.autoenablesItems = false
+        for (index, line) in lines.enumerated() {
+            if line.first == "s" {
+                built.addItem(NSMenuItem.separator())
+                continue
+            }
+            let entry = NSMenuItem(title: String(line.dropFirst()), action: #selector(chose(_:)), keyEquivalent: "")
+            entry.target = self
+            entry.tag = index
+            entry.isEnabled = line.first == "1"
+            built.addItem(entry)
+        }
+        item.menu = built
+    }
+
+    func remove() {
+        NSStatusBar.system.removeStatusItem(item)
+    }
+}
+
+@_cdecl("SwiftAppWindow_TrayNewMain") public func TrayNewMain(id: UInt64, callback: @escaping Tray.Callback) -> UnsafeMutableRawPointer {
+    MainActor.assumeIsolated {
+        Unmanaged.passRetained(Tray(id: id, callback: callback)).toOpaque()
+    }
+}
+
+@_cdecl("SwiftAppWindow_TrayUpdateMain") public func TrayUpdateMain(tray: UnsafeMutableRawPointer, rgba: SRData, width: Int, tooltip: SRString, menu: SRString) {
+    let tray = Unmanaged<Tray>.fromOpaque(tray).takeUnretainedValue()
+    let bytes = rgba.toArray()
+    let tooltip = tooltip.toString()
+    let menu = menu.toString()
+    MainActor.assumeIsolated {
+        tray.update(rgba: bytes, width: width, tooltip: tooltip, menu: menu)
+    }
+}
+
+@_cdecl("SwiftAppWindow_TrayFreeMain") public func TrayFreeMain(tray: UnsafeMutableRawPointer) {
+    let tray = Unmanaged<Tray>.fromOpaque(tray)
+    MainActor.assumeIsolated {
+        tray.takeUnretainedValue().remove()
+    }
+    tray.release()
+}
+#endif
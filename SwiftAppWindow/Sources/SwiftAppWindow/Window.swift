@@ -1,4 +1,5 @@
 //SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
 
 //
 //  Window.swift
@@ -9,31 +10,217 @@
 import AppKit
 import SwiftRs
 
+///How many windows currently inhibit system shortcuts; see Window.setInhibitShortcuts
+@MainActor var shortcutInhibitors = 0
+
 final class NSWindowCustomize: NSWindow {
+    ///Borderless windows can't take the keyboard unless they say so; chromeless app windows should
+    var keyableWhenBorderless = false
+
     override func keyDown(with event: NSEvent) {
         //don't call super to avoid the noise
     }
-    
+
+    override var canBecomeKey: Bool {
+        super.canBecomeKey || keyableWhenBorderless
+    }
+
+    override var canBecomeMain: Bool {
+        super.canBecomeMain || keyableWhenBorderless
+    }
+
+    ///The frame from before zooming or going fullscreen, since AppKit doesn't say what it restores to
+    private var savedFrame: NSRect?
+
+    ///The frame the window restores to, which is its own unless it's zoomed or fullscreen
+    var normalFrame: NSRect {
+        isZoomed || styleMask.contains(.fullScreen) ? savedFrame ?? frame : frame
+    }
+
+    override func zoom(_ sender: Any?) {
+        if !isZoomed && !styleMask.contains(.fullScreen) { savedFrame = frame }
+        super.zoom(sender)
+    }
+
+    override func toggleFullScreen(_ sender: Any?) {
+        if !isZoomed && !styleMask.contains(.fullScreen) { savedFrame = frame }
+        super.toggleFullScreen(sender)
+    }
+}
+
+///Implements CloseBehavior from Rust (see window.rs).
+@MainActor final class WindowDelegate: NSObject, NSWindowDelegate {
+    enum Behavior: Int {
+        case destroy = 0
+        case hideToTray = 1
+        case custom = 2
+    }
+    let behavior: Behavior
+    ///Told about close requests, whatever the behavior
+    weak var events: WindowEvents?
+    let context: UInt64
+    let call: @convention(c) @Sendable (UInt64) -> Bool
+    let free: @convention(c) @Sendable (UInt64) -> ()
+
+    init(behavior: Behavior, context: UInt64, call: @convention(c) @Sendable (UInt64) -> Bool, free: @convention(c) @Sendable (UInt64) -> ()) {
+        self.behavior = behavior
+        self.context = context
+        self.call = call
+        self.free = free
+    }
+    deinit {
+        free(context)
+    }
+
+    func windowShouldClose(_ sender: NSWindow) -> Bool {
+        events?.send(.closeRequested)
+        switch behavior {
+        case .destroy:
+            return true
+        case .hideToTray:
+            sender.orderOut(nil)
+            return false
+        case .custom:
+            //Rust decides whether this closes; see set_close_behavior in macos.rs
+            return call(context)
+        }
+    }
+}
+
+///Forwards window notifications to a WindowEventSender in Rust (see macos.rs).
+@MainActor final class WindowEvents: NSObject {
+    ///See WindowEvent in window/events.rs
+    enum Kind: Int {
+        case resized = 0
+        case moved = 1
+        case focused = 2
+        case unfocused = 3
+        case minimized = 4
+        case restored = 5
+        case scaleChanged = 6
+        case closeRequested = 7
+        case reconnected = 8
+        ///a is a bitmask; see call_event in macos.rs
+        case stateChanged = 9
+    }
+    let context: UInt64
+    let call: @convention(c) @Sendable (UInt64, Int, Double, Double) -> ()
+    let free: @convention(c) @Sendable (UInt64) -> ()
+
+    init(window: NSWindow, context: UInt64, call: @convention(c) @Sendable (UInt64, Int, Double, Double) -> (), free: @convention(c) @Sendable (UInt64) -> ()) {
+        self.context = context
+        self.call = call
+        self.free = free
+        super.init()
+        //selector-based observers are removed when we're deallocated
+        let center = NotificationCenter.default
+        center.addObserver(self, selector: #selector(didResize), name: NSWindow.didResizeNotification, object: window)
+        center.addObserver(self, selector: #selector(didMove), name: NSWindow.didMoveNotification, object: window)
+        center.addObserver(self, selector: #selector(didBecomeKey), name: NSWindow.didBecomeKeyNotification, object: window)
+        center.addObserver(self, selector: #selector(didResignKey), name: NSWindow.didResignKeyNotification, object: window)
+        center.addObserver(self, selector: #selector(didMiniaturize), name: NSWindow.didMiniaturizeNotification, object: window)
+        center.addObserver(self, selector: #selector(didDeminiaturize), name: NSWindow.didDeminiaturizeNotification, object: window)
+        center.addObserver(self, selector: #selector(didChangeBackingProperties), name: NSWindow.didChangeBackingPropertiesNotification, object: window)
+        center.addObserver(self, selector: #selector(willClose), name: NSWindow.willCloseNotification, object: window)
+        center.addObserver(self, selector: #selector(stateMayHaveChanged), name: NSWindow.didEnterFullScreenNotification, object: window)
+        center.addObserver(self, selector: #selector(stateMayHaveChanged), name: NSWindow.didExitFullScreenNotification, object: window)
+        center.addObserver(self, selector: #selector(stateMayHaveChanged), name: NSWindow.didChangeOcclusionStateNotification, object: window)
+        //workspace notifications have their own center
+        NSWorkspace.shared.notificationCenter.addObserver(self, selector: #selector(didWake), name: NSWorkspace.didWakeNotification, object: nil)
+    }
+    deinit {
+        free(context)
+    }
+
+    func send(_ kind: Kind, _ a: Double = 0, _ b: Double = 0) {
+        call(context, kind.rawValue, a, b)
+    }
+
+    @objc func didResize(_ notification: Notification) {
+        guard let window = notification.object as? NSWindow, let view = window.contentView else { return }
+        send(.resized, view.frame.size.width, view.frame.size.height)
+        stateMayHaveChanged(notification)
+    }
+    ///Rust only passes on changes, so this can be sent freely
+    @objc func stateMayHaveChanged(_ notification: Notification) {
+        guard let window = notification.object as? NSWindow else { return }
+        let fullscreen = window.styleMask.contains(.fullScreen)
+        var flags = 0
+        if window.isZoomed && !fullscreen { flags |= 1 }
+        if fullscreen { flags |= 2 }
+        if window.isKeyWindow { flags |= 4 }
+        if window.isMiniaturized || !window.occlusionState.contains(.visible) { flags |= 8 }
+        send(.stateChanged, Double(flags))
+    }
+    @objc func didMove(_ notification: Notification) {
+        guard let window = notification.object as? NSWindow, let origin = window.rustOrigin else { return }
+        send(.moved, origin.x, origin.y)
+    }
+    @objc func didBecomeKey(_ notification: Notification) {
+        send(.focused)
+        stateMayHaveChanged(notification)
+    }
+    @objc func didResignKey(_ notification: Notification) {
+        send(.unfocused)
+        stateMayHaveChanged(notification)
+    }
+    @objc func didMiniaturize(_ notification: Notification) {
+        send(.minimized)
+        stateMayHaveChanged(notification)
+    }
+    @objc func didDeminiaturize(_ notification: Notification) {
+        send(.restored)
+        stateMayHaveChanged(notification)
+    }
+    @objc func didChangeBackingProperties(_ notification: Notification) {
+        guard let window = notification.object as? NSWindow else { return }
+        send(.scaleChanged, window.backingScaleFactor)
+    }
+    @objc func willClose(_ notification: Notification) {
+        //a sheet that closes still has to end, or its parent stays blocked
+        guard let window = notification.object as? NSWindow, let parent = window.sheetParent else { return }
+        parent.endSheet(window)
+    }
+    @objc func didWake(_ notification: Notification) {
+        send(.reconnected)
+    }
 }
 
 public final class Window: Sendable {
     @MainActor var window: NSWindow?
+    ///NSWindow.delegate is weak, so we own it here
+    @MainActor var delegate: WindowDelegate?
+    @MainActor var events: WindowEvents?
+    @MainActor var inhibitsShortcuts = false
+    ///Made on the first Window::request_redraw
+    @MainActor var frames: FrameRequester?
+    ///Attached as a child when shown; see setParent
+    @MainActor weak var parent: Window?
+    ///Zooming only works on screen, so a hidden window zooms when shown
+    @MainActor var zoomOnShow = false
+    ///What an opaque window shows until the app presents a frame
+    @MainActor var background: NSColor = .windowBackgroundColor
     
-    init(x: CGFloat, y: CGFloat, width: CGFloat, height: CGFloat, title: String) {
+    init(x: CGFloat, y: CGFloat, width: CGFloat, height: CGFloat, title: String, visible: Bool = true, decorated: Bool = true) {
         Task {
             await MainActor.run {
-                NSApplication.shared.setActivationPolicy(.regular)
-                NSApplication.shared.activate()
-                let _window = NSWindowCustomize(contentRect: NSRect(origin: .zero, size: .init(width: width, height: height)) , styleMask: [.titled, .closable, .miniaturizable, .resizable], backing: .buffered, defer: false)
+                _ = applyActivationPolicy()
+                let styleMask: NSWindow.StyleMask = decorated ? [.titled, .closable, .miniaturizable, .resizable] : [.borderless]
+                let _window = NSWindowCustomize(contentRect: NSRect(origin: .zero, size: .init(width: width, height: height)) , styleMask: styleMask, backing: .buffered, defer: false)
+                _window.keyableWhenBorderless = !decorated
                 _window.isReleasedWhenClosed = false
                 _window.contentView = SurfaceView()
+                //so keys reach its NSTextInputClient
+                _window.makeFirstResponder(_window.contentView)
 
                 self.window = _window
                 
                 _window.title = title
                 let screen = _window.screen!
                 _window.setFrameOrigin(.init(rustX: x, rustY: y, outerBounds: screen.frame))
-                self.window!.makeKeyAndOrderFront(nil)
+                if visible {
+                    self.window!.makeKeyAndOrderFront(nil)
+                }
             }
         }
     }
@@ -42,11 +229,12 @@ public final class Window: Sendable {
         
         Task {
             await MainActor.run {
-                NSApplication.shared.setActivationPolicy(.regular)
-                NSApplication.shared.activate()
+                _ = applyActivationPolicy()
                 let _window = NSWindowCustomize(contentRect: .init(origin: .zero, size: NSScreen.main!.frame.size), styleMask: [.borderless], backing: .buffered, defer: false)
                 _window.isReleasedWhenClosed = false
                 _window.contentView = SurfaceView()
+                //so keys reach its NSTextInputClient
+                _window.makeFirstResponder(_window.contentView)
                 self.window = _window
                 
                 _window.title = title
@@ -58,6 +246,36 @@ public final class Window: Sendable {
             }
         }
     }
+    ///A borderless window showing an image, for SplashWindow, or floating over other windows
+    ///without taking focus, for DiagnosticOverlay
+    init(splashWidth width: CGFloat, height: CGFloat, rgba: [UInt8], imageWidth: Int, floating: Bool = false) {
+        Task {
+            await MainActor.run {
+                _ = applyActivationPolicy()
+                let _window = NSWindowCustomize(contentRect: NSRect(origin: .zero, size: .init(width: width, height: height)), styleMask: [.borderless], backing: .buffered, defer: false)
+                _window.isReleasedWhenClosed = false
+                let imageView = NSImageView()
+                imageView.image = rgbaImage(rgba: rgba, width: imageWidth)
+                imageView.imageScaling = .scaleAxesIndependently
+                _window.contentView = imageView
+                self.window = _window
+                if floating {
+                    _window.level = .floating
+                    _window.collectionBehavior = [.canJoinAllSpaces, .fullScreenAuxiliary]
+                    if let visible = _window.screen?.visibleFrame {
+                        _window.setFrameTopLeftPoint(NSPoint(x: visible.minX + 10, y: visible.maxY - 10))
+                    }
+                    _window.orderFrontRegardless()
+                } else {
+                    _window.center()
+                    _window.makeKeyAndOrderFront(nil)
+                }
+            }
+        }
+    }
+    @MainActor func setSplashImage(rgba: [UInt8], imageWidth: Int) {
+        (window?.contentView as? NSImageView)?.image = rgbaImage(rgba: rgba, width: imageWidth)
+    }
     deinit {
         //I'm not really sure why but there's some ARC issue here
         if let window {
@@ -78,6 +296,214 @@ public final class Window: Sendable {
         }
         return Surface(view: view)
     }
+    @MainActor func setVisible(_ visible: Bool) {
+        guard let window else { return }
+        if visible {
+            window.makeKeyAndOrderFront(nil)
+            if window.parent == nil, let parentWindow = parent?.window {
+                parentWindow.addChildWindow(window, ordered: .above)
+            }
+            if zoomOnShow {
+                zoomOnShow = false
+                if !window.isZoomed {
+                    window.zoom(nil)
+                }
+            }
+        } else if let sheetParent = window.sheetParent {
+            //hiding a sheet ends it
+            sheetParent.endSheet(window)
+        } else {
+            window.orderOut(nil)
+        }
+    }
+    @MainActor func setAllSpaces(_ allSpaces: Bool) {
+        if allSpaces {
+            window?.collectionBehavior.insert(.canJoinAllSpaces)
+        } else {
+            window?.collectionBehavior.remove(.canJoinAllSpaces)
+        }
+    }
+    @MainActor func setResizable(_ resizable: Bool) {
+        //borderless windows have no frame to resize by
+        guard let window, window.styleMask.contains(.titled) else { return }
+        if resizable {
+            window.styleMask.insert(.resizable)
+        } else {
+            window.styleMask.remove(.resizable)
+        }
+    }
+    ///Content sizes; zero means no limit.
+    @MainActor func setSizeLimits(minWidth: CGFloat, minHeight: CGFloat, maxWidth: CGFloat, maxHeight: CGFloat) {
+        window?.contentMinSize = .init(width: minWidth, height: minHeight)
+        window?.contentMaxSize = .init(width: maxWidth == 0 ? .greatestFiniteMagnitude : maxWidth,
+                                       height: maxHeight == 0 ? .greatestFiniteMagnitude : maxHeight)
+    }
+    @MainActor func setClickThrough(_ clickThrough: Bool) {
+        window?.ignoresMouseEvents = clickThrough
+    }
+    @MainActor func focus() {
+        guard let window else { return }
+        if window.isMiniaturized {
+            window.deminiaturize(nil)
+        }
+        NSApplication.shared.activate()
+        window.makeKeyAndOrderFront(nil)
+    }
+    @MainActor func setAlwaysOnTop(_ onTop: Bool) {
+        window?.level = onTop ? .floating : .normal
+    }
+    @MainActor func setMaximized(_ maximized: Bool) {
+        guard let window else { return }
+        guard window.isVisible else {
+            zoomOnShow = maximized
+            return
+        }
+        if window.isZoomed != maximized {
+            window.zoom(nil)
+        }
+    }
+    @MainActor func setBackground(_ color: NSColor) {
+        background = color
+        guard let window, window.isOpaque else { return }
+        window.backgroundColor = color
+    }
+    @MainActor func setTransparent(_ transparent: Bool) {
+        guard let window else { return }
+        window.isOpaque = !transparent
+        window.backgroundColor = transparent ? .clear : background
+        window.contentView?.layer?.isOpaque = !transparent
+    }
+    ///Adding a child window orders it in, so a hidden one waits until it's shown.
+    @MainActor func setParent(_ parent: Window) {
+        self.parent = parent
+        guard let window, window.isVisible, let parentWindow = parent.window else { return }
+        window.parent?.removeChildWindow(window)
+        parentWindow.addChildWindow(window, ordered: .above)
+    }
+    ///Sizes and positions mean what they do in `init`.
+    @MainActor func setContentSize(width: CGFloat, height: CGFloat) {
+        window?.setContentSize(.init(width: width, height: height))
+    }
+    @MainActor func setOrigin(x: CGFloat, y: CGFloat) {
+        guard let window, let screen = window.screen else { return }
+        window.setFrameOrigin(.init(rustX: x, rustY: y, outerBounds: screen.frame))
+    }
+    @MainActor func requestFrame(context: UInt64, call: @escaping FrameRequester.Callback) {
+        guard let view = window?.contentView else {
+            //closed, so no frame is coming; answer anyway so Rust can ask again
+            call(context, 0, 0)
+            return
+        }
+        let frames = self.frames ?? FrameRequester()
+        self.frames = frames
+        frames.request(view: view, context: context, call: call)
+    }
+    @MainActor func setSkipTaskbar(_ skip: Bool) {
+        //the dock shows apps, not windows; the closest per-window equivalent is leaving the
+        //Window menu and Cmd-` cycling
+        window?.isExcludedFromWindowsMenu = skip
+        if skip {
+            window?.collectionBehavior.insert(.ignoresCycle)
+        } else {
+            window?.collectionBehavior.remove(.ignoresCycle)
+        }
+    }
+    ///Presentation options are app-wide, so they stay while any window inhibits shortcuts.
+    @MainActor func setInhibitShortcuts(_ inhibit: Bool) {
+        guard inhibit != inhibitsShortcuts else { return }
+        inhibitsShortcuts = inhibit
+        shortcutInhibitors += inhibit ? 1 : -1
+        //only the auto-hiding options may be combined with .fullScreen
+        let fullScreen = NSApp.presentationOptions.contains(.fullScreen)
+        if shortcutInhibitors > 0 {
+            //leave force quit alone, so a stuck game can still be killed
+            NSApp.presentationOptions = fullScreen
+                ? [.fullScreen, .autoHideDock, .autoHideMenuBar, .disableProcessSwitching, .disableHideApplication]
+                : [.hideDock, .hideMenuBar, .disableProcessSwitching, .disableHideApplication]
+        } else {
+            NSApp.presentationOptions = fullScreen ? [.fullScreen] : []
+        }
+    }
+    @MainActor func setPreviewImage(_ image: NSImage?) {
+        window?.miniwindowImage = image
+    }
+    @MainActor func fadeOut(completion: @escaping @Sendable () -> ()) {
+        guard let window else {
+            completion()
+            return
+        }
+        NSAnimationContext.runAnimationGroup({ context in
+            context.duration = 0.2
+            window.animator().alphaValue = 0
+        }, completionHandler: completion)
+    }
+    ///Calls `done` once `sheet` ends, when it's hidden or closed.
+    @MainActor func beginSheet(_ sheet: Window, done: @escaping @Sendable () -> ()) {
+        guard let window, let sheetWindow = sheet.window else {
+            done()
+            return
+        }
+        window.beginSheet(sheetWindow) { _ in
+            done()
+        }
+    }
+    @MainActor func setCloseBehavior(_ delegate: WindowDelegate) {
+        delegate.events = events
+        self.delegate = delegate
+        window?.delegate = delegate
+    }
+    @MainActor func setEvents(context: UInt64, call: @convention(c) @Sendable (UInt64, Int, Double, Double) -> (), free: @convention(c) @Sendable (UInt64) -> ()) {
+        guard let window else {
+            free(context)
+            return
+        }
+        let events = WindowEvents(window: window, context: context, call: call, free: free)
+        self.events = events
+        //close requests come through the delegate, so make sure there is one
+        if delegate == nil {
+            setCloseBehavior(WindowDelegate(behavior: .destroy, context: 0, call: { _ in true }, free: { _ in }))
+        } else {
+            delegate?.events = events
+        }
+    }
+    @MainActor func setCursor(_ cursor: NSCursor?) {
+        guard let window, let view = window.contentView as? SurfaceView else { return }
+        view.cursor = cursor
+        window.invalidateCursorRects(for: view)
+        //cursor rects only apply on the next move
+        let location = view.convert(window.mouseLocationOutsideOfEventStream, from: nil)
+        if view.bounds.contains(location) {
+            view.effectiveCursor.set()
+        }
+    }
+    @MainActor func debugState() -> SwiftAppWindowDebugState {
+        guard let window else {
+            return SwiftAppWindowDebugState(width: 0, height: 0, scale: 1, focused: false, visible: false, decorated: false, state: 0, display: 0)
+        }
+        let size = window.contentView?.frame.size ?? .zero
+        let state: UInt8
+        if window.isMiniaturized {
+            state = 2
+        } else if window.styleMask.contains(.fullScreen) {
+            state = 3
+        } else if window.isZoomed {
+            state = 1
+        } else {
+            state = 0
+        }
+        let display = window.screen?.deviceDescription[NSDeviceDescriptionKey("NSScreenNumber")] as? UInt32 ?? 0
+        return SwiftAppWindowDebugState(width: size.width, height: size.height, scale: window.backingScaleFactor, focused: window.isKeyWindow, visible: window.isVisible, decorated: window.styleMask.contains(.titled), state: state, display: display)
+    }
+    ///macOS has no per-window system menu, so offer the usual title bar actions instead.
+    @MainActor func showWindowMenu(x: CGFloat, y: CGFloat) {
+        guard let window = self.window, let view = window.contentView else { return }
+        let menu = NSMenu()
+        menu.addItem(withTitle: "Minimize", action: #selector(NSWindow.performMiniaturize(_:)), keyEquivalent: "").target = window
+        menu.addItem(withTitle: "Zoom", action: #selector(NSWindow.performZoom(_:)), keyEquivalent: "").target = window
+        menu.addItem(.separator())
+        menu.addItem(withTitle: "Close", action: #selector(NSWindow.performClose(_:)), keyEquivalent: "").target = window
+        menu.popUp(positioning: nil, at: NSPoint(rustX: x, rustY: y, outerBounds: view.bounds), in: view)
+    }
 }
 
 @_cdecl("SwiftAppWindow_WindowNew") public func WindowNew(x: CGFloat, y: CGFloat, width: CGFloat, height: CGFloat, title: SRString) -> UnsafeMutableRawPointer {
@@ -86,12 +512,84 @@ public final class Window: Sendable {
     return unmanaged
 }
 
+///A window for WindowBuilder; undecorated ones have no title bar or borders
+@_cdecl("SwiftAppWindow_WindowNewWithOptions") public func WindowNewWithOptions(x: CGFloat, y: CGFloat, width: CGFloat, height: CGFloat, title: SRString, decorated: Bool, visible: Bool) -> UnsafeMutableRawPointer {
+    let w = Window(x: x, y: y, width: width, height: height, title: title.toString(), visible: visible, decorated: decorated)
+    let unmanaged = Unmanaged.passRetained(w).toOpaque()
+    return unmanaged
+}
+
+///A hidden window for the window pool; see WindowUnpark
+@_cdecl("SwiftAppWindow_WindowNewParked") public func WindowNewParked(x: CGFloat, y: CGFloat, width: CGFloat, height: CGFloat, title: SRString) -> UnsafeMutableRawPointer {
+    let w = Window(x: x, y: y, width: width, height: height, title: title.toString(), visible: false)
+    let unmanaged = Unmanaged.passRetained(w).toOpaque()
+    return unmanaged
+}
+
+@_cdecl("SwiftAppWindow_WindowUnpark") public func WindowUnpark(window: UnsafeMutableRawPointer, title: SRString) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    let title = title.toString()
+    Task {
+        await MainActor.run {
+            window.window?.title = title
+            window.setVisible(true)
+        }
+    }
+}
+
 @_cdecl("SwiftAppWindow_WindowNewFullscreen") public func WindowNew(title: SRString) -> UnsafeMutableRawPointer {
     let w = Window(fullscreen: (), title: title.toString())
     let unmanaged = Unmanaged.passRetained(w).toOpaque()
     return unmanaged
 }
 
+@_cdecl("SwiftAppWindow_WindowNewSplash") public func WindowNewSplash(width: CGFloat, height: CGFloat, rgba: SRData, imageWidth: Int) -> UnsafeMutableRawPointer {
+    //copy now; Rust frees its buffer once we return
+    let w = Window(splashWidth: width, height: height, rgba: rgba.toArray(), imageWidth: imageWidth)
+    let unmanaged = Unmanaged.passRetained(w).toOpaque()
+    return unmanaged
+}
+
+@_cdecl("SwiftAppWindow_WindowNewOverlay") public func WindowNewOverlay(width: CGFloat, height: CGFloat, rgba: SRData, imageWidth: Int) -> UnsafeMutableRawPointer {
+    let w = Window(splashWidth: width, height: height, rgba: rgba.toArray(), imageWidth: imageWidth, floating: true)
+    let unmanaged = Unmanaged.passRetained(w).toOpaque()
+    return unmanaged
+}
+
+@_cdecl("SwiftAppWindow_WindowSetSplashImage") public func WindowSetSplashImage(window: UnsafeMutableRawPointer, rgba: SRData, imageWidth: Int) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    //copy now; Rust frees its buffer once we return
+    let bytes = rgba.toArray()
+    Task {
+        await MainActor.run {
+            window.setSplashImage(rgba: bytes, imageWidth: imageWidth)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowFadeOut") public func WindowFadeOut(context: UInt64, window: UnsafeMutableRawPointer, ret: @convention(c) @Sendable (UInt64) -> ()) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.fadeOut {
+                ret(context)
+            }
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowBeginSheet") public func WindowBeginSheet(context: UInt64, window: UnsafeMutableRawPointer, sheet: UnsafeMutableRawPointer, ret: @convention(c) @Sendable (UInt64) -> ()) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    let sheet = Unmanaged<Window>.fromOpaque(sheet).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.beginSheet(sheet) {
+                ret(context)
+            }
+        }
+    }
+}
+
 @_cdecl("SwiftAppWindow_WindowFree") public func WindowFree(window: UInt64) {
     let window = UnsafeMutableRawPointer(bitPattern: Int(window))!
     Unmanaged<Window>.fromOpaque(window).release()
@@ -104,3 +602,272 @@ public final class Window: Sendable {
 }
 
 
+
+@_cdecl("SwiftAppWindow_WindowShowWindowMenu") public func WindowShowWindowMenu(window: UnsafeMutableRawPointer, x: CGFloat, y: CGFloat) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.showWindowMenu(x: x, y: y)
+        }
+    }
+}
+
+///Call on the main thread.
+@_cdecl("SwiftAppWindow_WindowDebugStateMain") public func WindowDebugStateMain(window: UnsafeMutableRawPointer) -> SwiftAppWindowDebugState {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    return MainActor.assumeIsolated {
+        window.debugState()
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowRequestFrame") public func WindowRequestFrame(window: UnsafeMutableRawPointer, context: UInt64, call: @escaping FrameRequester.Callback) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.requestFrame(context: context, call: call)
+        }
+    }
+}
+
+///Writes the window's position, and returns false if it isn't on a screen.  Call on the main thread.
+@_cdecl("SwiftAppWindow_WindowOuterPositionMain") public func WindowOuterPositionMain(window: UnsafeMutableRawPointer, x: UnsafeMutablePointer<Double>, y: UnsafeMutablePointer<Double>) -> Bool {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    return MainActor.assumeIsolated {
+        guard let origin = window.window?.rustOrigin else { return false }
+        x.pointee = origin.x
+        y.pointee = origin.y
+        return true
+    }
+}
+
+///Writes the frame the window restores to, as a position like WindowOuterPositionMain and a content size like WindowNew takes, and returns false if it isn't on a screen.  Call on the main thread.
+@_cdecl("SwiftAppWindow_WindowNormalBoundsMain") public func WindowNormalBoundsMain(window: UnsafeMutableRawPointer, x: UnsafeMutablePointer<Double>, y: UnsafeMutablePointer<Double>, width: UnsafeMutablePointer<Double>, height: UnsafeMutablePointer<Double>) -> Bool {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    return MainActor.assumeIsolated {
+        guard let window = window.window else { return false }
+        let frame = (window as? NSWindowCustomize)?.normalFrame ?? window.frame
+        guard let origin = window.rustOrigin(of: frame) else { return false }
+        let content = window.contentRect(forFrameRect: frame)
+        x.pointee = origin.x
+        y.pointee = origin.y
+        width.pointee = content.width
+        height.pointee = content.height
+        return true
+    }
+}
+
+///The pointer input reports this window by, or nil if it hasn't been created yet.  Call on the main thread.
+@_cdecl("SwiftAppWindow_WindowInputHandleMain") public func WindowInputHandleMain(window: UnsafeMutableRawPointer) -> UnsafeMutableRawPointer? {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    return MainActor.assumeIsolated {
+        window.window.map { Unmanaged.passUnretained($0).toOpaque() }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetVisible") public func WindowSetVisible(window: UnsafeMutableRawPointer, visible: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setVisible(visible)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetAllSpaces") public func WindowSetAllSpaces(window: UnsafeMutableRawPointer, allSpaces: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setAllSpaces(allSpaces)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetResizable") public func WindowSetResizable(window: UnsafeMutableRawPointer, resizable: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setResizable(resizable)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetSizeLimits") public func WindowSetSizeLimits(window: UnsafeMutableRawPointer, minWidth: CGFloat, minHeight: CGFloat, maxWidth: CGFloat, maxHeight: CGFloat) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setSizeLimits(minWidth: minWidth, minHeight: minHeight, maxWidth: maxWidth, maxHeight: maxHeight)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetClickThrough") public func WindowSetClickThrough(window: UnsafeMutableRawPointer, clickThrough: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setClickThrough(clickThrough)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowFocus") public func WindowFocus(window: UnsafeMutableRawPointer) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.focus()
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetAlwaysOnTop") public func WindowSetAlwaysOnTop(window: UnsafeMutableRawPointer, onTop: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setAlwaysOnTop(onTop)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetMaximized") public func WindowSetMaximized(window: UnsafeMutableRawPointer, maximized: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setMaximized(maximized)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetTransparent") public func WindowSetTransparent(window: UnsafeMutableRawPointer, transparent: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setTransparent(transparent)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetBackground") public func WindowSetBackground(window: UnsafeMutableRawPointer, red: Double, green: Double, blue: Double) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setBackground(NSColor(srgbRed: red, green: green, blue: blue, alpha: 1))
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetParent") public func WindowSetParent(window: UnsafeMutableRawPointer, parent: UnsafeMutableRawPointer) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    let parent = Unmanaged<Window>.fromOpaque(parent).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setParent(parent)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetSize") public func WindowSetSize(window: UnsafeMutableRawPointer, width: CGFloat, height: CGFloat) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setContentSize(width: width, height: height)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetPosition") public func WindowSetPosition(window: UnsafeMutableRawPointer, x: CGFloat, y: CGFloat) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setOrigin(x: x, y: y)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetSkipTaskbar") public func WindowSetSkipTaskbar(window: UnsafeMutableRawPointer, skip: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setSkipTaskbar(skip)
+        }
+    }
+}
+
+///The order matches the Rust side's `cursor_kind`.
+@_cdecl("SwiftAppWindow_WindowSetCursor") public func WindowSetCursor(window: UnsafeMutableRawPointer, kind: Int) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            let cursor: NSCursor? = switch kind {
+            case 1: .iBeam
+            case 2: .pointingHand
+            case 3: .crosshair
+            case 4: .operationNotAllowed
+            case 5: .resizeLeftRight
+            case 6: .resizeUpDown
+            case 7: nil
+            default: .arrow
+            }
+            window.setCursor(cursor)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowInhibitShortcuts") public func WindowInhibitShortcuts(window: UnsafeMutableRawPointer, inhibit: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setInhibitShortcuts(inhibit)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetPreviewImage") public func WindowSetPreviewImage(window: UnsafeMutableRawPointer, rgba: SRData, width: Int) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    //copy now; Rust frees its buffer once we return
+    let rgba = rgba.toArray()
+    Task {
+        await MainActor.run {
+            window.setPreviewImage(rgbaImage(rgba: rgba, width: width))
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_SetApplicationIcon") public func SetApplicationIcon(rgba: SRData, width: Int) {
+    //copy now; Rust frees its buffer once we return
+    let bytes = rgba.toArray()
+    Task {
+        await MainActor.run {
+            //nil goes back to the bundle's icon
+            NSApplication.shared.applicationIconImage = rgbaImage(rgba: bytes, width: width)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_SetBadgeLabel") public func SetBadgeLabel(label: SRString) {
+    let label = label.toString()
+    Task {
+        await MainActor.run {
+            //an empty label removes the badge
+            NSApplication.shared.dockTile.badgeLabel = label.isEmpty ? nil : label
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetCloseBehavior") public func WindowSetCloseBehavior(window: UnsafeMutableRawPointer, behavior: Int, context: UInt64, call: @convention(c) @Sendable (UInt64) -> Bool, free: @convention(c) @Sendable (UInt64) -> ()) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            let delegate = WindowDelegate(behavior: WindowDelegate.Behavior(rawValue: behavior) ?? .destroy, context: context, call: call, free: free)
+            window.setCloseBehavior(delegate)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetEvents") public func WindowSetEvents(window: UnsafeMutableRawPointer, context: UInt64, call: @convention(c) @Sendable (UInt64, Int, Double, Double) -> (), free: @convention(c) @Sendable (UInt64) -> ()) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setEvents(context: context, call: call, free: free)
+        }
+    }
+}
+#endif
@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
 //
 //  DebugWindow.swift
 //  SwiftRawInput
@@ -34,3 +35,4 @@ private final class DebugWindow: NSWindow {
         window = nil
     }
 }
+#endif
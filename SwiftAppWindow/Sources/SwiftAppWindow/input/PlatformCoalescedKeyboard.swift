@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
 //
 //  PlatformCoalescedKeyboard.swift
 //  SwiftRawInput
@@ -6,11 +7,9 @@
 //  Created by Drew Crawford on 12/13/24.
 //
 
-#if canImport(AppKit)
 import AppKit
-#else
-#error("This platform is not yet supported")
-#endif
+import Carbon
+import GameController
 import SwiftAppWindowC
 
 // Holds mutable modifier state for the event monitor closure
@@ -146,3 +145,48 @@ final class PlatformCoalescedKeyboard:
 @_cdecl("PlatformCoalescedKeyboardFree") public func PlatformCoalescedKeyboardFree(_ p: UnsafeMutableRawPointer) {
     Unmanaged<PlatformCoalescedKeyboard>.fromOpaque(p).release()
 }
+
+///Whether GameController knows of a keyboard.  Call on the main thread.
+@_cdecl("PlatformHardwareKeyboardPresentMain") public func PlatformHardwareKeyboardPresentMain() -> Bool {
+    MainActor.assumeIsolated {
+        GCKeyboard.coalesced != nil
+    }
+}
+
+///The character `keyCode` types on the current layout, or 0.  Call on the main thread.
+@_cdecl("PlatformKeyCharacterMain") public func PlatformKeyCharacterMain(keyCode: UInt16, shift: Bool, option: Bool) -> UInt32 {
+    MainActor.assumeIsolated {
+        guard let source = TISCopyCurrentKeyboardLayoutInputSource()?.takeRetainedValue(),
+              let layoutData = TISGetInputSourceProperty(source, kTISPropertyUnicodeKeyLayoutData) else {
+            //input methods like Japanese have no layout data of their own
+            return 0
+        }
+        let data = Unmanaged<CFData>.fromOpaque(layoutData).takeUnretainedValue() as Data
+        //UCKeyTranslate wants EventRecord modifiers, shifted down a byte
+        var modifiers: UInt32 = 0
+        if shift { modifiers |= UInt32(shiftKey >> 8) }
+        if option { modifiers |= UInt32(optionKey >> 8) }
+        var deadKeyState: UInt32 = 0
+        var chars = [UniChar](repeating: 0, count: 4)
+        var length = 0
+        let status = data.withUnsafeBytes { bytes in
+            UCKeyTranslate(
+                bytes.baseAddress!.assumingMemoryBound(to: UCKeyboardLayout.self),
+                keyCode,
+                UInt16(kUCKeyActionDisplay),
+                modifiers,
+                UInt32(LMGetKbdType()),
+                OptionBits(kUCKeyTranslateNoDeadKeysMask),
+                &deadKeyState,
+                chars.count,
+                &length,
+                &chars
+            )
+        }
+        guard status == noErr, length > 0 else { return 0 }
+        let scalars = String(utf16CodeUnits: chars, count: length).unicodeScalars
+        guard scalars.count == 1, let scalar = scalars.first else { return 0 }
+        return scalar.value
+    }
+}
+#endif
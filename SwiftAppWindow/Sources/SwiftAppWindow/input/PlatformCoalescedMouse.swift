@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
+#if os(macOS)
 //
 //  PlatformCoalescedMouse.swift
 //  SwiftRawInput
@@ -29,7 +30,7 @@ final class PlatformCoalescedMouse:
         
         let sendContext = Int(bitPattern: context)
         
-        self.monitor = NSEvent.addLocalMonitorForEvents(matching: [.mouseMoved, .leftMouseDown, .leftMouseUp, .otherMouseDown, .otherMouseUp, .rightMouseDown, .rightMouseUp,.scrollWheel]) { event in
+        self.monitor = NSEvent.addLocalMonitorForEvents(matching: [.mouseMoved, .leftMouseDragged, .rightMouseDragged, .otherMouseDragged, .leftMouseDown, .leftMouseUp, .otherMouseDown, .otherMouseUp, .rightMouseDown, .rightMouseUp,.scrollWheel]) { event in
             nonisolated(unsafe) let eventWindow: UnsafeMutableRawPointer?
             if let window = event.window {
                 eventWindow = Unmanaged.passUnretained(window).toOpaque()
@@ -38,7 +39,8 @@ final class PlatformCoalescedMouse:
                 eventWindow = nil
             }
             switch event.type {
-            case .mouseMoved:
+            //AppKit sends drags to the window they started in, even once they leave it
+            case .mouseMoved, .leftMouseDragged, .rightMouseDragged, .otherMouseDragged:
                 let location = event.locationInWindow
                 if let window = event.window {
                     MainActor.assumeIsolated {
@@ -56,19 +58,20 @@ final class PlatformCoalescedMouse:
                     
                 }
             case .leftMouseDown:
-                raw_input_mouse_button(context, eventWindow, 0, true)
+                raw_input_mouse_button(context, eventWindow, 0, true, UInt32(clamping: event.clickCount))
             case .leftMouseUp:
-                raw_input_mouse_button(context, eventWindow, 0, false)
+                raw_input_mouse_button(context, eventWindow, 0, false, UInt32(clamping: event.clickCount))
             case .rightMouseDown:
-                raw_input_mouse_button(context, eventWindow, 1, true)
+                raw_input_mouse_button(context, eventWindow, 1, true, UInt32(clamping: event.clickCount))
             case .rightMouseUp:
-                raw_input_mouse_button(context, eventWindow, 1, false)
+                raw_input_mouse_button(context, eventWindow, 1, false, UInt32(clamping: event.clickCount))
             case .otherMouseDown:
-                raw_input_mouse_button(context,  eventWindow, UInt8(event.buttonNumber), true)
+                raw_input_mouse_button(context,  eventWindow, UInt8(event.buttonNumber), true, UInt32(clamping: event.clickCount))
             case .otherMouseUp:
-                raw_input_mouse_button(context,  eventWindow, UInt8(event.buttonNumber), false)
+                raw_input_mouse_button(context,  eventWindow, UInt8(event.buttonNumber), false, UInt32(clamping: event.clickCount))
             case .scrollWheel:
-                raw_input_mouse_scroll(context,  eventWindow, event.scrollingDeltaX, event.scrollingDeltaY)
+                //wheels without precise deltas report lines
+                raw_input_mouse_scroll(context,  eventWindow, event.scrollingDeltaX, event.scrollingDeltaY, !event.hasPreciseScrollingDeltas)
             default:
                 fatalError("\(event)")
             }
@@ -96,3 +99,4 @@ public typealias MouseNotifyFunc = @convention(c) (UnsafeMutableRawPointer) -> (
 @_cdecl("PlatformCoalescedMouseFree") public func PlatformCoalescedMouseFree(_ p: UnsafeMutableRawPointer) {
     Unmanaged<PlatformCoalescedMouse>.fromOpaque(p).release()
 }
+#endif
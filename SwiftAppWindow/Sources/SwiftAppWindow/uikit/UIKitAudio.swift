@@ -0,0 +1,21 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(iOS)
+
+//
+//  UIKitAudio.swift
+//  SwiftAppWindow
+//
+//  Audio hints.  Whether audio keeps playing in the background is decided by the session
+//  category; the app must also declare the `audio` background mode in its Info.plist.
+//
+import AVFoundation
+
+@_cdecl("SwiftAppWindow_SetBackgroundAudio") public func SetBackgroundAudio(enabled: Bool) {
+    let session = AVAudioSession.sharedInstance()
+    do {
+        try session.setCategory(enabled ? .playback : .soloAmbient)
+    } catch {
+        print("WARN: app_window: can't set audio session category: \(error)")
+    }
+}
+#endif
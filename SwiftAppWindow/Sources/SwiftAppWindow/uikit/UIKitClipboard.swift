@@ -0,0 +1,16 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(iOS)
+
+//
+//  UIKitClipboard.swift
+//  SwiftAppWindow
+//
+//  The pasteboard's change count, which Rust polls to notice clipboard changes.  Reading it
+//  doesn't show the paste permission prompt.
+//
+import UIKit
+
+@_cdecl("SwiftAppWindow_PasteboardChangeCount") public func PasteboardChangeCount() -> Int {
+    UIPasteboard.general.changeCount
+}
+#endif
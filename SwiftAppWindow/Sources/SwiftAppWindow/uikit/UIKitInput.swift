@@ -0,0 +1,217 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(iOS)
+
+//
+//  UIKitInput.swift
+//  SwiftAppWindow
+//
+//  The iOS counterpart of PlatformCoalescedMouse.swift and PlatformCoalescedKeyboard.swift.
+//  UIKit has no app-wide event monitor, so SurfaceView forwards its events to every live context.
+//  The primary touch drives the mouse as if it were the left button.
+//
+import UIKit
+import GameController
+import SwiftAppWindowC
+
+///Live Rust contexts.  Only touched on the main thread.
+@MainActor private var mouseContexts: [UnsafeMutableRawPointer] = []
+@MainActor private var keyboardContexts: [UnsafeMutableRawPointer] = []
+
+final class PlatformCoalescedMouse: Sendable {
+    nonisolated(unsafe) let context: UnsafeMutableRawPointer
+
+    init(context: UnsafeMutableRawPointer) {
+        self.context = context
+        let sendContext = Int(bitPattern: context)
+        MainActor.shared.dispatchMainThreadFromRustContextDetached {
+            mouseContexts.append(UnsafeMutableRawPointer(bitPattern: sendContext)!)
+        }
+    }
+    deinit {
+        let sendContext = Int(bitPattern: context)
+        MainActor.shared.dispatchMainThreadFromRustContextDetached {
+            let context = UnsafeMutableRawPointer(bitPattern: sendContext)!
+            mouseContexts.removeAll { $0 == context }
+            raw_input_finish_mouse_event_context(context)
+        }
+    }
+}
+
+final class PlatformCoalescedKeyboard: Sendable {
+    nonisolated(unsafe) let context: UnsafeMutableRawPointer
+
+    init(context: UnsafeMutableRawPointer) {
+        self.context = context
+        let sendContext = Int(bitPattern: context)
+        MainActor.shared.dispatchMainThreadFromRustContextDetached {
+            keyboardContexts.append(UnsafeMutableRawPointer(bitPattern: sendContext)!)
+        }
+    }
+    deinit {
+        let sendContext = Int(bitPattern: context)
+        MainActor.shared.dispatchMainThreadFromRustContextDetached {
+            let context = UnsafeMutableRawPointer(bitPattern: sendContext)!
+            keyboardContexts.removeAll { $0 == context }
+        }
+    }
+}
+
+@_cdecl("PlatformCoalescedMouseNew") public func PlatformCoalescedMouseNew(context: UnsafeMutableRawPointer) -> UnsafeMutableRawPointer {
+    Unmanaged.passRetained(PlatformCoalescedMouse(context: context)).toOpaque()
+}
+
+@_cdecl("PlatformCoalescedMouseFree") public func PlatformCoalescedMouseFree(_ p: UnsafeMutableRawPointer) {
+    Unmanaged<PlatformCoalescedMouse>.fromOpaque(p).release()
+}
+
+@_cdecl("PlatformCoalescedKeyboardNew") public func PlatformCoalescedKeyboardNew(context: UnsafeMutableRawPointer) -> UnsafeMutableRawPointer {
+    Unmanaged.passRetained(PlatformCoalescedKeyboard(context: context)).toOpaque()
+}
+
+@_cdecl("PlatformCoalescedKeyboardFree") public func PlatformCoalescedKeyboardFree(_ p: UnsafeMutableRawPointer) {
+    Unmanaged<PlatformCoalescedKeyboard>.fromOpaque(p).release()
+}
+
+///Whether GameController knows of a keyboard.  Call on the main thread.
+@_cdecl("PlatformHardwareKeyboardPresentMain") public func PlatformHardwareKeyboardPresentMain() -> Bool {
+    MainActor.assumeIsolated {
+        GCKeyboard.coalesced != nil
+    }
+}
+
+@_cdecl("PlatformKeyCharacterMain") public func PlatformKeyCharacterMain(keyCode: UInt16, shift: Bool, option: Bool) -> UInt32 {
+    //UIKit doesn't expose the layout
+    0
+}
+
+@_cdecl("SwiftRawInputDebugWindowShow") public func DebugWindowShow() {
+    //not available on iOS
+}
+
+@_cdecl("SwiftRawInputDebugWindowHide") public func DebugWindowHide() {
+    //not available on iOS
+}
+
+///Maps UIKeyboardHIDUsage to the macOS virtual key codes Rust understands (HIToolbox/Events.h).
+private let hidToKeyCode: [UIKeyboardHIDUsage: UInt16] = [
+    .keyboardA: 0x00, .keyboardS: 0x01, .keyboardD: 0x02, .keyboardF: 0x03,
+    .keyboardH: 0x04, .keyboardG: 0x05, .keyboardZ: 0x06, .keyboardX: 0x07,
+    .keyboardC: 0x08, .keyboardV: 0x09, .keyboardNonUSBackslash: 0x0A, .keyboardB: 0x0B,
+    .keyboardQ: 0x0C, .keyboardW: 0x0D, .keyboardE: 0x0E, .keyboardR: 0x0F,
+    .keyboardY: 0x10, .keyboardT: 0x11, .keyboard1: 0x12, .keyboard2: 0x13,
+    .keyboard3: 0x14, .keyboard4: 0x15, .keyboard6: 0x16, .keyboard5: 0x17,
+    .keyboardEqualSign: 0x18, .keyboard9: 0x19, .keyboard7: 0x1A, .keyboardHyphen: 0x1B,
+    .keyboard8: 0x1C, .keyboard0: 0x1D, .keyboardCloseBracket: 0x1E, .keyboardO: 0x1F,
+    .keyboardU: 0x20, .keyboardOpenBracket: 0x21, .keyboardI: 0x22, .keyboardP: 0x23,
+    .keyboardReturnOrEnter: 0x24, .keyboardL: 0x25, .keyboardJ: 0x26, .keyboardQuote: 0x27,
+    .keyboardK: 0x28, .keyboardSemicolon: 0x29, .keyboardBackslash: 0x2A, .keyboardComma: 0x2B,
+    .keyboardSlash: 0x2C, .keyboardN: 0x2D, .keyboardM: 0x2E, .keyboardPeriod: 0x2F,
+    .keyboardTab: 0x30, .keyboardSpacebar: 0x31, .keyboardGraveAccentAndTilde: 0x32,
+    .keyboardDeleteOrBackspace: 0x33, .keyboardEscape: 0x35,
+    .keyboardRightGUI: 0x36, .keyboardLeftGUI: 0x37, .keyboardLeftShift: 0x38,
+    .keyboardCapsLock: 0x39, .keyboardLeftAlt: 0x3A, .keyboardLeftControl: 0x3B,
+    .keyboardRightShift: 0x3C, .keyboardRightAlt: 0x3D, .keyboardRightControl: 0x3E,
+    .keyboardF17: 0x40, .keypadDecimalPoint: 0x41, .keypadAsterisk: 0x43, .keypadPlus: 0x45,
+    .keypadNumLock: 0x47, .keyboardVolumeUp: 0x48, .keyboardVolumeDown: 0x49, .keyboardMute: 0x4A,
+    .keypadSlash: 0x4B, .keypadEnter: 0x4C, .keypadHyphen: 0x4E, .keyboardF18: 0x4F,
+    .keyboardF19: 0x50, .keypadEqualSign: 0x51, .keypad0: 0x52, .keypad1: 0x53,
+    .keypad2: 0x54, .keypad3: 0x55, .keypad4: 0x56, .keypad5: 0x57,
+    .keypad6: 0x58, .keypad7: 0x59, .keyboardF20: 0x5A, .keypad8: 0x5B, .keypad9: 0x5C,
+    .keyboardF5: 0x60, .keyboardF6: 0x61, .keyboardF7: 0x62, .keyboardF3: 0x63,
+    .keyboardF8: 0x64, .keyboardF9: 0x65, .keyboardF11: 0x67, .keyboardF13: 0x69,
+    .keyboardF16: 0x6A, .keyboardF14: 0x6B, .keyboardF10: 0x6D, .keyboardF12: 0x6F,
+    .keyboardF15: 0x71, .keyboardHelp: 0x72, .keyboardHome: 0x73, .keyboardPageUp: 0x74,
+    .keyboardDeleteForward: 0x75, .keyboardF4: 0x76, .keyboardEnd: 0x77, .keyboardF2: 0x78,
+    .keyboardPageDown: 0x79, .keyboardF1: 0x7A, .keyboardLeftArrow: 0x7B,
+    .keyboardRightArrow: 0x7C, .keyboardDownArrow: 0x7D, .keyboardUpArrow: 0x7E,
+]
+
+extension SurfaceView {
+    private var windowPointer: UnsafeMutableRawPointer? {
+        window.map { Unmanaged.passUnretained($0).toOpaque() }
+    }
+
+    private func reportPosition(_ point: CGPoint) {
+        let windowPointer = self.windowPointer
+        for context in mouseContexts {
+            raw_input_mouse_move(context, windowPointer, point.x, point.y, bounds.width, bounds.height)
+        }
+    }
+
+    private func reportButton(_ down: Bool) {
+        let windowPointer = self.windowPointer
+        for context in mouseContexts {
+            //0 asks Rust to count clicks
+            raw_input_mouse_button(context, windowPointer, 0, down, 0)
+        }
+    }
+
+    ///Adds recognizers for pointer hover and two-finger/trackpad scrolling.  Called once the view is in a window.
+    func installInputRecognizers() {
+        if gestureRecognizers?.isEmpty == false { return }
+        addGestureRecognizer(UIHoverGestureRecognizer(target: self, action: #selector(hover(_:))))
+        let scroll = UIPanGestureRecognizer(target: self, action: #selector(scroll(_:)))
+        scroll.allowedScrollTypesMask = .all
+        scroll.allowedTouchTypes = []
+        addGestureRecognizer(scroll)
+    }
+
+    @objc private func hover(_ recognizer: UIHoverGestureRecognizer) {
+        reportPosition(recognizer.location(in: self))
+    }
+
+    @objc private func scroll(_ recognizer: UIPanGestureRecognizer) {
+        let delta = recognizer.translation(in: self)
+        recognizer.setTranslation(.zero, in: self)
+        let windowPointer = self.windowPointer
+        for context in mouseContexts {
+            raw_input_mouse_scroll(context, windowPointer, delta.x, delta.y, false)
+        }
+    }
+
+    override func touchesBegan(_ touches: Set<UITouch>, with event: UIEvent?) {
+        guard let touch = touches.first, event?.allTouches?.count == 1 else { return }
+        reportPosition(touch.location(in: self))
+        reportButton(true)
+    }
+    override func touchesMoved(_ touches: Set<UITouch>, with event: UIEvent?) {
+        guard let touch = touches.first else { return }
+        reportPosition(touch.location(in: self))
+    }
+    override func touchesEnded(_ touches: Set<UITouch>, with event: UIEvent?) {
+        guard let touch = touches.first else { return }
+        reportPosition(touch.location(in: self))
+        reportButton(false)
+    }
+    override func touchesCancelled(_ touches: Set<UITouch>, with event: UIEvent?) {
+        reportButton(false)
+    }
+
+    private func reportPresses(_ presses: Set<UIPress>, down: Bool) -> Set<UIPress> {
+        var unhandled = Set<UIPress>()
+        let windowPointer = self.windowPointer
+        for press in presses {
+            guard let usage = press.key?.keyCode, let keyCode = hidToKeyCode[usage] else {
+                unhandled.insert(press)
+                continue
+            }
+            for context in keyboardContexts {
+                raw_input_key_notify_func(context, windowPointer, keyCode, down)
+            }
+        }
+        return unhandled
+    }
+    override func pressesBegan(_ presses: Set<UIPress>, with event: UIPressesEvent?) {
+        let unhandled = reportPresses(presses, down: true)
+        if !unhandled.isEmpty { super.pressesBegan(unhandled, with: event) }
+    }
+    override func pressesEnded(_ presses: Set<UIPress>, with event: UIPressesEvent?) {
+        let unhandled = reportPresses(presses, down: false)
+        if !unhandled.isEmpty { super.pressesEnded(unhandled, with: event) }
+    }
+    override func pressesCancelled(_ presses: Set<UIPress>, with event: UIPressesEvent?) {
+        let unhandled = reportPresses(presses, down: false)
+        if !unhandled.isEmpty { super.pressesCancelled(unhandled, with: event) }
+    }
+}
+#endif
@@ -0,0 +1,124 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(iOS)
+
+//
+//  UIKitSurface.swift
+//  SwiftAppWindow
+//
+//  The iOS counterpart of Surface.swift.  The view is backed by a CAMetalLayer.
+//
+import UIKit
+import QuartzCore
+import SwiftAppWindowC
+
+final class SurfaceView: UIView {
+    var sizeNotify: ((CGFloat, CGFloat) -> ())?
+    ///Frames for Rust's Surface, separate from the window's own.
+    lazy var frames = FrameRequester()
+
+    override class var layerClass: AnyClass {
+        CAMetalLayer.self
+    }
+    override var canBecomeFirstResponder: Bool {
+        true
+    }
+    override func didMoveToWindow() {
+        super.didMoveToWindow()
+        contentScaleFactor = window?.screen.scale ?? 1.0
+        installInputRecognizers()
+    }
+    override func layoutSubviews() {
+        super.layoutSubviews()
+        let scale = contentScaleFactor
+        if let layer = layer as? CAMetalLayer {
+            layer.drawableSize = CGSize(width: bounds.width * scale, height: bounds.height * scale)
+        }
+        sizeNotify?(bounds.width * scale, bounds.height * scale)
+    }
+}
+
+public final class Surface: Sendable {
+    let view: SurfaceView
+
+    init(view: SurfaceView) {
+        self.view = view
+    }
+
+    ///Returns the size, along with the backing scale.
+    func size() async -> (CGSize,CGFloat) {
+        await MainActor.run {
+            (view.bounds.size, view.contentScaleFactor)
+        }
+    }
+
+    func sizeMain() -> SwiftAppWindowSurfaceSize {
+        MainActor.assumeIsolated {
+            SwiftAppWindowSurfaceSize(width: view.bounds.width, height: view.bounds.height, scale: view.contentScaleFactor)
+        }
+    }
+    var rawHandle: UnsafeMutableRawPointer {
+        Unmanaged.passUnretained(view).toOpaque()
+    }
+    func sizeUpdate(notify: @escaping @Sendable (CGFloat, CGFloat) -> ()) {
+        Task {
+            await MainActor.run {
+                view.sizeNotify = notify
+            }
+        }
+    }
+    func requestFrame(context: UInt64, call: @escaping FrameRequester.Callback) {
+        Task {
+            await MainActor.run {
+                view.frames.request(context: context, call: call)
+            }
+        }
+    }
+    func damage(_ rect: CGRect) {
+        Task {
+            await MainActor.run {
+                view.layer.setNeedsDisplay(rect)
+            }
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_SurfaceSize") public func SurfaceSize(context: UInt64, surface: UnsafeMutableRawPointer, ret: @convention(c) @Sendable (UInt64, Double, Double, Double) -> ()) {
+    let surface = Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue()
+    Task {
+        let (size,scale) = await surface.size()
+        ret(context, size.width, size.height, scale)
+    }
+}
+
+@_cdecl("SwiftAppWindow_SurfaceSizeMain") public func SurfaceSizeMain(surface: UnsafeMutableRawPointer) -> SwiftAppWindowSurfaceSize {
+    let surface = Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue()
+    return surface.sizeMain()
+}
+
+@_cdecl("SwiftAppWindow_SurfaceRawHandle") public func RawHandle(surface: UnsafeMutableRawPointer) -> UnsafeMutableRawPointer {
+    let surface = Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue()
+    return surface.rawHandle
+}
+
+@_cdecl("SwiftAppWindow_SurfaceFree") public func SurfaceFree(surface: UnsafeMutableRawPointer) {
+    Unmanaged<Surface>.fromOpaque(surface).release()
+}
+
+///Balanced by SurfaceFree.
+@_cdecl("SwiftAppWindow_SurfaceRetain") public func SurfaceRetain(surface: UnsafeMutableRawPointer) {
+    _ = Unmanaged<Surface>.fromOpaque(surface).retain()
+}
+
+@_cdecl("SwiftAppWindow_SurfaceRequestFrame") public func SurfaceRequestFrame(surface: UnsafeMutableRawPointer, context: UInt64, call: @escaping FrameRequester.Callback) {
+    Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue().requestFrame(context: context, call: call)
+}
+
+@_cdecl("SwiftAppWindow_SurfaceSizeUpdate") public func SurfaceSizeUpdate(ctx: UInt64, surface: UnsafeMutableRawPointer, notify: @Sendable @convention(c) (UInt64, CGFloat, CGFloat) -> ()) {
+    Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue().sizeUpdate(notify: {
+        notify(ctx, $0, $1)
+    })
+}
+@_cdecl("SwiftAppWindow_SurfaceDamage") public func SurfaceDamage(surface: UnsafeMutableRawPointer, x: Double, y: Double, width: Double, height: Double) {
+    Unmanaged<Surface>.fromOpaque(surface).takeUnretainedValue().damage(CGRect(x: x, y: y, width: width, height: height))
+}
+#endif
@@ -0,0 +1,51 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(iOS)
+
+//
+//  UIKitThreading.swift
+//  SwiftAppWindow
+//
+//  The iOS counterpart of Threading.swift.  Rust calls the same symbols on both platforms.
+//
+import Foundation
+import UIKit
+
+final class AppDelegate: UIResponder, UIApplicationDelegate {}
+
+@_cdecl("SwiftAppWindowIsMainThread")
+public func IsMainThread() -> Bool {
+    Thread.current.isMainThread
+}
+
+@_cdecl("SwiftAppWindowRunMainThread")
+public func RunMainThread() {
+    MainActor.assumeIsolated {
+        //never returns
+        _ = UIApplicationMain(CommandLine.argc, CommandLine.unsafeArgv, nil, NSStringFromClass(AppDelegate.self))
+    }
+}
+
+@_cdecl("SwiftAppWindow_StopMainThread")
+public func StopMainThread() {
+    //iOS apps don't quit; the system suspends and terminates them
+    print("WARN: app_window: stop_main_thread has no effect on iOS")
+}
+
+@_cdecl("SwiftAppWindow_OnMainThread")
+public func OnMainThread(context: UInt64, cfn: @escaping @convention(c) @Sendable (UInt64) -> Void) {
+    Task {
+        await MainActor.run {
+            cfn(context)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_OnMainThreadAfter")
+public func OnMainThreadAfter(seconds: Double, context: UInt64, cfn: @escaping @convention(c) @Sendable (UInt64) -> Void) {
+    //in the common modes, so it keeps firing while the run loop tracks a resize or a scroll
+    let timer = CFRunLoopTimerCreateWithHandler(kCFAllocatorDefault, CFAbsoluteTimeGetCurrent() + seconds, 0, 0, 0) { _ in
+        cfn(context)
+    }
+    CFRunLoopAddTimer(CFRunLoopGetMain(), timer, .commonModes)
+}
+#endif
@@ -0,0 +1,208 @@
+//SPDX-License-Identifier: MPL-2.0
+#if os(iOS)
+
+//
+//  UIKitWindow.swift
+//  SwiftAppWindow
+//
+//  The iOS counterpart of Window.swift.  Windows fill their scene; position and size are ignored.
+//
+import UIKit
+import SwiftRs
+
+final class SurfaceViewController: UIViewController {
+    override func loadView() {
+        view = SurfaceView()
+    }
+}
+
+///Result codes shared with Rust (see sys/macos.rs)
+enum StartDragResult: Int32 {
+    case ok = 0
+    case noPointerGrab = 1
+    case unsupported = 2
+}
+
+///Holds the CloseBehavior callback from Rust (see window.rs).  iOS windows have no close button,
+///so it's never called, but Rust expects `free` once the behavior is replaced.
+final class CloseBehaviorContext: Sendable {
+    let context: UInt64
+    let free: @convention(c) @Sendable (UInt64) -> ()
+
+    init(context: UInt64, free: @convention(c) @Sendable (UInt64) -> ()) {
+        self.context = context
+        self.free = free
+    }
+    deinit {
+        free(context)
+    }
+}
+
+///Takes text, so UIKit shows the on-screen keyboard while it's first responder.  What's typed
+///on it isn't reported yet; hardware presses pass up the responder chain to the SurfaceView.
+final class VirtualKeyboardView: UIView, UIKeyInput {
+    override var canBecomeFirstResponder: Bool {
+        true
+    }
+    var hasText: Bool {
+        false
+    }
+    func insertText(_ text: String) {}
+    func deleteBackward() {}
+}
+
+@MainActor private func makeWindow() -> UIWindow {
+    let scene = UIApplication.shared.connectedScenes.compactMap { $0 as? UIWindowScene }.first
+    let window: UIWindow
+    if let scene {
+        window = UIWindow(windowScene: scene)
+    } else {
+        window = UIWindow(frame: UIScreen.main.bounds)
+    }
+    window.rootViewController = SurfaceViewController()
+    window.makeKeyAndVisible()
+    window.rootViewController!.view.becomeFirstResponder()
+    return window
+}
+
+public final class Window: Sendable {
+    @MainActor var window: UIWindow?
+    @MainActor var closeBehavior: CloseBehaviorContext?
+    @MainActor var virtualKeyboard: VirtualKeyboardView?
+    ///Made on the first Window::request_redraw
+    @MainActor var frames: FrameRequester?
+
+    init() {
+        Task {
+            await MainActor.run {
+                self.window = makeWindow()
+            }
+        }
+    }
+    deinit {
+        if let window {
+            Task {
+                await MainActor.run {
+                    window.isHidden = true
+                }
+            }
+        }
+    }
+    public func surface() async -> Surface {
+        let view = await MainActor.run {
+            self.window!.rootViewController!.view as! SurfaceView
+        }
+        return Surface(view: view)
+    }
+    @MainActor func setVisible(_ visible: Bool) {
+        window?.isHidden = !visible
+    }
+    @MainActor func requestFrame(context: UInt64, call: @escaping FrameRequester.Callback) {
+        let frames = self.frames ?? FrameRequester()
+        self.frames = frames
+        frames.request(context: context, call: call)
+    }
+    @MainActor func debugState() -> SwiftAppWindowDebugState {
+        guard let window else {
+            return SwiftAppWindowDebugState(width: 0, height: 0, scale: 1, focused: false, visible: false, decorated: false, state: 0, display: 0)
+        }
+        return SwiftAppWindowDebugState(width: window.bounds.width, height: window.bounds.height, scale: window.screen.scale, focused: window.isKeyWindow, visible: !window.isHidden, decorated: false, state: 0, display: 0)
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowNew") public func WindowNew(x: CGFloat, y: CGFloat, width: CGFloat, height: CGFloat, title: SRString) -> UnsafeMutableRawPointer {
+    Unmanaged.passRetained(Window()).toOpaque()
+}
+
+@_cdecl("SwiftAppWindow_WindowNewFullscreen") public func WindowNew(title: SRString) -> UnsafeMutableRawPointer {
+    Unmanaged.passRetained(Window()).toOpaque()
+}
+
+@_cdecl("SwiftAppWindow_WindowFree") public func WindowFree(window: UInt64) {
+    let window = UnsafeMutableRawPointer(bitPattern: Int(window))!
+    Unmanaged<Window>.fromOpaque(window).release()
+}
+
+@_cdecl("SwiftAppWindow_WindowSurface") public func WindowSurface(context: UInt64, window: UnsafeMutableRawPointer, ret: @convention(c) @Sendable (UInt64, UnsafeMutableRawPointer) -> ()) {
+    asyncBridge(context: context, input: window, inputType: Window.self, ret: ret) { window in
+        await window.surface()
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowShowWindowMenu") public func WindowShowWindowMenu(window: UnsafeMutableRawPointer, x: CGFloat, y: CGFloat) {
+    //no window menu on iOS
+}
+
+///Call on the main thread.
+@_cdecl("SwiftAppWindow_WindowDebugStateMain") public func WindowDebugStateMain(window: UnsafeMutableRawPointer) -> SwiftAppWindowDebugState {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    return MainActor.assumeIsolated {
+        window.debugState()
+    }
+}
+
+///The pointer input reports this window by, or nil if it hasn't been created yet.  Call on the main thread.
+@_cdecl("SwiftAppWindow_WindowInputHandleMain") public func WindowInputHandleMain(window: UnsafeMutableRawPointer) -> UnsafeMutableRawPointer? {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    return MainActor.assumeIsolated {
+        window.window.map { Unmanaged.passUnretained($0).toOpaque() }
+    }
+}
+
+///Shows or hides the on-screen keyboard by moving first responder.  Returns whether it moved.
+///Call on the main thread.
+@_cdecl("SwiftAppWindow_WindowSetVirtualKeyboardVisibleMain") public func WindowSetVirtualKeyboardVisibleMain(window: UnsafeMutableRawPointer, visible: Bool) -> Bool {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    return MainActor.assumeIsolated {
+        guard let view = window.window?.rootViewController?.view else {
+            return false
+        }
+        if visible {
+            let keyboard = window.virtualKeyboard ?? VirtualKeyboardView()
+            window.virtualKeyboard = keyboard
+            if keyboard.superview == nil {
+                view.addSubview(keyboard)
+            }
+            return keyboard.becomeFirstResponder()
+        } else {
+            //resigns first responder, which goes back to the surface for hardware presses
+            window.virtualKeyboard?.removeFromSuperview()
+            return view.isFirstResponder || view.becomeFirstResponder()
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowRequestFrame") public func WindowRequestFrame(window: UnsafeMutableRawPointer, context: UInt64, call: @escaping FrameRequester.Callback) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.requestFrame(context: context, call: call)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetVisible") public func WindowSetVisible(window: UnsafeMutableRawPointer, visible: Bool) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    Task {
+        await MainActor.run {
+            window.setVisible(visible)
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowSetCloseBehavior") public func WindowSetCloseBehavior(window: UnsafeMutableRawPointer, behavior: Int, context: UInt64, call: @convention(c) @Sendable (UInt64) -> Bool, free: @convention(c) @Sendable (UInt64) -> ()) {
+    let window = Unmanaged<Window>.fromOpaque(window).takeUnretainedValue()
+    let closeBehavior = CloseBehaviorContext(context: context, free: free)
+    Task {
+        await MainActor.run {
+            window.closeBehavior = closeBehavior
+        }
+    }
+}
+
+@_cdecl("SwiftAppWindow_WindowStartDrag") public func WindowStartDrag(context: UInt64, window: UnsafeMutableRawPointer, mime: SRString, payload: SRData, image: SRData, imageWidth: Int, operations: Int, endContext: UInt64, ended: @convention(c) @Sendable (UInt64, Int) -> (), ret: @convention(c) @Sendable (UInt64, Int32) -> ()) {
+    //drags on iOS begin from a UIDragInteraction the system recognizes, not on request
+    ended(endContext, 0)
+    ret(context, StartDragResult.unsupported.rawValue)
+}
+#endif
@@ -12,5 +12,19 @@ extern void raw_input_finish_key_event_context(const void* context);
 extern void raw_input_finish_mouse_event_context(const void* context);
 extern void raw_input_key_notify_func(const void *context, void *window, uint16_t keyCode, bool pressed);
 extern void raw_input_mouse_move(const void *context, void *window, double windowPosX, double windowPosY, double windowWidth, double windowHeight);
-extern void raw_input_mouse_button(const void *context, void *window, uint8_t button, bool down);
-extern void raw_input_mouse_scroll(const void *context, void *window, double deltaX, double deltaY);
\ No newline at end of file
+extern void raw_input_mouse_button(const void *context, void *window, uint8_t button, bool down, uint32_t clicks);
+extern void raw_input_mouse_scroll(const void *context, void *window, double deltaX, double deltaY, bool lines);
+extern void raw_input_text_commit(const char *text);
+extern void raw_input_text_preedit(const char *text, int64_t cursorStart, int64_t cursorEnd);
+extern void raw_input_text_cancel(void);
+
+typedef struct {
+    double width;
+    double height;
+    double scale;
+    bool focused;
+    bool visible;
+    bool decorated;
+    uint8_t state;
+    uint32_t display;
+} SwiftAppWindowDebugState;
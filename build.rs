@@ -1,15 +1,31 @@
 // SPDX-License-Identifier: MPL-2.0
 
 fn main() {
+    //targets without a built-in backend get `cfg(plugin_backend)`; see src/backend.rs
+    println!("cargo::rustc-check-cfg=cfg(plugin_backend)");
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    if !matches!(
+        target_os.as_str(),
+        "macos" | "ios" | "windows" | "linux" | "android"
+    ) && target_arch != "wasm32"
+    {
+        println!("cargo::rustc-cfg=plugin_backend");
+    }
+
     #[cfg(target_os = "macos")]
     {
-        let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+        use swift_rs::SwiftLinker;
         if target_os == "macos" {
-            use swift_rs::SwiftLinker;
             println!("cargo:rustc-env=MACOSX_DEPLOYMENT_TARGET=15.0");
             SwiftLinker::new("15.0")
                 .with_package("SwiftAppWindow", "SwiftAppWindow")
                 .link();
+        } else if target_os == "ios" {
+            SwiftLinker::new("15.0")
+                .with_ios("17.0")
+                .with_package("SwiftAppWindow", "SwiftAppWindow")
+                .link();
         }
     }
 }
@@ -77,7 +77,9 @@
 //! When `some_executor` support is enabled, this module automatically installs
 //! a [`MainThreadExecutor`](crate::some_executor::MainThreadExecutor) that can
 //! spawn futures on the main thread. This integrates seamlessly with the
-//! `some_executor` crate's executor-agnostic task spawning.
+//! `some_executor` crate's executor-agnostic task spawning.  Tasks start in order of their
+//! [`Priority`](::some_executor::Priority), as with
+//! [`application::submit_to_main_thread_with_priority`].
 //!
 //! # Performance Monitoring
 //!
@@ -89,15 +91,43 @@
 //! WARN: Main thread operation took too long: 15.2ms
 //! ```
 //!
+//! The threshold can be adjusted with [`application::set_slow_op_threshold`], and
+//! [`application::set_slow_op_callback`] lets you forward slow operations to your own
+//! telemetry instead of scraping logs.
+//!
 //! Keep main thread operations brief to maintain smooth UI performance.
 //!
+//! To find APIs that make more trips to the main thread or the window system than they
+//! should, turn on [`application::set_stats_enabled`] and read [`application::stats`].
+//!
+//! Many brief operations can add up, too.  The event loop stops draining its queue of
+//! closures once a [`application::DispatchBudget`] is spent, so platform events get a turn;
+//! tune it with [`application::set_dispatch_budget`].
+//!
+//! Closures submitted with [`application::submit_to_main_thread_with_priority`] don't wait
+//! behind less urgent ones, and can be cancelled until they start, so a burst of background
+//! work can't hold up input handling.
+//!
+//! Input doesn't have to wait its turn at all: with
+//! [`application::ApplicationOptions::dedicated_input_thread`] it's delivered on a thread of its
+//! own, where the platform allows.
+//!
 //! # Error Handling
 //!
 //! Most functions in this module will panic if [`application::main`] hasn't been called yet.
 //! This is intentional as it represents a programming error. Always ensure
 //! `main` is called at the start of your program.
 
-use std::sync::atomic::AtomicBool;
+mod priority;
+
+pub use priority::{
+    MainThreadWork, on_main_thread_with_priority, submit_to_main_thread_with_priority,
+};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) use std::time;
 #[cfg(target_arch = "wasm32")]
@@ -107,6 +137,39 @@ use crate::sys;
 
 pub(crate) static IS_MAIN_THREAD_RUNNING: AtomicBool = AtomicBool::new(false);
 
+/// Default threshold for [`set_slow_op_threshold`].
+const DEFAULT_SLOW_OP_THRESHOLD: std::time::Duration = std::time::Duration::from_millis(10);
+
+static SLOW_OP_THRESHOLD_NANOS: AtomicU64 =
+    AtomicU64::new(DEFAULT_SLOW_OP_THRESHOLD.as_nanos() as u64);
+
+/// Number of closures submitted to the main thread that have not started running yet.
+static MAIN_THREAD_QUEUE_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+/// Default for [`set_dispatch_budget`].
+const DEFAULT_DISPATCH_BUDGET: DispatchBudget =
+    DispatchBudget::new(64, std::time::Duration::from_millis(4));
+
+static DISPATCH_BUDGET_OPERATIONS: AtomicUsize =
+    AtomicUsize::new(DEFAULT_DISPATCH_BUDGET.max_operations);
+static DISPATCH_BUDGET_NANOS: AtomicU64 =
+    AtomicU64::new(DEFAULT_DISPATCH_BUDGET.max_duration.as_nanos() as u64);
+
+type SlowOpCallback = dyn Fn(&str, std::time::Duration, usize) + Send + Sync + 'static;
+static SLOW_OP_CALLBACK: RwLock<Option<Arc<SlowOpCallback>>> = RwLock::new(None);
+
+static STATS_ENABLED: AtomicBool = AtomicBool::new(false);
+static STATS: Mutex<BTreeMap<String, CallStats>> = Mutex::new(BTreeMap::new());
+static UNATTRIBUTED_ROUND_TRIPS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Debug label of the main thread closure running right now, while stats are enabled.
+    static CURRENT_LABEL: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+type LifecycleCallback = dyn Fn(LifecycleEvent) + Send + Sync + 'static;
+static LIFECYCLE_CALLBACK: RwLock<Option<Arc<LifecycleCallback>>> = RwLock::new(None);
+
 /// Error message constant used when operations require initialization.
 ///
 /// This message is used throughout the crate when operations fail because
@@ -141,9 +204,11 @@ pub(crate) const CALL_MAIN: &str = "Call app_window::application::main";
 /// | Platform | Main Thread Behavior | Closure Execution | Function Returns |
 /// |----------|---------------------|-------------------|------------------|
 /// | macOS    | Blocks processing AppKit events | Secondary thread | Never (unless app quits) |
+/// | iOS      | Runs `UIApplicationMain` | Secondary thread | Never |
 /// | Windows  | Blocks processing Win32 messages | Secondary thread | Never (unless app quits) |
 /// | Linux    | Blocks processing Wayland events | Secondary thread | Never (unless app quits) |
 /// | WASM     | Sets up event callbacks | Same thread | Immediately |
+/// | Android  | Hooks the activity's looper | Secondary thread | Immediately |
 ///
 /// # Executor Integration
 ///
@@ -157,6 +222,7 @@ pub(crate) const CALL_MAIN: &str = "Call app_window::application::main";
 /// This function will panic if:
 /// - Called from any thread other than the first thread (checked via [`is_main_thread()`])
 /// - Called more than once in the program's lifetime
+/// - The window system can't be reached; see [`try_main_with_options`]
 ///
 /// # Examples
 ///
@@ -170,14 +236,126 @@ pub(crate) const CALL_MAIN: &str = "Call app_window::application::main";
 /// });
 /// ```
 pub fn main<F: FnOnce() + Send + 'static>(closure: F) {
+    main_with_options(ApplicationOptions::default(), closure)
+}
+
+/// Options for [`main_with_options`].
+///
+/// The defaults are what [`main`] uses.
+///
+/// # Examples
+///
+/// ```
+/// use app_window::application::ApplicationOptions;
+///
+/// let options = ApplicationOptions::default().dedicated_input_thread(true);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ApplicationOptions {
+    dedicated_input_thread: bool,
+}
+
+impl ApplicationOptions {
+    /// Delivers input on a thread of its own, rather than the main thread.
+    ///
+    /// Input streams like `app_window::input::keyboard::KeyEvents` don't need the main thread,
+    /// but by default they get their events from it, so a slow main thread operation holds them
+    /// up too.  With this set, a long frame or a stuck closure no longer delays input.  Windows
+    /// are still managed on the main thread.
+    ///
+    /// The default is `false`.
+    ///
+    /// # Platform behavior
+    ///
+    /// On Linux, pointer, keyboard and text input get a Wayland event queue of their own,
+    /// dispatched on a thread named `app_window input`.  Elsewhere the platform delivers input
+    /// to the main thread, and this does nothing.
+    pub fn dedicated_input_thread(mut self, dedicated_input_thread: bool) -> Self {
+        self.dedicated_input_thread = dedicated_input_thread;
+        self
+    }
+}
+
+static DEDICATED_INPUT_THREAD: AtomicBool = AtomicBool::new(false);
+
+/// Whether the app asked for [`ApplicationOptions::dedicated_input_thread`].
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn dedicated_input_thread() -> bool {
+    DEDICATED_INPUT_THREAD.load(Ordering::Relaxed)
+}
+
+/// Like [`main`], with options.
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`main`], and if the window system can't be reached.
+/// Use [`try_main_with_options`] to handle that instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// # // ALLOW_NORUN_DOCTEST: Function blocks indefinitely running the event loop
+/// use app_window::application::{self, ApplicationOptions};
+///
+/// let options = ApplicationOptions::default().dedicated_input_thread(true);
+/// application::main_with_options(options, || {
+///     println!("Application ready!");
+/// });
+/// ```
+pub fn main_with_options<F: FnOnce() + Send + 'static>(options: ApplicationOptions, closure: F) {
+    if let Err(e) = try_main_with_options(options, closure) {
+        panic!("{e}");
+    }
+}
+
+/// Errors from [`try_main_with_options`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MainError {
+    /// The window system couldn't be reached, e.g. no Wayland compositor is running.
+    #[error("can't connect to the window system: {0}")]
+    Connect(String),
+}
+
+/// Like [`main_with_options`], but returns an error if the window system can't be reached,
+/// rather than panicking.
+///
+/// `closure` is not called in that case, and `main` may be called again, e.g. to retry.
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`main`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # // ALLOW_NORUN_DOCTEST: Function blocks indefinitely running the event loop
+/// use app_window::application::{self, ApplicationOptions};
+///
+/// if let Err(e) = application::try_main_with_options(ApplicationOptions::default(), || {
+///     println!("Application ready!");
+/// }) {
+///     eprintln!("{e}");
+///     std::process::exit(1);
+/// }
+/// ```
+pub fn try_main_with_options<F: FnOnce() + Send + 'static>(
+    options: ApplicationOptions,
+    closure: F,
+) -> Result<(), MainError> {
     assert!(sys::is_main_thread(), "Call main from the first thread");
     let old = IS_MAIN_THREAD_RUNNING.swap(true, std::sync::atomic::Ordering::Release);
 
     assert!(!old, "Do not call main more than once.");
-    main_postlude(closure)
+    DEDICATED_INPUT_THREAD.store(options.dedicated_input_thread, Ordering::Relaxed);
+    main_postlude(closure).inspect_err(|_| {
+        //never ran, so it may be tried again
+        IS_MAIN_THREAD_RUNNING.store(false, std::sync::atomic::Ordering::Release);
+    })
 }
 
-pub(crate) fn main_postlude<F>(closure: F)
+pub(crate) fn main_postlude<F>(closure: F) -> Result<(), MainError>
 where
     F: FnOnce() + Send + 'static,
 {
@@ -189,7 +367,774 @@ where
         MainThreadExecutor {},
     );
 
-    sys::run_main_thread(closure);
+    sys::run_main_thread(closure)
+}
+
+/// Sets how long a main thread operation may run before it is reported as slow.
+///
+/// Every closure run through [`on_main_thread`] or [`submit_to_main_thread`] is timed, and so
+/// is each poll of a future on the [main thread executor](crate::executor).  When one runs
+/// longer than this threshold, a warning is logged via `logwise` and the callback installed
+/// with [`set_slow_op_callback`] (if any) is invoked.
+///
+/// The default is 10ms.  Games with strict frame budgets may want something tighter;
+/// apps that knowingly do heavier main thread work may want to raise it to cut down on noise.
+///
+/// This may be called from any thread, at any time, including before [`main`].
+///
+/// # Examples
+///
+/// ```
+/// use app_window::application;
+/// use std::time::Duration;
+///
+/// // Report anything that would blow half a 120Hz frame
+/// application::set_slow_op_threshold(Duration::from_micros(4_166));
+/// assert_eq!(application::slow_op_threshold(), Duration::from_micros(4_166));
+/// ```
+pub fn set_slow_op_threshold(threshold: std::time::Duration) {
+    let nanos = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+    SLOW_OP_THRESHOLD_NANOS.store(nanos, Ordering::Relaxed);
+}
+
+/// Returns the threshold configured by [`set_slow_op_threshold`].
+pub fn slow_op_threshold() -> std::time::Duration {
+    std::time::Duration::from_nanos(SLOW_OP_THRESHOLD_NANOS.load(Ordering::Relaxed))
+}
+
+/// Installs a callback that is invoked whenever a main thread operation is slow.
+///
+/// The callback receives:
+///
+/// * the `debug_label` passed to [`on_main_thread`] or [`submit_to_main_thread`], or that the
+///   slow future was submitted to the executor with,
+/// * how long the operation ran,
+/// * how many other operations were queued for the main thread when it finished.
+///
+/// The queue depth is useful for telling an isolated slow operation apart from a backlog
+/// that will cause several frames of jank.
+///
+/// The callback runs on the main thread right after the slow operation, so it should be
+/// cheap; forward the data elsewhere rather than doing heavy work inline.  The `logwise`
+/// warning is still emitted regardless of whether a callback is installed.
+///
+/// Installing a callback replaces any previous one.  See [`clear_slow_op_callback`].
+///
+/// # Examples
+///
+/// ```
+/// use app_window::application;
+///
+/// application::set_slow_op_callback(|label, duration, queue_depth| {
+///     eprintln!("jank: {label} took {duration:?} ({queue_depth} queued)");
+/// });
+/// # application::clear_slow_op_callback();
+/// ```
+pub fn set_slow_op_callback<F>(callback: F)
+where
+    F: Fn(&str, std::time::Duration, usize) + Send + Sync + 'static,
+{
+    *SLOW_OP_CALLBACK.write().unwrap() = Some(Arc::new(callback));
+}
+
+/// Removes the callback installed by [`set_slow_op_callback`].
+pub fn clear_slow_op_callback() {
+    *SLOW_OP_CALLBACK.write().unwrap() = None;
+}
+
+/// How much queued main thread work may run before the platform's own events get a turn.
+///
+/// A burst of closures submitted with [`submit_to_main_thread`] (or futures woken on the main
+/// thread executor) could otherwise keep the event loop busy long enough that input and
+/// redraws pile up behind them.  Once either limit is reached, the event loop goes back to the
+/// platform, then resumes the queue where it left off.  Order is unaffected.
+///
+/// # Platform support
+///
+/// | Platform | Behavior |
+/// |----------|----------|
+/// | Linux    | Honored between Wayland dispatches |
+/// | Windows  | Honored; pending input and paint messages run before the queue resumes |
+/// | Android  | Honored; the looper services input before the queue resumes |
+/// | wasm32   | Honored; the browser's task queue runs before the queue resumes |
+/// | macOS, iOS | Not needed: each closure is its own main queue job, which the run loop already interleaves with events |
+///
+/// See [`set_dispatch_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DispatchBudget {
+    max_operations: usize,
+    max_duration: std::time::Duration,
+}
+
+impl DispatchBudget {
+    /// Creates a budget of at most `max_operations` closures or `max_duration`, whichever
+    /// comes first.  A `max_operations` of 0 is treated as 1.
+    pub const fn new(max_operations: usize, max_duration: std::time::Duration) -> Self {
+        DispatchBudget {
+            max_operations: if max_operations == 0 {
+                1
+            } else {
+                max_operations
+            },
+            max_duration,
+        }
+    }
+
+    /// The most closures to run before yielding.
+    pub const fn max_operations(&self) -> usize {
+        self.max_operations
+    }
+
+    /// The longest to keep running closures before yielding.
+    ///
+    /// This is checked between closures, so a single slow closure can exceed it; see
+    /// [`set_slow_op_threshold`] for finding those.
+    pub const fn max_duration(&self) -> std::time::Duration {
+        self.max_duration
+    }
+}
+
+impl Default for DispatchBudget {
+    fn default() -> Self {
+        DEFAULT_DISPATCH_BUDGET
+    }
+}
+
+/// Sets how much queued main thread work runs before the platform's events get a turn.
+///
+/// The default is 64 closures or 4ms.  Lower it if input feels laggy while your app floods the
+/// main thread; raise it if throughput of main thread work matters more than input latency.
+///
+/// This may be called from any thread, at any time, including before [`main`].
+///
+/// # Examples
+///
+/// ```
+/// use app_window::application::{self, DispatchBudget};
+/// use std::time::Duration;
+///
+/// application::set_dispatch_budget(DispatchBudget::new(16, Duration::from_millis(2)));
+/// assert_eq!(application::dispatch_budget().max_operations(), 16);
+/// # application::set_dispatch_budget(DispatchBudget::default());
+/// ```
+pub fn set_dispatch_budget(budget: DispatchBudget) {
+    let nanos = u64::try_from(budget.max_duration.as_nanos()).unwrap_or(u64::MAX);
+    DISPATCH_BUDGET_OPERATIONS.store(budget.max_operations, Ordering::Relaxed);
+    DISPATCH_BUDGET_NANOS.store(nanos, Ordering::Relaxed);
+}
+
+/// Returns the budget configured by [`set_dispatch_budget`].
+pub fn dispatch_budget() -> DispatchBudget {
+    DispatchBudget::new(
+        DISPATCH_BUDGET_OPERATIONS.load(Ordering::Relaxed),
+        std::time::Duration::from_nanos(DISPATCH_BUDGET_NANOS.load(Ordering::Relaxed)),
+    )
+}
+
+/// Tracks one turn of main thread dispatch against the [`DispatchBudget`].
+///
+/// Event loops create one when they start draining their queue and call
+/// [`DispatchSlice::record_op`] after each closure.
+#[cfg_attr(
+    any(target_os = "macos", target_os = "ios", plugin_backend),
+    allow(dead_code)
+)]
+pub(crate) struct DispatchSlice {
+    budget: DispatchBudget,
+    start: time::Instant,
+    operations: usize,
+}
+
+#[cfg_attr(
+    any(target_os = "macos", target_os = "ios", plugin_backend),
+    allow(dead_code)
+)]
+impl DispatchSlice {
+    pub(crate) fn new() -> Self {
+        DispatchSlice {
+            budget: dispatch_budget(),
+            start: crate::clock::now(),
+            operations: 0,
+        }
+    }
+
+    /// Counts a closure that just ran.  Returns `true` once the budget is spent and the
+    /// event loop should yield to the platform.
+    pub(crate) fn record_op(&mut self) -> bool {
+        self.operations += 1;
+        self.operations >= self.budget.max_operations
+            || crate::clock::since(self.start) >= self.budget.max_duration
+    }
+}
+
+/// Counts for one debug label, as reported by [`stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallStats {
+    main_thread_hops: u64,
+    platform_round_trips: u64,
+}
+
+impl CallStats {
+    /// How many closures with this label were sent to the main thread.
+    pub fn main_thread_hops(&self) -> u64 {
+        self.main_thread_hops
+    }
+
+    /// How many times closures with this label waited on the window system, such as a Wayland
+    /// round trip or a Win32 `SendMessage`.
+    pub fn platform_round_trips(&self) -> u64 {
+        self.platform_round_trips
+    }
+}
+
+/// A snapshot of the counters collected while [`set_stats_enabled`] is on.
+///
+/// Counts are kept per debug label.  The crate labels its own main thread work after the
+/// public API that does it (`"Window::new"`, `"surface"`, ...), so a label with more hops than
+/// calls points at an API that serializes several round trips.  Your own
+/// [`on_main_thread`] and [`submit_to_main_thread`] calls show up under the labels you give
+/// them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    by_label: BTreeMap<String, CallStats>,
+    unattributed_round_trips: u64,
+}
+
+impl Stats {
+    /// Counts for each debug label seen, in label order.
+    pub fn by_label(&self) -> impl Iterator<Item = (&str, &CallStats)> {
+        self.by_label
+            .iter()
+            .map(|(label, stats)| (label.as_str(), stats))
+    }
+
+    /// Counts for one debug label, if it was seen.
+    pub fn label(&self, label: &str) -> Option<&CallStats> {
+        self.by_label.get(label)
+    }
+
+    /// Closures sent to the main thread, across all labels.
+    pub fn main_thread_hops(&self) -> u64 {
+        self.by_label.values().map(|s| s.main_thread_hops).sum()
+    }
+
+    /// Waits on the window system, across all labels and including those outside any main
+    /// thread closure, such as connecting at startup.
+    pub fn platform_round_trips(&self) -> u64 {
+        self.unattributed_round_trips
+            + self
+                .by_label
+                .values()
+                .map(|s| s.platform_round_trips)
+                .sum::<u64>()
+    }
+}
+
+/// Turns the counters reported by [`stats`] on or off.
+///
+/// They're off by default.  While on, every main thread hop takes a lock to update its
+/// label's counts, so leave this off in release builds.  Turning it off keeps the counts
+/// collected so far; see [`reset_stats`].
+///
+/// This may be called from any thread, at any time, including before [`main`].
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application;
+///
+/// application::set_stats_enabled(true);
+/// let _surface = app_window::window::Window::default().await.surface().await;
+/// for (label, stats) in application::stats().by_label() {
+///     println!("{label}: {} hops", stats.main_thread_hops());
+/// }
+/// # }
+/// ```
+pub fn set_stats_enabled(enabled: bool) {
+    STATS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [`set_stats_enabled`] is on.
+pub fn stats_enabled() -> bool {
+    STATS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the counters collected while [`set_stats_enabled`] was on.
+pub fn stats() -> Stats {
+    Stats {
+        by_label: STATS.lock().unwrap().clone(),
+        unattributed_round_trips: UNATTRIBUTED_ROUND_TRIPS.load(Ordering::Relaxed),
+    }
+}
+
+/// Sets every counter reported by [`stats`] back to zero.
+pub fn reset_stats() {
+    STATS.lock().unwrap().clear();
+    UNATTRIBUTED_ROUND_TRIPS.store(0, Ordering::Relaxed);
+}
+
+/// Counts a closure sent to the main thread, if stats are enabled.
+fn record_main_thread_hop(debug_label: &str) {
+    if !stats_enabled() {
+        return;
+    }
+    let mut stats = STATS.lock().unwrap();
+    match stats.get_mut(debug_label) {
+        Some(s) => s.main_thread_hops += 1,
+        None => {
+            stats.insert(
+                debug_label.to_string(),
+                CallStats {
+                    main_thread_hops: 1,
+                    platform_round_trips: 0,
+                },
+            );
+        }
+    }
+}
+
+/// Counts a blocking wait on the window system, if stats are enabled.
+///
+/// Backends call this wherever they wait for the window system to answer.  The wait is
+/// charged to the main thread closure running it, if any.
+#[cfg_attr(
+    any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "android",
+        target_arch = "wasm32",
+        plugin_backend
+    ),
+    allow(dead_code)
+)]
+pub(crate) fn record_platform_round_trip() {
+    if !stats_enabled() {
+        return;
+    }
+    let label = CURRENT_LABEL.with_borrow(Clone::clone);
+    match label {
+        Some(label) => {
+            STATS
+                .lock()
+                .unwrap()
+                .entry(label)
+                .or_default()
+                .platform_round_trips += 1
+        }
+        None => {
+            UNATTRIBUTED_ROUND_TRIPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Reports a slow main thread operation to the log and the user's callback, if any.
+pub(crate) fn report_slow_op(debug_label: String, duration: std::time::Duration) {
+    let queue_depth = MAIN_THREAD_QUEUE_DEPTH.load(Ordering::Relaxed);
+    //clone out of the lock so the callback may itself reconfigure the hook
+    let callback = SLOW_OP_CALLBACK.read().unwrap().clone();
+    if let Some(callback) = callback {
+        callback(&debug_label, duration, queue_depth);
+    }
+    logwise::warn_sync!(
+        "main thread operation took too long: {duration}\n",
+        duration = logwise::privacy::LogIt(duration),
+        debug_label = logwise::privacy::IPromiseItsNotPrivate(debug_label),
+        queue_depth = queue_depth
+    );
+}
+
+/// A change in the application's lifecycle, as reported by the platform.
+///
+/// Desktop platforms keep windows and their surfaces alive until the app closes them, so
+/// today these are only reported on Android.  There the system may pause the app and take
+/// its surface away at any time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LifecycleEvent {
+    /// The app came to the foreground and is receiving input.
+    Resumed,
+    /// The app left the foreground.  Stop rendering and save any state you care about.
+    Paused,
+    /// A surface became available.  Call [`Window::surface`](crate::window::Window::surface)
+    /// again to get one for the new native window.
+    SurfaceCreated,
+    /// The surface is about to be destroyed.
+    ///
+    /// Tear down any graphics surface created from it before the callback returns; its
+    /// native window is invalid afterwards.
+    SurfaceLost,
+}
+
+/// Installs a callback that is invoked on every [`LifecycleEvent`].
+///
+/// The callback runs on the main thread, synchronously with the platform's notification, so
+/// that work like releasing a surface on [`LifecycleEvent::SurfaceLost`] completes before the
+/// platform proceeds.  Keep it short.
+///
+/// Installing a callback replaces any previous one.  See [`clear_lifecycle_callback`].
+///
+/// # Examples
+///
+/// ```
+/// use app_window::application::{self, LifecycleEvent};
+///
+/// application::set_lifecycle_callback(|event| {
+///     if event == LifecycleEvent::Paused {
+///         eprintln!("pausing rendering");
+///     }
+/// });
+/// # application::clear_lifecycle_callback();
+/// ```
+pub fn set_lifecycle_callback<F>(callback: F)
+where
+    F: Fn(LifecycleEvent) + Send + Sync + 'static,
+{
+    *LIFECYCLE_CALLBACK.write().unwrap() = Some(Arc::new(callback));
+}
+
+/// Removes the callback installed by [`set_lifecycle_callback`].
+pub fn clear_lifecycle_callback() {
+    *LIFECYCLE_CALLBACK.write().unwrap() = None;
+}
+
+/// Delivers a lifecycle event to the user's callback, if any.
+#[cfg_attr(not(target_os = "android"), allow(dead_code))]
+pub(crate) fn report_lifecycle_event(event: LifecycleEvent) {
+    logwise::info_sync!(
+        "lifecycle event {event}",
+        event = logwise::privacy::LogIt(event)
+    );
+    //clone out of the lock so the callback may itself reconfigure the hook
+    let callback = LIFECYCLE_CALLBACK.read().unwrap().clone();
+    if let Some(callback) = callback {
+        callback(event);
+    }
+}
+
+/// Whether this app may capture the contents of the screen.
+///
+/// Platforms that gate screen capture don't fail a capture without permission; they hand back
+/// black frames, or frames with only the wallpaper.  Check this first so you can tell the
+/// user why capture isn't working.
+///
+/// | Platform | Typical state |
+/// |----------|---------------|
+/// | macOS    | [`Granted`](Self::Granted) or [`NotGranted`](Self::NotGranted) (Screen Recording in System Settings) |
+/// | Linux    | [`AskEachTime`](Self::AskEachTime) through xdg-desktop-portal, or [`Unavailable`](Self::Unavailable) without a ScreenCast portal |
+/// | Windows  | [`NotRequired`](Self::NotRequired) |
+/// | WASM     | [`AskEachTime`](Self::AskEachTime) where the browser has `getDisplayMedia` |
+/// | iOS, Android | [`AskEachTime`](Self::AskEachTime) |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CapturePermission {
+    /// The platform doesn't gate screen capture.
+    NotRequired,
+    /// The user has allowed this app to capture the screen.
+    Granted,
+    /// The user hasn't allowed this app to capture the screen, or has denied it.  Captures
+    /// will come back blank.
+    NotGranted,
+    /// The platform asks the user each time a capture starts, so there's no lasting permission
+    /// to check.
+    AskEachTime,
+    /// There's no way to capture the screen here.
+    Unavailable,
+}
+
+/// Reports whether this app may capture the screen, without prompting the user.
+///
+/// See [`CapturePermission`] for what each platform reports.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application::{self, CapturePermission};
+///
+/// if application::capture_permission_state().await == CapturePermission::NotGranted {
+///     eprintln!("Allow screen recording in System Settings, then relaunch");
+/// }
+/// # }
+/// ```
+pub async fn capture_permission_state() -> CapturePermission {
+    sys::capture_permission_state().await
+}
+
+/// Asks the user for permission to capture the screen, and reports the result.
+///
+/// On macOS, the first request shows the system prompt; after that the user has to change it
+/// in System Settings.  Either way, macOS only applies a new grant after the app is
+/// relaunched, so this keeps returning [`CapturePermission::NotGranted`] until then.
+///
+/// Platforms that ask each time, or don't ask at all, have nothing to request up front and
+/// return the same as [`capture_permission_state`].
+pub async fn request_capture_permission() -> CapturePermission {
+    sys::request_capture_permission().await
+}
+
+/// How the app presents itself in the dock or taskbar.
+///
+/// Set this with [`set_activation_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum ActivationPolicy {
+    /// An ordinary app, with a dock icon or taskbar buttons.
+    #[default]
+    Regular,
+    /// A background utility that has windows, but no dock icon or taskbar buttons.
+    Accessory,
+    /// An app with no UI of its own.  On macOS it can't be activated or show windows; elsewhere
+    /// this behaves like [`Accessory`](Self::Accessory).
+    Prohibited,
+}
+
+/// An error changing the [`ActivationPolicy`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ActivationPolicyError {
+    /// The platform has no dock or taskbar the app can leave.
+    #[error("activation policies are not supported on this platform")]
+    Unsupported,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// Changes whether the app appears in the dock or taskbar.
+///
+/// Background utilities, such as apps that live in a menu bar extra or tray icon, can use
+/// [`ActivationPolicy::Accessory`] to stay out of the way.  The policy can be changed at any
+/// time, e.g. to show a dock icon only while a settings window is open.
+///
+/// To hide a single window rather than the whole app, see
+/// [`Window::set_skip_taskbar`](crate::window::Window::set_skip_taskbar).
+///
+/// # Platform behavior
+///
+/// | Platform | Behavior |
+/// |----------|----------|
+/// | macOS    | `NSApplication.setActivationPolicy` |
+/// | Windows  | Non-regular policies make every window, including ones created later, a tool window with no taskbar button |
+/// | Linux    | Unsupported: Wayland leaves the taskbar to the compositor, which lists every toplevel |
+/// | iOS, Android, WASM | Unsupported |
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application::{self, ActivationPolicy};
+///
+/// if let Err(e) = application::set_activation_policy(ActivationPolicy::Accessory).await {
+///     eprintln!("staying in the taskbar: {e}");
+/// }
+/// # }
+/// ```
+pub async fn set_activation_policy(policy: ActivationPolicy) -> Result<(), ActivationPolicyError> {
+    sys::set_activation_policy(policy).await
+}
+
+/// Hints about how the OS should treat a performance-sensitive app, such as a game.
+///
+/// All hints default to off, which leaves the platform's usual behavior alone.  Apply them
+/// with [`set_performance_hints`].
+///
+/// # Example
+///
+/// ```
+/// use app_window::application::PerformanceHints;
+///
+/// let hints = PerformanceHints::new().high_process_priority(true);
+/// assert!(hints.wants_high_process_priority());
+/// assert!(!hints.wants_prefer_discrete_gpu());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerformanceHints {
+    prefer_discrete_gpu: bool,
+    high_process_priority: bool,
+    disable_power_throttling: bool,
+}
+
+impl PerformanceHints {
+    /// Creates hints with everything off.
+    pub const fn new() -> Self {
+        PerformanceHints {
+            prefer_discrete_gpu: false,
+            high_process_priority: false,
+            disable_power_throttling: false,
+        }
+    }
+
+    /// Asks for the discrete GPU, on machines that also have an integrated one.
+    ///
+    /// Renderers that pick their own adapter can read this back with
+    /// [`PerformanceHints::wants_prefer_discrete_gpu`].
+    pub const fn prefer_discrete_gpu(mut self, prefer_discrete_gpu: bool) -> Self {
+        self.prefer_discrete_gpu = prefer_discrete_gpu;
+        self
+    }
+
+    /// Asks the scheduler to favor our threads over other apps' ordinary work.
+    pub const fn high_process_priority(mut self, high_process_priority: bool) -> Self {
+        self.high_process_priority = high_process_priority;
+        self
+    }
+
+    /// Opts out of the OS slowing us down to save power, e.g. by running us on efficiency
+    /// cores or coalescing our timers, even while in the background.
+    pub const fn disable_power_throttling(mut self, disable_power_throttling: bool) -> Self {
+        self.disable_power_throttling = disable_power_throttling;
+        self
+    }
+
+    /// Whether [`PerformanceHints::prefer_discrete_gpu`] is set.
+    pub const fn wants_prefer_discrete_gpu(&self) -> bool {
+        self.prefer_discrete_gpu
+    }
+
+    /// Whether [`PerformanceHints::high_process_priority`] is set.
+    pub const fn wants_high_process_priority(&self) -> bool {
+        self.high_process_priority
+    }
+
+    /// Whether [`PerformanceHints::disable_power_throttling`] is set.
+    pub const fn wants_disable_power_throttling(&self) -> bool {
+        self.disable_power_throttling
+    }
+}
+
+/// How the current platform handles each field of [`PerformanceHints`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PerformanceHintCapabilities {
+    /// Support for [`PerformanceHints::prefer_discrete_gpu`].
+    pub prefer_discrete_gpu: crate::audio::HintSupport,
+    /// Support for [`PerformanceHints::high_process_priority`].
+    pub high_process_priority: crate::audio::HintSupport,
+    /// Support for [`PerformanceHints::disable_power_throttling`].
+    pub disable_power_throttling: crate::audio::HintSupport,
+}
+
+/// An error applying [`PerformanceHints`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PerformanceHintError {
+    /// The platform reported an error, or refused, e.g. for want of privileges.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// Reports how the current platform handles each performance hint.
+///
+/// This doesn't require [`main`] to be running.
+pub fn performance_hint_capabilities() -> PerformanceHintCapabilities {
+    sys::performance_hint_capabilities()
+}
+
+/// Applies performance hints for the whole application.
+///
+/// Each call replaces the previous hints, and turning a hint off puts back what it changed.
+/// Hints the platform reports as
+/// [`HintSupport::NotNeeded`](crate::audio::HintSupport::NotNeeded) or
+/// [`HintSupport::Unsupported`](crate::audio::HintSupport::Unsupported) are silently ignored;
+/// check [`performance_hint_capabilities`] if you need to know.
+///
+/// # Platform behavior
+///
+/// | Platform | `prefer_discrete_gpu` | `high_process_priority` | `disable_power_throttling` |
+/// |----------|-----------------------|-------------------------|----------------------------|
+/// | Windows  | Writes the executable's `GpuPreference` to the per-user settings Settings > Display > Graphics uses, unless the user chose one there.  DXGI reads it when a factory is created, so set it before creating your renderer.  It outlasts the process unless turned off | `ABOVE_NORMAL_PRIORITY_CLASS` | Opts out of EcoQoS with `ProcessPowerThrottling` |
+/// | macOS    | Holds the system default `MTLDevice`, which switches Macs with automatic graphics switching to the discrete GPU | A user-initiated `NSProcessInfo` activity | A latency-critical `NSProcessInfo` activity, which also prevents App Nap |
+/// | Linux    | Unsupported: PRIME offload is chosen by environment variables before the renderer starts | Lowers the nice value of every thread, and so of threads they spawn.  Unsupported without `CAP_SYS_NICE` or an `RLIMIT_NICE` allowance | The shortest timer slack for the main thread |
+/// | iOS, Android, wasm32 | Unsupported | Unsupported | Unsupported |
+///
+/// Where the platform chooses the GPU per adapter, as with DXGI or WebGPU, renderers can ask for
+/// a high-performance adapter themselves (e.g. wgpu's `PowerPreference::HighPerformance`) when
+/// [`PerformanceHints::wants_prefer_discrete_gpu`] is set.
+///
+/// # Errors
+///
+/// Returns [`PerformanceHintError::Platform`] if a supported setting couldn't be changed.
+/// Hints applied before the failure stay applied.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application::{self, PerformanceHints};
+///
+/// let hints = PerformanceHints::new()
+///     .prefer_discrete_gpu(true)
+///     .high_process_priority(true)
+///     .disable_power_throttling(true);
+/// if let Err(e) = application::set_performance_hints(hints).await {
+///     eprintln!("Running with the OS's usual treatment: {e}");
+/// }
+/// # }
+/// ```
+pub async fn set_performance_hints(hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    sys::set_performance_hints(hints).await
+}
+
+/// Creates `count` hidden windows with default options, ready to be shown at once.
+///
+/// This is shorthand for [`WindowBuilder::prewarm`](crate::window::WindowBuilder::prewarm)
+/// on a default builder.  Claim one with
+/// [`WindowBuilder::from_pool`](crate::window::WindowBuilder::from_pool), leaving the position
+/// and size at their defaults.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application;
+/// use app_window::window::Window;
+///
+/// application::prewarm_windows(1).await;
+/// //...later
+/// let window = Window::builder().title("Inspector").from_pool().build().await;
+/// # }
+/// ```
+pub async fn prewarm_windows(count: usize) {
+    crate::window::WindowBuilder::new().prewarm(count).await
+}
+
+/// Sets the options that windows built from now on fall back on, where their
+/// [`WindowBuilder`](crate::window::WindowBuilder) doesn't set them.  See
+/// [`WindowDefaults`](crate::window::WindowDefaults) for how options are inherited.
+///
+/// This replaces any earlier defaults.  It may be called from any thread, at any time,
+/// including before [`main`].
+///
+/// # Example
+///
+/// ```
+/// use app_window::application;
+/// use app_window::coordinates::Size;
+/// use app_window::dialog::Color;
+/// use app_window::window::WindowDefaults;
+///
+/// application::set_window_defaults(
+///     WindowDefaults::new()
+///         .title("Sketch")
+///         .size(Size::new(1024.0, 768.0))
+///         .background(Color::from_rgb8(0x20, 0x20, 0x20)),
+/// );
+/// ```
+pub fn set_window_defaults(defaults: crate::window::WindowDefaults) {
+    crate::window::set_window_defaults(defaults)
+}
+
+/// Sets the defaults for windows built with
+/// [`WindowBuilder::class`](crate::window::WindowBuilder::class) `class`.  Their unset options
+/// come from these, then from [`set_window_defaults`].
+///
+/// This replaces any earlier defaults for the class.
+pub fn set_window_class_defaults(
+    class: impl Into<String>,
+    defaults: crate::window::WindowDefaults,
+) {
+    crate::window::set_window_class_defaults(class.into(), defaults)
 }
 
 /// Checks if the main thread event loop has been started.
@@ -248,10 +1193,17 @@ pub(crate) fn is_main_thread_running() -> bool {
 /// 3. Submits the wrapped closure to the main thread queue
 /// 4. Awaits the result from the receiver
 ///
+/// # Panics
+///
+/// If the closure panics, the panic is caught on the main thread and resumed in the awaiting
+/// task, with the original payload.  The event loop keeps running.  On wasm32, where panics
+/// abort, there's nothing to catch.
+///
 /// # Performance Monitoring
 ///
 /// Operations are automatically monitored for performance:
-/// - Operations taking >10ms generate a warning via `logwise`
+/// - Operations taking longer than [`slow_op_threshold`] (10ms by default) generate a
+///   warning via `logwise` and invoke the [`set_slow_op_callback`] hook
 /// - The `debug_label` helps identify slow operations
 /// - Each operation runs in a new `logwise` task context for tracing
 ///
@@ -366,12 +1318,15 @@ pub async fn on_main_thread<R: Send + 'static, F: FnOnce() -> R + Send + 'static
 ) -> R {
     let (sender, receiver) = r#continue::continuation();
     let block = move || {
-        let r = closure();
-        sender.send(r);
+        //the panic is the caller's, so send it back rather than losing the continuation
+        sender.send(std::panic::catch_unwind(AssertUnwindSafe(closure)));
     };
 
     submit_to_main_thread(debug_label, block);
-    receiver.await
+    match receiver.await {
+        Ok(r) => r,
+        Err(payload) => std::panic::resume_unwind(payload),
+    }
 }
 
 /// Submits a closure to be executed on the main thread without waiting.
@@ -383,7 +1338,7 @@ pub async fn on_main_thread<R: Send + 'static, F: FnOnce() -> R + Send + 'static
 /// # Arguments
 ///
 /// * `debug_label` - A descriptive label for debugging and performance monitoring.
-///   Shows up in logs if the operation takes >10ms.
+///   Shows up in logs if the operation takes longer than [`slow_op_threshold`].
 /// * `closure` - A function or closure to execute on the main thread.
 ///   Must be `FnOnce() + Send + 'static`.
 ///
@@ -393,12 +1348,14 @@ pub async fn on_main_thread<R: Send + 'static, F: FnOnce() -> R + Send + 'static
 /// - This function returns immediately without waiting
 /// - No way to get the result or know when execution completes
 /// - Operations are executed in the order they're submitted
+/// - If the closure panics, the panic is logged and the main thread moves on to the next
+///   operation (except on wasm32, where panics abort)
 ///
 /// # Performance Monitoring
 ///
 /// Like [`on_main_thread()`], this function includes automatic performance monitoring:
 /// - Creates a new `logwise` task context for the operation
-/// - Logs a warning if execution takes >10ms
+/// - Logs a warning if execution takes longer than [`slow_op_threshold`]
 /// - Preserves the calling context for tracing
 ///
 /// # Use Cases
@@ -489,14 +1446,25 @@ pub async fn on_main_thread<R: Send + 'static, F: FnOnce() -> R + Send + 'static
 /// This function wraps the closure with performance monitoring before calling
 /// the platform-specific `sys::on_main_thread()`. The wrapper:
 /// 1. Records the start time
-/// 2. Creates a new logwise task context
+/// 2. Creates a new logwise task context (except on wasm32 with the `wasm-slim` feature)
 /// 3. Executes the closure
 /// 4. Restores the previous context
-/// 5. Logs if execution was slow (>10ms)
+/// 5. Reports the operation if it was slow (see [`set_slow_op_threshold`])
 pub fn submit_to_main_thread<F: FnOnce() + Send + 'static>(debug_label: String, closure: F) {
     assert!(is_main_thread_running(), "{}", CALL_MAIN);
-    let perf = move || {
-        let start = time::Instant::now();
+    MAIN_THREAD_QUEUE_DEPTH.fetch_add(1, Ordering::Relaxed);
+    record_main_thread_hop(&debug_label);
+    sys::on_main_thread(move || run_submitted(debug_label, closure));
+}
+
+/// Runs a submitted closure on the main thread, with the monitoring described on
+/// [`submit_to_main_thread`].
+fn run_submitted<F: FnOnce()>(debug_label: String, closure: F) {
+    MAIN_THREAD_QUEUE_DEPTH.fetch_sub(1, Ordering::Relaxed);
+    let start = crate::clock::now();
+    //a task context per dispatch is most of logwise's weight in a wasm binary
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-slim")))]
+    let prior = {
         let prior = logwise::context::Context::current();
         let c = logwise::context::Context::new_task(
             Some(prior.clone()),
@@ -505,20 +1473,43 @@ pub fn submit_to_main_thread<F: FnOnce() + Send + 'static>(debug_label: String,
             logwise::log_enabled!(logwise::Level::DebugInternal),
         );
         c.set_current();
-        closure();
-        prior.set_current();
-
-        let duration = start.elapsed();
-        if duration > time::Duration::from_millis(10) {
-            logwise::warn_sync!(
-                "submit_to_main_thread operation took too long: {duration}\n",
-                duration = logwise::privacy::LogIt(duration),
-                debug_label = logwise::privacy::IPromiseItsNotPrivate(debug_label)
-            );
-        }
+        prior
     };
-    sys::on_main_thread(perf);
-    // sys::on_main_thread(closure);
+    let tracked = stats_enabled();
+    let prior_label = if tracked {
+        CURRENT_LABEL.replace(Some(debug_label.clone()))
+    } else {
+        None
+    };
+    //one bad closure shouldn't take the event loop, and everything queued behind it, down
+    if let Err(payload) = std::panic::catch_unwind(AssertUnwindSafe(closure)) {
+        logwise::error_sync!(
+            "Main thread closure {label} panicked: {message}",
+            label = logwise::privacy::LogIt(&debug_label),
+            message = logwise::privacy::LogIt(&panic_message(payload.as_ref()))
+        );
+    }
+    if tracked {
+        CURRENT_LABEL.set(prior_label);
+    }
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm-slim")))]
+    prior.set_current();
+
+    let duration = crate::clock::since(start);
+    if duration > slow_op_threshold() {
+        report_slow_op(debug_label, duration);
+    }
+}
+
+/// The message a panic was raised with, for the common payloads.
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "(non-string payload)"
+    }
 }
 
 /// Checks if the current thread is the main thread.
@@ -575,3 +1566,107 @@ pub fn submit_to_main_thread<F: FnOnce() + Send + 'static>(debug_label: String,
 pub fn is_main_thread() -> bool {
     sys::is_main_thread()
 }
+
+/// Proof that the code holding it runs on the main thread.
+///
+/// A token can only be obtained on the main thread, and it is neither `Send` nor `Sync`, so
+/// it can't leave.  APIs that are only sound on the main thread take one as an argument, such
+/// as [`Surface::raw_window_handle_with`](crate::surface::Surface::raw_window_handle_with).
+/// That turns "is this the main thread?" into a decision you make once, explicitly, instead
+/// of a silent assumption at every call.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application::{self, MainThreadToken};
+///
+/// application::on_main_thread("token".to_owned(), || {
+///     let token = MainThreadToken::new().expect("on_main_thread runs on the main thread");
+///     //...pass token to main-thread-only APIs
+/// #   let _ = token;
+/// })
+/// .await;
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MainThreadToken {
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl MainThreadToken {
+    /// Returns a token if called on the main thread, or `None` otherwise.
+    pub fn new() -> Option<Self> {
+        is_main_thread().then_some(MainThreadToken {
+            _not_send: std::marker::PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn dispatch_slice_counts_operations() {
+        let mut slice = DispatchSlice {
+            budget: DispatchBudget::new(3, std::time::Duration::from_secs(3600)),
+            start: crate::clock::now(),
+            operations: 0,
+        };
+        assert!(!slice.record_op());
+        assert!(!slice.record_op());
+        assert!(slice.record_op());
+
+        let mut slice = DispatchSlice {
+            budget: DispatchBudget::new(0, std::time::Duration::ZERO),
+            start: crate::clock::now(),
+            operations: 0,
+        };
+        assert_eq!(slice.budget.max_operations(), 1);
+        assert!(slice.record_op());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn dispatch_slice_yields_after_its_time() {
+        let mut slice = DispatchSlice {
+            budget: DispatchBudget::new(usize::MAX, std::time::Duration::from_secs(3600)),
+            start: crate::clock::now(),
+            operations: 0,
+        };
+        assert!(!slice.record_op());
+        //a slice whose time ran out before its first closure finished
+        let Some(start) = crate::clock::now().checked_sub(std::time::Duration::from_millis(5))
+        else {
+            return;
+        };
+        let mut slice = DispatchSlice {
+            budget: DispatchBudget::new(usize::MAX, std::time::Duration::from_millis(4)),
+            start,
+            operations: 0,
+        };
+        assert!(slice.record_op());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn stats_attribute_round_trips_to_labels() {
+        set_stats_enabled(true);
+        record_main_thread_hop("stats_test");
+        record_main_thread_hop("stats_test");
+        CURRENT_LABEL.set(Some("stats_test".to_string()));
+        record_platform_round_trip();
+        CURRENT_LABEL.set(None);
+        set_stats_enabled(false);
+        //disabled, so not counted
+        record_main_thread_hop("stats_test");
+
+        let stats = stats();
+        let call = stats.label("stats_test").unwrap();
+        assert_eq!(call.main_thread_hops(), 2);
+        assert_eq!(call.platform_round_trips(), 1);
+        assert!(stats.platform_round_trips() >= 1);
+    }
+}
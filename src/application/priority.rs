@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Main thread work that can jump the queue, or leave it, for
+//! [`submit_to_main_thread_with_priority`].
+//!
+//! Prioritized closures wait in a queue of our own.  Each submission also posts one dispatch to
+//! the platform, which runs whichever closure is most urgent when it arrives, not necessarily the
+//! one that posted it.  So input handling submitted behind a burst of background work takes the
+//! next dispatch, and a cancelled closure's dispatch finds nothing to do.
+
+use super::{
+    CALL_MAIN, MAIN_THREAD_QUEUE_DEPTH, is_main_thread_running, record_main_thread_hop,
+    run_submitted,
+};
+use crate::sys;
+use some_executor::Priority;
+use std::collections::{BTreeMap, VecDeque};
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+struct Queued {
+    id: u64,
+    debug_label: String,
+    closure: Box<dyn FnOnce() + Send>,
+}
+
+/// Closures by priority.  `Priority` orders most urgent first.
+#[derive(Default)]
+struct Queue(BTreeMap<Priority, VecDeque<Queued>>);
+
+impl Queue {
+    fn push(&mut self, priority: Priority, queued: Queued) {
+        self.0.entry(priority).or_default().push_back(queued);
+    }
+
+    fn pop_most_urgent(&mut self) -> Option<Queued> {
+        let mut entry = self.0.first_entry()?;
+        let queued = entry.get_mut().pop_front();
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+        queued
+    }
+
+    fn remove(&mut self, priority: Priority, id: u64) -> bool {
+        let Some(queue) = self.0.get_mut(&priority) else {
+            return false;
+        };
+        let Some(index) = queue.iter().position(|queued| queued.id == id) else {
+            return false;
+        };
+        queue.remove(index);
+        if queue.is_empty() {
+            self.0.remove(&priority);
+        }
+        true
+    }
+}
+
+static QUEUE: Mutex<Queue> = Mutex::new(Queue(BTreeMap::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A closure submitted with [`submit_to_main_thread_with_priority`], which can be cancelled
+/// until it starts.
+///
+/// Dropping the handle doesn't cancel the work.
+#[derive(Debug)]
+pub struct MainThreadWork {
+    id: u64,
+    priority: Priority,
+}
+
+impl MainThreadWork {
+    /// Removes the closure from the queue, if it hasn't started.
+    ///
+    /// Returns `true` if the closure was removed, and so will never run.  Returns `false` if it
+    /// already ran, is running, or was already cancelled.
+    pub fn cancel(&self) -> bool {
+        let removed = QUEUE.lock().unwrap().remove(self.priority, self.id);
+        if removed {
+            MAIN_THREAD_QUEUE_DEPTH.fetch_sub(1, Ordering::Relaxed);
+        }
+        removed
+    }
+
+    /// The priority the closure was submitted at.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+}
+
+/// Submits a closure to be executed on the main thread at `priority`, without waiting.
+///
+/// Like [`submit_to_main_thread`](super::submit_to_main_thread), but closures submitted this
+/// way run most urgent first: a [`Priority::UserInteractive`] closure runs before any
+/// [`Priority::Utility`] or [`Priority::Background`] closure still waiting, whenever it was
+/// submitted.  Closures of the same priority run in submission order.  Closures submitted without
+/// a priority keep their own order, and aren't reordered against these.
+///
+/// The returned [`MainThreadWork`] can cancel the closure until it starts.  Performance
+/// monitoring and panic handling are as for `submit_to_main_thread`.
+///
+/// # Example
+///
+/// ```
+/// # fn example() {
+/// use app_window::application;
+/// use some_executor::Priority;
+///
+/// let thumbnails: Vec<_> = (0..100)
+///     .map(|i| {
+///         application::submit_to_main_thread_with_priority(
+///             format!("thumbnail_{i}"),
+///             Priority::Background,
+///             move || println!("Drawing thumbnail {i}"),
+///         )
+///     })
+///     .collect();
+/// //runs before the thumbnails
+/// application::submit_to_main_thread_with_priority(
+///     "key_press".to_owned(),
+///     Priority::UserInteractive,
+///     || println!("Handling a key"),
+/// );
+/// //the user scrolled away; any that haven't started never will
+/// for thumbnail in &thumbnails {
+///     thumbnail.cancel();
+/// }
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// Panics if [`main`](super::main) hasn't been called.
+pub fn submit_to_main_thread_with_priority<F: FnOnce() + Send + 'static>(
+    debug_label: String,
+    priority: Priority,
+    closure: F,
+) -> MainThreadWork {
+    //a virtual main loop on this thread stands in for the native one, and won't run anything
+    //until it's pumped, so it can take the dispatch before the closure is queued
+    let dispatched = crate::executor::dispatch_to_virtual_loop(run_most_urgent);
+    if !dispatched {
+        assert!(is_main_thread_running(), "{}", CALL_MAIN);
+    }
+    MAIN_THREAD_QUEUE_DEPTH.fetch_add(1, Ordering::Relaxed);
+    record_main_thread_hop(&debug_label);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    QUEUE.lock().unwrap().push(
+        priority,
+        Queued {
+            id,
+            debug_label,
+            closure: Box::new(closure),
+        },
+    );
+    if !dispatched {
+        sys::on_main_thread(run_most_urgent);
+    }
+    MainThreadWork { id, priority }
+}
+
+/// Runs a closure on the main thread at `priority`, and returns its result.
+///
+/// Like [`on_main_thread`](super::on_main_thread), ordered as for
+/// [`submit_to_main_thread_with_priority`].  Dropping the future before the closure starts
+/// cancels it.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application;
+/// use some_executor::Priority;
+///
+/// let focused = application::on_main_thread_with_priority(
+///     "read_focus".to_owned(),
+///     Priority::UserInteractive,
+///     || true,
+/// )
+/// .await;
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// If the closure panics, the panic resumes in the awaiting task.
+pub async fn on_main_thread_with_priority<R: Send + 'static, F: FnOnce() -> R + Send + 'static>(
+    debug_label: String,
+    priority: Priority,
+    closure: F,
+) -> R {
+    /// Cancels the work if the future goes away first.
+    struct CancelOnDrop(MainThreadWork);
+    impl Drop for CancelOnDrop {
+        fn drop(&mut self) {
+            self.0.cancel();
+        }
+    }
+
+    let (sender, receiver) = r#continue::continuation();
+    let work = submit_to_main_thread_with_priority(debug_label, priority, move || {
+        sender.send(std::panic::catch_unwind(AssertUnwindSafe(closure)));
+    });
+    let _cancel = CancelOnDrop(work);
+    match receiver.await {
+        Ok(r) => r,
+        Err(payload) => std::panic::resume_unwind(payload),
+    }
+}
+
+fn run_most_urgent() {
+    let next = QUEUE.lock().unwrap().pop_most_urgent();
+    //nothing if the closure that posted us was cancelled, or run by an earlier dispatch
+    if let Some(queued) = next {
+        run_submitted(queued.debug_label, queued.closure);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Queue, Queued, submit_to_main_thread_with_priority};
+    use crate::executor::VirtualMainLoop;
+    use some_executor::Priority;
+    use std::sync::{Arc, Mutex};
+
+    fn queued(id: u64) -> Queued {
+        Queued {
+            id,
+            debug_label: String::new(),
+            closure: Box::new(|| ()),
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn most_urgent_first_then_fifo() {
+        let mut queue = Queue::default();
+        queue.push(Priority::Background, queued(0));
+        queue.push(Priority::Utility, queued(1));
+        queue.push(Priority::Background, queued(2));
+        queue.push(Priority::UserInteractive, queued(3));
+        assert!(queue.remove(Priority::Utility, 1));
+        assert!(!queue.remove(Priority::Utility, 1));
+        let order: Vec<u64> = std::iter::from_fn(|| queue.pop_most_urgent())
+            .map(|queued| queued.id)
+            .collect();
+        assert_eq!(order, [3, 0, 2]);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn virtual_loop_runs_most_urgent_first() {
+        let main_loop = VirtualMainLoop::install();
+        let ran: Arc<Mutex<Vec<&str>>> = Arc::default();
+        let submit = |name: &'static str, priority| {
+            let ran = ran.clone();
+            submit_to_main_thread_with_priority(name.to_string(), priority, move || {
+                //tasks spawned from prioritized work run on the same loop
+                crate::executor::already_on_main_thread_submit(name.to_string(), async move {
+                    ran.lock().unwrap().push(name)
+                })
+            })
+        };
+        submit("background", Priority::Background);
+        let cancelled = submit("cancelled", Priority::Utility);
+        submit("utility", Priority::Utility);
+        submit("interactive", Priority::UserInteractive);
+        assert!(cancelled.cancel());
+        //a dispatch each, though the cancelled one finds nothing to run
+        assert_eq!(main_loop.queue_depth(), 4);
+        assert_eq!(main_loop.run_until_idle(), 4);
+        assert_eq!(
+            *ran.lock().unwrap(),
+            ["interactive", "utility", "background"]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MPL-2.0
+
+/// Hints about how the OS should treat this application's audio.
+///
+/// All hints default to off, which leaves the platform's usual behavior alone.
+///
+/// # Example
+///
+/// ```
+/// use app_window::audio::AudioHints;
+///
+/// let hints = AudioHints::new().avoid_ducking(true).background_audio(true);
+/// assert!(hints.wants_avoid_ducking());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AudioHints {
+    avoid_ducking: bool,
+    background_audio: bool,
+}
+
+impl AudioHints {
+    /// Creates hints with everything off.
+    pub const fn new() -> Self {
+        AudioHints {
+            avoid_ducking: false,
+            background_audio: false,
+        }
+    }
+
+    /// Opts out of the OS lowering our volume while a communications app (a call, a voice
+    /// chat) is active.
+    pub const fn avoid_ducking(mut self, avoid_ducking: bool) -> Self {
+        self.avoid_ducking = avoid_ducking;
+        self
+    }
+
+    /// Asks the OS to keep our audio running at full fidelity while the window is in the
+    /// background or unfocused.
+    pub const fn background_audio(mut self, background_audio: bool) -> Self {
+        self.background_audio = background_audio;
+        self
+    }
+
+    /// Whether [`AudioHints::avoid_ducking`] is set.
+    pub const fn wants_avoid_ducking(&self) -> bool {
+        self.avoid_ducking
+    }
+
+    /// Whether [`AudioHints::background_audio`] is set.
+    pub const fn wants_background_audio(&self) -> bool {
+        self.background_audio
+    }
+}
+
+/// How the current platform handles a single hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HintSupport {
+    /// The platform has a setting for this and we change it.
+    Supported,
+    /// The platform already behaves this way; there's nothing to change.
+    NotNeeded,
+    /// The platform has no way for us to influence this.
+    Unsupported,
+}
+
+/// How the current platform handles each field of [`AudioHints`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AudioHintCapabilities {
+    /// Support for [`AudioHints::avoid_ducking`].
+    pub avoid_ducking: HintSupport,
+    /// Support for [`AudioHints::background_audio`].
+    pub background_audio: HintSupport,
+}
+
+/// An error applying [`AudioHints`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AudioHintError {
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// Reports how the current platform handles each audio hint.
+///
+/// This doesn't require [`application::main`](crate::application::main) to be running.
+///
+/// # Example
+///
+/// ```
+/// use app_window::audio::{HintSupport, capabilities};
+///
+/// if capabilities().avoid_ducking == HintSupport::Unsupported {
+///     println!("Can't stop the OS from ducking our audio here");
+/// }
+/// ```
+pub fn capabilities() -> AudioHintCapabilities {
+    crate::sys::audio_hint_capabilities()
+}
+
+/// Applies audio hints for the whole application.
+///
+/// Each call replaces the previous hints.  Hints the platform reports as
+/// [`HintSupport::NotNeeded`] or [`HintSupport::Unsupported`] are silently ignored; check
+/// [`capabilities`] if you need to know.
+///
+/// # Errors
+///
+/// Returns [`AudioHintError::Platform`] if a supported setting couldn't be changed.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::audio::{AudioHints, set_hints};
+///
+/// let hints = AudioHints::new().avoid_ducking(true).background_audio(true);
+/// if let Err(e) = set_hints(hints).await {
+///     eprintln!("Can't apply audio hints: {e}");
+/// }
+/// # }
+/// ```
+pub async fn set_hints(hints: AudioHints) -> Result<(), AudioHintError> {
+    crate::sys::set_audio_hints(hints).await
+}
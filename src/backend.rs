@@ -0,0 +1,548 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::Color;
+use crate::drag::{DragData, DragError, DragImage, DragReporter};
+use crate::window::{
+    CloseBehavior, Cursor, DecorationTheme, DecorationsMode, FrameTiming, IconData, NativeHandle,
+    PreviewImage, WindowDebugState, WindowEventSender, WindowSettingError,
+};
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// A boxed, `Send` future, as returned by the backend traits.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Requests one frame: calls its frame, on the main thread, when the compositor is ready for
+/// it.  Returned by [`BackendSurface::frame_requester`].
+pub type FrameRequester = Arc<dyn Fn(Box<dyn FnOnce(FrameTiming) + Send>) + Send + Sync>;
+
+/// The platform contract: main thread management and window creation.
+///
+/// Register an implementation with [`register`] before calling
+/// [`application::main`](crate::application::main).
+pub trait Backend: Send + Sync + 'static {
+    /// Whether the calling thread is the platform's main (UI) thread.
+    fn is_main_thread(&self) -> bool;
+
+    /// Runs the platform event loop on the current thread.
+    ///
+    /// Called once, from the main thread.  Run `closure` on a new thread once the loop is ready
+    /// to accept work, then keep servicing [`Backend::on_main_thread`] until
+    /// [`Backend::stop_main_thread`] is called.
+    fn run_main_thread(&self, closure: Box<dyn FnOnce() + Send>);
+
+    /// Runs `closure` on the main thread, from any thread, without waiting for it.
+    ///
+    /// Closures submitted from one thread must run in the order they were submitted.
+    fn on_main_thread(&self, closure: Box<dyn FnOnce() + Send>);
+
+    /// Runs `closure` on the main thread once `delay` has passed, from any thread.
+    ///
+    /// The default waits on a new thread, then calls [`Backend::on_main_thread`]; override it
+    /// to use the event loop's own timers.
+    fn on_main_thread_after(&self, delay: Duration, closure: Box<dyn FnOnce() + Send>) {
+        std::thread::Builder::new()
+            .name("app_window timer".to_string())
+            .spawn(move || {
+                std::thread::sleep(delay);
+                crate::sys::on_main_thread(closure);
+            })
+            .expect("Can't spawn timer thread");
+    }
+
+    /// Makes [`Backend::run_main_thread`] return.
+    fn stop_main_thread(&self);
+
+    /// Creates a window.
+    fn new_window(
+        &self,
+        position: Position,
+        size: Size,
+        title: String,
+    ) -> BoxFuture<'static, Box<dyn BackendWindow>>;
+
+    /// Creates a fullscreen window.
+    ///
+    /// The default reports that fullscreen isn't supported.
+    fn new_fullscreen_window(
+        &self,
+        title: String,
+    ) -> BoxFuture<'static, Result<Box<dyn BackendWindow>, String>> {
+        let _ = title;
+        Box::pin(async { Err("fullscreen windows aren't supported".to_string()) })
+    }
+
+    /// Shows a modal message to the user.
+    ///
+    /// The default logs the message.
+    fn alert(&self, message: String) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            logwise::warn_sync!("alert: {message}", message = message);
+        })
+    }
+}
+
+/// A window created by a [`Backend`].
+pub trait BackendWindow: Send + Sync + Debug {
+    /// Returns the window's drawable surface.
+    fn surface(&self) -> BoxFuture<'_, Box<dyn BackendSurface>>;
+
+    /// Shows or hides the window.  The default does nothing.
+    fn set_visible(&self, visible: bool) -> BoxFuture<'_, ()> {
+        let _ = visible;
+        Box::pin(async {})
+    }
+
+    /// Changes what happens when the user closes the window.  The default does nothing.
+    fn set_close_behavior(&self, close_behavior: CloseBehavior) -> BoxFuture<'_, ()> {
+        let _ = close_behavior;
+        Box::pin(async {})
+    }
+
+    /// Shows the window system's window menu.  The default does nothing.
+    fn show_window_menu(&self, position: Position) -> BoxFuture<'_, ()> {
+        let _ = position;
+        Box::pin(async {})
+    }
+
+    /// Resizes the window.  The default returns [`WindowSettingError::Unsupported`].
+    fn set_size(&self, size: Size) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = size;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Moves the window.  The default returns [`WindowSettingError::Unsupported`].
+    fn set_position(&self, position: Position) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = position;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Where the window is, as [`set_position`](Self::set_position) takes it.  The default
+    /// returns `None`.
+    fn outer_position(&self) -> BoxFuture<'_, Option<Position>> {
+        Box::pin(async { None })
+    }
+
+    /// Calls `frame`, on the main thread, when the compositor is ready for the window's next
+    /// frame.  The default paces frames like a 60Hz display.
+    fn request_frame(&self, frame: Box<dyn FnOnce(FrameTiming) + Send>) {
+        paced_frame(frame)
+    }
+
+    /// Shows the window on every virtual desktop.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_visible_on_all_workspaces(
+        &self,
+        visible: bool,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = visible;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Hides the window's taskbar entry.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_skip_taskbar(&self, skip: bool) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = skip;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Passes system keyboard shortcuts to the window.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn inhibit_system_shortcuts(
+        &self,
+        inhibit: bool,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = inhibit;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Keeps the pointer inside the window, or `region` of it.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn confine_pointer(
+        &self,
+        region: Option<Rect>,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = region;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Undoes [`BackendWindow::confine_pointer`].  The default does nothing.
+    fn release_pointer(&self) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Sets the picture task switchers show for the window.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_preview_image(
+        &self,
+        image: Option<PreviewImage>,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = image;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Sets the window's icon.  The default returns [`WindowSettingError::Unsupported`].
+    fn set_icon(&self, icon: Option<IconData>) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = icon;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Shows a label over the app's icon.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_badge(&self, label: Option<String>) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = label;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Shows progress over the window's taskbar button.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_progress(&self, progress: Option<f64>) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = progress;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Sets the cursor shown over the window's content.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_cursor(&self, cursor: Cursor) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = cursor;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Shows or hides the on-screen keyboard.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_virtual_keyboard_visible(
+        &self,
+        visible: bool,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = visible;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Chooses who draws the title bar and borders.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_decorations(
+        &self,
+        mode: DecorationsMode,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = mode;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Chooses how client-side title bar buttons are drawn.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_decoration_theme(
+        &self,
+        theme: DecorationTheme,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = theme;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Sets how wide the window's resize borders are.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_resize_border(&self, width: f64) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = width;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Shows `sheet` as a modal sheet on this window, finishing once it's dismissed.  The
+    /// default returns [`WindowSettingError::Unsupported`].
+    fn begin_sheet<'a>(
+        &'a self,
+        sheet: &'a dyn BackendWindow,
+    ) -> BoxFuture<'a, Result<(), WindowSettingError>> {
+        let _ = sheet;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Shows `sheet` as a modal sheet on this window, like [`begin_sheet`](Self::begin_sheet),
+    /// but finishes once it's up.  The default returns [`WindowSettingError::Unsupported`].
+    fn show_modal<'a>(
+        &'a self,
+        sheet: &'a dyn BackendWindow,
+    ) -> BoxFuture<'a, Result<(), WindowSettingError>> {
+        let _ = sheet;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Sets whether the user can resize the window.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_resizable(&self, resizable: bool) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = resizable;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Limits the sizes the user can resize the window to.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_size_limits(
+        &self,
+        min: Option<Size>,
+        max: Option<Size>,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = (min, max);
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Lets mouse and touch input pass through the window.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_click_through(
+        &self,
+        click_through: bool,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = click_through;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Publishes the app's accessibility tree for the window, or changes to it.  The default
+    /// returns [`WindowSettingError::Unsupported`].
+    #[cfg(feature = "accessibility")]
+    fn update_accessibility_tree(
+        &self,
+        update: accesskit::TreeUpdate,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = update;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Sets what runs when assistive technology acts on the app's tree.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    #[cfg(feature = "accessibility")]
+    fn set_accessibility_action_handler(
+        &self,
+        on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = on_action;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Asks for the window to be brought forward and given keyboard focus.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn focus(&self) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Whether the window has keyboard focus.  The default returns `false`.
+    fn is_focused(&self) -> BoxFuture<'_, bool> {
+        Box::pin(async { false })
+    }
+
+    /// Keeps the window above other apps' windows.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_always_on_top(&self, on_top: bool) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = on_top;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Sets the color the window shows until the app presents a frame.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_background(&self, color: Color) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = color;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Maximizes or restores the window.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_maximized(&self, maximized: bool) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = maximized;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Lets what's behind the window show through its transparent pixels.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_transparent(&self, transparent: bool) -> BoxFuture<'_, Result<(), WindowSettingError>> {
+        let _ = transparent;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// Makes the window belong to `parent`, staying above it.  The default returns
+    /// [`WindowSettingError::Unsupported`].
+    fn set_parent<'a>(
+        &'a self,
+        parent: &'a dyn BackendWindow,
+    ) -> BoxFuture<'a, Result<(), WindowSettingError>> {
+        let _ = parent;
+        Box::pin(async { Err(WindowSettingError::Unsupported) })
+    }
+
+    /// The handle this backend reports input for this window with, as in
+    /// [`crate::input::Window`].  The default returns `None`, so input isn't attributed to it.
+    fn input_handle(&self) -> BoxFuture<'_, Option<usize>> {
+        Box::pin(async { None })
+    }
+
+    /// The platform's own object for this window, for
+    /// [`Window::native_handle`](crate::window::Window::native_handle).  The default returns
+    /// `None`.
+    fn native_handle(&self) -> BoxFuture<'_, Option<NativeHandle>> {
+        Box::pin(async { None })
+    }
+
+    /// Where this window's events go; send to it as they happen.  The default returns a
+    /// sender nothing sends to, so `Window::events` never yields.
+    fn event_sender(&self) -> WindowEventSender {
+        WindowEventSender::default()
+    }
+
+    /// The window's scale factor.  The default takes it from [`debug_state`](Self::debug_state),
+    /// or 1.0 if that doesn't say.
+    fn scale_factor(&self) -> BoxFuture<'_, f64> {
+        Box::pin(async { self.debug_state().await.scale.unwrap_or(1.0) })
+    }
+
+    /// Describes the window's state for bug reports.  The default fills in only `backend`.
+    fn debug_state(&self) -> BoxFuture<'_, WindowDebugState> {
+        Box::pin(async { WindowDebugState::new("plugin") })
+    }
+
+    /// Starts an outgoing drag.  The default returns [`DragError::Unsupported`].
+    fn start_drag(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+    ) -> BoxFuture<'_, Result<(), DragError>> {
+        let _ = (data, image);
+        Box::pin(async { Err(DragError::Unsupported) })
+    }
+
+    /// Starts an outgoing drag allowing [`DragReporter::actions`], reporting how it goes to
+    /// `reporter`.  The default calls [`BackendWindow::start_drag`], so the outcome is unknown.
+    fn start_drag_with_actions(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+        reporter: DragReporter,
+    ) -> BoxFuture<'_, Result<(), DragError>> {
+        drop(reporter);
+        self.start_drag(data, image)
+    }
+}
+
+/// A surface belonging to a [`BackendWindow`].
+///
+/// See [`Surface`](crate::surface::Surface) for the semantics of each method.
+pub trait BackendSurface: Send + Sync + Debug {
+    /// Size in logical pixels and scale factor, callable from any thread.
+    fn size_scale(&self) -> BoxFuture<'_, (Size, f64)>;
+
+    /// Size in logical pixels and scale factor.  Only called on the main thread.
+    fn size_main(&self) -> (Size, f64);
+
+    /// The window handle for graphics APIs.
+    fn raw_window_handle(&self) -> RawWindowHandle;
+
+    /// The display handle for graphics APIs.
+    fn raw_display_handle(&self) -> RawDisplayHandle;
+
+    /// Calls `update` with the new size whenever the surface is resized.
+    fn size_update(&mut self, update: Box<dyn Fn(Size) + Send>);
+
+    /// Reports which parts of the surface changed in the next frame.  By default, nothing.
+    fn damage(&self, _rects: &[Rect]) {}
+
+    /// Returns a function that calls its frame, on the main thread, when the compositor is ready
+    /// for the surface's next frame.  It may outlive the surface.  The default paces frames like a
+    /// 60Hz display.
+    fn frame_requester(&self) -> FrameRequester {
+        Arc::new(paced_frame)
+    }
+}
+
+/// The default frame pacing, for backends without a compositor to ask.
+fn paced_frame(frame: Box<dyn FnOnce(FrameTiming) + Send>) {
+    crate::sys::on_main_thread_after(Duration::from_micros(16_667), move || {
+        frame(FrameTiming::new(
+            crate::application::time::Instant::now(),
+            None,
+            None,
+        ))
+    });
+}
+
+/// An error registering a [`Backend`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegisterError {
+    /// A backend was already registered.
+    #[error("a backend was already registered")]
+    AlreadyRegistered,
+    /// This target has a built-in backend, which can't be replaced.
+    #[error("this target has a built-in backend")]
+    BuiltInBackend,
+}
+
+static BACKEND: OnceLock<Box<dyn Backend>> = OnceLock::new();
+
+/// Installs the backend for a target without a built-in one.
+///
+/// Call this before [`application::main`](crate::application::main).
+///
+/// # Errors
+///
+/// * [`RegisterError::BuiltInBackend`] on targets app_window supports directly.
+/// * [`RegisterError::AlreadyRegistered`] if called more than once.
+pub fn register(backend: impl Backend) -> Result<(), RegisterError> {
+    if !cfg!(plugin_backend) {
+        return Err(RegisterError::BuiltInBackend);
+    }
+    BACKEND
+        .set(Box::new(backend))
+        .map_err(|_| RegisterError::AlreadyRegistered)
+}
+
+/// The registered backend, if any.
+#[cfg_attr(not(plugin_backend), allow(dead_code))]
+pub(crate) fn active() -> Option<&'static dyn Backend> {
+    BACKEND.get().map(|b| b.as_ref())
+}
+
+/// Delivers a keyboard event to every live [`Keyboard`](crate::input::keyboard::Keyboard).
+///
+/// Events are ignored on targets with a built-in backend.
+#[cfg(feature = "input")]
+pub fn report_key(key: crate::input::keyboard::key::KeyboardKey, down: bool) {
+    #[cfg(plugin_backend)]
+    crate::input::keyboard::plugin::report_key(key, down);
+    #[cfg(not(plugin_backend))]
+    let _ = (key, down);
+}
+
+/// Delivers the pointer's position within a window to every live
+/// [`Mouse`](crate::input::mouse::Mouse).
+///
+/// Events are ignored on targets with a built-in backend.
+#[cfg(feature = "input")]
+pub fn report_mouse_position(pos_x: f64, pos_y: f64, window_width: f64, window_height: f64) {
+    #[cfg(plugin_backend)]
+    crate::input::mouse::plugin::report_position(pos_x, pos_y, window_width, window_height);
+    #[cfg(not(plugin_backend))]
+    let _ = (pos_x, pos_y, window_width, window_height);
+}
+
+/// Delivers a mouse button event to every live [`Mouse`](crate::input::mouse::Mouse).
+///
+/// Events are ignored on targets with a built-in backend.
+#[cfg(feature = "input")]
+pub fn report_mouse_button(button: u8, down: bool) {
+    #[cfg(plugin_backend)]
+    crate::input::mouse::plugin::report_button(button, down);
+    #[cfg(not(plugin_backend))]
+    let _ = (button, down);
+}
+
+/// Delivers a scroll event to every live [`Mouse`](crate::input::mouse::Mouse).
+///
+/// Deltas are in logical pixels, positive toward the bottom right of the document, as
+/// [`Mouse::consume_scroll_delta`](crate::input::mouse::Mouse::consume_scroll_delta) reports
+/// them.  Events are ignored on targets with a built-in backend.
+#[cfg(feature = "input")]
+pub fn report_scroll(delta_x: f64, delta_y: f64) {
+    #[cfg(plugin_backend)]
+    crate::input::mouse::plugin::report_scroll(delta_x, delta_y);
+    #[cfg(not(plugin_backend))]
+    let _ = (delta_x, delta_y);
+}
@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use std::sync::{Arc, Mutex};
+
+/// An error watching the clipboard.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClipboardError {
+    /// The platform has no way for us to hear about clipboard changes.
+    #[error("clipboard change notifications are not supported here")]
+    Unsupported,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+type Handler = Arc<Mutex<dyn FnMut() + Send>>;
+
+struct Listeners {
+    next_id: u64,
+    handlers: Vec<(u64, Handler)>,
+}
+
+static LISTENERS: Mutex<Listeners> = Mutex::new(Listeners {
+    next_id: 0,
+    handlers: Vec::new(),
+});
+
+/// Keeps a handler from [`on_change`] registered.  Dropping it unregisters the handler, and
+/// once none are left the platform stops watching.
+#[derive(Debug)]
+#[must_use = "the handler is unregistered when the watch is dropped"]
+pub struct ClipboardWatch {
+    id: u64,
+}
+
+impl Drop for ClipboardWatch {
+    fn drop(&mut self) {
+        LISTENERS
+            .lock()
+            .unwrap()
+            .handlers
+            .retain(|(id, _)| *id != self.id);
+        if is_watched() || !crate::application::is_main_thread_running() {
+            return;
+        }
+        crate::application::submit_to_main_thread("ClipboardWatch::drop".to_string(), || {
+            //someone may have started watching again since
+            if !is_watched() {
+                crate::sys::unwatch_clipboard();
+            }
+        });
+    }
+}
+
+/// Whether any handler from [`on_change`] is registered.
+pub(crate) fn is_watched() -> bool {
+    !LISTENERS.lock().unwrap().handlers.is_empty()
+}
+
+fn register(handler: Handler) -> ClipboardWatch {
+    let mut listeners = LISTENERS.lock().unwrap();
+    let id = listeners.next_id;
+    listeners.next_id += 1;
+    listeners.handlers.push((id, handler));
+    ClipboardWatch { id }
+}
+
+/// Runs each handler from [`on_change`].  Call on the main thread when the platform says the
+/// clipboard changed.
+pub(crate) fn notify() {
+    //cloned out, so handlers can register or drop watches without deadlocking
+    let handlers: Vec<Handler> = LISTENERS
+        .lock()
+        .unwrap()
+        .handlers
+        .iter()
+        .map(|(_, handler)| handler.clone())
+        .collect();
+    for handler in handlers {
+        (handler.lock().unwrap())();
+    }
+}
+
+/// Calls `handler`, on the main thread, whenever the system clipboard's content changes.
+///
+/// This is for clipboard-manager-style features, or for enabling a Paste command only while
+/// there's something to paste.  The handler isn't told what changed; read the clipboard from
+/// it if you need to know.  Some platforms can only tell that the clipboard *may* have
+/// changed, so the handler can run when the content is the same as before.
+///
+/// The handler stays registered until the returned [`ClipboardWatch`] is dropped.
+///
+/// # Platform behavior
+///
+/// * Windows listens for `WM_CLIPBOARDUPDATE`.
+/// * macOS and iOS poll the pasteboard's `changeCount` twice a second, so a change may take
+///   that long to arrive.
+/// * Linux uses `ext-data-control` where the compositor offers it, which hears about every
+///   change.  Otherwise it uses `wl_data_device`, which only hears about the clipboard while one
+///   of our windows has keyboard focus, and again each time one gains it.
+/// * wasm32 hears about copies and cuts in the page, and changes elsewhere only in browsers
+///   with the `clipboardchange` event.
+///
+/// # Errors
+///
+/// * [`ClipboardError::Unsupported`] on Android, and on backends registered at runtime.
+/// * [`ClipboardError::Platform`] if the platform reported an error.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::clipboard;
+///
+/// let watch = clipboard::on_change(|| println!("the clipboard changed")).await;
+/// if let Err(e) = &watch {
+///     eprintln!("Can't watch the clipboard: {e}");
+/// }
+/// //...the handler runs until `watch` is dropped
+/// # }
+/// ```
+pub async fn on_change<F: FnMut() + Send + 'static>(
+    handler: F,
+) -> Result<ClipboardWatch, ClipboardError> {
+    //on failure, dropping this unregisters the handler
+    let watch = register(Arc::new(Mutex::new(handler)));
+    crate::application::on_main_thread("clipboard::on_change".to_string(), || {
+        crate::sys::watch_clipboard()
+    })
+    .await?;
+    Ok(watch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LISTENERS, notify, register};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn handlers_can_drop_their_own_watch() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        let slot = Arc::new(Mutex::new(None));
+        let own = slot.clone();
+        let watch = register(Arc::new(Mutex::new(move || {
+            counted.fetch_add(1, Ordering::Relaxed);
+            //unregisters from inside the handler
+            own.lock().unwrap().take();
+        })));
+        let id = watch.id;
+        *slot.lock().unwrap() = Some(watch);
+        notify();
+        notify();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        let listeners = LISTENERS.lock().unwrap();
+        assert!(listeners.handlers.iter().all(|(other, _)| *other != id));
+    }
+}
@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The time source app_window measures its own timing against.
+//!
+//! Dispatch budgets, slow operation reports and
+//! [`executor::run_chunked`](crate::executor::run_chunked) all read the time from
+//! [`now`](crate::clock::now).  By default that's the system clock.  Tests can install a
+//! [`TestClock`](crate::clock::TestClock) instead and move time forward with
+//! [`TestClock::advance`](crate::clock::TestClock::advance), so timing-dependent behavior is
+//! deterministic and doesn't cost real time.
+//!
+//! The clock is process-wide.  Install it once, before the code under test starts timing
+//! anything; an interval that straddles a clock change is measured across two clocks.
+
+use crate::application::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync + 'static {
+    /// The current time.  Must never go backwards.
+    fn now(&self) -> Instant;
+}
+
+/// The system's monotonic clock.  This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Clones share the same time, so keep one to advance after installing another with
+/// [`set_clock`].
+///
+/// # Example
+///
+/// ```
+/// use app_window::clock::{self, TestClock};
+/// use std::time::Duration;
+///
+/// let test_clock = TestClock::new();
+/// clock::set_clock(test_clock.clone());
+///
+/// let start = clock::now();
+/// test_clock.advance(Duration::from_millis(16));
+/// assert_eq!(clock::now() - start, Duration::from_millis(16));
+/// # clock::set_clock(clock::SystemClock);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    base: Instant,
+    offset_nanos: Arc<AtomicU64>,
+}
+
+impl TestClock {
+    /// Creates a clock stopped at the current system time.
+    pub fn new() -> Self {
+        TestClock {
+            base: Instant::now(),
+            offset_nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.offset_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// How far the clock has been advanced since it was created.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.offset_nanos.load(Ordering::Relaxed))
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.base + self.elapsed()
+    }
+}
+
+//checked first so the default clock doesn't take the lock
+static CUSTOM_CLOCK: AtomicBool = AtomicBool::new(false);
+static CLOCK: RwLock<Option<Arc<dyn Clock>>> = RwLock::new(None);
+
+/// Replaces the clock app_window reads the time from.
+///
+/// Install [`SystemClock`] to go back to the default.
+pub fn set_clock(clock: impl Clock) {
+    *CLOCK.write().unwrap() = Some(Arc::new(clock));
+    CUSTOM_CLOCK.store(true, Ordering::Release);
+}
+
+/// The current time, according to the installed clock.
+pub fn now() -> Instant {
+    if !CUSTOM_CLOCK.load(Ordering::Acquire) {
+        return Instant::now();
+    }
+    match CLOCK.read().unwrap().as_ref() {
+        Some(clock) => clock.now(),
+        None => Instant::now(),
+    }
+}
+
+/// Time passed since `earlier`, according to the installed clock.
+pub(crate) fn since(earlier: Instant) -> Duration {
+    now().saturating_duration_since(earlier)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn test_clock_advances_exactly() {
+        let clock = TestClock::new();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+        clock.clone().advance(Duration::from_millis(250));
+        assert_eq!(clock.now() - start, Duration::from_millis(250));
+        assert_eq!(clock.elapsed(), Duration::from_millis(250));
+    }
+}
@@ -6,6 +6,7 @@ A position type.
 The origin is in the upper-left corner.  Units are 'logical pixels', which may be pixels or points.
 */
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     x: f64,
     y: f64,
@@ -45,6 +46,7 @@ A size type.
 Units are 'logical pixels', which may be pixels or points.
 */
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Size {
     width: f64,
     height: f64,
@@ -79,3 +81,36 @@ impl Size {
         self.height
     }
 }
+
+/**
+A rectangle, given by its upper-left corner and its size.
+
+Units are 'logical pixels', which may be pixels or points.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Rect {
+    origin: Position,
+    size: Size,
+}
+
+impl Rect {
+    /**
+    Creates a new rectangle */
+    #[inline]
+    pub const fn new(origin: Position, size: Size) -> Rect {
+        Rect { origin, size }
+    }
+
+    /// Returns the upper-left corner of this rectangle.
+    #[inline]
+    pub const fn origin(&self) -> Position {
+        self.origin
+    }
+
+    /// Returns the size of this rectangle.
+    #[inline]
+    pub const fn size(&self) -> Size {
+        self.size
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MPL-2.0
+
+/// An sRGB color, with each component between 0.0 and 1.0.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Color {
+    red: f64,
+    green: f64,
+    blue: f64,
+}
+
+impl Color {
+    /// Creates a color from sRGB components, clamping each to 0.0..=1.0.
+    pub fn new(red: f64, green: f64, blue: f64) -> Self {
+        Color {
+            red: red.clamp(0.0, 1.0),
+            green: green.clamp(0.0, 1.0),
+            blue: blue.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Creates a color from 8-bit sRGB components.
+    pub fn from_rgb8(red: u8, green: u8, blue: u8) -> Self {
+        Color::new(
+            red as f64 / 255.0,
+            green as f64 / 255.0,
+            blue as f64 / 255.0,
+        )
+    }
+
+    /// The red component.
+    pub fn red(&self) -> f64 {
+        self.red
+    }
+
+    /// The green component.
+    pub fn green(&self) -> f64 {
+        self.green
+    }
+
+    /// The blue component.
+    pub fn blue(&self) -> f64 {
+        self.blue
+    }
+
+    /// The components as 8-bit sRGB values.
+    pub fn to_rgb8(&self) -> [u8; 3] {
+        let to_u8 = |c: f64| (c * 255.0).round() as u8;
+        [to_u8(self.red), to_u8(self.green), to_u8(self.blue)]
+    }
+
+    /// Parses a CSS-style `#rrggbb` color.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub(crate) fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#')?;
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Color::from_rgb8(
+            component(0)?,
+            component(2)?,
+            component(4)?,
+        ))
+    }
+}
+
+/// An error picking a color.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PickColorError {
+    /// The user dismissed the picker without choosing a color.
+    #[error("color picking was cancelled")]
+    Cancelled,
+    /// The platform or desktop environment has no way to pick a color from the screen.
+    #[error("color picking is not supported here")]
+    Unsupported,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// Lets the user pick a color from anywhere on the screen with the platform's eyedropper.
+///
+/// Resolves once the user clicks a pixel, or with [`PickColorError::Cancelled`] if they press
+/// Escape or otherwise dismiss the picker.  Only one picker should be open at a time.
+///
+/// # Errors
+///
+/// * [`PickColorError::Cancelled`] if the user dismissed the picker.
+/// * [`PickColorError::Unsupported`] if there's no eyedropper on this platform, or (on Linux)
+///   no xdg-desktop-portal that implements one or no off-by-default `portal` feature.
+/// * [`PickColorError::Platform`] if the platform reported an error.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::dialog::{PickColorError, pick_color};
+///
+/// match pick_color().await {
+///     Ok(color) => println!("picked {:?}", color.to_rgb8()),
+///     Err(PickColorError::Cancelled) => {}
+///     Err(e) => eprintln!("Can't pick a color: {e}"),
+/// }
+/// # }
+/// ```
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    crate::sys::pick_color().await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn hex_parsing() {
+        assert_eq!(
+            Color::from_hex("#ff8000").unwrap().to_rgb8(),
+            [0xFF, 0x80, 0x00]
+        );
+        assert_eq!(Color::from_hex("ff8000"), None);
+        assert_eq!(Color::from_hex("#ff80"), None);
+        assert_eq!(Color::from_hex("#gg8000"), None);
+    }
+}
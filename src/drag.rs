@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+
+/// The MIME type used to describe a list of files.
+pub const URI_LIST_MIME: &str = "text/uri-list";
+
+/// The content carried by a drag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DragData {
+    /// Arbitrary bytes, tagged with a MIME type such as `text/plain;charset=utf-8` or `image/png`.
+    Bytes {
+        /// The MIME type of `bytes`.
+        mime: String,
+        /// The payload.
+        bytes: Vec<u8>,
+    },
+    /// A list of files.
+    ///
+    /// These are offered to the receiving application as `text/uri-list` (and the
+    /// platform-native file list format, where there is one).
+    Paths(Vec<PathBuf>),
+}
+
+impl DragData {
+    /// Creates a drag payload from bytes and a MIME type.
+    pub fn bytes(mime: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+        DragData::Bytes {
+            mime: mime.into(),
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Creates a drag payload from a list of file paths.
+    pub fn paths(paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        DragData::Paths(paths.into_iter().map(Into::into).collect())
+    }
+
+    /// The MIME type this payload is offered as.
+    pub fn mime(&self) -> &str {
+        match self {
+            DragData::Bytes { mime, .. } => mime,
+            DragData::Paths(_) => URI_LIST_MIME,
+        }
+    }
+
+    /// The payload, encoded for [`Self::mime`].
+    ///
+    /// For [`DragData::Paths`] this is a `text/uri-list` document of `file://` URIs.
+    pub fn encoded(&self) -> Vec<u8> {
+        match self {
+            DragData::Bytes { bytes, .. } => bytes.clone(),
+            DragData::Paths(paths) => uri_list(paths).into_bytes(),
+        }
+    }
+}
+
+/// Encodes paths as a `text/uri-list` document (RFC 2483).
+pub(crate) fn uri_list(paths: &[PathBuf]) -> String {
+    let mut list = String::new();
+    for path in paths {
+        list.push_str(&file_uri(path));
+        list.push_str("\r\n");
+    }
+    list
+}
+
+/// Converts a path into a `file://` URI, percent-encoding anything that isn't unreserved.
+fn file_uri(path: &std::path::Path) -> String {
+    let path = path.to_string_lossy();
+    //windows paths use backslashes and a drive letter; URIs want file:///C:/foo
+    let path = path.replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+/// An image shown under the cursor while dragging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DragImage {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl DragImage {
+    /// Creates a drag image from non-premultiplied RGBA8 pixels, in row-major order.
+    ///
+    /// Returns [`DragError::InvalidImage`] if `rgba` is not `width * height * 4` bytes,
+    /// or if either dimension is zero.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Result<Self, DragError> {
+        if width == 0 || height == 0 || rgba.len() != width as usize * height as usize * 4 {
+            return Err(DragError::InvalidImage);
+        }
+        Ok(DragImage {
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    /// Width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The RGBA8 pixels.
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Converts to premultiplied BGRA, which is what most compositors want.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) fn premultiplied_bgra(&self) -> Vec<u8> {
+        premultiplied_bgra(&self.rgba)
+    }
+}
+
+/// Converts non-premultiplied RGBA8 pixels to premultiplied BGRA.
+#[cfg_attr(not(any(target_os = "linux", target_os = "windows")), allow(dead_code))]
+pub(crate) fn premultiplied_bgra(rgba: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(rgba.len());
+    for pixel in rgba.chunks_exact(4) {
+        let a = pixel[3] as u16;
+        let premultiply = |c: u8| ((c as u16 * a + 127) / 255) as u8;
+        out.extend_from_slice(&[
+            premultiply(pixel[2]),
+            premultiply(pixel[1]),
+            premultiply(pixel[0]),
+            pixel[3],
+        ]);
+    }
+    out
+}
+
+/// An error starting a drag.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DragError {
+    /// A drag can only start while a mouse button is held down over the window.
+    #[error("no mouse button is pressed over the window")]
+    NoPointerGrab,
+    /// The image dimensions don't match the pixel data.
+    #[error("drag image dimensions don't match the pixel data")]
+    InvalidImage,
+    /// The platform or compositor can't perform this drag.
+    #[error("drag-and-drop is not supported here")]
+    Unsupported,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// What a drop does with the dragged data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DragAction {
+    /// The target makes a copy; the source keeps its data.
+    Copy,
+    /// The target takes the data, so the source should delete its own once the drop is done.
+    Move,
+    /// The target makes a link or shortcut to the data.
+    Link,
+    /// The user chooses after dropping, usually from a menu the target shows.  Only Wayland
+    /// has this; the drag then ends with the action they chose.
+    Ask,
+}
+
+/// Which [`DragAction`]s a drag allows, combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DragActions(u8);
+
+impl DragActions {
+    /// No actions.
+    pub const NONE: DragActions = DragActions(0);
+    /// [`DragAction::Copy`].
+    pub const COPY: DragActions = DragActions(1);
+    /// [`DragAction::Move`].
+    pub const MOVE: DragActions = DragActions(2);
+    /// [`DragAction::Link`].
+    pub const LINK: DragActions = DragActions(4);
+    /// [`DragAction::Ask`].
+    pub const ASK: DragActions = DragActions(8);
+
+    /// Whether `action` is one of these.
+    pub const fn contains(self, action: DragAction) -> bool {
+        let bit = match action {
+            DragAction::Copy => Self::COPY,
+            DragAction::Move => Self::MOVE,
+            DragAction::Link => Self::LINK,
+            DragAction::Ask => Self::ASK,
+        };
+        self.0 & bit.0 != 0
+    }
+}
+
+impl std::ops::BitOr for DragActions {
+    type Output = DragActions;
+    fn bitor(self, rhs: DragActions) -> DragActions {
+        DragActions(self.0 | rhs.0)
+    }
+}
+
+/// How a drag ended; see [`DragSession::finished`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DragOutcome {
+    /// The data was dropped on a target, which performed this action.
+    Dropped(DragAction),
+    /// The user cancelled, or dropped somewhere that didn't accept the data.
+    Cancelled,
+    /// The drag is over, but the platform didn't say how it ended.
+    Unknown,
+}
+
+#[derive(Debug)]
+struct DragState {
+    actions: DragActions,
+    /// Whether the backend reads `actions` as the drag goes; see
+    /// [`DragReporter::follow_actions`].
+    followed: bool,
+    action: Option<DragAction>,
+    outcome: Option<DragOutcome>,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+struct DragShared {
+    state: Mutex<DragState>,
+    /// Live [`DragReporter`]s; once they're gone without finishing, the outcome is unknown.
+    reporters: AtomicUsize,
+}
+
+/// A drag in progress, as returned by
+/// [`Window::start_drag_with_actions`](crate::window::Window::start_drag_with_actions).
+#[derive(Debug)]
+pub struct DragSession {
+    shared: Arc<DragShared>,
+}
+
+impl DragSession {
+    /// Starts a session allowing `actions`, with the reporter its backend updates.
+    pub(crate) fn new(actions: DragActions) -> (DragSession, DragReporter) {
+        let shared = Arc::new(DragShared {
+            state: Mutex::new(DragState {
+                actions,
+                followed: false,
+                action: None,
+                outcome: None,
+                waker: None,
+            }),
+            reporters: AtomicUsize::new(1),
+        });
+        (
+            DragSession {
+                shared: shared.clone(),
+            },
+            DragReporter { shared },
+        )
+    }
+
+    /// The action the target under the pointer would perform if dropped on now, or `None`
+    /// if it wouldn't accept the drop.  After the drag, the action that was performed.
+    ///
+    /// Only Windows and Linux report this during the drag; elsewhere it's `None` until the
+    /// drop.
+    pub fn action(&self) -> Option<DragAction> {
+        self.shared.state.lock().unwrap().action
+    }
+
+    /// Changes the actions the drag allows, say as the app learns which ones make sense for
+    /// the target under the pointer.  Targets see the change the next time they ask, usually
+    /// as the pointer moves.  Once the drag is over this does nothing.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: the session's `NSDragOperation` mask is read again each time AppKit asks.
+    /// * **Linux, Windows, wasm32**: a drag's actions are fixed when it starts, so this
+    ///   returns [`DragError::Unsupported`].
+    pub fn set_actions(&self, actions: DragActions) -> Result<(), DragError> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.outcome.is_some() {
+            return Ok(());
+        }
+        if !state.followed {
+            return Err(DragError::Unsupported);
+        }
+        state.actions = actions;
+        Ok(())
+    }
+
+    /// Waits for the drag to end.
+    ///
+    /// If the outcome is [`DragOutcome::Dropped`] with [`DragAction::Move`], the target took
+    /// the data, and the source should now remove its copy.
+    pub async fn finished(&self) -> DragOutcome {
+        std::future::poll_fn(|cx| {
+            let mut state = self.shared.state.lock().unwrap();
+            match state.outcome {
+                Some(outcome) => Poll::Ready(outcome),
+                None => {
+                    state.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+/// Reports how a drag goes to its [`DragSession`].
+///
+/// Backends get one with each drag and call [`set_action`](Self::set_action) as the target
+/// changes its mind, then [`finish`](Self::finish) once.  Clones report to the same session;
+/// if they're all dropped without finishing, the outcome is [`DragOutcome::Unknown`].
+#[derive(Debug)]
+pub struct DragReporter {
+    shared: Arc<DragShared>,
+}
+
+impl DragReporter {
+    /// The actions the app allows for this drag.
+    pub fn actions(&self) -> DragActions {
+        self.shared.state.lock().unwrap().actions
+    }
+
+    /// Says this backend reads [`actions`](Self::actions) as the drag goes, rather than only
+    /// when it starts, so [`DragSession::set_actions`] can change them.
+    pub fn follow_actions(&self) {
+        self.shared.state.lock().unwrap().followed = true;
+    }
+
+    /// The action last recorded with [`set_action`](Self::set_action).
+    pub fn action(&self) -> Option<DragAction> {
+        self.shared.state.lock().unwrap().action
+    }
+
+    /// Records the action the target would perform now, or `None` if it wouldn't accept.
+    pub fn set_action(&self, action: Option<DragAction>) {
+        self.shared.state.lock().unwrap().action = action;
+    }
+
+    /// Ends the drag.  Only the first call counts.
+    pub fn finish(&self, outcome: DragOutcome) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.outcome.is_some() {
+            return;
+        }
+        if let DragOutcome::Dropped(action) = outcome {
+            state.action = Some(action);
+        }
+        state.outcome = Some(outcome);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Clone for DragReporter {
+    fn clone(&self) -> Self {
+        self.shared.reporters.fetch_add(1, Ordering::Relaxed);
+        DragReporter {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for DragReporter {
+    fn drop(&mut self) {
+        if self.shared.reporters.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.finish(DragOutcome::Unknown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn uri_list_encoding() {
+        let data = DragData::paths(["/tmp/a b.txt", "/home/ü"]);
+        assert_eq!(data.mime(), URI_LIST_MIME);
+        assert_eq!(
+            String::from_utf8(data.encoded()).unwrap(),
+            "file:///tmp/a%20b.txt\r\nfile:///home/%C3%BC\r\n"
+        );
+        assert_eq!(
+            uri_list(&[PathBuf::from("C:\\Users\\x.png")]),
+            "file:///C:/Users/x.png\r\n"
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn session_reports_first_outcome() {
+        let (session, reporter) = DragSession::new(DragActions::COPY | DragActions::MOVE);
+        assert!(reporter.actions().contains(DragAction::Move));
+        assert!(!reporter.actions().contains(DragAction::Link));
+        reporter.set_action(Some(DragAction::Copy));
+        assert_eq!(session.action(), Some(DragAction::Copy));
+        reporter.finish(DragOutcome::Dropped(DragAction::Move));
+        reporter.finish(DragOutcome::Cancelled);
+        let outcome = test_executors::spin_on(session.finished());
+        assert_eq!(outcome, DragOutcome::Dropped(DragAction::Move));
+        assert_eq!(session.action(), Some(DragAction::Move));
+
+        //a backend that never says
+        let (session, reporter) = DragSession::new(DragActions::COPY);
+        drop(reporter.clone());
+        assert_eq!(session.shared.state.lock().unwrap().outcome, None);
+        drop(reporter);
+        let outcome = test_executors::spin_on(session.finished());
+        assert_eq!(outcome, DragOutcome::Unknown);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn actions_change_mid_drag_when_followed() {
+        let (session, reporter) = DragSession::new(DragActions::COPY);
+        assert_eq!(
+            session.set_actions(DragActions::MOVE),
+            Err(DragError::Unsupported)
+        );
+        assert_eq!(reporter.actions(), DragActions::COPY);
+
+        reporter.follow_actions();
+        session
+            .set_actions(DragActions::COPY | DragActions::MOVE)
+            .unwrap();
+        assert!(reporter.actions().contains(DragAction::Move));
+        reporter.finish(DragOutcome::Dropped(DragAction::Copy));
+        //too late to matter
+        session.set_actions(DragActions::NONE).unwrap();
+        assert!(reporter.actions().contains(DragAction::Move));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn image_validation() {
+        assert_eq!(
+            DragImage::new(2, 1, vec![0; 4]),
+            Err(DragError::InvalidImage)
+        );
+        assert_eq!(DragImage::new(0, 0, vec![]), Err(DragError::InvalidImage));
+        let image = DragImage::new(1, 1, vec![255, 0, 0, 128]).unwrap();
+        assert_eq!(image.premultiplied_bgra(), vec![0, 0, 128, 128]);
+    }
+}
@@ -7,9 +7,12 @@ This module provides an executor specifically designed to run futures on the app
 main thread while cooperating with the native event loop. This is essential for platforms
 that require certain operations (like UI updates) to happen on the main thread.
 
-The executor uses a task queue that's processed whenever the main thread's event loop
-allows it, ensuring that futures can yield control back to the native event loop for
-smooth operation.
+The executor keeps a run queue of woken tasks, which is worked through whenever the main
+thread's event loop allows it.  Each turn polls the tasks that were waiting when it started,
+oldest first, for up to a few milliseconds; tasks woken during the turn wait for the next one.
+That way thousands of tasks, or a task that keeps waking itself, still leave the native event
+loop room to process input and draw.  A task woken several times before it's polled is only
+queued once.
 
 # Thread Safety
 
@@ -18,6 +21,12 @@ provides two main entry points:
 - [`on_main_thread_async`](crate::executor::on_main_thread_async): Can be called from any thread to run a future on the main thread
 - [`already_on_main_thread_submit`](crate::executor::already_on_main_thread_submit): Must be called from the main thread
 
+For medium-weight work that must happen on the main thread but would take too long in one go,
+[`run_chunked`](crate::executor::run_chunked) spreads it across several turns of the event loop.
+
+[`sleep`](crate::executor::sleep) and [`interval`](crate::executor::interval) wait on timers the
+native event loop drives, so apps can animate on the main thread without threads of their own.
+
 # Integration with `some_executor`
 
 When the `some_executor` feature is enabled, this executor can be wrapped with
@@ -26,27 +35,98 @@ implementation.
 */
 use crate::application::submit_to_main_thread;
 use crate::sys;
-use std::cell::Cell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::task::{Context, RawWaker, RawWakerVTable};
 
+mod timer;
+#[cfg(any(test, feature = "headless"))]
+mod virtual_loop;
+
+pub use timer::{Interval, Sleep, interval, sleep};
+#[cfg(any(test, feature = "headless"))]
+pub use virtual_loop::VirtualMainLoop;
+
 /// Static counter for generating unique task IDs.
 static NEXT_TASK_ID: AtomicUsize = AtomicUsize::new(1);
 
+/// How long one turn of the executor polls tasks before yielding to the event loop.
+const TURN_BUDGET: std::time::Duration = std::time::Duration::from_millis(4);
+
+/// Where the executor's work runs: the native event loop on the main thread, or a
+/// [`VirtualMainLoop`](virtual_loop::VirtualMainLoop) stepped by hand.
+#[derive(Clone)]
+enum MainLoop {
+    Native,
+    #[cfg(any(test, feature = "headless"))]
+    Virtual(Arc<virtual_loop::Queue>),
+}
+
+impl MainLoop {
+    /// The loop this thread belongs to.  Threads that aren't virtual main threads use the
+    /// native one.
+    fn current() -> Self {
+        #[cfg(any(test, feature = "headless"))]
+        if let Some(queue) = virtual_loop::current() {
+            return MainLoop::Virtual(queue);
+        }
+        MainLoop::Native
+    }
+
+    fn is_main_thread(&self) -> bool {
+        match self {
+            MainLoop::Native => sys::is_main_thread(),
+            //only the thread that installed it sees a virtual loop as current
+            #[cfg(any(test, feature = "headless"))]
+            MainLoop::Virtual(_) => true,
+        }
+    }
+
+    fn submit<F: FnOnce() + Send + 'static>(&self, debug_label: String, closure: F) {
+        match self {
+            MainLoop::Native => submit_to_main_thread(debug_label, closure),
+            #[cfg(any(test, feature = "headless"))]
+            MainLoop::Virtual(queue) => queue.push(Box::new(closure)),
+        }
+    }
+}
+
+/// Posts `dispatch` to the `VirtualMainLoop` installed on this thread, if there is one, and
+/// returns whether it did.  If not, it's for the native event loop.
+pub(crate) fn dispatch_to_virtual_loop(dispatch: fn()) -> bool {
+    #[cfg(any(test, feature = "headless"))]
+    if let MainLoop::Virtual(queue) = MainLoop::current() {
+        queue.push(Box::new(dispatch));
+        return true;
+    }
+    _ = dispatch;
+    false
+}
+
 /// Internal state shared between a task and its waker.
 ///
-/// This struct tracks the task ID for waker operations.
+/// This struct tracks the task ID for waker operations, and the loop the task runs on, so
+/// wakes from other threads find their way back to it.
 struct Inner {
     task_id: usize,
+    main_loop: MainLoop,
+    /// Set from the first wake until the task is polled, so later wakes don't queue it again.
+    queued: AtomicBool,
 }
 
 impl Inner {
-    fn new(task_id: usize) -> Self {
-        Inner { task_id }
+    fn new(task_id: usize, main_loop: MainLoop) -> Self {
+        Inner {
+            task_id,
+            main_loop,
+            //submitting queues it
+            queued: AtomicBool::new(true),
+        }
     }
 }
 
@@ -70,11 +150,11 @@ const WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
     },
     |data| {
         let w = unsafe { Arc::from_raw(data as *const Waker) };
-        wake_task(w.inner.task_id);
+        wake_task(&w.inner);
     },
     |data| {
         let w = unsafe { Arc::from_raw(data as *const Waker) };
-        wake_task(w.inner.task_id);
+        wake_task(&w.inner);
         std::mem::forget(w);
     },
     |data| {
@@ -93,30 +173,46 @@ impl Waker {
 /// Each task contains a pinned future, unique ID, and shared state for wake notifications.
 struct Task {
     context: logwise::context::Context,
+    debug_label: String,
     our_task_id: usize,
     future: Pin<Box<dyn Future<Output = ()> + 'static>>,
     wake_inner: Arc<Inner>,
 }
 
-/// Wakes a task by moving it from RUNNING to POLLABLE and scheduling executor iteration.
+/// Wakes a task by queueing it and scheduling executor iteration.
 ///
 /// This function handles the wake notification for a specific task ID.
-fn wake_task(task_id: usize) {
+fn wake_task(inner: &Inner) {
+    //a task woken several times before it's polled is polled once
+    if inner.queued.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let task_id = inner.task_id;
     // Schedule main executor iteration on the main thread
-    crate::application::submit_to_main_thread("wake_task".to_string(), move || {
-        // Add the task to the pollable queue
-        let mut pollable = POLLABLE.take();
-        pollable.push(task_id);
-        POLLABLE.replace(pollable);
+    inner.main_loop.submit("wake_task".to_string(), move || {
+        POLLABLE.with_borrow_mut(|pollable| pollable.push_back(task_id));
         main_executor_iter();
     });
 }
 
+/// Drops every task on this thread, without polling them again.
+#[cfg(any(test, feature = "headless"))]
+fn forget_tasks() {
+    POLLABLE.take();
+    TURN_SCHEDULED.set(false);
+    drop(RUNNING.take());
+}
+
 thread_local! {
-    // Thread-local storage for tasks that are running but not currently pollable.
+    // Thread-local storage for tasks that are running, keyed by task ID.
     static RUNNING: Cell<Option<HashMap<usize, Task>>> = const { Cell::new(None) };
-    // Thread-local storage for task IDs that are ready to be polled.
-    static POLLABLE: Cell<Vec<usize>> = const { Cell::new(Vec::new()) };
+    // The run queue: IDs of tasks that are ready to be polled, oldest first.  Each is in here
+    // at most once; see `Inner::queued`.
+    static POLLABLE: RefCell<VecDeque<usize>> = const { RefCell::new(VecDeque::new()) };
+    // Whether a task is being polled, so work submitted from inside it waits its turn.
+    static POLLING: Cell<bool> = const { Cell::new(false) };
+    // Whether a turn is already submitted to the event loop, so we don't submit another.
+    static TURN_SCHEDULED: Cell<bool> = const { Cell::new(false) };
 }
 
 /// Runs the specified future on the main thread and returns its result.
@@ -148,6 +244,12 @@ thread_local! {
 /// # }
 /// ```
 ///
+/// # Panics
+///
+/// If the future panics, the panic is caught on the main thread and resumed in the awaiting
+/// task, with the original payload.  The executor keeps running.  On wasm32, where panics
+/// abort, there's nothing to catch.
+///
 /// # Platform Behavior
 ///
 /// On all supported platforms, this ensures the future runs on the thread that owns
@@ -157,13 +259,30 @@ pub async fn on_main_thread_async<R: Send + 'static, F: Future<Output = R> + Sen
     future: F,
 ) -> R {
     let (sender, fut) = r#continue::continuation();
-    crate::application::submit_to_main_thread(debug_label.clone(), || {
+    MainLoop::current().submit(debug_label.clone(), || {
         already_on_main_thread_submit(debug_label, async move {
-            let r = future.await;
+            //the panic is the caller's, so send it back rather than losing the continuation
+            let r = CatchUnwind(Box::pin(future)).await;
             sender.send(r);
         })
     });
-    fut.await
+    match fut.await {
+        Ok(r) => r,
+        Err(payload) => std::panic::resume_unwind(payload),
+    }
+}
+
+/// Polls a future, turning its panic into an `Err` with the payload.
+struct CatchUnwind<F>(Pin<Box<F>>);
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = std::thread::Result<F::Output>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
+        match std::panic::catch_unwind(AssertUnwindSafe(|| self.0.as_mut().poll(cx))) {
+            Ok(poll) => poll.map(Ok),
+            Err(payload) => std::task::Poll::Ready(Err(payload)),
+        }
+    }
 }
 
 /// Submits a future to the main thread executor for execution.
@@ -175,6 +294,9 @@ pub async fn on_main_thread_async<R: Send + 'static, F: Future<Output = R> + Sen
 ///
 /// This function will panic if not called from the main thread.
 ///
+/// If the future panics, the panic is logged and the task is dropped; other tasks keep
+/// running.
+///
 /// # Examples
 ///
 /// ```
@@ -198,13 +320,14 @@ pub fn already_on_main_thread_submit<F: Future<Output = ()> + 'static>(
     debug_label: String,
     future: F,
 ) {
-    assert!(sys::is_main_thread());
+    let main_loop = MainLoop::current();
+    assert!(main_loop.is_main_thread());
 
     // Generate unique task ID
     let task_id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
 
     // Create task with unique ID
-    let wake_inner = Arc::new(Inner::new(task_id));
+    let wake_inner = Arc::new(Inner::new(task_id, main_loop));
     let parent_context = logwise::context::Context::current();
     //creating a task is a bit heavyweight, particularly on the main thread.
     // let new_context = logwise::context::Context::from_parent(parent_context);
@@ -218,84 +341,435 @@ pub fn already_on_main_thread_submit<F: Future<Output = ()> + 'static>(
     logwise::debuginternal_sync!(
         "Creating task {id} {label}",
         id = logwise::privacy::IPromiseItsNotPrivate(new_context.task_id()),
-        label = logwise::privacy::LogIt(debug_label)
+        label = logwise::privacy::LogIt(&debug_label)
     );
     let task = Task {
         our_task_id: task_id,
         context: new_context,
+        debug_label,
         future: Box::pin(future),
         wake_inner,
     };
 
-    // Add task to POLLABLE queue
-    let mut pollable = POLLABLE.take();
-    // logwise::info_sync!("Submitting task {id} to main executor", id = task_id);
-    pollable.push(task_id);
-    POLLABLE.replace(pollable);
-
     // Add task to RUNNING collection
     let mut running = RUNNING.take().unwrap_or_default();
     running.insert(task_id, task);
     RUNNING.replace(Some(running));
 
+    // Add task to POLLABLE queue
+    POLLABLE.with_borrow_mut(|pollable| pollable.push_back(task_id));
+
     // Execute the tasks
     main_executor_iter();
 }
 
-/// Polls all tasks that need attention.
+/// Runs one turn of the executor.
 ///
-/// This function loops while there are pollable tasks, handling new tasks
-/// that may be added during polling without losing them.
+/// Polls the tasks that were pollable when the turn started, oldest first, until they're done
+/// or [`TURN_BUDGET`] runs out.  Then it yields back to the event loop, scheduling another turn
+/// if more tasks are waiting.
 fn main_executor_iter() {
-    let begin_iter = crate::application::time::Instant::now();
-    // Pop off a pollable task
-    // let iter = perfwarn_begin!("main_executor_iter");
-    let mut swap_pollable = POLLABLE.take();
-    let poll = swap_pollable.pop();
-    POLLABLE.replace(swap_pollable);
-    match poll {
-        None => {
-            //No more pollable tasks, nothing to do.
+    //submitted from inside a poll; the outer iteration schedules us once it's done
+    if POLLING.get() {
+        return;
+    }
+    let begin_turn = crate::clock::now();
+    //tasks queued from here on wait for the next turn, so the event loop gets a look in
+    let mut turn_len = POLLABLE.with_borrow(|pollable| pollable.len());
+    while turn_len > 0 {
+        turn_len -= 1;
+        let Some(id) = POLLABLE.with_borrow_mut(|pollable| pollable.pop_front()) else {
+            break;
+        };
+        // Skip tasks that completed since they were woken
+        let mut running = RUNNING.take().unwrap_or_default();
+        let task = running.remove(&id);
+        RUNNING.replace(Some(running));
+        if let Some(task) = task {
+            poll_task(task);
+        }
+        if crate::clock::since(begin_turn) >= TURN_BUDGET {
+            break;
+        }
+    }
+    //there may be more pollable tasks.  However, we want to yield here
+    let more = POLLABLE.with_borrow(|pollable| !pollable.is_empty());
+    if more && !TURN_SCHEDULED.replace(true) {
+        MainLoop::current().submit("main_executor_iter".to_string(), || {
+            TURN_SCHEDULED.set(false);
+            main_executor_iter();
+        });
+    }
+}
+
+/// Polls `task` once, and keeps it if it's still running.
+fn poll_task(mut task: Task) {
+    let begin_poll = crate::clock::now();
+    //wakes from inside the poll queue it again
+    task.wake_inner.queued.store(false, Ordering::Release);
+
+    //with that out of the way, we can poll the task
+    let waker = Waker {
+        inner: task.wake_inner.clone(),
+    };
+    let into_waker = waker.into_waker();
+    let parent = logwise::context::Context::current();
+    task.context.clone().set_current();
+    let mut context = Context::from_waker(&into_waker);
+    POLLING.set(true);
+    //a panic mustn't leave POLLING set, or no task would be polled again
+    let poll_result =
+        std::panic::catch_unwind(AssertUnwindSafe(|| task.future.as_mut().poll(&mut context)));
+    POLLING.set(false);
+    parent.set_current();
+    let poll_duration = crate::clock::since(begin_poll);
+    if poll_duration > crate::application::slow_op_threshold() {
+        crate::application::report_slow_op(task.debug_label.clone(), poll_duration);
+    }
+    match poll_result {
+        Err(payload) => {
+            logwise::error_sync!(
+                "Main thread task {label} panicked: {message}",
+                label = logwise::privacy::LogIt(&task.debug_label),
+                message =
+                    logwise::privacy::LogIt(&crate::application::panic_message(payload.as_ref()))
+            );
         }
-        Some(task) => {
-            // Get the task from RUNNING
+        Ok(std::task::Poll::Ready(())) => {
+            // Task completed, don't put it back
+        }
+        Ok(std::task::Poll::Pending) => {
+            // Task is still running, put it back in RUNNING
             let mut running = RUNNING.take().unwrap_or_default();
-            let mut task = running.remove(&task).unwrap();
-            let task_id = task.context.task_id();
+            running.insert(task.our_task_id, task);
             RUNNING.replace(Some(running));
+        }
+    }
+}
 
-            //with that out of the way, we can poll the task
-            let waker = Waker {
-                inner: task.wake_inner.clone(),
-            };
-            let into_waker = waker.into_waker();
-            let parent = logwise::context::Context::current();
-            task.context.clone().set_current();
-            // logwise::info_sync!("Polling task {id}", id = task.id);
-            let mut context = Context::from_waker(&into_waker);
-            let poll_result = task.future.as_mut().poll(&mut context);
-            parent.set_current();
-            match poll_result {
-                std::task::Poll::Ready(()) => {
-                    // Task completed, don't put it back
-                }
-                std::task::Poll::Pending => {
-                    // Task is still running, put it back in RUNNING
-                    let mut running = RUNNING.take().unwrap_or_default();
-                    running.insert(task.our_task_id, task);
-                    RUNNING.replace(Some(running));
+/// Progress of a [`run_chunked`] workload, reported after each chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkProgress {
+    completed: usize,
+    remaining: Option<usize>,
+}
+
+impl ChunkProgress {
+    /// Items processed so far.
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    /// Items left, if the iterator knows exactly (see [`Iterator::size_hint`]).
+    pub fn remaining(&self) -> Option<usize> {
+        self.remaining
+    }
+
+    /// Fraction of the work done, from 0.0 to 1.0, if the total is known.
+    pub fn fraction(&self) -> Option<f64> {
+        self.remaining.map(|remaining| {
+            let total = self.completed + remaining;
+            if total == 0 {
+                1.0
+            } else {
+                self.completed as f64 / total as f64
+            }
+        })
+    }
+}
+
+/// Works through `work` on the main thread, a time-budgeted chunk at a time.
+///
+/// Each call to the iterator's `next` is one unit of work and runs on the main thread.  Units
+/// run back to back until `chunk_budget` has elapsed, then the main thread goes back to
+/// processing events before the next chunk starts.  The items produced are collected and
+/// returned in order.
+///
+/// This is the sanctioned way to do work that must be on the main thread but is too heavy for
+/// a single [`on_main_thread`](crate::application::on_main_thread) call: keep `chunk_budget`
+/// under [`slow_op_threshold`](crate::application::slow_op_threshold) and no chunk is reported
+/// as slow, unless a single unit takes longer than that on its own.
+///
+/// `progress` is called on the calling thread after every chunk.
+///
+/// This function can be called from any thread, but not from a future running on the main
+/// thread executor, which would wait on itself.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use app_window::executor::run_chunked;
+/// use std::time::Duration;
+///
+/// // Pretend each row needs a main-thread-only API
+/// let rows = (0..10_000).map(|row| row * 2);
+/// let results = run_chunked(
+///     "rebuild_rows".to_owned(),
+///     rows,
+///     Duration::from_millis(4),
+///     |progress| {
+///         if let Some(fraction) = progress.fraction() {
+///             println!("{:.0}% done", fraction * 100.0);
+///         }
+///     },
+/// )
+/// .await;
+/// assert_eq!(results.len(), 10_000);
+/// # }
+/// ```
+pub async fn run_chunked<I, P>(
+    debug_label: String,
+    work: I,
+    chunk_budget: std::time::Duration,
+    mut progress: P,
+) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::IntoIter: Send + 'static,
+    I::Item: Send + 'static,
+    P: FnMut(ChunkProgress),
+{
+    let mut iter = work.into_iter();
+    let mut results = Vec::new();
+    loop {
+        let (returned, chunk, done) =
+            crate::application::on_main_thread(debug_label.clone(), move || {
+                let start = crate::clock::now();
+                let mut chunk = Vec::new();
+                let mut done = false;
+                loop {
+                    match iter.next() {
+                        Some(item) => chunk.push(item),
+                        None => {
+                            done = true;
+                            break;
+                        }
+                    }
+                    if crate::clock::since(start) >= chunk_budget {
+                        break;
+                    }
                 }
+                (iter, chunk, done)
+            })
+            .await;
+        iter = returned;
+        results.extend(chunk);
+        let remaining = match iter.size_hint() {
+            _ if done => Some(0),
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        };
+        progress(ChunkProgress {
+            completed: results.len(),
+            remaining,
+        });
+        if done {
+            return results;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::virtual_loop::VirtualMainLoop;
+    use super::{already_on_main_thread_submit, on_main_thread_async};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+
+    /// A future that waits until it's opened, keeping its waker where tests can reach it.
+    #[derive(Clone, Default)]
+    struct Gate(Arc<Mutex<(bool, Option<Waker>)>>);
+
+    impl Gate {
+        fn open(&self) {
+            self.0.lock().unwrap().0 = true;
+        }
+        fn waker(&self) -> Waker {
+            self.0.lock().unwrap().1.clone().expect("polled")
+        }
+    }
+
+    impl Future for Gate {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let mut state = self.0.lock().unwrap();
+            state.1 = Some(cx.waker().clone());
+            if state.0 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    type Log = Rc<std::cell::RefCell<Vec<String>>>;
+
+    fn submit_nested(log: Log, depth: usize) {
+        let label = format!("level {depth}");
+        already_on_main_thread_submit(label.clone(), async move {
+            log.borrow_mut().push(format!("{label} start"));
+            if depth < 5 {
+                submit_nested(log.clone(), depth + 1);
             }
-            //there MAY be more pollable tasks.  However, we want to yield here
-            submit_to_main_thread("main_executor_iter".to_string(), main_executor_iter);
-            if begin_iter.elapsed() > crate::application::time::Duration::from_millis(10) {
-                logwise::warn_sync!(
-                    "main_executor_iter {task} took too long: {duration}",
-                    task = logwise::privacy::IPromiseItsNotPrivate(task_id),
-                    duration = logwise::privacy::IPromiseItsNotPrivate(begin_iter.elapsed())
-                );
+            log.borrow_mut().push(format!("{label} end"));
+        });
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn nested_submissions_wait_their_turn() {
+        let main_loop = VirtualMainLoop::install();
+        let log = Log::default();
+        submit_nested(log.clone(), 0);
+        //the outer task runs right away, but doesn't run the inner one from inside its poll
+        assert_eq!(*log.borrow(), ["level 0 start", "level 0 end"]);
+        assert_eq!(main_loop.queue_depth(), 1);
+        assert!(main_loop.pump_once());
+        assert_eq!(log.borrow().len(), 4);
+        assert_eq!(main_loop.run_until_idle(), 4);
+        assert_eq!(log.borrow().len(), 12);
+        assert_eq!(log.borrow().last().unwrap(), "level 5 end");
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn tasks_are_polled_in_order() {
+        let main_loop = VirtualMainLoop::install();
+        let log = Log::default();
+        let outer = log.clone();
+        already_on_main_thread_submit("outer".to_string(), async move {
+            for name in ["a", "b", "c"] {
+                let log = outer.clone();
+                already_on_main_thread_submit(name.to_string(), async move {
+                    log.borrow_mut().push(name.to_string());
+                });
             }
+        });
+        //queued during the outer poll, so they share the next turn, oldest first
+        assert_eq!(main_loop.queue_depth(), 1);
+        main_loop.pump_once();
+        assert_eq!(main_loop.queue_depth(), 0);
+        assert_eq!(*log.borrow(), ["a", "b", "c"]);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn self_waking_tasks_yield_each_turn() {
+        /// Wakes itself and stays pending, forever.
+        struct Spin(Rc<std::cell::Cell<usize>>);
+        impl Future for Spin {
+            type Output = ();
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+                self.0.set(self.0.get() + 1);
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+        let main_loop = VirtualMainLoop::install();
+        let polls = Rc::new(std::cell::Cell::new(0));
+        already_on_main_thread_submit("spin".to_string(), Spin(polls.clone()));
+        //other work on the loop still gets a turn in between
+        for turn in 1..=3 {
+            assert_eq!(polls.get(), turn);
+            assert_eq!(main_loop.queue_depth(), 1);
+            main_loop.pump_once();
         }
     }
-    // drop(iter);
+
+    #[test]
+    fn wakes_from_other_threads_reach_the_loop() {
+        let main_loop = VirtualMainLoop::install();
+        let gate = Gate::default();
+        let done = Rc::new(std::cell::Cell::new(false));
+        let (waiting, finished) = (gate.clone(), done.clone());
+        already_on_main_thread_submit("waiting".to_string(), async move {
+            waiting.await;
+            finished.set(true);
+        });
+        assert_eq!(main_loop.queue_depth(), 0);
+        let waker = gate.waker();
+        let opener = gate.clone();
+        std::thread::spawn(move || {
+            opener.open();
+            waker.wake();
+        })
+        .join()
+        .unwrap();
+        assert!(!done.get());
+        assert_eq!(main_loop.queue_depth(), 1);
+        main_loop.pump_once();
+        assert!(done.get());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn wakes_after_completion_are_ignored() {
+        let main_loop = VirtualMainLoop::install();
+        let gate = Gate::default();
+        let polls = Rc::new(std::cell::Cell::new(0));
+        let (waiting, counted) = (gate.clone(), polls.clone());
+        already_on_main_thread_submit("waiting".to_string(), async move {
+            counted.set(counted.get() + 1);
+            waiting.await;
+            counted.set(counted.get() + 1);
+        });
+        let waker = gate.waker();
+        gate.open();
+        //woken twice before the loop gets to it, which queues it once, then again once it's done
+        waker.wake_by_ref();
+        waker.wake_by_ref();
+        assert_eq!(main_loop.run_until_idle(), 1);
+        assert_eq!(polls.get(), 2);
+        waker.wake();
+        assert_eq!(main_loop.run_until_idle(), 1);
+        assert_eq!(polls.get(), 2);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn dropping_the_loop_cancels_pending_tasks() {
+        struct Flag(Rc<std::cell::Cell<bool>>);
+        impl Drop for Flag {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+        let main_loop = VirtualMainLoop::install();
+        let dropped = Rc::new(std::cell::Cell::new(false));
+        let flag = Flag(dropped.clone());
+        let gate = Gate::default();
+        already_on_main_thread_submit("never opened".to_string(), async move {
+            let _flag = flag;
+            gate.await;
+        });
+        assert!(!dropped.get());
+        drop(main_loop);
+        assert!(dropped.get());
+    }
+
+    //panics abort on wasm32
+    #[test]
+    fn panicking_tasks_leave_the_executor_running() {
+        let main_loop = VirtualMainLoop::install();
+        let mut cx = Context::from_waker(Waker::noop());
+        already_on_main_thread_submit("panics".to_string(), async { panic!("on purpose") });
+        //the caller of on_main_thread_async gets its future's panic back
+        let mut panicking = Box::pin(on_main_thread_async("panics too".to_string(), async {
+            panic!("on purpose")
+        }));
+        assert!(panicking.as_mut().poll(&mut cx).is_pending());
+        main_loop.run_until_idle();
+        let resumed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            panicking.as_mut().poll(&mut cx)
+        }));
+        assert!(resumed.is_err());
+        let mut later = Box::pin(on_main_thread_async("later".to_string(), async { 2 + 2 }));
+        assert!(later.as_mut().poll(&mut cx).is_pending());
+        main_loop.run_until_idle();
+        assert_eq!(later.as_mut().poll(&mut cx), Poll::Ready(4));
+    }
 }
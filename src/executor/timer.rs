@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Timers driven by the main thread's event loop.
+//!
+//! Each pending [`Sleep`] is one native timer on the main thread (a run loop timer on Apple
+//! platforms, `SetTimer` on Windows, an io_uring timeout on Linux, `setTimeout` on the web),
+//! so waiting doesn't need a thread of its own.  When the timer fires it wakes whichever task
+//! is awaiting, on whatever executor that is.
+
+use crate::application::time::Instant;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// The shortest period between ticks, so a zero period doesn't spin.
+const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+#[derive(Debug, Default)]
+struct TimerState {
+    fired: bool,
+    waker: Option<Waker>,
+}
+
+/// A future that completes once its deadline has passed.  See [`sleep`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct Sleep {
+    deadline: Instant,
+    /// Set once the native timer is scheduled, on the first poll.
+    timer: Option<Arc<Mutex<TimerState>>>,
+}
+
+impl Sleep {
+    fn until(deadline: Instant) -> Self {
+        Sleep {
+            deadline,
+            timer: None,
+        }
+    }
+
+    /// When the sleep completes.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let now = Instant::now();
+        if now >= self.deadline {
+            return Poll::Ready(());
+        }
+        let timer = match &self.timer {
+            Some(timer) => timer.clone(),
+            None => {
+                let timer = Arc::new(Mutex::new(TimerState::default()));
+                let fire = timer.clone();
+                crate::sys::on_main_thread_after(self.deadline - now, move || {
+                    let waker = {
+                        let mut state = fire.lock().unwrap();
+                        state.fired = true;
+                        state.waker.take()
+                    };
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                });
+                self.timer = Some(timer.clone());
+                timer
+            }
+        };
+        let mut state = timer.lock().unwrap();
+        //native timers may round the delay down a little, which we don't mind
+        if state.fired {
+            return Poll::Ready(());
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Waits for `duration`, without blocking the thread.
+///
+/// The wait is driven by a timer on the main thread's event loop, so a single-threaded app can
+/// animate or debounce from futures on the
+/// [main thread executor](crate::executor::already_on_main_thread_submit).  The future can be
+/// awaited on any executor, but the main thread must be running (see
+/// [`application::main`](crate::application::main)).
+///
+/// Timers measure real time; they don't follow a [`TestClock`](crate::clock::TestClock).
+///
+/// # Platform behavior
+///
+/// * Android waits on a thread per pending sleep, then hops to the main thread.  Backends
+///   registered at runtime do the same, unless they schedule timers themselves.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use app_window::executor::sleep;
+/// use std::time::Duration;
+///
+/// sleep(Duration::from_millis(250)).await;
+/// # }
+/// ```
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep::until(Instant::now() + duration)
+}
+
+/// Ticks every `period`.  See [`interval`].
+#[derive(Debug)]
+pub struct Interval {
+    period: Duration,
+    next: Instant,
+}
+
+impl Interval {
+    /// Waits for the next tick, and returns when it was due.
+    ///
+    /// If ticks were missed, say because nothing awaited them, the next tick is due right
+    /// away and the ones after carry on from now, instead of firing a burst to catch up.
+    pub async fn tick(&mut self) -> Instant {
+        let due = self.next;
+        Sleep::until(due).await;
+        self.next = next_tick(due, self.period, Instant::now());
+        due
+    }
+
+    /// The time between ticks.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+/// When the tick after the one due at `due` should be, given that one completed at `now`.
+fn next_tick(due: Instant, period: Duration, now: Instant) -> Instant {
+    let next = due + period;
+    //after a stall, carry on from now instead of catching up
+    if next <= now { now + period } else { next }
+}
+
+/// Ticks every `period`, starting one `period` from now.
+///
+/// Like [`sleep`], this is driven by the main thread's event loop.  Periods under a
+/// millisecond are treated as one millisecond.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use app_window::executor::interval;
+/// use std::time::Duration;
+///
+/// let mut frames = interval(Duration::from_millis(16));
+/// for _ in 0..3 {
+///     frames.tick().await;
+///     // advance the animation
+/// }
+/// # }
+/// ```
+pub fn interval(period: Duration) -> Interval {
+    let period = period.max(MIN_PERIOD);
+    Interval {
+        period,
+        next: Instant::now() + period,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::next_tick;
+    use crate::application::time::Instant;
+    use std::time::Duration;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn interval_skips_missed_ticks() {
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        let period = Duration::from_millis(10);
+        assert_eq!(next_tick(ms(10), period, ms(12)), ms(20));
+        //woken late, but before the next tick was due
+        assert_eq!(next_tick(ms(20), period, ms(29)), ms(30));
+        //missed two ticks
+        assert_eq!(next_tick(ms(30), period, ms(55)), ms(65));
+    }
+}
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: MPL-2.0
+//! A main loop stepped through by hand, for tests and headless use.
+//!
+//! Installing a [`VirtualMainLoop`] makes the current thread the executor's main thread, and
+//! everything the executor would hand the native event loop is queued here instead.  Callers run
+//! the queue one closure at a time with [`VirtualMainLoop::pump_once`], so tests can check what
+//! is pending between steps instead of sleeping and hoping.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Closures waiting for the virtual main thread.  Any thread can push.
+#[derive(Default)]
+pub(super) struct Queue(Mutex<VecDeque<Job>>);
+
+impl Queue {
+    pub(super) fn push(&self, job: Job) {
+        self.0.lock().unwrap().push_back(job);
+    }
+}
+
+thread_local! {
+    static INSTALLED: RefCell<Option<Arc<Queue>>> = const { RefCell::new(None) };
+}
+
+/// The queue installed on this thread, if it's a virtual main thread.
+pub(super) fn current() -> Option<Arc<Queue>> {
+    INSTALLED.with_borrow(|installed| installed.clone())
+}
+
+/// Stands in for the native event loop on the thread that installs it, until dropped.
+///
+/// While installed, the executor runs on this thread without a platform event loop, or
+/// [`application::main`](crate::application::main):
+/// [`already_on_main_thread_submit`](super::already_on_main_thread_submit) and
+/// [`on_main_thread_async`](super::on_main_thread_async) called from this thread, and
+/// [`submit_to_main_thread_with_priority`](crate::application::submit_to_main_thread_with_priority)
+/// (and so [`MainThreadExecutor`](crate::some_executor::MainThreadExecutor)), queue their work
+/// here, in the order the native loop would run it.  Nothing runs until it's pumped.  Tasks
+/// woken from other threads come back here too.
+///
+/// Work submitted from other threads, and the rest of the crate's main thread functions, still
+/// need the native event loop.  Dropping the loop uninstalls it, and drops any tasks still
+/// waiting on it.
+///
+/// Requires the `headless` cargo feature.
+///
+/// # Example
+///
+/// ```
+/// use app_window::executor::{VirtualMainLoop, already_on_main_thread_submit};
+///
+/// let main_loop = VirtualMainLoop::install();
+/// already_on_main_thread_submit("outer".to_string(), async {
+///     //waits for the outer task's poll to finish
+///     already_on_main_thread_submit("inner".to_string(), async {});
+/// });
+/// assert_eq!(main_loop.queue_depth(), 1);
+/// assert_eq!(main_loop.run_until_idle(), 1);
+/// ```
+#[must_use = "the loop is uninstalled when dropped"]
+pub struct VirtualMainLoop {
+    queue: Arc<Queue>,
+}
+
+impl std::fmt::Debug for VirtualMainLoop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtualMainLoop")
+            .field("queue_depth", &self.queue_depth())
+            .finish()
+    }
+}
+
+impl VirtualMainLoop {
+    /// Installs a loop on the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one is already installed on this thread.
+    pub fn install() -> Self {
+        let queue = Arc::new(Queue::default());
+        INSTALLED.with_borrow_mut(|installed| {
+            assert!(
+                installed.is_none(),
+                "a virtual main loop is already installed"
+            );
+            *installed = Some(queue.clone());
+        });
+        VirtualMainLoop { queue }
+    }
+
+    /// Runs the oldest pending closure.  Returns `false` if there was none.
+    pub fn pump_once(&self) -> bool {
+        //popped before running, so the closure can queue more
+        let job = self.queue.0.lock().unwrap().pop_front();
+        match job {
+            Some(job) => {
+                job();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pumps until nothing is pending, and returns how many closures ran.
+    pub fn run_until_idle(&self) -> usize {
+        let mut ran = 0;
+        while self.pump_once() {
+            ran += 1;
+        }
+        ran
+    }
+
+    /// How many closures are waiting.
+    pub fn queue_depth(&self) -> usize {
+        self.queue.0.lock().unwrap().len()
+    }
+}
+
+impl Drop for VirtualMainLoop {
+    fn drop(&mut self) {
+        INSTALLED.with_borrow_mut(|installed| *installed = None);
+        //tasks left behind would otherwise outlive the loop they were waiting on
+        super::forget_tasks();
+    }
+}
@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Input batched once per frame.
+//!
+//! [`Mouse`](crate::input::mouse::Mouse) and [`Keyboard`](crate::input::keyboard::Keyboard)
+//! report state: what's down and where the cursor is right now.  Render-loop apps usually want
+//! something between that and a stream of every event: one delivery per frame, with the cursor
+//! collapsed to where it ended up, but every press and release kept, in order, so a tap shorter
+//! than a frame isn't lost.  [`FrameInput`](crate::input::frame::FrameInput) provides that.
+//! Nothing wakes the app between frames; events wait until its `take`.
+
+use crate::input::Window;
+use crate::input::keyboard::Keyboard;
+use crate::input::keyboard::key::KeyboardKey;
+use crate::input::mouse::{ButtonEvent, Mouse, MouseWindowLocation};
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Most edges a [`FrameInput`] holds before dropping the oldest.  A frame that long is
+/// already a stall.
+const FRAME_EDGE_CAPACITY: usize = 1024;
+
+/// A press or release kept in a [`FrameBatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FrameEdge {
+    /// A keyboard key was pressed (`down`) or released.
+    Key {
+        /// The key.
+        key: KeyboardKey,
+        /// Whether it was pressed, rather than released.
+        down: bool,
+    },
+    /// A mouse button was pressed or released.
+    Button(ButtonEvent),
+}
+
+/// The input for one frame; see [`FrameInput::take`].
+#[derive(Debug, Clone, Default)]
+pub struct FrameBatch {
+    position: Option<MouseWindowLocation>,
+    scroll_delta: (f64, f64),
+    edges: Vec<FrameEdge>,
+}
+
+impl FrameBatch {
+    /// Where the cursor was after the last motion this frame, or `None` if it didn't move.
+    pub fn position(&self) -> Option<MouseWindowLocation> {
+        self.position
+    }
+
+    /// Scroll accumulated this frame, like [`Mouse::load_clear_scroll_delta`].
+    pub fn scroll_delta(&self) -> (f64, f64) {
+        self.scroll_delta
+    }
+
+    /// Every key and button press and release this frame, oldest first.
+    pub fn edges(&self) -> &[FrameEdge] {
+        &self.edges
+    }
+
+    /// Whether nothing happened this frame.
+    pub fn is_empty(&self) -> bool {
+        self.position.is_none() && self.scroll_delta == (0.0, 0.0) && self.edges.is_empty()
+    }
+}
+
+/// What a [`FrameInput`] has collected since the last `take`.
+#[derive(Debug)]
+pub(crate) struct PendingFrame {
+    /// Only events for this window (as reported in [`Window`]) are kept, if set.
+    window: Option<usize>,
+    batch: FrameBatch,
+}
+
+impl PendingFrame {
+    /// Events the platform couldn't attribute to a window are always kept.
+    fn wants(&self, window: *mut c_void) -> bool {
+        match self.window {
+            Some(only) => window.is_null() || window as usize == only,
+            None => true,
+        }
+    }
+
+    pub(crate) fn position(&mut self, location: MouseWindowLocation, window: *mut c_void) {
+        if self.wants(window) {
+            self.batch.position = Some(location);
+        }
+    }
+
+    pub(crate) fn scroll(&mut self, delta_x: f64, delta_y: f64, window: *mut c_void) {
+        if self.wants(window) {
+            self.batch.scroll_delta.0 += delta_x;
+            self.batch.scroll_delta.1 += delta_y;
+        }
+    }
+
+    pub(crate) fn edge(&mut self, edge: FrameEdge, window: *mut c_void) {
+        if self.wants(window) {
+            if self.batch.edges.len() == FRAME_EDGE_CAPACITY {
+                self.batch.edges.remove(0);
+            }
+            self.batch.edges.push(edge);
+        }
+    }
+}
+
+/// The [`FrameInput`]s listening to a mouse or keyboard.
+///
+/// Like button events, this runs once per platform event, so it costs one relaxed load until
+/// someone listens.
+#[derive(Debug, Default)]
+pub(crate) struct FrameListeners {
+    active: AtomicBool,
+    listeners: Mutex<Vec<Weak<Mutex<PendingFrame>>>>,
+}
+
+impl FrameListeners {
+    #[inline]
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    #[cold]
+    pub(crate) fn send(&self, f: impl Fn(&mut PendingFrame)) {
+        let mut listeners = self.listeners.lock().unwrap();
+        listeners.retain(|listener| {
+            let Some(pending) = listener.upgrade() else {
+                return false;
+            };
+            f(&mut pending.lock().unwrap());
+            true
+        });
+        if listeners.is_empty() {
+            self.active.store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn add(&self, pending: &Arc<Mutex<PendingFrame>>) {
+        self.listeners.lock().unwrap().push(Arc::downgrade(pending));
+        self.active.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Collects input from a [`Mouse`] and a [`Keyboard`] and hands it over once per frame.
+///
+/// Cursor motion is coalesced to the last position and scrolling is summed, while key and
+/// button edges are all kept, in order.  Call [`FrameInput::take`] at the start of each frame.
+/// Input only accumulates while the `FrameInput` is alive.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::input::frame::{FrameEdge, FrameInput};
+/// use app_window::input::keyboard::Keyboard;
+/// use app_window::input::keyboard::key::KeyboardKey;
+/// use app_window::input::mouse::Mouse;
+///
+/// let mouse = Mouse::coalesced().await;
+/// let keyboard = Keyboard::coalesced().await;
+/// let input = FrameInput::new(&mouse, &keyboard, None);
+/// loop {
+///     let batch = input.take();
+///     for edge in batch.edges() {
+///         if let FrameEdge::Key { key: KeyboardKey::Space, down: true } = edge {
+///             println!("jump");
+///         }
+///     }
+///     //...render the frame...
+/// #   break;
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FrameInput {
+    pending: Arc<Mutex<PendingFrame>>,
+}
+
+impl FrameInput {
+    /// Starts collecting input from `mouse` and `keyboard`.
+    ///
+    /// With a `window`, only input the platform delivered to that window is kept, compared by
+    /// the handle described in [`Window`].  Input the platform doesn't attribute to any window
+    /// is kept either way.
+    pub fn new(mouse: &Mouse, keyboard: &Keyboard, window: Option<Window>) -> Self {
+        let pending = Arc::new(Mutex::new(PendingFrame {
+            window: window.map(|w| w.0.as_ptr() as usize),
+            batch: FrameBatch::default(),
+        }));
+        mouse.frame_listeners().add(&pending);
+        keyboard.frame_listeners().add(&pending);
+        FrameInput { pending }
+    }
+
+    /// Returns everything since the last call, and starts a new frame.
+    pub fn take(&self) -> FrameBatch {
+        std::mem::take(&mut self.pending.lock().unwrap().batch)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FrameEdge, FrameListeners, PendingFrame};
+    use crate::input::keyboard::key::KeyboardKey;
+    use std::sync::{Arc, Mutex};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn frames_sum_scroll_and_keep_edges() {
+        let listeners = FrameListeners::default();
+        assert!(!listeners.is_active());
+        let pending = Arc::new(Mutex::new(PendingFrame {
+            window: Some(8),
+            batch: Default::default(),
+        }));
+        listeners.add(&pending);
+        let other_window = 16 as *mut _;
+        let our_window = 8 as *mut _;
+        listeners.send(|p| {
+            p.scroll(0.0, 1.0, our_window);
+            p.scroll(0.0, 5.0, other_window);
+            //a tap within one frame
+            p.edge(
+                FrameEdge::Key {
+                    key: KeyboardKey::Space,
+                    down: true,
+                },
+                our_window,
+            );
+            p.edge(
+                FrameEdge::Key {
+                    key: KeyboardKey::Space,
+                    down: false,
+                },
+                std::ptr::null_mut(),
+            );
+            p.scroll(0.0, 2.0, our_window);
+        });
+        let batch = std::mem::take(&mut pending.lock().unwrap().batch);
+        assert_eq!(batch.scroll_delta(), (0.0, 3.0));
+        assert_eq!(batch.edges().len(), 2);
+        assert!(pending.lock().unwrap().batch.is_empty());
+
+        drop(pending);
+        listeners.send(|_| {});
+        assert!(!listeners.is_active());
+    }
+}
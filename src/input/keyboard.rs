@@ -68,10 +68,13 @@
 //!     (KeyboardKey::D, (1.0, 0.0)),  // Right
 //! ];
 //!
-//! // You can iterate over keys
+//! // Sample every key once per frame, then check the ones you care about
+//! // In real code: let pressed = keyboard.pressed_set();
+//! let pressed = app_window::input::keyboard::key::KeySet::of(&[KeyboardKey::W]);
 //! for (key, _direction) in &move_keys {
-//!     // In real code: if keyboard.is_pressed(*key) { ... }
-//!     println!("Checking key: {:?}", key);
+//!     if pressed.contains(*key) {
+//!         println!("Moving: {:?}", key);
+//!     }
 //! }
 //! # }
 //! ```
@@ -88,15 +91,17 @@
 //! When using the `app_window` crate's window management, this integration is handled
 //! automatically.
 
+use std::collections::VecDeque;
 use std::ffi::c_void;
 use std::hash::Hash;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicPtr};
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Poll, Waker};
 
 /// Keyboard key definitions and enumerations.
 pub mod key;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub(crate) mod macos;
 
 #[cfg(target_arch = "wasm32")]
@@ -108,7 +113,15 @@ pub(crate) mod windows;
 #[cfg(target_os = "linux")]
 pub(crate) mod linux;
 
-#[cfg(target_os = "macos")]
+#[cfg(target_os = "android")]
+pub(crate) mod android;
+
+#[cfg(plugin_backend)]
+pub(crate) mod plugin;
+
+mod repeat;
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub(crate) use macos as sys;
 
 #[cfg(target_arch = "wasm32")]
@@ -120,9 +133,124 @@ pub(crate) use windows as sys;
 #[cfg(target_os = "linux")]
 pub(crate) use linux as sys;
 
+#[cfg(target_os = "android")]
+pub(crate) use android as sys;
+
+#[cfg(plugin_backend)]
+pub(crate) use plugin as sys;
+
 use crate::application::is_main_thread_running;
-use crate::input::keyboard::key::KeyboardKey;
+use crate::application::time::Instant;
+use crate::input::frame::{FrameEdge, FrameListeners};
+use crate::input::keyboard::key::{KeySet, KeyboardKey};
+use crate::input::keyboard::repeat::Repeater;
 use crate::input::keyboard::sys::PlatformCoalescedKeyboard;
+use crate::window::WindowId;
+use std::time::Duration;
+
+/// Most key events a [`KeyEvents`] holds before dropping the oldest.
+const KEY_EVENT_CAPACITY: usize = 256;
+
+/// What happened to a key in a [`KeyEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyState {
+    /// The key went down.
+    Pressed,
+    /// The key went up.
+    Released,
+    /// The key is being held, and the press repeated.  See [`Keyboard::set_repeat`].
+    Repeated,
+}
+
+/// How a held key repeats; see [`Keyboard::set_repeat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum KeyRepeat {
+    /// As the user set up the platform.
+    #[default]
+    Platform,
+    /// Repeat after `delay`, then every `interval`, whatever the platform's settings.
+    Custom {
+        /// How long a key is held before it first repeats.
+        delay: Duration,
+        /// How long between repeats after that.
+        interval: Duration,
+    },
+    /// Don't repeat.  Games that only care whether a key is down usually want this.
+    Disabled,
+}
+
+/// A key changing state; see [`Keyboard::events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    key: KeyboardKey,
+    state: KeyState,
+    timestamp: Instant,
+    window: Option<WindowId>,
+}
+
+impl KeyEvent {
+    /// The key.
+    pub fn key(&self) -> KeyboardKey {
+        self.key
+    }
+
+    /// Whether the key was pressed, released or repeated.
+    pub fn state(&self) -> KeyState {
+        self.state
+    }
+
+    /// When app_window received the event, according to [`crate::clock::now`].
+    pub fn timestamp(&self) -> Instant {
+        self.timestamp
+    }
+
+    /// The window that had keyboard focus, if the platform said and the app created it.  See
+    /// [`Window::id`](crate::window::Window::id).
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window
+    }
+}
+
+#[derive(Debug, Default)]
+struct KeyEventQueue {
+    events: VecDeque<KeyEvent>,
+    waker: Option<Waker>,
+}
+
+/// Every key press, release and repeat on a [`Keyboard`], in order.
+///
+/// [`Keyboard::is_pressed`] only says whether a key is down right now, so a tap shorter than
+/// a poll can be missed entirely.  This keeps each transition.  If the events aren't read,
+/// the oldest are dropped once 256 are waiting.
+///
+/// Created by [`Keyboard::events`].
+#[derive(Debug)]
+pub struct KeyEvents {
+    queue: Arc<Mutex<KeyEventQueue>>,
+}
+
+impl KeyEvents {
+    /// Waits for the next key event.
+    pub async fn next(&mut self) -> KeyEvent {
+        std::future::poll_fn(|cx| {
+            let mut queue = self.queue.lock().unwrap();
+            match queue.events.pop_front() {
+                Some(event) => Poll::Ready(event),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Returns the next key event, if one is waiting.
+    pub fn try_next(&mut self) -> Option<KeyEvent> {
+        self.queue.lock().unwrap().events.pop_front()
+    }
+}
 
 /// Internal shared state for keyboard tracking.
 ///
@@ -136,6 +264,15 @@ struct Shared {
     key_states: Vec<AtomicBool>,
     /// Platform-specific window pointer that received the most recent keyboard event.
     window_ptr: AtomicPtr<c_void>,
+    /// [`crate::input::frame::FrameInput`]s collecting key edges.
+    frame_listeners: FrameListeners,
+    /// Whether `key_listeners` may be non-empty, so key events are free until someone listens.
+    has_key_listeners: AtomicBool,
+    key_listeners: Mutex<Vec<Weak<Mutex<KeyEventQueue>>>>,
+    repeat: Mutex<KeyRepeat>,
+    /// Whether `repeat` is [`KeyRepeat::Platform`], so the platform's repeats are passed on.
+    platform_repeats: AtomicBool,
+    repeater: Repeater,
 }
 
 impl Shared {
@@ -150,6 +287,12 @@ impl Shared {
         Shared {
             key_states: vec,
             window_ptr: AtomicPtr::new(std::ptr::null_mut()),
+            frame_listeners: FrameListeners::default(),
+            has_key_listeners: AtomicBool::new(false),
+            key_listeners: Mutex::new(Vec::new()),
+            repeat: Mutex::new(KeyRepeat::Platform),
+            platform_repeats: AtomicBool::new(true),
+            repeater: Repeater::default(),
         }
     }
 
@@ -166,15 +309,105 @@ impl Shared {
     /// This method uses relaxed atomic ordering for performance. The exact ordering
     /// of concurrent key state changes is not guaranteed, but each individual key's
     /// state will be eventually consistent.
+    ///
+    /// This runs once per platform event, so it doesn't lock, allocate or log unless a
+    /// `FrameInput` or [`KeyEvents`] is listening, or app_window is repeating keys itself.
     fn set_key_state(&self, key: KeyboardKey, state: bool, window_ptr: *mut c_void) {
-        logwise::debuginternal_sync!(
-            "Setting key {key} to {state}",
-            key = logwise::privacy::LogIt(key),
-            state = state
-        );
-        self.window_ptr
-            .store(window_ptr, std::sync::atomic::Ordering::Relaxed);
-        self.key_states[key as usize].store(state, std::sync::atomic::Ordering::Relaxed);
+        self.window_ptr.store(window_ptr, Ordering::Relaxed);
+        let was_down = self.key_states[key as usize].swap(state, Ordering::Relaxed);
+        let state = match (was_down, state) {
+            (true, true) if !self.platform_repeats.load(Ordering::Relaxed) => return,
+            (true, true) => KeyState::Repeated,
+            (_, true) => KeyState::Pressed,
+            (_, false) => KeyState::Released,
+        };
+        if self.repeater.is_running() {
+            match state {
+                KeyState::Pressed => {
+                    self.repeater
+                        .press(key, window_ptr, self.synthesized_timing(), Instant::now())
+                }
+                KeyState::Released => self.repeater.release(key),
+                KeyState::Repeated => {}
+            }
+        }
+        self.deliver(key, state, window_ptr);
+    }
+
+    /// Sends a key event to whoever is listening, without changing the key's state.
+    fn deliver(&self, key: KeyboardKey, state: KeyState, window_ptr: *mut c_void) {
+        if self.has_key_listeners.load(Ordering::Relaxed) {
+            self.send_key_event(KeyEvent {
+                key,
+                state,
+                timestamp: crate::clock::now(),
+                window: super::window_id(window_ptr),
+            });
+        }
+        if self.frame_listeners.is_active() {
+            let down = state != KeyState::Released;
+            self.frame_listeners
+                .send(|pending| pending.edge(FrameEdge::Key { key, down }, window_ptr));
+        }
+    }
+
+    fn set_repeat(&self, repeat: KeyRepeat) {
+        *self.repeat.lock().unwrap() = repeat;
+        self.platform_repeats
+            .store(repeat == KeyRepeat::Platform, Ordering::Relaxed);
+        //a key already repeating keeps its old timing; the next press picks up the new one
+        if repeat == KeyRepeat::Disabled {
+            self.repeater.stop();
+        }
+    }
+
+    /// The delay and interval for app_window to repeat keys with, if it repeats them itself.
+    fn synthesized_timing(&self) -> Option<(Duration, Duration)> {
+        match *self.repeat.lock().unwrap() {
+            #[cfg(target_os = "linux")]
+            KeyRepeat::Platform => linux::repeat_info(),
+            #[cfg(not(target_os = "linux"))]
+            KeyRepeat::Platform => None,
+            KeyRepeat::Custom { delay, interval } => Some((delay, interval)),
+            KeyRepeat::Disabled => None,
+        }
+    }
+
+    #[cold]
+    fn send_key_event(&self, event: KeyEvent) {
+        let mut listeners = self.key_listeners.lock().unwrap();
+        listeners.retain(|listener| {
+            let Some(queue) = listener.upgrade() else {
+                return false;
+            };
+            let mut queue = queue.lock().unwrap();
+            if queue.events.len() == KEY_EVENT_CAPACITY {
+                queue.events.pop_front();
+            }
+            queue.events.push_back(event);
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+            true
+        });
+        if listeners.is_empty() {
+            self.has_key_listeners.store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn events(&self) -> KeyEvents {
+        let queue = Arc::new(Mutex::new(KeyEventQueue::default()));
+        let mut listeners = self.key_listeners.lock().unwrap();
+        listeners.push(Arc::downgrade(&queue));
+        self.has_key_listeners.store(true, Ordering::Relaxed);
+        KeyEvents { queue }
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        //so the repeat thread notices it's done
+        self.repeater.wake();
     }
 }
 
@@ -305,6 +538,9 @@ impl Keyboard {
         );
         let shared = Arc::new(Shared::new());
         let _platform_coalesced_keyboard = PlatformCoalescedKeyboard::new(&shared).await;
+        //Wayland leaves repeat to clients
+        #[cfg(target_os = "linux")]
+        Repeater::start(&shared);
         Self {
             shared,
             _platform_coalesced_keyboard,
@@ -339,10 +575,152 @@ impl Keyboard {
     /// * **Linux**: You must call `wl_keyboard_event` from your Wayland dispatch queue
     ///
     pub fn is_pressed(&self, key: KeyboardKey) -> bool {
-        self.shared.key_states[key as usize].load(std::sync::atomic::Ordering::Relaxed)
+        self.shared.key_states[key as usize].load(Ordering::Relaxed)
+    }
+
+    /// Returns every key that is currently pressed.
+    ///
+    /// This is one pass over the key states, for sampling input once per frame instead of
+    /// calling [`is_pressed`](Self::is_pressed) for each key.  Each key is loaded separately,
+    /// so a key that changes during the pass may be seen either way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::keyboard::Keyboard;
+    /// use app_window::input::keyboard::key::{KeyboardKey, KeySet};
+    ///
+    /// const MOVE: KeySet = KeySet::of(&[KeyboardKey::W, KeyboardKey::A, KeyboardKey::S, KeyboardKey::D]);
+    ///
+    /// let keyboard = Keyboard::coalesced().await;
+    /// let pressed = keyboard.pressed_set();
+    /// if pressed.contains_any(MOVE) {
+    ///     //...move...
+    /// }
+    /// # }
+    /// ```
+    pub fn pressed_set(&self) -> KeySet {
+        let mut set = KeySet::new();
+        for (index, state) in self.shared.key_states.iter().enumerate() {
+            if state.load(Ordering::Relaxed) {
+                set.insert_index(index);
+            }
+        }
+        set
+    }
+
+    /// Returns every key press, release and repeat from now on, with when it happened.
+    ///
+    /// Each call returns an independent stream.  Events are only recorded while at least one
+    /// is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::keyboard::{Keyboard, KeyState};
+    /// use app_window::input::keyboard::key::KeyboardKey;
+    ///
+    /// let keyboard = Keyboard::coalesced().await;
+    /// let mut events = keyboard.events();
+    /// loop {
+    ///     let event = events.next().await;
+    ///     if event.key() == KeyboardKey::Space && event.state() == KeyState::Pressed {
+    ///         println!("jump at {:?}", event.timestamp());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn events(&self) -> KeyEvents {
+        self.shared.events()
+    }
+
+    /// Sets how held keys repeat for this keyboard, which is [`KeyRepeat::Platform`] until
+    /// changed.  Repeats show up as [`KeyState::Repeated`] in [`events`](Self::events).
+    ///
+    /// Other [`Keyboard`]s keep their own setting, so a game can disable repeat for its
+    /// controls while a text field elsewhere in the app still gets repeats.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: Wayland leaves repeat to clients, so app_window always repeats keys itself,
+    ///   using the compositor's settings for [`KeyRepeat::Platform`].
+    /// * **Everywhere else**: [`KeyRepeat::Platform`] passes on the platform's repeats.
+    ///   [`KeyRepeat::Custom`] drops them and repeats keys itself.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::keyboard::{KeyRepeat, Keyboard};
+    /// use std::time::Duration;
+    ///
+    /// let keyboard = Keyboard::coalesced().await;
+    /// keyboard.set_repeat(KeyRepeat::Custom {
+    ///     delay: Duration::from_millis(300),
+    ///     interval: Duration::from_millis(30),
+    /// });
+    /// # }
+    /// ```
+    pub fn set_repeat(&self, repeat: KeyRepeat) {
+        if matches!(repeat, KeyRepeat::Custom { .. }) {
+            Repeater::start(&self.shared);
+        }
+        self.shared.set_repeat(repeat);
+    }
+
+    /// How held keys repeat for this keyboard; see [`set_repeat`](Self::set_repeat).
+    pub fn repeat(&self) -> KeyRepeat {
+        *self.shared.repeat.lock().unwrap()
+    }
+
+    pub(crate) fn frame_listeners(&self) -> &FrameListeners {
+        &self.shared.frame_listeners
+    }
+
+    /// Records a synthetic key event, as if the platform had reported it.
+    ///
+    /// Only available with the `conformance` feature, so the conformance suite can check
+    /// state tracking on machines without a keyboard.
+    #[cfg(feature = "conformance")]
+    #[doc(hidden)]
+    pub fn inject_key(&self, key: KeyboardKey, down: bool) {
+        self.shared.set_key_state(key, down, std::ptr::null_mut());
     }
 }
 
+/// Whether a hardware keyboard is attached, as far as the platform can tell.
+///
+/// Touch-first apps can use this to decide whether to bring up the on-screen keyboard with
+/// [`Window::set_virtual_keyboard_visible`](crate::window::Window::set_virtual_keyboard_visible).
+/// `None` means the platform doesn't say.
+///
+/// # Platform behavior
+///
+/// * **Windows**: whether any keyboard device is installed.  A convertible's keyboard counts
+///   even while it's folded away in tablet mode.
+/// * **macOS, iOS**: from GameController's `GCKeyboard`.  A keyboard attached at launch may not
+///   be seen until shortly after.
+/// * **Linux**: whether the seat has a keyboard.  Unknown until a window is created.
+///   On-screen keyboards that send key events (rather than text) count.
+/// * **wasm32, Android**: always `None`.
+///
+/// # Example
+///
+/// ```
+/// # async fn example(window: &app_window::window::Window) {
+/// use app_window::input::keyboard::hardware_keyboard_present;
+///
+/// if hardware_keyboard_present().await == Some(false) {
+///     _ = window.set_virtual_keyboard_visible(true).await;
+/// }
+/// # }
+/// ```
+pub async fn hardware_keyboard_present() -> Option<bool> {
+    sys::hardware_keyboard_present().await
+}
+
 // Trait implementations for Keyboard
 
 impl PartialEq for Keyboard {
@@ -372,7 +750,32 @@ impl Hash for Keyboard {
 
 #[cfg(test)]
 mod test {
-    use crate::input::keyboard::Keyboard;
+    use crate::input::keyboard::key::KeyboardKey;
+    use crate::input::keyboard::{KeyState, Keyboard, Shared};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn key_events_keep_every_edge() {
+        let shared = Shared::new();
+        let mut events = shared.events();
+        for down in [true, true, false] {
+            shared.set_key_state(KeyboardKey::Space, down, std::ptr::null_mut());
+        }
+        let states: Vec<_> = std::iter::from_fn(|| events.try_next())
+            .map(|e| (e.key(), e.state()))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                (KeyboardKey::Space, KeyState::Pressed),
+                (KeyboardKey::Space, KeyState::Repeated),
+                (KeyboardKey::Space, KeyState::Released),
+            ]
+        );
+        drop(events);
+        shared.set_key_state(KeyboardKey::Space, true, std::ptr::null_mut());
+        assert!(shared.key_listeners.lock().unwrap().is_empty());
+    }
 
     #[test]
     fn test_send_sync() {
@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::input::keyboard::Shared;
+use crate::input::keyboard::key::{KeyModifiers, KeyboardKey};
+use ndk_sys::{
+    AInputEvent, AKEY_EVENT_ACTION_DOWN, AKEY_EVENT_ACTION_UP, AKeyEvent_getAction,
+    AKeyEvent_getKeyCode,
+};
+use std::sync::{Arc, Mutex, Weak};
+
+static KEYBOARDS: Mutex<Vec<Weak<Shared>>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+pub(super) struct PlatformCoalescedKeyboard {}
+
+impl PlatformCoalescedKeyboard {
+    pub async fn new(shared: &Arc<Shared>) -> Self {
+        KEYBOARDS.lock().unwrap().push(Arc::downgrade(shared));
+        PlatformCoalescedKeyboard {}
+    }
+}
+
+/**
+Processes an `AINPUT_EVENT_TYPE_KEY` event.
+
+Returns whether the event was consumed.  Keys the system acts on (Back, volume) are tracked but
+not consumed.
+*/
+pub(crate) fn key_event(event: *const AInputEvent) -> bool {
+    let down = match unsafe { AKeyEvent_getAction(event) } as u32 {
+        AKEY_EVENT_ACTION_DOWN => true,
+        AKEY_EVENT_ACTION_UP => false,
+        //ACTION_MULTIPLE is for repeats and character strings
+        _ => return false,
+    };
+    let Some(key) = KeyboardKey::from_keycode(unsafe { AKeyEvent_getKeyCode(event) } as u32) else {
+        return false;
+    };
+    KEYBOARDS
+        .lock()
+        .unwrap()
+        .retain(|weak| match weak.upgrade() {
+            Some(shared) => {
+                shared.set_key_state(key, down, std::ptr::null_mut());
+                true
+            }
+            None => false,
+        });
+    !matches!(
+        key,
+        KeyboardKey::BrowserBack
+            | KeyboardKey::VolumeUp
+            | KeyboardKey::VolumeDown
+            | KeyboardKey::Mute
+            | KeyboardKey::Power
+    )
+}
+
+//would need the activity's Configuration, over JNI
+pub(super) async fn hardware_keyboard_present() -> Option<bool> {
+    None
+}
+
+pub(super) async fn key_character(_key: KeyboardKey, _modifiers: KeyModifiers) -> Option<char> {
+    None
+}
+
+pub fn debug_window_show() {
+    //no debug window on Android
+}
+
+pub fn debug_window_hide() {}
+
+impl KeyboardKey {
+    fn from_keycode(keycode: u32) -> Option<Self> {
+        use ndk_sys::*;
+        match keycode {
+            AKEYCODE_A => Some(KeyboardKey::A),
+            AKEYCODE_B => Some(KeyboardKey::B),
+            AKEYCODE_C => Some(KeyboardKey::C),
+            AKEYCODE_D => Some(KeyboardKey::D),
+            AKEYCODE_E => Some(KeyboardKey::E),
+            AKEYCODE_F => Some(KeyboardKey::F),
+            AKEYCODE_G => Some(KeyboardKey::G),
+            AKEYCODE_H => Some(KeyboardKey::H),
+            AKEYCODE_I => Some(KeyboardKey::I),
+            AKEYCODE_J => Some(KeyboardKey::J),
+            AKEYCODE_K => Some(KeyboardKey::K),
+            AKEYCODE_L => Some(KeyboardKey::L),
+            AKEYCODE_M => Some(KeyboardKey::M),
+            AKEYCODE_N => Some(KeyboardKey::N),
+            AKEYCODE_O => Some(KeyboardKey::O),
+            AKEYCODE_P => Some(KeyboardKey::P),
+            AKEYCODE_Q => Some(KeyboardKey::Q),
+            AKEYCODE_R => Some(KeyboardKey::R),
+            AKEYCODE_S => Some(KeyboardKey::S),
+            AKEYCODE_T => Some(KeyboardKey::T),
+            AKEYCODE_U => Some(KeyboardKey::U),
+            AKEYCODE_V => Some(KeyboardKey::V),
+            AKEYCODE_W => Some(KeyboardKey::W),
+            AKEYCODE_X => Some(KeyboardKey::X),
+            AKEYCODE_Y => Some(KeyboardKey::Y),
+            AKEYCODE_Z => Some(KeyboardKey::Z),
+            AKEYCODE_0 => Some(KeyboardKey::Num0),
+            AKEYCODE_1 => Some(KeyboardKey::Num1),
+            AKEYCODE_2 => Some(KeyboardKey::Num2),
+            AKEYCODE_3 => Some(KeyboardKey::Num3),
+            AKEYCODE_4 => Some(KeyboardKey::Num4),
+            AKEYCODE_5 => Some(KeyboardKey::Num5),
+            AKEYCODE_6 => Some(KeyboardKey::Num6),
+            AKEYCODE_7 => Some(KeyboardKey::Num7),
+            AKEYCODE_8 => Some(KeyboardKey::Num8),
+            AKEYCODE_9 => Some(KeyboardKey::Num9),
+            AKEYCODE_COMMA => Some(KeyboardKey::Comma),
+            AKEYCODE_PERIOD => Some(KeyboardKey::Period),
+            AKEYCODE_GRAVE => Some(KeyboardKey::Grave),
+            AKEYCODE_MINUS => Some(KeyboardKey::Minus),
+            AKEYCODE_EQUALS => Some(KeyboardKey::Equal),
+            AKEYCODE_LEFT_BRACKET => Some(KeyboardKey::LeftBracket),
+            AKEYCODE_RIGHT_BRACKET => Some(KeyboardKey::RightBracket),
+            AKEYCODE_BACKSLASH => Some(KeyboardKey::Backslash),
+            AKEYCODE_SEMICOLON => Some(KeyboardKey::Semicolon),
+            AKEYCODE_APOSTROPHE => Some(KeyboardKey::Quote),
+            AKEYCODE_SLASH => Some(KeyboardKey::Slash),
+            AKEYCODE_TAB => Some(KeyboardKey::Tab),
+            AKEYCODE_SPACE => Some(KeyboardKey::Space),
+            AKEYCODE_ENTER => Some(KeyboardKey::Return),
+            //DEL is backspace, FORWARD_DEL is delete
+            AKEYCODE_DEL => Some(KeyboardKey::Delete),
+            AKEYCODE_FORWARD_DEL => Some(KeyboardKey::ForwardDelete),
+            AKEYCODE_ESCAPE => Some(KeyboardKey::Escape),
+            AKEYCODE_SHIFT_LEFT => Some(KeyboardKey::Shift),
+            AKEYCODE_SHIFT_RIGHT => Some(KeyboardKey::RightShift),
+            AKEYCODE_CTRL_LEFT => Some(KeyboardKey::Control),
+            AKEYCODE_CTRL_RIGHT => Some(KeyboardKey::RightControl),
+            AKEYCODE_ALT_LEFT => Some(KeyboardKey::Option),
+            AKEYCODE_ALT_RIGHT => Some(KeyboardKey::RightOption),
+            AKEYCODE_META_LEFT => Some(KeyboardKey::Command),
+            AKEYCODE_META_RIGHT => Some(KeyboardKey::RightCommand),
+            AKEYCODE_FUNCTION => Some(KeyboardKey::Function),
+            AKEYCODE_CAPS_LOCK => Some(KeyboardKey::CapsLock),
+            AKEYCODE_SCROLL_LOCK => Some(KeyboardKey::ScrollLock),
+            AKEYCODE_NUM_LOCK => Some(KeyboardKey::NumLock),
+            AKEYCODE_SYSRQ => Some(KeyboardKey::PrintScreen),
+            AKEYCODE_BREAK => Some(KeyboardKey::Pause),
+            AKEYCODE_INSERT => Some(KeyboardKey::Insert),
+            AKEYCODE_MOVE_HOME => Some(KeyboardKey::Home),
+            AKEYCODE_MOVE_END => Some(KeyboardKey::End),
+            AKEYCODE_PAGE_UP => Some(KeyboardKey::PageUp),
+            AKEYCODE_PAGE_DOWN => Some(KeyboardKey::PageDown),
+            AKEYCODE_DPAD_LEFT => Some(KeyboardKey::LeftArrow),
+            AKEYCODE_DPAD_RIGHT => Some(KeyboardKey::RightArrow),
+            AKEYCODE_DPAD_UP => Some(KeyboardKey::UpArrow),
+            AKEYCODE_DPAD_DOWN => Some(KeyboardKey::DownArrow),
+            AKEYCODE_F1 => Some(KeyboardKey::F1),
+            AKEYCODE_F2 => Some(KeyboardKey::F2),
+            AKEYCODE_F3 => Some(KeyboardKey::F3),
+            AKEYCODE_F4 => Some(KeyboardKey::F4),
+            AKEYCODE_F5 => Some(KeyboardKey::F5),
+            AKEYCODE_F6 => Some(KeyboardKey::F6),
+            AKEYCODE_F7 => Some(KeyboardKey::F7),
+            AKEYCODE_F8 => Some(KeyboardKey::F8),
+            AKEYCODE_F9 => Some(KeyboardKey::F9),
+            AKEYCODE_F10 => Some(KeyboardKey::F10),
+            AKEYCODE_F11 => Some(KeyboardKey::F11),
+            AKEYCODE_F12 => Some(KeyboardKey::F12),
+            AKEYCODE_NUMPAD_0 => Some(KeyboardKey::Keypad0),
+            AKEYCODE_NUMPAD_1 => Some(KeyboardKey::Keypad1),
+            AKEYCODE_NUMPAD_2 => Some(KeyboardKey::Keypad2),
+            AKEYCODE_NUMPAD_3 => Some(KeyboardKey::Keypad3),
+            AKEYCODE_NUMPAD_4 => Some(KeyboardKey::Keypad4),
+            AKEYCODE_NUMPAD_5 => Some(KeyboardKey::Keypad5),
+            AKEYCODE_NUMPAD_6 => Some(KeyboardKey::Keypad6),
+            AKEYCODE_NUMPAD_7 => Some(KeyboardKey::Keypad7),
+            AKEYCODE_NUMPAD_8 => Some(KeyboardKey::Keypad8),
+            AKEYCODE_NUMPAD_9 => Some(KeyboardKey::Keypad9),
+            AKEYCODE_NUMPAD_DIVIDE => Some(KeyboardKey::KeypadDivide),
+            AKEYCODE_NUMPAD_MULTIPLY => Some(KeyboardKey::KeypadMultiply),
+            AKEYCODE_NUMPAD_SUBTRACT => Some(KeyboardKey::KeypadMinus),
+            AKEYCODE_NUMPAD_ADD => Some(KeyboardKey::KeypadPlus),
+            AKEYCODE_NUMPAD_DOT => Some(KeyboardKey::KeypadDecimal),
+            AKEYCODE_NUMPAD_COMMA => Some(KeyboardKey::JISKeypadComma),
+            AKEYCODE_NUMPAD_ENTER => Some(KeyboardKey::KeypadEnter),
+            AKEYCODE_NUMPAD_EQUALS => Some(KeyboardKey::KeypadEquals),
+            AKEYCODE_CLEAR => Some(KeyboardKey::KeypadClear),
+            AKEYCODE_YEN => Some(KeyboardKey::JISYen),
+            AKEYCODE_RO => Some(KeyboardKey::JISUnderscore),
+            AKEYCODE_EISU => Some(KeyboardKey::JISEisu),
+            AKEYCODE_KANA => Some(KeyboardKey::JISKana),
+            AKEYCODE_HENKAN => Some(KeyboardKey::Convert),
+            AKEYCODE_MUHENKAN => Some(KeyboardKey::NonConvert),
+            AKEYCODE_MENU => Some(KeyboardKey::ContextMenu),
+            AKEYCODE_HELP => Some(KeyboardKey::Help),
+            AKEYCODE_CUT => Some(KeyboardKey::Cut),
+            AKEYCODE_COPY => Some(KeyboardKey::Copy),
+            AKEYCODE_PASTE => Some(KeyboardKey::Paste),
+            AKEYCODE_VOLUME_UP => Some(KeyboardKey::VolumeUp),
+            AKEYCODE_VOLUME_DOWN => Some(KeyboardKey::VolumeDown),
+            AKEYCODE_VOLUME_MUTE => Some(KeyboardKey::Mute),
+            AKEYCODE_MEDIA_PLAY_PAUSE => Some(KeyboardKey::Play),
+            AKEYCODE_MEDIA_STOP => Some(KeyboardKey::Stop),
+            AKEYCODE_MEDIA_NEXT => Some(KeyboardKey::NextTrack),
+            AKEYCODE_MEDIA_PREVIOUS => Some(KeyboardKey::PreviousTrack),
+            AKEYCODE_MEDIA_EJECT => Some(KeyboardKey::Eject),
+            AKEYCODE_POWER => Some(KeyboardKey::Power),
+            AKEYCODE_WAKEUP => Some(KeyboardKey::WakeUp),
+            AKEYCODE_BACK => Some(KeyboardKey::BrowserBack),
+            AKEYCODE_FORWARD => Some(KeyboardKey::BrowserForward),
+            AKEYCODE_SEARCH => Some(KeyboardKey::BrowserSearch),
+            AKEYCODE_BOOKMARK => Some(KeyboardKey::BrowserFavorites),
+            AKEYCODE_REFRESH => Some(KeyboardKey::BrowserRefresh),
+            AKEYCODE_ENVELOPE => Some(KeyboardKey::LaunchMail),
+            AKEYCODE_CALCULATOR => Some(KeyboardKey::LaunchApp2),
+            _ => None,
+        }
+    }
+}
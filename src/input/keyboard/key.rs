@@ -625,3 +625,230 @@ impl KeyboardKey {
         ]
     }
 }
+
+impl KeyboardKey {
+    /// The character this key types on the user's current keyboard layout, with `modifiers`
+    /// held.
+    ///
+    /// This is for labels: a shortcut bound to [`KeyboardKey::Q`] should say "A" to someone
+    /// on AZERTY, and "'" on Dvorak.  It isn't text input; dead keys report their own
+    /// character rather than waiting for the next key, and input methods aren't consulted.
+    /// For text, see [`crate::input::text`].
+    ///
+    /// Returns `None` for keys that don't type a character (arrows, function keys, ...), or
+    /// where the layout can't be read.
+    ///
+    /// # Platform behavior
+    ///
+    /// | Platform | Source |
+    /// |----------|--------|
+    /// | macOS    | `UCKeyTranslate` with the current input source's layout |
+    /// | Windows  | `ToUnicodeEx` with the main thread's layout |
+    /// | Linux    | libxkbcommon with the compositor's keymap and its active layout, loaded at runtime.  `None` before the first window, or without libxkbcommon |
+    /// | wasm32   | `KeyboardEvent.key` from keys pressed so far, or without modifiers, `navigator.keyboard.getLayoutMap()` where the browser has it |
+    /// | iOS, Android | always `None` |
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::keyboard::key::{KeyModifiers, KeyboardKey};
+    ///
+    /// let label = KeyboardKey::Q
+    ///     .to_character(KeyModifiers::new())
+    ///     .await
+    ///     .map(|c| c.to_uppercase().to_string())
+    ///     .unwrap_or_else(|| "Q".to_string());
+    /// println!("Quit: Ctrl+{label}");
+    /// # }
+    /// ```
+    pub async fn to_character(self, modifiers: KeyModifiers) -> Option<char> {
+        //Return, Tab, Escape and so on type control characters, which aren't labels
+        super::sys::key_character(self, modifiers)
+            .await
+            .filter(|c| !c.is_control())
+    }
+}
+
+/// The modifiers that change which character a key types, for [`KeyboardKey::to_character`].
+///
+/// Modifiers that make shortcuts rather than characters, like Control and Command, aren't
+/// here.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, Default)]
+pub struct KeyModifiers {
+    shift: bool,
+    alt_graph: bool,
+}
+
+impl KeyModifiers {
+    /// No modifiers.
+    pub const fn new() -> Self {
+        KeyModifiers {
+            shift: false,
+            alt_graph: false,
+        }
+    }
+
+    /// Shift.
+    pub const fn shift(mut self, shift: bool) -> Self {
+        self.shift = shift;
+        self
+    }
+
+    /// AltGr, or Option on macOS: the key that reaches a layout's third level.
+    pub const fn alt_graph(mut self, alt_graph: bool) -> Self {
+        self.alt_graph = alt_graph;
+        self
+    }
+
+    /// Whether Shift is held.
+    pub const fn has_shift(self) -> bool {
+        self.shift
+    }
+
+    /// Whether AltGr (or Option) is held.
+    pub const fn has_alt_graph(self) -> bool {
+        self.alt_graph
+    }
+}
+
+/// How many [`KeyboardKey`] variants there are.  `WakeUp` is the last one.
+const KEY_COUNT: usize = KeyboardKey::WakeUp as usize + 1;
+const KEY_SET_WORDS: usize = KEY_COUNT.div_ceil(64);
+
+/// Every key, indexed by discriminant, for [`KeySet::iter`].
+static ALL_KEYS: std::sync::LazyLock<Vec<KeyboardKey>> =
+    std::sync::LazyLock::new(KeyboardKey::all_keys);
+
+/// A set of keys, stored as one bit per key.
+///
+/// Returned by [`Keyboard::pressed_set`](super::Keyboard::pressed_set), which samples every
+/// key at once.  Build masks with [`KeySet::of`] and test them against the sample with
+/// [`contains_all`](Self::contains_all) or [`contains_any`](Self::contains_any).
+///
+/// # Examples
+///
+/// ```
+/// use app_window::input::keyboard::key::{KeyboardKey, KeySet};
+///
+/// const MOVE: KeySet = KeySet::of(&[KeyboardKey::W, KeyboardKey::A, KeyboardKey::S, KeyboardKey::D]);
+/// const SAVE: KeySet = KeySet::of(&[KeyboardKey::Command, KeyboardKey::S]);
+///
+/// //in a game, this would be keyboard.pressed_set()
+/// let pressed = KeySet::of(&[KeyboardKey::Command, KeyboardKey::S]);
+/// assert!(pressed.contains_any(MOVE));
+/// assert!(pressed.contains_all(SAVE));
+/// assert_eq!(pressed.iter().collect::<Vec<_>>(), [KeyboardKey::S, KeyboardKey::Command]);
+/// ```
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, Default)]
+pub struct KeySet {
+    words: [u64; KEY_SET_WORDS],
+}
+
+impl KeySet {
+    /// The empty set.
+    pub const fn new() -> Self {
+        KeySet {
+            words: [0; KEY_SET_WORDS],
+        }
+    }
+
+    /// The set of `keys`.  Usable in a `const`.
+    pub const fn of(keys: &[KeyboardKey]) -> Self {
+        let mut set = KeySet::new();
+        let mut i = 0;
+        while i < keys.len() {
+            set.insert(keys[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Adds `key` to the set.
+    pub const fn insert(&mut self, key: KeyboardKey) {
+        let index = key as usize;
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    /// Removes `key` from the set.
+    pub const fn remove(&mut self, key: KeyboardKey) {
+        let index = key as usize;
+        self.words[index / 64] &= !(1 << (index % 64));
+    }
+
+    /// Whether `key` is in the set.
+    pub const fn contains(&self, key: KeyboardKey) -> bool {
+        let index = key as usize;
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Whether every key in `mask` is in the set.
+    pub fn contains_all(&self, mask: KeySet) -> bool {
+        self.words
+            .iter()
+            .zip(mask.words)
+            .all(|(word, mask)| word & mask == mask)
+    }
+
+    /// Whether any key in `mask` is in the set.
+    pub fn contains_any(&self, mask: KeySet) -> bool {
+        self.words
+            .iter()
+            .zip(mask.words)
+            .any(|(word, mask)| word & mask != 0)
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// How many keys are in the set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// The keys in the set, in discriminant order.
+    pub fn iter(&self) -> impl Iterator<Item = KeyboardKey> + '_ {
+        (0..KEY_COUNT)
+            .filter(|index| self.words[index / 64] & (1 << (index % 64)) != 0)
+            .map(|index| ALL_KEYS[index])
+    }
+
+    /// Sets the bit for the key with discriminant `index`.
+    pub(super) fn insert_index(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+}
+
+impl FromIterator<KeyboardKey> for KeySet {
+    fn from_iter<I: IntoIterator<Item = KeyboardKey>>(iter: I) -> Self {
+        let mut set = KeySet::new();
+        for key in iter {
+            set.insert(key);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KEY_COUNT, KeySet, KeyboardKey};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn key_set_covers_every_key() {
+        let all = KeyboardKey::all_keys();
+        assert_eq!(all.len(), KEY_COUNT);
+        let set: KeySet = all.iter().copied().collect();
+        assert_eq!(set.len(), KEY_COUNT);
+        assert_eq!(set.iter().collect::<Vec<_>>(), all);
+        let mut set = KeySet::of(&[KeyboardKey::WakeUp, KeyboardKey::A]);
+        set.remove(KeyboardKey::A);
+        assert!(set.contains(KeyboardKey::WakeUp));
+        assert!(!set.contains_all(KeySet::of(&[KeyboardKey::A, KeyboardKey::WakeUp])));
+    }
+}
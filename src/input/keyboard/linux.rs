@@ -1,20 +1,22 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::input::keyboard::Shared;
-use crate::input::keyboard::key::KeyboardKey;
+use crate::input::keyboard::key::{KeyModifiers, KeyboardKey};
 use crate::input::mouse::linux::motion_event;
 use crate::input::mouse::sys::{axis_event, button_event, xdg_toplevel_configure_event};
 use memmap2::MmapMut;
 use std::ffi::c_void;
 use std::fs::File;
-use std::os::fd::AsFd;
+use std::os::fd::{AsFd, OwnedFd};
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
 use wayland_client::backend::ObjectId;
 use wayland_client::globals::{GlobalListContents, registry_queue_init};
 use wayland_client::protocol::wl_buffer::WlBuffer;
 use wayland_client::protocol::wl_compositor::WlCompositor;
-use wayland_client::protocol::wl_keyboard::WlKeyboard;
+use wayland_client::protocol::wl_keyboard::{KeymapFormat, WlKeyboard};
 use wayland_client::protocol::wl_pointer::WlPointer;
-use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::protocol::wl_seat::{Capability, WlSeat};
 use wayland_client::protocol::wl_shm::{Format, WlShm};
 use wayland_client::protocol::wl_shm_pool::WlShmPool;
 use wayland_client::protocol::wl_surface::WlSurface;
@@ -26,6 +28,7 @@ use wayland_protocols::xdg::shell::client::xdg_toplevel::XdgToplevel;
 use wayland_protocols::xdg::shell::client::xdg_wm_base::{Event, XdgWmBase};
 
 pub(crate) mod ax;
+mod xkb;
 
 #[derive(Default)]
 struct KeyboardState {
@@ -45,6 +48,13 @@ impl KeyboardState {
 }
 static KEYBOARD_STATE: OnceLock<Mutex<KeyboardState>> = OnceLock::new();
 
+/// The compositor's repeat delay and interval, or `None` if it doesn't want keys repeated.
+static REPEAT_INFO: Mutex<Option<(Duration, Duration)>> = Mutex::new(None);
+
+pub(super) fn repeat_info() -> Option<(Duration, Duration)> {
+    *REPEAT_INFO.lock().unwrap()
+}
+
 #[derive(Debug)]
 pub(super) struct PlatformCoalescedKeyboard {}
 
@@ -306,10 +316,72 @@ impl Dispatch<WlPointer, ObjectId> for AppData {
     }
 }
 
+/// Whether the seat has a keyboard: 0 until we hear, then 1 for no and 2 for yes.
+static SEAT_HAS_KEYBOARD: AtomicU8 = AtomicU8::new(0);
+
+/**
+Call this from [WlSeat] dispatch for [wayland_client::protocol::wl_seat::Event::Capabilities] event.
+*/
+pub fn wl_seat_capabilities_event(capabilities: u32) {
+    let has_keyboard = capabilities & u32::from(Capability::Keyboard) != 0;
+    SEAT_HAS_KEYBOARD.store(if has_keyboard { 2 } else { 1 }, Ordering::Relaxed);
+}
+
+pub(super) async fn hardware_keyboard_present() -> Option<bool> {
+    match SEAT_HAS_KEYBOARD.load(Ordering::Relaxed) {
+        0 => None,
+        has_keyboard => Some(has_keyboard == 2),
+    }
+}
+
+/// The compositor's keymap, from `wl_keyboard.keymap`, following `wl_keyboard.modifiers`.
+static KEYMAP: Mutex<Option<xkb::Keymap>> = Mutex::new(None);
+
+pub(super) async fn key_character(key: KeyboardKey, modifiers: KeyModifiers) -> Option<char> {
+    let code = (0..0x300).find(|code| KeyboardKey::from_vk(*code) == Some(key))?;
+    KEYMAP.lock().unwrap().as_ref()?.character(
+        code,
+        modifiers.has_shift(),
+        modifiers.has_alt_graph(),
+    )
+}
+
+/// Whether the compositor's keymap says `key` repeats, or `None` without one.
+pub(super) fn key_repeats(key: KeyboardKey) -> Option<bool> {
+    let code = (0..0x300).find(|code| KeyboardKey::from_vk(*code) == Some(key))?;
+    Some(KEYMAP.lock().unwrap().as_ref()?.repeats(code))
+}
+
+/// Compiles the compositor's keymap, from `wl_keyboard.keymap`.
+pub fn wl_keyboard_keymap_event(format: KeymapFormat, fd: OwnedFd, size: u32) {
+    if format != KeymapFormat::XkbV1 {
+        return;
+    }
+    let file = File::from(fd);
+    //the compositor may share one mapping with every client, so only read it
+    let Ok(map) = (unsafe { memmap2::MmapOptions::new().len(size as usize).map(&file) }) else {
+        logwise::warn_sync!("Can't map the keymap");
+        return;
+    };
+    //it's NUL-terminated
+    let text = map.split(|b| *b == 0).next().unwrap_or_default();
+    *KEYMAP.lock().unwrap() = xkb::Keymap::new(&String::from_utf8_lossy(text));
+}
+
+/// Tracks modifiers and the active layout, from `wl_keyboard.modifiers`.
+pub fn wl_keyboard_modifiers_event(depressed: u32, latched: u32, locked: u32, group: u32) {
+    if let Some(keymap) = KEYMAP.lock().unwrap().as_mut() {
+        keymap.update(depressed, latched, locked, group);
+    }
+}
+
 /**
 Call this from [WlKeyboard] dispatch for [wayland_client::protocol::wl_keyboard::Event::Key] event.
 */
 pub fn wl_keyboard_event(_serial: u32, _time: u32, key: u32, state: u32, surface_id: ObjectId) {
+    if state == 1 {
+        compose(key);
+    }
     if let Some(key) = KeyboardKey::from_vk(key) {
         let down = state == 1;
         KEYBOARD_STATE
@@ -321,7 +393,79 @@ pub fn wl_keyboard_event(_serial: u32, _time: u32, key: u32, state: u32, surface
             });
         ax::ax_press(key, down);
     } else {
-        logwise::warn_sync!("Unknown key {key}", key = key);
+        //the keymap may know what it is, even if we don't
+        let symbol = KEYMAP
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|keymap| keymap.symbol_name(key))
+            .unwrap_or_default();
+        logwise::warn_sync!(
+            "Unknown key {key} {symbol}",
+            key = key,
+            symbol = logwise::privacy::LogIt(&symbol)
+        );
+    }
+}
+
+/// Dead keys and the Compose key, or `None` without a compose table.
+fn composer() -> Option<&'static Mutex<xkb::Composer>> {
+    static COMPOSER: OnceLock<Option<Mutex<xkb::Composer>>> = OnceLock::new();
+    COMPOSER
+        .get_or_init(|| xkb::Composer::new().map(Mutex::new))
+        .as_ref()
+}
+
+/// Runs a pressed key through the compose table.
+///
+/// An input method composes dead keys itself, and takes them before they get here, so these
+/// are only the ones nothing else handles.
+fn compose(key: u32) {
+    let Some(keysym) = KEYMAP.lock().unwrap().as_ref().map(|k| k.keysym(key)) else {
+        return;
+    };
+    if let Some(composer) = composer() {
+        let compose = composer.lock().unwrap().feed(keysym);
+        report_compose(compose);
+    }
+}
+
+/// Reports a compose sequence's progress as text.
+fn report_compose(compose: xkb::Compose) {
+    match compose {
+        xkb::Compose::Nothing => {}
+        xkb::Compose::Composing(preedit) => crate::input::text::preedit(&preedit, None),
+        xkb::Compose::Composed(text) => crate::input::text::commit(&text),
+        xkb::Compose::Cancelled(text) if text.is_empty() => crate::input::text::end_composition(),
+        xkb::Compose::Cancelled(text) => crate::input::text::commit(&text),
+    }
+}
+
+/// Records the compositor's key repeat settings, from `wl_keyboard.repeat_info`.
+pub fn wl_keyboard_repeat_info_event(rate: i32, delay: i32) {
+    //rate is in repeats per second, and 0 turns repeat off
+    *REPEAT_INFO.lock().unwrap() = u64::try_from(rate)
+        .ok()
+        .filter(|rate| *rate > 0)
+        .map(|rate| {
+            (
+                Duration::from_millis(delay.max(0) as u64),
+                Duration::from_micros(1_000_000 / rate),
+            )
+        });
+}
+
+/// Stops any key repeating and abandons any compose sequence, for when a window loses keyboard
+/// focus.
+pub fn wl_keyboard_leave_event() {
+    KEYBOARD_STATE
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap()
+        .apply_all(|shared| shared.repeater.stop());
+    if let Some(composer) = composer() {
+        composer.lock().unwrap().reset();
+        crate::input::text::end_composition();
     }
 }
 
@@ -343,6 +487,23 @@ impl Dispatch<WlKeyboard, ObjectId> for AppData {
             } => {
                 wl_keyboard_event(serial, time, key, state.into(), data.clone());
             }
+            wayland_client::protocol::wl_keyboard::Event::RepeatInfo { rate, delay } => {
+                wl_keyboard_repeat_info_event(rate, delay);
+            }
+            wayland_client::protocol::wl_keyboard::Event::Keymap { format, fd, size } => {
+                if let wayland_client::WEnum::Value(format) = format {
+                    wl_keyboard_keymap_event(format, fd, size);
+                }
+            }
+            wayland_client::protocol::wl_keyboard::Event::Modifiers {
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                group,
+                ..
+            } => {
+                wl_keyboard_modifiers_event(mods_depressed, mods_latched, mods_locked, group);
+            }
             _ => {
                 logwise::debuginternal_sync!(
                     "Got wlKeyboard event {event}",
@@ -600,3 +761,78 @@ impl KeyboardKey {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::report_compose;
+    use super::xkb::{Compose, Composer, dead_key_accent};
+    use crate::input::text::TextEvent;
+
+    //XKB_KEY_dead_acute
+    const DEAD_ACUTE: u32 = 0xfe51;
+    //XKB_KEY_e, XKB_KEY_Left, XKB_KEY_Shift_L
+    const E: u32 = 0x65;
+    const LEFT: u32 = 0xff51;
+    const SHIFT: u32 = 0xffe1;
+
+    #[test]
+    fn compose_table() {
+        //without libxkbcommon or a compose table, there's nothing to test
+        let Some(mut composer) = Composer::new() else {
+            return;
+        };
+        assert_eq!(composer.feed(E), Compose::Nothing);
+        assert_eq!(
+            composer.feed(DEAD_ACUTE),
+            Compose::Composing("´".to_string())
+        );
+        //shift for a capital doesn't end it
+        assert_eq!(composer.feed(SHIFT), Compose::Nothing);
+        assert_eq!(composer.feed(E), Compose::Composed("é".to_string()));
+
+        assert_eq!(
+            composer.feed(DEAD_ACUTE),
+            Compose::Composing("´".to_string())
+        );
+        assert_eq!(composer.feed(LEFT), Compose::Cancelled(String::new()));
+
+        composer.feed(DEAD_ACUTE);
+        composer.reset();
+        assert_eq!(composer.feed(E), Compose::Nothing);
+    }
+
+    #[test]
+    fn dead_key_then_arrow_cancels() {
+        let (_sending, mut text) = crate::input::text::test::listen();
+        let accent = dead_key_accent(DEAD_ACUTE).unwrap().to_string();
+        report_compose(Compose::Composing(accent.clone()));
+        //an arrow types nothing
+        report_compose(Compose::Cancelled(String::new()));
+        assert_eq!(
+            text.try_next(),
+            Some(TextEvent::Preedit {
+                text: accent,
+                cursor: None
+            })
+        );
+        assert_eq!(text.try_next(), Some(TextEvent::Cancel));
+        assert_eq!(text.try_next(), None);
+    }
+
+    #[test]
+    fn dead_key_then_other_key_commits_both() {
+        let (_sending, mut text) = crate::input::text::test::listen();
+        report_compose(Compose::Composing("´".to_string()));
+        report_compose(Compose::Cancelled("´q".to_string()));
+        report_compose(Compose::Nothing);
+        assert!(matches!(text.try_next(), Some(TextEvent::Preedit { .. })));
+        assert_eq!(text.try_next(), Some(TextEvent::Commit("´q".to_string())));
+        assert_eq!(text.try_next(), None);
+
+        report_compose(Compose::Composing("´".to_string()));
+        report_compose(Compose::Composed("é".to_string()));
+        assert!(matches!(text.try_next(), Some(TextEvent::Preedit { .. })));
+        assert_eq!(text.try_next(), Some(TextEvent::Commit("é".to_string())));
+        assert_eq!(text.try_next(), None);
+    }
+}
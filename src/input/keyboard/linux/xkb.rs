@@ -0,0 +1,434 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The compositor's XKB keymap: what keys type, and what they're called.
+//!
+//! Keys themselves come from evdev codes, which name positions on the keyboard whatever the
+//! layout; the keymap says what each position means on the user's layout, and which layout
+//! (group) is active.
+//!
+//! libxkbcommon is loaded at runtime, so it isn't a build or link dependency.  Every Wayland
+//! desktop has it, since compositors use it themselves; where it's missing, we know no
+//! characters or symbols.
+//!
+//! It also has the locale's compose sequences (`Compose` files), which is how dead keys and the
+//! Compose key type text when no input method does it for us.
+
+use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::sync::OnceLock;
+
+#[allow(non_camel_case_types)]
+type xkb_context = c_void;
+#[allow(non_camel_case_types)]
+type xkb_keymap = c_void;
+#[allow(non_camel_case_types)]
+type xkb_state = c_void;
+#[allow(non_camel_case_types)]
+type xkb_compose_table = c_void;
+#[allow(non_camel_case_types)]
+type xkb_compose_state = c_void;
+
+/// `XKB_KEYMAP_FORMAT_TEXT_V1`, which is what `wl_keyboard` sends.
+const FORMAT_TEXT_V1: c_int = 1;
+/// `XKB_MOD_INVALID`.
+const MOD_INVALID: u32 = u32::MAX;
+/// `XKB_STATE_LAYOUT_EFFECTIVE`.
+const LAYOUT_EFFECTIVE: c_int = 1 << 7;
+/// `XKB_COMPOSE_FEED_ACCEPTED`; modifiers are `XKB_COMPOSE_FEED_IGNORED`.
+const COMPOSE_FEED_ACCEPTED: c_int = 1;
+/// `XKB_COMPOSE_COMPOSING`.
+const COMPOSE_COMPOSING: c_int = 1;
+/// `XKB_COMPOSE_COMPOSED`.
+const COMPOSE_COMPOSED: c_int = 2;
+/// `XKB_COMPOSE_CANCELLED`.
+const COMPOSE_CANCELLED: c_int = 3;
+/// `XKB_KEY_Multi_key`, the Compose key.
+const MULTI_KEY: u32 = 0xff20;
+
+struct Library {
+    context_new: unsafe extern "C" fn(flags: c_int) -> *mut xkb_context,
+    context_unref: unsafe extern "C" fn(context: *mut xkb_context),
+    keymap_new_from_string: unsafe extern "C" fn(
+        context: *mut xkb_context,
+        string: *const c_char,
+        format: c_int,
+        flags: c_int,
+    ) -> *mut xkb_keymap,
+    keymap_unref: unsafe extern "C" fn(keymap: *mut xkb_keymap),
+    keymap_mod_get_index: unsafe extern "C" fn(keymap: *mut xkb_keymap, name: *const c_char) -> u32,
+    keymap_key_repeats: unsafe extern "C" fn(keymap: *mut xkb_keymap, key: u32) -> c_int,
+    state_new: unsafe extern "C" fn(keymap: *mut xkb_keymap) -> *mut xkb_state,
+    state_unref: unsafe extern "C" fn(state: *mut xkb_state),
+    state_update_mask: unsafe extern "C" fn(
+        state: *mut xkb_state,
+        depressed_mods: u32,
+        latched_mods: u32,
+        locked_mods: u32,
+        depressed_layout: u32,
+        latched_layout: u32,
+        locked_layout: u32,
+    ) -> c_int,
+    state_key_get_utf32: unsafe extern "C" fn(state: *mut xkb_state, key: u32) -> u32,
+    state_key_get_one_sym: unsafe extern "C" fn(state: *mut xkb_state, key: u32) -> u32,
+    state_serialize_layout: unsafe extern "C" fn(state: *mut xkb_state, components: c_int) -> u32,
+    keysym_get_name: unsafe extern "C" fn(keysym: u32, buffer: *mut c_char, size: usize) -> c_int,
+    keysym_to_utf32: unsafe extern "C" fn(keysym: u32) -> u32,
+    /// Older libxkbcommons don't have compose.
+    compose: Option<ComposeLibrary>,
+}
+
+struct ComposeLibrary {
+    table_new_from_locale: unsafe extern "C" fn(
+        context: *mut xkb_context,
+        locale: *const c_char,
+        flags: c_int,
+    ) -> *mut xkb_compose_table,
+    table_unref: unsafe extern "C" fn(table: *mut xkb_compose_table),
+    state_new:
+        unsafe extern "C" fn(table: *mut xkb_compose_table, flags: c_int) -> *mut xkb_compose_state,
+    state_unref: unsafe extern "C" fn(state: *mut xkb_compose_state),
+    state_feed: unsafe extern "C" fn(state: *mut xkb_compose_state, keysym: u32) -> c_int,
+    state_reset: unsafe extern "C" fn(state: *mut xkb_compose_state),
+    state_get_status: unsafe extern "C" fn(state: *mut xkb_compose_state) -> c_int,
+    state_get_utf8: unsafe extern "C" fn(
+        state: *mut xkb_compose_state,
+        buffer: *mut c_char,
+        size: usize,
+    ) -> c_int,
+}
+
+impl ComposeLibrary {
+    unsafe fn open(handle: *mut c_void) -> Option<ComposeLibrary> {
+        unsafe {
+            Some(ComposeLibrary {
+                table_new_from_locale: symbol(handle, c"xkb_compose_table_new_from_locale")?,
+                table_unref: symbol(handle, c"xkb_compose_table_unref")?,
+                state_new: symbol(handle, c"xkb_compose_state_new")?,
+                state_unref: symbol(handle, c"xkb_compose_state_unref")?,
+                state_feed: symbol(handle, c"xkb_compose_state_feed")?,
+                state_reset: symbol(handle, c"xkb_compose_state_reset")?,
+                state_get_status: symbol(handle, c"xkb_compose_state_get_status")?,
+                state_get_utf8: symbol(handle, c"xkb_compose_state_get_utf8")?,
+            })
+        }
+    }
+}
+
+/// `name` from `handle`, as `F`, which must be its function pointer type.
+unsafe fn symbol<F>(handle: *mut c_void, name: &CStr) -> Option<F> {
+    let symbol = unsafe { libc::dlsym(handle, name.as_ptr()) };
+    (!symbol.is_null()).then(|| unsafe { std::mem::transmute_copy::<*mut c_void, F>(&symbol) })
+}
+
+impl Library {
+    fn open() -> Option<Library> {
+        let handle = unsafe { libc::dlopen(c"libxkbcommon.so.0".as_ptr(), libc::RTLD_NOW) };
+        if handle.is_null() {
+            logwise::warn_sync!("No libxkbcommon, so key characters are unknown");
+            return None;
+        }
+        //the library stays loaded for the life of the process
+        unsafe {
+            Some(Library {
+                context_new: symbol(handle, c"xkb_context_new")?,
+                context_unref: symbol(handle, c"xkb_context_unref")?,
+                keymap_new_from_string: symbol(handle, c"xkb_keymap_new_from_string")?,
+                keymap_unref: symbol(handle, c"xkb_keymap_unref")?,
+                keymap_mod_get_index: symbol(handle, c"xkb_keymap_mod_get_index")?,
+                keymap_key_repeats: symbol(handle, c"xkb_keymap_key_repeats")?,
+                state_new: symbol(handle, c"xkb_state_new")?,
+                state_unref: symbol(handle, c"xkb_state_unref")?,
+                state_update_mask: symbol(handle, c"xkb_state_update_mask")?,
+                state_key_get_utf32: symbol(handle, c"xkb_state_key_get_utf32")?,
+                state_key_get_one_sym: symbol(handle, c"xkb_state_key_get_one_sym")?,
+                state_serialize_layout: symbol(handle, c"xkb_state_serialize_layout")?,
+                keysym_get_name: symbol(handle, c"xkb_keysym_get_name")?,
+                keysym_to_utf32: symbol(handle, c"xkb_keysym_to_utf32")?,
+                compose: ComposeLibrary::open(handle),
+            })
+        }
+    }
+}
+
+fn library() -> Option<&'static Library> {
+    static LIBRARY: OnceLock<Option<Library>> = OnceLock::new();
+    LIBRARY.get_or_init(Library::open).as_ref()
+}
+
+/// XKB keycodes are evdev's, plus 8.
+const fn keycode(evdev: u32) -> u32 {
+    evdev + 8
+}
+
+/// A compiled keymap, and the keyboard's state in it.
+pub(super) struct Keymap {
+    library: &'static Library,
+    keymap: *mut xkb_keymap,
+    /// Follows `wl_keyboard.modifiers`.
+    state: *mut xkb_state,
+}
+
+//libxkbcommon objects aren't thread-safe, but they're only used behind a lock
+unsafe impl Send for Keymap {}
+
+impl Keymap {
+    /// Compiles `text`, an XKB keymap as `wl_keyboard.keymap` sends it.
+    pub(super) fn new(text: &str) -> Option<Keymap> {
+        let library = library()?;
+        let text = CString::new(text).ok()?;
+        unsafe {
+            let context = (library.context_new)(0);
+            if context.is_null() {
+                return None;
+            }
+            //the keymap keeps what it needs of the context
+            let keymap =
+                (library.keymap_new_from_string)(context, text.as_ptr(), FORMAT_TEXT_V1, 0);
+            (library.context_unref)(context);
+            if keymap.is_null() {
+                logwise::warn_sync!("Can't compile the compositor's keymap");
+                return None;
+            }
+            let state = (library.state_new)(keymap);
+            if state.is_null() {
+                (library.keymap_unref)(keymap);
+                return None;
+            }
+            Some(Keymap {
+                library,
+                keymap,
+                state,
+            })
+        }
+    }
+
+    /// Applies `wl_keyboard.modifiers`, which is also how the active layout changes.
+    pub(super) fn update(&mut self, depressed: u32, latched: u32, locked: u32, group: u32) {
+        unsafe {
+            (self.library.state_update_mask)(self.state, depressed, latched, locked, 0, 0, group)
+        };
+    }
+
+    fn mod_mask(&self, name: &CStr) -> u32 {
+        match unsafe { (self.library.keymap_mod_get_index)(self.keymap, name.as_ptr()) } {
+            MOD_INVALID => 0,
+            index => 1 << index,
+        }
+    }
+
+    /// The character evdev `key` types on the active layout with just these modifiers, whatever
+    /// is held now.
+    pub(super) fn character(&self, key: u32, shift: bool, alt_graph: bool) -> Option<char> {
+        let mut mods = 0;
+        if shift {
+            mods |= self.mod_mask(c"Shift");
+        }
+        //ISO_Level3_Shift is Mod5 in the standard keymaps
+        if alt_graph {
+            mods |= self.mod_mask(c"Mod5");
+        }
+        unsafe {
+            let group = (self.library.state_serialize_layout)(self.state, LAYOUT_EFFECTIVE);
+            let scratch = (self.library.state_new)(self.keymap);
+            if scratch.is_null() {
+                return None;
+            }
+            (self.library.state_update_mask)(scratch, mods, 0, 0, 0, 0, group);
+            let scalar = (self.library.state_key_get_utf32)(scratch, keycode(key));
+            (self.library.state_unref)(scratch);
+            //0 for no character
+            char::from_u32(scalar).filter(|c| *c != '\0')
+        }
+    }
+
+    /// Whether the keymap says evdev `key` repeats when held.
+    pub(super) fn repeats(&self, key: u32) -> bool {
+        unsafe { (self.library.keymap_key_repeats)(self.keymap, keycode(key)) != 0 }
+    }
+
+    /// The keysym evdev `key` produces now.
+    pub(super) fn keysym(&self, key: u32) -> u32 {
+        unsafe { (self.library.state_key_get_one_sym)(self.state, keycode(key)) }
+    }
+
+    /// The keysym evdev `key` produces now, by name, such as `"eacute"` or `"XF86AudioMute"`.
+    pub(super) fn symbol_name(&self, key: u32) -> Option<String> {
+        let mut buffer = [0 as c_char; 64];
+        unsafe {
+            let keysym = (self.library.state_key_get_one_sym)(self.state, keycode(key));
+            //XKB_KEY_NoSymbol
+            if keysym == 0 {
+                return None;
+            }
+            let written = (self.library.keysym_get_name)(keysym, buffer.as_mut_ptr(), buffer.len());
+            if written <= 0 {
+                return None;
+            }
+            Some(
+                CStr::from_ptr(buffer.as_ptr())
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }
+    }
+}
+
+impl Drop for Keymap {
+    fn drop(&mut self) {
+        unsafe {
+            (self.library.state_unref)(self.state);
+            (self.library.keymap_unref)(self.keymap);
+        }
+    }
+}
+
+/// The spacing form of a dead key's accent, which is how a pending one is shown.
+///
+/// libxkbcommon gives dead keys no character, so these are the common ones by hand.
+pub(super) fn dead_key_accent(keysym: u32) -> Option<char> {
+    Some(match keysym {
+        0xfe50 => '`',
+        0xfe51 => '´',
+        0xfe52 => '^',
+        0xfe53 => '~',
+        0xfe54 => '¯',
+        0xfe55 => '˘',
+        0xfe56 => '˙',
+        0xfe57 => '¨',
+        0xfe58 => '˚',
+        0xfe59 => '˝',
+        0xfe5a => 'ˇ',
+        0xfe5b => '¸',
+        0xfe5c => '˛',
+        _ => return None,
+    })
+}
+
+/// What a key press did to a compose sequence.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Compose {
+    /// The key isn't part of a sequence.
+    Nothing,
+    /// The sequence so far, which is waiting for another key.
+    Composing(String),
+    /// The sequence's result.
+    Composed(String),
+    /// The key didn't continue the sequence.  Holds what the sequence and that key type on
+    /// their own, which is empty if the key doesn't type anything (an arrow, or Escape).
+    Cancelled(String),
+}
+
+/// Dead keys and Compose key sequences, from the locale's compose table.
+pub(super) struct Composer {
+    library: &'static Library,
+    compose: &'static ComposeLibrary,
+    table: *mut xkb_compose_table,
+    state: *mut xkb_compose_state,
+    /// Keysyms in the sequence so far.
+    pending: Vec<u32>,
+}
+
+//as for Keymap
+unsafe impl Send for Composer {}
+
+impl Composer {
+    /// Loads the compose table for the user's locale.
+    pub(super) fn new() -> Option<Composer> {
+        let library = library()?;
+        let compose = library.compose.as_ref()?;
+        //the same variables setlocale(LC_CTYPE, "") reads
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+            .unwrap_or_else(|| "C".to_string());
+        let locale = CString::new(locale).ok()?;
+        unsafe {
+            let context = (library.context_new)(0);
+            if context.is_null() {
+                return None;
+            }
+            //the table keeps what it needs of the context
+            let table = (compose.table_new_from_locale)(context, locale.as_ptr(), 0);
+            (library.context_unref)(context);
+            if table.is_null() {
+                logwise::warn_sync!("No compose table for the locale, so dead keys don't compose");
+                return None;
+            }
+            let state = (compose.state_new)(table, 0);
+            if state.is_null() {
+                (compose.table_unref)(table);
+                return None;
+            }
+            Some(Composer {
+                library,
+                compose,
+                table,
+                state,
+                pending: Vec::new(),
+            })
+        }
+    }
+
+    /// What `keysyms` type on their own; dead keys type their accent.
+    fn text(&self, keysyms: &[u32]) -> String {
+        keysyms
+            .iter()
+            .filter_map(|keysym| {
+                dead_key_accent(*keysym)
+                    .or_else(|| char::from_u32(unsafe { (self.library.keysym_to_utf32)(*keysym) }))
+            })
+            .filter(|c| *c != '\0' && !c.is_control())
+            .collect()
+    }
+
+    /// Feeds a pressed key's keysym to the sequence.
+    pub(super) fn feed(&mut self, keysym: u32) -> Compose {
+        unsafe {
+            //modifiers don't count
+            if (self.compose.state_feed)(self.state, keysym) != COMPOSE_FEED_ACCEPTED {
+                return Compose::Nothing;
+            }
+            match (self.compose.state_get_status)(self.state) {
+                COMPOSE_COMPOSING => {
+                    self.pending.push(keysym);
+                    //Compose itself types nothing, but shows that a sequence has started
+                    let shown = self.text(&self.pending);
+                    let compose_keys = self.pending.iter().filter(|k| **k == MULTI_KEY).count();
+                    Compose::Composing("·".repeat(compose_keys) + &shown)
+                }
+                COMPOSE_COMPOSED => {
+                    self.pending.clear();
+                    let mut buffer = [0 as c_char; 64];
+                    (self.compose.state_get_utf8)(self.state, buffer.as_mut_ptr(), buffer.len());
+                    let text = CStr::from_ptr(buffer.as_ptr())
+                        .to_string_lossy()
+                        .into_owned();
+                    Compose::Composed(text)
+                }
+                COMPOSE_CANCELLED => {
+                    let pending = std::mem::take(&mut self.pending);
+                    let key = self.text(&[keysym]);
+                    if key.is_empty() {
+                        Compose::Cancelled(key)
+                    } else {
+                        Compose::Cancelled(self.text(&pending) + &key)
+                    }
+                }
+                _ => Compose::Nothing,
+            }
+        }
+    }
+
+    /// Abandons any sequence, for when the window loses keyboard focus.
+    pub(super) fn reset(&mut self) {
+        self.pending.clear();
+        unsafe { (self.compose.state_reset)(self.state) };
+    }
+}
+
+impl Drop for Composer {
+    fn drop(&mut self) {
+        unsafe {
+            (self.compose.state_unref)(self.state);
+            (self.compose.table_unref)(self.table);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::input::keyboard::Shared;
-use crate::input::keyboard::key::KeyboardKey;
+use crate::input::keyboard::key::{KeyModifiers, KeyboardKey};
 use std::ffi::c_void;
 use std::sync::Arc;
 use std::sync::Weak;
@@ -34,10 +34,32 @@ unsafe extern "C" {
     fn PlatformCoalescedKeyboardNew(context: *const c_void) -> *mut c_void;
     fn PlatformCoalescedKeyboardFree(imp: *mut c_void);
 
+    fn PlatformHardwareKeyboardPresentMain() -> bool;
+    fn PlatformKeyCharacterMain(key_code: u16, shift: bool, option: bool) -> u32;
+
     fn SwiftRawInputDebugWindowShow();
     fn SwiftRawInputDebugWindowHide();
 }
 
+pub(super) async fn hardware_keyboard_present() -> Option<bool> {
+    let present =
+        crate::application::on_main_thread("hardware_keyboard_present".to_string(), || unsafe {
+            PlatformHardwareKeyboardPresentMain()
+        })
+        .await;
+    Some(present)
+}
+
+pub(super) async fn key_character(key: KeyboardKey, modifiers: KeyModifiers) -> Option<char> {
+    let key_code = (0..0x80).find(|code| KeyboardKey::from_code(*code) == Some(key))?;
+    let scalar = crate::application::on_main_thread("key_character".to_string(), move || unsafe {
+        PlatformKeyCharacterMain(key_code, modifiers.has_shift(), modifiers.has_alt_graph())
+    })
+    .await;
+    //0 for nothing typed, which is a control character
+    char::from_u32(scalar)
+}
+
 /// Shows the debug window for raw keyboard input on macOS.
 ///
 /// This function displays a debug window that shows real-time information about
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Keyboard events reported by an out-of-tree backend via [`crate::backend::report_key`].
+
+use crate::input::keyboard::Shared;
+use crate::input::keyboard::key::{KeyModifiers, KeyboardKey};
+use std::sync::{Arc, Mutex, Weak};
+
+static KEYBOARDS: Mutex<Vec<Weak<Shared>>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+pub(super) struct PlatformCoalescedKeyboard {}
+
+impl PlatformCoalescedKeyboard {
+    pub async fn new(shared: &Arc<Shared>) -> Self {
+        KEYBOARDS.lock().unwrap().push(Arc::downgrade(shared));
+        PlatformCoalescedKeyboard {}
+    }
+}
+
+pub(crate) fn report_key(key: KeyboardKey, down: bool) {
+    KEYBOARDS
+        .lock()
+        .unwrap()
+        .retain(|weak| match weak.upgrade() {
+            Some(shared) => {
+                shared.set_key_state(key, down, std::ptr::null_mut());
+                true
+            }
+            None => false,
+        });
+}
+
+pub(super) async fn hardware_keyboard_present() -> Option<bool> {
+    None
+}
+
+pub(super) async fn key_character(_key: KeyboardKey, _modifiers: KeyModifiers) -> Option<char> {
+    None
+}
+
+pub fn debug_window_show() {}
+
+pub fn debug_window_hide() {}
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Key repeat that app_window synthesizes itself.
+//!
+//! Wayland leaves key repeat to clients, and [`KeyRepeat::Custom`](super::KeyRepeat::Custom)
+//! replaces the platform's timing everywhere, so in those cases a thread per keyboard repeats
+//! the most recently pressed key while it's held.  As on the platforms, pressing another key
+//! moves the repeat to it, and modifiers don't repeat.  On Wayland the compositor's keymap also
+//! says which keys repeat, and we go along with it.
+
+use super::key::{KeySet, KeyboardKey};
+use super::{KeyState, Shared};
+use crate::application::time::Instant;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+#[cfg(target_arch = "wasm32")]
+use wasm_safe_thread as thread;
+
+/// Keys that are held for their effect on other keys, not to type, so aren't repeated.
+const NOT_REPEATED: KeySet = KeySet::of(&[
+    KeyboardKey::Shift,
+    KeyboardKey::RightShift,
+    KeyboardKey::Control,
+    KeyboardKey::RightControl,
+    KeyboardKey::Option,
+    KeyboardKey::RightOption,
+    KeyboardKey::Command,
+    KeyboardKey::RightCommand,
+    KeyboardKey::Function,
+    KeyboardKey::CapsLock,
+    KeyboardKey::NumLock,
+]);
+
+/// Whether the platform lets `key` repeat; only Wayland's keymap has a say.
+fn platform_repeats(key: KeyboardKey) -> bool {
+    #[cfg(target_os = "linux")]
+    return super::linux::key_repeats(key).unwrap_or(true);
+    #[cfg(not(target_os = "linux"))]
+    {
+        _ = key;
+        true
+    }
+}
+
+/// The shortest interval between repeats, so a zero interval doesn't spin.
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The key being repeated.
+#[derive(Debug)]
+struct Held {
+    key: KeyboardKey,
+    /// The platform window pointer, as an address so the thread can have it.
+    window_ptr: usize,
+    interval: Duration,
+    next: Instant,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Repeater {
+    /// Whether the thread is running, so presses are free until it is.
+    running: AtomicBool,
+    held: Mutex<Option<Held>>,
+    thread: OnceLock<thread::Thread>,
+}
+
+impl Repeater {
+    /// Starts repeating keys for `shared`, if it isn't already.
+    pub(super) fn start(shared: &Arc<Shared>) {
+        let weak = Arc::downgrade(shared);
+        shared.repeater.thread.get_or_init(|| {
+            thread::Builder::new()
+                .name("app_window key repeat".to_string())
+                .spawn(move || run(weak))
+                .expect("Can't start key repeat thread")
+                .thread()
+                .clone()
+        });
+        shared.repeater.running.store(true, Ordering::Relaxed);
+    }
+
+    pub(super) fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Repeats `key` from `delay` after `now`, every `interval`, or stops repeating if `timing`
+    /// is `None`.
+    pub(super) fn press(
+        &self,
+        key: KeyboardKey,
+        window_ptr: *mut c_void,
+        timing: Option<(Duration, Duration)>,
+        now: Instant,
+    ) {
+        if NOT_REPEATED.contains(key) || !platform_repeats(key) {
+            return;
+        }
+        *self.held.lock().unwrap() = timing.map(|(delay, interval)| Held {
+            key,
+            window_ptr: window_ptr as usize,
+            interval: interval.max(MIN_INTERVAL),
+            next: now + delay,
+        });
+        self.wake();
+    }
+
+    /// Stops repeating `key`, if it's the one repeating.
+    pub(super) fn release(&self, key: KeyboardKey) {
+        let mut held = self.held.lock().unwrap();
+        if held.as_ref().is_some_and(|held| held.key == key) {
+            *held = None;
+        }
+    }
+
+    /// Stops repeating whatever is repeating.
+    pub(super) fn stop(&self) {
+        *self.held.lock().unwrap() = None;
+    }
+
+    pub(super) fn wake(&self) {
+        if let Some(thread) = self.thread.get() {
+            thread.unpark();
+        }
+    }
+
+    /// Sends a repeat if one is due at `now`, and returns how long until the next.
+    fn tick(&self, shared: &Shared, now: Instant) -> Option<Duration> {
+        let mut held = self.held.lock().unwrap();
+        let held = held.as_mut()?;
+        if now >= held.next {
+            //sent under the lock, so a release can't slip in before it
+            shared.deliver(held.key, KeyState::Repeated, held.window_ptr as *mut c_void);
+            held.next += held.interval;
+            //after a stall, carry on from now instead of catching up
+            if held.next <= now {
+                held.next = now + held.interval;
+            }
+        }
+        Some(held.next.duration_since(now))
+    }
+}
+
+fn run(shared: Weak<Shared>) {
+    loop {
+        //dropping the keyboard wakes us, and this ends the thread
+        let Some(strong) = shared.upgrade() else {
+            return;
+        };
+        let wait = strong.repeater.tick(&strong, Instant::now());
+        drop(strong);
+        match wait {
+            Some(wait) => thread::park_timeout(wait),
+            None => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::application::time::Instant;
+    use crate::input::keyboard::key::KeyboardKey;
+    use crate::input::keyboard::{KeyRepeat, KeyState, Shared};
+    use std::time::Duration;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn custom_repeat_replaces_platform_repeat() {
+        let shared = Shared::new();
+        shared.set_repeat(KeyRepeat::Custom {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(50),
+        });
+        let mut events = shared.events();
+        let start = Instant::now();
+        let timing = shared.synthesized_timing();
+        shared.set_key_state(KeyboardKey::A, true, std::ptr::null_mut());
+        //the platform's own repeat is dropped
+        shared.set_key_state(KeyboardKey::A, true, std::ptr::null_mut());
+        shared
+            .repeater
+            .press(KeyboardKey::A, std::ptr::null_mut(), timing, start);
+        let tick = |ms| {
+            shared
+                .repeater
+                .tick(&shared, start + Duration::from_millis(ms))
+        };
+        assert_eq!(tick(100), Some(Duration::from_millis(400)));
+        assert_eq!(tick(500), Some(Duration::from_millis(50)));
+        assert_eq!(tick(550), Some(Duration::from_millis(50)));
+        shared.set_key_state(KeyboardKey::A, false, std::ptr::null_mut());
+        shared.repeater.release(KeyboardKey::A);
+        assert_eq!(tick(600), None);
+
+        let states: Vec<_> = std::iter::from_fn(|| events.try_next())
+            .map(|e| e.state())
+            .collect();
+        assert_eq!(
+            states,
+            [
+                KeyState::Pressed,
+                KeyState::Repeated,
+                KeyState::Repeated,
+                KeyState::Released,
+            ]
+        );
+    }
+}
@@ -1,16 +1,121 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::input::keyboard::Shared;
-use crate::input::keyboard::key::KeyboardKey;
+use crate::input::keyboard::key::{KeyModifiers, KeyboardKey};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ffi::c_void;
 use std::sync::Arc;
 use wasm_bindgen::prelude::*;
-use web_sys::KeyboardEvent;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_futures::js_sys::{Array, Promise};
+use web_sys::{Event, HtmlCanvasElement, KeyboardEvent};
 
 #[derive(Debug)]
 pub(super) struct PlatformCoalescedKeyboard {}
 
+/// Events we can't place in a canvas go to the first window, whose id is 1.
 pub(crate) const ARBITRARY_WINDOW_PTR: *mut c_void = std::ptr::dangling_mut::<c_void>();
 
+thread_local! {
+    /// The canvas last pressed in, which gets the keys.
+    static KEY_WINDOW: Cell<*mut c_void> = const { Cell::new(ARBITRARY_WINDOW_PTR) };
+    /// What keys have typed, from `KeyboardEvent.key`.
+    static TYPED: RefCell<HashMap<(KeyboardKey, KeyModifiers), char>> =
+        RefCell::new(HashMap::new());
+}
+
+#[wasm_bindgen(inline_js = r#"
+export function keyboardLayoutMap() {
+  if (typeof navigator === 'undefined' || !navigator.keyboard || !navigator.keyboard.getLayoutMap) {
+    return null;
+  }
+  return navigator.keyboard.getLayoutMap().then((map) => Array.from(map.entries()));
+}
+"#)]
+extern "C" {
+    /// Resolves with `[code, key]` pairs for the unmodified layout, or `None` where the browser
+    /// can't say (Firefox and Safari).
+    #[wasm_bindgen(js_name = keyboardLayoutMap)]
+    fn keyboard_layout_map() -> Option<Promise>;
+}
+
+/// Remembers what a keydown typed.  Call on the main thread.
+fn record_typed(key: KeyboardKey, event: &KeyboardEvent) {
+    //shortcuts may not say what the key types on its own
+    if event.ctrl_key() || event.meta_key() {
+        return;
+    }
+    let text = event.key();
+    let mut chars = text.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        //"Enter", "Dead" and so on
+        return;
+    };
+    let modifiers = KeyModifiers::new()
+        .shift(event.shift_key())
+        .alt_graph(event.get_modifier_state("AltGraph") || event.alt_key());
+    TYPED.with_borrow_mut(|typed| typed.insert((key, modifiers), c));
+}
+
+pub(super) async fn key_character(key: KeyboardKey, modifiers: KeyModifiers) -> Option<char> {
+    let (sender, fut) = r#continue::continuation();
+    crate::application::on_main_thread("key_character".to_string(), move || {
+        if let Some(c) = TYPED.with_borrow(|typed| typed.get(&(key, modifiers)).copied()) {
+            sender.send(Some(c));
+            return;
+        }
+        //the layout map only knows the unmodified layer
+        let promise = (modifiers == KeyModifiers::new())
+            .then(keyboard_layout_map)
+            .flatten();
+        let Some(promise) = promise else {
+            sender.send(None);
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let found = JsFuture::from(promise).await.ok().and_then(|entries| {
+                Array::from(&entries).iter().find_map(|entry| {
+                    let entry = Array::from(&entry);
+                    let code = entry.get(0).as_string()?;
+                    if KeyboardKey::from_js_code(&code) != Some(key) {
+                        return None;
+                    }
+                    let text = entry.get(1).as_string()?;
+                    let mut chars = text.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => Some(c),
+                        _ => None,
+                    }
+                })
+            });
+            sender.send(found);
+        });
+    })
+    .await;
+    fut.await
+}
+
+/// The canvas `event` went to, if it's one of our windows.
+pub(crate) fn target_canvas(event: &Event) -> Option<HtmlCanvasElement> {
+    let canvas = event.target()?.dyn_into::<HtmlCanvasElement>().ok()?;
+    canvas.has_attribute("data-raw-handle").then_some(canvas)
+}
+
+/// The window `event` went to, as the sys window's `input_handle` reports it.  Call on the main
+/// thread.
+pub(crate) fn target_window(event: &Event) -> *mut c_void {
+    target_canvas(event)
+        .and_then(|canvas| canvas.get_attribute("data-raw-handle"))
+        .and_then(|id| id.parse::<usize>().ok())
+        .filter(|id| *id != 0)
+        .map_or(ARBITRARY_WINDOW_PTR, |id| id as *mut c_void)
+}
+
+/// Keys go to `window` from now on.  Call on the main thread.
+pub(crate) fn set_key_window(window: *mut c_void) {
+    KEY_WINDOW.set(window);
+}
+
 impl PlatformCoalescedKeyboard {
     pub async fn new(shared: &Arc<Shared>) -> Self {
         let shared = shared.clone();
@@ -20,17 +125,25 @@ impl PlatformCoalescedKeyboard {
             move || {
                 let weak = Arc::downgrade(&shared);
                 let weak_up = weak.clone();
-                let window = web_sys::window().expect("no global window exists");
+                //without a DOM (say, under Node) there are no keys; conformance hooks can inject them
+                let Some(window) = web_sys::window() else {
+                    return PlatformCoalescedKeyboard {};
+                };
                 let document = window.document().expect("no document on window");
                 let keydown_callback = Closure::wrap(Box::new(move |event: KeyboardEvent| {
                     let key = event.key();
                     let code = event.code();
+                    //on-screen keyboards don't say which key, only what it typed
+                    if code.is_empty() {
+                        return;
+                    }
 
                     if let Some(shared) = weak.upgrade() {
                         let key = KeyboardKey::from_js_code(&code)
                             .unwrap_or_else(|| panic!("Unknown key: {}", key));
+                        record_typed(key, &event);
 
-                        shared.set_key_state(key, true, ARBITRARY_WINDOW_PTR);
+                        shared.set_key_state(key, true, KEY_WINDOW.get());
                     }
                 })
                     as Box<dyn FnMut(KeyboardEvent)>);
@@ -45,10 +158,14 @@ impl PlatformCoalescedKeyboard {
                 let keyup_callback = Closure::wrap(Box::new(move |event: KeyboardEvent| {
                     let key = event.key();
                     let code = event.code();
+                    //on-screen keyboards don't say which key, only what it typed
+                    if code.is_empty() {
+                        return;
+                    }
                     if let Some(shared) = weak_up.upgrade() {
                         let key = KeyboardKey::from_js_code(&code)
                             .unwrap_or_else(|| panic!("Unknown key: {}", key));
-                        shared.set_key_state(key, false, ARBITRARY_WINDOW_PTR);
+                        shared.set_key_state(key, false, KEY_WINDOW.get());
                     }
                 })
                     as Box<dyn FnMut(KeyboardEvent)>);
@@ -67,6 +184,11 @@ impl PlatformCoalescedKeyboard {
     }
 }
 
+//browsers don't say
+pub(super) async fn hardware_keyboard_present() -> Option<bool> {
+    None
+}
+
 pub fn debug_window_show() {
     //nothing?
 }
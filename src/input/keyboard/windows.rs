@@ -1,27 +1,29 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::input::keyboard::Shared;
-use crate::input::keyboard::key::KeyboardKey;
+use crate::input::keyboard::key::{KeyModifiers, KeyboardKey};
 use std::ffi::c_void;
 use std::sync::{Arc, Mutex, OnceLock, Weak};
 use windows::Win32::Foundation::{GetLastError, HWND, LPARAM, LRESULT, WPARAM};
 use windows::Win32::Graphics::Gdi::{COLOR_WINDOW, HBRUSH};
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    VK_ADD, VK_APPS, VK_BACK, VK_BROWSER_BACK, VK_BROWSER_FAVORITES, VK_BROWSER_FORWARD,
-    VK_BROWSER_HOME, VK_BROWSER_REFRESH, VK_BROWSER_SEARCH, VK_BROWSER_STOP, VK_CAPITAL, VK_CLEAR,
-    VK_CONTROL, VK_CONVERT, VK_DECIMAL, VK_DELETE, VK_DIVIDE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1,
-    VK_F2, VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8, VK_F9, VK_F10, VK_F11, VK_F12, VK_F13, VK_F14,
-    VK_F15, VK_F16, VK_F17, VK_F18, VK_F19, VK_F20, VK_F21, VK_F22, VK_F23, VK_F24, VK_HELP,
-    VK_HOME, VK_INSERT, VK_KANA, VK_LAUNCH_APP1, VK_LAUNCH_APP2, VK_LAUNCH_MAIL, VK_LCONTROL,
-    VK_LEFT, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MEDIA_NEXT_TRACK, VK_MEDIA_PLAY_PAUSE,
-    VK_MEDIA_PREV_TRACK, VK_MEDIA_STOP, VK_MENU, VK_MULTIPLY, VK_NEXT, VK_NONCONVERT, VK_NUMLOCK,
-    VK_NUMPAD0, VK_NUMPAD1, VK_NUMPAD2, VK_NUMPAD3, VK_NUMPAD4, VK_NUMPAD5, VK_NUMPAD6, VK_NUMPAD7,
-    VK_NUMPAD8, VK_NUMPAD9, VK_OEM_1, VK_OEM_2, VK_OEM_3, VK_OEM_4, VK_OEM_5, VK_OEM_6, VK_OEM_7,
-    VK_OEM_102, VK_OEM_COMMA, VK_OEM_MINUS, VK_OEM_PERIOD, VK_OEM_PLUS, VK_PAUSE, VK_PLAY,
-    VK_PRINT, VK_PRIOR, VK_RCONTROL, VK_RETURN, VK_RIGHT, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SCROLL,
-    VK_SELECT, VK_SEPARATOR, VK_SHIFT, VK_SNAPSHOT, VK_SPACE, VK_SUBTRACT, VK_TAB, VK_UP,
-    VK_VOLUME_DOWN, VK_VOLUME_MUTE, VK_VOLUME_UP,
+    GetKeyboardLayout, MAPVK_VK_TO_VSC, MapVirtualKeyExW, ToUnicodeEx, VK_ADD, VK_APPS, VK_BACK,
+    VK_BROWSER_BACK, VK_BROWSER_FAVORITES, VK_BROWSER_FORWARD, VK_BROWSER_HOME, VK_BROWSER_REFRESH,
+    VK_BROWSER_SEARCH, VK_BROWSER_STOP, VK_CAPITAL, VK_CLEAR, VK_CONTROL, VK_CONVERT, VK_DECIMAL,
+    VK_DELETE, VK_DIVIDE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_F2, VK_F3, VK_F4, VK_F5, VK_F6,
+    VK_F7, VK_F8, VK_F9, VK_F10, VK_F11, VK_F12, VK_F13, VK_F14, VK_F15, VK_F16, VK_F17, VK_F18,
+    VK_F19, VK_F20, VK_F21, VK_F22, VK_F23, VK_F24, VK_HELP, VK_HOME, VK_INSERT, VK_KANA,
+    VK_LAUNCH_APP1, VK_LAUNCH_APP2, VK_LAUNCH_MAIL, VK_LCONTROL, VK_LEFT, VK_LMENU, VK_LSHIFT,
+    VK_LWIN, VK_MEDIA_NEXT_TRACK, VK_MEDIA_PLAY_PAUSE, VK_MEDIA_PREV_TRACK, VK_MEDIA_STOP, VK_MENU,
+    VK_MULTIPLY, VK_NEXT, VK_NONCONVERT, VK_NUMLOCK, VK_NUMPAD0, VK_NUMPAD1, VK_NUMPAD2,
+    VK_NUMPAD3, VK_NUMPAD4, VK_NUMPAD5, VK_NUMPAD6, VK_NUMPAD7, VK_NUMPAD8, VK_NUMPAD9, VK_OEM_1,
+    VK_OEM_2, VK_OEM_3, VK_OEM_4, VK_OEM_5, VK_OEM_6, VK_OEM_7, VK_OEM_102, VK_OEM_COMMA,
+    VK_OEM_MINUS, VK_OEM_PERIOD, VK_OEM_PLUS, VK_PAUSE, VK_PLAY, VK_PRINT, VK_PRIOR, VK_RCONTROL,
+    VK_RETURN, VK_RIGHT, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SCROLL, VK_SELECT, VK_SEPARATOR,
+    VK_SHIFT, VK_SNAPSHOT, VK_SPACE, VK_SUBTRACT, VK_TAB, VK_UP, VK_VOLUME_DOWN, VK_VOLUME_MUTE,
+    VK_VOLUME_UP,
 };
+use windows::Win32::UI::Input::{GetRawInputDeviceList, RAWINPUTDEVICELIST, RIM_TYPEKEYBOARD};
 use windows::Win32::UI::WindowsAndMessaging::{
     CW_USEDEFAULT, CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, IDC_ARROW,
     LoadCursorW, MSG, RegisterClassExW, SW_SHOWNORMAL, ShowWindow, TranslateMessage,
@@ -71,6 +73,59 @@ impl PlatformCoalescedKeyboard {
     }
 }
 
+pub(super) async fn hardware_keyboard_present() -> Option<bool> {
+    let size = std::mem::size_of::<RAWINPUTDEVICELIST>() as u32;
+    let mut count = 0;
+    if unsafe { GetRawInputDeviceList(None, &mut count, size) } == u32::MAX {
+        return None;
+    }
+    let mut devices = vec![RAWINPUTDEVICELIST::default(); count as usize];
+    let read = unsafe { GetRawInputDeviceList(Some(devices.as_mut_ptr()), &mut count, size) };
+    //a device may have arrived in between, and then it's an error
+    if read == u32::MAX {
+        return None;
+    }
+    devices.truncate(read as usize);
+    Some(
+        devices
+            .iter()
+            .any(|device| device.dwType == RIM_TYPEKEYBOARD),
+    )
+}
+
+pub(super) async fn key_character(key: KeyboardKey, modifiers: KeyModifiers) -> Option<char> {
+    //keys are reported by virtual key, which the layout already chose, so ask about that one
+    let vk = (0..256).find(|vk| KeyboardKey::from_vk(*vk) == Some(key))? as u32;
+    crate::application::on_main_thread("key_character".to_string(), move || {
+        //the main thread's, which is the layout our windows type with
+        let layout = unsafe { GetKeyboardLayout(0) };
+        let scan_code = unsafe { MapVirtualKeyExW(vk, MAPVK_VK_TO_VSC, Some(layout)) };
+        let mut state = [0u8; 256];
+        if modifiers.has_shift() {
+            state[VK_SHIFT.0 as usize] = 0x80;
+        }
+        //AltGr is Control and Alt together
+        if modifiers.has_alt_graph() {
+            state[VK_CONTROL.0 as usize] = 0x80;
+            state[VK_MENU.0 as usize] = 0x80;
+        }
+        let mut buffer = [0u16; 4];
+        //4 leaves the keyboard state alone, so a dead key isn't left pending for the user
+        let written = unsafe { ToUnicodeEx(vk, scan_code, &state, &mut buffer, 4, Some(layout)) };
+        //negative for a dead key, which writes its own character
+        let length = match written {
+            -1 => 1,
+            n => usize::try_from(n).ok()?,
+        };
+        let mut chars = char::decode_utf16(buffer[..length].iter().copied());
+        match (chars.next(), chars.next()) {
+            (Some(Ok(c)), None) => Some(c),
+            _ => None,
+        }
+    })
+    .await
+}
+
 /**
 Processes window key events.
 
@@ -18,7 +18,7 @@ Input handling functionality for app_window - a cross-platform library for recei
 * Keyboard events:
    * Report raw up/down events whenever possible
         * We map hardware keys rather than unicode characters
-        * If you are trying to implement text input, you have much work to do, including but not limited to the shift key
+        * For text input, use [`crate::input::text::TextInput`] instead, which handles layouts and input methods
    * Keycodes are translated into a platform-independent enum that works everywhere
    * On Linux, key events are broadcasted over ATSPI.  Due to some [questionable decisions in the Linux ecosystem](https://github.com/AccessKit/accesskit/discussions/503#discussioncomment-11862133)
      this is required for screenreaders to work but nobody does it.  We do!
@@ -29,6 +29,7 @@ Input handling functionality for app_window - a cross-platform library for recei
 |----------|--------------------------|
 | Windows  | win32*                   |
 | macOS    | AppKit                   |
+| iOS      | UIKit (touch, keys)      |
 | Linux    | Wayland*                 |
 | wasm32   | KeyboardEvent \| MouseEvent  |
 | Yours    | Send a PR!               |
@@ -42,10 +43,14 @@ This library is also available as an npm package for JavaScript/TypeScript proje
 The package provides type definitions and can be used in web applications.
 
 */
+///Batches keyboard and mouse input once per frame.
+pub mod frame;
 ///Provides information about keyboard events.
 pub mod keyboard;
 ///Provides information about mouse events.
 pub mod mouse;
+///Provides text entry, including input method composition.
+pub mod text;
 
 /// Shows a debug window for testing keyboard input
 ///
@@ -65,17 +70,36 @@ Provides information about the window an event was delivered to.
 # Platform specifics
 * On Windows, this value contains an HWND.
 * on macOS, this is the pointer of an NSWindow.  No memory management is performed, so dereferencing the window may be invalid.
+* on iOS, this is the pointer of a UIWindow, with the same caveats as macOS.
 * on wasm32, we attach to the global DOM window, and we choose an opaque value arbitrarily for this type.
 * on Linux, we return the wayland surface ID.  No memory management is performed, so values may refer to previous surfaces, etc.
+* on Android, this is the activity's `ANativeWindow` pointer.  No memory management is performed, so the window may since have been destroyed.
 */
 #[derive(Debug, Copy, Clone)]
 pub struct Window(pub std::ptr::NonNull<std::ffi::c_void>);
 //we don't do anything with it so it's fine to send
 unsafe impl Send for Window {}
 
+impl Window {
+    /// The [`crate::window::Window`] this is, if the app created it and it's still open.
+    ///
+    /// Compare against [`crate::window::Window::id`] to route input with several windows open.
+    pub fn id(&self) -> Option<crate::window::WindowId> {
+        crate::window::id_for_handle(self.0.as_ptr() as usize)
+    }
+}
+
+/// The window for a handle the platform reported, which may be null.
+fn window_id(window: *mut std::ffi::c_void) -> Option<crate::window::WindowId> {
+    std::ptr::NonNull::new(window).and_then(|window| Window(window).id())
+}
+
 #[cfg(target_os = "linux")]
 pub mod linux {
-    pub use crate::input::keyboard::linux::wl_keyboard_event;
+    pub use crate::input::keyboard::linux::{
+        wl_keyboard_event, wl_keyboard_keymap_event, wl_keyboard_leave_event,
+        wl_keyboard_modifiers_event, wl_keyboard_repeat_info_event, wl_seat_capabilities_event,
+    };
     pub use crate::input::mouse::linux::{
         button_event, motion_event, xdg_toplevel_configure_event,
     };
@@ -86,6 +110,8 @@ use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
 #[cfg(target_os = "windows")]
 pub fn window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
     if mouse::windows::window_proc(hwnd, msg, w_param, l_param) == LRESULT(0)
+        //text watches key presses for dead keys, but leaves them to the keyboard
+        || text::windows::text_window_proc(hwnd, msg, w_param, l_param) == LRESULT(0)
         || keyboard::windows::kbd_window_proc(hwnd, msg, w_param, l_param) == LRESULT(0)
     {
         LRESULT(0)
@@ -1,5 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub(crate) mod macos;
 #[cfg(target_arch = "wasm32")]
 pub(crate) mod wasm;
@@ -10,7 +10,13 @@ pub(crate) mod windows;
 #[cfg(target_os = "linux")]
 pub(crate) mod linux;
 
-#[cfg(target_os = "macos")]
+#[cfg(target_os = "android")]
+pub(crate) mod android;
+
+#[cfg(plugin_backend)]
+pub(crate) mod plugin;
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub(crate) use macos as sys;
 use std::ffi::c_void;
 use std::hash::{Hash, Hasher};
@@ -24,11 +30,24 @@ pub(crate) use windows as sys;
 #[cfg(target_os = "linux")]
 pub(crate) use linux as sys;
 
+#[cfg(target_os = "android")]
+pub(crate) use android as sys;
+
+#[cfg(plugin_backend)]
+pub(crate) use plugin as sys;
+
 use crate::application::is_main_thread_running;
+use crate::application::time::{Duration, Instant};
+use crate::coordinates::Rect;
 use crate::input::Window;
+use crate::input::frame::{FrameEdge, FrameListeners};
+use crate::window::{WindowId, WindowSettingError};
 use atomic_float::AtomicF64;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::collections::VecDeque;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::task::{Poll, Waker};
 
 /// Mouse button constant for the left mouse button.
 ///
@@ -72,11 +91,256 @@ pub const MOUSE_BUTTON_RIGHT: u8 = 1;
 /// ```
 pub const MOUSE_BUTTON_MIDDLE: u8 = 2;
 
+/// Mouse button constant for the "back" side button, usually under the thumb.
+///
+/// Reported for `XBUTTON1` on Windows, `BTN_SIDE` or `BTN_BACK` on Linux, button number 3 on
+/// macOS and in browsers, and `AMOTION_EVENT_BUTTON_BACK` on Android.
+///
+/// # Examples
+///
+/// ```
+/// # async fn example() {
+/// use app_window::input::mouse::{Mouse, MOUSE_BUTTON_BACK};
+///
+/// let mouse = Mouse::coalesced().await;
+/// let back_pressed = mouse.button_state(MOUSE_BUTTON_BACK);
+/// # }
+/// ```
+pub const MOUSE_BUTTON_BACK: u8 = 3;
+
+/// Mouse button constant for the "forward" side button.
+///
+/// Reported for `XBUTTON2` on Windows, `BTN_EXTRA` or `BTN_FORWARD` on Linux, button number 4
+/// on macOS and in browsers, and `AMOTION_EVENT_BUTTON_FORWARD` on Android.
+pub const MOUSE_BUTTON_FORWARD: u8 = 4;
+
+/// Most events a [`ButtonEvents`] or [`ScrollEvents`] holds before dropping the oldest.
+const MOUSE_EVENT_CAPACITY: usize = 256;
+
+/// How far one line of scrolling moves, in logical pixels, where the platform reports
+/// scrolling in lines or wheel notches.  See [`Mouse::consume_scroll_delta`].
+pub const SCROLL_LINE_PIXELS: f64 = 20.0;
+
+/// Lines per wheel notch, where the platform has no setting for it.  This is the usual default
+/// on Windows and in browsers.
+#[cfg_attr(not(target_os = "android"), allow(dead_code))]
+const SCROLL_LINES_PER_NOTCH: f64 = 3.0;
+
+/// Most positions [`Mouse::recent_motion`] remembers.
+pub const MOTION_HISTORY_LEN: usize = 32;
+
+/// The default for [`Mouse::set_double_click_interval`], which is also Windows' default.
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How far, in logical pixels along either axis, the pointer can move between presses that
+/// still count as one multi-click, where the platform doesn't say.
+pub const DOUBLE_CLICK_DISTANCE: f64 = 4.0;
+
+/// A mouse button being pressed or released; see [`Mouse::button_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ButtonEvent {
+    button: u8,
+    down: bool,
+    clicks: u32,
+    window: Option<WindowId>,
+}
+
+impl ButtonEvent {
+    /// The button, such as [`MOUSE_BUTTON_LEFT`] or [`MOUSE_BUTTON_BACK`].  Buttons past
+    /// [`MOUSE_BUTTON_FORWARD`] are numbered in the order the platform reports them.
+    pub fn button(&self) -> u8 {
+        self.button
+    }
+
+    /// Whether the button was pressed, rather than released.
+    pub fn down(&self) -> bool {
+        self.down
+    }
+
+    /// Which click of a multi-click this is: 1 for a single click, 2 for the second press of
+    /// a double-click, and so on.  A release repeats its press's count.  See
+    /// [`Mouse::last_click_count`].
+    pub fn click_count(&self) -> u32 {
+        self.clicks
+    }
+
+    /// The window under the pointer, if the platform said and the app created it.  See
+    /// [`Window::id`](crate::window::Window::id).
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window
+    }
+}
+
+/// A scroll from a wheel or touchpad; see [`Mouse::scroll_events`].
+///
+/// Distances are in logical pixels, normalized as described in [`Mouse::consume_scroll_delta`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollEvent {
+    delta_x: f64,
+    delta_y: f64,
+    window: Option<WindowId>,
+}
+
+impl ScrollEvent {
+    /// How far to scroll horizontally.  Positive moves toward the right of the document.
+    pub fn delta_x(&self) -> f64 {
+        self.delta_x
+    }
+
+    /// How far to scroll vertically.  Positive moves toward the bottom of the document.
+    pub fn delta_y(&self) -> f64 {
+        self.delta_y
+    }
+
+    /// The window under the pointer, if the platform said and the app created it.  See
+    /// [`Window::id`](crate::window::Window::id).
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window
+    }
+}
+
+#[derive(Debug)]
+struct EventQueue<T> {
+    events: VecDeque<T>,
+    waker: Option<Waker>,
+}
+
+impl<T> EventQueue<T> {
+    async fn next(queue: &Mutex<Self>) -> T {
+        std::future::poll_fn(|cx| {
+            let mut queue = queue.lock().unwrap();
+            match queue.events.pop_front() {
+                Some(event) => Poll::Ready(event),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+/// The streams listening for one kind of mouse event.
+///
+/// Like frame listeners, this runs once per platform event, so it costs one relaxed load until
+/// someone listens.
+#[derive(Debug)]
+struct EventListeners<T> {
+    active: AtomicBool,
+    queues: Mutex<Vec<Weak<Mutex<EventQueue<T>>>>>,
+}
+
+impl<T: Copy> EventListeners<T> {
+    fn new() -> Self {
+        EventListeners {
+            active: AtomicBool::new(false),
+            queues: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[inline]
+    fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    #[cold]
+    fn send(&self, event: T) {
+        let mut queues = self.queues.lock().unwrap();
+        queues.retain(|listener| {
+            let Some(queue) = listener.upgrade() else {
+                return false;
+            };
+            let mut queue = queue.lock().unwrap();
+            if queue.events.len() == MOUSE_EVENT_CAPACITY {
+                queue.events.pop_front();
+            }
+            queue.events.push_back(event);
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+            true
+        });
+        if queues.is_empty() {
+            self.active.store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn subscribe(&self) -> Arc<Mutex<EventQueue<T>>> {
+        let queue = Arc::new(Mutex::new(EventQueue {
+            events: VecDeque::new(),
+            waker: None,
+        }));
+        self.queues.lock().unwrap().push(Arc::downgrade(&queue));
+        self.active.store(true, Ordering::Relaxed);
+        queue
+    }
+}
+
+/// Every button press and release on a [`Mouse`], in order.
+///
+/// [`Mouse::button_state`] only says whether a button is down right now, so a click shorter
+/// than a frame can be missed entirely.  This keeps each transition.  If the events aren't
+/// read, the oldest are dropped once 256 are waiting.
+///
+/// Created by [`Mouse::button_events`].
+#[derive(Debug)]
+pub struct ButtonEvents {
+    queue: Arc<Mutex<EventQueue<ButtonEvent>>>,
+}
+
+impl ButtonEvents {
+    /// Waits for the next button event.
+    pub async fn next(&mut self) -> ButtonEvent {
+        EventQueue::next(&self.queue).await
+    }
+
+    /// Returns the next button event, if one is waiting.
+    pub fn try_next(&mut self) -> Option<ButtonEvent> {
+        self.queue.lock().unwrap().events.pop_front()
+    }
+}
+
+/// Every scroll on a [`Mouse`], in order.
+///
+/// If the events aren't read, the oldest are dropped once 256 are waiting.
+///
+/// Created by [`Mouse::scroll_events`].
+#[derive(Debug)]
+pub struct ScrollEvents {
+    queue: Arc<Mutex<EventQueue<ScrollEvent>>>,
+}
+
+impl ScrollEvents {
+    /// Waits for the next scroll event.
+    pub async fn next(&mut self) -> ScrollEvent {
+        EventQueue::next(&self.queue).await
+    }
+
+    /// Returns the next scroll event, if one is waiting.
+    pub fn try_next(&mut self) -> Option<ScrollEvent> {
+        self.queue.lock().unwrap().events.pop_front()
+    }
+}
+
 /// Mouse's location within a window, in points.
 ///
 /// The coordinate system has its origin at the upper-left corner of the window.
 /// The position is reported in logical points, not physical pixels.
 ///
+/// # Drags
+///
+/// While a button is held, the window it went down in captures the mouse: positions keep
+/// coming relative to that window even once the cursor leaves it, until the last button is
+/// released.  They can then be negative, or past the window's width and height, so a drag
+/// keeps tracking.
+///
+/// * **Windows**: `SetCapture` on each press, released with the last button.
+/// * **macOS**: AppKit sends drags to the window they started in.
+/// * **Linux**: Wayland's implicit grab keeps the pointer on the surface.
+/// * **wasm32**: `setPointerCapture` on the canvas, which the browser releases on `pointerup`.
+/// * **iOS, Android**: the view a press began in keeps receiving it.
+///
 /// # Examples
 ///
 /// ```
@@ -191,58 +455,426 @@ impl MouseWindowLocation {
     pub fn window_height(&self) -> f64 {
         self.window_height
     }
+
+    /// The window the mouse is in, if the app created it.  See
+    /// [`Window::id`](crate::window::Window::id).
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window.and_then(|window| window.id())
+    }
+}
+
+/// The most recent [`MouseWindowLocation`], readable and writable without a lock.
+///
+/// Motion events arrive at up to several kHz, so they shouldn't contend on a Mutex with
+/// readers.  This is a seqlock: a writer makes `seq` odd while it stores the fields, and a
+/// reader retries until it sees the same even `seq` before and after its reads.  `seq` is 0
+/// until the first location is stored.
+#[derive(Debug)]
+struct LocationCell {
+    seq: AtomicUsize,
+    pos_x: AtomicU64,
+    pos_y: AtomicU64,
+    window_width: AtomicU64,
+    window_height: AtomicU64,
+    window: AtomicPtr<c_void>,
+    /// When the location was recorded, for [`MotionHistory`]; see [`MotionHistory::nanos`].
+    timestamp: AtomicU64,
+}
+
+impl LocationCell {
+    const fn new() -> Self {
+        LocationCell {
+            seq: AtomicUsize::new(0),
+            pos_x: AtomicU64::new(0),
+            pos_y: AtomicU64::new(0),
+            window_width: AtomicU64::new(0),
+            window_height: AtomicU64::new(0),
+            window: AtomicPtr::new(std::ptr::null_mut()),
+            timestamp: AtomicU64::new(0),
+        }
+    }
+
+    fn store(&self, location: MouseWindowLocation) {
+        self.store_at(location, 0);
+    }
+
+    fn load(&self) -> Option<MouseWindowLocation> {
+        self.load_at().map(|(location, _)| location)
+    }
+
+    fn store_at(&self, location: MouseWindowLocation, timestamp: u64) {
+        //writers are normally just the main thread, but plugin backends may report from anywhere
+        let mut seq = self.seq.load(Ordering::Relaxed);
+        loop {
+            if seq % 2 == 1 {
+                std::hint::spin_loop();
+                seq = self.seq.load(Ordering::Relaxed);
+                continue;
+            }
+            match self
+                .seq
+                .compare_exchange_weak(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(current) => seq = current,
+            }
+        }
+        std::sync::atomic::fence(Ordering::Release);
+        self.pos_x
+            .store(location.pos_x.to_bits(), Ordering::Relaxed);
+        self.pos_y
+            .store(location.pos_y.to_bits(), Ordering::Relaxed);
+        self.window_width
+            .store(location.window_width.to_bits(), Ordering::Relaxed);
+        self.window_height
+            .store(location.window_height.to_bits(), Ordering::Relaxed);
+        self.window.store(
+            location.window.map(|w| w.0.as_ptr()).unwrap_or_default(),
+            Ordering::Relaxed,
+        );
+        self.timestamp.store(timestamp, Ordering::Relaxed);
+        self.seq.store(seq + 2, Ordering::Release);
+    }
+
+    fn load_at(&self) -> Option<(MouseWindowLocation, u64)> {
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            if before == 0 {
+                return None;
+            }
+            if before % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let location = MouseWindowLocation::new(
+                f64::from_bits(self.pos_x.load(Ordering::Relaxed)),
+                f64::from_bits(self.pos_y.load(Ordering::Relaxed)),
+                f64::from_bits(self.window_width.load(Ordering::Relaxed)),
+                f64::from_bits(self.window_height.load(Ordering::Relaxed)),
+                NonNull::new(self.window.load(Ordering::Relaxed)).map(Window),
+            );
+            let timestamp = self.timestamp.load(Ordering::Relaxed);
+            std::sync::atomic::fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == before {
+                return Some((location, timestamp));
+            }
+        }
+    }
+}
+
+/// A position the pointer was at, and when; see [`Mouse::recent_motion`].
+#[derive(Debug, Clone, Copy)]
+pub struct MotionSample {
+    location: MouseWindowLocation,
+    timestamp: Instant,
+}
+
+impl MotionSample {
+    /// Where the pointer was.
+    pub fn location(&self) -> MouseWindowLocation {
+        self.location
+    }
+
+    /// When app_window received the position, according to [`crate::clock::now`].
+    pub fn timestamp(&self) -> Instant {
+        self.timestamp
+    }
+}
+
+/// The last [`MOTION_HISTORY_LEN`] locations, as a ring of [`LocationCell`]s so recording
+/// stays lock-free.
+#[derive(Debug)]
+struct MotionHistory {
+    /// How many locations have been recorded; the next goes in `slots[next % len]`.
+    next: AtomicUsize,
+    slots: [LocationCell; MOTION_HISTORY_LEN],
+}
+
+impl MotionHistory {
+    fn new() -> Self {
+        MotionHistory {
+            next: AtomicUsize::new(0),
+            slots: [const { LocationCell::new() }; MOTION_HISTORY_LEN],
+        }
+    }
+
+    /// Timestamps are stored as nanoseconds since the first one, so they fit in an atomic.
+    fn epoch(timestamp: Instant) -> Instant {
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+        *EPOCH.get_or_init(|| timestamp)
+    }
+
+    fn nanos(timestamp: Instant) -> u64 {
+        timestamp
+            .saturating_duration_since(Self::epoch(timestamp))
+            .as_nanos() as u64
+    }
+
+    fn record(&self, location: MouseWindowLocation, timestamp: Instant) {
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        self.slots[index % MOTION_HISTORY_LEN].store_at(location, Self::nanos(timestamp));
+    }
+
+    fn recent(&self, n: usize) -> Vec<MotionSample> {
+        loop {
+            let end = self.next.load(Ordering::Acquire);
+            let n = n.min(end).min(MOTION_HISTORY_LEN);
+            let samples: Vec<_> = (end - n..end)
+                .filter_map(|index| self.slots[index % MOTION_HISTORY_LEN].load_at())
+                .collect();
+            //if writers lapped a slot we read, it holds a newer location; read again
+            if self.next.load(Ordering::Acquire) - end > MOTION_HISTORY_LEN - n {
+                continue;
+            }
+            let Some(&(last, _)) = samples.last() else {
+                return Vec::new();
+            };
+            //velocity across windows means nothing, so only the latest window's run
+            let same_window = samples
+                .iter()
+                .rev()
+                .take_while(|(location, _)| {
+                    location.window.map(|w| w.0) == last.window.map(|w| w.0)
+                })
+                .count();
+            //recording set the epoch already
+            let epoch = Self::epoch(crate::clock::now());
+            return samples[samples.len() - same_window..]
+                .iter()
+                .map(|&(location, nanos)| MotionSample {
+                    location,
+                    timestamp: epoch + Duration::from_nanos(nanos),
+                })
+                .collect();
+        }
+    }
 }
 
+/// Counts multi-clicks, for platforms that don't count them.  Presses arrive on one thread, so
+/// plain atomics are enough.
+#[derive(Debug)]
+struct ClickCounter {
+    /// The last press's button, or `u32::MAX` before the first.
+    button: AtomicU32,
+    window: AtomicPtr<c_void>,
+    /// When the last press happened, per [`MotionHistory::nanos`].
+    at: AtomicU64,
+    /// Where the last press happened; NaN if the pointer's position was unknown.
+    x: AtomicF64,
+    y: AtomicF64,
+    /// The last press's count, which its release repeats.
+    count: AtomicU32,
+    /// How close presses must be in time; see [`Mouse::set_double_click_interval`].
+    interval: AtomicU64,
+}
+
+impl ClickCounter {
+    fn new() -> Self {
+        ClickCounter {
+            button: AtomicU32::new(u32::MAX),
+            window: AtomicPtr::new(std::ptr::null_mut()),
+            at: AtomicU64::new(0),
+            x: AtomicF64::new(f64::NAN),
+            y: AtomicF64::new(f64::NAN),
+            count: AtomicU32::new(0),
+            interval: AtomicU64::new(DOUBLE_CLICK_INTERVAL.as_nanos() as u64),
+        }
+    }
+
+    /// How far apart presses can be, in time and logical pixels, and still be one multi-click.
+    fn limits(&self) -> (Duration, f64) {
+        #[cfg(target_os = "windows")]
+        return sys::double_click_limits();
+        #[cfg(not(target_os = "windows"))]
+        (
+            Duration::from_nanos(self.interval.load(Ordering::Relaxed)),
+            DOUBLE_CLICK_DISTANCE,
+        )
+    }
+
+    /// Counts a press of `button`, at `position` if known.
+    fn press(
+        &self,
+        button: u8,
+        window: *mut c_void,
+        position: Option<(f64, f64)>,
+        at: Instant,
+    ) -> u32 {
+        let (interval, distance) = self.limits();
+        let at = MotionHistory::nanos(at);
+        let (x, y) = position.unwrap_or((f64::NAN, f64::NAN));
+        let (last_x, last_y) = (
+            self.x.load(Ordering::Relaxed),
+            self.y.load(Ordering::Relaxed),
+        );
+        let near = (x.is_nan() && last_x.is_nan())
+            || ((x - last_x).abs() <= distance && (y - last_y).abs() <= distance);
+        let last = self.count.load(Ordering::Relaxed);
+        let count = if last > 0
+            && self.button.load(Ordering::Relaxed) == button as u32
+            && self.window.load(Ordering::Relaxed) == window
+            && at.saturating_sub(self.at.load(Ordering::Relaxed)) <= interval.as_nanos() as u64
+            && near
+        {
+            last + 1
+        } else {
+            1
+        };
+        self.set(button, window, count);
+        self.at.store(at, Ordering::Relaxed);
+        self.x.store(x, Ordering::Relaxed);
+        self.y.store(y, Ordering::Relaxed);
+        count
+    }
+
+    /// Records a press the platform counted itself.
+    fn set(&self, button: u8, window: *mut c_void, count: u32) {
+        self.button.store(button as u32, Ordering::Relaxed);
+        self.window.store(window, Ordering::Relaxed);
+        self.count.store(count, Ordering::Relaxed);
+    }
+
+    /// The count for a release of `button`: its press's, or 1 if another press came between.
+    fn release(&self, button: u8) -> u32 {
+        if self.button.load(Ordering::Relaxed) == button as u32 {
+            self.count.load(Ordering::Relaxed).max(1)
+        } else {
+            1
+        }
+    }
+}
+
+//Every method here runs once per platform event, so they stay free of locks, allocations and
+//logging.  The exceptions are delivering button events and frame batches, and only while someone
+//listens.
 #[derive(Debug)]
 struct Shared {
-    window: std::sync::Mutex<Option<MouseWindowLocation>>,
+    window: LocationCell,
+    motion: MotionHistory,
 
     buttons: [AtomicBool; 255],
+    clicks: ClickCounter,
+    button_listeners: EventListeners<ButtonEvent>,
+    scroll_listeners: EventListeners<ScrollEvent>,
+    frame_listeners: FrameListeners,
     scroll_delta_x: AtomicF64,
     scroll_delta_y: AtomicF64,
+    scroll_pixels_x: AtomicF64,
+    scroll_pixels_y: AtomicF64,
+    scroll_lines_x: AtomicF64,
+    scroll_lines_y: AtomicF64,
     last_window: AtomicPtr<c_void>,
 }
 impl Shared {
     fn new() -> Self {
         Shared {
-            window: std::sync::Mutex::new(None),
+            window: LocationCell::new(),
+            motion: MotionHistory::new(),
             buttons: [const { AtomicBool::new(false) }; 255],
+            clicks: ClickCounter::new(),
+            button_listeners: EventListeners::new(),
+            scroll_listeners: EventListeners::new(),
+            frame_listeners: FrameListeners::default(),
             scroll_delta_x: AtomicF64::new(0.0),
             scroll_delta_y: AtomicF64::new(0.0),
+            scroll_pixels_x: AtomicF64::new(0.0),
+            scroll_pixels_y: AtomicF64::new(0.0),
+            scroll_lines_x: AtomicF64::new(0.0),
+            scroll_lines_y: AtomicF64::new(0.0),
             last_window: AtomicPtr::new(std::ptr::null_mut()),
         }
     }
 
     fn set_window_location(&self, location: MouseWindowLocation) {
-        logwise::debuginternal_sync!(
-            "Set mouse window location {location}",
-            location = logwise::privacy::LogIt(&location)
-        );
-        *self.window.lock().unwrap() = Some(location);
-        self.last_window.store(
-            location.window.map(|e| e.0.as_ptr()).unwrap_or_default(),
-            Ordering::Relaxed,
-        )
+        self.window.store(location);
+        self.motion.record(location, crate::clock::now());
+        let window = location.window.map(|e| e.0.as_ptr()).unwrap_or_default();
+        self.last_window.store(window, Ordering::Relaxed);
+        if self.frame_listeners.is_active() {
+            self.frame_listeners
+                .send(|pending| pending.position(location, window));
+        }
     }
     fn set_key_state(&self, key: u8, down: bool, window: *mut c_void) {
-        logwise::debuginternal_sync!("Set mouse key {key} state {down}", key = key, down = down);
-        self.buttons[key as usize].store(down, std::sync::atomic::Ordering::Relaxed);
-        self.last_window
-            .store(window, std::sync::atomic::Ordering::Relaxed);
+        let clicks = if down {
+            let position = self.window.load().map(|l| (l.pos_x(), l.pos_y()));
+            self.clicks
+                .press(key, window, position, crate::clock::now())
+        } else {
+            self.clicks.release(key)
+        };
+        self.set_button_state(key, down, clicks, window);
+    }
+
+    /// Records a press or release the platform counted clicks for.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "ios")), allow(dead_code))]
+    fn set_counted_key_state(&self, key: u8, down: bool, clicks: u32, window: *mut c_void) {
+        if down {
+            self.clicks.set(key, window, clicks);
+        }
+        self.set_button_state(key, down, clicks, window);
+    }
+
+    fn set_button_state(&self, key: u8, down: bool, clicks: u32, window: *mut c_void) {
+        self.buttons[key as usize].store(down, Ordering::Relaxed);
+        self.last_window.store(window, Ordering::Relaxed);
+        //looking up the window takes a lock, so only when someone's listening
+        let event = || ButtonEvent {
+            button: key,
+            down,
+            clicks,
+            window: super::window_id(window),
+        };
+        if self.button_listeners.is_active() {
+            self.button_listeners.send(event());
+        }
+        if self.frame_listeners.is_active() {
+            self.frame_listeners
+                .send(|pending| pending.edge(FrameEdge::Button(event()), window));
+        }
+    }
+
+    fn button_events(&self) -> ButtonEvents {
+        ButtonEvents {
+            queue: self.button_listeners.subscribe(),
+        }
+    }
+
+    fn scroll_events(&self) -> ScrollEvents {
+        ScrollEvents {
+            queue: self.scroll_listeners.subscribe(),
+        }
     }
 
     fn add_scroll_delta(&self, delta_x: f64, delta_y: f64, window: *mut c_void) {
-        logwise::debuginternal_sync!(
-            "Add mouse scroll delta {delta_x},{delta_y}",
-            delta_x = delta_x,
-            delta_y = delta_y
-        );
-        self.scroll_delta_x
-            .fetch_add(delta_x, std::sync::atomic::Ordering::Relaxed);
-        self.scroll_delta_y
-            .fetch_add(delta_y, std::sync::atomic::Ordering::Relaxed);
-        self.last_window
-            .store(window, std::sync::atomic::Ordering::Relaxed);
+        self.scroll_delta_x.fetch_add(delta_x, Ordering::Relaxed);
+        self.scroll_delta_y.fetch_add(delta_y, Ordering::Relaxed);
+        self.last_window.store(window, Ordering::Relaxed);
+        if self.frame_listeners.is_active() {
+            self.frame_listeners
+                .send(|pending| pending.scroll(delta_x, delta_y, window));
+        }
+    }
+
+    /// Records scrolling in logical pixels, positive toward the bottom right of the document.
+    /// Platforms call this alongside [`Shared::add_scroll_delta`], having converted from their
+    /// own units.
+    fn add_scroll_pixels(&self, pixels_x: f64, pixels_y: f64, window: *mut c_void) {
+        self.scroll_pixels_x.fetch_add(pixels_x, Ordering::Relaxed);
+        self.scroll_pixels_y.fetch_add(pixels_y, Ordering::Relaxed);
+        if self.scroll_listeners.is_active() {
+            self.scroll_listeners.send(ScrollEvent {
+                delta_x: pixels_x,
+                delta_y: pixels_y,
+                window: super::window_id(window),
+            });
+        }
+    }
+
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn add_scroll_lines(&self, lines_x: f64, lines_y: f64) {
+        self.scroll_lines_x.fetch_add(lines_x, Ordering::Relaxed);
+        self.scroll_lines_y.fetch_add(lines_y, Ordering::Relaxed);
     }
 }
 
@@ -250,9 +882,9 @@ impl Shared {
 ///
 /// This type coalesces input from all connected mice into a single interface.
 /// It provides access to:
-/// - Mouse position within windows
+/// - Mouse position within windows, and its recent history
 /// - Button states (left, right, middle, and others)
-/// - Accumulated scroll deltas
+/// - Accumulated scroll deltas, and a stream of scroll events
 ///
 /// # Examples
 ///
@@ -321,7 +953,36 @@ impl Mouse {
         You may need to create a window first, using APIs in this crate.
     */
     pub fn window_pos(&self) -> Option<MouseWindowLocation> {
-        *self.shared.window.lock().unwrap()
+        self.shared.window.load()
+    }
+
+    /// The last `n` positions the pointer moved through, oldest first, with when each was
+    /// reported.  For working out how fast the pointer is moving, say to fling a list on
+    /// release, or to smooth a custom cursor.
+    ///
+    /// Positions are kept from when the `Mouse` was created, up to [`MOTION_HISTORY_LEN`] of
+    /// them, so fewer than `n` may come back.  Only the run of positions in the window the
+    /// pointer is in now is returned, since positions in different windows can't be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::mouse::Mouse;
+    ///
+    /// let mouse = Mouse::coalesced().await;
+    /// let motion = mouse.recent_motion(5);
+    /// if let [first, .., last] = motion.as_slice() {
+    ///     let seconds = (last.timestamp() - first.timestamp()).as_secs_f64();
+    ///     let dx = last.location().pos_x() - first.location().pos_x();
+    ///     if seconds > 0.0 {
+    ///         println!("moving {} points per second horizontally", dx / seconds);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn recent_motion(&self, n: usize) -> Vec<MotionSample> {
+        self.shared.motion.recent(n)
     }
 
     /// Determines if the specified mouse button is currently pressed.
@@ -329,8 +990,9 @@ impl Mouse {
     /// # Arguments
     ///
     /// * `button` - The button to check. Use constants like [`MOUSE_BUTTON_LEFT`],
-    ///   [`MOUSE_BUTTON_RIGHT`], or [`MOUSE_BUTTON_MIDDLE`]. Other button values
-    ///   (e.g., for mice with additional buttons) may be supported on a best-effort basis.
+    ///   [`MOUSE_BUTTON_RIGHT`], [`MOUSE_BUTTON_MIDDLE`], [`MOUSE_BUTTON_BACK`] or
+    ///   [`MOUSE_BUTTON_FORWARD`]. Other button values (e.g., for mice with more buttons)
+    ///   may be supported on a best-effort basis.
     ///
     /// # Returns
     ///
@@ -357,9 +1019,142 @@ impl Mouse {
         self.shared.buttons[button as usize].load(Ordering::Relaxed)
     }
 
-    /// Returns the accumulated scroll delta and resets it to zero.
+    /// Which click of a multi-click the most recent button press was: 1 for a single click, 2
+    /// for a double-click, 3 for a triple-click and so on, or 0 before any press.
+    ///
+    /// Presses count together when they're of the same button in the same window, close
+    /// enough in time and place.  To see every press's count, such as to select a word on a
+    /// double-click even if it's released within a frame, use [`ButtonEvent::click_count`].
+    ///
+    /// # Platform behavior
+    ///
+    /// | Platform | Counted by |
+    /// |----------|------------|
+    /// | macOS    | `NSEvent.clickCount`, per the user's double-click speed |
+    /// | Windows  | `GetDoubleClickTime`, within the `SM_CXDOUBLECLK` by `SM_CYDOUBLECLK` rectangle |
+    /// | Others   | [`Mouse::set_double_click_interval`], within [`DOUBLE_CLICK_DISTANCE`] |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::mouse::{Mouse, MOUSE_BUTTON_LEFT};
+    ///
+    /// let mouse = Mouse::coalesced().await;
+    /// let mut events = mouse.button_events();
+    /// loop {
+    ///     let event = events.next().await;
+    ///     if event.button() == MOUSE_BUTTON_LEFT && event.down() {
+    ///         match event.click_count() {
+    ///             1 => println!("place the caret"),
+    ///             2 => println!("select the word"),
+    ///             _ => println!("select the line"),
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn last_click_count(&self) -> u32 {
+        self.shared.clicks.count.load(Ordering::Relaxed)
+    }
+
+    /// Sets how soon after a press the next must come to count toward a multi-click, which is
+    /// [`DOUBLE_CLICK_INTERVAL`] until you set it.
+    ///
+    /// Set this from your app's preferences where the platform has no setting of its own.
+    /// macOS and Windows use the user's system-wide double-click speed instead, and ignore it.
+    pub fn set_double_click_interval(&self, interval: Duration) {
+        self.shared
+            .clicks
+            .interval
+            .store(interval.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// The interval set by [`Mouse::set_double_click_interval`].
+    pub fn double_click_interval(&self) -> Duration {
+        Duration::from_nanos(self.shared.clicks.interval.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn frame_listeners(&self) -> &FrameListeners {
+        &self.shared.frame_listeners
+    }
+
+    /// Keeps the cursor inside `window`, or inside `region` of it, until
+    /// [`Mouse::release_confinement`] or the window closes.
+    ///
+    /// Unlike pointer lock, the cursor stays visible and keeps moving; it just can't leave.
+    /// This is what RTS-style edge scrolling wants.  `region` is in the window's logical
+    /// coordinates, like [`MouseWindowLocation`]; `None` means the whole window.  Calling this
+    /// again replaces the previous region.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: uses `zwp_pointer_constraints_v1`.  The compositor decides when the
+    ///   confinement activates, usually once the cursor is inside the region, and lifts it
+    ///   while the window is unfocused.  Compositors without the protocol return
+    ///   [`WindowSettingError::Unsupported`].
+    /// * **Windows**: uses `ClipCursor`, lifted while the window is in the background and
+    ///   reapplied when it moves or resizes.
+    /// * **macOS, iOS, Android, wasm32**: there's no way to confine a visible cursor, so this
+    ///   does nothing and returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::input::mouse::Mouse;
+    ///
+    /// let mouse = Mouse::coalesced().await;
+    /// if mouse.confine_to(window, None).await.is_err() {
+    ///     //fall back to scrolling only while the cursor is near the edge
+    /// }
+    /// # }
+    /// ```
+    pub async fn confine_to(
+        &self,
+        window: &crate::window::Window,
+        region: Option<Rect>,
+    ) -> Result<(), WindowSettingError> {
+        window.confine_pointer(region).await
+    }
+
+    /// Undoes [`Mouse::confine_to`] for `window`.  Does nothing if it isn't confined.
+    pub async fn release_confinement(
+        &self,
+        window: &crate::window::Window,
+    ) -> Result<(), WindowSettingError> {
+        window.release_pointer().await
+    }
+
+    /// Returns a stream of this mouse's button presses and releases, from now on.
+    ///
+    /// Each call returns an independent stream.  Drop it when you're done; until then, button
+    /// events are queued for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::mouse::{Mouse, MOUSE_BUTTON_BACK};
+    ///
+    /// let mouse = Mouse::coalesced().await;
+    /// let mut events = mouse.button_events();
+    /// loop {
+    ///     let event = events.next().await;
+    ///     if event.button() == MOUSE_BUTTON_BACK && event.down() {
+    ///         println!("navigate back");
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn button_events(&self) -> ButtonEvents {
+        self.shared.button_events()
+    }
+
+    /// Returns the accumulated scroll delta, in the platform's own units, and resets it to zero.
     ///
-    /// This method is useful for implementing scroll handling in your application.
+    /// The units and signs differ between platforms; [`Mouse::consume_scroll_delta`] reports
+    /// the same scrolling in logical pixels everywhere.  This method is useful for implementing scroll handling in your application.
     /// The scroll delta accumulates between calls, so you should call this
     /// periodically (e.g., once per frame) to process scroll events.
     ///
@@ -388,6 +1183,138 @@ impl Mouse {
         let y = self.shared.scroll_delta_y.swap(0.0, Ordering::Relaxed);
         (x, y)
     }
+
+    /// Returns the accumulated scroll distance in logical pixels and resets it to zero.
+    ///
+    /// Unlike [`Mouse::load_clear_scroll_delta`], this is normalized: positive `y` moves
+    /// toward the bottom of the document and positive `x` toward the right, whichever way the
+    /// user has set their scroll direction, and wheels that scroll by lines are converted at
+    /// [`SCROLL_LINE_PIXELS`] per line.  A touchpad's pixel deltas are passed through, so
+    /// scrolling a view by exactly this much tracks the user's fingers.
+    ///
+    /// # Platform support
+    ///
+    /// | Platform | Source |
+    /// |----------|--------|
+    /// | Windows  | `WM_MOUSEWHEEL` and `WM_MOUSEHWHEEL`, in lines as for [`Mouse::load_clear_scroll_lines`] |
+    /// | macOS    | `NSEvent.scrollingDeltaX/Y`, in lines unless `hasPreciseScrollingDeltas` |
+    /// | iOS      | Two-finger trackpad pans, in points |
+    /// | Linux    | `wl_pointer.axis`, already in surface pixels |
+    /// | wasm32   | `wheel` events, in lines or pages per their `deltaMode` |
+    /// | Android  | `AXIS_VSCROLL` and `AXIS_HSCROLL`, at 3 lines per notch |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::mouse::Mouse;
+    ///
+    /// let mut mouse = Mouse::coalesced().await;
+    /// let mut scroll_offset = 0.0;
+    ///
+    /// // In your update loop:
+    /// let (_, delta_y) = mouse.consume_scroll_delta();
+    /// scroll_offset += delta_y;
+    /// # }
+    /// ```
+    pub fn consume_scroll_delta(&mut self) -> (f64, f64) {
+        let x = self.shared.scroll_pixels_x.swap(0.0, Ordering::Relaxed);
+        let y = self.shared.scroll_pixels_y.swap(0.0, Ordering::Relaxed);
+        (x, y)
+    }
+
+    /// Returns a stream of this mouse's scrolling, from now on.
+    ///
+    /// Each event carries the distance [`Mouse::consume_scroll_delta`] would have added, and
+    /// the window it scrolled.  Each call returns an independent stream; drop it when you're
+    /// done.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::mouse::Mouse;
+    ///
+    /// let mouse = Mouse::coalesced().await;
+    /// let mut events = mouse.scroll_events();
+    /// loop {
+    ///     let event = events.next().await;
+    ///     println!("scroll by {} in {:?}", event.delta_y(), event.window_id());
+    /// }
+    /// # }
+    /// ```
+    pub fn scroll_events(&self) -> ScrollEvents {
+        self.shared.scroll_events()
+    }
+
+    /// Returns the accumulated scroll distance in lines and resets it to zero.
+    ///
+    /// Unlike [`Mouse::load_clear_scroll_delta`], which reports the platform's raw units, this
+    /// applies the user's scrolling preferences, so an app scrolling text can move by exactly
+    /// this many lines.  Values are fractional: high-resolution wheels and touchpads report
+    /// partial notches, which accumulate here until you read them.
+    ///
+    /// # Platform support
+    ///
+    /// | Platform | Behavior |
+    /// |----------|----------|
+    /// | Windows  | `WHEEL_DELTA` units scaled by `SPI_GETWHEELSCROLLLINES` (vertical) and `SPI_GETWHEELSCROLLCHARS` (horizontal) |
+    /// | Others   | Not yet reported; always `(0.0, 0.0)` |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::input::mouse::Mouse;
+    ///
+    /// let mut mouse = Mouse::coalesced().await;
+    ///
+    /// // In your update loop:
+    /// let (_, lines) = mouse.load_clear_scroll_lines();
+    /// if lines != 0.0 {
+    ///     println!("Scroll the document by {lines} lines");
+    /// }
+    /// # }
+    /// ```
+    pub fn load_clear_scroll_lines(&mut self) -> (f64, f64) {
+        let x = self.shared.scroll_lines_x.swap(0.0, Ordering::Relaxed);
+        let y = self.shared.scroll_lines_y.swap(0.0, Ordering::Relaxed);
+        (x, y)
+    }
+
+    /// Records a synthetic button event, as if the platform had reported it.
+    ///
+    /// Only available with the `conformance` feature, so the conformance suite can check
+    /// state tracking on machines without a mouse.
+    #[cfg(feature = "conformance")]
+    #[doc(hidden)]
+    pub fn inject_button(&self, button: u8, down: bool) {
+        self.shared
+            .set_key_state(button, down, std::ptr::null_mut());
+    }
+
+    /// Records a synthetic pointer position; see [`Mouse::inject_button`].
+    #[cfg(feature = "conformance")]
+    #[doc(hidden)]
+    pub fn inject_position(&self, pos_x: f64, pos_y: f64, window_width: f64, window_height: f64) {
+        self.shared.set_window_location(MouseWindowLocation::new(
+            pos_x,
+            pos_y,
+            window_width,
+            window_height,
+            None,
+        ));
+    }
+
+    /// Records a synthetic scroll event; see [`Mouse::inject_button`].
+    #[cfg(feature = "conformance")]
+    #[doc(hidden)]
+    pub fn inject_scroll(&self, delta_x: f64, delta_y: f64) {
+        self.shared
+            .add_scroll_delta(delta_x, delta_y, std::ptr::null_mut());
+        self.shared
+            .add_scroll_pixels(delta_x, delta_y, std::ptr::null_mut());
+    }
 }
 
 impl PartialEq for Mouse {
@@ -406,7 +1333,131 @@ impl Hash for Mouse {
 
 #[cfg(test)]
 mod test {
-    use crate::input::mouse::Mouse;
+    use crate::application::time::Duration;
+    use crate::input::mouse::{
+        ButtonEvent, ClickCounter, DOUBLE_CLICK_INTERVAL, LocationCell, MOTION_HISTORY_LEN,
+        MOUSE_BUTTON_BACK, Mouse, MouseWindowLocation, ScrollEvent, Shared,
+    };
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn location_cell_is_never_torn() {
+        let cell = Arc::new(LocationCell::new());
+        assert!(cell.load().is_none());
+        let done = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let cell = cell.clone();
+            let done = done.clone();
+            std::thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    if let Some(location) = cell.load() {
+                        //every write uses the same value for all four fields
+                        assert_eq!(location.pos_x(), location.pos_y());
+                        assert_eq!(location.pos_x(), location.window_width());
+                        assert_eq!(location.pos_x(), location.window_height());
+                    }
+                }
+            })
+        };
+        for i in 0..100_000 {
+            let v = i as f64;
+            cell.store(MouseWindowLocation::new(v, v, v, v, None));
+        }
+        done.store(true, Ordering::Relaxed);
+        reader.join().unwrap();
+        assert_eq!(cell.load().unwrap().pos_x(), 99_999.0);
+    }
+
+    #[test]
+    fn button_events_keep_every_edge() {
+        let shared = Shared::new();
+        shared.set_key_state(MOUSE_BUTTON_BACK, true, std::ptr::null_mut());
+        let mut events = shared.button_events();
+        //a click within one frame: button_state would never see it
+        shared.set_key_state(MOUSE_BUTTON_BACK, true, std::ptr::null_mut());
+        shared.set_key_state(MOUSE_BUTTON_BACK, false, std::ptr::null_mut());
+        assert_eq!(
+            events.try_next(),
+            Some(ButtonEvent {
+                button: MOUSE_BUTTON_BACK,
+                down: true,
+                clicks: 2,
+                window: None
+            })
+        );
+        assert_eq!(
+            events.try_next(),
+            Some(ButtonEvent {
+                button: MOUSE_BUTTON_BACK,
+                down: false,
+                clicks: 2,
+                window: None
+            })
+        );
+        assert_eq!(events.try_next(), None);
+        drop(events);
+        shared.set_key_state(MOUSE_BUTTON_BACK, true, std::ptr::null_mut());
+        assert!(shared.button_listeners.queues.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn scroll_events_match_consumed_delta() {
+        let shared = Shared::new();
+        shared.add_scroll_pixels(0.0, 20.0, std::ptr::null_mut());
+        let mut events = shared.scroll_events();
+        shared.add_scroll_pixels(0.0, -60.0, std::ptr::null_mut());
+        shared.add_scroll_pixels(3.5, 0.0, std::ptr::null_mut());
+        assert_eq!(
+            events.try_next(),
+            Some(ScrollEvent {
+                delta_x: 0.0,
+                delta_y: -60.0,
+                window: None
+            })
+        );
+        assert_eq!(events.try_next().map(|e| e.delta_x()), Some(3.5));
+        assert_eq!(events.try_next(), None);
+        assert_eq!(shared.scroll_pixels_x.load(Ordering::Relaxed), 3.5);
+        assert_eq!(shared.scroll_pixels_y.load(Ordering::Relaxed), -40.0);
+    }
+
+    #[test]
+    fn clicks_count_when_close_in_time_and_place() {
+        let clicks = ClickCounter::new();
+        let null = std::ptr::null_mut();
+        let start = crate::clock::now();
+        let soon = start + Duration::from_millis(100);
+        assert_eq!(clicks.press(0, null, Some((10.0, 10.0)), start), 1);
+        assert_eq!(clicks.release(0), 1);
+        assert_eq!(clicks.press(0, null, Some((12.0, 9.0)), soon), 2);
+        assert_eq!(clicks.release(0), 2);
+        assert_eq!(clicks.press(0, null, Some((12.0, 9.0)), soon), 3);
+        //too far
+        assert_eq!(clicks.press(0, null, Some((30.0, 9.0)), soon), 1);
+        //another button
+        assert_eq!(clicks.press(1, null, Some((30.0, 9.0)), soon), 1);
+        assert_eq!(clicks.release(0), 1);
+        //too late
+        let late = soon + DOUBLE_CLICK_INTERVAL * 2;
+        assert_eq!(clicks.press(1, null, Some((30.0, 9.0)), late), 1);
+    }
+
+    #[test]
+    fn recent_motion_is_oldest_first() {
+        let shared = Shared::new();
+        assert!(shared.motion.recent(4).is_empty());
+        for i in 0..MOTION_HISTORY_LEN + 3 {
+            let v = i as f64;
+            shared.set_window_location(MouseWindowLocation::new(v, v, 100.0, 100.0, None));
+        }
+        let motion = shared.motion.recent(4);
+        let xs: Vec<_> = motion.iter().map(|s| s.location().pos_x()).collect();
+        let last = (MOTION_HISTORY_LEN + 2) as f64;
+        assert_eq!(xs, [last - 3.0, last - 2.0, last - 1.0, last]);
+        assert!(motion[0].timestamp() <= motion[3].timestamp());
+        assert_eq!(shared.motion.recent(100).len(), MOTION_HISTORY_LEN);
+    }
 
     #[test]
     fn test_send_sync() {
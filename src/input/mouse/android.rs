@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::coordinates::Size;
+use crate::input::Window;
+use crate::input::mouse::{
+    MOUSE_BUTTON_BACK, MOUSE_BUTTON_FORWARD, MOUSE_BUTTON_LEFT, MOUSE_BUTTON_MIDDLE,
+    MOUSE_BUTTON_RIGHT, MouseWindowLocation, SCROLL_LINE_PIXELS, SCROLL_LINES_PER_NOTCH, Shared,
+};
+use ndk_sys::{
+    AInputEvent, AMOTION_EVENT_ACTION_CANCEL, AMOTION_EVENT_ACTION_DOWN, AMOTION_EVENT_ACTION_MASK,
+    AMOTION_EVENT_ACTION_SCROLL, AMOTION_EVENT_ACTION_UP, AMOTION_EVENT_AXIS_HSCROLL,
+    AMOTION_EVENT_AXIS_VSCROLL, AMOTION_EVENT_BUTTON_BACK, AMOTION_EVENT_BUTTON_FORWARD,
+    AMOTION_EVENT_BUTTON_PRIMARY, AMOTION_EVENT_BUTTON_SECONDARY, AMOTION_EVENT_BUTTON_TERTIARY,
+    AMOTION_EVENT_TOOL_TYPE_MOUSE, AMotionEvent_getAction, AMotionEvent_getAxisValue,
+    AMotionEvent_getButtonState, AMotionEvent_getToolType, AMotionEvent_getX, AMotionEvent_getY,
+};
+use std::ffi::c_void;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+static MICE: Mutex<Vec<Weak<Shared>>> = Mutex::new(Vec::new());
+
+/// Mouse buttons held as of the last event, so we can report changes.
+static BUTTON_STATE: AtomicU32 = AtomicU32::new(0);
+
+const BUTTONS: [(u32, u8); 5] = [
+    (AMOTION_EVENT_BUTTON_PRIMARY, MOUSE_BUTTON_LEFT),
+    (AMOTION_EVENT_BUTTON_SECONDARY, MOUSE_BUTTON_RIGHT),
+    (AMOTION_EVENT_BUTTON_TERTIARY, MOUSE_BUTTON_MIDDLE),
+    (AMOTION_EVENT_BUTTON_BACK, MOUSE_BUTTON_BACK),
+    (AMOTION_EVENT_BUTTON_FORWARD, MOUSE_BUTTON_FORWARD),
+];
+
+fn apply_all<F: Fn(&Shared)>(f: F) {
+    MICE.lock().unwrap().retain(|weak| match weak.upgrade() {
+        Some(shared) => {
+            f(&shared);
+            true
+        }
+        None => false,
+    });
+}
+
+/**
+Processes an `AINPUT_EVENT_TYPE_MOTION` event.
+
+Mice report their buttons directly.  Touches and styluses act as the left button of a mouse that
+follows the first pointer, so apps written against the mouse model work on touchscreens.
+
+`scale` converts the event's physical pixels to the logical `window_size`.  Returns whether the
+event was consumed.
+*/
+pub(crate) fn motion_event(
+    event: *const AInputEvent,
+    window: *mut c_void,
+    scale: f64,
+    window_size: Size,
+) -> bool {
+    let action = unsafe { AMotionEvent_getAction(event) } as u32 & AMOTION_EVENT_ACTION_MASK;
+    let window_ptr = NonNull::new(window).map(Window);
+    let x = unsafe { AMotionEvent_getX(event, 0) } as f64 / scale;
+    let y = unsafe { AMotionEvent_getY(event, 0) } as f64 / scale;
+    let location =
+        MouseWindowLocation::new(x, y, window_size.width(), window_size.height(), window_ptr);
+    apply_all(|shared| shared.set_window_location(location));
+
+    if action == AMOTION_EVENT_ACTION_SCROLL {
+        let dx = unsafe { AMotionEvent_getAxisValue(event, AMOTION_EVENT_AXIS_HSCROLL as i32, 0) };
+        let dy = unsafe { AMotionEvent_getAxisValue(event, AMOTION_EVENT_AXIS_VSCROLL as i32, 0) };
+        //Android's vertical axis is positive away from the user; ours is positive down
+        //both axes are in notches
+        let notch = SCROLL_LINES_PER_NOTCH * SCROLL_LINE_PIXELS;
+        apply_all(|shared| {
+            shared.add_scroll_delta(dx as f64, -dy as f64, window);
+            shared.add_scroll_pixels(dx as f64 * notch, -dy as f64 * notch, window);
+        });
+        return true;
+    }
+
+    if unsafe { AMotionEvent_getToolType(event, 0) } as u32 == AMOTION_EVENT_TOOL_TYPE_MOUSE {
+        let buttons = unsafe { AMotionEvent_getButtonState(event) } as u32;
+        let old = BUTTON_STATE.swap(buttons, Ordering::Relaxed);
+        for (mask, button) in BUTTONS {
+            if (old ^ buttons) & mask != 0 {
+                let down = buttons & mask != 0;
+                apply_all(|shared| shared.set_key_state(button, down, window));
+            }
+        }
+    } else {
+        let down = match action {
+            AMOTION_EVENT_ACTION_DOWN => true,
+            AMOTION_EVENT_ACTION_UP | AMOTION_EVENT_ACTION_CANCEL => false,
+            _ => return true,
+        };
+        apply_all(|shared| shared.set_key_state(MOUSE_BUTTON_LEFT, down, window));
+    }
+    true
+}
+
+#[derive(Debug)]
+pub(super) struct PlatformCoalescedMouse {}
+
+impl PlatformCoalescedMouse {
+    pub(crate) async fn new(shared: &Arc<Shared>) -> PlatformCoalescedMouse {
+        MICE.lock().unwrap().push(Arc::downgrade(shared));
+        PlatformCoalescedMouse {}
+    }
+}
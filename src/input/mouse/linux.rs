@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::input::Window;
-use crate::input::mouse::{MouseWindowLocation, Shared};
+use crate::input::mouse::{
+    MOUSE_BUTTON_BACK, MOUSE_BUTTON_FORWARD, MOUSE_BUTTON_LEFT, MOUSE_BUTTON_MIDDLE,
+    MOUSE_BUTTON_RIGHT, MouseWindowLocation, Shared,
+};
 use std::ffi::c_void;
 use std::ptr::NonNull;
 use std::sync::{Arc, Mutex, OnceLock, Weak};
@@ -93,16 +96,17 @@ pub fn button_event(_time: u32, button: u32, state: u32, window: ObjectId) {
     let down = state != 0;
     //see https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
     let btn_code = match button {
-        0x110 => 0, //BTN_LEFT
-        0x111 => 1, //BTN_RIGHT
-        0x112 => 2, //BTN_MIDDLE
-        0x113 => 3, //BTN_SIDE
-        0x114 => 4, //BTN_EXTRA
-        0x115 => 5, //BTN_FORWARD
-        0x116 => 6, //BTN_BACK
-        0x117 => 7, //BTN_TASK
-        0x118 => 8,
-        0x119 => 9,
+        0x110 => MOUSE_BUTTON_LEFT,    //BTN_LEFT
+        0x111 => MOUSE_BUTTON_RIGHT,   //BTN_RIGHT
+        0x112 => MOUSE_BUTTON_MIDDLE,  //BTN_MIDDLE
+        0x113 => MOUSE_BUTTON_BACK,    //BTN_SIDE
+        0x114 => MOUSE_BUTTON_FORWARD, //BTN_EXTRA
+        //a few mice report these instead of SIDE and EXTRA
+        0x115 => MOUSE_BUTTON_FORWARD, //BTN_FORWARD
+        0x116 => MOUSE_BUTTON_BACK,    //BTN_BACK
+        0x117 => 5,                    //BTN_TASK
+        0x118 => 6,
+        0x119 => 7,
         _ => {
             logwise::warn_sync!("Unknown button code: {button}", button = button);
             return;
@@ -127,6 +131,7 @@ pub fn axis_event(_time: u32, axis: u32, value: f64, window: ObjectId) {
             .unwrap()
             .apply_all(|shared| {
                 shared.add_scroll_delta(0.0, value, window.protocol_id() as *mut c_void);
+                shared.add_scroll_pixels(0.0, value, window.protocol_id() as *mut c_void);
             })
     } else {
         //horizontal
@@ -136,6 +141,7 @@ pub fn axis_event(_time: u32, axis: u32, value: f64, window: ObjectId) {
             .unwrap()
             .apply_all(|shared| {
                 shared.add_scroll_delta(value, 0.0, window.protocol_id() as *mut c_void);
+                shared.add_scroll_pixels(value, 0.0, window.protocol_id() as *mut c_void);
             })
     }
 }
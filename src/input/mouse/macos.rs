@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::input::Window;
-use crate::input::mouse::{MouseWindowLocation, Shared};
+use crate::input::mouse::{MouseWindowLocation, SCROLL_LINE_PIXELS, Shared};
 use std::ffi::c_void;
 use std::ptr::NonNull;
 use std::sync::{Arc, Weak};
@@ -51,10 +51,16 @@ extern "C" fn raw_input_mouse_button(
     window: *mut c_void,
     button: u8,
     down: bool,
+    clicks: u32,
 ) {
     let weak = unsafe { Weak::from_raw(ctx as *const Shared) };
     if let Some(shared) = weak.upgrade() {
-        shared.set_key_state(button, down, window);
+        //0 when the platform didn't count
+        if clicks == 0 {
+            shared.set_key_state(button, down, window);
+        } else {
+            shared.set_counted_key_state(button, down, clicks, window);
+        }
     }
     std::mem::forget(weak);
 }
@@ -65,10 +71,14 @@ extern "C" fn raw_input_mouse_scroll(
     window: *mut c_void,
     delta_x: f64,
     delta_y: f64,
+    lines: bool,
 ) {
     let weak = unsafe { Weak::from_raw(ctx as *const Shared) };
     if let Some(shared) = weak.upgrade() {
         shared.add_scroll_delta(delta_x, delta_y, window);
+        //positive moves the content down, toward the top of the document
+        let scale = if lines { -SCROLL_LINE_PIXELS } else { -1.0 };
+        shared.add_scroll_pixels(delta_x * scale, delta_y * scale, window);
     }
     std::mem::forget(weak);
 }
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Mouse events reported by an out-of-tree backend via [`crate::backend`].
+
+use crate::input::mouse::{MouseWindowLocation, Shared};
+use std::sync::{Arc, Mutex, Weak};
+
+static MICE: Mutex<Vec<Weak<Shared>>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+pub(super) struct PlatformCoalescedMouse {}
+
+impl PlatformCoalescedMouse {
+    pub async fn new(shared: &Arc<Shared>) -> Self {
+        MICE.lock().unwrap().push(Arc::downgrade(shared));
+        PlatformCoalescedMouse {}
+    }
+}
+
+fn each_mouse(f: impl Fn(&Shared)) {
+    MICE.lock().unwrap().retain(|weak| match weak.upgrade() {
+        Some(shared) => {
+            f(&shared);
+            true
+        }
+        None => false,
+    });
+}
+
+pub(crate) fn report_position(pos_x: f64, pos_y: f64, window_width: f64, window_height: f64) {
+    let location = MouseWindowLocation::new(pos_x, pos_y, window_width, window_height, None);
+    each_mouse(|shared| shared.set_window_location(location));
+}
+
+pub(crate) fn report_button(button: u8, down: bool) {
+    each_mouse(|shared| shared.set_key_state(button, down, std::ptr::null_mut()));
+}
+
+pub(crate) fn report_scroll(delta_x: f64, delta_y: f64) {
+    each_mouse(|shared| {
+        shared.add_scroll_delta(delta_x, delta_y, std::ptr::null_mut());
+        shared.add_scroll_pixels(delta_x, delta_y, std::ptr::null_mut());
+    });
+}
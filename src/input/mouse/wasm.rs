@@ -1,17 +1,21 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::input::Window;
-use crate::input::keyboard::wasm::ARBITRARY_WINDOW_PTR;
-use crate::input::mouse::MouseWindowLocation;
+use crate::input::keyboard::wasm::{set_key_window, target_canvas, target_window};
+use crate::input::mouse::{
+    MOUSE_BUTTON_BACK, MOUSE_BUTTON_FORWARD, MouseWindowLocation, SCROLL_LINE_PIXELS,
+};
 use std::ptr::NonNull;
 use std::sync::Arc;
 use wasm_bindgen::prelude::*;
-use web_sys::{MouseEvent, WheelEvent};
+use web_sys::{MouseEvent, PointerEvent, WheelEvent};
 
 fn js_button_to_rust(button: i16) -> u8 {
     match button {
         0 => 0,
         1 => 2,
         2 => 1,
+        3 => MOUSE_BUTTON_BACK,
+        4 => MOUSE_BUTTON_FORWARD,
         _ => button as u8,
     }
 }
@@ -24,7 +28,10 @@ impl PlatformCoalescedMouse {
         let shared = shared.clone();
 
         crate::application::on_main_thread("PlatformCoalescedMouse setup".to_string(), move || {
-            let window = web_sys::window().expect("no global window exists");
+            //without a DOM (say, under Node) there's no mouse; conformance hooks can inject one
+            let Some(window) = web_sys::window() else {
+                return PlatformCoalescedMouse {};
+            };
             let document = window.document().expect("no document on window");
 
             let weak = Arc::downgrade(&shared);
@@ -35,19 +42,27 @@ impl PlatformCoalescedMouse {
             // Mouse move callback
             let mousemove_callback = Closure::wrap(Box::new(move |event: MouseEvent| {
                 if let Some(shared) = weak.upgrade() {
-                    let window = web_sys::window().expect("no global window exists");
-                    let width = window
-                        .inner_width()
-                        .expect("failed to get width")
-                        .as_f64()
-                        .unwrap_or(0.0);
-
-                    let height = window
-                        .inner_height()
-                        .expect("failed to get height")
-                        .as_f64()
-                        .unwrap_or(0.0);
-                    let window = Some(Window(NonNull::new(ARBITRARY_WINDOW_PTR).unwrap()));
+                    let (width, height) = match target_canvas(&event) {
+                        Some(canvas) => {
+                            (canvas.client_width() as f64, canvas.client_height() as f64)
+                        }
+                        None => {
+                            let window = web_sys::window().expect("no global window exists");
+                            let width = window
+                                .inner_width()
+                                .expect("failed to get width")
+                                .as_f64()
+                                .unwrap_or(0.0);
+
+                            let height = window
+                                .inner_height()
+                                .expect("failed to get height")
+                                .as_f64()
+                                .unwrap_or(0.0);
+                            (width, height)
+                        }
+                    };
+                    let window = NonNull::new(target_window(&event)).map(Window);
 
                     shared.set_window_location(MouseWindowLocation::new(
                         event.offset_x() as f64,
@@ -67,13 +82,30 @@ impl PlatformCoalescedMouse {
                 .expect("Can't add event listener");
             mousemove_callback.forget();
 
+            //capture the pointer for the canvas it went down on, so a drag that leaves the
+            //canvas keeps reporting positions relative to it; the browser releases it on
+            //pointerup.  Draggable canvases are starting a drag-and-drop, which captures itself.
+            let pointerdown_callback = Closure::wrap(Box::new(move |event: PointerEvent| {
+                if let Some(canvas) = target_canvas(&event)
+                    && !canvas.draggable()
+                {
+                    _ = canvas.set_pointer_capture(event.pointer_id());
+                }
+            })
+                as Box<dyn FnMut(PointerEvent)>);
+            document
+                .add_event_listener_with_callback(
+                    "pointerdown",
+                    pointerdown_callback.as_ref().unchecked_ref(),
+                )
+                .expect("Can't add event listener");
+            pointerdown_callback.forget();
+
             let mousedown_callback = Closure::wrap(Box::new(move |event: MouseEvent| {
+                let window = target_window(&event);
+                set_key_window(window);
                 if let Some(shared) = weak_down.upgrade() {
-                    shared.set_key_state(
-                        js_button_to_rust(event.button()),
-                        true,
-                        ARBITRARY_WINDOW_PTR,
-                    );
+                    shared.set_key_state(js_button_to_rust(event.button()), true, window);
                 }
             }) as Box<dyn FnMut(MouseEvent)>);
             document
@@ -89,7 +121,7 @@ impl PlatformCoalescedMouse {
                     shared.set_key_state(
                         js_button_to_rust(event.button()),
                         false,
-                        ARBITRARY_WINDOW_PTR,
+                        target_window(&event),
                     );
                 }
             }) as Box<dyn FnMut(MouseEvent)>);
@@ -111,8 +143,25 @@ impl PlatformCoalescedMouse {
                     _ => (raw_x, raw_y),
                 };
 
+                //DOM_DELTA_PAGE scrolls by the viewport
+                let (page_x, page_y) = web_sys::window()
+                    .map(|w| {
+                        let size = |v: Result<JsValue, JsValue>| {
+                            v.ok().and_then(|v| v.as_f64()).unwrap_or(0.0)
+                        };
+                        (size(w.inner_width()), size(w.inner_height()))
+                    })
+                    .unwrap_or_default();
+                let (pixels_x, pixels_y) = match mode {
+                    1 => (raw_x * SCROLL_LINE_PIXELS, raw_y * SCROLL_LINE_PIXELS),
+                    2 => (raw_x * page_x, raw_y * page_y),
+                    _ => (raw_x, raw_y),
+                };
+
                 if let Some(shared) = weak_wheel.upgrade() {
-                    shared.add_scroll_delta(x, y, ARBITRARY_WINDOW_PTR);
+                    let window = target_window(&event);
+                    shared.add_scroll_delta(x, y, window);
+                    shared.add_scroll_pixels(pixels_x, pixels_y, window);
                 }
             }) as Box<dyn FnMut(WheelEvent)>);
             document
@@ -1,15 +1,22 @@
 // SPDX-License-Identifier: MPL-2.0
+use crate::application::time::Duration;
 use crate::input::Window;
-use crate::input::mouse::{MouseWindowLocation, Shared};
+use crate::input::mouse::{
+    MOUSE_BUTTON_BACK, MOUSE_BUTTON_FORWARD, MouseWindowLocation, SCROLL_LINE_PIXELS, Shared,
+};
 use std::mem::MaybeUninit;
 use std::ptr::NonNull;
 use std::sync::{Arc, Mutex, OnceLock, Weak};
 use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
 use windows::Win32::Graphics::Gdi::ClientToScreen;
+use windows::Win32::UI::HiDpi::{GetDpiForWindow, GetSystemMetricsForDpi};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetDoubleClickTime, ReleaseCapture, SetCapture};
 use windows::Win32::UI::WindowsAndMessaging::{
-    GetClientRect, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL,
-    WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
-    XBUTTON1, XBUTTON2,
+    GetClientRect, SM_CXDOUBLECLK, SM_CYDOUBLECLK, SPI_GETWHEELSCROLLCHARS,
+    SPI_GETWHEELSCROLLLINES, SYSTEM_PARAMETERS_INFO_ACTION, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    SystemParametersInfoW, WHEEL_DELTA, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP,
+    WM_MOUSEHWHEEL, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_XBUTTONDOWN,
+    WM_XBUTTONUP, XBUTTON1, XBUTTON2,
 };
 
 fn get_x_lparam(lparam: LPARAM) -> i16 {
@@ -28,6 +35,52 @@ fn get_wheel_delta_wparam(wparam: WPARAM) -> i16 {
     ((wparam.0 & 0xFFFF_0000) >> 16) as u16 as i16
 }
 
+/// `SPI_GETWHEELSCROLLLINES` value meaning "scroll one screen per notch".
+const WHEEL_PAGESCROLL: u32 = u32::MAX;
+/// Windows' default for both wheel settings.
+const DEFAULT_WHEEL_SCROLL: u32 = 3;
+
+/// Lines (or characters) to scroll per `WHEEL_DELTA`, per the user's mouse settings.
+///
+/// Read on every message so changes in Settings apply immediately.
+fn wheel_scroll_setting(action: SYSTEM_PARAMETERS_INFO_ACTION) -> f64 {
+    let mut value: u32 = DEFAULT_WHEEL_SCROLL;
+    let result = unsafe {
+        SystemParametersInfoW(
+            action,
+            0,
+            Some(&mut value as *mut u32 as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    match (result, value) {
+        (Err(_), _) => DEFAULT_WHEEL_SCROLL as f64,
+        //we don't know how many lines fit on a page, so fall back to the default
+        (Ok(()), WHEEL_PAGESCROLL) => DEFAULT_WHEEL_SCROLL as f64,
+        (Ok(()), value) => value as f64,
+    }
+}
+
+/// Converts a wheel delta to lines.  High-resolution wheels send deltas smaller than
+/// `WHEEL_DELTA`, which come out as fractional lines.
+fn wheel_lines(delta: i16, action: SYSTEM_PARAMETERS_INFO_ACTION) -> f64 {
+    delta as f64 / WHEEL_DELTA as f64 * wheel_scroll_setting(action)
+}
+
+/// The user's double-click speed, and half the double-click rectangle in logical pixels.
+///
+/// Read on every press, like the wheel settings.
+pub(super) fn double_click_limits() -> (Duration, f64) {
+    let time = unsafe { GetDoubleClickTime() };
+    //at 96 DPI, logical and physical pixels agree
+    let width = unsafe { GetSystemMetricsForDpi(SM_CXDOUBLECLK, 96) };
+    let height = unsafe { GetSystemMetricsForDpi(SM_CYDOUBLECLK, 96) };
+    (
+        Duration::from_millis(time as u64),
+        width.max(height) as f64 / 2.0,
+    )
+}
+
 struct MouseState {
     shareds: Vec<Weak<Shared>>,
 }
@@ -66,6 +119,27 @@ fn apply_all<F: Fn(&Shared)>(f: F) {
 }
 
 static MOUSE_STATE: OnceLock<Mutex<MouseState>> = OnceLock::new();
+
+/// `MK_LBUTTON | MK_RBUTTON | MK_MBUTTON | MK_XBUTTON1 | MK_XBUTTON2`, the buttons a mouse
+/// message's `wParam` says are held.
+const MK_BUTTONS: usize = 0x0001 | 0x0002 | 0x0010 | 0x0020 | 0x0040;
+
+/// Captures the mouse while a button is held, so a drag that leaves `hwnd` keeps sending it
+/// `WM_MOUSEMOVE`s, and the release, until the last button comes up.
+fn update_capture(hwnd: HWND, msg: u32, w_param: WPARAM) {
+    match msg {
+        WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN | WM_XBUTTONDOWN => {
+            unsafe { SetCapture(hwnd) };
+        }
+        WM_LBUTTONUP | WM_RBUTTONUP | WM_MBUTTONUP | WM_XBUTTONUP
+            if w_param.0 & MK_BUTTONS == 0 =>
+        {
+            //fails if the capture was already lost, which is fine
+            _ = unsafe { ReleaseCapture() };
+        }
+        _ => {}
+    }
+}
 /**
 Provide windows key events to raw_input.
 
@@ -73,6 +147,7 @@ Provide windows key events to raw_input.
 If we processed the message, returns LRESULT(0).  Otherwise returns non-zero.
 */
 pub(crate) fn window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    update_capture(hwnd, msg, w_param);
     match msg {
         msg if msg == WM_MOUSEMOVE => {
             let window = NonNull::new(hwnd.0).map(Window);
@@ -87,11 +162,16 @@ pub(crate) fn window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM
             unsafe { GetClientRect(hwnd, rect.as_mut_ptr()) }.expect("failed to get client rect");
 
             let rect = unsafe { rect.assume_init() };
+            //logical, like the window's size
+            let scale = match unsafe { GetDpiForWindow(hwnd) } {
+                0 => 1.0,
+                dpi => dpi as f64 / 96.0,
+            };
             let rel_mouse = MouseWindowLocation::new(
-                x as f64,
-                y as f64,
-                rect.right as f64,
-                rect.bottom as f64,
+                x as f64 / scale,
+                y as f64 / scale,
+                rect.right as f64 / scale,
+                rect.bottom as f64 / scale,
                 window,
             );
 
@@ -139,11 +219,9 @@ pub(crate) fn window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM
         msg if msg == WM_XBUTTONDOWN => {
             let xbutton = get_xbutton_wparam(w_param);
             let key = match xbutton {
-                x if x == XBUTTON1 => 3,
-                x if x == XBUTTON2 => 4,
-                _ => {
-                    unimplemented!("Unknown xbutton {:?}", xbutton)
-                }
+                x if x == XBUTTON1 => MOUSE_BUTTON_BACK,
+                x if x == XBUTTON2 => MOUSE_BUTTON_FORWARD,
+                _ => return LRESULT(1),
             };
             apply_all(|shared| {
                 shared.set_key_state(key, true, hwnd.0);
@@ -153,11 +231,9 @@ pub(crate) fn window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM
         msg if msg == WM_XBUTTONUP => {
             let xbutton = get_xbutton_wparam(w_param);
             let key = match xbutton {
-                x if x == XBUTTON1 => 3,
-                x if x == XBUTTON2 => 4,
-                _ => {
-                    unimplemented!("Unknown xbutton {:?}", xbutton)
-                }
+                x if x == XBUTTON1 => MOUSE_BUTTON_BACK,
+                x if x == XBUTTON2 => MOUSE_BUTTON_FORWARD,
+                _ => return LRESULT(1),
             };
             apply_all(|shared| {
                 shared.set_key_state(key, false, hwnd.0);
@@ -165,18 +241,23 @@ pub(crate) fn window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM
             LRESULT(0)
         }
         msg if msg == WM_MOUSEWHEEL => {
-            //todo: should this be scaled in some way?
             let delta = get_wheel_delta_wparam(w_param);
+            let lines = wheel_lines(delta, SPI_GETWHEELSCROLLLINES);
             apply_all(|shared| {
                 shared.add_scroll_delta(0.0, delta as f64, hwnd.0);
+                shared.add_scroll_lines(0.0, lines);
+                //positive is the wheel turning away from the user, which scrolls up
+                shared.add_scroll_pixels(0.0, -lines * SCROLL_LINE_PIXELS, hwnd.0);
             });
             LRESULT(0)
         }
         msg if msg == WM_MOUSEHWHEEL => {
-            //todo: should this be scaled in some way?
             let delta = get_wheel_delta_wparam(w_param);
+            let lines = wheel_lines(delta, SPI_GETWHEELSCROLLCHARS);
             apply_all(|shared| {
                 shared.add_scroll_delta(delta as f64, 0.0, hwnd.0);
+                shared.add_scroll_lines(lines, 0.0);
+                shared.add_scroll_pixels(lines * SCROLL_LINE_PIXELS, 0.0, hwnd.0);
             });
             LRESULT(0)
         }
@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Text entry, including input method (IME) composition.
+//!
+//! [`crate::input::keyboard`] reports physical keys, which is what games and shortcuts want,
+//! but turning keys into text means knowing the layout, dead keys, and input methods that
+//! assemble one character from several keystrokes.  The platform already knows all of that,
+//! so [`TextInput`] asks it for the text instead.
+//!
+//! Typing delivers [`TextEvent::Commit`] with the text to insert.  An input method (say, for
+//! Japanese or Chinese) first shows text that isn't final yet, the *preedit*, which arrives as
+//! [`TextEvent::Preedit`].  Each preedit replaces the last; the composition ends with a
+//! [`TextEvent::Commit`] of the final text, or [`TextEvent::Cancel`] if the user abandoned it.
+//! Editors usually draw the preedit underlined at the caret.
+//!
+//! Dead keys and compose sequences work the same way.  Typing ´ on a layout where it's a dead
+//! key sends a preedit of `"´"`, so an editor can show that an accent is pending; typing e then
+//! commits `"é"`.  If the next key doesn't combine, both characters are committed.
+//!
+//! Text is coalesced from every window in the app, like [`crate::input::keyboard::Keyboard`].
+//! Control characters (Return, Tab, Backspace, ...) aren't text; watch the keyboard for those.
+//!
+//! # Platform specifics
+//!
+//! | Platform | Backend                                          |
+//! |----------|--------------------------------------------------|
+//! | Windows  | `WM_CHAR` and `WM_DEADCHAR`, and IMM32           |
+//! | macOS    | `NSTextInputClient` on the window's content view |
+//! | Linux    | `zwp_text_input_v3`                              |
+//! | wasm32   | `keydown`, and the DOM's composition events      |
+//!
+//! * On Linux, text comes from the compositor's input method (IBus or fcitx, the defaults on
+//!   GNOME and KDE).  A compositor without `zwp_text_input_v3`, or without an input method
+//!   running, reports no typed text.  Dead keys and the Compose key work either way: the input
+//!   method composes them if there is one, and otherwise the locale's XKB compose table does.
+//!   A sequence that doesn't match commits what its keys type on their own; one ended by a key
+//!   that types nothing (an arrow, Escape, or losing focus) is cancelled.
+//! * On wasm32, browsers only compose into editable elements, so composition is reported only
+//!   while one has focus, such as the one [`crate::window::Window::set_virtual_keyboard_visible`]
+//!   focuses.  Typed characters are reported either way.  Browsers don't say
+//!   which dead key is pending, so dead keys only show up as a preedit where the browser
+//!   composes them (as on macOS); elsewhere only the combined character is reported.
+//! * iOS and Android don't report text yet.
+//!
+//! # Example
+//!
+//! ```
+//! # // ALLOW_NORUN_DOCTEST: Requires main thread initialization
+//! # async fn example() {
+//! use app_window::input::text::{TextEvent, TextInput};
+//!
+//! let mut text = TextInput::new().await;
+//! let mut document = String::new();
+//! loop {
+//!     match text.next().await {
+//!         TextEvent::Commit(committed) => document.push_str(&committed),
+//!         TextEvent::Preedit { text, .. } => println!("composing {text}"),
+//!         TextEvent::Cancel => println!("composition cancelled"),
+//!         _ => {}
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! [`TextInput`]: crate::input::text::TextInput
+//! [`TextEvent::Commit`]: crate::input::text::TextEvent::Commit
+//! [`TextEvent::Preedit`]: crate::input::text::TextEvent::Preedit
+//! [`TextEvent::Cancel`]: crate::input::text::TextEvent::Cancel
+
+use crate::application::is_main_thread_running;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Poll, Waker};
+
+#[cfg(target_os = "macos")]
+pub(crate) mod macos;
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) mod wasm;
+
+#[cfg(target_os = "windows")]
+pub(crate) mod windows;
+
+/// Most events a [`TextInput`] holds before dropping the oldest.
+const TEXT_EVENT_CAPACITY: usize = 256;
+
+/// Text entered by the user; see [`TextInput`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TextEvent {
+    /// Text to insert at the caret.  This also ends any composition, replacing its preedit.
+    Commit(String),
+    /// An input method is composing text that isn't final yet, or a dead key or compose
+    /// sequence is waiting for its next key.  Replaces the previous preedit, if any; an empty
+    /// string means nothing is pending, though the composition continues.
+    Preedit {
+        /// The text being composed.
+        text: String,
+        /// Where the input method's caret is, as a byte range into `text`.  An empty range is
+        /// a caret; otherwise it's the part being converted.  `None` means don't draw one.
+        cursor: Option<Range<usize>>,
+    },
+    /// The composition ended without committing anything.  Remove the preedit.
+    Cancel,
+}
+
+#[derive(Debug, Default)]
+struct TextEventQueue {
+    events: VecDeque<TextEvent>,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+struct Listeners {
+    queues: Vec<Weak<Mutex<TextEventQueue>>>,
+    /// Whether a preedit has been sent since the last commit or cancel.
+    composing: bool,
+}
+
+static LISTENERS: Mutex<Listeners> = Mutex::new(Listeners {
+    queues: Vec::new(),
+    composing: false,
+});
+
+impl Listeners {
+    fn send(&mut self, event: TextEvent) {
+        self.queues.retain(|listener| {
+            let Some(queue) = listener.upgrade() else {
+                return false;
+            };
+            let mut queue = queue.lock().unwrap();
+            if queue.events.len() == TEXT_EVENT_CAPACITY {
+                queue.events.pop_front();
+            }
+            queue.events.push_back(event.clone());
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+            true
+        });
+    }
+}
+
+/// Reports committed text.  Empty text is dropped, but still ends the composition.
+#[cfg_attr(
+    any(target_os = "ios", target_os = "android", plugin_backend),
+    allow(dead_code)
+)]
+pub(crate) fn commit(text: &str) {
+    let mut listeners = LISTENERS.lock().unwrap();
+    listeners.composing = false;
+    if !text.is_empty() {
+        listeners.send(TextEvent::Commit(text.to_string()));
+    }
+}
+
+/// Reports the preedit.  An empty preedit outside a composition is dropped.
+#[cfg_attr(
+    any(target_os = "ios", target_os = "android", plugin_backend),
+    allow(dead_code)
+)]
+pub(crate) fn preedit(text: &str, cursor: Option<Range<usize>>) {
+    let mut listeners = LISTENERS.lock().unwrap();
+    if text.is_empty() && !listeners.composing {
+        return;
+    }
+    listeners.composing = true;
+    listeners.send(TextEvent::Preedit {
+        text: text.to_string(),
+        cursor,
+    });
+}
+
+/// Reports the end of a composition; if nothing was committed, that's a [`TextEvent::Cancel`].
+#[cfg_attr(
+    any(target_os = "ios", target_os = "android", plugin_backend),
+    allow(dead_code)
+)]
+pub(crate) fn end_composition() {
+    let mut listeners = LISTENERS.lock().unwrap();
+    if std::mem::take(&mut listeners.composing) {
+        listeners.send(TextEvent::Cancel);
+    }
+}
+
+/// Text entered into any of the app's windows, in order.
+///
+/// If the events aren't read, the oldest are dropped once 256 are waiting.
+#[derive(Debug)]
+pub struct TextInput {
+    queue: Arc<Mutex<TextEventQueue>>,
+}
+
+impl TextInput {
+    /// Starts receiving text.  Only text entered after this returns is reported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the main thread has not been initialized via `app_window::application::main()`.
+    pub async fn new() -> Self {
+        assert!(
+            is_main_thread_running(),
+            "Main thread must be started before creating text input"
+        );
+        #[cfg(target_arch = "wasm32")]
+        wasm::install().await;
+        Self::subscribe()
+    }
+
+    fn subscribe() -> Self {
+        let queue = Arc::new(Mutex::new(TextEventQueue::default()));
+        LISTENERS
+            .lock()
+            .unwrap()
+            .queues
+            .push(Arc::downgrade(&queue));
+        TextInput { queue }
+    }
+
+    /// Waits for the next event.
+    pub async fn next(&mut self) -> TextEvent {
+        std::future::poll_fn(|cx| {
+            let mut queue = self.queue.lock().unwrap();
+            match queue.events.pop_front() {
+                Some(event) => Poll::Ready(event),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Returns the next event, if one is waiting.
+    pub fn try_next(&mut self) -> Option<TextEvent> {
+        self.queue.lock().unwrap().events.pop_front()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::{TextEvent, TextInput, commit, end_composition, preedit};
+    use std::sync::{Mutex, MutexGuard};
+
+    /// A listener, and the right to be the only test sending text while the guard is held;
+    /// every listener hears every test.
+    pub(crate) fn listen() -> (MutexGuard<'static, ()>, TextInput) {
+        static SENDING: Mutex<()> = Mutex::new(());
+        let guard = SENDING.lock().unwrap_or_else(|e| e.into_inner());
+        //a test that failed may have left a composition going
+        end_composition();
+        (guard, TextInput::subscribe())
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn composition_ends_in_commit_or_cancel() {
+        let (_sending, mut text) = listen();
+        //not composing, so nothing to cancel
+        preedit("", None);
+        end_composition();
+        commit("a");
+        assert_eq!(text.try_next(), Some(TextEvent::Commit("a".to_string())));
+        assert_eq!(text.try_next(), None);
+
+        preedit("か", Some(3..3));
+        commit("蚊");
+        end_composition();
+        assert_eq!(
+            text.try_next(),
+            Some(TextEvent::Preedit {
+                text: "か".to_string(),
+                cursor: Some(3..3)
+            })
+        );
+        assert_eq!(text.try_next(), Some(TextEvent::Commit("蚊".to_string())));
+        assert_eq!(text.try_next(), None);
+
+        preedit("k", None);
+        preedit("", None);
+        end_composition();
+        assert!(matches!(text.try_next(), Some(TextEvent::Preedit { .. })));
+        assert!(matches!(text.try_next(), Some(TextEvent::Preedit { .. })));
+        assert_eq!(text.try_next(), Some(TextEvent::Cancel));
+        assert_eq!(text.try_next(), None);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn dead_key_then_cancel() {
+        let (_sending, mut text) = listen();
+        preedit("´", None);
+        end_composition();
+        assert_eq!(
+            text.try_next(),
+            Some(TextEvent::Preedit {
+                text: "´".to_string(),
+                cursor: None
+            })
+        );
+        assert_eq!(text.try_next(), Some(TextEvent::Cancel));
+        //the accent is gone, so nothing is left to cancel
+        end_composition();
+        assert_eq!(text.try_next(), None);
+    }
+}
@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Called by the window's content view, which implements `NSTextInputClient`; see Surface.swift.
+
+use std::ffi::{CStr, c_char};
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn raw_input_text_commit(text: *const c_char) {
+    let text = unsafe { CStr::from_ptr(text) };
+    super::commit(&text.to_string_lossy());
+}
+
+/// The cursor offsets are in bytes, or -1 for no cursor.
+#[unsafe(no_mangle)]
+unsafe extern "C" fn raw_input_text_preedit(
+    text: *const c_char,
+    cursor_start: i64,
+    cursor_end: i64,
+) {
+    let text = unsafe { CStr::from_ptr(text) };
+    let cursor = usize::try_from(cursor_start)
+        .ok()
+        .zip(usize::try_from(cursor_end).ok())
+        .map(|(start, end)| start..end);
+    super::preedit(&text.to_string_lossy(), cursor);
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn raw_input_text_cancel() {
+    super::end_composition();
+}
@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Text from `keydown`, and composition from the DOM's composition events.
+//!
+//! The listeners go on the document, once, the first time a [`super::TextInput`] is created.
+
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use web_sys::{CompositionEvent, Document, KeyboardEvent};
+
+thread_local! {
+    static INSTALLED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn listen<E: wasm_bindgen::convert::FromWasmAbi + 'static>(
+    document: &Document,
+    event: &str,
+    f: fn(E),
+) {
+    let callback = Closure::<dyn FnMut(E)>::new(f);
+    document
+        .add_event_listener_with_callback(event, callback.as_ref().unchecked_ref())
+        .expect("Can't add event listener");
+    callback.forget();
+}
+
+fn keydown(event: KeyboardEvent) {
+    //the composition events report these
+    if event.is_composing() {
+        return;
+    }
+    //shortcuts, though AltGr shows up as ctrl+alt
+    if event.meta_key() || (event.ctrl_key() && !event.alt_key()) {
+        return;
+    }
+    //otherwise it's a key name, like "Enter" or "Dead"
+    let key = event.key();
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && !c.is_control()
+    {
+        super::commit(&key);
+    }
+}
+
+fn composition_update(event: CompositionEvent) {
+    super::preedit(&event.data().unwrap_or_default(), None);
+}
+
+fn composition_end(event: CompositionEvent) {
+    match event.data() {
+        Some(data) if !data.is_empty() => super::commit(&data),
+        _ => super::end_composition(),
+    }
+}
+
+pub(super) async fn install() {
+    crate::application::on_main_thread("TextInput::install".to_string(), || {
+        if INSTALLED.replace(true) {
+            return;
+        }
+        //without a DOM (say, under Node) there's no text
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let document = window.document().expect("no document on window");
+        listen(&document, "keydown", keydown);
+        listen(&document, "compositionupdate", composition_update);
+        listen(&document, "compositionend", composition_end);
+    })
+    .await
+}
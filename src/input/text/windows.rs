@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Text from `WM_CHAR`, and composition from IMM32.
+//!
+//! The main loop calls `TranslateMessage`, so typing produces `WM_CHAR`s, one UTF-16 unit at a
+//! time.  Input methods instead send `WM_IME_COMPOSITION`; left to `DefWindowProcW`, its result
+//! would come back as `WM_CHAR`s too, so we take the result there and stop it.
+//!
+//! `TranslateMessage` also runs dead keys through the layout (`ToUnicodeEx`), sending
+//! `WM_DEADCHAR` with the pending accent and then a `WM_CHAR` with the combined character.
+//! A key that types nothing (an arrow), a control character (Escape), or losing focus sends no
+//! `WM_CHAR` to end it, so we cancel the accent ourselves, and drop it from the layout too.
+
+use std::cell::Cell;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::UI::Input::Ime::{
+    GCS_COMPSTR, GCS_CURSORPOS, GCS_RESULTSTR, IME_COMPOSITION_STRING, ImmGetCompositionStringW,
+    ImmGetContext, ImmReleaseContext,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetKeyboardLayout, MAPVK_VK_TO_CHAR, MAPVK_VK_TO_VSC, MapVirtualKeyExW, MapVirtualKeyW,
+    ToUnicodeEx, VIRTUAL_KEY, VK_CAPITAL, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN,
+    VK_MENU, VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT, VK_SPACE,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    WM_CHAR, WM_DEADCHAR, WM_IME_COMPOSITION, WM_IME_ENDCOMPOSITION, WM_KEYDOWN, WM_KILLFOCUS,
+};
+
+thread_local! {
+    /// The first half of a surrogate pair, waiting for its second `WM_CHAR`.
+    static HIGH_SURROGATE: Cell<Option<u16>> = const { Cell::new(None) };
+    /// The accent from `WM_DEADCHAR`, until a `WM_CHAR` or a cancel ends it.
+    static DEAD_KEY: Cell<Option<u16>> = const { Cell::new(None) };
+    /// A cancelled accent that the layout may still type, in the `WM_CHAR` already on its way.
+    static CANCELLED_ACCENT: Cell<Option<u16>> = const { Cell::new(None) };
+}
+
+/// Whether pressing `vk`, which types `character` (0 for nothing), cancels a pending dead key.
+///
+/// Modifiers don't, since Shift is how you type a capital under the accent.
+fn cancels_dead_key(vk: VIRTUAL_KEY, character: u32) -> bool {
+    let modifiers = [
+        VK_SHIFT,
+        VK_LSHIFT,
+        VK_RSHIFT,
+        VK_CONTROL,
+        VK_LCONTROL,
+        VK_RCONTROL,
+        VK_MENU,
+        VK_LMENU,
+        VK_RMENU,
+        VK_LWIN,
+        VK_RWIN,
+        VK_CAPITAL,
+    ];
+    if modifiers.contains(&vk) {
+        return false;
+    }
+    character == 0 || char::from_u32(character).is_some_and(char::is_control)
+}
+
+/// Drops a pending dead key from the layout, so it doesn't combine with the next key.
+fn flush_dead_key() {
+    let layout = unsafe { GetKeyboardLayout(0) };
+    let scan_code = unsafe { MapVirtualKeyExW(VK_SPACE.0 as u32, MAPVK_VK_TO_VSC, Some(layout)) };
+    let mut buffer = [0u16; 4];
+    //space types the accent on its own, which we throw away; negative means still pending
+    for _ in 0..2 {
+        let written = unsafe {
+            ToUnicodeEx(
+                VK_SPACE.0 as u32,
+                scan_code,
+                &[0; 256],
+                &mut buffer,
+                0,
+                Some(layout),
+            )
+        };
+        if written >= 0 {
+            break;
+        }
+    }
+}
+
+/// Cancels the pending dead key, if there is one, because `vk` was pressed.
+fn on_key_down(vk: VIRTUAL_KEY) {
+    let Some(accent) = DEAD_KEY.get() else {
+        return;
+    };
+    let character = unsafe { MapVirtualKeyW(vk.0 as u32, MAPVK_VK_TO_CHAR) };
+    if !cancels_dead_key(vk, character) {
+        return;
+    }
+    DEAD_KEY.set(None);
+    super::end_composition();
+    if character == 0 {
+        //no WM_CHAR is coming, so the layout still has it
+        flush_dead_key();
+    } else {
+        //TranslateMessage already ran, and may have typed it alongside the control character
+        CANCELLED_ACCENT.set(Some(accent));
+    }
+}
+
+/// One of the composition's strings, if it has that part.
+fn composition_string(hwnd: HWND, part: IME_COMPOSITION_STRING) -> Option<Vec<u16>> {
+    let context = unsafe { ImmGetContext(hwnd) };
+    if context.is_invalid() {
+        return None;
+    }
+    //the lengths are in bytes
+    let len = unsafe { ImmGetCompositionStringW(context, part, None, 0) };
+    let string = (len >= 0).then(|| {
+        let mut buffer = vec![0u16; len as usize / 2];
+        let read = unsafe {
+            ImmGetCompositionStringW(context, part, Some(buffer.as_mut_ptr().cast()), len as u32)
+        };
+        buffer.truncate(read.max(0) as usize / 2);
+        buffer
+    });
+    _ = unsafe { ImmReleaseContext(hwnd, context) };
+    string
+}
+
+/// Where the caret is in the composition, as a byte offset into `text`.
+fn cursor(hwnd: HWND, text: &[u16]) -> Option<usize> {
+    let context = unsafe { ImmGetContext(hwnd) };
+    if context.is_invalid() {
+        return None;
+    }
+    //in UTF-16 units, returned directly
+    let position = unsafe { ImmGetCompositionStringW(context, GCS_CURSORPOS, None, 0) };
+    _ = unsafe { ImmReleaseContext(hwnd, context) };
+    let position = usize::try_from(position).ok()?;
+    Some(String::from_utf16_lossy(text.get(..position)?).len())
+}
+
+fn on_char(unit: u16) {
+    DEAD_KEY.set(None);
+    if CANCELLED_ACCENT.take() == Some(unit) {
+        return;
+    }
+    let units = match HIGH_SURROGATE.take() {
+        Some(high) => vec![high, unit],
+        None if (0xD800..0xDC00).contains(&unit) => {
+            HIGH_SURROGATE.set(Some(unit));
+            return;
+        }
+        None => vec![unit],
+    };
+    let text: String = String::from_utf16_lossy(&units)
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    super::commit(&text);
+}
+
+/**
+Processes window text events.
+
+Returns LResult(0) if we handled the message, or nonzero otherwise.
+*/
+pub fn text_window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    match msg {
+        m if m == WM_CHAR => {
+            on_char(w_param.0 as u16);
+            LRESULT(0)
+        }
+        m if m == WM_DEADCHAR => {
+            //accents are all in the BMP, so there's no pair to wait for; the next WM_CHAR
+            //commits, ending the preedit
+            let accent = w_param.0 as u16;
+            DEAD_KEY.set(Some(accent));
+            CANCELLED_ACCENT.set(None);
+            super::preedit(&String::from_utf16_lossy(&[accent]), None);
+            LRESULT(0)
+        }
+        m if m == WM_KEYDOWN => {
+            on_key_down(VIRTUAL_KEY(w_param.0 as u16));
+            //the keyboard still needs to hear about it
+            LRESULT(1)
+        }
+        m if m == WM_KILLFOCUS => {
+            if DEAD_KEY.take().is_some() {
+                super::end_composition();
+                flush_dead_key();
+            }
+            LRESULT(1)
+        }
+        m if m == WM_IME_COMPOSITION => {
+            let flags = l_param.0 as u32;
+            let mut handled = false;
+            if flags & GCS_RESULTSTR.0 != 0
+                && let Some(result) = composition_string(hwnd, GCS_RESULTSTR)
+            {
+                super::commit(&String::from_utf16_lossy(&result));
+                handled = true;
+            }
+            if flags & GCS_COMPSTR.0 != 0
+                && let Some(composition) = composition_string(hwnd, GCS_COMPSTR)
+            {
+                let caret = if flags & GCS_CURSORPOS.0 != 0 {
+                    cursor(hwnd, &composition).map(|caret| caret..caret)
+                } else {
+                    None
+                };
+                super::preedit(&String::from_utf16_lossy(&composition), caret);
+            }
+            if handled { LRESULT(0) } else { LRESULT(1) }
+        }
+        m if m == WM_IME_ENDCOMPOSITION => {
+            super::end_composition();
+            //the IME's own window still needs to hear about it
+            LRESULT(1)
+        }
+        _ => LRESULT(1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DEAD_KEY, cancels_dead_key, text_window_proc};
+    use crate::input::text::TextEvent;
+    use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{VK_ESCAPE, VK_LEFT, VK_SHIFT};
+    use windows::Win32::UI::WindowsAndMessaging::{WM_DEADCHAR, WM_KEYDOWN};
+
+    #[test]
+    fn what_cancels_a_dead_key() {
+        assert!(cancels_dead_key(VK_LEFT, 0));
+        assert!(cancels_dead_key(VK_ESCAPE, 0x1b));
+        assert!(!cancels_dead_key(VK_SHIFT, 0));
+        assert!(!cancels_dead_key(VK_LEFT, 'e' as u32));
+    }
+
+    #[test]
+    fn dead_key_then_arrow_cancels() {
+        let (_sending, mut text) = crate::input::text::test::listen();
+        text_window_proc(
+            HWND::default(),
+            WM_DEADCHAR,
+            WPARAM('´' as usize),
+            LPARAM(0),
+        );
+        text_window_proc(
+            HWND::default(),
+            WM_KEYDOWN,
+            WPARAM(VK_LEFT.0 as usize),
+            LPARAM(0),
+        );
+        assert_eq!(
+            text.try_next(),
+            Some(TextEvent::Preedit {
+                text: "´".to_string(),
+                cursor: None
+            })
+        );
+        assert_eq!(text.try_next(), Some(TextEvent::Cancel));
+        assert_eq!(text.try_next(), None);
+        assert_eq!(DEAD_KEY.get(), None);
+    }
+}
@@ -6,14 +6,14 @@ A cross-platform window management crate with async-first APIs.
 ![logo](https://github.com/drewcrawford/app_window/raw/main/art/logo.png)
 
 `app_window` provides a modern alternative to winit for creating and managing windows across
-Windows, macOS, Linux, and WebAssembly. The crate's primary goal is to provide a unified,
+Windows, macOS, iOS, Linux, Android, and WebAssembly. The crate's primary goal is to provide a unified,
 async-first API that works seamlessly across platforms with wildly different threading
 requirements.
 
 # Key Features
 
 - **Async-first design**: All APIs are async functions that can be called from any thread
-- **Modern platform backends**: Win32 on Windows, AppKit on macOS, Wayland on Linux, Canvas on Web
+- **Modern platform backends**: Win32 on Windows, AppKit on macOS, UIKit on iOS, Wayland on Linux, NativeActivity on Android, Canvas on Web
 - **Unified threading model**: Works correctly whether the platform requires UI on the main thread or not
 - **Graphics API integration**: Provides `raw-window-handle` for wgpu, OpenGL, Vulkan, etc.
 - **Built-in input handling**: Cross-platform keyboard and mouse support
@@ -116,13 +116,74 @@ match WGPU_STRATEGY {
 }
 ```
 
+# Cargo Features
+
+Most features are on by default.  Apps that draw every pixel themselves (say, with wgpu) can
+turn off `default-features` and pick only what they need:
+
+- `input`: the [`input`] module for keyboard and mouse.  On Linux this pulls in `atspi`.
+- `accessibility`: lets apps publish an AccessKit tree for what they draw, with
+  [`Window::update_accessibility_tree`](window::Window::update_accessibility_tree), and
+  re-exports [`accesskit`] for building it.  On Linux it also publishes our window decorations.
+  Implies `decorations`.
+- `decorations` (Linux): draws client-side title bar buttons.  Without it, windows have no
+  title bar unless the compositor draws one.
+- `cursor-theme` (Linux): loads the user's cursor theme with `wayland-cursor`.  Without it,
+  the compositor picks the cursor, and [`Window::set_cursor`](window::Window::set_cursor)
+  isn't supported.
+- `software-present` (Linux): fills windows with a placeholder buffer until the app presents
+  its own.  Without it, a window isn't mapped until the app presents a frame.
+
+The off-by-default `portal` feature (Linux) uses xdg-desktop-portal over D-Bus (`zbus`) for
+[`dialog`]s, and to find out whether screen capture is available and hear about [`power`]
+changes.  It's off so apps that don't need them don't build a D-Bus stack.  Without it,
+[`dialog::pick_color`] and [`power::on_change`] are unsupported, and
+[`application::capture_permission_state`] assumes a portal.
+
+The off-by-default `tray` feature (Linux) shows [`tray`] icons as `StatusNotifierItem`s over
+D-Bus (`zbus`), which it's off to avoid building for apps without one.  Without it,
+[`tray::TrayIcon::new`] is unsupported.
+
+The off-by-default `notifications` feature (Linux) posts [`notification`]s to
+`org.freedesktop.Notifications` over D-Bus (`zbus`).  Without it, [`notification::post`] is
+unsupported.
+
+The off-by-default `unstable-backend` feature enables the `backend` module for out-of-tree
+platform backends.
+
+The off-by-default `headless` feature enables `executor::VirtualMainLoop`, a main loop stepped
+by hand, so the main thread executor can run on a thread without a platform event loop, such as
+in tests of code that spawns onto it.
+
+The off-by-default `serde` feature implements `serde::Serialize` for diagnostic types such as
+[`window::WindowDebugState`], so they can be attached to bug reports.  It also implements
+`serde::Deserialize` for [`window::WindowGeometry`], so window layout can be saved between
+launches.
+
+The off-by-default `wasm-slim` feature trims the wasm32 build for web apps that care about
+download size.  Main-thread dispatch skips creating a `logwise` task context, unrecoverable
+browser errors abort instead of panicking with a formatted message, and Node.js isn't detected,
+so it's for browsers only.  On `examples/alert.rs` built with `opt-level = "z"` and stripped,
+it saves about 3 KB (0.3%) of the `.wasm`; most of a binary is the app, std, and
+wasm-bindgen.  It has no effect on other targets.
+
+The off-by-default `conformance` feature adds hooks for the backend conformance suite in
+`tests/conformance`; apps shouldn't need it.
+
+```toml
+[dependencies]
+app_window = { version = "0.3", default-features = false, features = ["input"] }
+```
+
 # Threading Model
 
 This crate abstracts over platform threading differences:
 
 - **macOS**: All UI operations dispatched to main thread via GCD
+- **iOS**: As on macOS; the main thread runs `UIApplicationMain`
 - **Windows**: UI operations can run on any thread
 - **Linux (Wayland)**: Compositor-dependent, handled per-connection
+- **Android**: The activity's UI thread is the main thread; we run on its looper
 - **WebAssembly**: Single-threaded, operations run directly
 
 You write the same async code for all platforms:
@@ -272,9 +333,40 @@ See `examples/gpu.rs` for a complete wgpu integration example.
 |----------|---------|--------|-------|
 | Windows  | Win32 API | ✅ Stable | Full async support, relaxed threading |
 | macOS    | AppKit via Swift | ✅ Stable | Main thread UI, Swift interop |
+| iOS      | UIKit via Swift | 🧪 Experimental | Windows fill the scene, touch acts as mouse |
 | Linux    | Wayland | ✅ Stable | Client-side decorations, compositor-dependent |
+| Android  | NativeActivity | 🧪 Experimental | One window per activity, touch acts as mouse |
 | Web      | Canvas API | ✅ Stable | Requires atomics & bulk memory features |
 
+## Android
+
+Android starts apps by loading their library and calling into it, rather than running `main`.
+Build your app as a `cdylib`, package it with a `NativeActivity`, and export an `android_main`
+for app_window to call once the activity exists:
+
+```ignore
+# // ALLOW_IGNORE_DOCTEST: only links on Android
+#[unsafe(no_mangle)]
+fn android_main() {
+    app_window::application::main(|| {
+        // as on other platforms
+    });
+}
+```
+
+The activity owns a single native window that the system destroys while the app is paused.
+Install a callback with [`application::set_lifecycle_callback`] to stop rendering on
+[`application::LifecycleEvent::SurfaceLost`] and fetch a fresh surface on
+[`application::LifecycleEvent::SurfaceCreated`].  `GameActivity` isn't supported yet.
+
+## iOS
+
+iOS shares the macOS backend and Swift package; the view under each window is backed by a
+`CAMetalLayer`, and [`surface::Surface::raw_window_handle`] returns a `UiKit` handle.
+[`application::main`] calls `UIApplicationMain`, so call it from your app's `main` as usual.
+Windows ignore their requested position and size and fill the first connected scene, and the
+event loop never returns because iOS apps don't quit themselves.
+
 # Performance Considerations
 
 - **Lazy surface creation**: Surfaces are only allocated when requested via `window.surface()`
@@ -386,18 +478,303 @@ pub mod coordinates;
 /// ```
 pub mod surface;
 
+/// Outgoing drag-and-drop.
+///
+/// This module provides the types used by [`window::Window::start_drag`] to drag content
+/// out of your window and into other applications.
+///
+/// A drag is described by [`drag::DragData`], which is either an opaque byte payload tagged
+/// with a MIME type, or a list of file paths.  An optional [`drag::DragImage`] is shown under
+/// the cursor while the drag is in progress.
+///
+/// Every platform requires that a drag begins in response to a mouse button press.  Call
+/// `start_drag` while the primary button is held down over the window (typically after the
+/// mouse has moved a few pixels).
+///
+/// Drags copy by default.  [`window::Window::start_drag_with_actions`] also lets targets move
+/// or link the data, and returns a [`drag::DragSession`] that says which [`drag::DragAction`]
+/// they chose, so a file manager knows whether to remove what was dragged.
+///
+/// # Platform support
+///
+/// | Platform | Backend | Drag image |
+/// |----------|---------|------------|
+/// | Linux    | `wl_data_source` + `wl_data_device.start_drag` | Yes |
+/// | Windows  | OLE `DoDragDrop` | Ignored |
+/// | macOS    | `NSDraggingSession` | Yes |
+/// | iOS      | Unsupported | - |
+/// | wasm32   | `dragstart` + `DataTransfer` | Yes |
+/// | Android  | Unsupported | - |
+///
+/// On wasm32, browsers only allow a drag to begin from their own `dragstart` event.  Calling
+/// `start_drag` arms the canvas so that the browser's next drag gesture carries your data.
+/// File paths can't be dragged out of a browser, so [`drag::DragData::Paths`] returns
+/// [`drag::DragError::Unsupported`] there.
+///
+/// # Example
+/// ```
+/// # async fn example(window: &app_window::window::Window) {
+/// use app_window::drag::{DragData, DragImage};
+///
+/// let data = DragData::bytes("text/plain;charset=utf-8", "Hello from app_window");
+/// // a 1x1 opaque red pixel
+/// let image = DragImage::new(1, 1, vec![0xFF, 0, 0, 0xFF]).unwrap();
+/// if let Err(e) = window.start_drag(data, Some(image)).await {
+///     eprintln!("Can't start drag: {e}");
+/// }
+/// # }
+/// ```
+pub mod drag;
+
+/// Hints that tune how the OS treats the application's audio.
+///
+/// Games and media apps usually want their audio to keep playing unchanged when the window
+/// loses focus or another app starts a voice call.  [`audio::set_hints`] asks the OS for that,
+/// and [`audio::capabilities`] reports what the current platform can do.
+///
+/// # Platform support
+///
+/// | Platform | `avoid_ducking` | `background_audio` |
+/// |----------|-----------------|--------------------|
+/// | Windows  | Supported: `IAudioSessionControl2::SetDuckingPreference` | Not needed |
+/// | macOS    | Not needed | Supported: opts out of App Nap |
+/// | iOS      | Unsupported | Supported: `AVAudioSession` playback category |
+/// | Linux    | Not needed | Not needed |
+/// | wasm32   | Not needed | Unsupported: browsers decide |
+/// | Android  | Unsupported | Unsupported |
+///
+/// # Example
+/// ```
+/// # async fn example() {
+/// use app_window::audio::{AudioHints, set_hints};
+///
+/// set_hints(AudioHints::new().avoid_ducking(true).background_audio(true))
+///     .await
+///     .expect("Can't set audio hints");
+/// # }
+/// ```
+pub mod audio;
+
+/// Native system dialogs.
+///
+/// [`dialog::pick_color`] opens the platform's eyedropper so the user can pick a color from
+/// anywhere on the screen, which is what design tools usually want without writing platform
+/// code.
+///
+/// # Platform support
+///
+/// | Platform | Backend |
+/// |----------|---------|
+/// | Linux    | xdg-desktop-portal `Screenshot.PickColor` (needs the `portal` feature) |
+/// | Windows  | A full-screen capture overlay; click to pick, Escape to cancel |
+/// | macOS    | `NSColorSampler` |
+/// | iOS      | Unsupported |
+/// | wasm32   | The `EyeDropper` API, where the browser has it |
+/// | Android  | Unsupported |
+///
+/// On wasm32, browsers only open the eyedropper in response to a user gesture such as a
+/// click.
+///
+/// # Example
+/// ```
+/// # async fn example() {
+/// if let Ok(color) = app_window::dialog::pick_color().await {
+///     let [r, g, b] = color.to_rgb8();
+///     println!("#{r:02x}{g:02x}{b:02x}");
+/// }
+/// # }
+/// ```
+pub mod dialog;
+
+/// Notifications when the system clipboard changes.
+///
+/// [`clipboard::on_change`] calls a handler whenever another app (or this one) puts something
+/// on the clipboard, which is what clipboard managers and "paste recent" menus are built on.
+///
+/// # Platform support
+///
+/// | Platform | Backend |
+/// |----------|---------|
+/// | Windows  | `AddClipboardFormatListener` |
+/// | macOS    | Polling `NSPasteboard`'s `changeCount` |
+/// | iOS      | Polling `UIPasteboard`'s `changeCount` |
+/// | Linux    | `ext-data-control`, or `wl_data_device` while one of our windows has focus |
+/// | wasm32   | `copy` and `cut` in the page, and `clipboardchange` where the browser has it |
+/// | Android  | Unsupported |
+///
+/// # Example
+/// ```
+/// # async fn example() {
+/// let _watch = app_window::clipboard::on_change(|| println!("the clipboard changed"))
+///     .await
+///     .expect("Can't watch the clipboard");
+/// # }
+/// ```
+pub mod clipboard;
+
+/// An icon in the system tray, with a tooltip and a menu.
+///
+/// [`tray::TrayIcon`] keeps a background app reachable without a window.  Clicks and menu
+/// choices arrive as [`tray::TrayEvent`]s on the main thread.
+///
+/// # Platform support
+///
+/// | Platform | Backend |
+/// |----------|---------|
+/// | macOS    | `NSStatusItem` |
+/// | Windows  | `Shell_NotifyIconW` |
+/// | Linux    | `StatusNotifierItem` and `com.canonical.dbusmenu` over D-Bus (`tray` feature) |
+/// | wasm32   | Accepted, but shows nothing |
+/// | iOS, Android | Unsupported |
+///
+/// # Example
+/// ```
+/// # async fn example(icon: app_window::window::IconData) {
+/// use app_window::tray::{TrayEvent, TrayIcon};
+///
+/// let _tray = TrayIcon::new(icon, "My App".to_string(), |event| {
+///     if event == TrayEvent::Clicked {
+///         println!("show the main window");
+///     }
+/// })
+/// .await
+/// .expect("Can't show a tray icon");
+/// # }
+/// ```
+pub mod tray;
+
+/// Desktop notifications, for telling the user about work that finished in the background.
+///
+/// [`notification::post`] shows a [`notification::Notification`] with a title, body and
+/// optional icon in the platform's notification center.
+///
+/// # Platform support
+///
+/// | Platform | Backend |
+/// |----------|---------|
+/// | macOS, iOS | `UNUserNotificationCenter` (bundled apps only) |
+/// | Windows  | WinRT toast notifications |
+/// | Linux    | `org.freedesktop.Notifications` over D-Bus (`notifications` feature) |
+/// | wasm32   | The web `Notification` API |
+/// | Android  | Unsupported |
+///
+/// # Example
+/// ```
+/// # async fn example(icon: app_window::window::IconData) {
+/// use app_window::notification::{Notification, post};
+///
+/// let mut notification = Notification::new("Backup complete", "1,024 files copied");
+/// notification.icon = Some(icon);
+/// _ = post(notification).await;
+/// # }
+/// ```
+pub mod notification;
+/// The system's power state, for renderers that want to draw less when it counts.
+///
+/// [`power::on_change`] reports the displays sleeping and waking, and the system starting or
+/// stopping a battery saver.  Whether each window can be seen is reported separately, as
+/// [`window::WindowEvent::Occluded`] and [`window::WindowEvent::Visible`].
+///
+/// # Platform support
+///
+/// | Platform | Backend |
+/// |----------|---------|
+/// | macOS    | `NSWorkspace` display sleep, and `NSProcessInfo` Low Power Mode |
+/// | iOS      | `NSProcessInfo` Low Power Mode |
+/// | Windows  | `RegisterPowerSettingNotification` |
+/// | Linux    | xdg-desktop-portal `PowerProfileMonitor` and `Inhibit` monitor (`portal` feature) |
+/// | wasm32, Android | Unsupported |
+///
+/// # Example
+/// ```
+/// # async fn example() {
+/// use app_window::power::{self, PowerEvent};
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// let paused = Arc::new(AtomicBool::new(false));
+/// let render_loop_paused = paused.clone();
+/// let _watch = power::on_change(move |event| match event {
+///     PowerEvent::DisplayOff => render_loop_paused.store(true, Ordering::Relaxed),
+///     PowerEvent::DisplayOn => render_loop_paused.store(false, Ordering::Relaxed),
+///     _ => {}
+/// })
+/// .await;
+/// # }
+/// ```
+pub mod power;
+
+/// A splash window to show while the app starts up.
+///
+/// [`splash::SplashWindow`] shows a color or image without touching the GPU, so it's on screen
+/// at once, even while the main window is still creating its device and pipelines.
+///
+/// # Platform support
+///
+/// | Platform | Drawn with | Closing |
+/// |----------|------------|---------|
+/// | Linux    | A shared memory buffer | Immediate; Wayland has no window opacity |
+/// | Windows  | GDI | Fades out |
+/// | macOS    | An `NSImageView` | Fades out |
+/// | iOS, Android, wasm32 | Not shown; use the platform's launch screen or the page itself | |
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::coordinates::Size;
+/// use app_window::dialog::Color;
+/// use app_window::splash::SplashWindow;
+///
+/// let splash = SplashWindow::show(Color::from_rgb8(0x20, 0x20, 0x28), Size::new(480.0, 320.0)).await;
+/// //...create the main window, set up the GPU, present the first frame...
+/// splash.close().await;
+/// # }
+/// ```
+pub mod splash;
+
+/// A diagnostic overlay showing frame rate, main thread latency and [`application::stats`].
+///
+/// [`overlay::DiagnosticOverlay`] is a small window drawn without the GPU, for watching an
+/// app's performance while debugging it.
+///
+/// # Platform support
+///
+/// | Platform | Drawn with | Stays on top |
+/// |----------|------------|--------------|
+/// | Linux    | A shared memory buffer | No; xdg-shell leaves placement to the compositor |
+/// | Windows  | GDI | Yes, as a topmost tool window |
+/// | macOS    | An `NSImageView` | Yes, at the floating window level |
+/// | iOS, Android, wasm32 | Not shown | |
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::overlay::DiagnosticOverlay;
+///
+/// let overlay = DiagnosticOverlay::show().await;
+/// loop {
+///     //...render and present...
+///     overlay.frame().await;
+/// }
+/// # }
+/// ```
+pub mod overlay;
+
 /// Cross-platform mouse and keyboard input handling.
 ///
 /// This module provides keyboard and mouse input functionality that integrates
 /// with app_window. It handles platform-specific input events and provides
-/// a unified API across Windows, macOS, Linux, and WebAssembly.
+/// a unified API across Windows, macOS, iOS, Linux, Android, and WebAssembly.
 ///
 /// # Keyboard Input
 ///
 /// The keyboard module uses physical key mappings rather than logical characters.
 /// This means [`input::keyboard::key::KeyboardKey`] represents actual physical keys on the
 /// keyboard (e.g., the key labeled 'A' on QWERTY), independent of keyboard layout.
-/// This approach is ideal for game controls and shortcuts but not for text input.
+/// This approach is ideal for game controls and shortcuts but not for text input; for that,
+/// use [`input::text::TextInput`], which also reports input method composition.
 ///
 /// Comprehensive key mappings include:
 /// - Standard alphanumeric keys (A-Z, 0-9) and symbol keys (brackets, quotes, etc.)
@@ -424,6 +801,9 @@ pub mod surface;
 /// let mouse = Mouse::coalesced().await;
 /// # }
 /// ```
+///
+/// Requires the `input` cargo feature (on by default).
+#[cfg(feature = "input")]
 pub mod input;
 
 /// Main thread executor for async operations.
@@ -432,6 +812,9 @@ pub mod input;
 /// required for UI operations on many platforms. The executor integrates with the
 /// native event loop to process both async tasks and platform events.
 ///
+/// [`executor::run_chunked`] splits heavier main thread work into time-budgeted chunks so
+/// events keep flowing while it runs.
+///
 /// # Example
 /// ```
 /// #[cfg(target_arch = "wasm32")] {
@@ -461,6 +844,25 @@ pub mod input;
 /// ```
 pub mod executor;
 
+/// The time source for app_window's own timing.
+///
+/// Dispatch budgets, slow operation reports and chunked work measure time with
+/// [`clock::now`].  Tests can install a [`clock::TestClock`] and advance it by hand instead of
+/// sleeping.
+///
+/// # Example
+/// ```
+/// use app_window::clock::{self, TestClock};
+/// use std::time::Duration;
+///
+/// let test_clock = TestClock::new();
+/// clock::set_clock(test_clock.clone());
+/// test_clock.advance(Duration::from_secs(1));
+/// assert_eq!(test_clock.elapsed(), Duration::from_secs(1));
+/// # clock::set_clock(clock::SystemClock);
+/// ```
+pub mod clock;
+
 /// Integration with the `some_executor` crate.
 ///
 /// This module provides [`some_executor::MainThreadExecutor`], which implements
@@ -532,6 +934,50 @@ pub mod main_thread_cell;
 /// See the module documentation for more details and integration test examples.
 pub mod test_support;
 
+/// Out-of-tree platform backends.
+///
+/// app_window has built-in backends for macOS, iOS, Windows, Linux (Wayland), Android and
+/// WebAssembly.  On any other target the crate forwards to a [`Backend`](backend::Backend) you
+/// register at runtime, so platforms like Redox or Haiku can be developed in their own crate.
+///
+/// A backend implements three traits:
+///
+/// - [`Backend`](backend::Backend): main thread management and window creation
+/// - [`BackendWindow`](backend::BackendWindow): per-window operations
+/// - [`BackendSurface`](backend::BackendSurface): sizing and raw handles for graphics APIs
+///
+/// Optional operations (fullscreen, drag-and-drop, window menus and so on) have default
+/// implementations that report them as unsupported or do nothing.  Input events are fed in
+/// with [`report_key`](backend::report_key), [`report_mouse_button`](backend::report_mouse_button)
+/// and friends.  Check your backend with the suite in `tests/conformance`.
+///
+/// Requires the `unstable-backend` cargo feature.  This API may change in minor releases.
+///
+/// # Example
+///
+/// ```ignore
+/// # // ALLOW_IGNORE_DOCTEST: requires the unstable-backend feature
+/// fn main() {
+///     app_window::backend::register(my_platform::Backend::new())
+///         .expect("Can't register backend");
+///     app_window::application::main(|| {
+///         // your app here
+///     });
+/// }
+/// ```
+///
+/// # Platform support
+///
+/// [`backend::register`] returns [`RegisterError::BuiltInBackend`](backend::RegisterError::BuiltInBackend)
+/// on targets with a built-in backend, so backend crates still build (but can't run) there.
+#[cfg(feature = "unstable-backend")]
+pub mod backend;
+
+/// The AccessKit version [`Window::update_accessibility_tree`](window::Window::update_accessibility_tree)
+/// takes, so apps build their trees with the same one.
+#[cfg(feature = "accessibility")]
+pub use accesskit;
+
 /// Describes the preferred strategy for interacting with wgpu on different platforms.
 ///
 /// Different platforms have different requirements for which thread can access
@@ -586,6 +1032,7 @@ pub enum WGPUStrategy {
 /// This function displays a modal alert dialog to the user. The behavior is platform-specific:
 ///
 /// - **WebAssembly**: Uses the browser's native `window.alert()` function
+/// - **Android**: Logs the message (a dialog is not yet implemented)
 /// - **macOS, Windows, Linux**: Not yet implemented (will panic with `todo!`)
 ///
 /// # Platform-specific behavior
@@ -622,6 +1069,8 @@ pub async fn alert(message: String) {
 /// - **Linux**: `NotMainThread` - wgpu should be accessed from a worker thread
 /// - **Windows**: `Relaxed` - wgpu can be accessed from any thread
 /// - **macOS**: `Relaxed` - wgpu can be accessed from any thread
+/// - **iOS**: `Relaxed` - wgpu can be accessed from any thread
+/// - **Android**: `Relaxed` - wgpu can be accessed from any thread
 /// - **WebAssembly**: `MainThread` - wgpu must be accessed from the main thread
 #[cfg(target_os = "linux")]
 pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::NotMainThread;
@@ -635,7 +1084,7 @@ pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::Relaxed;
 /// The preferred strategy for interacting with wgpu on the current platform.
 ///
 /// See [`WGPU_STRATEGY`] documentation for details.
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::Relaxed;
 
 /// The preferred strategy for interacting with wgpu on the current platform.
@@ -644,6 +1093,18 @@ pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::Relaxed;
 #[cfg(target_arch = "wasm32")]
 pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::MainThread;
 
+/// The preferred strategy for interacting with wgpu on the current platform.
+///
+/// See [`WGPU_STRATEGY`] documentation for details.
+#[cfg(target_os = "android")]
+pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::Relaxed;
+
+/// The preferred strategy for interacting with wgpu on the current platform.
+///
+/// Out-of-tree backends (see [`backend`]) get the most conservative strategy.
+#[cfg(plugin_backend)]
+pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::MainThread;
+
 /// The preferred strategy for interacting with wgpu surfaces on the current platform.
 ///
 /// This constant provides the platform-specific threading requirements for wgpu
@@ -655,6 +1116,8 @@ pub const WGPU_STRATEGY: WGPUStrategy = WGPUStrategy::MainThread;
 /// - **Linux**: `NotMainThread` - surfaces should be created from a worker thread
 /// - **Windows**: `Relaxed` - surfaces can be created from any thread
 /// - **macOS**: `MainThread` - surfaces must be created from the main thread
+/// - **iOS**: `MainThread` - surfaces must be created from the main thread
+/// - **Android**: `Relaxed` - surfaces can be created from any thread
 /// - **WebAssembly**: `MainThread` - surfaces must be created from the main thread
 ///
 /// # Difference from `WGPU_STRATEGY`
@@ -674,7 +1137,7 @@ pub const WGPU_SURFACE_STRATEGY: WGPUStrategy = WGPUStrategy::Relaxed;
 /// The preferred strategy for interacting with wgpu surfaces on the current platform.
 ///
 /// See [`WGPU_SURFACE_STRATEGY`] documentation for details.
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub const WGPU_SURFACE_STRATEGY: WGPUStrategy = WGPUStrategy::MainThread;
 
 /// The preferred strategy for interacting with wgpu surfaces on the current platform.
@@ -683,4 +1146,16 @@ pub const WGPU_SURFACE_STRATEGY: WGPUStrategy = WGPUStrategy::MainThread;
 #[cfg(target_arch = "wasm32")]
 pub const WGPU_SURFACE_STRATEGY: WGPUStrategy = WGPUStrategy::MainThread;
 
+/// The preferred strategy for interacting with wgpu surfaces on the current platform.
+///
+/// See [`WGPU_SURFACE_STRATEGY`] documentation for details.
+#[cfg(target_os = "android")]
+pub const WGPU_SURFACE_STRATEGY: WGPUStrategy = WGPUStrategy::Relaxed;
+
+/// The preferred strategy for interacting with wgpu surfaces on the current platform.
+///
+/// Out-of-tree backends (see [`backend`]) get the most conservative strategy.
+#[cfg(plugin_backend)]
+pub const WGPU_SURFACE_STRATEGY: WGPUStrategy = WGPUStrategy::MainThread;
+
 logwise::declare_logging_domain!();
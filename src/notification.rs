@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::window::IconData;
+
+/// An error posting a notification.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NotificationError {
+    /// The platform or desktop environment can't show notifications, or not to this app.
+    #[error("notifications are not supported here")]
+    Unsupported,
+    /// The user turned notifications off for this app, or declined to allow them.
+    #[error("notifications are not allowed")]
+    Denied,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// A notification to [`post`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// The first line, usually shown in bold.
+    pub title: String,
+    /// The text under the title.
+    pub body: String,
+    /// An image shown with the notification.  Without one, platforms show the app's icon.
+    pub icon: Option<IconData>,
+}
+
+impl Notification {
+    /// A notification with `title` and `body`, and no icon.
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Notification {
+            title: title.into(),
+            body: body.into(),
+            icon: None,
+        }
+    }
+}
+
+/// Shows `notification` with the platform's notification center, where it stays until the
+/// user dismisses it or the platform times it out.
+///
+/// This is for telling the user about something that happened while they were elsewhere,
+/// like a download finishing.  The first notification may ask the user for permission, and
+/// resolves once they've answered.
+///
+/// # Errors
+///
+/// * [`NotificationError::Unsupported`] if the platform can't show notifications.
+/// * [`NotificationError::Denied`] if the user doesn't allow them.
+/// * [`NotificationError::Platform`] if the platform reported an error.
+///
+/// # Platform behavior
+///
+/// * **macOS, iOS**: `UNUserNotificationCenter`, which asks for permission the first time.
+///   Only apps in a bundle can post notifications; elsewhere, such as with `cargo run`, this
+///   returns [`NotificationError::Unsupported`].  Notifications show even while the app is in
+///   front, unless the app has its own notification center delegate.
+/// * **Windows**: a toast.  Toasts belong to an AppUserModelID: unpackaged apps should set
+///   one with `SetCurrentProcessExplicitAppUserModelID`, and register it with a Start menu
+///   shortcut for their name and icon to show.  Otherwise the toast is attributed to the
+///   executable's name.
+/// * **Linux**: `org.freedesktop.Notifications` over D-Bus.  Needs the off-by-default
+///   `notifications` feature.
+/// * **wasm32**: the `Notification` API, which asks for permission the first time.  Browsers
+///   only ask after the user has interacted with the page, and only in secure contexts.
+/// * **Android**: returns [`NotificationError::Unsupported`].
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::notification::{Notification, NotificationError, post};
+///
+/// match post(Notification::new("Export finished", "holiday.mp4 is ready")).await {
+///     Ok(()) | Err(NotificationError::Denied) => {}
+///     Err(e) => eprintln!("Can't notify: {e}"),
+/// }
+/// # }
+/// ```
+pub async fn post(notification: Notification) -> Result<(), NotificationError> {
+    crate::sys::post_notification(notification).await
+}
@@ -0,0 +1,334 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::application::time::{Duration, Instant};
+use crate::application::{self, Stats};
+use crate::coordinates::Size;
+use crate::window::PreviewImage;
+use std::sync::Mutex;
+
+/// How often the overlay redraws.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Glyphs are 3x5 font pixels, each drawn as a square this many image pixels wide.
+const SCALE: usize = 2;
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+/// Font pixels from one character, or line, to the next.
+const ADVANCE: usize = GLYPH_WIDTH + 1;
+const LINE_HEIGHT: usize = GLYPH_HEIGHT + 2;
+const MARGIN: usize = 2;
+const COLUMNS: usize = 28;
+const LINES: usize = 5;
+
+/// Main thread work the overlay itself does each refresh.
+const OWN_LABELS: [&str; 2] = ["DiagnosticOverlay::frame", "Window::set_splash_image"];
+
+const BACKGROUND: [u8; 4] = [0x18, 0x18, 0x18, 0xFF];
+const FOREGROUND: [u8; 4] = [0x80, 0xFF, 0x80, 0xFF];
+
+/// Rows of a character, top first, with the leftmost pixel in bit 2.  Letters are drawn in
+/// upper case; characters without a glyph are drawn as `?`.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ' ' => [0; GLYPH_HEIGHT],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+fn image_size() -> (usize, usize) {
+    (
+        (MARGIN * 2 + COLUMNS * ADVANCE - 1) * SCALE,
+        (MARGIN * 2 + LINES * LINE_HEIGHT - 2) * SCALE,
+    )
+}
+
+/// Draws up to [`LINES`] lines of up to [`COLUMNS`] characters; the rest is cut off.
+fn render(lines: &[String]) -> PreviewImage {
+    let (width, height) = image_size();
+    let mut rgba = BACKGROUND.repeat(width * height);
+    for (row, line) in lines.iter().take(LINES).enumerate() {
+        for (column, c) in line.chars().take(COLUMNS).enumerate() {
+            let left = MARGIN + column * ADVANCE;
+            let top = MARGIN + row * LINE_HEIGHT;
+            for (y, bits) in glyph(c).into_iter().enumerate() {
+                for x in 0..GLYPH_WIDTH {
+                    if bits & (0b100 >> x) == 0 {
+                        continue;
+                    }
+                    for dy in 0..SCALE {
+                        let py = (top + y) * SCALE + dy;
+                        let px = (left + x) * SCALE;
+                        let start = (py * width + px) * 4;
+                        rgba[start..start + SCALE * 4].copy_from_slice(&FOREGROUND.repeat(SCALE));
+                    }
+                }
+            }
+        }
+    }
+    PreviewImage::new(width as u32, height as u32, rgba).expect("Overlay image is valid")
+}
+
+/// What the overlay shows, measured over one refresh.
+#[derive(Debug, Default)]
+struct Report {
+    frames_per_second: f64,
+    mean_frame: Duration,
+    max_frame: Duration,
+    main_thread_latency: Duration,
+    hops_per_second: f64,
+    round_trips_per_second: f64,
+    busiest: Option<String>,
+}
+
+impl Report {
+    fn lines(&self) -> Vec<String> {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        vec![
+            format!("FPS {:.1}", self.frames_per_second),
+            format!(
+                "FRAME {:.1} MAX {:.1} MS",
+                ms(self.mean_frame),
+                ms(self.max_frame)
+            ),
+            format!("MAIN THREAD {:.2} MS", ms(self.main_thread_latency)),
+            format!(
+                "HOPS/S {:.0} WAITS/S {:.0}",
+                self.hops_per_second, self.round_trips_per_second
+            ),
+            format!("BUSY {}", self.busiest.as_deref().unwrap_or("-")),
+        ]
+    }
+}
+
+#[derive(Debug)]
+struct Counters {
+    last_frame: Option<Instant>,
+    frames: u32,
+    frame_total: Duration,
+    max_frame: Duration,
+    last_refresh: Instant,
+    last_stats: Stats,
+}
+
+impl Counters {
+    /// Counts a frame at `now`, and if it's time to refresh, returns what to show.
+    fn frame(&mut self, now: Instant) -> Option<Report> {
+        if let Some(last) = self.last_frame.replace(now) {
+            let frame = now.saturating_duration_since(last);
+            self.frames += 1;
+            self.frame_total += frame;
+            self.max_frame = self.max_frame.max(frame);
+        }
+        let elapsed = now.saturating_duration_since(self.last_refresh);
+        if elapsed < REFRESH_INTERVAL {
+            return None;
+        }
+        let seconds = elapsed.as_secs_f64();
+        let stats = application::stats();
+        //per label, so a reset_stats in between reads as no hops rather than underflowing
+        let hops =
+            |stats: &Stats, label: &str| stats.label(label).map_or(0, |s| s.main_thread_hops());
+        let busiest = stats
+            .by_label()
+            .map(|(label, s)| {
+                (
+                    label,
+                    s.main_thread_hops()
+                        .saturating_sub(hops(&self.last_stats, label)),
+                )
+            })
+            //not the overlay's own hops
+            .filter(|(label, hops)| *hops > 0 && !OWN_LABELS.contains(label))
+            .max_by_key(|(_, hops)| *hops)
+            .map(|(label, _)| label.to_string());
+        let report = Report {
+            frames_per_second: self.frames as f64 / seconds,
+            mean_frame: self
+                .frame_total
+                .checked_div(self.frames)
+                .unwrap_or_default(),
+            max_frame: self.max_frame,
+            main_thread_latency: Duration::ZERO,
+            hops_per_second: stats
+                .main_thread_hops()
+                .saturating_sub(self.last_stats.main_thread_hops())
+                as f64
+                / seconds,
+            round_trips_per_second: stats
+                .platform_round_trips()
+                .saturating_sub(self.last_stats.platform_round_trips())
+                as f64
+                / seconds,
+            busiest,
+        };
+        self.frames = 0;
+        self.frame_total = Duration::ZERO;
+        self.max_frame = Duration::ZERO;
+        self.last_refresh = now;
+        self.last_stats = stats;
+        Some(report)
+    }
+}
+
+/// A small window showing how the app is performing, for debugging.
+///
+/// It shows frames per second and frame times, how long work sent to the main thread waits
+/// before it runs, and the [`application::stats`] counters: main thread hops and waits on the
+/// window system per second, and the label with the most hops.  It's drawn without the GPU,
+/// like a [`SplashWindow`](crate::splash::SplashWindow), so it works however the app renders.
+///
+/// app_window can't see when your renderer presents, so call [`DiagnosticOverlay::frame`]
+/// once per presented frame.  That also redraws the overlay, twice a second.
+///
+/// Showing the overlay turns on [`application::set_stats_enabled`], which costs a lock per
+/// main thread hop.  Dropping it closes the window and puts stats back how they were.
+#[derive(Debug)]
+#[must_use = "Dropping the overlay closes it"]
+pub struct DiagnosticOverlay {
+    sys: Option<crate::sys::Window>,
+    counters: Mutex<Counters>,
+    stats_were_enabled: bool,
+}
+
+impl DiagnosticOverlay {
+    /// Shows the overlay, near the top left of the screen where the platform allows.
+    ///
+    /// On platforms without separate floating windows (iOS, Android, wasm32), this shows
+    /// nothing and [`DiagnosticOverlay::frame`] does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`application::main()`](crate::application::main) has not been called.
+    pub async fn show() -> Self {
+        assert!(
+            application::is_main_thread_running(),
+            "{}",
+            application::CALL_MAIN
+        );
+        let stats_were_enabled = application::stats_enabled();
+        application::set_stats_enabled(true);
+        let (width, height) = image_size();
+        let image = render(&Report::default().lines());
+        let sys =
+            crate::sys::Window::new_overlay(Size::new(width as f64, height as f64), image).await;
+        DiagnosticOverlay {
+            sys,
+            counters: Mutex::new(Counters {
+                last_frame: None,
+                frames: 0,
+                frame_total: Duration::ZERO,
+                max_frame: Duration::ZERO,
+                last_refresh: crate::clock::now(),
+                last_stats: application::stats(),
+            }),
+            stats_were_enabled,
+        }
+    }
+
+    /// Counts a presented frame, and redraws the overlay if it's due.
+    ///
+    /// Redrawing measures a trip to the main thread, so twice a second this waits for one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::overlay::DiagnosticOverlay;
+    ///
+    /// let overlay = DiagnosticOverlay::show().await;
+    /// loop {
+    ///     //...render and present...
+    ///     overlay.frame().await;
+    /// }
+    /// # }
+    /// ```
+    pub async fn frame(&self) {
+        let Some(sys) = self.sys.as_ref() else {
+            return;
+        };
+        let report = self.counters.lock().unwrap().frame(crate::clock::now());
+        if let Some(mut report) = report {
+            let sent = crate::clock::now();
+            report.main_thread_latency =
+                application::on_main_thread("DiagnosticOverlay::frame".to_string(), move || {
+                    crate::clock::since(sent)
+                })
+                .await;
+            sys.set_splash_image(render(&report.lines())).await;
+        }
+    }
+}
+
+impl Drop for DiagnosticOverlay {
+    fn drop(&mut self) {
+        application::set_stats_enabled(self.stats_were_enabled);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MARGIN, SCALE, image_size, render};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn render_draws_glyphs_at_scale() {
+        let image = render(&["1".to_string()]);
+        let (width, height) = image_size();
+        assert_eq!(
+            (image.width() as usize, image.height() as usize),
+            (width, height)
+        );
+        let lit = |x: usize, y: usize| {
+            let start = (y * width + x) * 4;
+            image.rgba()[start..start + 4] == super::FOREGROUND
+        };
+        //'1' is the middle column of its first row, and not the corners
+        let middle = (MARGIN + 1) * SCALE;
+        let top = MARGIN * SCALE;
+        assert!(lit(middle, top));
+        assert!(lit(middle + SCALE - 1, top + SCALE - 1));
+        assert!(!lit(MARGIN * SCALE, top));
+        assert!(!lit(0, 0));
+    }
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use std::sync::{Arc, Mutex};
+
+/// An error watching the system's power state.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PowerError {
+    /// The platform has no way for us to hear about power changes.
+    #[error("power notifications are not supported here")]
+    Unsupported,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// A change in the system's power state.  See [`on_change`].
+///
+/// Each `...Off` or `...Entered` event is followed by its opposite when the system comes
+/// back, so renderers can slow down in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PowerEvent {
+    /// The displays went to sleep, or a screensaver is covering them.  Nothing drawn can be
+    /// seen.
+    DisplayOff,
+    /// The displays woke after [`PowerEvent::DisplayOff`].
+    DisplayOn,
+    /// The system started saving power, because the user turned on a battery saver or Low
+    /// Power Mode, or the battery ran low.  Draw less often if you can.
+    LowPowerEntered,
+    /// The system stopped saving power after [`PowerEvent::LowPowerEntered`].
+    LowPowerExited,
+}
+
+type Handler = Arc<Mutex<dyn FnMut(PowerEvent) + Send>>;
+
+struct Listeners {
+    next_id: u64,
+    handlers: Vec<(u64, Handler)>,
+}
+
+static LISTENERS: Mutex<Listeners> = Mutex::new(Listeners {
+    next_id: 0,
+    handlers: Vec::new(),
+});
+
+/// Keeps a handler from [`on_change`] registered.  Dropping it unregisters the handler, and
+/// once none are left the platform stops watching.
+#[derive(Debug)]
+#[must_use = "the handler is unregistered when the watch is dropped"]
+pub struct PowerWatch {
+    id: u64,
+}
+
+impl Drop for PowerWatch {
+    fn drop(&mut self) {
+        LISTENERS
+            .lock()
+            .unwrap()
+            .handlers
+            .retain(|(id, _)| *id != self.id);
+        if is_watched() || !crate::application::is_main_thread_running() {
+            return;
+        }
+        crate::application::submit_to_main_thread("PowerWatch::drop".to_string(), || {
+            //someone may have started watching again since
+            if !is_watched() {
+                crate::sys::unwatch_power();
+            }
+        });
+    }
+}
+
+/// Whether any handler from [`on_change`] is registered.
+pub(crate) fn is_watched() -> bool {
+    !LISTENERS.lock().unwrap().handlers.is_empty()
+}
+
+fn register(handler: Handler) -> PowerWatch {
+    let mut listeners = LISTENERS.lock().unwrap();
+    let id = listeners.next_id;
+    listeners.next_id += 1;
+    listeners.handlers.push((id, handler));
+    PowerWatch { id }
+}
+
+/// Runs each handler from [`on_change`] with `event`.  Call on the main thread when the
+/// platform says the power state changed.
+#[cfg_attr(
+    not(any(
+        all(target_os = "linux", feature = "portal"),
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "ios"
+    )),
+    allow(dead_code)
+)]
+pub(crate) fn notify(event: PowerEvent) {
+    //cloned out, so handlers can register or drop watches without deadlocking
+    let handlers: Vec<Handler> = LISTENERS
+        .lock()
+        .unwrap()
+        .handlers
+        .iter()
+        .map(|(_, handler)| handler.clone())
+        .collect();
+    for handler in handlers {
+        (handler.lock().unwrap())(event);
+    }
+}
+
+/// Calls `handler`, on the main thread, whenever the displays sleep or wake, or the system
+/// starts or stops saving power.
+///
+/// This is for game loops and other continuous renderers, which can stop drawing while the
+/// displays are off and draw less often while the system saves power.  Whether a particular
+/// window can be seen is a separate matter; see
+/// [`WindowEvent::Occluded`](crate::window::WindowEvent::Occluded).
+///
+/// If the system is already saving power, or the displays are already off, the handler is
+/// told so straight away.  Otherwise it only hears about changes.
+///
+/// The handler stays registered until the returned [`PowerWatch`] is dropped.
+///
+/// # Platform behavior
+///
+/// * macOS hears about displays sleeping from `NSWorkspace`, and about Low Power Mode from
+///   `NSProcessInfo`.
+/// * iOS only hears about Low Power Mode.
+/// * Windows registers for the console display state and battery saver with
+///   `RegisterPowerSettingNotification`.  A dimmed display still counts as on.
+/// * Linux asks xdg-desktop-portal: its power profile monitor says when the power saver
+///   profile is on, and its inhibit monitor says when the screensaver is active.  Needs the
+///   off-by-default `portal` feature.
+///
+/// # Errors
+///
+/// * [`PowerError::Unsupported`] on wasm32 and Android, on Linux without a portal that
+///   implements either monitor, and on backends registered at runtime.
+/// * [`PowerError::Platform`] if the platform reported an error.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::power::{self, PowerEvent};
+///
+/// let watch = power::on_change(|event| match event {
+///     PowerEvent::DisplayOff => println!("stop rendering"),
+///     PowerEvent::DisplayOn => println!("start rendering"),
+///     PowerEvent::LowPowerEntered => println!("render at 30 fps"),
+///     PowerEvent::LowPowerExited => println!("render at full speed"),
+///     _ => {}
+/// })
+/// .await;
+/// if let Err(e) = &watch {
+///     eprintln!("Can't watch the power state: {e}");
+/// }
+/// //...the handler runs until `watch` is dropped
+/// # }
+/// ```
+pub async fn on_change<F: FnMut(PowerEvent) + Send + 'static>(
+    handler: F,
+) -> Result<PowerWatch, PowerError> {
+    //on failure, dropping this unregisters the handler
+    let watch = register(Arc::new(Mutex::new(handler)));
+    crate::sys::watch_power().await?;
+    Ok(watch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PowerEvent, notify, register};
+    use std::sync::{Arc, Mutex};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn handlers_hear_until_dropped() {
+        let heard = Arc::new(Mutex::new(Vec::new()));
+        let recorded = heard.clone();
+        let watch = register(Arc::new(Mutex::new(move |event| {
+            recorded.lock().unwrap().push(event);
+        })));
+        notify(PowerEvent::DisplayOff);
+        drop(watch);
+        notify(PowerEvent::DisplayOn);
+        assert_eq!(*heard.lock().unwrap(), [PowerEvent::DisplayOff]);
+    }
+}
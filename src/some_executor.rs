@@ -4,7 +4,7 @@
 Implements the some_executor traits for the main thread executor
 */
 
-use crate::application::submit_to_main_thread;
+use crate::application::submit_to_main_thread_with_priority;
 use crate::executor::already_on_main_thread_submit;
 use some_executor::observer::{ExecutorNotified, FinishedObservation, Observer, ObserverNotified};
 use some_executor::task::Task;
@@ -166,7 +166,7 @@ impl SomeExecutor for MainThreadExecutor {
     {
         let (s, o) = task.spawn(self);
         let task_label = s.label().to_string();
-        submit_to_main_thread(task_label.clone(), || {
+        submit_to_main_thread_with_priority(task_label.clone(), s.priority(), || {
             already_on_main_thread_submit(task_label, async {
                 s.into_future().await;
             });
@@ -184,7 +184,7 @@ impl SomeExecutor for MainThreadExecutor {
     {
         let (s, o) = task.spawn(self);
         let task_label = s.label().to_string();
-        submit_to_main_thread(task_label.clone(), || {
+        submit_to_main_thread_with_priority(task_label.clone(), s.priority(), || {
             already_on_main_thread_submit(task_label, async {
                 s.into_future().await;
             });
@@ -198,7 +198,7 @@ impl SomeExecutor for MainThreadExecutor {
     ) -> some_executor::BoxedSendObserver {
         let (s, o) = task.spawn_objsafe(self);
         let task_label = s.label().to_string();
-        submit_to_main_thread(task_label.clone(), || {
+        submit_to_main_thread_with_priority(task_label.clone(), s.priority(), || {
             already_on_main_thread_submit(task_label, async {
                 s.into_future().await;
             });
@@ -211,7 +211,7 @@ impl SomeExecutor for MainThreadExecutor {
             let (s, o) = task.spawn_objsafe(self);
             let task_label = s.label().to_string();
 
-            submit_to_main_thread(task_label.clone(), || {
+            submit_to_main_thread_with_priority(task_label.clone(), s.priority(), || {
                 already_on_main_thread_submit(task_label, async {
                     s.into_future().await;
                 });
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::coordinates::Size;
+use crate::dialog::Color;
+use crate::window::PreviewImage;
+
+/// What a [`SplashWindow`] shows.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SplashContent {
+    /// A solid color.
+    Color(Color),
+    /// An image, stretched to fill the window.
+    Image(PreviewImage),
+}
+
+impl From<Color> for SplashContent {
+    fn from(color: Color) -> Self {
+        SplashContent::Color(color)
+    }
+}
+
+impl From<PreviewImage> for SplashContent {
+    fn from(image: PreviewImage) -> Self {
+        SplashContent::Image(image)
+    }
+}
+
+impl SplashContent {
+    fn into_image(self) -> PreviewImage {
+        match self {
+            SplashContent::Color(color) => {
+                let [red, green, blue] = color.to_rgb8();
+                PreviewImage::new(1, 1, vec![red, green, blue, 0xFF]).expect("1x1 image is valid")
+            }
+            SplashContent::Image(image) => image,
+        }
+    }
+}
+
+/// A window shown while the app starts up.
+///
+/// Creating a GPU device and compiling shaders can take a noticeable time before the main
+/// window shows anything.  A splash window is drawn without the GPU, so it appears at once;
+/// close it when the main window has presented its first frame.
+///
+/// app_window can't see when your renderer presents, so call [`SplashWindow::close`] right
+/// after the first present.  Dropping the splash closes it too, without fading.
+#[derive(Debug)]
+#[must_use = "Dropping a splash window closes it"]
+pub struct SplashWindow {
+    sys: Option<crate::sys::Window>,
+}
+
+impl SplashWindow {
+    /// Shows a borderless splash window of `size`, centered where the platform allows.
+    ///
+    /// On platforms with their own launch screens (iOS, Android) or no separate windows
+    /// (wasm32), this shows nothing and [`SplashWindow::close`] does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`application::main()`](crate::application::main) has not been called.
+    pub async fn show(content: impl Into<SplashContent>, size: Size) -> Self {
+        assert!(
+            crate::application::is_main_thread_running(),
+            "{}",
+            crate::application::CALL_MAIN
+        );
+        let image = content.into().into_image();
+        SplashWindow {
+            sys: crate::sys::Window::new_splash(size, image).await,
+        }
+    }
+
+    /// Fades the splash out, where the platform can, and closes it.
+    pub async fn close(mut self) {
+        if let Some(sys) = self.sys.take() {
+            sys.fade_out().await;
+        }
+    }
+}
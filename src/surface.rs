@@ -1,8 +1,14 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::coordinates::Size;
+use crate::application::MainThreadToken;
+use crate::coordinates::{Rect, Size};
 use crate::sys;
+use crate::window::{EventHandler, ScaleOverride, WindowEvent, WindowEventSender};
 use raw_window_handle::{DisplayHandle, RawDisplayHandle, RawWindowHandle, WindowHandle};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+mod resize;
 
 /// A type that can be drawn on, e.g. by wgpu.
 ///
@@ -12,8 +18,8 @@ use raw_window_handle::{DisplayHandle, RawDisplayHandle, RawWindowHandle, Window
 /// # Creating a Surface
 ///
 /// A Surface is created from a [`Window`](crate::window::Window) using the
-/// [`Window::surface()`](crate::window::Window::surface) method. Only one Surface can
-/// be created per window.
+/// [`Window::surface()`](crate::window::Window::surface) method.  A window has one surface;
+/// calling `surface()` again, or cloning a `Surface`, returns another handle to it.
 ///
 /// ```
 /// # async fn example() {
@@ -30,6 +36,26 @@ use raw_window_handle::{DisplayHandle, RawDisplayHandle, RawWindowHandle, Window
 /// # }
 /// ```
 ///
+/// # Clones
+///
+/// Clones are cheap, and every clone configures the same surface.  There's one
+/// [`size_update`](Self::size_update) callback (or [`size_update_per_frame`](Self::size_update_per_frame)
+/// and [`resize_complete`](Self::resize_complete) pair) and one
+/// [`scale_update`](Self::scale_update) callback per surface, whichever clone registered it;
+/// registering again from any clone replaces it.  So have one part of the app own the callbacks,
+/// and hand other parts clones for the handles and size.
+///
+/// # Lifetime
+///
+/// Dropping the last handle to a `Surface` detaches its callbacks; the window itself stays
+/// open.
+///
+/// A `Surface` may outlive its [`Window`](crate::window::Window) without memory unsafety, but
+/// once the window is gone the surface is inert: callbacks stop, [`damage`](Self::damage) does
+/// nothing, and [`size_scale`](Self::size_scale) reports the last known size (or zero, on
+/// Windows).  The raw handles then name a destroyed window, so drop any graphics surface made
+/// from them before dropping the `Window`.
+///
 /// # Platform Implementation Details
 ///
 /// The Surface abstraction exists for several reasons:
@@ -40,18 +66,86 @@ use raw_window_handle::{DisplayHandle, RawDisplayHandle, RawWindowHandle, Window
 ///    than creating a blank window. Applications that don't need to draw can skip this cost.
 /// 3. **Compositing**: Platform window decorations (title bars, borders) often require
 ///    special handling when composited with the application's rendered content.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[must_use = "Dropping a surface may release resources"]
 pub struct Surface {
-    pub(super) sys: sys::Surface,
+    pub(super) shared: Arc<Shared>,
+}
+
+/// A size callback.  The `Mutex` makes it `Sync` whether or not the callback is, and the `Arc`
+/// lets callers clone it out of wherever it's stored and call it without holding that lock.
+type SizeCallback = Arc<Mutex<Box<dyn Fn(Size) + Send>>>;
+
+fn size_callback<F: Fn(Size) + Send + 'static>(callback: F) -> SizeCallback {
+    Arc::new(Mutex::new(Box::new(callback)))
+}
+
+/// Where the platform's size callback goes: the app's, or the per-frame coalescer.
+#[derive(Clone, Default)]
+struct SizeHandler(Arc<Mutex<Option<SizeCallback>>>);
+
+impl SizeHandler {
+    fn set(&self, handler: SizeCallback) {
+        *self.0.lock().unwrap() = Some(handler);
+    }
+
+    /// Calls the handler, cloned out of the lock so it can replace itself from any clone of
+    /// the surface.
+    fn call(&self, size: Size) {
+        let handler = self.0.lock().unwrap().clone();
+        if let Some(handler) = handler {
+            handler.lock().unwrap()(size)
+        }
+    }
+}
+
+/// What every clone of a [`Surface`] shares.
+pub(crate) struct Shared {
+    sys: sys::Surface,
+    scale_override: ScaleOverride,
+    events: WindowEventSender,
+    size_handler: SizeHandler,
+    /// Calls the [`scale_update`](Surface::scale_update) callback.
+    scale_handler: Mutex<Option<EventHandler>>,
+    /// Paces [`size_update_per_frame`](Surface::size_update_per_frame), once installed.
+    resize: Mutex<Option<Arc<resize::Coalescer>>>,
+}
+
+impl Debug for Shared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Shared").field("sys", &self.sys).finish()
+    }
 }
 
 impl Surface {
+    pub(crate) fn new(
+        mut sys: sys::Surface,
+        scale_override: ScaleOverride,
+        events: WindowEventSender,
+    ) -> Self {
+        //the platform holds one callback for the surface's life, and clones swap what it calls
+        let size_handler = SizeHandler::default();
+        let move_handler = size_handler.clone();
+        sys.size_update(move |size| move_handler.call(size));
+        Surface {
+            shared: Arc::new(Shared {
+                sys,
+                scale_override,
+                events,
+                size_handler,
+                scale_handler: Mutex::new(None),
+                resize: Mutex::new(None),
+            }),
+        }
+    }
+
     /// Returns the size and scale factor of the surface.
     ///
     /// The size is returned in logical pixels, which may differ from physical pixels
     /// on high-DPI displays. The scale factor indicates the ratio between logical
-    /// and physical pixels.
+    /// and physical pixels.  If the window has a scale override (see
+    /// [`Window::set_scale_override`](crate::window::Window::set_scale_override)), that's the
+    /// scale reported.
     ///
     /// # Returns
     ///
@@ -77,7 +171,8 @@ impl Surface {
     /// # }
     /// ```
     pub async fn size_scale(&self) -> (Size, f64) {
-        self.sys.size_scale().await
+        let (size, scale) = self.shared.sys.size_scale().await;
+        (size, self.shared.scale_override.get().unwrap_or(scale))
     }
 
     /// Returns the size and scale factor of the surface from the main thread.
@@ -129,7 +224,8 @@ impl Surface {
             sys::is_main_thread(),
             "`size_main` must be called from the main thread"
         );
-        self.sys.size_main()
+        let (size, scale) = self.shared.sys.size_main();
+        (size, self.shared.scale_override.get().unwrap_or(scale))
     }
 
     /// Returns the raw window handle for this surface.
@@ -164,7 +260,37 @@ impl Surface {
     /// # }
     /// ```
     pub fn raw_window_handle(&self) -> RawWindowHandle {
-        self.sys.raw_window_handle()
+        self.shared.sys.raw_window_handle()
+    }
+
+    /// Returns the raw window handle for this surface, for use on the main thread.
+    ///
+    /// The handle is a bare pointer.  On macOS and iOS it points at an `NSView` or `UIView`,
+    /// which may only be touched on the main thread; on Windows, most calls on an `HWND` are
+    /// only safe from the thread that owns it.  [`raw_window_handle`](Self::raw_window_handle)
+    /// leaves that up to you.  This variant takes a [`MainThreadToken`], so code that hands
+    /// the handle to a platform API directly can't compile off the main thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// # use app_window::window::Window;
+    /// # let mut window: Window = todo!();
+    /// use app_window::application::{self, MainThreadToken};
+    ///
+    /// let surface = window.surface().await;
+    /// application::on_main_thread("interop".to_owned(), move || {
+    ///     let token = MainThreadToken::new().unwrap();
+    ///     let handle = surface.raw_window_handle_with(token);
+    ///     //...call into the platform with handle
+    /// #   let _ = handle;
+    /// })
+    /// .await;
+    /// # }
+    /// ```
+    pub fn raw_window_handle_with(&self, _token: MainThreadToken) -> RawWindowHandle {
+        self.shared.sys.raw_window_handle()
     }
 
     /// Returns a borrowed window handle for this surface.
@@ -210,7 +336,14 @@ impl Surface {
     ///
     /// See [`raw_window_handle()`](Self::raw_window_handle) for usage with graphics APIs.
     pub fn raw_display_handle(&self) -> RawDisplayHandle {
-        self.sys.raw_display_handle()
+        self.shared.sys.raw_display_handle()
+    }
+
+    /// Returns the raw display handle for this surface, for use on the main thread.
+    ///
+    /// See [`raw_window_handle_with`](Self::raw_window_handle_with).
+    pub fn raw_display_handle_with(&self, _token: MainThreadToken) -> RawDisplayHandle {
+        self.shared.sys.raw_display_handle()
     }
 
     /// Returns a borrowed display handle for this surface.
@@ -245,8 +378,9 @@ impl Surface {
     ///
     /// # Thread Safety
     ///
-    /// The callback must be `Send` and `'static` as it may be called from different threads
-    /// depending on the platform. The callback should be efficient as it may be called
+    /// The callback must be `Send` and `'static` as it may be called from different
+    /// threads depending on the platform.  It may register a new callback on any clone of the
+    /// surface, which takes effect from the next resize. The callback should be efficient as it may be called
     /// frequently during resize operations.
     ///
     /// # Example
@@ -274,19 +408,215 @@ impl Surface {
     /// - **All platforms**: The callback is invoked after the resize has occurred
     /// - **macOS**: May be called multiple times during a resize drag operation
     /// - **Windows/Linux**: Typically called at the end of a resize operation
+    /// - **Windows**: Also called on moving to a monitor with a different DPI, where the window
+    ///   is resized to keep its logical size
     pub fn size_update<F: Fn(Size) + Send + 'static>(&mut self, update: F) {
-        self.sys.size_update(update)
+        //there's one callback for the platform, so this one replaces the per-frame ones
+        *self.shared.resize.lock().unwrap() = None;
+        self.shared.size_handler.set(size_callback(update));
+    }
+
+    /// Registers a callback to be invoked with the surface's size at most once per frame.
+    ///
+    /// A live resize can report sizes faster than the display refreshes.  Renderers that
+    /// reconfigure their swapchain for every one fall behind, and the window contents lag the
+    /// pointer.  This holds sizes until the surface's next frame callback, and delivers only the
+    /// newest one.  It replaces the [`size_update`](Self::size_update) callback, and vice versa;
+    /// calling it again replaces the callback.
+    ///
+    /// # Thread Safety
+    ///
+    /// The callback is invoked on the main thread, when the compositor is ready for the next
+    /// frame, so it's a good place to resize and present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// # use app_window::window::Window;
+    /// # let mut window: Window = todo!();
+    /// let mut surface = window.surface().await;
+    /// surface.size_update_per_frame(|size| {
+    ///     //reconfigure the swapchain once, however many sizes arrived
+    ///     println!("Drawing at {}x{}", size.width(), size.height());
+    /// });
+    /// # }
+    /// ```
+    ///
+    /// # Platform Behavior
+    ///
+    /// Frames come from the same source as
+    /// [`Window::request_redraw`](crate::window::Window::request_redraw).  Custom backends
+    /// without one are paced like a 60Hz display.
+    pub fn size_update_per_frame<F: Fn(Size) + Send + 'static>(&mut self, update: F) {
+        self.coalescer().set_update(size_callback(update))
+    }
+
+    /// Registers a callback to be invoked once a resize settles.
+    ///
+    /// After [`size_update_per_frame`](Self::size_update_per_frame) delivers a size, the next
+    /// frame checks whether a newer one arrived.  If none did, the resize is over and this
+    /// callback receives the final size.  It runs synchronously in that frame callback, on the
+    /// main thread, so expensive work deferred during the drag (rebuilding caches, full-quality
+    /// rendering) can finish before the frame is presented.  Calling it again replaces the
+    /// callback.
+    ///
+    /// This works whether or not a `size_update_per_frame` callback is registered, but not
+    /// alongside [`size_update`](Self::size_update), which replaces both.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// # use app_window::window::Window;
+    /// # let mut window: Window = todo!();
+    /// let mut surface = window.surface().await;
+    /// surface.resize_complete(|size| {
+    ///     println!("Settled at {}x{}", size.width(), size.height());
+    /// });
+    /// # }
+    /// ```
+    pub fn resize_complete<F: Fn(Size) + Send + 'static>(&mut self, complete: F) {
+        self.coalescer().set_complete(size_callback(complete))
+    }
+
+    fn coalescer(&self) -> Arc<resize::Coalescer> {
+        let mut resize = self.shared.resize.lock().unwrap();
+        if let Some(coalescer) = resize.as_ref() {
+            return coalescer.clone();
+        }
+        let coalescer = resize::Coalescer::new(self.shared.sys.frame_requester());
+        let move_coalescer = coalescer.clone();
+        self.shared
+            .size_handler
+            .set(size_callback(move |size| move_coalescer.push(size)));
+        *resize = Some(coalescer.clone());
+        coalescer
+    }
+
+    /// Registers a callback to be invoked when the surface's scale factor changes.
+    ///
+    /// Moving a window to a display with a different DPI changes how many physical pixels the
+    /// surface has without changing its logical size, so [`size_update`](Self::size_update)
+    /// doesn't fire.  Renderers that size their swapchain in physical pixels should reconfigure
+    /// it here.  Calling this again replaces the callback.
+    ///
+    /// While [`Window::set_scale_override`](crate::window::Window::set_scale_override) is in
+    /// effect the scale doesn't follow the display, so the callback isn't invoked.
+    ///
+    /// # Thread Safety
+    ///
+    /// As with `size_update`, the callback may be invoked on any thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// # use app_window::window::Window;
+    /// # let mut window: Window = todo!();
+    /// let mut surface = window.surface().await;
+    ///
+    /// surface.scale_update(|scale: f64| {
+    ///     println!("Surface is now at {scale}x");
+    ///     // Reconfigure the swapchain at the new physical size
+    /// });
+    /// # }
+    /// ```
+    ///
+    /// # Platform Behavior
+    ///
+    /// - **macOS**: `NSWindow` `didChangeBackingProperties`
+    /// - **Windows**: `WM_DPICHANGED`
+    /// - **Linux**: the compositor's preferred scale for the surface
+    /// - **WASM**: a `(resolution)` media query on `devicePixelRatio`, which also catches
+    ///   browser zoom
+    /// - **iOS/Android**: Never invoked; the scale doesn't change while the app runs
+    pub fn scale_update<F: Fn(f64) + Send + 'static>(&mut self, update: F) {
+        let scale_override = self.shared.scale_override.clone();
+        //Mutex, so the handler is Sync whether or not the callback is
+        let update = Mutex::new(update);
+        let handler = self.shared.events.handle(move |event| {
+            if let WindowEvent::ScaleChanged(scale) = event
+                && scale_override.get().is_none()
+            {
+                update.lock().unwrap()(*scale)
+            }
+        });
+        *self.shared.scale_handler.lock().unwrap() = Some(handler);
+    }
+
+    /// Tells the compositor which parts of the surface changed in the next frame.
+    ///
+    /// Renderers that only redraw part of the surface, such as software renderers and
+    /// mostly-static tool UIs, can report just those regions so the compositor doesn't
+    /// recompose the rest.  Rects are in the units of [`size_scale`](Self::size_scale)'s size,
+    /// with the origin at the upper-left.  Call before presenting the frame.  This is only a
+    /// hint: the compositor may still redraw more.
+    ///
+    /// # Platform Behavior
+    ///
+    /// - **Linux**: `wl_surface.damage_buffer`, applied on the next commit
+    /// - **Windows**: `RedrawWindow` with the region
+    /// - **macOS/iOS**: `CALayer` `setNeedsDisplayInRect:`
+    /// - **WASM/Android**: Does nothing; the whole surface is presented each frame
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// # use app_window::window::Window;
+    /// use app_window::coordinates::{Position, Rect, Size};
+    /// # let mut window: Window = todo!();
+    /// let surface = window.surface().await;
+    /// //only the status bar changed
+    /// surface.damage(&[Rect::new(Position::new(0.0, 580.0), Size::new(800.0, 20.0))]);
+    /// # }
+    /// ```
+    pub fn damage(&self, rects: &[Rect]) {
+        if !rects.is_empty() {
+            self.shared.sys.damage(rects)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::surface::Surface;
+    use crate::coordinates::Size;
+    use crate::surface::{SizeHandler, Surface, size_callback};
+    use std::sync::{Arc, Mutex};
 
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
     #[test]
     fn send_sync() {
         fn assert_send<T: Send + Sync>() {}
         assert_send::<Surface>();
+        //size callbacks only need to be Send
+        let count = std::cell::Cell::new(0);
+        _ = size_callback(move |_| count.set(count.get() + 1));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn handler_can_replace_itself() {
+        let handler = SizeHandler::default();
+        let seen: Arc<Mutex<Vec<(&str, Size)>>> = Arc::default();
+        let move_handler = handler.clone();
+        let move_seen = seen.clone();
+        handler.set(size_callback(move |size| {
+            move_seen.lock().unwrap().push(("first", size));
+            let move_seen = move_seen.clone();
+            move_handler.set(size_callback(move |size| {
+                move_seen.lock().unwrap().push(("second", size))
+            }));
+        }));
+        handler.call(Size::new(100.0, 100.0));
+        handler.call(Size::new(200.0, 100.0));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                ("first", Size::new(100.0, 100.0)),
+                ("second", Size::new(200.0, 100.0))
+            ]
+        );
     }
 }
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Sizes delivered once a frame, for [`Surface::size_update_per_frame`](super::Surface::size_update_per_frame).
+//!
+//! A live resize can report sizes faster than the display refreshes, and a renderer that
+//! reconfigures its swapchain for each one falls behind the pointer.  Sizes are held until the
+//! surface's next frame, which delivers only the newest.  Each delivery asks for one more frame;
+//! if no size arrived by then, the resize has settled and the completion callback runs.
+
+use super::SizeCallback as Callback;
+use crate::coordinates::Size;
+use crate::window::FrameRequester;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct State {
+    /// The newest size no frame has delivered yet.
+    pending: Option<Size>,
+    /// The last size delivered, until the resize settles.
+    delivered: Option<Size>,
+    frame_requested: bool,
+}
+
+pub(crate) struct Coalescer {
+    state: Mutex<State>,
+    update: Mutex<Option<Callback>>,
+    complete: Mutex<Option<Callback>>,
+    requester: FrameRequester,
+}
+
+impl Debug for Coalescer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Coalescer")
+    }
+}
+
+impl Coalescer {
+    pub(super) fn new(requester: FrameRequester) -> Arc<Self> {
+        Arc::new(Coalescer {
+            state: Mutex::new(State::default()),
+            update: Mutex::new(None),
+            complete: Mutex::new(None),
+            requester,
+        })
+    }
+
+    pub(super) fn set_update(&self, update: Callback) {
+        *self.update.lock().unwrap() = Some(update);
+    }
+
+    pub(super) fn set_complete(&self, complete: Callback) {
+        *self.complete.lock().unwrap() = Some(complete);
+    }
+
+    /// Holds `size` for the next frame, replacing any size already waiting.
+    pub(super) fn push(self: &Arc<Self>, size: Size) {
+        let mut state = self.state.lock().unwrap();
+        state.pending = Some(size);
+        let request = !std::mem::replace(&mut state.frame_requested, true);
+        drop(state);
+        if request {
+            self.request();
+        }
+    }
+
+    fn request(self: &Arc<Self>) {
+        //the surface may be dropped before the frame arrives
+        let weak = Arc::downgrade(self);
+        (self.requester)(Box::new(move |_timing| {
+            if let Some(coalescer) = weak.upgrade() {
+                coalescer.frame()
+            }
+        }));
+    }
+
+    fn frame(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        match state.pending.take() {
+            Some(size) => {
+                //keep frame_requested: one more frame tells us whether the resize settled
+                state.delivered = Some(size);
+                drop(state);
+                self.request();
+                //cloned out, so callbacks can replace themselves
+                let update = self.update.lock().unwrap().clone();
+                if let Some(update) = update {
+                    update.lock().unwrap()(size)
+                }
+            }
+            None => {
+                state.frame_requested = false;
+                let settled = state.delivered.take();
+                drop(state);
+                let complete = self.complete.lock().unwrap().clone();
+                if let Some(size) = settled
+                    && let Some(complete) = complete
+                {
+                    complete.lock().unwrap()(size)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Coalescer;
+    use crate::coordinates::Size;
+    use crate::surface::size_callback;
+    use crate::window::{FrameCallback, FrameTiming};
+    use std::sync::{Arc, Mutex};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn delivers_newest_size_then_completes() {
+        let frames: Arc<Mutex<Vec<FrameCallback>>> = Arc::default();
+        let move_frames = frames.clone();
+        let coalescer = Coalescer::new(Arc::new(move |frame| {
+            move_frames.lock().unwrap().push(frame)
+        }));
+        let seen: Arc<Mutex<Vec<(&str, Size)>>> = Arc::default();
+        let move_seen = seen.clone();
+        coalescer.set_update(size_callback(move |size| {
+            move_seen.lock().unwrap().push(("update", size))
+        }));
+        let move_seen = seen.clone();
+        coalescer.set_complete(size_callback(move |size| {
+            move_seen.lock().unwrap().push(("complete", size))
+        }));
+        let next_frame = || {
+            let frame = frames.lock().unwrap().pop().expect("no frame requested");
+            assert!(frames.lock().unwrap().is_empty());
+            frame(FrameTiming::new(
+                crate::application::time::Instant::now(),
+                None,
+                None,
+            ));
+        };
+
+        for width in [100.0, 200.0, 300.0] {
+            coalescer.push(Size::new(width, 100.0));
+        }
+        next_frame();
+        assert_eq!(*seen.lock().unwrap(), [("update", Size::new(300.0, 100.0))]);
+        coalescer.push(Size::new(400.0, 100.0));
+        next_frame();
+        next_frame();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                ("update", Size::new(300.0, 100.0)),
+                ("update", Size::new(400.0, 100.0)),
+                ("complete", Size::new(400.0, 100.0)),
+            ]
+        );
+        //settled, so it stops asking for frames
+        assert!(frames.lock().unwrap().is_empty());
+    }
+}
@@ -3,10 +3,10 @@
 /*!
 Platform-specific backends
 */
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 mod macos;
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub use macos::*;
 
 #[cfg(target_arch = "wasm32")]
@@ -26,3 +26,21 @@ mod linux;
 
 #[cfg(target_os = "linux")]
 pub use linux::*;
+
+#[cfg(target_os = "android")]
+mod android;
+
+#[cfg(target_os = "android")]
+pub use android::*;
+
+//targets without a built-in backend use one registered at runtime
+#[cfg(all(plugin_backend, feature = "unstable-backend"))]
+mod plugin;
+
+#[cfg(all(plugin_backend, feature = "unstable-backend"))]
+pub use plugin::*;
+
+#[cfg(all(plugin_backend, not(feature = "unstable-backend")))]
+compile_error!(
+    "app_window has no built-in backend for this target; enable the `unstable-backend` feature and register one with app_window::backend::register"
+);
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: MPL-2.0
+//! `NativeActivity` glue.  Android loads the app's library and calls `ANativeActivity_onCreate`
+//! on its UI thread; we hook the activity's callbacks and then call the app's `android_main`.
+
+use crate::application::{LifecycleEvent, report_lifecycle_event};
+use crate::coordinates::Size;
+use crate::window::{WindowEvent, WindowEventSender};
+use libc::{c_int, c_void};
+use ndk_sys::{
+    ACONFIGURATION_DENSITY_ANY, ACONFIGURATION_DENSITY_DEFAULT, ACONFIGURATION_DENSITY_MEDIUM,
+    ACONFIGURATION_DENSITY_NONE, AConfiguration_delete, AConfiguration_fromAssetManager,
+    AConfiguration_getDensity, AConfiguration_new, AInputQueue, AInputQueue_attachLooper,
+    AInputQueue_detachLooper, ALOOPER_POLL_CALLBACK, ALooper_forThread, ANativeActivity,
+    ANativeActivity_finish, ANativeWindow, ANativeWindow_getHeight, ANativeWindow_getWidth, ARect,
+};
+use std::cell::RefCell;
+use std::sync::{LazyLock, Once};
+
+unsafe extern "Rust" {
+    /// The application's entry point.  See the crate docs.
+    fn android_main();
+}
+
+pub(super) struct ActivityState {
+    activity: *mut ANativeActivity,
+    /// Null while the activity has no surface (e.g. while paused).
+    pub(super) window: *mut ANativeWindow,
+    /// Physical pixels per logical pixel.
+    pub(super) scale: f64,
+    pub(super) size_notify: Option<Box<dyn Fn(Size)>>,
+    /// Woken when a native window becomes available.
+    pub(super) window_waiters: Vec<r#continue::Sender<()>>,
+    /// Whether the activity's window has input focus.
+    pub(super) focused: bool,
+}
+
+impl ActivityState {
+    /// The window's size in logical pixels, or zero if there isn't one.
+    pub(super) fn logical_size(&self) -> Size {
+        if self.window.is_null() {
+            return Size::new(0.0, 0.0);
+        }
+        let width = unsafe { ANativeWindow_getWidth(self.window) } as f64;
+        let height = unsafe { ANativeWindow_getHeight(self.window) } as f64;
+        Size::new(width / self.scale, height / self.scale)
+    }
+
+    fn notify_size(&self) {
+        if let Some(notify) = &self.size_notify {
+            notify(self.logical_size());
+        }
+    }
+
+    fn refresh_scale(&mut self) {
+        if self.activity.is_null() {
+            return;
+        }
+        let density = unsafe {
+            let config = AConfiguration_new();
+            AConfiguration_fromAssetManager(config, (*self.activity).assetManager);
+            let density = AConfiguration_getDensity(config) as u32;
+            AConfiguration_delete(config);
+            density
+        };
+        self.scale = match density {
+            ACONFIGURATION_DENSITY_DEFAULT
+            | ACONFIGURATION_DENSITY_ANY
+            | ACONFIGURATION_DENSITY_NONE => 1.0,
+            dpi => dpi as f64 / ACONFIGURATION_DENSITY_MEDIUM as f64,
+        };
+    }
+}
+
+thread_local! {
+    pub(super) static ACTIVITY: RefCell<ActivityState> = RefCell::new(ActivityState {
+        activity: std::ptr::null_mut(),
+        window: std::ptr::null_mut(),
+        scale: 1.0,
+        size_notify: None,
+        window_waiters: Vec::new(),
+        focused: false,
+    });
+}
+
+/// Every `Window` is the activity's, so they share its events.
+pub(super) static EVENTS: LazyLock<WindowEventSender> = LazyLock::new(WindowEventSender::default);
+
+/// Finishes the current activity, if any.
+pub(super) fn finish() {
+    let activity = ACTIVITY.with_borrow(|s| s.activity);
+    if !activity.is_null() {
+        unsafe { ANativeActivity_finish(activity) };
+    }
+}
+
+/// # Safety
+/// Called by Android on the UI thread with a valid activity.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ANativeActivity_onCreate(
+    activity: *mut ANativeActivity,
+    _saved_state: *mut c_void,
+    _saved_state_size: usize,
+) {
+    //Android may recreate the activity (e.g. on rotation) without restarting the process;
+    //the executor and the app itself only start once.
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(super::main_thread::install);
+
+    let callbacks = unsafe { &mut *(*activity).callbacks };
+    callbacks.onResume = Some(on_resume);
+    callbacks.onPause = Some(on_pause);
+    callbacks.onWindowFocusChanged = Some(on_window_focus_changed);
+    callbacks.onDestroy = Some(on_destroy);
+    callbacks.onNativeWindowCreated = Some(on_native_window_created);
+    callbacks.onNativeWindowResized = Some(on_native_window_resized);
+    callbacks.onNativeWindowDestroyed = Some(on_native_window_destroyed);
+    callbacks.onInputQueueCreated = Some(on_input_queue_created);
+    callbacks.onInputQueueDestroyed = Some(on_input_queue_destroyed);
+    callbacks.onContentRectChanged = Some(on_content_rect_changed);
+    callbacks.onConfigurationChanged = Some(on_configuration_changed);
+
+    ACTIVITY.with_borrow_mut(|s| {
+        s.activity = activity;
+        s.refresh_scale();
+    });
+
+    static MAIN: Once = Once::new();
+    MAIN.call_once(|| unsafe { android_main() });
+}
+
+unsafe extern "C" fn on_resume(_activity: *mut ANativeActivity) {
+    report_lifecycle_event(LifecycleEvent::Resumed);
+}
+
+unsafe extern "C" fn on_pause(_activity: *mut ANativeActivity) {
+    report_lifecycle_event(LifecycleEvent::Paused);
+}
+
+unsafe extern "C" fn on_window_focus_changed(_activity: *mut ANativeActivity, has_focus: c_int) {
+    let focused = has_focus != 0;
+    ACTIVITY.with_borrow_mut(|s| s.focused = focused);
+    EVENTS.send(if focused {
+        WindowEvent::Focused
+    } else {
+        WindowEvent::Unfocused
+    });
+}
+
+unsafe extern "C" fn on_destroy(activity: *mut ANativeActivity) {
+    ACTIVITY.with_borrow_mut(|s| {
+        if s.activity == activity {
+            s.activity = std::ptr::null_mut();
+        }
+    });
+}
+
+unsafe extern "C" fn on_native_window_created(
+    _activity: *mut ANativeActivity,
+    window: *mut ANativeWindow,
+) {
+    let waiters = ACTIVITY.with_borrow_mut(|s| {
+        s.window = window;
+        std::mem::take(&mut s.window_waiters)
+    });
+    for waiter in waiters {
+        waiter.send(());
+    }
+    //outside the borrow; the callback may ask for a surface
+    report_lifecycle_event(LifecycleEvent::SurfaceCreated);
+    ACTIVITY.with_borrow(|s| s.notify_size());
+}
+
+unsafe extern "C" fn on_native_window_resized(
+    _activity: *mut ANativeActivity,
+    _window: *mut ANativeWindow,
+) {
+    ACTIVITY.with_borrow(|s| s.notify_size());
+}
+
+unsafe extern "C" fn on_native_window_destroyed(
+    _activity: *mut ANativeActivity,
+    _window: *mut ANativeWindow,
+) {
+    //the app must let go of the window before we return
+    report_lifecycle_event(LifecycleEvent::SurfaceLost);
+    ACTIVITY.with_borrow_mut(|s| s.window = std::ptr::null_mut());
+}
+
+unsafe extern "C" fn on_content_rect_changed(_activity: *mut ANativeActivity, _rect: *const ARect) {
+    ACTIVITY.with_borrow(|s| s.notify_size());
+}
+
+unsafe extern "C" fn on_configuration_changed(_activity: *mut ANativeActivity) {
+    ACTIVITY.with_borrow_mut(|s| s.refresh_scale());
+    ACTIVITY.with_borrow(|s| s.notify_size());
+}
+
+unsafe extern "C" fn on_input_queue_created(
+    _activity: *mut ANativeActivity,
+    queue: *mut AInputQueue,
+) {
+    //events must be drained and finished even when we ignore them, or Android reports an ANR
+    unsafe {
+        AInputQueue_attachLooper(
+            queue,
+            ALooper_forThread(),
+            ALOOPER_POLL_CALLBACK,
+            Some(super::input::process_queue),
+            queue as *mut c_void,
+        )
+    };
+}
+
+unsafe extern "C" fn on_input_queue_destroyed(
+    _activity: *mut ANativeActivity,
+    queue: *mut AInputQueue,
+) {
+    unsafe { AInputQueue_detachLooper(queue) };
+}
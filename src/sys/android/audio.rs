@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Audio hints.  Ducking and background playback are negotiated through audio focus and
+//! foreground services on the Java side, which we don't reach yet.
+
+use crate::audio::{AudioHintCapabilities, AudioHintError, AudioHints, HintSupport};
+
+pub fn audio_hint_capabilities() -> AudioHintCapabilities {
+    AudioHintCapabilities {
+        avoid_ducking: HintSupport::Unsupported,
+        background_audio: HintSupport::Unsupported,
+    }
+}
+
+pub async fn set_audio_hints(_hints: AudioHints) -> Result<(), AudioHintError> {
+    Ok(())
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Drains the activity's `AInputQueue` and hands events to the input module.
+
+use libc::{c_int, c_void};
+use ndk_sys::{
+    AInputEvent, AInputQueue, AInputQueue_finishEvent, AInputQueue_getEvent,
+    AInputQueue_preDispatchEvent,
+};
+
+pub(super) unsafe extern "C" fn process_queue(
+    _fd: c_int,
+    _events: c_int,
+    data: *mut c_void,
+) -> c_int {
+    let queue = data as *mut AInputQueue;
+    let mut event: *mut AInputEvent = std::ptr::null_mut();
+    while unsafe { AInputQueue_getEvent(queue, &mut event) } >= 0 {
+        //the IME gets first look; if it takes the event it finishes it for us
+        if unsafe { AInputQueue_preDispatchEvent(queue, event) } != 0 {
+            continue;
+        }
+        let handled = handle(event);
+        unsafe { AInputQueue_finishEvent(queue, event, handled as c_int) };
+    }
+    //keep the callback registered
+    1
+}
+
+/// Returns whether we consumed the event.  Unconsumed events get the system's default handling,
+/// e.g. Back finishes the activity.
+#[cfg(feature = "input")]
+fn handle(event: *mut AInputEvent) -> bool {
+    use ndk_sys::{AINPUT_EVENT_TYPE_KEY, AINPUT_EVENT_TYPE_MOTION, AInputEvent_getType};
+    match unsafe { AInputEvent_getType(event) } as u32 {
+        AINPUT_EVENT_TYPE_KEY => crate::input::keyboard::android::key_event(event),
+        AINPUT_EVENT_TYPE_MOTION => {
+            let (window, scale, size) =
+                super::activity::ACTIVITY.with_borrow(|s| (s.window, s.scale, s.logical_size()));
+            crate::input::mouse::android::motion_event(event, window as *mut c_void, scale, size)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(not(feature = "input"))]
+fn handle(_event: *mut AInputEvent) -> bool {
+    false
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The main thread executor.  Android owns the UI thread's loop, so rather than running our own
+//! we register an eventfd with its `ALooper` and drain a channel of closures when it fires.
+
+use crate::application::DispatchSlice;
+use libc::{EFD_CLOEXEC, EFD_NONBLOCK, c_int, c_void, eventfd};
+use ndk_sys::{
+    ALOOPER_EVENT_INPUT, ALOOPER_POLL_CALLBACK, ALooper_acquire, ALooper_addFd, ALooper_forThread,
+};
+use std::sync::OnceLock;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread::ThreadId;
+
+type Closure = Box<dyn FnOnce() + Send>;
+
+struct MainThreadSender {
+    sender: Sender<Closure>,
+    eventfd: c_int,
+}
+
+impl MainThreadSender {
+    fn send(&self, closure: Closure) {
+        self.sender.send(closure).expect("Can't send closure");
+        self.wake();
+    }
+
+    /// Makes the looper call [`drain`].
+    fn wake(&self) {
+        let val = 1_u64;
+        let w = unsafe {
+            libc::write(
+                self.eventfd,
+                &val as *const _ as *const c_void,
+                std::mem::size_of_val(&val),
+            )
+        };
+        assert_eq!(
+            w,
+            std::mem::size_of_val(&val) as isize,
+            "Failed to write to eventfd: {err}",
+            err = std::io::Error::last_os_error()
+        );
+    }
+}
+
+static MAIN_THREAD: OnceLock<ThreadId> = OnceLock::new();
+static MAIN_THREAD_SENDER: OnceLock<MainThreadSender> = OnceLock::new();
+
+pub fn is_main_thread() -> bool {
+    MAIN_THREAD.get() == Some(&std::thread::current().id())
+}
+
+/// Claims the calling thread as the main thread and attaches the executor to its looper.
+///
+/// Called once per process, from the activity's UI thread.
+pub(super) fn install() {
+    MAIN_THREAD
+        .set(std::thread::current().id())
+        .expect("Main thread already installed");
+    let looper = unsafe { ALooper_forThread() };
+    assert!(!looper.is_null(), "UI thread has no looper");
+    //we keep the looper for the life of the process
+    unsafe { ALooper_acquire(looper) };
+
+    let fd = unsafe { eventfd(0, EFD_CLOEXEC | EFD_NONBLOCK) };
+    assert!(
+        fd >= 0,
+        "Can't create eventfd: {err}",
+        err = std::io::Error::last_os_error()
+    );
+    let (sender, receiver) = channel();
+    //leaked; the looper calls us with it for the life of the process
+    let receiver = Box::into_raw(Box::new(receiver));
+    let r = unsafe {
+        ALooper_addFd(
+            looper,
+            fd,
+            ALOOPER_POLL_CALLBACK,
+            ALOOPER_EVENT_INPUT as c_int,
+            Some(drain),
+            receiver as *mut c_void,
+        )
+    };
+    assert_eq!(r, 1, "ALooper_addFd failed");
+    MAIN_THREAD_SENDER
+        .set(MainThreadSender {
+            sender,
+            eventfd: fd,
+        })
+        .unwrap_or_else(|_| panic!("Main thread sender already set"));
+}
+
+unsafe extern "C" fn drain(fd: c_int, _events: c_int, data: *mut c_void) -> c_int {
+    //reset the counter; we drain the channel regardless of its value
+    let mut val = 0_u64;
+    _ = unsafe {
+        libc::read(
+            fd,
+            &mut val as *mut _ as *mut c_void,
+            std::mem::size_of_val(&val),
+        )
+    };
+    let receiver = unsafe { &*(data as *const Receiver<Closure>) };
+    let mut slice = DispatchSlice::new();
+    while let Ok(closure) = receiver.try_recv() {
+        closure();
+        if slice.record_op() {
+            //come back once the looper has serviced input
+            MAIN_THREAD_SENDER
+                .get()
+                .expect("Main thread sender not set")
+                .wake();
+            break;
+        }
+    }
+    //keep the callback registered
+    1
+}
+
+pub fn on_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
+    MAIN_THREAD_SENDER
+        .get()
+        .expect("Main thread sender not set")
+        .send(Box::new(closure));
+}
+
+/// ALooper has no timers of its own, so a thread waits out the delay.
+pub fn on_main_thread_after<F: FnOnce() + Send + 'static>(delay: std::time::Duration, closure: F) {
+    std::thread::Builder::new()
+        .name("app_window timer".to_string())
+        .spawn(move || {
+            std::thread::sleep(delay);
+            on_main_thread(closure);
+        })
+        .expect("Can't spawn timer thread");
+}
+
+pub fn run_main_thread<F: FnOnce() + Send + 'static>(
+    closure: F,
+) -> Result<(), crate::application::MainError> {
+    //the UI thread's loop belongs to Android and is already running, so there's nothing to
+    //block on here; onCreate must return for the activity to make progress.
+    std::thread::Builder::new()
+        .name("app_window main".to_string())
+        .spawn(closure)
+        .expect("Can't spawn main closure thread");
+    Ok(())
+}
+
+pub fn stop_main_thread() {
+    on_main_thread(super::activity::finish);
+}
+
+pub async fn alert(message: String) {
+    //todo: show an AlertDialog via JNI
+    logwise::warn_sync!("alert: {message}", message = message);
+}
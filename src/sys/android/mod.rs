@@ -0,0 +1,499 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Android backend, via `NativeActivity`.
+//!
+//! An activity has exactly one native window, which comes and goes as the app is paused and
+//! resumed.  Every [`Window`] refers to it, and surfaces wait for it to exist.
+
+use crate::application::time::Instant;
+use crate::application::{
+    ActivationPolicy, ActivationPolicyError, CapturePermission, PerformanceHintCapabilities,
+    PerformanceHintError, PerformanceHints,
+};
+use crate::audio::HintSupport;
+use crate::clipboard::ClipboardError;
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::{Color, PickColorError};
+use crate::drag::{DragData, DragError, DragImage, DragReporter};
+use crate::notification::{Notification, NotificationError};
+use crate::power::PowerError;
+use crate::tray::{TrayError, TrayState};
+use crate::window::{
+    CanvasBehavior, CloseBehavior, CreateOptions, Cursor, DecorationTheme, DecorationsMode,
+    FrameCallback, FrameRequester, FrameTiming, IconData, NativeHandle, PreviewImage,
+    WindowDebugState, WindowEventSender, WindowSettingError, WindowState,
+};
+use ndk_sys::{
+    AChoreographer_getInstance, AChoreographer_postFrameCallback64, ANativeWindow,
+    ANativeWindow_acquire, ANativeWindow_release,
+};
+use raw_window_handle::{
+    AndroidDisplayHandle, AndroidNdkWindowHandle, RawDisplayHandle, RawWindowHandle,
+};
+use send_cells::unsafe_send_cell::UnsafeSendCell;
+use std::ffi::c_void;
+use std::fmt::Display;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+mod activity;
+mod audio;
+mod input;
+mod main_thread;
+
+use activity::ACTIVITY;
+pub use audio::{audio_hint_capabilities, set_audio_hints};
+pub use main_thread::{
+    alert, is_main_thread, on_main_thread, on_main_thread_after, run_main_thread, stop_main_thread,
+};
+
+/// `frameTimeNanos` is on `CLOCK_MONOTONIC`, which `Instant` can't be made from.
+unsafe extern "C" fn frame_done(_frame_time_nanos: i64, data: *mut c_void) {
+    let frame = unsafe { Box::from_raw(data as *mut FrameCallback) };
+    frame(FrameTiming::new(Instant::now(), None, None));
+}
+
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    Err(PickColorError::Unsupported)
+}
+
+//posting needs a NotificationChannel, and the POST_NOTIFICATIONS permission from Java
+pub async fn post_notification(_notification: Notification) -> Result<(), NotificationError> {
+    Err(NotificationError::Unsupported)
+}
+
+//ClipboardManager's listener is Java, which we'd need a JNI bridge for
+pub fn watch_clipboard() -> Result<(), ClipboardError> {
+    Err(ClipboardError::Unsupported)
+}
+
+pub fn unwatch_clipboard() {}
+
+//PowerManager's broadcasts are Java too
+pub async fn watch_power() -> Result<(), PowerError> {
+    Err(PowerError::Unsupported)
+}
+
+pub fn unwatch_power() {}
+
+//the status bar's notifications are Android's nearest thing, and aren't icons we keep
+#[derive(Debug)]
+pub struct TrayIcon(());
+
+impl TrayIcon {
+    pub async fn new(_id: u64, _state: TrayState) -> Result<Self, TrayError> {
+        Err(TrayError::Unsupported)
+    }
+
+    pub async fn update(&self, _state: TrayState) -> Result<(), TrayError> {
+        Err(TrayError::Unsupported)
+    }
+}
+
+//MediaProjection shows its consent dialog for every capture session
+pub async fn capture_permission_state() -> CapturePermission {
+    CapturePermission::AskEachTime
+}
+
+pub async fn request_capture_permission() -> CapturePermission {
+    CapturePermission::AskEachTime
+}
+
+pub async fn set_activation_policy(_policy: ActivationPolicy) -> Result<(), ActivationPolicyError> {
+    Err(ActivationPolicyError::Unsupported)
+}
+
+//sustained performance mode and thread priorities are set from Java
+pub fn performance_hint_capabilities() -> PerformanceHintCapabilities {
+    PerformanceHintCapabilities {
+        prefer_discrete_gpu: HintSupport::Unsupported,
+        high_process_priority: HintSupport::Unsupported,
+        disable_power_throttling: HintSupport::Unsupported,
+    }
+}
+
+pub async fn set_performance_hints(_hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct FullscreenError;
+
+impl Display for FullscreenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "FullscreenError")
+    }
+}
+impl std::error::Error for FullscreenError {}
+
+#[derive(Debug)]
+pub struct Window {}
+
+impl Window {
+    pub async fn new(_position: Position, _size: Size, _title: String) -> Self {
+        //the activity decides where its window goes and how big it is
+        Window {}
+    }
+
+    pub async fn new_splash(_size: Size, _image: PreviewImage) -> Option<Self> {
+        //the activity's launch theme covers this
+        None
+    }
+
+    pub async fn fade_out(self) {}
+
+    pub async fn new_overlay(_size: Size, _image: PreviewImage) -> Option<Self> {
+        //windows fill the screen, so there is nowhere to float one
+        None
+    }
+
+    pub async fn set_splash_image(&self, _image: PreviewImage) {}
+
+    pub async fn new_with_options(
+        position: Position,
+        size: Size,
+        title: String,
+        _options: CreateOptions,
+    ) -> Self {
+        //activities have no decorations, and are shown by the system
+        Window::new(position, size, title).await
+    }
+
+    pub async fn new_parked(_position: Position, _size: Size, _title: String) -> Option<Self> {
+        None
+    }
+
+    pub async fn unpark(&self, _title: String) {}
+
+    pub async fn default() -> Self {
+        Window {}
+    }
+
+    pub async fn fullscreen(_title: String, _output: Option<u32>) -> Result<Self, FullscreenError> {
+        //activities are fullscreen already
+        Ok(Window {})
+    }
+
+    pub async fn surface(&self) -> Surface {
+        loop {
+            let (sender, fut) = r#continue::continuation();
+            let window = crate::application::on_main_thread("Window::surface".into(), move || {
+                ACTIVITY.with_borrow_mut(|s| {
+                    if s.window.is_null() {
+                        s.window_waiters.push(sender);
+                        None
+                    } else {
+                        sender.send(());
+                        //hold a reference so the pointer stays valid for as long as the Surface
+                        unsafe { ANativeWindow_acquire(s.window) };
+                        Some(UnsafeSendCell::new(s.window))
+                    }
+                })
+            })
+            .await;
+            if let Some(window) = window {
+                let window = unsafe { window.into_inner() };
+                return Surface {
+                    window: NonNull::new(window).expect("ANativeWindow is null"),
+                };
+            }
+            //the window may be gone again by the time we look, so check again
+            fut.await;
+        }
+    }
+
+    pub async fn set_visible(&self, _visible: bool) {
+        //visibility follows the activity's lifecycle
+    }
+
+    pub async fn set_canvas_behavior(&self, _behavior: CanvasBehavior) {
+        //only browsers have defaults to suppress
+    }
+
+    pub async fn set_close_behavior(&self, _close_behavior: CloseBehavior) {
+        //Back finishes the activity; there's no close button to configure
+    }
+
+    pub async fn show_window_menu(&self, _position: Position) {}
+
+    //the activity fills its display area
+    pub async fn set_size(&self, _size: Size) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_position(&self, _position: Position) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        _visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_skip_taskbar(&self, _skip: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn inhibit_system_shortcuts(&self, _inhibit: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn confine_pointer(&self, _region: Option<Rect>) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn release_pointer(&self) -> Result<(), WindowSettingError> {
+        Ok(())
+    }
+
+    pub async fn set_preview_image(
+        &self,
+        _image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_icon(&self, _icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        //the launcher icon comes from the manifest
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_badge(&self, _label: Option<String>) -> Result<(), WindowSettingError> {
+        //launchers badge apps from their notifications
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_progress(&self, _progress: Option<f64>) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_cursor(&self, _cursor: Cursor) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        _visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        //InputMethodManager is only reachable over JNI
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_decorations(&self, _mode: DecorationsMode) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_decoration_theme(
+        &self,
+        _theme: DecorationTheme,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_resize_border(&self, _width: f64) -> Result<(), WindowSettingError> {
+        //the system draws the frame
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn begin_sheet(&self, _sheet: &Window) -> Result<(), WindowSettingError> {
+        //there's one window, the activity's
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn show_modal(&self, _sheet: &Window) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    //the activity's window is the system's to arrange
+    pub async fn set_resizable(&self, _resizable: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_size_limits(
+        &self,
+        _min: Option<Size>,
+        _max: Option<Size>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_click_through(&self, _click_through: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn update_accessibility_tree(
+        &self,
+        _update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn set_accessibility_action_handler(
+        &self,
+        _on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn is_focused(&self) -> bool {
+        crate::application::on_main_thread("Window::is_focused".into(), || {
+            ACTIVITY.with_borrow(|s| s.focused)
+        })
+        .await
+    }
+
+    pub async fn set_always_on_top(&self, _on_top: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_background(&self, _color: Color) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_maximized(&self, _maximized: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_transparent(&self, _transparent: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_parent(&self, _parent: &Window) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    /// Input reports the activity's ANativeWindow, which is replaced whenever the activity
+    /// recreates its surface, so it can't identify this window.
+    pub async fn input_handle(&self) -> Option<usize> {
+        None
+    }
+
+    pub async fn native_handle(&self) -> Option<NativeHandle> {
+        None
+    }
+
+    pub fn event_sender(&self) -> WindowEventSender {
+        activity::EVENTS.clone()
+    }
+
+    pub async fn normal_bounds(&self) -> Option<(Option<Position>, Size)> {
+        None
+    }
+
+    pub async fn outer_position(&self) -> Option<Position> {
+        None
+    }
+
+    pub fn request_frame(&self, frame: FrameCallback) {
+        request_frame(frame)
+    }
+
+    pub async fn scale_factor(&self) -> f64 {
+        crate::application::on_main_thread("Window::scale_factor".into(), || {
+            ACTIVITY.with_borrow(|s| s.scale)
+        })
+        .await
+    }
+
+    pub async fn debug_state(&self) -> WindowDebugState {
+        crate::application::on_main_thread("Window::debug_state".into(), || {
+            ACTIVITY.with_borrow(|s| WindowDebugState {
+                backend: "android",
+                applied_size: Some(s.logical_size()),
+                proposed_size: None,
+                scale: Some(s.scale),
+                preferred_scale: None,
+                outputs: Vec::new(),
+                focused: Some(s.focused),
+                visible: Some(!s.window.is_null()),
+                configured: None,
+                decorations: Some(DecorationsMode::None),
+                buffer: None,
+                cursor: None,
+                //activities fill their task's window
+                state: Some(WindowState::Normal),
+            })
+        })
+        .await
+    }
+
+    pub async fn start_drag(
+        &self,
+        _data: DragData,
+        _image: Option<DragImage>,
+        _reporter: DragReporter,
+    ) -> Result<(), DragError> {
+        Err(DragError::Unsupported)
+    }
+}
+
+/// Calls `frame` on the choreographer's next frame, which the activity's one window shares.
+fn request_frame(frame: FrameCallback) {
+    on_main_thread(move || {
+        //the main thread has a looper, so it has a choreographer
+        let choreographer = unsafe { AChoreographer_getInstance() };
+        let data = Box::into_raw(Box::new(frame)) as *mut c_void;
+        unsafe { AChoreographer_postFrameCallback64(choreographer, Some(frame_done), data) };
+    });
+}
+
+#[derive(Debug)]
+pub struct Surface {
+    window: NonNull<ANativeWindow>,
+}
+
+//ANativeWindow is reference counted and thread safe
+unsafe impl Send for Surface {}
+unsafe impl Sync for Surface {}
+
+impl Surface {
+    pub async fn size_scale(&self) -> (Size, f64) {
+        crate::application::on_main_thread("Surface::size_scale".into(), || {
+            ACTIVITY.with_borrow(|s| (s.logical_size(), s.scale))
+        })
+        .await
+    }
+
+    pub fn size_main(&self) -> (Size, f64) {
+        assert!(
+            crate::application::is_main_thread(),
+            "Call from main thread only"
+        );
+        ACTIVITY.with_borrow(|s| (s.logical_size(), s.scale))
+    }
+
+    pub fn raw_window_handle(&self) -> RawWindowHandle {
+        RawWindowHandle::AndroidNdk(AndroidNdkWindowHandle::new(self.window.cast()))
+    }
+
+    pub fn raw_display_handle(&self) -> RawDisplayHandle {
+        RawDisplayHandle::Android(AndroidDisplayHandle::new())
+    }
+
+    pub fn size_update<F: Fn(Size) + Send + 'static>(&mut self, update: F) {
+        on_main_thread(move || {
+            ACTIVITY.with_borrow_mut(|s| s.size_notify = Some(Box::new(update)));
+        });
+    }
+
+    pub fn frame_requester(&self) -> FrameRequester {
+        Arc::new(request_frame)
+    }
+
+    pub fn damage(&self, _rects: &[Rect]) {
+        //ANativeWindow only takes a dirty rect for CPU locking, which isn't ours to do
+    }
+}
+
+impl Drop for Surface {
+    fn drop(&mut self) {
+        unsafe { ANativeWindow_release(self.window.as_ptr()) };
+    }
+}
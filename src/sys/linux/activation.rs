@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Asking for focus with `xdg_activation_v1`.
+//!
+//! The compositor hands out a token, which we spend activating our surface.  Compositors guard
+//! against focus stealing, so a token made during one of our button presses may take focus,
+//! while others usually only mark the window as wanting attention.
+
+use super::App;
+use super::main_thread::MainThreadInfo;
+use crate::sys::window::WindowInternal;
+use crate::window::WindowSettingError;
+use wayland_client::protocol::wl_surface::WlSurface;
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::xdg::activation::v1::client::xdg_activation_token_v1::{
+    self, XdgActivationTokenV1,
+};
+use wayland_protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
+
+/// What to activate once the token arrives.
+pub(super) struct TokenData {
+    activation: XdgActivationV1,
+    surface: WlSurface,
+}
+
+impl Dispatch<XdgActivationV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &XdgActivationV1,
+        event: <XdgActivationV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got XdgActivationV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<XdgActivationTokenV1, TokenData> for App {
+    fn event(
+        _state: &mut Self,
+        proxy: &XdgActivationTokenV1,
+        event: <XdgActivationTokenV1 as Proxy>::Event,
+        data: &TokenData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got XdgActivationTokenV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+        if let xdg_activation_token_v1::Event::Done { token } = event {
+            data.activation.activate(token, &data.surface);
+            proxy.destroy();
+        }
+    }
+}
+
+/// Asks the compositor to focus the window.  Call on the main thread.
+pub(super) fn request_focus(
+    info: &MainThreadInfo,
+    window: &WindowInternal,
+) -> Result<(), WindowSettingError> {
+    let activation = info
+        .activation
+        .as_ref()
+        .ok_or(WindowSettingError::Unsupported)?;
+    //lost with the connection; nothing to focus until it's rebuilt
+    let Some(surface) = window.wl_surface.clone() else {
+        return Ok(());
+    };
+    let token = activation.get_activation_token(
+        &info.queue_handle,
+        TokenData {
+            activation: activation.clone(),
+            surface: surface.clone(),
+        },
+    );
+    token.set_surface(&surface);
+    //the press shows the user asked for this
+    if let Some(serial) = window.wl_pointer_button_serial
+        && let Some(seat) = info.app_state.seat.lock().unwrap().as_ref()
+    {
+        token.set_serial(serial, seat);
+    }
+    token.commit();
+    Ok(())
+}
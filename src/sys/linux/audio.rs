@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Audio hints.  Neither PipeWire nor PulseAudio ducks or throttles an app based on window
+//! focus, so there's nothing to do.
+
+use crate::audio::{AudioHintCapabilities, AudioHintError, AudioHints, HintSupport};
+
+pub fn audio_hint_capabilities() -> AudioHintCapabilities {
+    AudioHintCapabilities {
+        avoid_ducking: HintSupport::NotNeeded,
+        background_audio: HintSupport::NotNeeded,
+    }
+}
+
+pub async fn set_audio_hints(_hints: AudioHints) -> Result<(), AudioHintError> {
+    Ok(())
+}
@@ -1,12 +1,27 @@
 // SPDX-License-Identifier: MPL-2.0
-use super::{BUTTON_WIDTH, CLOSE_ID, MAXIMIZE_ID, MINIMIZE_ID, TITLEBAR_HEIGHT};
+use super::{CLOSE_ID, CONTENT_ID, MAXIMIZE_ID, MINIMIZE_ID};
 use crate::coordinates::Size;
 
 use crate::sys::window::WindowInternal;
-use accesskit::{Action, ActionRequest, NodeId, Rect, Role, TreeId, TreeUpdate};
+use crate::window::DecorationTheme;
+use crate::window::accessibility::AccessibilityContent;
+use accesskit::{Action, ActionRequest, NodeId, Rect, Role, TreeId, TreeUpdate, Uuid};
 use std::sync::{Arc, Mutex};
 
-pub fn build_tree_update(title: String, window_size: Size) -> TreeUpdate {
+/// The app's tree, as a subtree of ours.
+pub const CONTENT_TREE: TreeId = TreeId(Uuid::from_u128(1));
+
+/// The window, our decor's buttons if we draw them with `buttons`, and where the app's tree
+/// goes if `content`.
+pub fn build_tree_update(
+    title: String,
+    window_size: Size,
+    buttons: Option<&DecorationTheme>,
+    content: bool,
+) -> TreeUpdate {
+    let (titlebar_height, button_width) = buttons.map_or((0.0, 0.0), |theme| {
+        (theme.titlebar_height(), theme.button_width())
+    });
     let mut window = accesskit::Node::new(Role::Window);
     window.set_label(title);
     //accesskit rect is min and max, not origin and height!
@@ -18,21 +33,16 @@ pub fn build_tree_update(title: String, window_size: Size) -> TreeUpdate {
     ));
     let mut title_bar = accesskit::Node::new(Role::TitleBar);
     title_bar.set_label("app_window");
-    title_bar.set_bounds(Rect::new(
-        0.0,
-        0.0,
-        window_size.width(),
-        TITLEBAR_HEIGHT as f64,
-    ));
+    title_bar.set_bounds(Rect::new(0.0, 0.0, window_size.width(), titlebar_height));
     let mut close_button = accesskit::Node::new(Role::Button);
     close_button.add_action(Action::Click);
     close_button.add_action(Action::Focus);
 
     close_button.set_bounds(Rect::new(
-        window_size.width() - BUTTON_WIDTH as f64,
+        window_size.width() - button_width,
         0.0,
         window_size.width(),
-        TITLEBAR_HEIGHT as f64,
+        titlebar_height,
     ));
     close_button.set_label("Close");
 
@@ -40,10 +50,10 @@ pub fn build_tree_update(title: String, window_size: Size) -> TreeUpdate {
     maximize_button.add_action(Action::Click);
     maximize_button.add_action(Action::Focus);
     maximize_button.set_bounds(Rect::new(
-        window_size.width() - BUTTON_WIDTH as f64 * 2.0,
+        window_size.width() - button_width * 2.0,
         0.0,
-        window_size.width() - BUTTON_WIDTH as f64 * 1.0,
-        TITLEBAR_HEIGHT as f64,
+        window_size.width() - button_width * 1.0,
+        titlebar_height,
     ));
     maximize_button.set_label("Maximize");
 
@@ -51,16 +61,42 @@ pub fn build_tree_update(title: String, window_size: Size) -> TreeUpdate {
     minimize_button.add_action(Action::Click);
     minimize_button.add_action(Action::Focus);
     minimize_button.set_bounds(Rect::new(
-        window_size.width() - BUTTON_WIDTH as f64 * 3.0,
+        window_size.width() - button_width * 3.0,
         0.0,
-        window_size.width() - BUTTON_WIDTH as f64 * 2.0,
-        TITLEBAR_HEIGHT as f64,
+        window_size.width() - button_width * 2.0,
+        titlebar_height,
     ));
     minimize_button.set_label("Minimize");
 
     //window.set_children(vec![NodeId(2)]);
     //title_bar.set_children(vec![NodeId(3),NodeId(4), NodeId(5)]);
-    window.set_children(vec![CLOSE_ID, MINIMIZE_ID, MAXIMIZE_ID]);
+    //otherwise the compositor draws them, and publishes its own
+    let mut button_nodes = if buttons.is_some() {
+        window.set_children(vec![CLOSE_ID, MINIMIZE_ID, MAXIMIZE_ID]);
+        vec![
+            /*(NodeId(2), title_bar),*/ (CLOSE_ID, close_button),
+            (MAXIMIZE_ID, maximize_button),
+            (MINIMIZE_ID, minimize_button),
+        ]
+    } else {
+        Vec::new()
+    };
+    //our buttons float over the app's content, which fills the window
+    let focus = if content {
+        let mut graft = accesskit::Node::new(Role::GenericContainer);
+        graft.set_tree_id(CONTENT_TREE);
+        graft.set_bounds(Rect::new(
+            0.0,
+            0.0,
+            window_size.width(),
+            window_size.height(),
+        ));
+        window.push_child(CONTENT_ID);
+        button_nodes.push((CONTENT_ID, graft));
+        CONTENT_ID
+    } else {
+        NodeId(1)
+    };
 
     let tree = accesskit::Tree {
         root: NodeId(1),
@@ -68,16 +104,13 @@ pub fn build_tree_update(title: String, window_size: Size) -> TreeUpdate {
         toolkit_version: Some("0.1.0".to_string()),
     };
 
+    let mut nodes = vec![(NodeId(1), window)];
+    nodes.extend(button_nodes);
     accesskit::TreeUpdate {
-        nodes: vec![
-            (NodeId(1), window),
-            /*(NodeId(2), title_bar),*/ (CLOSE_ID, close_button),
-            (MAXIMIZE_ID, maximize_button),
-            (MINIMIZE_ID, minimize_button),
-        ],
+        nodes,
         tree: Some(tree),
         tree_id: TreeId::ROOT,
-        focus: NodeId(1),
+        focus,
     }
 }
 
@@ -90,6 +123,7 @@ pub struct Inner {
 pub(super) struct AX {
     inner: Arc<Inner>,
     window_internal: Arc<Mutex<WindowInternal>>,
+    content: Arc<Mutex<AccessibilityContent>>,
 }
 
 impl AX {
@@ -97,57 +131,70 @@ impl AX {
         window_size: Size,
         title: String,
         window_internal: Arc<Mutex<WindowInternal>>,
+        content: Arc<Mutex<AccessibilityContent>>,
     ) -> Self {
         AX {
             inner: Arc::new(Inner { window_size, title }),
             window_internal,
+            content,
         }
     }
 }
 
 impl accesskit::ActivationHandler for AX {
     fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        let content = self.content.lock().unwrap().is_published();
+        if content {
+            //the adapter is locked until we return, so this waits for it to be active
+            let window_internal = self.window_internal.clone();
+            super::main_thread::on_main_thread(move || {
+                let mut locked = window_internal.lock().unwrap();
+                let update = locked.ax_content.lock().unwrap().full_update();
+                if let (Some(update), Some(a)) = (update, locked.adapter.as_mut()) {
+                    a.update_if_active(|| update);
+                }
+            });
+        }
+        //until the compositor says otherwise, we draw the buttons; a theme set since will
+        //update the tree
         Some(build_tree_update(
             self.inner.title.clone(),
             self.inner.window_size,
+            Some(&DecorationTheme::default()),
+            content,
         ))
     }
 }
 
 impl accesskit::ActionHandler for AX {
     fn do_action(&mut self, request: ActionRequest) {
-        if request.target_node == CLOSE_ID {
-            match request.action {
-                Action::Click => {
-                    self.window_internal.lock().unwrap().close_window();
-                }
-                _ => unimplemented!(),
-            }
-        } else if request.target_node == MAXIMIZE_ID {
-            match request.action {
-                Action::Click => {
-                    self.window_internal.lock().unwrap().maximize();
-                }
-                _ => unimplemented!(),
-            }
-        } else if request.target_node == MINIMIZE_ID {
-            match request.action {
-                Action::Click => {
-                    self.window_internal.lock().unwrap().minimize();
-                }
-                _ => unimplemented!(),
+        if request.target_tree == CONTENT_TREE {
+            AccessibilityContent::perform(&self.content, request);
+            return;
+        }
+        //the buttons only click; they can't take keyboard focus
+        if request.action != Action::Click {
+            return;
+        }
+        let mut window_internal = self.window_internal.lock().unwrap();
+        match request.target_node {
+            CLOSE_ID => window_internal.close_window(),
+            MAXIMIZE_ID => window_internal.maximize(),
+            MINIMIZE_ID => window_internal.minimize(),
+            target => {
+                logwise::debuginternal_sync!(
+                    "Ignoring click on unknown accessibility node {target}",
+                    target = logwise::privacy::LogIt(&target)
+                );
             }
-        } else {
-            unimplemented!(
-                "Unknown action target: {target:?}",
-                target = request.target_node
-            );
         }
     }
 }
 
 impl accesskit::DeactivationHandler for AX {
     fn deactivate_accessibility(&mut self) {
-        todo!()
+        //the adapter stops asking for updates; keep the app's tree for whoever listens next,
+        //but not the nodes it has since removed
+        self.content.lock().unwrap().forget_removed();
     }
 }
@@ -1,16 +1,17 @@
 // SPDX-License-Identifier: MPL-2.0
 use super::{App, BufferReleaseInfo, ReleaseOpt};
 use crate::sys::window::WindowInternal;
+#[cfg(feature = "decorations")]
+use crate::window::DecorationTheme;
+use crate::window::PreviewImage;
 use libc::{MFD_ALLOW_SEALING, MFD_CLOEXEC, c_char, memfd_create};
 use memmap2::MmapMut;
 use std::fs::File;
-use std::io::Cursor;
 use std::os::fd::{AsFd, AsRawFd, FromRawFd};
 use std::sync::{Arc, Mutex};
 use wayland_client::QueueHandle;
 use wayland_client::protocol::wl_buffer::WlBuffer;
 use wayland_client::protocol::wl_shm::{Format, WlShm};
-use zune_png::zune_core::result::DecodingResult;
 
 #[derive(Debug, Clone)]
 pub struct AllocatedBuffer {
@@ -26,6 +27,7 @@ impl AllocatedBuffer {
         shm: &WlShm,
         queue_handle: &QueueHandle<App>,
         window_internal: Arc<Mutex<WindowInternal>>,
+        fill: Option<&PreviewImage>,
     ) -> AllocatedBuffer {
         logwise::debuginternal_sync!(
             "Creating shm buffer width {width}, height {height}",
@@ -55,9 +57,17 @@ impl AllocatedBuffer {
         }
 
         let mut mmap = unsafe { MmapMut::map_mut(&file) }.unwrap();
-        const DEFAULT_COLOR: [u8; 4] = [0, 0, 0xFF, 0xFF];
-        for pixel in mmap.chunks_exact_mut(4) {
-            pixel.copy_from_slice(&DEFAULT_COLOR); //I guess due to endiannness we are actually BGRA?
+        match fill {
+            Some(image) => {
+                let image = image.resized(width as u32, height as u32);
+                mmap.copy_from_slice(&crate::drag::premultiplied_bgra(image.rgba()));
+            }
+            None => {
+                const DEFAULT_COLOR: [u8; 4] = [0, 0, 0xFF, 0xFF];
+                for pixel in mmap.chunks_exact_mut(4) {
+                    pixel.copy_from_slice(&DEFAULT_COLOR); //I guess due to endiannness we are actually BGRA?
+                }
+            }
         }
 
         let pool = shm.create_pool(file.as_fd(), width * height * 4, queue_handle, ());
@@ -97,22 +107,42 @@ impl AllocatedBuffer {
     }
 }
 
+/// Creates a buffer of our title bar buttons, `scale` pixels to the logical pixel.
+#[cfg(feature = "decorations")]
 pub(super) fn create_shm_buffer_decor(
     shm: &WlShm,
     queue_handle: &QueueHandle<App>,
     window_internal: Arc<Mutex<WindowInternal>>,
+    theme: &DecorationTheme,
+    scale: u32,
+) -> AllocatedBuffer {
+    let image = theme.render(scale);
+    create_shm_buffer_pixels(
+        shm,
+        queue_handle,
+        window_internal,
+        image.width() as i32,
+        image.height() as i32,
+        &crate::drag::premultiplied_bgra(image.rgba()),
+    )
+}
+
+/**
+Creates a buffer holding a copy of `pixels` (ARGB8888, i.e. BGRA in memory).
+
+Like the decor buffer, the buffer is destroyed once the compositor releases it.
+*/
+pub(super) fn create_shm_buffer_pixels(
+    shm: &WlShm,
+    queue_handle: &QueueHandle<App>,
+    window_internal: Arc<Mutex<WindowInternal>>,
+    width: i32,
+    height: i32,
+    pixels: &[u8],
 ) -> AllocatedBuffer {
-    let decor = include_bytes!("../../../linux_assets/decor.png");
-    let mut decode_decor = zune_png::PngDecoder::new(Cursor::new(&decor[..]));
-    let decode = decode_decor.decode().expect("Can't decode decor");
-    let dimensions = decode_decor.dimensions().expect("Can't decode decor");
-    let decor = match decode {
-        DecodingResult::U8(d) => d,
-        _ => todo!(),
-    };
     let file = unsafe {
         memfd_create(
-            b"decor\0" as *const _ as *const c_char,
+            b"pixels\0" as *const _ as *const c_char,
             MFD_ALLOW_SEALING | MFD_CLOEXEC,
         )
     };
@@ -124,7 +154,7 @@ pub(super) fn create_shm_buffer_decor(
     }
     let file = unsafe { File::from_raw_fd(file) };
 
-    let r = unsafe { libc::ftruncate(file.as_raw_fd(), (dimensions.0 * dimensions.1 * 4) as i64) };
+    let r = unsafe { libc::ftruncate(file.as_raw_fd(), (width * height * 4) as i64) };
     if r < 0 {
         panic!(
             "Failed to truncate memfd: {err}",
@@ -133,15 +163,10 @@ pub(super) fn create_shm_buffer_decor(
     }
 
     let mut mmap = unsafe { MmapMut::map_mut(&file) }.unwrap();
-    for (pixel, decor_pixel) in mmap.chunks_exact_mut(4).zip(decor.chunks_exact(4)) {
-        pixel.copy_from_slice(decor_pixel);
+    for (pixel, source_pixel) in mmap.chunks_exact_mut(4).zip(pixels.chunks_exact(4)) {
+        pixel.copy_from_slice(source_pixel);
     }
-    let pool = shm.create_pool(
-        file.as_fd(),
-        dimensions.0 as i32 * dimensions.1 as i32 * 4,
-        queue_handle,
-        (),
-    );
+    let pool = shm.create_pool(file.as_fd(), width * height * 4, queue_handle, ());
     let release_opt = Arc::new(Mutex::new(Some(ReleaseOpt {
         _file: file,
         _mmap: Arc::new(mmap),
@@ -155,17 +180,17 @@ pub(super) fn create_shm_buffer_decor(
 
     let buf = pool.create_buffer(
         0,
-        dimensions.0 as i32,
-        dimensions.1 as i32,
-        dimensions.0 as i32 * 4,
+        width,
+        height,
+        width * 4,
         Format::Argb8888,
         queue_handle,
         release_info,
     );
     let allocated_buffer = AllocatedBuffer {
         buffer: buf,
-        width: dimensions.0 as i32,
-        height: dimensions.1 as i32,
+        width,
+        height,
     };
     release_opt
         .lock()
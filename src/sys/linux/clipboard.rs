@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Clipboard change notifications.
+//!
+//! `ext-data-control` is meant for clipboard managers, and tells us about every selection
+//! change.  Without it we fall back on `wl_data_device`, whose `selection` event only comes
+//! while one of our surfaces has keyboard focus, and again each time one gains it.
+
+use super::main_thread::MAIN_THREAD_INFO;
+use crate::clipboard::ClipboardError;
+use std::sync::atomic::AtomicBool;
+use wayland_client::protocol::wl_seat::WlSeat;
+
+/// Call on the main thread.
+pub fn watch_clipboard() -> Result<(), ClipboardError> {
+    MAIN_THREAD_INFO.with_borrow(|info| {
+        let info = info.as_ref().expect("Main thread info not set");
+        let app_state = &info.app_state;
+        //windows bind the seat; we may be first
+        let seat = {
+            let mut seat = app_state.seat.lock().unwrap();
+            match &*seat {
+                Some(seat) => seat.clone(),
+                None => {
+                    let bound: WlSeat = info
+                        .globals
+                        .bind(&info.input_queue_handle, 8..=9, ())
+                        .map_err(|_| ClipboardError::Unsupported)?;
+                    seat.replace(bound.clone());
+                    bound
+                }
+            }
+        };
+        if let Some(manager) = &info.data_control_manager {
+            app_state
+                .data_control_device
+                .lock()
+                .unwrap()
+                //not primed until the selection it starts with has gone by
+                .get_or_insert_with(|| {
+                    manager.get_data_device(&seat, &info.queue_handle, AtomicBool::new(false))
+                });
+            return Ok(());
+        }
+        let manager = info
+            .data_device_manager
+            .as_ref()
+            .ok_or(ClipboardError::Unsupported)?;
+        //shared with drags, so it stays around after we stop watching
+        app_state
+            .data_device
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| manager.get_data_device(&seat, &info.queue_handle, ()));
+        Ok(())
+    })
+}
+
+/// Call on the main thread.
+pub fn unwatch_clipboard() {
+    MAIN_THREAD_INFO.with_borrow(|info| {
+        let info = info.as_ref().expect("Main thread info not set");
+        if let Some(device) = info.app_state.data_control_device.lock().unwrap().take() {
+            device.destroy();
+        }
+    })
+}
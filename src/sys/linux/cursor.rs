@@ -1,201 +1,301 @@
 // SPDX-License-Identifier: MPL-2.0
-use std::sync::mpsc::Sender;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use wayland_client::protocol::wl_compositor::WlCompositor;
-use wayland_client::protocol::wl_shm::WlShm;
-use wayland_client::protocol::wl_surface::WlSurface;
-use wayland_client::{Connection, QueueHandle};
-use wayland_cursor::CursorTheme;
+#[cfg(feature = "cursor-theme")]
+pub use theme::ActiveCursor;
 
-use super::main_thread::on_main_thread;
-use super::{App, AppState, BUTTON_WIDTH, SurfaceEvents, TITLEBAR_HEIGHT};
 use crate::coordinates::{Position, Size};
+use crate::window::{DecorationTheme, MouseRegion};
 
-const CURSOR_SIZE: i32 = 16;
+/// Cursors loaded from the user's theme with `wayland-cursor`.
+///
+/// Without the `cursor-theme` feature we never set a cursor and the compositor picks one.
+#[cfg(feature = "cursor-theme")]
+mod theme {
+    use std::sync::mpsc::Sender;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use wayland_client::protocol::wl_compositor::WlCompositor;
+    use wayland_client::protocol::wl_shm::WlShm;
+    use wayland_client::protocol::wl_surface::WlSurface;
+    use wayland_client::{Connection, QueueHandle};
+    use wayland_cursor::CursorTheme;
 
-#[derive(Clone, PartialEq)]
-pub struct CursorRequest {
-    pub name: &'static str,
-    pub hot_x: i32,
-    pub hot_y: i32,
-}
+    use super::super::main_thread::on_main_thread;
+    use super::super::window::WindowInternal;
+    use super::super::{App, AppState, SurfaceEvents};
+    use crate::coordinates::Position;
+    use crate::window::Cursor;
+    use crate::window::{MouseRegion, ResizeEdge};
+    use wayland_client::protocol::wl_pointer::WlPointer;
 
-impl CursorRequest {
-    pub fn wait() -> Self {
-        CursorRequest {
-            name: "wait",
-            hot_x: 0,
-            hot_y: 0,
-        }
+    const CURSOR_SIZE: i32 = 16;
+
+    #[derive(Clone, PartialEq)]
+    pub struct CursorRequest {
+        pub name: &'static str,
+        pub hot_x: i32,
+        pub hot_y: i32,
     }
-    pub fn right_side() -> Self {
-        CursorRequest {
-            name: "right_side",
-            hot_x: CURSOR_SIZE / 2,
-            hot_y: 0,
+
+    impl CursorRequest {
+        pub fn wait() -> Self {
+            CursorRequest {
+                name: "wait",
+                hot_x: 0,
+                hot_y: 0,
+            }
         }
-    }
-    pub fn bottom_side() -> Self {
-        CursorRequest {
-            name: "bottom_side",
-            hot_x: 0,
-            hot_y: CURSOR_SIZE / 2,
+        pub fn left_ptr() -> Self {
+            CursorRequest {
+                name: "left_ptr",
+                hot_x: CURSOR_SIZE / 8,
+                hot_y: CURSOR_SIZE / 8,
+            }
         }
-    }
-    pub fn left_ptr() -> Self {
-        CursorRequest {
-            name: "left_ptr",
-            hot_x: CURSOR_SIZE / 8,
-            hot_y: CURSOR_SIZE / 8,
+        /// The theme's cursor for resizing from `edge`.
+        pub fn for_edge(edge: ResizeEdge) -> Self {
+            let (name, hot_x, hot_y) = match edge {
+                ResizeEdge::Top => ("top_side", CURSOR_SIZE / 2, 0),
+                ResizeEdge::Bottom => ("bottom_side", 0, CURSOR_SIZE / 2),
+                ResizeEdge::Left => ("left_side", 0, CURSOR_SIZE / 2),
+                ResizeEdge::Right => ("right_side", CURSOR_SIZE / 2, 0),
+                ResizeEdge::TopLeft => ("top_left_corner", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+                ResizeEdge::TopRight => ("top_right_corner", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+                ResizeEdge::BottomLeft => ("bottom_left_corner", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+                ResizeEdge::BottomRight => {
+                    ("bottom_right_corner", CURSOR_SIZE / 2, CURSOR_SIZE / 2)
+                }
+            };
+            CursorRequest { name, hot_x, hot_y }
         }
-    }
-    pub fn bottom_right_corner() -> Self {
-        CursorRequest {
-            name: "bottom_right_corner",
-            hot_x: CURSOR_SIZE / 2,
-            hot_y: CURSOR_SIZE / 2,
+        /// The theme's cursor for `cursor`, or `None` to hide it.
+        pub fn for_cursor(cursor: Cursor) -> Option<Self> {
+            let (name, hot_x, hot_y) = match cursor {
+                Cursor::Arrow => return Some(Self::left_ptr()),
+                Cursor::Wait => return Some(Self::wait()),
+                Cursor::Hidden => return None,
+                Cursor::Text => ("xterm", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+                Cursor::Hand => ("hand2", CURSOR_SIZE / 4, 0),
+                Cursor::Crosshair => ("crosshair", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+                Cursor::NotAllowed => ("not-allowed", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+                Cursor::ResizeHorizontal => ("sb_h_double_arrow", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+                Cursor::ResizeVertical => ("sb_v_double_arrow", CURSOR_SIZE / 2, CURSOR_SIZE / 2),
+            };
+            Some(CursorRequest { name, hot_x, hot_y })
         }
     }
-}
 
-pub struct ActiveCursor {
-    pub cursor_surface: Arc<WlSurface>,
-    pub cursor_sender: Sender<CursorRequest>,
-    pub active_request: Arc<Mutex<CursorRequest>>,
-}
+    pub struct ActiveCursor {
+        pub cursor_surface: Arc<WlSurface>,
+        pub cursor_sender: Sender<CursorRequest>,
+        pub active_request: Arc<Mutex<CursorRequest>>,
+    }
 
-impl ActiveCursor {
-    pub(super) fn new(
-        connection: &Connection,
-        shm: WlShm,
-        _a: &Arc<AppState>,
-        compositor: &WlCompositor,
-        queue_handle: &QueueHandle<App>,
-    ) -> Self {
-        let mut cursor_theme =
-            CursorTheme::load(connection, shm, CURSOR_SIZE as u32).expect("Can't load cursors");
-        cursor_theme
-            .set_fallback(|_, _| Some(include_bytes!("../../../linux_assets/left_ptr").into()));
-        let cursor = cursor_theme.get_cursor("wait").expect("Can't get cursor");
-        //I guess we fake an internal window here?
-        let cursor_surface = compositor.create_surface(queue_handle, SurfaceEvents::Cursor);
-        let start_time = std::time::Instant::now();
-        let frame_info = cursor.frame_and_duration(start_time.elapsed().as_millis() as u32);
-        let buffer = &cursor[frame_info.frame_index];
-        cursor_surface.attach(Some(buffer), 0, 0);
-        cursor_surface.commit();
-        let cursor_surface = Arc::new(cursor_surface);
-        let move_cursor_surface = cursor_surface.clone();
-        let move_cursor_theme = Arc::new(Mutex::new(cursor_theme));
-        let (cursor_request_sender, cursor_request_receiver) = std::sync::mpsc::channel();
-        let active_request = Arc::new(Mutex::new(CursorRequest::wait()));
-        let move_active_request = active_request.clone();
-        std::thread::Builder::new()
-            .name("Cursor thread".to_string())
-            .spawn(move || {
-                loop {
-                    let move_cursor_theme = move_cursor_theme.clone();
-                    let move_cursor_surface = move_cursor_surface.clone();
-                    let mt_active_request = move_active_request.clone();
-                    let (sender, receiver) = std::sync::mpsc::channel();
+    impl WindowInternal {
+        /// Shows our cursor on the surface the pointer just entered.
+        pub(in super::super) fn enter_cursor(&mut self, pointer: &WlPointer, serial: u32) {
+            self.cursor_hidden = false;
+            let app = self.app_state.upgrade().expect("App state gone");
+            let lock = app.active_cursor.lock().unwrap();
+            let active_cursor = lock.as_ref().expect("No active cursor");
+            let cursor_request = active_cursor.active_request.lock().unwrap().clone();
+            pointer.set_cursor(
+                serial,
+                Some(&active_cursor.cursor_surface),
+                cursor_request.hot_x,
+                cursor_request.hot_y,
+            );
+        }
 
-                    on_main_thread(move || {
-                        let mut binding = move_cursor_theme.lock().unwrap();
-                        let cursor = binding
-                            .get_cursor(mt_active_request.lock().unwrap().name)
-                            .expect("Can't get cursor");
-                        let present_time = start_time.elapsed();
-                        let frame_info = cursor.frame_and_duration(present_time.as_millis() as u32);
-                        let buffer = &cursor[frame_info.frame_index];
-                        move_cursor_surface.attach(Some(buffer), 0, 0);
-                        move_cursor_surface.damage_buffer(
-                            0,
-                            0,
-                            buffer.dimensions().0 as i32,
-                            buffer.dimensions().1 as i32,
-                        );
-                        move_cursor_surface.commit();
-                        let next_present_time =
-                            present_time + Duration::from_millis(frame_info.frame_duration as u64);
-                        sender
-                            .send(next_present_time)
-                            .expect("Can't send next present time");
-                    });
-                    let next_present_time =
-                        receiver.recv().expect("Can't receive next present time");
-                    let sleep_time = next_present_time.saturating_sub(start_time.elapsed());
-                    // println!("sleep_time {:?}", sleep_time);
-                    match cursor_request_receiver.recv_timeout(sleep_time) {
-                        Ok(request) => {
-                            *move_active_request.lock().unwrap() = request;
+        /// Switches to the cursor for the region under `position`, e.g. a resize edge.
+        pub(in super::super) fn update_cursor(&mut self, pointer: &WlPointer, position: Position) {
+            let cursor_request = match self.mouse_region(position) {
+                MouseRegion::Resize(edge) => CursorRequest::for_edge(edge),
+                MouseRegion::Client => match CursorRequest::for_cursor(self.cursor) {
+                    Some(request) => request,
+                    None => {
+                        if !self.cursor_hidden {
+                            pointer.set_cursor(
+                                self.wl_pointer_enter_serial.expect("No serial"),
+                                None,
+                                0,
+                                0,
+                            );
+                            self.cursor_hidden = true;
                         }
-                        Err(e) => {
-                            match e {
-                                std::sync::mpsc::RecvTimeoutError::Timeout => {
-                                    //continue
-                                }
-                                std::sync::mpsc::RecvTimeoutError::Disconnected => {
-                                    panic!("Cursor request channel disconnected");
+                        return;
+                    }
+                },
+                MouseRegion::MaximizeButton
+                | MouseRegion::CloseButton
+                | MouseRegion::MinimizeButton => CursorRequest::left_ptr(),
+                MouseRegion::Titlebar => CursorRequest::left_ptr(),
+            };
+            let app_state = self.app_state.upgrade().unwrap();
+            let lock_a = app_state.active_cursor.lock().unwrap();
+            let active_cursor = lock_a.as_ref().expect("No active cursor");
+            let active_request = active_cursor.active_request.lock().unwrap();
+            let changed = *active_request != cursor_request || self.cursor_hidden;
+            if changed {
+                self.cursor_hidden = false;
+                pointer.set_cursor(
+                    self.wl_pointer_enter_serial.expect("No serial"),
+                    Some(&active_cursor.cursor_surface),
+                    cursor_request.hot_x,
+                    cursor_request.hot_y,
+                );
+                active_cursor.cursor_request(cursor_request);
+            }
+        }
+    }
+
+    impl ActiveCursor {
+        pub(in super::super) fn new(
+            connection: &Connection,
+            shm: WlShm,
+            _a: &Arc<AppState>,
+            compositor: &WlCompositor,
+            queue_handle: &QueueHandle<App>,
+        ) -> Self {
+            let mut cursor_theme =
+                CursorTheme::load(connection, shm, CURSOR_SIZE as u32).expect("Can't load cursors");
+            cursor_theme
+                .set_fallback(|_, _| Some(include_bytes!("../../../linux_assets/left_ptr").into()));
+            let cursor = cursor_theme.get_cursor("wait").expect("Can't get cursor");
+            //I guess we fake an internal window here?
+            let cursor_surface = compositor.create_surface(queue_handle, SurfaceEvents::Cursor);
+            let start_time = std::time::Instant::now();
+            let frame_info = cursor.frame_and_duration(start_time.elapsed().as_millis() as u32);
+            let buffer = &cursor[frame_info.frame_index];
+            cursor_surface.attach(Some(buffer), 0, 0);
+            cursor_surface.commit();
+            let cursor_surface = Arc::new(cursor_surface);
+            let move_cursor_surface = cursor_surface.clone();
+            let move_cursor_theme = Arc::new(Mutex::new(cursor_theme));
+            let (cursor_request_sender, cursor_request_receiver) = std::sync::mpsc::channel();
+            let active_request = Arc::new(Mutex::new(CursorRequest::wait()));
+            let move_active_request = active_request.clone();
+            std::thread::Builder::new()
+                .name("Cursor thread".to_string())
+                .spawn(move || {
+                    loop {
+                        let move_cursor_theme = move_cursor_theme.clone();
+                        let move_cursor_surface = move_cursor_surface.clone();
+                        let mt_active_request = move_active_request.clone();
+                        let (sender, receiver) = std::sync::mpsc::channel();
+
+                        on_main_thread(move || {
+                            let mut binding = move_cursor_theme.lock().unwrap();
+                            let cursor = binding
+                                .get_cursor(mt_active_request.lock().unwrap().name)
+                                .expect("Can't get cursor");
+                            let present_time = start_time.elapsed();
+                            let frame_info =
+                                cursor.frame_and_duration(present_time.as_millis() as u32);
+                            let buffer = &cursor[frame_info.frame_index];
+                            move_cursor_surface.attach(Some(buffer), 0, 0);
+                            move_cursor_surface.damage_buffer(
+                                0,
+                                0,
+                                buffer.dimensions().0 as i32,
+                                buffer.dimensions().1 as i32,
+                            );
+                            move_cursor_surface.commit();
+                            let next_present_time = present_time
+                                + Duration::from_millis(frame_info.frame_duration as u64);
+                            sender
+                                .send(next_present_time)
+                                .expect("Can't send next present time");
+                        });
+                        let next_present_time =
+                            receiver.recv().expect("Can't receive next present time");
+                        let sleep_time = next_present_time.saturating_sub(start_time.elapsed());
+                        // println!("sleep_time {:?}", sleep_time);
+                        match cursor_request_receiver.recv_timeout(sleep_time) {
+                            Ok(request) => {
+                                *move_active_request.lock().unwrap() = request;
+                            }
+                            Err(e) => {
+                                match e {
+                                    std::sync::mpsc::RecvTimeoutError::Timeout => {
+                                        //continue
+                                    }
+                                    std::sync::mpsc::RecvTimeoutError::Disconnected => {
+                                        //our AppState is gone; the connection failed and
+                                        //its replacement has a cursor of its own
+                                        return;
+                                    }
                                 }
                             }
                         }
                     }
-                }
-            })
-            .expect("Can't launch cursor thread");
+                })
+                .expect("Can't launch cursor thread");
 
-        ActiveCursor {
-            cursor_surface,
-            cursor_sender: cursor_request_sender,
-            active_request,
+            ActiveCursor {
+                cursor_surface,
+                cursor_sender: cursor_request_sender,
+                active_request,
+            }
+        }
+        pub fn cursor_request(&self, request: CursorRequest) {
+            self.cursor_sender
+                .send(request)
+                .expect("Can't send cursor request");
         }
-    }
-    pub fn cursor_request(&self, request: CursorRequest) {
-        self.cursor_sender
-            .send(request)
-            .expect("Can't send cursor request");
     }
 }
 
-pub enum MouseRegion {
-    BottomRight,
-    Bottom,
-    Right,
-    Titlebar,
-    CloseButton,
-    MaximizeButton,
-    MinimizeButton,
-    Client,
+/// What's under `position` in a window of `size`: our decorations' title bar and buttons, if
+/// we draw them with `titlebar`, or else a resize border `border` wide.
+pub fn mouse_region(
+    size: Size,
+    position: Position,
+    border: f64,
+    titlebar: Option<&DecorationTheme>,
+) -> MouseRegion {
+    let (in_titlebar, button_width) = match titlebar {
+        Some(theme) => (position.y() < theme.titlebar_height(), theme.button_width()),
+        None => (false, 0.0),
+    };
+    if in_titlebar && position.x() > size.width() - button_width {
+        MouseRegion::CloseButton
+    } else if in_titlebar && position.x() > size.width() - button_width * 2.0 {
+        MouseRegion::MaximizeButton
+    } else if in_titlebar && position.x() > size.width() - button_width * 3.0 {
+        MouseRegion::MinimizeButton
+    } else if in_titlebar {
+        MouseRegion::Titlebar
+    } else {
+        MouseRegion::from_position(size, position, border)
+    }
 }
 
-impl MouseRegion {
-    pub fn from_position(size: Size, position: Position) -> Self {
-        const EDGE_REGION: f64 = 10.0;
-        if position.y() < TITLEBAR_HEIGHT as f64
-            && position.x() > size.width() - BUTTON_WIDTH as f64
-        {
-            MouseRegion::CloseButton
-        } else if position.y() < TITLEBAR_HEIGHT as f64
-            && position.x() > size.width() - BUTTON_WIDTH as f64 * 2.0
-        {
-            MouseRegion::MaximizeButton
-        } else if position.y() < TITLEBAR_HEIGHT as f64
-            && position.x() > size.width() - BUTTON_WIDTH as f64 * 3.0
-        {
-            MouseRegion::MinimizeButton
-        } else if position.y() < TITLEBAR_HEIGHT as f64 {
-            MouseRegion::Titlebar
-        } else if size.width() - position.x() < EDGE_REGION {
-            if size.height() - position.y() < EDGE_REGION {
-                MouseRegion::BottomRight
-            } else {
-                MouseRegion::Right
-            }
-        } else if size.height() - position.y() < EDGE_REGION {
-            MouseRegion::Bottom
-        } else {
-            MouseRegion::Client
-        }
+#[cfg(all(test, feature = "cursor-theme"))]
+mod test {
+    use super::theme::CursorRequest;
+    use crate::window::Cursor;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn each_cursor_has_its_own_theme_cursor() {
+        let cursors = [
+            Cursor::Arrow,
+            Cursor::Text,
+            Cursor::Hand,
+            Cursor::Wait,
+            Cursor::Crosshair,
+            Cursor::NotAllowed,
+            Cursor::ResizeHorizontal,
+            Cursor::ResizeVertical,
+        ];
+        let mut names: Vec<_> = cursors
+            .iter()
+            .map(|&cursor| CursorRequest::for_cursor(cursor).unwrap().name)
+            .collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), cursors.len());
+        assert!(CursorRequest::for_cursor(Cursor::Arrow) == Some(CursorRequest::left_ptr()));
+        assert!(CursorRequest::for_cursor(Cursor::Hidden).is_none());
     }
 }
@@ -1,11 +1,18 @@
 // SPDX-License-Identifier: MPL-2.0
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use wayland_client::globals::GlobalListContents;
 use wayland_client::protocol::wl_buffer::{Event, WlBuffer};
+use wayland_client::protocol::wl_callback::{self, WlCallback};
 use wayland_client::protocol::wl_compositor::WlCompositor;
+use wayland_client::protocol::wl_data_device::{self, WlDataDevice};
+use wayland_client::protocol::wl_data_device_manager::WlDataDeviceManager;
+use wayland_client::protocol::wl_data_offer::WlDataOffer;
+use wayland_client::protocol::wl_data_source::{self, WlDataSource};
 use wayland_client::protocol::wl_keyboard::WlKeyboard;
 use wayland_client::protocol::wl_output::WlOutput;
 use wayland_client::protocol::wl_pointer::WlPointer;
+use wayland_client::protocol::wl_region::WlRegion;
 use wayland_client::protocol::wl_registry;
 use wayland_client::protocol::wl_seat::WlSeat;
 use wayland_client::protocol::wl_shm::WlShm;
@@ -13,18 +20,44 @@ use wayland_client::protocol::wl_shm_pool::WlShmPool;
 use wayland_client::protocol::wl_subcompositor::WlSubcompositor;
 use wayland_client::protocol::wl_subsurface::WlSubsurface;
 use wayland_client::protocol::wl_surface::WlSurface;
-use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, WEnum, event_created_child};
+use wayland_protocols::ext::data_control::v1::client::ext_data_control_device_v1::{
+    self, ExtDataControlDeviceV1,
+};
+use wayland_protocols::ext::data_control::v1::client::ext_data_control_manager_v1::ExtDataControlManagerV1;
+use wayland_protocols::ext::data_control::v1::client::ext_data_control_offer_v1::ExtDataControlOfferV1;
+use wayland_protocols::wp::fractional_scale::v1::client::wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1;
+use wayland_protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::{
+    self, WpFractionalScaleV1,
+};
+use wayland_protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibit_manager_v1::ZwpKeyboardShortcutsInhibitManagerV1;
+use wayland_protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibitor_v1::ZwpKeyboardShortcutsInhibitorV1;
+use wayland_protocols::wp::pointer_constraints::zv1::client::zwp_confined_pointer_v1::ZwpConfinedPointerV1;
+use wayland_protocols::wp::pointer_constraints::zv1::client::zwp_pointer_constraints_v1::ZwpPointerConstraintsV1;
+use wayland_protocols::wp::viewporter::client::wp_viewport::WpViewport;
+use wayland_protocols::wp::viewporter::client::wp_viewporter::WpViewporter;
+use wayland_protocols::xdg::decoration::zv1::client::zxdg_decoration_manager_v1::ZxdgDecorationManagerV1;
+use wayland_protocols::xdg::decoration::zv1::client::zxdg_toplevel_decoration_v1::{
+    self, ZxdgToplevelDecorationV1,
+};
+use wayland_protocols::xdg::dialog::v1::client::xdg_dialog_v1::XdgDialogV1;
+use wayland_protocols::xdg::dialog::v1::client::xdg_wm_dialog_v1::XdgWmDialogV1;
 use wayland_protocols::xdg::shell::client::xdg_surface::XdgSurface;
 use wayland_protocols::xdg::shell::client::xdg_toplevel::XdgToplevel;
 use wayland_protocols::xdg::shell::client::xdg_wm_base::XdgWmBase;
+use wayland_protocols::xdg::toplevel_icon::v1::client::xdg_toplevel_icon_manager_v1::XdgToplevelIconManagerV1;
+use wayland_protocols::xdg::toplevel_icon::v1::client::xdg_toplevel_icon_v1::XdgToplevelIconV1;
 use wayland_protocols::xdg::shell::client::{xdg_surface, xdg_toplevel};
 
-use super::ax;
-use super::buffer::AllocatedBuffer;
-use super::cursor::{CursorRequest, MouseRegion};
+use super::drag::{DragSource, drag_action};
+use super::scale::{FRACTIONAL_SCALE_DENOMINATOR, update_scale};
 use super::{App, BufferReleaseInfo, Configure, OutputInfo, SurfaceEvents};
-use crate::coordinates::Position;
-use crate::sys::window::WindowInternal;
+use crate::application::time::Instant;
+use crate::drag::DragOutcome;
+use crate::sys::window::{FrameDone, WindowInternal};
+use crate::window::{
+    FrameTiming, MouseRegion, ResizeEdge, TiledEdges, WindowEvent, WindowState, WindowStateFlags,
+};
 
 impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for App {
     fn event(
@@ -120,7 +153,7 @@ impl Dispatch<WlSurface, SurfaceEvents> for App {
         event: <WlSurface as Proxy>::Event,
         data: &SurfaceEvents,
         _conn: &Connection,
-        _qhandle: &QueueHandle<Self>,
+        qhandle: &QueueHandle<Self>,
     ) {
         match event {
             wayland_client::protocol::wl_surface::Event::Enter { output } => {
@@ -131,6 +164,7 @@ impl Dispatch<WlSurface, SurfaceEvents> for App {
                         .unwrap()
                         .current_outputs
                         .insert(output_id);
+                    update_scale(window_internal, qhandle);
                 }
             }
             wayland_client::protocol::wl_surface::Event::Leave { output } => {
@@ -141,6 +175,20 @@ impl Dispatch<WlSurface, SurfaceEvents> for App {
                         .unwrap()
                         .current_outputs
                         .remove(&output_id);
+                    update_scale(window_internal, qhandle);
+                }
+            }
+            wayland_client::protocol::wl_surface::Event::PreferredBufferScale { factor } => {
+                if let SurfaceEvents::Standard(window_internal) = data {
+                    {
+                        let mut locked = window_internal.lock().unwrap();
+                        //wp_fractional_scale_v1 is more precise when we have it
+                        if locked.fractional_scale.is_some() {
+                            return;
+                        }
+                        locked.preferred_scale = Some(factor as f64);
+                    }
+                    update_scale(window_internal, qhandle);
                 }
             }
             _ => {
@@ -165,13 +213,26 @@ impl Dispatch<XdgSurface, Arc<Mutex<WindowInternal>>> for App {
         let mut locked_data = data.as_ref().lock().unwrap();
         match event {
             xdg_surface::Event::Configure { serial } => {
+                //applied under the same lock as the size, so snapshots see both or neither
+                if let Some((state, flags)) = locked_data.proposed_state.take() {
+                    locked_data.state = state;
+                    locked_data.events.update_state(flags);
+                }
+                if let Some(waiter) = locked_data.fullscreen_waiter.take() {
+                    waiter.send(locked_data.state == WindowState::Fullscreen);
+                }
                 let proposed = locked_data.proposed_configure.take();
                 if let Some(mut configure) = proposed {
-                    let app_state = locked_data.app_state.upgrade().unwrap();
                     if configure.width == 0 && configure.height == 0 {
                         //pick our own size
-                        configure.width = 800;
-                        configure.height = 600;
+                        configure = locked_data.requested_size.clone().unwrap_or(Configure {
+                            width: 800,
+                            height: 600,
+                        });
+                    }
+                    //what Window::geometry restores to
+                    if locked_data.state == WindowState::Normal {
+                        locked_data.normal_size = Some(configure.clone());
                     }
                     //check size (always attach on first configure)
                     let size_changed = locked_data
@@ -179,37 +240,15 @@ impl Dispatch<XdgSurface, Arc<Mutex<WindowInternal>>> for App {
                         .as_ref()
                         .map(|c| c.width != configure.width || c.height != configure.height)
                         .unwrap_or(true);
-                    if !locked_data.has_been_configured || size_changed {
-                        //apply decor position
-                        locked_data
-                            .decor_subsurface
-                            .as_ref()
-                            .unwrap()
-                            .set_position(configure.width - app_state.decor_dimensions.0 as i32, 0);
+                    if locked_data.parked {
+                        //remember the size for unpark, but leave the surface unmapped
                         locked_data.applied_configure = Some(configure);
-                        let title = locked_data.title.clone();
-                        let applied_size = locked_data.applied_size();
-                        if let Some(a) = locked_data.adapter.as_mut() {
-                            a.update_if_active(|| ax::build_tree_update(title, applied_size))
-                        }
-                        if let Some(f) = locked_data.size_update_notify.as_ref() {
-                            f.0(locked_data.applied_size())
-                        }
-
-                        //rebuild main buffer
-                        let buffer = AllocatedBuffer::new(
-                            locked_data.applied_configure.as_ref().unwrap().width,
-                            locked_data.applied_configure.as_ref().unwrap().height,
-                            &app_state.shm,
-                            qh,
-                            data.clone(),
-                        );
-                        //attach to surface
-                        locked_data.wl_surface.as_ref().expect("No surface").attach(
-                            Some(&buffer.buffer),
-                            0,
-                            0,
-                        );
+                        proxy.ack_configure(serial);
+                        locked_data.has_been_configured = true;
+                    } else if !locked_data.has_been_configured || size_changed || locked_data.hidden
+                    {
+                        locked_data.hidden = false;
+                        locked_data.apply_size(configure, qh, data);
                         // ack_configure MUST come before commit per xdg-shell protocol
                         proxy.ack_configure(serial);
                         locked_data.has_been_configured = true;
@@ -222,6 +261,12 @@ impl Dispatch<XdgSurface, Arc<Mutex<WindowInternal>>> for App {
                         // No buffer changes needed, but still must ack
                         proxy.ack_configure(serial);
                         locked_data.has_been_configured = true;
+                        //our decor may have come or gone with the decoration mode
+                        locked_data
+                            .wl_surface
+                            .as_ref()
+                            .expect("No surface")
+                            .commit();
                     }
                 } else {
                     // No proposed configure, still ack
@@ -256,12 +301,18 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<XdgToplevel, A> for App {
             xdg_toplevel::Event::Configure {
                 width,
                 height,
-                states: _,
+                states,
             } => {
+                #[cfg(feature = "input")]
                 crate::input::linux::xdg_toplevel_configure_event(width, height);
 
-                data.as_ref().lock().unwrap().proposed_configure =
-                    Some(Configure { width, height });
+                let mut locked = data.as_ref().lock().unwrap();
+                locked.proposed_configure = Some(Configure { width, height });
+                let states = toplevel_states(&states);
+                locked.proposed_state = Some((toplevel_state(&states), toplevel_flags(&states)));
+            }
+            xdg_toplevel::Event::Close => {
+                data.as_ref().lock().unwrap().request_close();
             }
             _ => {
                 //?
@@ -337,6 +388,10 @@ impl Dispatch<WlSeat, ()> for App {
             "Got WlSeat event {event}",
             event = logwise::privacy::LogIt(&event)
         );
+        #[cfg(feature = "input")]
+        if let wayland_client::protocol::wl_seat::Event::Capabilities { capabilities } = event {
+            crate::input::linux::wl_seat_capabilities_event(capabilities.into());
+        }
     }
 }
 
@@ -375,7 +430,7 @@ impl Dispatch<WlSubsurface, ()> for App {
 impl Dispatch<WlOutput, u32> for App {
     fn event(
         state: &mut Self,
-        _proxy: &WlOutput,
+        proxy: &WlOutput,
         event: <WlOutput as Proxy>::Event,
         output_id: &u32,
         _conn: &Connection,
@@ -391,6 +446,7 @@ impl Dispatch<WlOutput, u32> for App {
                         *output_id,
                         OutputInfo {
                             scale_factor: factor as f64,
+                            output: Some(proxy.clone()),
                         },
                     );
                 }
@@ -405,7 +461,56 @@ impl Dispatch<WlOutput, u32> for App {
     }
 }
 
+/// The state in a toplevel configure's `states`, an array of native-endian `u32`s.
+/// The `xdg_toplevel::State`s in a configure's array.
+fn toplevel_states(states: &[u8]) -> Vec<u32> {
+    states
+        .chunks_exact(4)
+        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+fn toplevel_state(states: &[u32]) -> WindowState {
+    if states.contains(&(xdg_toplevel::State::Fullscreen as u32)) {
+        WindowState::Fullscreen
+    } else if states.contains(&(xdg_toplevel::State::Maximized as u32)) {
+        WindowState::Maximized
+    } else {
+        WindowState::Normal
+    }
+}
+
+fn toplevel_flags(states: &[u32]) -> WindowStateFlags {
+    let has = |state: xdg_toplevel::State| states.contains(&(state as u32));
+    WindowStateFlags {
+        maximized: has(xdg_toplevel::State::Maximized),
+        fullscreen: has(xdg_toplevel::State::Fullscreen),
+        tiled: TiledEdges {
+            left: has(xdg_toplevel::State::TiledLeft),
+            right: has(xdg_toplevel::State::TiledRight),
+            top: has(xdg_toplevel::State::TiledTop),
+            bottom: has(xdg_toplevel::State::TiledBottom),
+        },
+        activated: has(xdg_toplevel::State::Activated),
+        suspended: has(xdg_toplevel::State::Suspended),
+    }
+}
+
+fn xdg_edge(edge: ResizeEdge) -> xdg_toplevel::ResizeEdge {
+    match edge {
+        ResizeEdge::Top => xdg_toplevel::ResizeEdge::Top,
+        ResizeEdge::Bottom => xdg_toplevel::ResizeEdge::Bottom,
+        ResizeEdge::Left => xdg_toplevel::ResizeEdge::Left,
+        ResizeEdge::Right => xdg_toplevel::ResizeEdge::Right,
+        ResizeEdge::TopLeft => xdg_toplevel::ResizeEdge::TopLeft,
+        ResizeEdge::TopRight => xdg_toplevel::ResizeEdge::TopRight,
+        ResizeEdge::BottomLeft => xdg_toplevel::ResizeEdge::BottomLeft,
+        ResizeEdge::BottomRight => xdg_toplevel::ResizeEdge::BottomRight,
+    }
+}
+
 impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlPointer, A> for App {
+    #[cfg_attr(not(feature = "cursor-theme"), allow(unused_variables))]
     fn event(
         _state: &mut Self,
         proxy: &WlPointer,
@@ -429,82 +534,20 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlPointer, A> for App {
                 data.wl_pointer_enter_serial = Some(serial);
                 data.wl_pointer_enter_surface = Some(surface);
                 //set cursor?
-                let app = data.app_state.upgrade().expect("App state gone");
-                let cursor_request = app
-                    .active_cursor
-                    .lock()
-                    .unwrap()
-                    .as_ref()
-                    .unwrap()
-                    .active_request
-                    .lock()
-                    .unwrap()
-                    .clone();
-
-                proxy.set_cursor(
-                    serial,
-                    Some(
-                        &app.active_cursor
-                            .lock()
-                            .unwrap()
-                            .as_ref()
-                            .unwrap()
-                            .cursor_surface,
-                    ),
-                    cursor_request.hot_x,
-                    cursor_request.hot_y,
-                );
+                #[cfg(feature = "cursor-theme")]
+                data.enter_cursor(proxy, serial);
             }
             wayland_client::protocol::wl_pointer::Event::Motion {
                 surface_x,
                 surface_y,
                 time: _time,
             } => {
-                let parent_surface_x;
-                let parent_surface_y;
-                if data.wl_pointer_enter_surface != data.wl_surface {
-                    //we're in the decor; slide by decor dimensions
-                    let surface_dimensions = data
-                        .applied_configure
-                        .clone()
-                        .expect("No surface dimensions");
-                    parent_surface_x = surface_x + surface_dimensions.width as f64
-                        - data.app_state.upgrade().unwrap().decor_dimensions.0 as f64;
-                    parent_surface_y = surface_y;
-                } else {
-                    parent_surface_x = surface_x;
-                    parent_surface_y = surface_y;
-                }
-                crate::input::linux::motion_event(_time, parent_surface_x, parent_surface_y);
-
-                //get current size
-                let size = data.applied_size();
-                let position = Position::new(parent_surface_x, parent_surface_y);
+                let position = data.pointer_position(surface_x, surface_y);
+                #[cfg(feature = "input")]
+                crate::input::linux::motion_event(_time, position.x(), position.y());
                 data.wl_pointer_pos.replace(position);
-                let cursor_request = match MouseRegion::from_position(size, position) {
-                    MouseRegion::BottomRight => CursorRequest::bottom_right_corner(),
-                    MouseRegion::Bottom => CursorRequest::bottom_side(),
-                    MouseRegion::Right => CursorRequest::right_side(),
-                    MouseRegion::Client
-                    | MouseRegion::MaximizeButton
-                    | MouseRegion::CloseButton
-                    | MouseRegion::MinimizeButton => CursorRequest::left_ptr(),
-                    MouseRegion::Titlebar => CursorRequest::left_ptr(),
-                };
-                let app_state = data.app_state.upgrade().unwrap();
-                let lock_a = app_state.active_cursor.lock().unwrap();
-                let active_cursor = lock_a.as_ref().expect("No active cursor");
-                let active_request = active_cursor.active_request.lock().unwrap();
-                let changed = *active_request != cursor_request;
-                if changed {
-                    proxy.set_cursor(
-                        data.wl_pointer_enter_serial.expect("No serial"),
-                        Some(&active_cursor.cursor_surface),
-                        cursor_request.hot_x,
-                        cursor_request.hot_y,
-                    );
-                    active_cursor.cursor_request(cursor_request);
-                }
+                #[cfg(feature = "cursor-theme")]
+                data.update_cursor(proxy, position);
             }
             wayland_client::protocol::wl_pointer::Event::Button {
                 serial,
@@ -512,6 +555,7 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlPointer, A> for App {
                 button,
                 state,
             } => {
+                #[cfg(feature = "input")]
                 crate::input::linux::button_event(
                     _time,
                     button,
@@ -519,44 +563,23 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlPointer, A> for App {
                     data.wl_surface.as_ref().unwrap().id(),
                 );
 
-                //get current size
-                let size = data.applied_size();
                 let mouse_pos = data.wl_pointer_pos.expect("No pointer position");
-                let mouse_region = MouseRegion::from_position(size, mouse_pos);
+                let mouse_region = data.mouse_region(mouse_pos);
                 let pressed: u32 = state.into();
+                if pressed == 1 {
+                    data.wl_pointer_button_serial = Some(serial);
+                } else {
+                    data.wl_pointer_button_serial = None;
+                }
                 if button == 0x110 {
                     //BUTTON_LEFT
                     if pressed == 1 {
                         match mouse_region {
-                            MouseRegion::BottomRight => {
+                            MouseRegion::Resize(edge) => {
                                 let toplevel = data.xdg_toplevel.as_ref().unwrap();
                                 let app_state = data.app_state.upgrade().unwrap();
                                 let seat = app_state.seat.lock().unwrap();
-                                toplevel.resize(
-                                    seat.as_ref().unwrap(),
-                                    serial,
-                                    xdg_toplevel::ResizeEdge::BottomRight,
-                                );
-                            }
-                            MouseRegion::Bottom => {
-                                let toplevel = data.xdg_toplevel.as_ref().unwrap();
-                                let app_state = data.app_state.upgrade().unwrap();
-                                let seat = app_state.seat.lock().unwrap();
-                                toplevel.resize(
-                                    seat.as_ref().unwrap(),
-                                    serial,
-                                    xdg_toplevel::ResizeEdge::Bottom,
-                                );
-                            }
-                            MouseRegion::Right => {
-                                let toplevel = data.xdg_toplevel.as_ref().unwrap();
-                                let app_state = data.app_state.upgrade().unwrap();
-                                let seat = app_state.seat.lock().unwrap();
-                                toplevel.resize(
-                                    seat.as_ref().unwrap(),
-                                    serial,
-                                    xdg_toplevel::ResizeEdge::Right,
-                                );
+                                toplevel.resize(seat.as_ref().unwrap(), serial, xdg_edge(edge));
                             }
                             MouseRegion::Client => {}
                             MouseRegion::Titlebar => {
@@ -566,7 +589,7 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlPointer, A> for App {
                                 toplevel._move(seat.as_ref().unwrap(), serial);
                             }
                             MouseRegion::CloseButton => {
-                                data.close_window();
+                                data.request_close();
                             }
                             MouseRegion::MaximizeButton => data.maximize(),
                             MouseRegion::MinimizeButton => {
@@ -574,6 +597,12 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlPointer, A> for App {
                             }
                         }
                     }
+                } else if button == 0x111
+                    && pressed == 1
+                    && matches!(mouse_region, MouseRegion::Titlebar)
+                {
+                    //BUTTON_RIGHT
+                    data.show_window_menu(mouse_pos);
                 }
             }
             _ => {
@@ -584,6 +613,10 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlPointer, A> for App {
 }
 
 impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlKeyboard, A> for App {
+    #[cfg_attr(
+        not(any(feature = "input", feature = "accessibility")),
+        allow(unused_variables)
+    )]
     fn event(
         _state: &mut Self,
         _proxy: &WlKeyboard,
@@ -599,20 +632,34 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlKeyboard, A> for App {
         match event {
             wayland_client::protocol::wl_keyboard::Event::Enter {
                 serial: _,
-                surface: _,
+                surface,
                 keys: _,
             } => {
-                if let Some(e) = data.as_ref().lock().unwrap().adapter.as_mut() {
+                let mut lock = data.as_ref().lock().unwrap();
+                //each window binds its own wl_keyboard, but all of them see every enter
+                if lock.wl_surface.as_ref() != Some(&surface) {
+                    return;
+                }
+                lock.focused = true;
+                lock.events.send(WindowEvent::Focused);
+                #[cfg(feature = "accessibility")]
+                if let Some(e) = lock.adapter.as_mut() {
                     e.update_window_focus_state(true)
                 }
             }
-            wayland_client::protocol::wl_keyboard::Event::Leave {
-                serial: _,
-                surface: _,
-            } => {
-                if let Some(e) = data.as_ref().lock().unwrap().adapter.as_mut() {
+            wayland_client::protocol::wl_keyboard::Event::Leave { serial: _, surface } => {
+                let mut lock = data.as_ref().lock().unwrap();
+                if lock.wl_surface.as_ref() != Some(&surface) {
+                    return;
+                }
+                lock.focused = false;
+                lock.events.send(WindowEvent::Unfocused);
+                #[cfg(feature = "accessibility")]
+                if let Some(e) = lock.adapter.as_mut() {
                     e.update_window_focus_state(false)
                 }
+                #[cfg(feature = "input")]
+                crate::input::linux::wl_keyboard_leave_event();
             }
             wayland_client::protocol::wl_keyboard::Event::Key {
                 serial: _serial,
@@ -620,6 +667,7 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlKeyboard, A> for App {
                 key: _key,
                 state: _state,
             } => {
+                #[cfg(feature = "input")]
                 crate::input::linux::wl_keyboard_event(
                     _serial,
                     _time,
@@ -634,7 +682,484 @@ impl<A: AsRef<Mutex<WindowInternal>>> Dispatch<WlKeyboard, A> for App {
                         .id(),
                 );
             }
+            #[cfg(feature = "input")]
+            wayland_client::protocol::wl_keyboard::Event::RepeatInfo { rate, delay } => {
+                crate::input::linux::wl_keyboard_repeat_info_event(rate, delay);
+            }
+            #[cfg(feature = "input")]
+            wayland_client::protocol::wl_keyboard::Event::Keymap {
+                format: wayland_client::WEnum::Value(format),
+                fd,
+                size,
+            } => {
+                crate::input::linux::wl_keyboard_keymap_event(format, fd, size);
+            }
+            #[cfg(feature = "input")]
+            wayland_client::protocol::wl_keyboard::Event::Modifiers {
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                group,
+                ..
+            } => {
+                crate::input::linux::wl_keyboard_modifiers_event(
+                    mods_depressed,
+                    mods_latched,
+                    mods_locked,
+                    group,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlDataDeviceManager, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlDataDeviceManager,
+        event: <WlDataDeviceManager as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WlDataDeviceManager event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<WlDataDevice, ()> for App {
+    fn event(
+        state: &mut Self,
+        _proxy: &WlDataDevice,
+        event: <WlDataDevice as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WlDataDevice event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+        //with ext-data-control we hear about the selection from there, focused or not
+        if let wl_data_device::Event::Selection { .. } = event
+            && state.0.data_control_device.lock().unwrap().is_none()
+        {
+            crate::clipboard::notify();
+        }
+    }
+
+    event_created_child!(App, WlDataDevice, [
+        wl_data_device::EVT_DATA_OFFER_OPCODE => (WlDataOffer, ()),
+    ]);
+}
+
+impl Dispatch<ExtDataControlManagerV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ExtDataControlManagerV1,
+        event: <ExtDataControlManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ExtDataControlManagerV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+/// The data is whether the device's first `selection`, which describes the clipboard as it
+/// was before we started watching, has gone by.
+impl Dispatch<ExtDataControlDeviceV1, AtomicBool> for App {
+    fn event(
+        state: &mut Self,
+        proxy: &ExtDataControlDeviceV1,
+        event: <ExtDataControlDeviceV1 as Proxy>::Event,
+        primed: &AtomicBool,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ExtDataControlDeviceV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+        match event {
+            ext_data_control_device_v1::Event::Selection { id } => {
+                //we never read the clipboard, only notice it changed
+                if let Some(offer) = id {
+                    offer.destroy();
+                }
+                if primed.swap(true, Ordering::Relaxed) {
+                    crate::clipboard::notify();
+                }
+            }
+            ext_data_control_device_v1::Event::PrimarySelection { id: Some(offer) } => {
+                offer.destroy();
+            }
+            ext_data_control_device_v1::Event::Finished => {
+                //the seat went away; nothing more will come from this device
+                let mut device = state.0.data_control_device.lock().unwrap();
+                if device.as_ref() == Some(proxy) {
+                    device.take();
+                }
+                proxy.destroy();
+            }
             _ => {}
         }
     }
+
+    event_created_child!(App, ExtDataControlDeviceV1, [
+        ext_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ExtDataControlOfferV1, ()),
+    ]);
+}
+
+impl Dispatch<ExtDataControlOfferV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ExtDataControlOfferV1,
+        event: <ExtDataControlOfferV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ExtDataControlOfferV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<WlDataOffer, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlDataOffer,
+        event: <WlDataOffer as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WlDataOffer event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<WlDataSource, Arc<DragSource>> for App {
+    fn event(
+        _state: &mut Self,
+        proxy: &WlDataSource,
+        event: <WlDataSource as Proxy>::Event,
+        data: &Arc<DragSource>,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WlDataSource event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+        match event {
+            wl_data_source::Event::Send { mime_type, fd } => data.send(mime_type, fd),
+            wl_data_source::Event::Action {
+                dnd_action: WEnum::Value(dnd_action),
+            } => data.reporter.set_action(drag_action(dnd_action)),
+            wl_data_source::Event::Cancelled => {
+                data.finish(DragOutcome::Cancelled);
+                proxy.destroy();
+            }
+            wl_data_source::Event::DndFinished => {
+                //the last action event is the one performed
+                let outcome = data
+                    .reporter
+                    .action()
+                    .map_or(DragOutcome::Unknown, DragOutcome::Dropped);
+                data.finish(outcome);
+                proxy.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WpFractionalScaleManagerV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpFractionalScaleManagerV1,
+        event: <WpFractionalScaleManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WpFractionalScaleManagerV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<WpFractionalScaleV1, Arc<Mutex<WindowInternal>>> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpFractionalScaleV1,
+        event: <WpFractionalScaleV1 as Proxy>::Event,
+        data: &Arc<Mutex<WindowInternal>>,
+        _conn: &Connection,
+        qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            wp_fractional_scale_v1::Event::PreferredScale { scale } => {
+                data.lock().unwrap().preferred_scale =
+                    Some(scale as f64 / FRACTIONAL_SCALE_DENOMINATOR);
+                update_scale(data, qhandle);
+            }
+            _ => {
+                logwise::debuginternal_sync!(
+                    "Got WpFractionalScaleV1 event {event}",
+                    event = logwise::privacy::LogIt(&event)
+                );
+            }
+        }
+    }
+}
+
+impl Dispatch<WpViewporter, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpViewporter,
+        event: <WpViewporter as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WpViewporter event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<XdgWmDialogV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &XdgWmDialogV1,
+        event: <XdgWmDialogV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got XdgWmDialogV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<XdgDialogV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &XdgDialogV1,
+        event: <XdgDialogV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got XdgDialogV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<WlCallback, FrameDone> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlCallback,
+        event: <WlCallback as Proxy>::Event,
+        data: &FrameDone,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        //callback_data is a timestamp in milliseconds, from no particular base
+        if let wl_callback::Event::Done { .. } = event
+            && let Some(frame) = data.0.lock().unwrap().take()
+        {
+            frame(FrameTiming::new(Instant::now(), None, None));
+        }
+    }
+}
+
+impl Dispatch<XdgToplevelIconManagerV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &XdgToplevelIconManagerV1,
+        event: <XdgToplevelIconManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got XdgToplevelIconManagerV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<XdgToplevelIconV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &XdgToplevelIconV1,
+        event: <XdgToplevelIconV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got XdgToplevelIconV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<WpViewport, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpViewport,
+        event: <WpViewport as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WpViewport event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<ZwpKeyboardShortcutsInhibitManagerV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpKeyboardShortcutsInhibitManagerV1,
+        event: <ZwpKeyboardShortcutsInhibitManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ZwpKeyboardShortcutsInhibitManagerV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<ZwpKeyboardShortcutsInhibitorV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpKeyboardShortcutsInhibitorV1,
+        event: <ZwpKeyboardShortcutsInhibitorV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        //active/inactive follow focus, and whether the compositor (or user) agreed
+        logwise::debuginternal_sync!(
+            "Got ZwpKeyboardShortcutsInhibitorV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<WlRegion, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlRegion,
+        event: <WlRegion as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got WlRegion event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<ZwpPointerConstraintsV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpPointerConstraintsV1,
+        event: <ZwpPointerConstraintsV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ZwpPointerConstraintsV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<ZwpConfinedPointerV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpConfinedPointerV1,
+        event: <ZwpConfinedPointerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        //confined/unconfined follow focus; the confinement is persistent, so it comes back
+        logwise::debuginternal_sync!(
+            "Got ZwpConfinedPointerV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<ZxdgDecorationManagerV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZxdgDecorationManagerV1,
+        event: <ZxdgDecorationManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ZxdgDecorationManagerV1 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<ZxdgToplevelDecorationV1, Arc<Mutex<WindowInternal>>> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZxdgToplevelDecorationV1,
+        event: <ZxdgToplevelDecorationV1 as Proxy>::Event,
+        data: &Arc<Mutex<WindowInternal>>,
+        _conn: &Connection,
+        qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            //followed by an xdg_surface configure, whose commit shows the change
+            zxdg_toplevel_decoration_v1::Event::Configure {
+                mode: WEnum::Value(mode),
+            } => {
+                data.lock().unwrap().configure_decorations(mode, qhandle);
+            }
+            _ => {
+                logwise::debuginternal_sync!(
+                    "Got ZxdgToplevelDecorationV1 event {event}",
+                    event = logwise::privacy::LogIt(&event)
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Outgoing drag-and-drop via `wl_data_device`.
+
+use std::io::Write;
+use std::os::fd::OwnedFd;
+use std::sync::{Arc, Mutex};
+use wayland_client::Proxy;
+use wayland_client::protocol::wl_data_device_manager::DndAction;
+use wayland_client::protocol::wl_surface::WlSurface;
+
+use super::SurfaceEvents;
+use super::buffer::create_shm_buffer_pixels;
+use super::main_thread::{MAIN_THREAD_INFO, MainThreadInfo};
+use super::window::{Window, WindowInternal};
+use crate::drag::{
+    DragAction, DragActions, DragData, DragError, DragImage, DragOutcome, DragReporter,
+};
+
+/// User data for a `wl_data_source` we created to start a drag.
+#[derive(Debug)]
+pub(super) struct DragSource {
+    pub mime: String,
+    pub bytes: Arc<[u8]>,
+    /// The drag icon surface, destroyed along with the source.
+    pub icon: Mutex<Option<WlSurface>>,
+    pub reporter: DragReporter,
+}
+
+impl DragSource {
+    /// Writes our payload to the fd the receiving client gave us.
+    ///
+    /// The receiver may read slowly (or not at all), so we never block the main thread on it.
+    pub fn send(&self, mime_type: String, fd: OwnedFd) {
+        if mime_type != self.mime {
+            logwise::debuginternal_sync!(
+                "Asked for unoffered mime type {mime}",
+                mime = logwise::privacy::LogIt(mime_type)
+            );
+            return;
+        }
+        let bytes = self.bytes.clone();
+        _ = std::thread::Builder::new()
+            .name("app_window drag send".to_string())
+            .spawn(move || {
+                let mut file = std::fs::File::from(fd);
+                if let Err(e) = file.write_all(&bytes) {
+                    logwise::warn_sync!(
+                        "Can't write drag data: {err}",
+                        err = logwise::privacy::LogIt(e)
+                    );
+                }
+            });
+    }
+
+    /// Called when the drag is over, whether it succeeded or not.
+    pub fn finish(&self, outcome: DragOutcome) {
+        if let Some(icon) = self.icon.lock().unwrap().take() {
+            icon.destroy();
+        }
+        self.reporter.finish(outcome);
+    }
+}
+
+fn dnd_actions(actions: DragActions) -> DndAction {
+    let mut dnd = DndAction::empty();
+    for (action, flag) in [
+        (DragAction::Copy, DndAction::Copy),
+        (DragAction::Move, DndAction::Move),
+        (DragAction::Ask, DndAction::Ask),
+    ] {
+        if actions.contains(action) {
+            dnd |= flag;
+        }
+    }
+    dnd
+}
+
+/// The action in a `wl_data_source.action` event, which is one action or none.
+pub(super) fn drag_action(dnd: DndAction) -> Option<DragAction> {
+    if dnd.contains(DndAction::Move) {
+        Some(DragAction::Move)
+    } else if dnd.contains(DndAction::Copy) {
+        Some(DragAction::Copy)
+    } else if dnd.contains(DndAction::Ask) {
+        Some(DragAction::Ask)
+    } else {
+        None
+    }
+}
+
+fn start_drag_main(
+    info: &MainThreadInfo,
+    window_internal: &Arc<Mutex<WindowInternal>>,
+    data: DragData,
+    image: Option<DragImage>,
+    reporter: DragReporter,
+) -> Result<(), DragError> {
+    let manager = info
+        .data_device_manager
+        .as_ref()
+        .ok_or(DragError::Unsupported)?;
+    let (serial, origin, app_state) = {
+        let internal = window_internal.lock().unwrap();
+        (
+            internal.wl_pointer_button_serial,
+            internal.wl_surface.clone(),
+            internal.app_state.upgrade().expect("App state gone"),
+        )
+    };
+    let serial = serial.ok_or(DragError::NoPointerGrab)?;
+    let origin = origin.expect("No surface");
+    let seat = app_state
+        .seat
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(DragError::NoPointerGrab)?;
+
+    let data_device = app_state
+        .data_device
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| manager.get_data_device(&seat, &info.queue_handle, ()))
+        .clone();
+
+    let drag_source = Arc::new(DragSource {
+        mime: data.mime().to_string(),
+        bytes: data.encoded().into(),
+        icon: Mutex::new(None),
+        reporter,
+    });
+    let source = manager.create_data_source(&info.queue_handle, drag_source.clone());
+    source.offer(drag_source.mime.clone());
+    //actions were added in version 3; before that every drop is a copy
+    if manager.version() >= 3 {
+        source.set_actions(dnd_actions(drag_source.reporter.actions()));
+    }
+
+    let icon = image.map(|image| {
+        let surface = app_state
+            .compositor
+            .create_surface(&info.queue_handle, SurfaceEvents::DragIcon);
+        let buffer = create_shm_buffer_pixels(
+            &app_state.shm,
+            &info.queue_handle,
+            window_internal.clone(),
+            image.width() as i32,
+            image.height() as i32,
+            &image.premultiplied_bgra(),
+        );
+        surface.attach(Some(&buffer.buffer), 0, 0);
+        surface
+    });
+
+    data_device.start_drag(Some(&source), &origin, icon.as_ref(), serial);
+    //the icon gets its role from start_drag, so commit afterwards
+    if let Some(icon) = icon {
+        icon.commit();
+        drag_source.icon.lock().unwrap().replace(icon);
+    }
+    Ok(())
+}
+
+impl Window {
+    pub async fn start_drag(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+        reporter: DragReporter,
+    ) -> Result<(), DragError> {
+        let window_internal = self.internal.clone();
+        crate::application::on_main_thread("Window::start_drag".to_string(), move || {
+            MAIN_THREAD_INFO.with_borrow(|info| {
+                let info = info.as_ref().expect("Main thread info not set");
+                start_drag_main(info, &window_internal, data, image, reporter)
+            })
+        })
+        .await
+    }
+}
@@ -1,18 +1,32 @@
 // SPDX-License-Identifier: MPL-2.0
 use super::{App, AppState};
-use crate::application::IS_MAIN_THREAD_RUNNING;
+use crate::application::{DispatchSlice, IS_MAIN_THREAD_RUNNING, MainError};
 use libc::{EFD_SEMAPHORE, SYS_gettid, c_int, c_void, eventfd, getpid, pid_t, syscall};
 use std::cell::RefCell;
-use std::os::fd::AsRawFd;
+use std::collections::HashMap;
+use std::os::fd::{AsFd, AsRawFd, RawFd};
 use std::sync::OnceLock;
 use std::sync::atomic::Ordering;
 use std::sync::mpsc::{Sender, channel};
 use std::time::Duration;
 use wayland_client::backend::WaylandError;
 use wayland_client::globals::{GlobalList, registry_queue_init};
+use wayland_client::protocol::wl_data_device_manager::WlDataDeviceManager;
+#[cfg(feature = "decorations")]
 use wayland_client::protocol::wl_subcompositor::WlSubcompositor;
 use wayland_client::protocol::{wl_compositor, wl_output::WlOutput, wl_shm::WlShm};
-use wayland_client::{Connection, QueueHandle};
+use wayland_client::{Connection, DispatchError, EventQueue, QueueHandle};
+use wayland_protocols::ext::data_control::v1::client::ext_data_control_manager_v1::ExtDataControlManagerV1;
+use wayland_protocols::wp::fractional_scale::v1::client::wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1;
+use wayland_protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibit_manager_v1::ZwpKeyboardShortcutsInhibitManagerV1;
+use wayland_protocols::wp::pointer_constraints::zv1::client::zwp_pointer_constraints_v1::ZwpPointerConstraintsV1;
+#[cfg(feature = "input")]
+use wayland_protocols::wp::text_input::zv3::client::zwp_text_input_manager_v3::ZwpTextInputManagerV3;
+use wayland_protocols::wp::viewporter::client::wp_viewporter::WpViewporter;
+use wayland_protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
+use wayland_protocols::xdg::decoration::zv1::client::zxdg_decoration_manager_v1::ZxdgDecorationManagerV1;
+use wayland_protocols::xdg::dialog::v1::client::xdg_wm_dialog_v1::XdgWmDialogV1;
+use wayland_protocols::xdg::toplevel_icon::v1::client::xdg_toplevel_icon_manager_v1::XdgToplevelIconManagerV1;
 
 pub fn is_main_thread() -> bool {
     let current_pid = unsafe { getpid() };
@@ -22,8 +36,18 @@ pub fn is_main_thread() -> bool {
 
 enum Message {
     Closure(Box<dyn FnOnce() + Send>),
+    /// Runs the closure after the delay.
+    Timer(Duration, Box<dyn FnOnce() + Send>),
     Stop,
 }
+
+/// A timeout submitted to io_uring, waiting to run its closure.
+struct PendingTimer {
+    //the kernel reads this when the timeout is submitted, so it has to live until then
+    _timespec: Box<io_uring::types::Timespec>,
+    closure: Box<dyn FnOnce() + Send>,
+}
+
 struct MainThreadSender {
     sender: Sender<Message>,
     eventfd: c_int,
@@ -54,15 +78,66 @@ static MAIN_THREAD_SENDER: OnceLock<MainThreadSender> = OnceLock::new();
 pub(super) struct MainThreadInfo {
     pub globals: GlobalList,
     pub queue_handle: QueueHandle<App>,
+    /// The queue for seat objects: `queue_handle`, unless they're dispatched on an input thread;
+    /// see [`crate::application::ApplicationOptions::dedicated_input_thread`].
+    pub input_queue_handle: QueueHandle<App>,
     pub connection: Connection,
     pub app_state: std::sync::Arc<AppState>,
+    #[cfg(feature = "decorations")]
     pub subcompositor: WlSubcompositor,
+    /// Not every compositor (e.g. some headless ones) offers drag-and-drop.
+    pub data_device_manager: Option<WlDataDeviceManager>,
+    /// Watches the clipboard without focus; a staging protocol, so often missing.
+    pub data_control_manager: Option<ExtDataControlManagerV1>,
+    /// Fractional scaling needs both of these; either may be missing on older compositors.
+    pub fractional_scale_manager: Option<WpFractionalScaleManagerV1>,
+    pub viewporter: Option<WpViewporter>,
+    pub shortcuts_inhibit_manager: Option<ZwpKeyboardShortcutsInhibitManagerV1>,
+    pub pointer_constraints: Option<ZwpPointerConstraintsV1>,
+    /// Only compositors that run input methods offer this.
+    #[cfg(feature = "input")]
+    pub text_input_manager: Option<ZwpTextInputManagerV3>,
+    /// Makes sheets modal; a staging protocol, so often missing.
+    pub dialog_manager: Option<XdgWmDialogV1>,
+    /// Sets window icons; a staging protocol, so often missing.
+    pub toplevel_icon_manager: Option<XdgToplevelIconManagerV1>,
+    /// Lets the compositor draw title bars.  GNOME doesn't offer it.
+    pub decoration_manager: Option<ZxdgDecorationManagerV1>,
+    /// Lets windows ask for focus; a staging protocol, so sometimes missing.
+    pub activation: Option<XdgActivationV1>,
 }
 
 thread_local! {
     pub static MAIN_THREAD_INFO: RefCell<Option<MainThreadInfo>> = const { RefCell::new(None) };
 }
 
+/// [`MAIN_THREAD_INFO`], taken out until this is dropped.
+///
+/// The main loop carries on after a closure panics, so the info goes back even then.
+pub(super) struct TakenInfo(Option<MainThreadInfo>);
+
+impl TakenInfo {
+    /// Call on the main thread, while nothing else has the info out.
+    pub fn take() -> Self {
+        TakenInfo(Some(
+            MAIN_THREAD_INFO.take().expect("Main thread info not set"),
+        ))
+    }
+}
+
+impl std::ops::Deref for TakenInfo {
+    type Target = MainThreadInfo;
+    fn deref(&self) -> &MainThreadInfo {
+        self.0.as_ref().expect("Main thread info already put back")
+    }
+}
+
+impl Drop for TakenInfo {
+    fn drop(&mut self) {
+        MAIN_THREAD_INFO.replace(self.0.take());
+    }
+}
+
 pub fn on_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
     MAIN_THREAD_SENDER
         .get()
@@ -70,6 +145,13 @@ pub fn on_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
         .send(Message::Closure(Box::new(closure)));
 }
 
+pub fn on_main_thread_after<F: FnOnce() + Send + 'static>(delay: Duration, closure: F) {
+    MAIN_THREAD_SENDER
+        .get()
+        .expect("Main thread sender not set")
+        .send(Message::Timer(delay, Box::new(closure)));
+}
+
 pub fn stop_main_thread() {
     MAIN_THREAD_SENDER
         .get()
@@ -81,44 +163,179 @@ pub async fn alert(message: String) {
     todo!("alert not yet implemented for Linux: {}", message)
 }
 
-pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
-    let (sender, receiver) = channel();
-    let channel_read_event = unsafe { eventfd(0, EFD_SEMAPHORE) };
-    assert_ne!(channel_read_event, -1, "Failed to create eventfd");
-    MAIN_THREAD_SENDER.get_or_init(|| MainThreadSender {
-        sender,
-        eventfd: channel_read_event,
-    });
+/// Dispatches a queue of its own on a new thread, so input isn't held up by the main thread.
+///
+/// libwayland lets each thread read for its own queue; window management stays on the main
+/// thread's queue.
+fn spawn_input_thread(connection: &Connection, app: &App) -> QueueHandle<App> {
+    let mut event_queue = connection.new_event_queue();
+    let queue_handle = event_queue.handle();
+    let mut app = App(app.0.clone());
+    std::thread::Builder::new()
+        .name("app_window input".to_string())
+        .spawn(move || {
+            while event_queue.blocking_dispatch(&mut app).is_ok() {}
+            //the main thread reads the same connection, so it reports the failure
+            logwise::debuginternal_sync!("Input thread stopping; the connection failed");
+        })
+        .expect("Can't spawn input thread");
+    queue_handle
+}
+
+/// The connection failed: a protocol error, or the compositor went away.  libwayland can't use
+/// a connection after that, so every window is lost; we tell them, and carry on running
+/// closures so the app can notice.
+fn connection_failed(app: &App, error: DispatchError) {
+    let message = match &error {
+        DispatchError::Backend(WaylandError::Protocol(e)) => format!(
+            "protocol error {code} on {interface}@{id}: {message}",
+            code = e.code,
+            interface = e.object_interface,
+            id = e.object_id,
+            message = e.message
+        ),
+        e => format!("connection lost: {e}"),
+    };
+    logwise::error_sync!(
+        "Wayland connection failed: {message}",
+        message = logwise::privacy::LogIt(&message)
+    );
+    app.0
+        .report_backend_error(crate::window::BackendError::new(message, true));
+}
 
-    let connection = Connection::connect_to_env().expect("Failed to connect to wayland server");
-    let (globals, mut event_queue) =
-        registry_queue_init::<App>(&connection).expect("Can't initialize registry");
+/// Connects to the compositor and binds its globals.
+fn connect() -> Result<(MainThreadInfo, EventQueue<App>, App), Box<dyn std::error::Error>> {
+    let connection = Connection::connect_to_env()?;
+    let (globals, event_queue) = registry_queue_init::<App>(&connection)?;
+    //registry_queue_init waits on a wl_display.sync
+    crate::application::record_platform_round_trip();
     let qh = event_queue.handle();
-    let compositor: wl_compositor::WlCompositor = globals.bind(&qh, 5..=6, ()).unwrap();
-    let subcompositor: WlSubcompositor = globals.bind(&qh, 1..=1, ()).unwrap();
+    let compositor: wl_compositor::WlCompositor = globals.bind(&qh, 5..=6, ())?;
+    #[cfg(feature = "decorations")]
+    let subcompositor: WlSubcompositor = globals.bind(&qh, 1..=1, ())?;
     //fedora 41 KDE uses version 1?
-    let shm: WlShm = globals.bind(&qh, 1..=2, ()).unwrap();
+    let shm: WlShm = globals.bind(&qh, 1..=2, ())?;
+    let data_device_manager: Option<WlDataDeviceManager> = globals.bind(&qh, 1..=3, ()).ok();
+    let data_control_manager: Option<ExtDataControlManagerV1> = globals.bind(&qh, 1..=1, ()).ok();
+    let fractional_scale_manager: Option<WpFractionalScaleManagerV1> =
+        globals.bind(&qh, 1..=1, ()).ok();
+    let viewporter: Option<WpViewporter> = globals.bind(&qh, 1..=1, ()).ok();
+    let shortcuts_inhibit_manager: Option<ZwpKeyboardShortcutsInhibitManagerV1> =
+        globals.bind(&qh, 1..=1, ()).ok();
+    let pointer_constraints: Option<ZwpPointerConstraintsV1> = globals.bind(&qh, 1..=1, ()).ok();
+    #[cfg(feature = "input")]
+    let text_input_manager: Option<ZwpTextInputManagerV3> = globals.bind(&qh, 1..=1, ()).ok();
+    let dialog_manager: Option<XdgWmDialogV1> = globals.bind(&qh, 1..=1, ()).ok();
+    let toplevel_icon_manager: Option<XdgToplevelIconManagerV1> = globals.bind(&qh, 1..=1, ()).ok();
+    let decoration_manager: Option<ZxdgDecorationManagerV1> = globals.bind(&qh, 1..=1, ()).ok();
+    let activation: Option<XdgActivationV1> = globals.bind(&qh, 1..=1, ()).ok();
 
     // Bind all available wl_output interfaces
+    let mut outputs = Vec::new();
     for global in globals.contents().clone_list() {
         if global.interface == "wl_output" {
-            let _output: WlOutput = globals
-                .bind(&qh, global.version..=global.version, global.name)
-                .unwrap();
+            let output: WlOutput =
+                globals.bind(&qh, global.version..=global.version, global.name)?;
+            outputs.push((global.name, output));
         }
     }
 
-    let mut app = App(AppState::new(&qh, compositor, &connection, shm));
+    let app = App(AppState::new(&qh, compositor, &connection, shm));
+    //kept for fullscreen requests; their events fill in the rest
+    app.0
+        .outputs
+        .lock()
+        .unwrap()
+        .extend(outputs.into_iter().map(|(name, output)| {
+            (
+                name,
+                super::OutputInfo {
+                    output: Some(output),
+                    ..Default::default()
+                },
+            )
+        }));
+    let input_queue_handle = if crate::application::dedicated_input_thread() {
+        spawn_input_thread(&connection, &app)
+    } else {
+        qh.clone()
+    };
     let main_thread_info = MainThreadInfo {
         globals,
         queue_handle: qh,
+        input_queue_handle,
         connection,
         app_state: app.0.clone(),
+        #[cfg(feature = "decorations")]
         subcompositor,
+        data_device_manager,
+        data_control_manager,
+        fractional_scale_manager,
+        viewporter,
+        shortcuts_inhibit_manager,
+        pointer_constraints,
+        #[cfg(feature = "input")]
+        text_input_manager,
+        dialog_manager,
+        toplevel_icon_manager,
+        decoration_manager,
+        activation,
+    };
+    Ok((main_thread_info, event_queue, app))
+}
+
+/// Tries for a new connection after the last one failed, and moves the windows to it.
+///
+/// On success, returns the new queue and state for the main loop, and the fd to poll.
+fn reconnect(old: &App) -> Option<(EventQueue<App>, App, RawFd)> {
+    let (info, event_queue, app) = match connect() {
+        Ok(connected) => connected,
+        Err(e) => {
+            logwise::debuginternal_sync!(
+                "Can't reconnect yet: {err}",
+                err = logwise::privacy::LogIt(&e.to_string())
+            );
+            return None;
+        }
     };
+    logwise::info_sync!("Reconnected to the Wayland compositor");
+    let windows: Vec<_> = std::mem::take(&mut *old.0.windows.lock().unwrap())
+        .into_iter()
+        .filter_map(|window| window.upgrade())
+        .collect();
+    for window in &windows {
+        super::window::rebuild(&info, window);
+    }
+    let fd = info.connection.as_fd().as_raw_fd();
+    MAIN_THREAD_INFO.replace(Some(info));
+    if crate::clipboard::is_watched()
+        && let Err(e) = super::clipboard::watch_clipboard()
+    {
+        logwise::warn_sync!(
+            "Can't watch the clipboard after reconnecting: {err}",
+            err = logwise::privacy::LogIt(&e)
+        );
+    }
+    Some((event_queue, app, fd))
+}
+
+pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) -> Result<(), MainError> {
+    //before anything's set up, so a failed attempt leaves nothing behind
+    let (main_thread_info, mut event_queue, mut app) =
+        connect().map_err(|e| MainError::Connect(e.to_string()))?;
+
+    let (sender, receiver) = channel();
+    let channel_read_event = unsafe { eventfd(0, EFD_SEMAPHORE) };
+    assert_ne!(channel_read_event, -1, "Failed to create eventfd");
+    MAIN_THREAD_SENDER.get_or_init(|| MainThreadSender {
+        sender,
+        eventfd: channel_read_event,
+    });
 
     MAIN_THREAD_INFO.replace(Some(main_thread_info));
-    let mut io_uring = io_uring::IoUring::new(2).expect("Failed to create io_uring");
+    //room for a burst of timers alongside the polls
+    let mut io_uring = io_uring::IoUring::new(32).expect("Failed to create io_uring");
 
     _ = std::thread::Builder::new()
         .name("app_window closure".to_string())
@@ -127,11 +344,17 @@ pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
     event_queue.flush().expect("Failed to flush event queue");
 
     let mut read_guard = Some(event_queue.prepare_read().expect("Failed to prepare read"));
+    //the low byte; wayland polls carry the connection's generation above it
     const WAYLAND_DATA_AVAILABLE: u64 = 1;
     const CHANNEL_DATA_AVAILABLE: u64 = 2;
+    const RECONNECT_DUE: u64 = 3;
+    //timers carry their id above the low byte
+    const TIMER_DUE: u64 = 4;
+    let reconnect_interval = io_uring::types::Timespec::from(Duration::from_secs(1));
+    let mut generation: u64 = 0;
     let fd = read_guard.as_ref().unwrap().connection_fd();
-    let io_uring_fd = io_uring::types::Fd(fd.as_raw_fd());
-    let io_uring_fd_raw = io_uring_fd.0.as_raw_fd();
+    let mut io_uring_fd = io_uring::types::Fd(fd.as_raw_fd());
+    let mut io_uring_fd_raw = io_uring_fd.0.as_raw_fd();
     let mut wayland_entry =
         io_uring::opcode::PollAdd::new(io_uring_fd, libc::POLLIN as u32).build();
     wayland_entry = wayland_entry.user_data(WAYLAND_DATA_AVAILABLE);
@@ -160,20 +383,18 @@ pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
         event_queue: &mut wayland_client::EventQueue<App>,
         app: &mut App,
         read_guard: &mut Option<wayland_client::backend::ReadEventsGuard>,
-    ) {
+    ) -> Result<(), DispatchError> {
         loop {
             let _read_guard = event_queue.prepare_read();
 
             match _read_guard {
                 Some(guard) => {
                     *read_guard = Some(guard);
-                    break; //out of loop
+                    return Ok(());
                 }
                 None => {
-                    event_queue
-                        .dispatch_pending(app)
-                        .expect("Can't dispatch events");
-                    event_queue.flush().expect("Failed to flush event queue");
+                    event_queue.dispatch_pending(app)?;
+                    event_queue.flush()?;
                     //try again
                     logwise::debuginternal_sync!("Retrying");
                 }
@@ -181,10 +402,39 @@ pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
         }
     }
 
+    /// Dispatches what we read, and sends what that queued (such as proxy replies, etc).
+    fn dispatch(
+        event_queue: &mut wayland_client::EventQueue<App>,
+        app: &mut App,
+    ) -> Result<(), DispatchError> {
+        event_queue.dispatch_pending(app)?;
+        event_queue.flush()?;
+        Ok(())
+    }
+
+    //cleared once the connection fails; until we reconnect, we only run closures
+    let mut connected = true;
+    let mut reconnect_armed = false;
+    let mut timers: HashMap<u64, PendingTimer> = HashMap::new();
+    let mut next_timer: u64 = 0;
+    let mut timers_due = Vec::new();
+
     //park
-    loop {
-        next_read_guard(&mut event_queue, &mut app, &mut read_guard);
-        assert!(read_guard.as_ref().unwrap().connection_fd().as_raw_fd() == io_uring_fd_raw);
+    'park: loop {
+        if connected && let Err(e) = next_read_guard(&mut event_queue, &mut app, &mut read_guard) {
+            connection_failed(&app, e);
+            connected = false;
+        }
+        if !connected && !reconnect_armed {
+            let entry = io_uring::opcode::Timeout::new(&reconnect_interval)
+                .build()
+                .user_data(RECONNECT_DUE);
+            unsafe { io_uring.submission().push(&entry) }.expect("Can't submit timeout");
+            reconnect_armed = true;
+        }
+        if let Some(guard) = read_guard.as_ref() {
+            assert!(guard.connection_fd().as_raw_fd() == io_uring_fd_raw);
+        }
         let r = io_uring.submit_and_wait(1);
         //we also want to take once regardless of entry
         let mut take_read_guard = read_guard.take();
@@ -200,12 +450,28 @@ pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
         }
         let mut wayland_data_available = false;
         let mut channel_data_available = false;
+        let mut reconnect_due = false;
         for entry in io_uring.completion() {
             let result = entry.result();
+            let user_data = entry.user_data();
+            match user_data & 0xFF {
+                //a poll on a connection that has since failed
+                WAYLAND_DATA_AVAILABLE if user_data >> 8 != generation => continue,
+                //timeouts complete with -ETIME
+                RECONNECT_DUE => {
+                    reconnect_due = true;
+                    continue;
+                }
+                TIMER_DUE => {
+                    timers_due.push(user_data >> 8);
+                    continue;
+                }
+                _ => {}
+            }
             if result < 0 {
                 panic!("Error in completion queue: {err}", err = result);
             }
-            match entry.user_data() {
+            match user_data & 0xFF {
                 WAYLAND_DATA_AVAILABLE => {
                     wayland_data_available = true;
                 }
@@ -217,69 +483,128 @@ pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
                 }
             }
         }
-        if wayland_data_available {
-            match take_read_guard
+        if reconnect_due {
+            reconnect_armed = false;
+            if let Some((new_queue, new_app, fd)) = reconnect(&app) {
+                event_queue = new_queue;
+                app = new_app;
+                generation += 1;
+                io_uring_fd = io_uring::types::Fd(fd);
+                io_uring_fd_raw = fd;
+                //send the rebuilt windows' requests
+                match event_queue.flush() {
+                    Ok(()) => {
+                        connected = true;
+                        wayland_entry =
+                            io_uring::opcode::PollAdd::new(io_uring_fd, libc::POLLIN as u32)
+                                .build()
+                                .user_data(WAYLAND_DATA_AVAILABLE | (generation << 8));
+                        unsafe { io_uring.submission().push(&wayland_entry) }
+                            .expect("Can't submit peek");
+                    }
+                    Err(e) => connection_failed(&app, e.into()),
+                }
+            }
+        }
+        if wayland_data_available && connected {
+            let read = match take_read_guard
                 .take()
                 .expect("Read guard not available")
                 .read()
             {
-                Ok(_) => {}
+                //continue
+                Err(WaylandError::Io(e)) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(()),
+                r => r.map(drop).map_err(DispatchError::from),
+            };
+            match read.and_then(|()| dispatch(&mut event_queue, &mut app)) {
+                Ok(()) => {
+                    //prepare next read
+                    let mut sqs = io_uring.submission();
+                    wayland_entry =
+                        io_uring::opcode::PollAdd::new(io_uring_fd, libc::POLLIN as u32).build();
+                    wayland_entry =
+                        wayland_entry.user_data(WAYLAND_DATA_AVAILABLE | (generation << 8));
+                    unsafe { sqs.push(&wayland_entry) }.expect("Can't submit peek");
+                    //return to submit_and_wait
+                }
                 Err(e) => {
-                    match e {
-                        WaylandError::Io(e) => {
-                            match e.kind() {
-                                std::io::ErrorKind::WouldBlock => {
-                                    //continue
-                                }
-                                _ => {
-                                    panic!("Error reading from wayland: {err}", err = e);
-                                }
-                            }
-                        }
-                        WaylandError::Protocol(_) => {
-                            panic!("Protocol error reading from wayland");
-                        }
-                    }
+                    //the fd stays readable (or hung up), so don't poll it again
+                    connection_failed(&app, e);
+                    connected = false;
+                }
+            }
+        }
+        if !timers_due.is_empty() {
+            //like closures, timers run without holding up other readers
+            take_read_guard = None;
+            for id in timers_due.drain(..) {
+                if let Some(timer) = timers.remove(&id) {
+                    (timer.closure)();
                 }
             }
-            event_queue
-                .dispatch_pending(&mut app)
-                .expect("Can't dispatch events");
-            //prepare next read
-            //ensure writes queued during dispatch_pending go out (such as proxy replies, etc)
-            event_queue.flush().expect("Failed to flush event queue");
-
-            let mut sqs = io_uring.submission();
-            wayland_entry =
-                io_uring::opcode::PollAdd::new(io_uring_fd, libc::POLLIN as u32).build();
-            wayland_entry = wayland_entry.user_data(WAYLAND_DATA_AVAILABLE);
-            unsafe { sqs.push(&wayland_entry) }.expect("Can't submit peek");
-            //return to submit_and_wait
         }
         if channel_data_available {
             drop(take_read_guard); //we don't need it anymore
-            let mut buf = [0u8; 8];
-            let r = unsafe { libc::read(channel_read_event, buf.as_mut_ptr() as *mut c_void, 8) };
-            assert_eq!(r, 8, "Failed to read from eventfd");
-            let message = receiver
-                .recv_timeout(Duration::from_secs(0))
-                .expect("Failed to receive closure");
-            match message {
-                Message::Closure(closure) => closure(),
-                Message::Stop => {
-                    IS_MAIN_THREAD_RUNNING.store(false, Ordering::Relaxed);
+            let read_eventfd = || {
+                let mut buf = [0u8; 8];
+                let r =
+                    unsafe { libc::read(channel_read_event, buf.as_mut_ptr() as *mut c_void, 8) };
+                assert_eq!(r, 8, "Failed to read from eventfd");
+            };
+            read_eventfd();
+            let mut message = Some(
+                receiver
+                    .recv_timeout(Duration::from_secs(0))
+                    .expect("Failed to receive closure"),
+            );
+            //run queued closures until the budget is spent, then give wayland a turn
+            let mut slice = DispatchSlice::new();
+            while let Some(next) = message.take() {
+                match next {
+                    Message::Closure(closure) => closure(),
+                    Message::Timer(delay, closure) => {
+                        let id = next_timer;
+                        next_timer += 1;
+                        let timespec = Box::new(io_uring::types::Timespec::from(delay));
+                        let entry = io_uring::opcode::Timeout::new(&*timespec)
+                            .build()
+                            .user_data(TIMER_DUE | (id << 8));
+                        timers.insert(
+                            id,
+                            PendingTimer {
+                                _timespec: timespec,
+                                closure,
+                            },
+                        );
+                        //a full queue goes to the kernel now, making room
+                        while unsafe { io_uring.submission().push(&entry) }.is_err() {
+                            io_uring.submit().expect("Can't submit timeouts");
+                        }
+                    }
+                    Message::Stop => {
+                        IS_MAIN_THREAD_RUNNING.store(false, Ordering::Relaxed);
+                        break 'park;
+                    }
+                }
+                if slice.record_op() {
                     break;
                 }
+                if let Ok(next) = receiver.try_recv() {
+                    //each message comes with one eventfd count, written right after it's queued
+                    read_eventfd();
+                    message = Some(next);
+                }
             }
             //let's ensure any writes went out to wayland
-            event_queue
-                .dispatch_pending(&mut app)
-                .expect("can't dispatch events");
-            event_queue.flush().expect("Failed to flush event queue");
+            if connected && let Err(e) = dispatch(&mut event_queue, &mut app) {
+                connection_failed(&app, e);
+                connected = false;
+            }
             //submit new peek
             let mut sqs = io_uring.submission();
             unsafe { sqs.push(&eventfd_opcode) }.expect("Can't submit peek");
             //return to submit_and_wait
         }
     }
+    Ok(())
 }
@@ -1,20 +1,115 @@
 // SPDX-License-Identifier: MPL-2.0
 
 // Re-export main types and functions
+pub use audio::{audio_hint_capabilities, set_audio_hints};
 pub use buffer::AllocatedBuffer;
+pub use clipboard::{unwatch_clipboard, watch_clipboard};
+#[cfg(feature = "cursor-theme")]
 pub use cursor::ActiveCursor;
-pub use main_thread::{alert, is_main_thread, on_main_thread, run_main_thread, stop_main_thread};
+pub use main_thread::{
+    alert, is_main_thread, on_main_thread, on_main_thread_after, run_main_thread, stop_main_thread,
+};
+pub use performance::{performance_hint_capabilities, set_performance_hints};
 pub(crate) use window::Window;
+
+#[cfg(feature = "portal")]
+pub use portal::{capture_permission_state, pick_color, request_capture_permission};
+
+#[cfg(feature = "portal")]
+pub use power::{unwatch_power, watch_power};
+
+#[cfg(not(feature = "portal"))]
+pub async fn watch_power() -> Result<(), crate::power::PowerError> {
+    Err(crate::power::PowerError::Unsupported)
+}
+
+#[cfg(not(feature = "portal"))]
+pub fn unwatch_power() {}
+
+#[cfg(not(feature = "portal"))]
+pub async fn pick_color() -> Result<crate::dialog::Color, crate::dialog::PickColorError> {
+    Err(crate::dialog::PickColorError::Unsupported)
+}
+
+//capture on Wayland always goes through the portal, which asks each time
+#[cfg(not(feature = "portal"))]
+pub async fn capture_permission_state() -> crate::application::CapturePermission {
+    crate::application::CapturePermission::AskEachTime
+}
+
+#[cfg(not(feature = "portal"))]
+pub async fn request_capture_permission() -> crate::application::CapturePermission {
+    crate::application::CapturePermission::AskEachTime
+}
+
+#[cfg(feature = "notifications")]
+pub use notification::post_notification;
+
+#[cfg(not(feature = "notifications"))]
+pub async fn post_notification(
+    _notification: crate::notification::Notification,
+) -> Result<(), crate::notification::NotificationError> {
+    Err(crate::notification::NotificationError::Unsupported)
+}
+
+#[cfg(feature = "tray")]
+pub use tray::TrayIcon;
+
+#[cfg(not(feature = "tray"))]
+#[derive(Debug)]
+pub struct TrayIcon(());
+
+#[cfg(not(feature = "tray"))]
+impl TrayIcon {
+    pub async fn new(
+        _id: u64,
+        _state: crate::tray::TrayState,
+    ) -> Result<Self, crate::tray::TrayError> {
+        Err(crate::tray::TrayError::Unsupported)
+    }
+
+    pub async fn update(
+        &self,
+        _state: crate::tray::TrayState,
+    ) -> Result<(), crate::tray::TrayError> {
+        Err(crate::tray::TrayError::Unsupported)
+    }
+}
+//the compositor decides what appears in its taskbar or dock
+pub async fn set_activation_policy(
+    _policy: crate::application::ActivationPolicy,
+) -> Result<(), crate::application::ActivationPolicyError> {
+    Err(crate::application::ActivationPolicyError::Unsupported)
+}
 // Module declarations
+pub mod activation;
+pub mod audio;
+#[cfg(feature = "accessibility")]
 pub mod ax;
 pub mod buffer;
+pub mod clipboard;
 pub mod cursor;
 pub mod dispatchers;
+pub mod drag;
 pub mod main_thread;
+#[cfg(feature = "notifications")]
+pub mod notification;
+pub mod performance;
+#[cfg(feature = "portal")]
+pub mod portal;
+#[cfg(feature = "portal")]
+pub mod power;
+pub mod scale;
+#[cfg(feature = "input")]
+pub mod text_input;
+#[cfg(feature = "tray")]
+pub mod tray;
 pub mod window;
 
-use crate::coordinates::Size;
+use crate::coordinates::{Rect, Size};
 use crate::sys::window::WindowInternal;
+use crate::window::{BackendError, FrameRequester, WindowEvent};
+#[cfg(feature = "accessibility")]
 use accesskit::NodeId;
 use memmap2::MmapMut;
 use raw_window_handle::{
@@ -23,47 +118,56 @@ use raw_window_handle::{
 use std::collections::HashMap;
 use std::ffi::c_void;
 use std::fs::File;
-use std::io::Cursor;
 use std::ptr::NonNull;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 use wayland_client::protocol::wl_compositor::WlCompositor;
-use wayland_client::protocol::wl_display::WlDisplay;
+use wayland_client::protocol::wl_data_device::WlDataDevice;
+use wayland_client::protocol::wl_output::WlOutput;
 use wayland_client::protocol::wl_seat::WlSeat;
 use wayland_client::protocol::wl_shm::WlShm;
 use wayland_client::protocol::wl_surface::WlSurface;
 use wayland_client::{Connection, Proxy, QueueHandle};
-use zune_png::zune_core::result::DecodingResult;
+use wayland_protocols::ext::data_control::v1::client::ext_data_control_device_v1::ExtDataControlDeviceV1;
 
 // Constants
+#[cfg(feature = "accessibility")]
 const CLOSE_ID: NodeId = NodeId(3);
+#[cfg(feature = "accessibility")]
 const MAXIMIZE_ID: NodeId = NodeId(4);
+#[cfg(feature = "accessibility")]
 const MINIMIZE_ID: NodeId = NodeId(5);
-const TITLEBAR_HEIGHT: u64 = 25;
-const BUTTON_WIDTH: u64 = 25;
+/// Where the app's own tree is grafted; see `AccessibilityContent`.
+#[cfg(feature = "accessibility")]
+const CONTENT_ID: NodeId = NodeId(6);
 
-#[derive(Debug)]
-pub struct FullscreenError;
-
-impl std::error::Error for FullscreenError {}
-
-impl std::fmt::Display for FullscreenError {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        unimplemented!()
-    }
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum FullscreenError {
+    /// No connected `wl_output` has this name.
+    #[error("no output {0}")]
+    NoSuchOutput(u32),
+    /// The first configure didn't have the fullscreen state, or the window went first.
+    #[error("the compositor didn't make the window fullscreen")]
+    Declined,
 }
 
 #[derive(Debug, Clone)]
 struct OutputInfo {
     scale_factor: f64,
+    /// For `xdg_toplevel.set_fullscreen`.
+    output: Option<WlOutput>,
 }
 
 impl Default for OutputInfo {
     fn default() -> Self {
-        Self { scale_factor: 1.0 }
+        Self {
+            scale_factor: 1.0,
+            output: None,
+        }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 struct Configure {
     width: i32,
     height: i32,
@@ -73,50 +177,67 @@ pub(super) struct App(Arc<AppState>);
 
 enum SurfaceEvents {
     Standard(Arc<Mutex<WindowInternal>>),
+    #[cfg(feature = "cursor-theme")]
     Cursor,
+    #[cfg(feature = "decorations")]
     Decor,
+    DragIcon,
 }
 
 struct AppState {
     compositor: WlCompositor,
     shm: WlShm,
     //option for lazy-init purposes
+    #[cfg(feature = "cursor-theme")]
     active_cursor: Mutex<Option<ActiveCursor>>,
     seat: Mutex<Option<WlSeat>>,
+    //created lazily the first time we drag, or watch the clipboard without ext-data-control
+    data_device: Mutex<Option<WlDataDevice>>,
+    /// While anyone watches the clipboard, if the compositor offers ext-data-control.
+    data_control_device: Mutex<Option<ExtDataControlDeviceV1>>,
     outputs: Mutex<HashMap<u32, OutputInfo>>,
-    _decor: Vec<u8>,
-    decor_dimensions: (usize, usize),
+    /// Every window, to tell if the connection fails.
+    windows: Mutex<Vec<Weak<Mutex<WindowInternal>>>>,
 }
 
 impl AppState {
+    #[cfg_attr(not(feature = "cursor-theme"), allow(unused_variables))]
     fn new(
         queue_handle: &QueueHandle<App>,
         compositor: WlCompositor,
         connection: &Connection,
         shm: WlShm,
     ) -> Arc<Self> {
-        let decor = include_bytes!("../../../linux_assets/decor.png");
-        let mut decode_decor = zune_png::PngDecoder::new(Cursor::new(&decor[..]));
-        let decode = decode_decor.decode().expect("Can't decode decor");
-        let dimensions = decode_decor.dimensions().expect("Can't decode decor");
-        let decor = match decode {
-            DecodingResult::U8(d) => d,
-            _ => todo!(),
-        };
-
         let a = Arc::new(AppState {
             compositor: compositor.clone(),
             shm: shm.clone(),
+            #[cfg(feature = "cursor-theme")]
             active_cursor: Mutex::new(None),
             seat: Mutex::new(None),
+            data_device: Mutex::new(None),
+            data_control_device: Mutex::new(None),
             outputs: Mutex::new(HashMap::new()),
-            _decor: decor,
-            decor_dimensions: dimensions,
+            windows: Mutex::new(Vec::new()),
         });
-        let active_cursor = ActiveCursor::new(connection, shm, &a, &compositor, queue_handle);
-        a.active_cursor.lock().unwrap().replace(active_cursor);
+        #[cfg(feature = "cursor-theme")]
+        {
+            let active_cursor = ActiveCursor::new(connection, shm, &a, &compositor, queue_handle);
+            a.active_cursor.lock().unwrap().replace(active_cursor);
+        }
         a
     }
+
+    /// Tells every window about `error`.
+    fn report_backend_error(&self, error: BackendError) {
+        self.windows.lock().unwrap().retain(|window| {
+            let Some(window) = window.upgrade() else {
+                return false;
+            };
+            let events = window.lock().unwrap().events.clone();
+            events.send(WindowEvent::BackendError(error.clone()));
+            true
+        });
+    }
 }
 
 struct BufferReleaseInfo {
@@ -133,7 +254,6 @@ struct ReleaseOpt {
 
 #[derive(Debug)]
 pub struct Surface {
-    wl_display: WlDisplay,
     wl_surface: WlSurface,
     window_internal: Arc<Mutex<WindowInternal>>,
 }
@@ -143,35 +263,12 @@ unsafe impl Sync for Surface {}
 
 impl Surface {
     fn size_scale_impl(&self) -> (Size, f64) {
-        let size = self.window_internal.lock().unwrap().applied_size();
-
-        // Get the scale factor from the app state directly (accessible from any thread)
         let window_internal = self.window_internal.lock().unwrap();
-        let current_outputs = window_internal.current_outputs.clone();
-        let app_state = window_internal
-            .app_state
-            .upgrade()
-            .expect("App state is gone");
-        drop(window_internal);
-
-        // Get the scale factor for the outputs this window is currently on
-        let outputs = app_state.outputs.lock().unwrap();
-        let scale = if current_outputs.is_empty() {
-            // If no outputs are tracked yet, default to 1.0
-            1.0
-        } else {
-            // Use the scale factor of the first output the window is on
-            // In a proper implementation, you might want to use the "primary" output
-            // or the one with the largest intersection area with the window
-            current_outputs
-                .iter()
-                .filter_map(|output_id| outputs.get(output_id))
-                .map(|output_info| output_info.scale_factor)
-                .next()
-                .unwrap_or(1.0)
-        };
-
-        (size, scale)
+        //report the scale we actually commit buffers at, so consumers render at the right size
+        (
+            window_internal.applied_size(),
+            window_internal.applied_scale,
+        )
     }
 
     pub async fn size_scale(&self) -> (Size, f64) {
@@ -191,8 +288,9 @@ impl Surface {
     }
 
     pub fn raw_display_handle(&self) -> RawDisplayHandle {
+        //the surface's connection, which is the current one unless the compositor restarted
         let ptr = self
-            .wl_display
+            .wl_surface
             .backend()
             .upgrade()
             .unwrap()
@@ -208,12 +306,52 @@ impl Surface {
         self.window_internal.lock().unwrap().size_update_notify =
             Some(window::DebugWrapper(Box::new(update)));
     }
+
+    pub fn frame_requester(&self) -> FrameRequester {
+        //the window holds our size callback, which may hold this
+        let internal = Arc::downgrade(&self.window_internal);
+        Arc::new(move |frame| {
+            if let Some(internal) = internal.upgrade() {
+                window::request_frame(internal, frame)
+            }
+        })
+    }
+
+    pub fn damage(&self, rects: &[Rect]) {
+        let locked = self.window_internal.lock().unwrap();
+        //the window is closed, or our wl_surface was lost with the connection
+        if locked.wl_surface.as_ref() != Some(&self.wl_surface) || !self.wl_surface.is_alive() {
+            return;
+        }
+        let scale = locked.applied_scale;
+        drop(locked);
+        //damage is pending state, so it's picked up by whoever commits the next buffer
+        for rect in rects {
+            let x = (rect.origin().x() * scale).floor();
+            let y = (rect.origin().y() * scale).floor();
+            let right = ((rect.origin().x() + rect.size().width()) * scale).ceil();
+            let bottom = ((rect.origin().y() + rect.size().height()) * scale).ceil();
+            self.wl_surface.damage_buffer(
+                x as i32,
+                y as i32,
+                (right - x) as i32,
+                (bottom - y) as i32,
+            );
+        }
+    }
 }
 
 impl Drop for Surface {
     fn drop(&mut self) {
         // wl_surface is destroyed by Window::Drop via close_window()
-        // wl_display is owned by Connection
-        // window_internal is an Arc (just decrements refcount)
+        let mut locked = self.window_internal.lock().unwrap();
+        //a surface from after a reconnect owns the callback now
+        if locked
+            .wl_surface
+            .as_ref()
+            .is_none_or(|current| current == &self.wl_surface)
+        {
+            locked.size_update_notify = None;
+        }
     }
 }
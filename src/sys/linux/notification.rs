@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Notifications, with `org.freedesktop.Notifications` on the session D-Bus.
+
+use crate::notification::{Notification, NotificationError};
+use std::collections::HashMap;
+use zbus::zvariant::{Structure, Value};
+
+fn platform(e: zbus::Error) -> NotificationError {
+    NotificationError::Platform(e.to_string())
+}
+
+/// Whether `e` means there's no notification server.
+fn is_missing(e: &zbus::Error) -> bool {
+    match e {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.ServiceUnknown"
+                | "org.freedesktop.DBus.Error.UnknownMethod"
+                | "org.freedesktop.DBus.Error.UnknownObject"
+        ),
+        _ => false,
+    }
+}
+
+/// The name servers show the notification under.
+fn app_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default()
+}
+
+pub async fn post_notification(notification: Notification) -> Result<(), NotificationError> {
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|_| NotificationError::Unsupported)?;
+    let mut hints: HashMap<&str, Value> = HashMap::new();
+    if let Some(icon) = &notification.icon {
+        //the image-data hint: width, height, rowstride, has alpha, bits per sample, channels
+        let image = Structure::from((
+            icon.width() as i32,
+            icon.height() as i32,
+            icon.width() as i32 * 4,
+            true,
+            8i32,
+            4i32,
+            icon.rgba().to_vec(),
+        ));
+        hints.insert("image-data", Value::from(image));
+    }
+    let actions: Vec<&str> = Vec::new();
+    let notified: zbus::Result<u32> = connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            //replaces nothing, no icon name, and the server's default timeout
+            &(
+                app_name(),
+                0u32,
+                "",
+                notification.title.as_str(),
+                notification.body.as_str(),
+                actions,
+                hints,
+                -1i32,
+            ),
+        )
+        .await
+        .and_then(|reply| reply.body().deserialize());
+    match notified {
+        Ok(_) => Ok(()),
+        Err(e) if is_missing(&e) => Err(NotificationError::Unsupported),
+        Err(e) => Err(platform(e)),
+    }
+}
@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Performance hints, through the scheduler.
+//!
+//! Niceness and timer slack both belong to threads on Linux, not the process.  Threads take
+//! their creator's nice value, so once every thread is raised, threads spawned later are too.
+
+use crate::application::{PerformanceHintCapabilities, PerformanceHintError, PerformanceHints};
+use crate::audio::HintSupport;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The nice value for [`PerformanceHints::high_process_priority`].  Unprivileged processes may
+/// only go this low with an `RLIMIT_NICE` allowance.
+const HIGH_PRIORITY_NICE: libc::c_int = -5;
+
+/// Not in `libc`; see capabilities(7).
+const CAP_SYS_NICE: u32 = 23;
+
+/// Nice values from before we raised our priority.
+struct SavedNice {
+    threads: HashMap<libc::id_t, libc::c_int>,
+    /// For threads spawned since, which inherited the raised value: the main thread's.
+    spawned: libc::c_int,
+}
+
+/// What we changed, so we only undo that, and back to how it was.
+struct Applied {
+    hints: PerformanceHints,
+    nice: Option<SavedNice>,
+    /// The main thread's timer slack before we shortened it.
+    timer_slack: libc::c_ulong,
+}
+
+static APPLIED: Mutex<Applied> = Mutex::new(Applied {
+    hints: PerformanceHints::new(),
+    nice: None,
+    timer_slack: 0,
+});
+
+pub fn performance_hint_capabilities() -> PerformanceHintCapabilities {
+    PerformanceHintCapabilities {
+        prefer_discrete_gpu: HintSupport::Unsupported,
+        high_process_priority: if may_raise_priority() {
+            HintSupport::Supported
+        } else {
+            HintSupport::Unsupported
+        },
+        disable_power_throttling: HintSupport::Supported,
+    }
+}
+
+fn platform_error(what: &str, e: std::io::Error) -> PerformanceHintError {
+    PerformanceHintError::Platform(format!("can't {what}: {e}"))
+}
+
+/// Whether we can go down to [`HIGH_PRIORITY_NICE`]: with `CAP_SYS_NICE`, an `RLIMIT_NICE`
+/// allowance, or because we're there already.
+fn may_raise_priority() -> bool {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    //the limit allows nice values down to 20 - rlim_cur
+    if unsafe { libc::getrlimit(libc::RLIMIT_NICE, &mut limit) } == 0
+        && limit.rlim_cur >= (20 - HIGH_PRIORITY_NICE) as libc::rlim_t
+    {
+        return true;
+    }
+    if get_nice(0).is_ok_and(|nice| nice <= HIGH_PRIORITY_NICE) {
+        return true;
+    }
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            let caps = status.lines().find_map(|l| l.strip_prefix("CapEff:"))?;
+            u64::from_str_radix(caps.trim(), 16).ok()
+        })
+        .is_some_and(|caps| caps & (1 << CAP_SYS_NICE) != 0)
+}
+
+/// Our threads, as of now.
+fn threads() -> Result<Vec<libc::id_t>, PerformanceHintError> {
+    let tasks =
+        std::fs::read_dir("/proc/self/task").map_err(|e| platform_error("list threads", e))?;
+    Ok(tasks
+        .flatten()
+        .filter_map(|task| task.file_name().to_str()?.parse().ok())
+        .collect())
+}
+
+/// The nice value of thread `tid`, or of this thread for 0.
+fn get_nice(tid: libc::id_t) -> std::io::Result<libc::c_int> {
+    //-1 is a nice value too, so look at errno
+    unsafe { *libc::__errno_location() = 0 };
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, tid) };
+    let e = std::io::Error::last_os_error();
+    match e.raw_os_error() {
+        Some(0) => Ok(nice),
+        _ => Err(e),
+    }
+}
+
+fn set_nice(tid: libc::id_t, nice: libc::c_int) -> std::io::Result<()> {
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, nice) } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Whether `e` says the thread exited since we listed it.
+fn exited(e: &std::io::Error) -> bool {
+    e.raw_os_error() == Some(libc::ESRCH)
+}
+
+/// Lowers the nice value of each of our threads to [`HIGH_PRIORITY_NICE`], leaving those
+/// already lower alone.  Call on the main thread.
+fn raise_priority() -> Result<SavedNice, PerformanceHintError> {
+    let mut saved = SavedNice {
+        threads: HashMap::new(),
+        spawned: get_nice(0).map_err(|e| platform_error("read thread priority", e))?,
+    };
+    for tid in threads()? {
+        let nice = match get_nice(tid) {
+            Ok(nice) => nice,
+            Err(e) if exited(&e) => continue,
+            Err(e) => {
+                undo(&saved);
+                return Err(platform_error("read thread priority", e));
+            }
+        };
+        match set_nice(tid, nice.min(HIGH_PRIORITY_NICE)) {
+            Ok(()) => {
+                saved.threads.insert(tid, nice);
+            }
+            Err(e) if exited(&e) => {}
+            Err(e) => {
+                undo(&saved);
+                return Err(platform_error("set thread priority", e));
+            }
+        }
+    }
+    Ok(saved)
+}
+
+/// Puts back the threads [`raise_priority`] got to before it failed.
+fn undo(saved: &SavedNice) {
+    for (&tid, &nice) in &saved.threads {
+        _ = set_nice(tid, nice);
+    }
+}
+
+/// Puts back the nice values from before [`raise_priority`].
+fn restore_priority(saved: &SavedNice) -> Result<(), PerformanceHintError> {
+    for tid in threads()? {
+        let nice = saved.threads.get(&tid).copied().unwrap_or(saved.spawned);
+        match set_nice(tid, nice) {
+            Ok(()) => {}
+            Err(e) if exited(&e) => {}
+            Err(e) => return Err(platform_error("set thread priority", e)),
+        }
+    }
+    Ok(())
+}
+
+/// Call on the main thread, whose timer slack we change.
+fn apply(hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    let mut applied = APPLIED.lock().unwrap();
+    //unsupported without the privilege, so skipped like other unsupported hints
+    let high_priority =
+        hints.wants_high_process_priority() && (applied.nice.is_some() || may_raise_priority());
+    if high_priority != applied.nice.is_some() {
+        match &applied.nice {
+            Some(saved) => {
+                restore_priority(saved)?;
+                applied.nice = None;
+            }
+            None => applied.nice = Some(raise_priority()?),
+        }
+    }
+    applied.hints = applied.hints.high_process_priority(high_priority);
+    if hints.wants_disable_power_throttling() != applied.hints.wants_disable_power_throttling() {
+        let slack = if hints.wants_disable_power_throttling() {
+            applied.timer_slack = unsafe { libc::prctl(libc::PR_GET_TIMERSLACK) } as libc::c_ulong;
+            //1ns is the least slack
+            1
+        } else {
+            applied.timer_slack
+        };
+        if unsafe { libc::prctl(libc::PR_SET_TIMERSLACK, slack) } == -1 {
+            return Err(platform_error(
+                "set timer slack",
+                std::io::Error::last_os_error(),
+            ));
+        }
+        applied.hints = applied
+            .hints
+            .disable_power_throttling(hints.wants_disable_power_throttling());
+    }
+    Ok(())
+}
+
+pub async fn set_performance_hints(hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    crate::application::on_main_thread("set_performance_hints".to_string(), move || apply(hints))
+        .await
+}
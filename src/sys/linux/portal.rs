@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Dialogs provided by xdg-desktop-portal, over the session D-Bus.
+
+use crate::application::CapturePermission;
+use crate::dialog::{Color, PickColorError};
+use std::collections::HashMap;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use zbus::export::futures_core::Stream;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+pub(super) const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+pub(super) const PATH: &str = "/org/freedesktop/portal/desktop";
+
+fn platform(e: zbus::Error) -> PickColorError {
+    PickColorError::Platform(e.to_string())
+}
+
+/// Whether `e` means there's no portal, or it doesn't implement the interface.
+pub(super) fn is_missing(e: &zbus::Error) -> bool {
+    match e {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.ServiceUnknown"
+                | "org.freedesktop.DBus.Error.UnknownMethod"
+                | "org.freedesktop.DBus.Error.UnknownInterface"
+                | "org.freedesktop.DBus.Error.UnknownObject"
+        ),
+        _ => false,
+    }
+}
+
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    static NEXT_TOKEN: AtomicU32 = AtomicU32::new(0);
+
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|_| PickColorError::Unsupported)?;
+
+    //the portal may answer before PickColor returns, so listen on the request path up front;
+    //see org.freedesktop.portal.Request
+    let token = format!("app_window_{}", NEXT_TOKEN.fetch_add(1, Ordering::Relaxed));
+    let sender = connection
+        .unique_name()
+        .map(|name| name.trim_start_matches(':').replace('.', "_"))
+        .ok_or_else(|| PickColorError::Platform("no D-Bus unique name".to_string()))?;
+    let request_path = format!("{PATH}/request/{sender}/{token}");
+    let request = zbus::Proxy::new(
+        &connection,
+        DESTINATION,
+        request_path.as_str(),
+        "org.freedesktop.portal.Request",
+    )
+    .await
+    .map_err(platform)?;
+    let mut responses = request.receive_signal("Response").await.map_err(platform)?;
+
+    let screenshot = zbus::Proxy::new(
+        &connection,
+        DESTINATION,
+        PATH,
+        "org.freedesktop.portal.Screenshot",
+    )
+    .await
+    .map_err(platform)?;
+    let mut options: HashMap<&str, Value> = HashMap::new();
+    options.insert("handle_token", Value::from(token.as_str()));
+    //no parent window: exporting a Wayland handle needs xdg-foreign
+    let reply: Result<OwnedObjectPath, zbus::Error> =
+        screenshot.call("PickColor", &("", options)).await;
+    match reply {
+        Ok(_) => {}
+        Err(e) if is_missing(&e) => return Err(PickColorError::Unsupported),
+        Err(e) => return Err(platform(e)),
+    }
+
+    let message = poll_fn(|cx| Pin::new(&mut responses).poll_next(cx))
+        .await
+        .ok_or_else(|| PickColorError::Platform("the portal went away".to_string()))?;
+    let (response, results): (u32, HashMap<String, OwnedValue>) =
+        message.body().deserialize().map_err(platform)?;
+    match response {
+        0 => {}
+        1 => return Err(PickColorError::Cancelled),
+        other => {
+            return Err(PickColorError::Platform(format!(
+                "the portal ended the request ({other})"
+            )));
+        }
+    }
+    let color = results
+        .get("color")
+        .ok_or_else(|| PickColorError::Platform("the portal returned no color".to_string()))?;
+    let (red, green, blue): (f64, f64, f64) = color
+        .try_clone()
+        .and_then(TryInto::try_into)
+        .map_err(|e| PickColorError::Platform(e.to_string()))?;
+    Ok(Color::new(red, green, blue))
+}
+
+/// The ScreenCast portal asks the user each time a capture starts, so all we can check is
+/// whether there is one.
+pub async fn capture_permission_state() -> CapturePermission {
+    let Ok(connection) = zbus::Connection::session().await else {
+        return CapturePermission::Unavailable;
+    };
+    let Ok(screen_cast) = zbus::Proxy::new(
+        &connection,
+        DESTINATION,
+        PATH,
+        "org.freedesktop.portal.ScreenCast",
+    )
+    .await
+    else {
+        return CapturePermission::Unavailable;
+    };
+    //every version of the interface has this property
+    match screen_cast.get_property::<u32>("version").await {
+        Ok(_) => CapturePermission::AskEachTime,
+        Err(_) => CapturePermission::Unavailable,
+    }
+}
+
+pub async fn request_capture_permission() -> CapturePermission {
+    capture_permission_state().await
+}
@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Power notifications from xdg-desktop-portal.
+//!
+//! `PowerProfileMonitor` says whether the power saver profile is on, and an `Inhibit` monitor
+//! says whether the screensaver is active.  Either may be missing, depending on the desktop.
+//! The watch has its own connection, so closing it is all it takes to end the monitor's
+//! session.
+
+use super::portal::{DESTINATION, PATH, is_missing};
+use crate::power::{PowerError, PowerEvent};
+use std::collections::HashMap;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use zbus::export::futures_core::Stream;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+/// `Inhibit`'s session state while the session is about to end.
+const QUERY_END: u32 = 2;
+
+struct Watch {
+    connection: zbus::Connection,
+    //dropping these cancels them
+    _tasks: Vec<zbus::Task<()>>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let connection = self.connection.clone();
+        self.connection
+            .executor()
+            .spawn(
+                async move {
+                    _ = connection.close().await;
+                },
+                "power::Watch::drop",
+            )
+            .detach();
+    }
+}
+
+static WATCH: Mutex<Option<Watch>> = Mutex::new(None);
+
+fn platform(e: zbus::Error) -> PowerError {
+    PowerError::Platform(e.to_string())
+}
+
+fn report(event: PowerEvent) {
+    if crate::application::is_main_thread_running() {
+        crate::application::submit_to_main_thread("power::report".to_string(), move || {
+            crate::power::notify(event)
+        });
+    }
+}
+
+/// Watches the power saver profile, or returns `None` if the portal doesn't have a monitor.
+async fn watch_power_saver(
+    connection: &zbus::Connection,
+) -> Result<Option<zbus::Task<()>>, PowerError> {
+    let monitor = zbus::Proxy::new(
+        connection,
+        DESTINATION,
+        PATH,
+        "org.freedesktop.portal.PowerProfileMonitor",
+    )
+    .await
+    .map_err(platform)?;
+    if monitor
+        .get_property::<bool>("power-saver-enabled")
+        .await
+        .is_err()
+    {
+        return Ok(None);
+    }
+    //starts with the current value
+    let mut changes = monitor
+        .receive_property_changed::<bool>("power-saver-enabled")
+        .await;
+    let task = connection.executor().spawn(
+        async move {
+            let mut saving = false;
+            while let Some(change) = poll_fn(|cx| Pin::new(&mut changes).poll_next(cx)).await {
+                let Ok(now) = change.get().await else {
+                    continue;
+                };
+                if now != saving {
+                    saving = now;
+                    report(if now {
+                        PowerEvent::LowPowerEntered
+                    } else {
+                        PowerEvent::LowPowerExited
+                    });
+                }
+            }
+        },
+        "power::watch_power_saver",
+    );
+    Ok(Some(task))
+}
+
+/// Watches the screensaver, or returns `None` if the portal doesn't have a monitor.
+async fn watch_screensaver(
+    connection: &zbus::Connection,
+) -> Result<Option<zbus::Task<()>>, PowerError> {
+    static NEXT_TOKEN: AtomicU32 = AtomicU32::new(0);
+
+    let inhibit = zbus::Proxy::new(
+        connection,
+        DESTINATION,
+        PATH,
+        "org.freedesktop.portal.Inhibit",
+    )
+    .await
+    .map_err(platform)?;
+    //the first state may arrive before CreateMonitor returns
+    let mut states = inhibit
+        .receive_signal("StateChanged")
+        .await
+        .map_err(platform)?;
+
+    let token = format!(
+        "app_window_power_{}",
+        NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+    );
+    let sender = connection
+        .unique_name()
+        .map(|name| name.trim_start_matches(':').replace('.', "_"))
+        .ok_or_else(|| PowerError::Platform("no D-Bus unique name".to_string()))?;
+    let session = format!("{PATH}/session/{sender}/{token}");
+    let mut options: HashMap<&str, Value> = HashMap::new();
+    options.insert("handle_token", Value::from(token.as_str()));
+    options.insert("session_handle_token", Value::from(token.as_str()));
+    //no parent window: exporting a Wayland handle needs xdg-foreign
+    let reply: zbus::Result<OwnedObjectPath> = inhibit.call("CreateMonitor", &("", options)).await;
+    match reply {
+        Ok(_) => {}
+        Err(e) if is_missing(&e) => return Ok(None),
+        Err(e) => return Err(platform(e)),
+    }
+
+    let task = connection.executor().spawn(
+        async move {
+            let mut screensaver = false;
+            while let Some(message) = poll_fn(|cx| Pin::new(&mut states).poll_next(cx)).await {
+                let Ok((handle, state)) = message
+                    .body()
+                    .deserialize::<(OwnedObjectPath, HashMap<String, OwnedValue>)>()
+                else {
+                    continue;
+                };
+                if handle.as_str() != session {
+                    continue;
+                }
+                let now = state
+                    .get("screensaver-active")
+                    .and_then(|value| bool::try_from(value).ok())
+                    .unwrap_or(screensaver);
+                if now != screensaver {
+                    screensaver = now;
+                    report(if now {
+                        PowerEvent::DisplayOff
+                    } else {
+                        PowerEvent::DisplayOn
+                    });
+                }
+                //we never hold up logging out, but the portal waits to hear so
+                let session_state = state
+                    .get("session-state")
+                    .and_then(|value| u32::try_from(value).ok());
+                if session_state == Some(QUERY_END) {
+                    let _: zbus::Result<()> = inhibit.call("QueryEndResponse", &(&handle,)).await;
+                }
+            }
+        },
+        "power::watch_screensaver",
+    );
+    Ok(Some(task))
+}
+
+pub async fn watch_power() -> Result<(), PowerError> {
+    if WATCH.lock().unwrap().is_some() {
+        return Ok(());
+    }
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|_| PowerError::Unsupported)?;
+    let tasks: Vec<_> = [
+        watch_power_saver(&connection).await?,
+        watch_screensaver(&connection).await?,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if tasks.is_empty() {
+        return Err(PowerError::Unsupported);
+    }
+    let watch = Watch {
+        connection,
+        _tasks: tasks,
+    };
+    let mut current = WATCH.lock().unwrap();
+    //someone else may have started watching meanwhile, in which case ours is dropped
+    if current.is_none() {
+        *current = Some(watch);
+    }
+    Ok(())
+}
+
+/// Call on the main thread.
+pub fn unwatch_power() {
+    let watch = WATCH.lock().unwrap().take();
+    drop(watch);
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Keeps each window's buffer scale in step with the outputs it's on.
+//!
+//! In order of preference we learn the scale from `wp_fractional_scale_v1`, from
+//! `wl_surface.preferred_buffer_scale` (compositor v6), or from the largest `wl_output` scale the
+//! surface has entered.  Fractional scales need `wp_viewporter`: we render at buffer scale 1 and
+//! let the viewport map the larger buffer back down to the window's logical size.
+
+use std::sync::{Arc, Mutex};
+use wayland_client::QueueHandle;
+
+use super::App;
+use super::buffer::AllocatedBuffer;
+use super::window::WindowInternal;
+use crate::window::WindowEvent;
+
+/// `wp_fractional_scale_v1` reports scales in 120ths.
+pub(super) const FRACTIONAL_SCALE_DENOMINATOR: f64 = 120.0;
+
+impl WindowInternal {
+    /// The scale we'd like to render at, given what the compositor has told us so far.
+    fn desired_scale(&self) -> f64 {
+        let scale = self.preferred_scale.unwrap_or_else(|| {
+            let app_state = self.app_state.upgrade().expect("App state gone");
+            let outputs = app_state.outputs.lock().unwrap();
+            self.current_outputs
+                .iter()
+                .filter_map(|id| outputs.get(id))
+                .map(|info| info.scale_factor)
+                .fold(1.0, f64::max)
+        });
+        if self.viewport.is_some() {
+            scale
+        } else {
+            //wl_surface.set_buffer_scale only takes integers
+            scale.ceil()
+        }
+    }
+
+    /// Size in pixels of a buffer that covers the window at the applied scale.
+    pub fn buffer_size(&self) -> (i32, i32) {
+        let size = self.applied_size();
+        (
+            (size.width() * self.applied_scale).round() as i32,
+            (size.height() * self.applied_scale).round() as i32,
+        )
+    }
+
+    /// Tells the compositor how our buffers map to the window's logical size.
+    ///
+    /// This is pending surface state; it takes effect on the next commit.
+    pub fn apply_buffer_scale(&self) {
+        let surface = self.wl_surface.as_ref().expect("No surface");
+        if let Some(viewport) = self.viewport.as_ref() {
+            let size = self.applied_size();
+            viewport.set_destination(size.width() as i32, size.height() as i32);
+        } else {
+            surface.set_buffer_scale(self.applied_scale as i32);
+        }
+    }
+}
+
+/// Re-evaluates the window's scale and, if it changed, re-commits at the new scale.
+///
+/// Call this whenever any of the inputs to [`WindowInternal::desired_scale`] change.
+pub(super) fn update_scale(window_internal: &Arc<Mutex<WindowInternal>>, qh: &QueueHandle<App>) {
+    let mut locked = window_internal.lock().unwrap();
+    let scale = locked.desired_scale();
+    if scale == locked.applied_scale {
+        return;
+    }
+    logwise::debuginternal_sync!(
+        "Window scale changed to {scale}",
+        scale = logwise::privacy::LogIt(scale)
+    );
+    locked.applied_scale = scale;
+    locked.events.send(WindowEvent::ScaleChanged(scale));
+    //before the first configure, or while parked, there's nothing on screen; configure or
+    //unpark will apply the scale
+    if !locked.has_been_configured || locked.parked {
+        return;
+    }
+    locked.apply_buffer_scale();
+    let surface = locked.wl_surface.as_ref().expect("No surface");
+    if locked.software_present() {
+        //the placeholder buffer must match the new scale or the surface would change size
+        let (width, height) = locked.buffer_size();
+        let app_state = locked.app_state.upgrade().expect("App state gone");
+        let buffer = AllocatedBuffer::new(
+            width,
+            height,
+            &app_state.shm,
+            qh,
+            window_internal.clone(),
+            locked.placeholder.as_ref(),
+        );
+        surface.attach(Some(&buffer.buffer), 0, 0);
+        surface.damage_buffer(0, 0, width, height);
+    }
+    //our buttons are drawn for one scale
+    #[cfg(feature = "decorations")]
+    locked.attach_decor(qh);
+    surface.commit();
+    //let GPU consumers reconfigure their swapchain via size_scale
+    if let Some(f) = locked.size_update_notify.as_ref() {
+        f.0(locked.applied_size())
+    }
+}
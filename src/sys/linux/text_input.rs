@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Text and composition for [`crate::input::text`], from `zwp_text_input_v3`.
+//!
+//! Each window gets a text input for the seat.  The compositor only sends text to an enabled
+//! one, so each enables itself while its surface has focus.  Events are double-buffered: they
+//! pile up until `done`, which applies them together.
+
+use super::App;
+use crate::sys::window::WindowInternal;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::wp::text_input::zv3::client::zwp_text_input_manager_v3::ZwpTextInputManagerV3;
+use wayland_protocols::wp::text_input::zv3::client::zwp_text_input_v3::{self, ZwpTextInputV3};
+
+#[derive(Debug, Default)]
+struct Pending {
+    preedit: Option<(String, Option<Range<usize>>)>,
+    commit: Option<String>,
+}
+
+pub(super) struct TextInputData {
+    window_internal: Arc<Mutex<WindowInternal>>,
+    pending: Mutex<Pending>,
+}
+
+impl TextInputData {
+    pub(super) fn new(window_internal: Arc<Mutex<WindowInternal>>) -> Self {
+        TextInputData {
+            window_internal,
+            pending: Mutex::default(),
+        }
+    }
+
+    fn is_ours(&self, surface: &wayland_client::protocol::wl_surface::WlSurface) -> bool {
+        self.window_internal.lock().unwrap().wl_surface.as_ref() == Some(surface)
+    }
+
+    fn virtual_keyboard_hidden(&self) -> bool {
+        self.window_internal.lock().unwrap().virtual_keyboard_hidden
+    }
+}
+
+impl Dispatch<ZwpTextInputManagerV3, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpTextInputManagerV3,
+        event: <ZwpTextInputManagerV3 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ZwpTextInputManagerV3 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+    }
+}
+
+impl Dispatch<ZwpTextInputV3, TextInputData> for App {
+    fn event(
+        _state: &mut Self,
+        proxy: &ZwpTextInputV3,
+        event: <ZwpTextInputV3 as Proxy>::Event,
+        data: &TextInputData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        logwise::debuginternal_sync!(
+            "Got ZwpTextInputV3 event {event}",
+            event = logwise::privacy::LogIt(&event)
+        );
+        match event {
+            //every window's text input sees every enter.  Once enabled, the compositor may show
+            //its on-screen keyboard, unless the app hid it
+            zwp_text_input_v3::Event::Enter { surface }
+                if data.is_ours(&surface) && !data.virtual_keyboard_hidden() =>
+            {
+                proxy.enable();
+                proxy.commit();
+            }
+            zwp_text_input_v3::Event::Leave { surface } if data.is_ours(&surface) => {
+                proxy.disable();
+                proxy.commit();
+                crate::input::text::end_composition();
+            }
+            zwp_text_input_v3::Event::PreeditString {
+                text,
+                cursor_begin,
+                cursor_end,
+            } => {
+                //byte offsets, or both -1 to hide the cursor
+                let cursor = usize::try_from(cursor_begin)
+                    .ok()
+                    .zip(usize::try_from(cursor_end).ok())
+                    .map(|(begin, end)| begin..end);
+                data.pending.lock().unwrap().preedit = Some((text.unwrap_or_default(), cursor));
+            }
+            zwp_text_input_v3::Event::CommitString { text } => {
+                data.pending.lock().unwrap().commit = text;
+            }
+            zwp_text_input_v3::Event::Done { serial: _ } => {
+                let pending = std::mem::take(&mut *data.pending.lock().unwrap());
+                //per the protocol, the commit goes in before the new preedit
+                if let Some(commit) = pending.commit {
+                    crate::input::text::commit(&commit);
+                }
+                match pending.preedit {
+                    Some((text, cursor)) if !text.is_empty() => {
+                        crate::input::text::preedit(&text, cursor)
+                    }
+                    //an unset preedit is an empty one
+                    _ => crate::input::text::end_composition(),
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,389 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Tray icons, as `StatusNotifierItem`s on the session D-Bus.
+//!
+//! Each icon has its own connection, so it has its own well-known name to register with the
+//! `StatusNotifierWatcher`, and closing the connection is all it takes to remove it.  The menu
+//! is served next to the item as `com.canonical.dbusmenu`, which is what hosts expect; menu
+//! item IDs are indices into the menu plus one, as 0 is the root.
+
+use crate::tray::{TrayError, TrayEvent, TrayMenuItem, TrayState};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+/// Sizes we offer the icon at; hosts pick the nearest.
+const ICON_SIDES: [u32; 5] = [16, 22, 24, 32, 48];
+
+type Pixmap = (i32, i32, Vec<u8>);
+/// A dbusmenu layout node: ID, properties, and children as variants of further nodes.
+type Layout = (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>);
+
+fn platform(e: zbus::Error) -> TrayError {
+    TrayError::Platform(e.to_string())
+}
+
+/// Whether `e` means nobody's watching for status items.
+fn is_missing(e: &zbus::Error) -> bool {
+    match e {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.ServiceUnknown"
+                | "org.freedesktop.DBus.Error.UnknownMethod"
+                | "org.freedesktop.DBus.Error.UnknownObject"
+        ),
+        _ => false,
+    }
+}
+
+/// `value` as an [`OwnedValue`].  None of ours hold file descriptors, which is all that can fail.
+fn owned<'a>(value: impl Into<Value<'a>>) -> OwnedValue {
+    value
+        .into()
+        .try_into_owned()
+        .expect("a value without file descriptors")
+}
+
+struct Shared {
+    id: u64,
+    state: Mutex<TrayState>,
+    /// Bumped with each new menu, for `LayoutUpdated`.
+    revision: AtomicU32,
+}
+
+impl Shared {
+    fn state(&self) -> TrayState {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+/// The icon at each of [`ICON_SIDES`], as ARGB32 in network byte order.
+fn pixmaps(state: &TrayState) -> Vec<Pixmap> {
+    ICON_SIDES
+        .iter()
+        .map(|&side| {
+            let image = state.icon.square(side);
+            let argb = image
+                .rgba()
+                .chunks_exact(4)
+                .flat_map(|p| [p[3], p[0], p[1], p[2]])
+                .collect();
+            (side as i32, side as i32, argb)
+        })
+        .collect()
+}
+
+struct Item(Arc<Shared>);
+
+#[zbus::interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "ApplicationStatus"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> String {
+        format!("app_window-{}", self.0.id)
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> String {
+        self.0.state().tooltip
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> &str {
+        //we only have pixels
+        ""
+    }
+
+    #[zbus(property)]
+    fn icon_pixmap(&self) -> Vec<Pixmap> {
+        pixmaps(&self.0.state())
+    }
+
+    #[zbus(property)]
+    fn tool_tip(&self) -> (String, Vec<Pixmap>, String, String) {
+        (
+            String::new(),
+            Vec::new(),
+            self.0.state().tooltip,
+            String::new(),
+        )
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn menu(&self) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(MENU_PATH).expect("a valid path")
+    }
+
+    fn activate(&self, _x: i32, _y: i32) {
+        crate::tray::notify(self.0.id, TrayEvent::Clicked)
+    }
+
+    //hosts show the menu themselves
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: String) {}
+
+    #[zbus(signal)]
+    async fn new_title(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_icon(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_tool_tip(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+/// The properties of menu item `id`, or `None` if there's no such item.
+fn menu_properties(menu: &[TrayMenuItem], id: i32) -> Option<HashMap<String, OwnedValue>> {
+    let mut properties = HashMap::new();
+    if id == 0 {
+        properties.insert("children-display".to_string(), owned("submenu"));
+        return Some(properties);
+    }
+    match menu.get(usize::try_from(id - 1).ok()?)? {
+        TrayMenuItem::Item { label, enabled } => {
+            //dbusmenu labels take underscores as mnemonics
+            properties.insert("label".to_string(), owned(label.replace('_', "__")));
+            properties.insert("enabled".to_string(), owned(*enabled));
+        }
+        TrayMenuItem::Separator => {
+            properties.insert("type".to_string(), owned("separator"));
+        }
+    }
+    Some(properties)
+}
+
+/// `properties`, less those not in `names`.  No names means all of them.
+fn filter_properties(
+    mut properties: HashMap<String, OwnedValue>,
+    names: &[String],
+) -> HashMap<String, OwnedValue> {
+    if !names.is_empty() {
+        properties.retain(|name, _| names.contains(name));
+    }
+    properties
+}
+
+struct Menu(Arc<Shared>);
+
+#[zbus::interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    fn text_direction(&self) -> &str {
+        "ltr"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[zbus(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// The menu is flat, so only the root has children.
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        recursion_depth: i32,
+        property_names: Vec<String>,
+    ) -> zbus::fdo::Result<(u32, Layout)> {
+        let menu = self.0.state().menu;
+        let properties = menu_properties(&menu, parent_id)
+            .ok_or_else(|| zbus::fdo::Error::InvalidArgs(format!("no menu item {parent_id}")))?;
+        let children = if parent_id == 0 && recursion_depth != 0 {
+            (1..=menu.len() as i32)
+                .filter_map(|id| {
+                    let properties = menu_properties(&menu, id)?;
+                    let child: Layout = (
+                        id,
+                        filter_properties(properties, &property_names),
+                        Vec::new(),
+                    );
+                    Some(owned(child))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok((
+            self.0.revision.load(Ordering::Relaxed),
+            (
+                parent_id,
+                filter_properties(properties, &property_names),
+                children,
+            ),
+        ))
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, OwnedValue>)> {
+        let menu = self.0.state().menu;
+        let ids = if ids.is_empty() {
+            (0..=menu.len() as i32).collect()
+        } else {
+            ids
+        };
+        ids.into_iter()
+            .filter_map(|id| {
+                let properties = menu_properties(&menu, id)?;
+                Some((id, filter_properties(properties, &property_names)))
+            })
+            .collect()
+    }
+
+    fn get_property(&self, id: i32, name: String) -> zbus::fdo::Result<OwnedValue> {
+        menu_properties(&self.0.state().menu, id)
+            .and_then(|mut properties| properties.remove(&name))
+            .ok_or_else(|| zbus::fdo::Error::InvalidArgs(format!("no {name} on menu item {id}")))
+    }
+
+    fn event(&self, id: i32, event_id: String, _data: OwnedValue, _timestamp: u32) {
+        if event_id == "clicked" && id > 0 {
+            crate::tray::notify(self.0.id, TrayEvent::MenuItem(id as usize - 1))
+        }
+    }
+
+    /// Returns the IDs of no such item.
+    fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        let len = self.0.state().menu.len() as i32;
+        let mut errors = Vec::new();
+        for (id, event_id, data, timestamp) in events {
+            if (0..=len).contains(&id) {
+                self.event(id, event_id, data, timestamp);
+            } else {
+                errors.push(id);
+            }
+        }
+        errors
+    }
+
+    /// Our menu never changes just because it's shown.
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+
+    #[zbus(signal)]
+    async fn layout_updated(
+        emitter: &SignalEmitter<'_>,
+        revision: u32,
+        parent: i32,
+    ) -> zbus::Result<()>;
+}
+
+#[derive(Debug)]
+pub struct TrayIcon {
+    connection: zbus::Connection,
+    shared: Arc<Shared>,
+}
+
+impl std::fmt::Debug for Shared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Shared").field("id", &self.id).finish()
+    }
+}
+
+impl TrayIcon {
+    pub async fn new(id: u64, state: TrayState) -> Result<Self, TrayError> {
+        let shared = Arc::new(Shared {
+            id,
+            state: Mutex::new(state),
+            revision: AtomicU32::new(0),
+        });
+        let name = format!("org.kde.StatusNotifierItem-{}-{id}", std::process::id());
+        let connection = zbus::connection::Builder::session()
+            .and_then(|builder| builder.name(name.as_str()))
+            .and_then(|builder| builder.serve_at(ITEM_PATH, Item(shared.clone())))
+            .and_then(|builder| builder.serve_at(MENU_PATH, Menu(shared.clone())))
+            .map_err(platform)?
+            .build()
+            .await
+            //no session bus, no tray
+            .map_err(|_| TrayError::Unsupported)?;
+        let watcher = zbus::Proxy::new(
+            &connection,
+            "org.kde.StatusNotifierWatcher",
+            "/StatusNotifierWatcher",
+            "org.kde.StatusNotifierWatcher",
+        )
+        .await
+        .map_err(platform)?;
+        let registered: zbus::Result<()> = watcher
+            .call("RegisterStatusNotifierItem", &(name.as_str(),))
+            .await;
+        match registered {
+            Ok(()) => Ok(TrayIcon { connection, shared }),
+            Err(e) if is_missing(&e) => Err(TrayError::Unsupported),
+            Err(e) => Err(platform(e)),
+        }
+    }
+
+    pub async fn update(&self, state: TrayState) -> Result<(), TrayError> {
+        let old = std::mem::replace(&mut *self.shared.state.lock().unwrap(), state.clone());
+        let item = SignalEmitter::new(&self.connection, ITEM_PATH).map_err(platform)?;
+        if old.icon != state.icon {
+            Item::new_icon(&item).await.map_err(platform)?;
+        }
+        if old.tooltip != state.tooltip {
+            Item::new_title(&item).await.map_err(platform)?;
+            Item::new_tool_tip(&item).await.map_err(platform)?;
+        }
+        if old.menu != state.menu {
+            let revision = self.shared.revision.fetch_add(1, Ordering::Relaxed) + 1;
+            let menu = SignalEmitter::new(&self.connection, MENU_PATH).map_err(platform)?;
+            Menu::layout_updated(&menu, revision, 0)
+                .await
+                .map_err(platform)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        //the watcher notices our name go away and removes the item
+        let connection = self.connection.clone();
+        self.connection
+            .executor()
+            .spawn(
+                async move {
+                    _ = connection.close().await;
+                },
+                "TrayIcon::drop",
+            )
+            .detach();
+    }
+}
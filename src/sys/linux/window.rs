@@ -1,20 +1,91 @@
 // SPDX-License-Identifier: MPL-2.0
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::ptr::NonNull;
 use std::sync::{Arc, Mutex, Weak};
-use wayland_client::QueueHandle;
+use wayland_client::{Proxy, QueueHandle};
+use wayland_client::protocol::wl_buffer::WlBuffer;
+use wayland_client::protocol::wl_compositor::WlCompositor;
+use wayland_client::protocol::wl_pointer::WlPointer;
 use wayland_client::protocol::wl_seat::WlSeat;
+#[cfg(feature = "decorations")]
 use wayland_client::protocol::wl_subsurface::WlSubsurface;
 use wayland_client::protocol::wl_surface::WlSurface;
+use wayland_protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::WpFractionalScaleV1;
+use wayland_protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibitor_v1::ZwpKeyboardShortcutsInhibitorV1;
+use wayland_protocols::wp::pointer_constraints::zv1::client::zwp_confined_pointer_v1::ZwpConfinedPointerV1;
+use wayland_protocols::wp::pointer_constraints::zv1::client::zwp_pointer_constraints_v1::Lifetime;
+#[cfg(feature = "input")]
+use wayland_protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
+use wayland_protocols::wp::viewporter::client::wp_viewport::WpViewport;
+use wayland_protocols::xdg::decoration::zv1::client::zxdg_toplevel_decoration_v1::{
+    Mode, ZxdgToplevelDecorationV1,
+};
+use wayland_protocols::xdg::dialog::v1::client::xdg_dialog_v1::XdgDialogV1;
 use wayland_protocols::xdg::shell::client::xdg_surface::XdgSurface;
 use wayland_protocols::xdg::shell::client::xdg_toplevel::XdgToplevel;
 use wayland_protocols::xdg::shell::client::xdg_wm_base::XdgWmBase;
+use wayland_protocols::xdg::toplevel_icon::v1::client::xdg_toplevel_icon_v1::XdgToplevelIconV1;
 
+#[cfg(feature = "accessibility")]
 use super::ax::AX;
-use super::buffer::{AllocatedBuffer, create_shm_buffer_decor};
-use super::main_thread::MAIN_THREAD_INFO;
+use super::buffer::AllocatedBuffer;
+#[cfg(feature = "decorations")]
+use super::buffer::create_shm_buffer_decor;
+use super::buffer::create_shm_buffer_pixels;
+use super::main_thread::{MAIN_THREAD_INFO, MainThreadInfo, TakenInfo};
 use super::{App, AppState, Configure, FullscreenError, Surface, SurfaceEvents};
-use crate::coordinates::{Position, Size};
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::Color;
+#[cfg(feature = "accessibility")]
+use crate::window::accessibility::AccessibilityContent;
+use crate::window::{
+    BufferStatus, CanvasBehavior, CloseBehavior, CloseResponse, CreateOptions, Cursor,
+    DecorationTheme, DecorationsMode, FrameCallback, IconData, NativeHandle, PreviewImage,
+    WindowDebugState, WindowEvent, WindowEventSender, WindowSettingError, WindowState,
+    WindowStateFlags,
+};
+
+/// The callback waiting on a `wl_surface.frame`.
+pub(super) struct FrameDone(pub Mutex<Option<FrameCallback>>);
+
+/// Asks for a `wl_surface.frame` on the window's current surface.  Surfaces share this.
+pub(super) fn request_frame(internal: Arc<Mutex<WindowInternal>>, frame: FrameCallback) {
+    crate::application::submit_to_main_thread("Window::request_frame".to_string(), move || {
+        let info = TakenInfo::take();
+        let surface = internal.lock().unwrap().wl_surface.clone();
+        match surface {
+            Some(surface) => {
+                surface.frame(&info.queue_handle, FrameDone(Mutex::new(Some(frame))));
+                //the callback is only sent for a commit
+                surface.commit();
+            }
+            None => logwise::warn_sync!("Can't request a frame for a closed window"),
+        }
+        drop(info);
+    });
+}
+
+/// Tells [`Window::fullscreen`] whether the compositor made the window fullscreen.  Dropped
+/// unsent, as when the window closes first, it says no.
+#[derive(Debug)]
+pub(super) struct FullscreenWaiter(Option<r#continue::Sender<bool>>);
+
+impl FullscreenWaiter {
+    pub fn send(mut self, fullscreen: bool) {
+        if let Some(sender) = self.0.take() {
+            sender.send(fullscreen)
+        }
+    }
+}
+
+impl Drop for FullscreenWaiter {
+    fn drop(&mut self) {
+        if let Some(sender) = self.0.take() {
+            sender.send(false)
+        }
+    }
+}
 
 pub struct DebugWrapper(pub Box<dyn Fn(Size) + Send>);
 impl Debug for DebugWrapper {
@@ -28,12 +99,32 @@ pub(crate) struct Window {
     pub(super) internal: Arc<Mutex<WindowInternal>>,
 }
 
+/// A sheet that's up; see `Window::begin_sheet`.
+#[derive(Debug)]
+pub(super) struct Sheet {
+    /// Makes it modal, if the compositor supports that.
+    dialog: Option<XdgDialogV1>,
+    done: r#continue::Sender<()>,
+}
+
 #[derive(Debug)]
 pub(super) struct WindowInternal {
     pub app_state: Weak<AppState>,
+    /// Ourselves, for work that has to wait until our lock is released.
+    this: Weak<Mutex<WindowInternal>>,
     pub proposed_configure: Option<Configure>,
+    /// From the same toplevel configure as `proposed_configure`, applied with it.
+    pub proposed_state: Option<(WindowState, WindowStateFlags)>,
+    pub state: WindowState,
+    /// Size from `set_size`, used when the compositor lets us pick.
+    pub requested_size: Option<Configure>,
+    /// The last size we had while neither maximized nor fullscreen.
+    pub normal_size: Option<Configure>,
     pub applied_configure: Option<Configure>,
     pub wl_pointer_enter_serial: Option<u32>,
+    /// Serial of the button press currently held down, if any.  Needed to start drags and
+    /// show the window menu.
+    pub wl_pointer_button_serial: Option<u32>,
     pub wl_pointer_enter_surface: Option<WlSurface>,
     pub wl_pointer_pos: Option<Position>,
     pub xdg_toplevel: Option<XdgToplevel>,
@@ -41,12 +132,85 @@ pub(super) struct WindowInternal {
     pub xdg_surface: Option<XdgSurface>,
     pub drawable_buffer: Option<AllocatedBuffer>,
     pub requested_maximize: bool,
+    /// Fullscreen on this output (any, if `None`), asked for before the initial commit.
+    pub requested_fullscreen: Option<Option<u32>>,
+    /// Told by the first configure whether we got it.
+    pub fullscreen_waiter: Option<FullscreenWaiter>,
+    #[cfg(feature = "accessibility")]
     pub adapter: Option<accesskit_unix::Adapter>,
+    /// The app's accessibility tree, grafted under ours.  Lock it after the window, and never
+    /// while calling into the adapter, which holds its own lock while asking us for the
+    /// initial tree.
+    #[cfg(feature = "accessibility")]
+    pub ax_content: Arc<Mutex<AccessibilityContent>>,
     pub size_update_notify: Option<DebugWrapper>,
+    #[cfg(feature = "decorations")]
     pub decor_subsurface: Option<WlSubsurface>,
+    #[cfg(feature = "decorations")]
+    pub decor_surface: Option<WlSurface>,
+    /// Negotiates who draws the title bar, if the compositor can draw one.
+    pub decoration: Option<ZxdgToplevelDecorationV1>,
+    /// Who the app wants to draw the title bar; see `Window::set_decorations`.
+    pub decorations_wanted: DecorationsMode,
+    /// Who draws it, as agreed with the compositor.
+    pub decorations: DecorationsMode,
+    /// How we draw it, when we do.
+    pub decoration_theme: DecorationTheme,
+    #[cfg_attr(not(feature = "accessibility"), allow(dead_code))]
     pub title: String,
     pub current_outputs: HashSet<u32>,
     pub has_been_configured: bool,
+    /// Scale the compositor asked for directly, if it supports telling us.
+    pub preferred_scale: Option<f64>,
+    /// Scale our buffers are currently committed at; see `scale.rs`.
+    pub applied_scale: f64,
+    pub viewport: Option<WpViewport>,
+    pub fractional_scale: Option<WpFractionalScaleV1>,
+    pub close_behavior: CloseBehavior,
+    /// We unmapped the surface; the next configure must attach a buffer to map it again.
+    pub hidden: bool,
+    /// Between wl_keyboard enter and leave for our surface.
+    pub focused: bool,
+    /// What a splash window draws in its buffer.
+    pub placeholder: Option<PreviewImage>,
+    /// Waiting in the window pool.  Configures are acked, but nothing is attached until
+    /// `unpark`, so the surface stays unmapped.
+    pub parked: bool,
+    /// Present while the app asked for system shortcuts; destroying it gives them back.
+    pub shortcuts_inhibitor: Option<ZwpKeyboardShortcutsInhibitorV1>,
+    /// Our pointer on the seat, if it has one.
+    pub wl_pointer: Option<WlPointer>,
+    /// Present while the pointer is confined; destroying it releases the pointer.
+    pub confined_pointer: Option<ZwpConfinedPointerV1>,
+    /// Our text input on the seat, if the compositor offers one.
+    #[cfg(feature = "input")]
+    pub text_input: Option<ZwpTextInputV3>,
+    /// The app hid the on-screen keyboard, so the text input stays disabled; see
+    /// `Window::set_virtual_keyboard_visible`.
+    #[cfg(feature = "input")]
+    pub virtual_keyboard_hidden: bool,
+    pub events: WindowEventSender,
+    /// Shown over the client area; see `Window::set_cursor`.
+    #[cfg_attr(not(feature = "cursor-theme"), allow(dead_code))]
+    pub cursor: Cursor,
+    /// We took the cursor away for [`Cursor::Hidden`], so the next visible one must be set even
+    /// if it's the theme's current one.
+    #[cfg(feature = "cursor-theme")]
+    pub cursor_hidden: bool,
+    /// Set while this window is up as a sheet.
+    pub sheet: Option<Sheet>,
+    /// How close to an edge a press resizes; see `Window::set_resize_border`.
+    pub resize_border: f64,
+    /// The icon we set, and the buffer holding its pixels, which must outlive it.
+    pub icon: Option<(XdgToplevelIconV1, WlBuffer)>,
+    /// False pins our size; see `WindowInternal::apply_size_limits`.
+    pub resizable: bool,
+    /// Smallest and largest sizes the app allows.
+    pub size_limits: (Option<Size>, Option<Size>),
+    /// The toplevel we belong to, restored when a sheet ends; see `Window::set_parent`.
+    pub parent: Option<XdgToplevel>,
+    /// Input passes through us; see `WindowInternal::apply_input_region`.
+    pub click_through: bool,
 }
 
 impl WindowInternal {
@@ -55,59 +219,231 @@ impl WindowInternal {
         size: Size,
         title: String,
         queue_handle: &QueueHandle<App>,
-        ax: bool,
     ) -> Arc<Mutex<Self>> {
-        let window_internal = Arc::new(Mutex::new(WindowInternal {
-            title: title.clone(),
-            app_state: Arc::downgrade(app_state),
-            proposed_configure: None,
-            //in case we are asked for size prior to configure?
-            applied_configure: Some(Configure {
-                width: size.width() as i32,
-                height: size.height() as i32,
-            }),
-            wl_pointer_enter_serial: None,
-            wl_pointer_enter_surface: None,
-            wl_pointer_pos: None,
-            xdg_toplevel: None,
-            wl_surface: None,
-            requested_maximize: false,
-            drawable_buffer: None,
-            adapter: None,
-            size_update_notify: None,
-            decor_subsurface: None,
-            xdg_surface: None,
-            current_outputs: HashSet::new(),
-            has_been_configured: false,
-        }));
-        if ax {
-            let _aximpl = AX::new(size, title.clone(), window_internal.clone());
+        let window_internal = Arc::new_cyclic(|this| {
+            Mutex::new(WindowInternal {
+                this: this.clone(),
+                title: title.clone(),
+                app_state: Arc::downgrade(app_state),
+                proposed_configure: None,
+                proposed_state: None,
+                state: WindowState::Normal,
+                //the size we were made with, unless the compositor says otherwise
+                requested_size: Some(Configure {
+                    width: size.width() as i32,
+                    height: size.height() as i32,
+                }),
+                normal_size: None,
+                //in case we are asked for size prior to configure?
+                applied_configure: Some(Configure {
+                    width: size.width() as i32,
+                    height: size.height() as i32,
+                }),
+                wl_pointer_enter_serial: None,
+                wl_pointer_button_serial: None,
+                wl_pointer_enter_surface: None,
+                wl_pointer_pos: None,
+                xdg_toplevel: None,
+                wl_surface: None,
+                requested_maximize: false,
+                requested_fullscreen: None,
+                fullscreen_waiter: None,
+                drawable_buffer: None,
+                #[cfg(feature = "accessibility")]
+                adapter: None,
+                #[cfg(feature = "accessibility")]
+                ax_content: Arc::new(Mutex::new(AccessibilityContent::new(
+                    super::ax::CONTENT_TREE,
+                ))),
+                size_update_notify: None,
+                #[cfg(feature = "decorations")]
+                decor_subsurface: None,
+                #[cfg(feature = "decorations")]
+                decor_surface: None,
+                decoration: None,
+                decorations_wanted: DecorationsMode::Native,
+                decorations: unnegotiated_decorations(DecorationsMode::Native),
+                decoration_theme: DecorationTheme::default(),
+                xdg_surface: None,
+                current_outputs: HashSet::new(),
+                preferred_scale: None,
+                applied_scale: 1.0,
+                viewport: None,
+                fractional_scale: None,
+                close_behavior: CloseBehavior::Destroy,
+                hidden: false,
+                focused: false,
+                placeholder: None,
+                parked: false,
+                shortcuts_inhibitor: None,
+                wl_pointer: None,
+                confined_pointer: None,
+                #[cfg(feature = "input")]
+                text_input: None,
+                #[cfg(feature = "input")]
+                virtual_keyboard_hidden: false,
+                events: WindowEventSender::default(),
+                cursor: Cursor::Arrow,
+                #[cfg(feature = "cursor-theme")]
+                cursor_hidden: false,
+                sheet: None,
+                resize_border: crate::window::DEFAULT_RESIZE_BORDER,
+                icon: None,
+                resizable: true,
+                size_limits: (None, None),
+                parent: None,
+                click_through: false,
+                has_been_configured: false,
+            })
+        });
+        app_state
+            .windows
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&window_internal));
+        #[cfg(feature = "accessibility")]
+        {
+            let content = window_internal.lock().unwrap().ax_content.clone();
+            let _aximpl = AX::new(size, title.clone(), window_internal.clone(), content);
             let adapter = Some(accesskit_unix::Adapter::new(
                 _aximpl.clone(),
                 _aximpl.clone(),
                 _aximpl.clone(),
             ));
+            window_internal.lock().unwrap().adapter = adapter;
+        }
+        if cfg!(feature = "software-present") {
             let buffer = AllocatedBuffer::new(
                 size.width() as i32,
                 size.height() as i32,
                 &app_state.shm,
                 queue_handle,
                 window_internal.clone(),
+                None,
             );
             window_internal.lock().unwrap().drawable_buffer = Some(buffer);
-            window_internal.lock().unwrap().adapter = adapter;
         }
         window_internal
     }
 
+    /// Resizes to `configure`: moves the decor, tells observers and attaches a new buffer if we
+    /// draw one.  The caller commits.
+    pub fn apply_size(
+        &mut self,
+        configure: Configure,
+        queue_handle: &QueueHandle<App>,
+        internal: &Arc<Mutex<WindowInternal>>,
+    ) {
+        let app_state = self.app_state.upgrade().expect("App state gone");
+        #[cfg(feature = "decorations")]
+        self.decor_subsurface
+            .as_ref()
+            .unwrap()
+            .set_position(configure.width - self.decor_width(), 0);
+        let resized = self.applied_configure.as_ref() != Some(&configure);
+        self.applied_configure = Some(configure);
+        #[cfg(feature = "accessibility")]
+        self.update_accessibility_root();
+        if let Some(f) = self.size_update_notify.as_ref() {
+            f.0(self.applied_size())
+        }
+        if resized {
+            self.events.send(WindowEvent::Resized(self.applied_size()));
+        }
+
+        self.apply_buffer_scale();
+        //rebuild main buffer; without software-present the app presents its own
+        if self.software_present() {
+            let (width, height) = self.buffer_size();
+            let buffer = AllocatedBuffer::new(
+                width,
+                height,
+                &app_state.shm,
+                queue_handle,
+                internal.clone(),
+                self.placeholder.as_ref(),
+            );
+            //attach to surface
+            self.wl_surface
+                .as_ref()
+                .expect("No surface")
+                .attach(Some(&buffer.buffer), 0, 0);
+        }
+    }
+
+    /// Whether we draw the window's buffer ourselves, rather than the app.
+    pub fn software_present(&self) -> bool {
+        cfg!(feature = "software-present") || self.placeholder.is_some()
+    }
+
     pub fn applied_size(&self) -> Size {
         let applied = self.applied_configure.clone().expect("No configure event");
         Size::new(applied.width as f64, applied.height as f64)
     }
 
-    pub fn close_window(&self) {
+    /// Converts a pointer position on the surface the pointer entered into main surface
+    /// coordinates.
+    pub fn pointer_position(&self, surface_x: f64, surface_y: f64) -> Position {
+        #[cfg(feature = "decorations")]
+        if self.wl_pointer_enter_surface != self.wl_surface {
+            //we're in the decor; slide by decor dimensions
+            let surface_dimensions = self
+                .applied_configure
+                .clone()
+                .expect("No surface dimensions");
+            return Position::new(
+                surface_x + surface_dimensions.width as f64 - self.decor_width() as f64,
+                surface_y,
+            );
+        }
+        Position::new(surface_x, surface_y)
+    }
+
+    /// How wide our decor is, in logical pixels.
+    #[cfg(feature = "decorations")]
+    fn decor_width(&self) -> i32 {
+        (self.decoration_theme.button_width() * 3.0) as i32
+    }
+
+    /// Draws our decor at the applied scale if we draw the title bar, or takes it down if we
+    /// don't.  A synchronized subsurface, so this shows with the window's next commit.
+    #[cfg(feature = "decorations")]
+    pub fn attach_decor(&self, queue_handle: &QueueHandle<App>) {
+        let (Some(surface), Some(app_state), Some(this)) = (
+            self.decor_surface.as_ref(),
+            self.app_state.upgrade(),
+            self.this.upgrade(),
+        ) else {
+            return;
+        };
+        if self.decorations == DecorationsMode::ClientSide {
+            //set_buffer_scale only takes integers, and drawing sharper beats drawing blurry
+            let scale = self.applied_scale.ceil() as u32;
+            //the last buffer was destroyed when the compositor let it go
+            let buffer = create_shm_buffer_decor(
+                &app_state.shm,
+                queue_handle,
+                this,
+                &self.decoration_theme,
+                scale,
+            );
+            surface.set_buffer_scale(scale as i32);
+            surface.attach(Some(&buffer.buffer), 0, 0);
+            surface.damage_buffer(0, 0, buffer.width, buffer.height);
+        } else {
+            surface.attach(None, 0, 0);
+        }
+        surface.commit();
+    }
+
+    pub fn close_window(&mut self) {
+        self.end_sheet();
         // Only destroy xdg objects if we received a configure event.
         // Destroying an unconfigured xdg_surface is a protocol error in Weston.
+        //the decoration has to go before its toplevel
+        if let Some(d) = self.decoration.take() {
+            d.destroy()
+        }
         if self.has_been_configured {
             if let Some(e) = self.xdg_toplevel.as_ref() {
                 e.destroy()
@@ -116,11 +452,141 @@ impl WindowInternal {
                 s.destroy()
             }
         }
+        if let Some(i) = self.shortcuts_inhibitor.as_ref() {
+            i.destroy()
+        }
+        if let Some(c) = self.confined_pointer.as_ref() {
+            c.destroy()
+        }
+        self.forget_icon();
+        #[cfg(feature = "input")]
+        if let Some(t) = self.text_input.as_ref() {
+            t.destroy()
+        }
+        if let Some(f) = self.fractional_scale.as_ref() {
+            f.destroy()
+        }
+        if let Some(v) = self.viewport.as_ref() {
+            v.destroy()
+        }
         if let Some(s) = self.wl_surface.as_ref() {
             s.destroy()
         }
     }
 
+    /// The user asked to close the window, via our decor or the compositor.
+    pub fn request_close(&mut self) {
+        //our decor is clicked on the input thread, if there is one; closing is the main thread's
+        if !super::main_thread::is_main_thread() {
+            let this = self.this.clone();
+            super::main_thread::on_main_thread(move || {
+                if let Some(this) = this.upgrade() {
+                    this.lock().unwrap().request_close();
+                }
+            });
+            return;
+        }
+        self.events.send(WindowEvent::CloseRequested);
+        match &self.close_behavior {
+            CloseBehavior::Destroy => self.close_window(),
+            CloseBehavior::HideToTray => self.set_visible(false),
+            CloseBehavior::Custom(f) => {
+                //we're inside a dispatcher holding our own lock; run it after we return
+                let f = f.clone();
+                super::main_thread::on_main_thread(move || f());
+            }
+            CloseBehavior::Ask(f) => {
+                let f = f.clone();
+                let this = self.this.clone();
+                super::main_thread::on_main_thread(move || {
+                    if f() == CloseResponse::Close
+                        && let Some(this) = this.upgrade()
+                    {
+                        this.lock().unwrap().close_window();
+                    }
+                });
+            }
+        }
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        if visible != self.hidden {
+            return;
+        }
+        let surface = self.wl_surface.as_ref().expect("No surface");
+        if visible {
+            //like the initial commit: no buffer, and the compositor replies with a configure
+            surface.commit();
+        } else {
+            //attaching a null buffer unmaps the toplevel
+            surface.attach(None, 0, 0);
+            surface.commit();
+            self.hidden = true;
+            self.end_sheet();
+        }
+    }
+
+    /// Ends our sheet, if we're up as one: we're no longer modal or attached to the parent.
+    /// Destroys the icon we set, which the toplevel keeps.
+    pub fn forget_icon(&mut self) {
+        if let Some((icon, buffer)) = self.icon.take() {
+            icon.destroy();
+            buffer.destroy();
+        }
+    }
+
+    pub fn end_sheet(&mut self) {
+        let Some(sheet) = self.sheet.take() else {
+            return;
+        };
+        if let Some(dialog) = sheet.dialog {
+            dialog.destroy();
+        }
+        if let Some(toplevel) = self.xdg_toplevel.as_ref() {
+            toplevel.set_parent(self.parent.as_ref());
+        }
+        sheet.done.send(());
+    }
+
+    /// Gives our surfaces an empty input region if we're click-through, so input goes to
+    /// whatever is below, or the default whole-surface one.  Takes effect on the next commit.
+    pub fn apply_input_region(&self, compositor: &WlCompositor, queue_handle: &QueueHandle<App>) {
+        let empty = self
+            .click_through
+            .then(|| compositor.create_region(queue_handle, ()));
+        if let Some(surface) = self.wl_surface.as_ref() {
+            surface.set_input_region(empty.as_ref());
+        }
+        //a synchronized subsurface's state waits for ours
+        #[cfg(feature = "decorations")]
+        if let Some(surface) = self.decor_surface.as_ref() {
+            surface.set_input_region(empty.as_ref());
+            surface.commit();
+        }
+        if let Some(region) = empty {
+            region.destroy();
+        }
+    }
+
+    /// Tells the compositor our size limits, pinned to our size if we aren't resizable.  They
+    /// take effect on the next commit.
+    pub fn apply_size_limits(&self) {
+        let Some(toplevel) = self.xdg_toplevel.as_ref() else {
+            return;
+        };
+        let (min, max) = if self.resizable {
+            self.size_limits
+        } else {
+            let size = self.applied_size();
+            (Some(size), Some(size))
+        };
+        //zero means no limit
+        let (min_width, min_height) = min.map_or((0, 0), |s| (s.width() as i32, s.height() as i32));
+        let (max_width, max_height) = max.map_or((0, 0), |s| (s.width() as i32, s.height() as i32));
+        toplevel.set_min_size(min_width, min_height);
+        toplevel.set_max_size(max_width, max_height);
+    }
+
     pub fn maximize(&mut self) {
         if self.requested_maximize {
             self.requested_maximize = false;
@@ -137,6 +603,276 @@ impl WindowInternal {
         let toplevel = self.xdg_toplevel.as_ref().unwrap();
         toplevel.set_minimized();
     }
+
+    /// What's under `position`, given who draws the title bar.
+    pub fn mouse_region(&self, position: Position) -> crate::window::MouseRegion {
+        let titlebar =
+            (self.decorations == DecorationsMode::ClientSide).then_some(&self.decoration_theme);
+        //the compositor's own borders resize windows it decorates
+        let border = if self.decorations == DecorationsMode::Native {
+            0.0
+        } else {
+            self.resize_border
+        };
+        super::cursor::mouse_region(self.applied_size(), position, border, titlebar)
+    }
+
+    /// Asks the compositor for the decorations the app wants.  Without the decoration protocol
+    /// there's nothing to ask, and we decide for ourselves.
+    pub fn request_decorations(&mut self, queue_handle: &QueueHandle<App>) {
+        match self.decoration.as_ref() {
+            Some(decoration) => decoration.set_mode(match self.decorations_wanted {
+                DecorationsMode::Native => Mode::ServerSide,
+                _ => Mode::ClientSide,
+            }),
+            None => {
+                let decorations = unnegotiated_decorations(self.decorations_wanted);
+                self.apply_decorations(decorations, queue_handle);
+            }
+        }
+    }
+
+    /// Shows or hides our decor for the mode the compositor agreed to.  The caller commits.
+    pub fn configure_decorations(&mut self, mode: Mode, queue_handle: &QueueHandle<App>) {
+        let decorations = match mode {
+            Mode::ServerSide => DecorationsMode::Native,
+            //the compositor won't, so we fall back to ours
+            _ => unnegotiated_decorations(self.decorations_wanted),
+        };
+        self.apply_decorations(decorations, queue_handle);
+    }
+
+    #[cfg_attr(not(feature = "decorations"), allow(unused_variables))]
+    fn apply_decorations(&mut self, decorations: DecorationsMode, queue_handle: &QueueHandle<App>) {
+        if decorations == self.decorations {
+            return;
+        }
+        self.decorations = decorations;
+        #[cfg(feature = "decorations")]
+        self.attach_decor(queue_handle);
+        #[cfg(feature = "accessibility")]
+        if self.applied_configure.is_some() {
+            self.update_accessibility_root();
+        }
+    }
+
+    /// Sends our part of the accessibility tree again, after the window changed.
+    #[cfg(feature = "accessibility")]
+    fn update_accessibility_root(&mut self) {
+        let title = self.title.clone();
+        let applied_size = self.applied_size();
+        let buttons = (self.decorations == DecorationsMode::ClientSide)
+            .then(|| self.decoration_theme.clone());
+        let content = self.ax_content.lock().unwrap().is_published();
+        if let Some(a) = self.adapter.as_mut() {
+            a.update_if_active(|| {
+                super::ax::build_tree_update(title, applied_size, buttons.as_ref(), content)
+            })
+        }
+    }
+
+    /// Asks the compositor for its window menu at `position` (surface-local).
+    ///
+    /// The compositor only honors this during a button press, so it's a no-op otherwise.
+    pub fn show_window_menu(&self, position: Position) {
+        let Some(serial) = self.wl_pointer_button_serial else {
+            logwise::debuginternal_sync!("show_window_menu called without a button press");
+            return;
+        };
+        let app_state = self.app_state.upgrade().unwrap();
+        let seat = app_state.seat.lock().unwrap();
+        let Some(seat) = seat.as_ref() else {
+            return;
+        };
+        self.xdg_toplevel.as_ref().unwrap().show_window_menu(
+            seat,
+            serial,
+            position.x() as i32,
+            position.y() as i32,
+        );
+    }
+}
+
+/// Creates the window's Wayland objects, from its surface to its seat devices.
+///
+/// A hidden window (see [`WindowInternal::set_visible`]) skips the initial commit, so it stays
+/// unmapped until shown.
+#[cfg_attr(not(feature = "decorations"), allow(unused_variables))]
+fn build(info: &MainThreadInfo, window_internal: &Arc<Mutex<WindowInternal>>, size: Size) {
+    // Support xdg_wm_base versions 5-6
+    // Version 5 is used by Weston headless in CI
+    // Version 6 is used by most modern compositors
+    let xdg_wm_base: XdgWmBase = info.globals.bind(&info.queue_handle, 5..=6, ()).unwrap();
+
+    let surface = info.app_state.compositor.create_surface(
+        &info.queue_handle,
+        SurfaceEvents::Standard(window_internal.clone()),
+    );
+
+    #[cfg(feature = "decorations")]
+    {
+        let decor_surface = info
+            .app_state
+            .compositor
+            .create_surface(&info.queue_handle, SurfaceEvents::Decor);
+        let decor_subsurface =
+            info.subcompositor
+                .get_subsurface(&decor_surface, &surface, &info.queue_handle, ());
+        let mut locked = window_internal.lock().unwrap();
+        decor_subsurface.set_position(size.width() as i32 - locked.decor_width(), 0);
+        locked.decor_subsurface.replace(decor_subsurface);
+        locked.decor_surface.replace(decor_surface);
+        //hidden again if the compositor turns out to draw the title bar
+        locked.attach_decor(&info.queue_handle);
+    }
+    window_internal
+        .lock()
+        .unwrap()
+        .wl_surface
+        .replace(surface.clone());
+    if let (Some(manager), Some(viewporter)) = (
+        info.fractional_scale_manager.as_ref(),
+        info.viewporter.as_ref(),
+    ) {
+        let fractional_scale =
+            manager.get_fractional_scale(&surface, &info.queue_handle, window_internal.clone());
+        let viewport = viewporter.get_viewport(&surface, &info.queue_handle, ());
+        let mut locked = window_internal.lock().unwrap();
+        locked.fractional_scale = Some(fractional_scale);
+        locked.viewport = Some(viewport);
+    }
+
+    // Create a toplevel surface
+    let xdg_surface =
+        xdg_wm_base.get_xdg_surface(&surface, &info.queue_handle, window_internal.clone());
+    let xdg_toplevel = xdg_surface.get_toplevel(&info.queue_handle, window_internal.clone());
+    window_internal
+        .lock()
+        .unwrap()
+        .xdg_surface
+        .replace(xdg_surface);
+
+    let decoration = info.decoration_manager.as_ref().map(|manager| {
+        manager.get_toplevel_decoration(&xdg_toplevel, &info.queue_handle, window_internal.clone())
+    });
+    {
+        let mut locked = window_internal.lock().unwrap();
+        locked.xdg_toplevel.replace(xdg_toplevel);
+        locked.decoration = decoration;
+        //before the initial commit, so the first configure says who draws
+        locked.request_decorations(&info.queue_handle);
+        if let Some(name) = locked.requested_fullscreen {
+            //names don't survive a reconnect, so then it's wherever the compositor likes
+            let output = name.and_then(|name| {
+                let outputs = info.app_state.outputs.lock().unwrap();
+                outputs.get(&name).and_then(|info| info.output.clone())
+            });
+            let toplevel = locked.xdg_toplevel.as_ref().unwrap();
+            toplevel.set_fullscreen(output.as_ref());
+        }
+        locked.apply_size_limits();
+        if locked.click_through {
+            locked.apply_input_region(&info.app_state.compositor, &info.queue_handle);
+        }
+    }
+
+    // Initial commit without buffer to trigger configure event.
+    // Per xdg-shell protocol, we must wait for configure before attaching a buffer.
+    // The configure handler in dispatchers.rs will attach the buffer.
+    if !window_internal.lock().unwrap().hidden {
+        surface.commit();
+    }
+
+    // Seat (input devices) may not be available in headless environments
+    let seat_result: Result<WlSeat, _> = info.globals.bind(&info.input_queue_handle, 8..=9, ());
+    if let Ok(seat) = seat_result {
+        info.app_state.seat.lock().unwrap().replace(seat.clone());
+        let pointer = seat.get_pointer(&info.input_queue_handle, window_internal.clone());
+        window_internal.lock().unwrap().wl_pointer = Some(pointer);
+        let _keyboard = seat.get_keyboard(&info.input_queue_handle, window_internal.clone());
+        #[cfg(feature = "input")]
+        if let Some(manager) = info.text_input_manager.as_ref() {
+            let text_input = manager.get_text_input(
+                &seat,
+                &info.input_queue_handle,
+                super::text_input::TextInputData::new(window_internal.clone()),
+            );
+            window_internal.lock().unwrap().text_input = Some(text_input);
+        }
+    }
+}
+
+/// Recreates a window on a new connection, after the compositor restarted.
+///
+/// The old objects went with the old connection, so there's nothing to destroy; we forget
+/// them, keep the app's settings, and build again.
+pub(super) fn rebuild(info: &MainThreadInfo, window_internal: &Arc<Mutex<WindowInternal>>) {
+    let size = {
+        let mut locked = window_internal.lock().unwrap();
+        //the parent relationship went with the connection
+        if let Some(sheet) = locked.sheet.take() {
+            sheet.done.send(());
+        }
+        locked.parent = None;
+        locked.app_state = Arc::downgrade(&info.app_state);
+        //come back at the size we had, if the compositor lets us pick
+        locked.requested_size = locked.applied_configure.clone();
+        locked.proposed_configure = None;
+        locked.has_been_configured = false;
+        locked.xdg_toplevel = None;
+        locked.xdg_surface = None;
+        locked.wl_surface = None;
+        #[cfg(feature = "decorations")]
+        {
+            locked.decor_subsurface = None;
+            locked.decor_surface = None;
+        }
+        locked.decoration = None;
+        //the new compositor may not draw title bars
+        locked.decorations = unnegotiated_decorations(locked.decorations_wanted);
+        locked.viewport = None;
+        locked.fractional_scale = None;
+        locked.drawable_buffer = None;
+        locked.current_outputs.clear();
+        locked.preferred_scale = None;
+        locked.wl_pointer = None;
+        locked.wl_pointer_enter_serial = None;
+        locked.wl_pointer_button_serial = None;
+        locked.wl_pointer_enter_surface = None;
+        locked.wl_pointer_pos = None;
+        locked.shortcuts_inhibitor = None;
+        locked.confined_pointer = None;
+        #[cfg(feature = "input")]
+        {
+            locked.text_input = None;
+        }
+        #[cfg(feature = "cursor-theme")]
+        {
+            locked.cursor_hidden = false;
+        }
+        locked.focused = false;
+        locked.applied_size()
+    };
+    info.app_state
+        .windows
+        .lock()
+        .unwrap()
+        .push(Arc::downgrade(window_internal));
+    build(info, window_internal, size);
+    let locked = window_internal.lock().unwrap();
+    if locked.requested_maximize {
+        locked.xdg_toplevel.as_ref().unwrap().set_maximized();
+    }
+    locked.events.send(WindowEvent::Reconnected);
+}
+
+/// Who draws the title bar when the compositor won't: us, if we can and the app wants one.
+fn unnegotiated_decorations(wanted: DecorationsMode) -> DecorationsMode {
+    if cfg!(feature = "decorations") && wanted != DecorationsMode::None {
+        DecorationsMode::ClientSide
+    } else {
+        DecorationsMode::None
+    }
 }
 
 unsafe impl Send for Window {}
@@ -144,99 +880,52 @@ unsafe impl Sync for Window {}
 
 impl Window {
     pub async fn new(_position: Position, size: Size, title: String) -> Self {
+        Self::create(size, title, false, true, DecorationsMode::Native, None).await
+    }
+
+    pub async fn new_with_options(
+        _position: Position,
+        size: Size,
+        title: String,
+        options: CreateOptions,
+    ) -> Self {
+        let decorations = if options.decorated {
+            DecorationsMode::Native
+        } else {
+            DecorationsMode::None
+        };
+        Self::create(size, title, false, options.visible, decorations, None).await
+    }
+
+    async fn create(
+        size: Size,
+        title: String,
+        parked: bool,
+        visible: bool,
+        decorations: DecorationsMode,
+        fullscreen: Option<(Option<u32>, FullscreenWaiter)>,
+    ) -> Self {
         let window_internal =
             crate::application::on_main_thread("Window::new".to_string(), move || {
-                let info = MAIN_THREAD_INFO.take().expect("Main thread info not set");
-
-                // Support xdg_wm_base versions 5-6
-                // Version 5 is used by Weston headless in CI
-                // Version 6 is used by most modern compositors
-                let xdg_wm_base: XdgWmBase =
-                    info.globals.bind(&info.queue_handle, 5..=6, ()).unwrap();
+                let info = TakenInfo::take();
                 let window_internal =
-                    WindowInternal::new(&info.app_state, size, title, &info.queue_handle, true);
-
-                let surface = info.app_state.compositor.create_surface(
-                    &info.queue_handle,
-                    SurfaceEvents::Standard(window_internal.clone()),
-                );
-
-                let decor_surface = info
-                    .app_state
-                    .compositor
-                    .create_surface(&info.queue_handle, SurfaceEvents::Decor);
-                let decor_subsurface = info.subcompositor.get_subsurface(
-                    &decor_surface,
-                    &surface,
-                    &info.queue_handle,
-                    (),
-                );
-                let decor_buffer = create_shm_buffer_decor(
-                    &info.app_state.shm,
-                    &info.queue_handle,
-                    window_internal.clone(),
-                );
-                decor_surface.attach(Some(&decor_buffer.buffer), 0, 0);
-                decor_surface.commit();
-                decor_subsurface.set_position(
-                    size.width() as i32 - info.app_state.decor_dimensions.0 as i32,
-                    0,
-                );
-                window_internal
-                    .lock()
-                    .unwrap()
-                    .decor_subsurface
-                    .replace(decor_subsurface);
-                window_internal
-                    .lock()
-                    .unwrap()
-                    .wl_surface
-                    .replace(surface.clone());
-
-                // Create a toplevel surface
-                let xdg_surface = xdg_wm_base.get_xdg_surface(
-                    &surface,
-                    &info.queue_handle,
-                    window_internal.clone(),
-                );
-                let xdg_toplevel =
-                    xdg_surface.get_toplevel(&info.queue_handle, window_internal.clone());
-                window_internal
-                    .lock()
-                    .unwrap()
-                    .xdg_surface
-                    .replace(xdg_surface);
-
-                window_internal
-                    .lock()
-                    .unwrap()
-                    .xdg_toplevel
-                    .replace(xdg_toplevel);
-
-                // Initial commit without buffer to trigger configure event.
-                // Per xdg-shell protocol, we must wait for configure before attaching a buffer.
-                // The configure handler in dispatchers.rs will attach the buffer.
-                surface.commit();
-
-                // Seat (input devices) may not be available in headless environments
-                let seat_result: Result<WlSeat, _> =
-                    info.globals.bind(&info.queue_handle, 8..=9, ());
-                if let Ok(seat) = seat_result {
-                    window_internal
-                        .lock()
-                        .unwrap()
-                        .app_state
-                        .upgrade()
-                        .unwrap()
-                        .seat
-                        .lock()
-                        .unwrap()
-                        .replace(seat.clone());
-                    let _pointer = seat.get_pointer(&info.queue_handle, window_internal.clone());
-                    let _keyboard = seat.get_keyboard(&info.queue_handle, window_internal.clone());
+                    WindowInternal::new(&info.app_state, size, title, &info.queue_handle);
+                //before the initial commit, so the first configure sees them
+                {
+                    let mut locked = window_internal.lock().unwrap();
+                    locked.parked = parked;
+                    //skips the initial commit, so it stays unmapped until shown
+                    locked.hidden = !visible;
+                    locked.decorations_wanted = decorations;
+                    //so the decor is never attached
+                    locked.decorations = unnegotiated_decorations(decorations);
+                    if let Some((output, waiter)) = fullscreen {
+                        locked.requested_fullscreen = Some(output);
+                        locked.fullscreen_waiter = Some(waiter);
+                    }
                 }
-
-                MAIN_THREAD_INFO.replace(Some(info));
+                build(&info, &window_internal, size);
+                drop(info);
                 window_internal
             })
             .await;
@@ -246,6 +935,100 @@ impl Window {
         }
     }
 
+    pub async fn new_splash(size: Size, image: PreviewImage) -> Option<Self> {
+        let window = Window::new(Position::new(0.0, 0.0), size, String::new()).await;
+        window.set_splash_image(image).await;
+        Some(window)
+    }
+
+    pub async fn new_overlay(size: Size, image: PreviewImage) -> Option<Self> {
+        //xdg-shell has no way to keep a window on top; the compositor places it
+        let window = Window::new(Position::new(0.0, 0.0), size, "Diagnostics".to_string()).await;
+        window.set_splash_image(image).await;
+        Some(window)
+    }
+
+    pub async fn set_splash_image(&self, image: PreviewImage) {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::set_splash_image".to_string(), move || {
+            let info = TakenInfo::take();
+            let mut locked = internal.lock().unwrap();
+            locked.placeholder = Some(image);
+            //otherwise the first configure draws it
+            if locked.has_been_configured {
+                let (width, height) = locked.buffer_size();
+                let app_state = locked.app_state.upgrade().expect("App state gone");
+                let buffer = AllocatedBuffer::new(
+                    width,
+                    height,
+                    &app_state.shm,
+                    &info.queue_handle,
+                    internal.clone(),
+                    locked.placeholder.as_ref(),
+                );
+                let surface = locked.wl_surface.as_ref().expect("No surface");
+                surface.attach(Some(&buffer.buffer), 0, 0);
+                surface.damage_buffer(0, 0, width, height);
+                surface.commit();
+            }
+            drop(locked);
+            drop(info);
+        })
+        .await;
+    }
+
+    pub async fn set_background(&self, color: Color) -> Result<(), WindowSettingError> {
+        //a one-pixel placeholder, stretched over the window
+        let [red, green, blue] = color.to_rgb8();
+        let image = PreviewImage::new(1, 1, vec![red, green, blue, 0xFF])?;
+        self.set_splash_image(image).await;
+        Ok(())
+    }
+
+    pub async fn fade_out(self) {
+        //Wayland has no window opacity; just close
+    }
+
+    pub async fn new_parked(_position: Position, size: Size, title: String) -> Option<Self> {
+        Some(Self::create(size, title, true, true, DecorationsMode::Native, None).await)
+    }
+
+    pub async fn unpark(&self, title: String) {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::unpark".to_string(), move || {
+            let info = TakenInfo::take();
+            let mut locked = internal.lock().unwrap();
+            locked.parked = false;
+            locked.title = title;
+            //otherwise the first configure maps it as usual
+            if locked.has_been_configured {
+                locked.apply_buffer_scale();
+                let surface = locked.wl_surface.as_ref().expect("No surface");
+                if locked.software_present() {
+                    let (width, height) = locked.buffer_size();
+                    let app_state = locked.app_state.upgrade().expect("App state gone");
+                    let buffer = AllocatedBuffer::new(
+                        width,
+                        height,
+                        &app_state.shm,
+                        &info.queue_handle,
+                        internal.clone(),
+                        locked.placeholder.as_ref(),
+                    );
+                    surface.attach(Some(&buffer.buffer), 0, 0);
+                    surface.damage_buffer(0, 0, width, height);
+                }
+                surface.commit();
+                if let Some(f) = locked.size_update_notify.as_ref() {
+                    f.0(locked.applied_size())
+                }
+            }
+            drop(locked);
+            drop(info);
+        })
+        .await;
+    }
+
     pub async fn default() -> Self {
         Window::new(
             Position::new(0.0, 0.0),
@@ -255,26 +1038,45 @@ impl Window {
         .await
     }
 
-    pub async fn fullscreen(title: String) -> Result<Self, FullscreenError> {
-        let w = Self::new(Position::new(0.0, 0.0), Size::new(800.0, 600.0), title).await;
-        w.internal
-            .lock()
-            .unwrap()
-            .xdg_toplevel
-            .as_ref()
-            .expect("No xdg_toplevel")
-            .set_fullscreen(None);
-        Ok(w)
+    pub async fn fullscreen(title: String, output: Option<u32>) -> Result<Self, FullscreenError> {
+        if let Some(name) = output {
+            let known =
+                crate::application::on_main_thread("Window::fullscreen".to_string(), move || {
+                    let info = TakenInfo::take();
+                    let known = info
+                        .app_state
+                        .outputs
+                        .lock()
+                        .unwrap()
+                        .get(&name)
+                        .is_some_and(|info| info.output.is_some());
+                    drop(info);
+                    known
+                })
+                .await;
+            if !known {
+                return Err(FullscreenError::NoSuchOutput(name));
+            }
+        }
+        let (sender, fullscreen) = r#continue::continuation();
+        let request = (output, FullscreenWaiter(Some(sender)));
+        let window = Self::create(
+            Size::new(800.0, 600.0),
+            title,
+            false,
+            true,
+            DecorationsMode::Native,
+            Some(request),
+        )
+        .await;
+        if fullscreen.await {
+            Ok(window)
+        } else {
+            Err(FullscreenError::Declined)
+        }
     }
 
-    pub async fn surface(&self) -> crate::surface::Surface {
-        let display = crate::application::on_main_thread("surface".to_string(), || {
-            let info = MAIN_THREAD_INFO.take().expect("Main thread info not set");
-            let display = info.connection.display();
-            MAIN_THREAD_INFO.replace(Some(info));
-            display
-        })
-        .await;
+    pub async fn surface(&self) -> Surface {
         let surface = self
             .internal
             .lock()
@@ -283,12 +1085,616 @@ impl Window {
             .as_ref()
             .expect("No surface")
             .clone();
-        crate::surface::Surface {
-            sys: Surface {
-                wl_display: display,
-                wl_surface: surface,
-                window_internal: self.internal.clone(),
+        Surface {
+            wl_surface: surface,
+            window_internal: self.internal.clone(),
+        }
+    }
+
+    pub async fn set_visible(&self, visible: bool) {
+        self.internal.lock().unwrap().set_visible(visible);
+    }
+
+    pub async fn set_canvas_behavior(&self, _behavior: CanvasBehavior) {
+        //only browsers have defaults to suppress
+    }
+
+    pub async fn set_close_behavior(&self, close_behavior: CloseBehavior) {
+        self.internal.lock().unwrap().close_behavior = close_behavior;
+    }
+
+    pub async fn show_window_menu(&self, position: Position) {
+        self.internal.lock().unwrap().show_window_menu(position);
+    }
+
+    pub async fn set_size(&self, size: Size) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::set_size".to_string(), move || {
+            let info = TakenInfo::take();
+            let mut locked = internal.lock().unwrap();
+            let configure = Configure {
+                width: size.width() as i32,
+                height: size.height() as i32,
+            };
+            locked.requested_size = Some(configure.clone());
+            //before the first configure, or while hidden or parked, the next configure applies it
+            if locked.has_been_configured && !locked.hidden && !locked.parked {
+                locked.apply_size(configure, &info.queue_handle, &internal);
+                locked.wl_surface.as_ref().expect("No surface").commit();
+            }
+            drop(locked);
+            drop(info);
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_position(&self, _position: Position) -> Result<(), WindowSettingError> {
+        //xdg-shell doesn't let clients place their toplevels
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        _visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        //xdg-shell leaves workspaces entirely to the compositor
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_skip_taskbar(&self, _skip: bool) -> Result<(), WindowSettingError> {
+        //likewise the taskbar
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn inhibit_system_shortcuts(&self, inhibit: bool) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread(
+            "Window::inhibit_system_shortcuts".to_string(),
+            move || {
+                let info = TakenInfo::take();
+                let mut locked = internal.lock().unwrap();
+                let result = if !inhibit {
+                    if let Some(inhibitor) = locked.shortcuts_inhibitor.take() {
+                        inhibitor.destroy();
+                    }
+                    Ok(())
+                } else if locked.shortcuts_inhibitor.is_some() {
+                    Ok(())
+                } else if let Some(manager) = info.shortcuts_inhibit_manager.as_ref() {
+                    let app_state = locked.app_state.upgrade().expect("App state gone");
+                    let seat = app_state.seat.lock().unwrap().clone();
+                    match seat {
+                        Some(seat) => {
+                            let surface = locked.wl_surface.as_ref().expect("No surface");
+                            let inhibitor =
+                                manager.inhibit_shortcuts(surface, &seat, &info.queue_handle, ());
+                            locked.shortcuts_inhibitor = Some(inhibitor);
+                            Ok(())
+                        }
+                        None => Err(WindowSettingError::Platform("no seat".to_string())),
+                    }
+                } else {
+                    Err(WindowSettingError::Unsupported)
+                };
+                drop(locked);
+                drop(info);
+                result
+            },
+        )
+        .await
+    }
+
+    pub async fn confine_pointer(&self, region: Option<Rect>) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::confine_pointer".to_string(), move || {
+            let info = TakenInfo::take();
+            let mut locked = internal.lock().unwrap();
+            let result = match (info.pointer_constraints.as_ref(), locked.wl_pointer.clone()) {
+                (None, _) => Err(WindowSettingError::Unsupported),
+                (Some(_), None) => Err(WindowSettingError::Platform("no pointer".to_string())),
+                (Some(constraints), Some(pointer)) => {
+                    //a surface can only have one constraint per pointer
+                    if let Some(confined) = locked.confined_pointer.take() {
+                        confined.destroy();
+                    }
+                    let surface = locked.wl_surface.as_ref().expect("No surface");
+                    //the compositor copies the region, so it can go right away
+                    let wl_region = region.map(|region| {
+                        let app_state = locked.app_state.upgrade().expect("App state gone");
+                        let wl_region = app_state.compositor.create_region(&info.queue_handle, ());
+                        wl_region.add(
+                            region.origin().x() as i32,
+                            region.origin().y() as i32,
+                            region.size().width() as i32,
+                            region.size().height() as i32,
+                        );
+                        wl_region
+                    });
+                    let confined = constraints.confine_pointer(
+                        surface,
+                        &pointer,
+                        wl_region.as_ref(),
+                        Lifetime::Persistent,
+                        &info.queue_handle,
+                        (),
+                    );
+                    if let Some(wl_region) = wl_region {
+                        wl_region.destroy();
+                    }
+                    locked.confined_pointer = Some(confined);
+                    Ok(())
+                }
+            };
+            drop(locked);
+            drop(info);
+            result
+        })
+        .await
+    }
+
+    pub async fn release_pointer(&self) -> Result<(), WindowSettingError> {
+        if let Some(confined) = self.internal.lock().unwrap().confined_pointer.take() {
+            confined.destroy();
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "cursor-theme")]
+    pub async fn set_cursor(&self, cursor: Cursor) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::set_cursor".to_string(), move || {
+            let mut locked = internal.lock().unwrap();
+            locked.cursor = cursor;
+            //otherwise nothing changes until the pointer moves
+            if let (Some(pointer), Some(position), Some(_)) = (
+                locked.wl_pointer.clone(),
+                locked.wl_pointer_pos,
+                locked.wl_pointer_enter_serial,
+            ) {
+                locked.update_cursor(&pointer, position);
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "cursor-theme"))]
+    pub async fn set_cursor(&self, _cursor: Cursor) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_decorations(&self, mode: DecorationsMode) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::set_decorations".to_string(), move || {
+            let info = TakenInfo::take();
+            let mut locked = internal.lock().unwrap();
+            let result = match mode {
+                DecorationsMode::Native if locked.decoration.is_none() => {
+                    Err(WindowSettingError::Unsupported)
+                }
+                DecorationsMode::ClientSide if !cfg!(feature = "decorations") => {
+                    Err(WindowSettingError::Unsupported)
+                }
+                _ => {
+                    locked.decorations_wanted = mode;
+                    locked.request_decorations(&info.queue_handle);
+                    //without the protocol nothing else commits our decor
+                    if locked.decoration.is_none()
+                        && let Some(surface) = locked.wl_surface.as_ref()
+                    {
+                        surface.commit();
+                    }
+                    Ok(())
+                }
+            };
+            drop(locked);
+            drop(info);
+            result
+        })
+        .await
+    }
+
+    pub async fn set_decoration_theme(
+        &self,
+        theme: DecorationTheme,
+    ) -> Result<(), WindowSettingError> {
+        if !cfg!(feature = "decorations") {
+            return Err(WindowSettingError::Unsupported);
+        }
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::set_decoration_theme".to_string(), move || {
+            let info = TakenInfo::take();
+            let mut locked = internal.lock().unwrap();
+            locked.decoration_theme = theme;
+            if locked.has_been_configured {
+                #[cfg(feature = "decorations")]
+                {
+                    let width = locked.applied_size().width() as i32;
+                    let decor_width = locked.decor_width();
+                    if let Some(subsurface) = locked.decor_subsurface.as_ref() {
+                        subsurface.set_position(width - decor_width, 0);
+                    }
+                    locked.attach_decor(&info.queue_handle);
+                }
+                #[cfg(feature = "accessibility")]
+                locked.update_accessibility_root();
+                if !locked.hidden
+                    && let Some(surface) = locked.wl_surface.as_ref()
+                {
+                    surface.commit();
+                }
+            }
+            drop(locked);
+            drop(info);
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_resize_border(&self, width: f64) -> Result<(), WindowSettingError> {
+        self.internal.lock().unwrap().resize_border = width;
+        Ok(())
+    }
+
+    /// Runs `f` on the locked window, then commits if we're mapped, so the state it sets takes
+    /// effect.  Hidden windows pick it up when shown.
+    async fn set_committed_state(
+        &self,
+        label: &str,
+        f: impl FnOnce(&mut WindowInternal) + Send + 'static,
+    ) {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread(label.to_string(), move || {
+            let mut locked = internal.lock().unwrap();
+            f(&mut locked);
+            if !locked.hidden
+                && let Some(surface) = locked.wl_surface.as_ref()
+            {
+                surface.commit();
+            }
+        })
+        .await
+    }
+
+    pub async fn set_resizable(&self, resizable: bool) -> Result<(), WindowSettingError> {
+        self.set_committed_state("Window::set_resizable", move |locked| {
+            locked.resizable = resizable;
+            locked.resize_border = if resizable {
+                crate::window::DEFAULT_RESIZE_BORDER
+            } else {
+                0.0
+            };
+            locked.apply_size_limits();
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_size_limits(
+        &self,
+        min: Option<Size>,
+        max: Option<Size>,
+    ) -> Result<(), WindowSettingError> {
+        self.set_committed_state("Window::set_size_limits", move |locked| {
+            locked.size_limits = (min, max);
+            locked.apply_size_limits();
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_click_through(&self, click_through: bool) -> Result<(), WindowSettingError> {
+        self.set_committed_state("Window::set_click_through", move |locked| {
+            locked.click_through = click_through;
+            MAIN_THREAD_INFO.with_borrow(|info| {
+                let info = info.as_ref().expect("Main thread info not set");
+                locked.apply_input_region(&info.app_state.compositor, &info.queue_handle);
+            });
+        })
+        .await;
+        Ok(())
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn update_accessibility_tree(
+        &self,
+        update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        let mut locked = self.internal.lock().unwrap();
+        let (update, first) = {
+            let mut content = locked.ax_content.lock().unwrap();
+            let first = !content.is_published();
+            (content.apply(update)?, first)
+        };
+        //the subtree needs somewhere to go first
+        if first {
+            locked.update_accessibility_root();
+        }
+        if let Some(a) = locked.adapter.as_mut() {
+            a.update_if_active(|| update);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn set_accessibility_action_handler(
+        &self,
+        on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> Result<(), WindowSettingError> {
+        let locked = self.internal.lock().unwrap();
+        locked
+            .ax_content
+            .lock()
+            .unwrap()
+            .set_action_handler(on_action);
+        Ok(())
+    }
+
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::focus".to_string(), move || {
+            MAIN_THREAD_INFO.with_borrow(|info| {
+                let info = info.as_ref().expect("Main thread info not set");
+                super::activation::request_focus(info, &internal.lock().unwrap())
+            })
+        })
+        .await
+    }
+
+    pub async fn is_focused(&self) -> bool {
+        self.internal.lock().unwrap().focused
+    }
+
+    pub async fn set_always_on_top(&self, _on_top: bool) -> Result<(), WindowSettingError> {
+        //xdg-shell leaves stacking to the compositor
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_maximized(&self, maximized: bool) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::set_maximized".to_string(), move || {
+            let mut locked = internal.lock().unwrap();
+            if locked.requested_maximize != maximized {
+                locked.maximize();
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_transparent(&self, _transparent: bool) -> Result<(), WindowSettingError> {
+        //surfaces are always composited with their alpha
+        Ok(())
+    }
+
+    pub async fn set_parent(&self, parent: &Window) -> Result<(), WindowSettingError> {
+        let parent = parent.internal.clone();
+        let child = self.internal.clone();
+        crate::application::on_main_thread("Window::set_parent".to_string(), move || {
+            let parent_toplevel = parent.lock().unwrap().xdg_toplevel.clone();
+            let mut locked = child.lock().unwrap();
+            //a sheet's parent is set until it ends, which restores this one
+            if locked.sheet.is_none()
+                && let Some(toplevel) = locked.xdg_toplevel.as_ref()
+            {
+                toplevel.set_parent(parent_toplevel.as_ref());
+            }
+            locked.parent = parent_toplevel;
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn begin_sheet(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        self.start_sheet(sheet).await.await;
+        Ok(())
+    }
+
+    pub async fn show_modal(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        //nobody waits for it to be dismissed
+        drop(self.start_sheet(sheet).await);
+        Ok(())
+    }
+
+    /// Puts `sheet` up, returning what finishes once it's dismissed.
+    async fn start_sheet(&self, sheet: &Window) -> r#continue::Future<()> {
+        let parent = self.internal.clone();
+        let child = sheet.internal.clone();
+        let (sender, fut) = r#continue::continuation();
+        crate::application::on_main_thread("Window::begin_sheet".to_string(), move || {
+            let info = TakenInfo::take();
+            let parent_toplevel = parent.lock().unwrap().xdg_toplevel.clone();
+            let mut locked = child.lock().unwrap();
+            //already up over something else
+            locked.end_sheet();
+            let toplevel = locked.xdg_toplevel.as_ref().expect("No xdg_toplevel");
+            toplevel.set_parent(parent_toplevel.as_ref());
+            let dialog = info.dialog_manager.as_ref().map(|manager| {
+                let dialog = manager.get_xdg_dialog(toplevel, &info.queue_handle, ());
+                dialog.set_modal();
+                dialog
+            });
+            locked.sheet = Some(Sheet {
+                dialog,
+                done: sender,
+            });
+            locked.set_visible(true);
+            drop(info);
+        })
+        .await;
+        fut
+    }
+
+    #[cfg(feature = "input")]
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread(
+            "Window::set_virtual_keyboard_visible".to_string(),
+            move || {
+                let mut locked = internal.lock().unwrap();
+                let Some(text_input) = locked.text_input.clone() else {
+                    return Err(WindowSettingError::Unsupported);
+                };
+                locked.virtual_keyboard_hidden = !visible;
+                //otherwise it's applied when focus comes back
+                if locked.focused {
+                    if visible {
+                        text_input.enable();
+                    } else {
+                        text_input.disable();
+                        crate::input::text::end_composition();
+                    }
+                    text_input.commit();
+                }
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    #[cfg(not(feature = "input"))]
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        _visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_preview_image(
+        &self,
+        _image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        //task switchers are part of the compositor, which captures our buffers itself
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_icon(&self, icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        let internal = self.internal.clone();
+        crate::application::on_main_thread("Window::set_icon".to_string(), move || {
+            let info = TakenInfo::take();
+            let result = (|| {
+                //compositors without it read the icon from the app's .desktop file
+                let manager = info
+                    .toplevel_icon_manager
+                    .as_ref()
+                    .ok_or(WindowSettingError::Unsupported)?;
+                let mut locked = internal.lock().unwrap();
+                let toplevel = locked.xdg_toplevel.clone().expect("No xdg_toplevel");
+                let surface = locked.wl_surface.clone().expect("No wl_surface");
+                let new_icon = icon.map(|icon| {
+                    //icons must be square
+                    let side = icon.width().max(icon.height());
+                    let pixels = icon.square(side);
+                    let buffer = create_shm_buffer_pixels(
+                        &info.app_state.shm,
+                        &info.queue_handle,
+                        internal.clone(),
+                        side as i32,
+                        side as i32,
+                        &crate::drag::premultiplied_bgra(pixels.rgba()),
+                    );
+                    let toplevel_icon = manager.create_icon(&info.queue_handle, ());
+                    toplevel_icon.add_buffer(&buffer.buffer, 1);
+                    (toplevel_icon, buffer.buffer)
+                });
+                manager.set_icon(&toplevel, new_icon.as_ref().map(|(icon, _)| icon));
+                //takes effect on the next commit
+                surface.commit();
+                locked.forget_icon();
+                locked.icon = new_icon;
+                Ok(())
+            })();
+            drop(info);
+            result
+        })
+        .await
+    }
+
+    pub async fn set_badge(&self, _label: Option<String>) -> Result<(), WindowSettingError> {
+        //desktops that badge icons do it through the launcher, not the window
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_progress(&self, _progress: Option<f64>) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    /// The surface's protocol id, which input reports.
+    pub async fn input_handle(&self) -> Option<usize> {
+        let internal = self.internal.lock().unwrap();
+        let surface = internal.wl_surface.as_ref()?;
+        Some(surface.id().protocol_id() as usize)
+    }
+
+    pub async fn native_handle(&self) -> Option<NativeHandle> {
+        let internal = self.internal.lock().unwrap();
+        let surface = internal.wl_surface.as_ref()?;
+        let toplevel = internal.xdg_toplevel.as_ref()?;
+        let display = surface.backend().upgrade()?.display_id();
+        Some(NativeHandle::Wayland {
+            display: NonNull::new(display.as_ptr().cast())?,
+            wl_surface: NonNull::new(surface.id().as_ptr().cast())?,
+            xdg_toplevel: NonNull::new(toplevel.id().as_ptr().cast())?,
+        })
+    }
+
+    pub fn event_sender(&self) -> WindowEventSender {
+        self.internal.lock().unwrap().events.clone()
+    }
+
+    pub async fn normal_bounds(&self) -> Option<(Option<Position>, Size)> {
+        //Wayland doesn't say where we are, and the compositor places the restored window
+        let internal = self.internal.lock().unwrap();
+        let size = internal
+            .normal_size
+            .as_ref()
+            .or(internal.requested_size.as_ref())?;
+        Some((None, Size::new(size.width as f64, size.height as f64)))
+    }
+
+    pub async fn outer_position(&self) -> Option<Position> {
+        None
+    }
+
+    pub fn request_frame(&self, frame: FrameCallback) {
+        request_frame(self.internal.clone(), frame)
+    }
+
+    pub async fn scale_factor(&self) -> f64 {
+        self.internal.lock().unwrap().applied_scale
+    }
+
+    pub async fn debug_state(&self) -> WindowDebugState {
+        let internal = self.internal.lock().unwrap();
+        let size = |c: &Configure| Size::new(c.width as f64, c.height as f64);
+        let mut outputs: Vec<u32> = internal.current_outputs.iter().copied().collect();
+        outputs.sort_unstable();
+        let buffer = match &internal.drawable_buffer {
+            Some(buffer) => BufferStatus::Held {
+                width: buffer.width as u32,
+                height: buffer.height as u32,
             },
+            None => BufferStatus::NotHeld,
+        };
+        WindowDebugState {
+            backend: "wayland",
+            applied_size: internal.applied_configure.as_ref().map(size),
+            proposed_size: internal.proposed_configure.as_ref().map(size),
+            scale: Some(internal.applied_scale),
+            preferred_scale: internal.preferred_scale,
+            outputs,
+            focused: Some(internal.focused),
+            visible: Some(!internal.hidden),
+            configured: Some(internal.has_been_configured),
+            decorations: Some(internal.decorations),
+            buffer: Some(buffer),
+            //without a theme the compositor picks
+            cursor: cfg!(feature = "cursor-theme").then_some(internal.cursor),
+            state: Some(internal.state),
         }
     }
 }
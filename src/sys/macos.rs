@@ -1,19 +1,44 @@
 // SPDX-License-Identifier: MPL-2.0
+//! Apple platforms.  The Swift package exports the same symbols on macOS (AppKit) and iOS (UIKit).
 
 #![allow(non_snake_case)]
 
 use std::error::Error;
 
-use crate::coordinates::{Position, Size};
-use r#continue::Sender;
-use raw_window_handle::{
-    AppKitDisplayHandle, AppKitWindowHandle, RawDisplayHandle, RawWindowHandle,
+use crate::application::time::{Duration, Instant};
+use crate::application::{
+    ActivationPolicy, ActivationPolicyError, CapturePermission, PerformanceHintCapabilities,
+    PerformanceHintError, PerformanceHints,
+};
+use crate::audio::{AudioHintCapabilities, AudioHintError, AudioHints, HintSupport};
+use crate::clipboard::ClipboardError;
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::{Color, PickColorError};
+use crate::drag::{
+    DragAction, DragActions, DragData, DragError, DragImage, DragOutcome, DragReporter,
 };
+use crate::notification::{Notification, NotificationError};
+use crate::power::{PowerError, PowerEvent};
+use crate::tray::{TrayError, TrayState};
+#[cfg(target_os = "macos")]
+use crate::tray::{TrayEvent, TrayMenuItem};
+use crate::window::{
+    CanvasBehavior, CloseBehavior, CloseResponse, CreateOptions, Cursor, DecorationTheme,
+    DecorationsMode, FrameCallback, FrameRequester, FrameTiming, IconData, NativeHandle,
+    PreviewImage, WindowDebugState, WindowEventSender, WindowSettingError, WindowState,
+};
+use r#continue::Sender;
+#[cfg(target_os = "macos")]
+use raw_window_handle::{AppKitDisplayHandle, AppKitWindowHandle};
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+#[cfg(target_os = "ios")]
+use raw_window_handle::{UiKitDisplayHandle, UiKitWindowHandle};
+use std::cell::Cell;
 use std::ffi::c_void;
 use std::fmt::{Debug, Display, Formatter};
 use std::ptr::NonNull;
-use std::sync::{Arc, Weak};
-use swift_rs::{SRString, SwiftRet, swift};
+use std::sync::{Arc, Mutex, Weak};
+use swift_rs::{SRData, SRString, SwiftRet, swift};
 
 #[derive(Debug)]
 pub struct FullscreenError;
@@ -33,7 +58,101 @@ swift!(fn SwiftAppWindow_WindowFree(window: *mut c_void)  -> ());
 swift!(fn SwiftAppWindow_WindowNewFullscreen(title: SRString)  -> *mut c_void);
 swift!(fn SwiftAppWindow_WindowSurface(ctx: *mut c_void, window: *mut c_void, ret: *mut c_void)  -> ());
 swift!(fn SwiftAppWindow_OnMainThread(ctx: *mut c_void, c_fn: *mut c_void)  -> ());
+swift!(fn SwiftAppWindow_OnMainThreadAfter(seconds: f64, ctx: *mut c_void, c_fn: *mut c_void)  -> ());
 swift!(fn SwiftAppWindow_StopMainThread()  -> ());
+swift!(fn SwiftAppWindow_WindowShowWindowMenu(window: *mut c_void, x: f64, y: f64) -> ());
+swift!(fn SwiftAppWindow_SetBackgroundAudio(enabled: bool) -> ());
+swift!(fn SwiftAppWindow_PasteboardChangeCount() -> isize);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_SetPerformanceActivity(user_initiated: bool, latency_critical: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_SetPreferDiscreteGPU(prefer: bool) -> ());
+swift!(fn SwiftAppWindow_WindowSetVisible(window: *mut c_void, visible: bool) -> ());
+swift!(fn SwiftAppWindow_WindowRequestFrame(window: *mut c_void, ctx: *mut c_void, call: *mut c_void) -> ());
+swift!(fn SwiftAppWindow_WindowSetCloseBehavior(window: *mut c_void, behavior: isize, ctx: *mut c_void, call: *mut c_void, free: *mut c_void) -> ());
+#[cfg(all(target_os = "macos", feature = "accessibility"))]
+mod ax;
+//swift! can't take attributes, and this one needs them all
+#[allow(clippy::too_many_arguments)]
+mod drag_ffi {
+    use std::ffi::c_void;
+    use swift_rs::{SRData, SRString, swift};
+    swift!(pub(super) fn SwiftAppWindow_WindowStartDrag(ctx: *mut c_void, window: *mut c_void, mime: SRString, payload: &SRData, image: &SRData, image_width: isize, end_ctx: *mut c_void, operations: *mut c_void, ended: *mut c_void, ret: *mut c_void) -> ());
+}
+use drag_ffi::SwiftAppWindow_WindowStartDrag;
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_PickColor(ctx: *mut c_void, ret: *mut c_void) -> ());
+swift!(fn SwiftAppWindow_PostNotification(ctx: *mut c_void, title: SRString, body: SRString, rgba: &SRData, width: isize, ret: *mut c_void) -> ());
+swift!(fn SwiftAppWindow_WatchPowerMain(callback: *mut c_void) -> ());
+swift!(fn SwiftAppWindow_UnwatchPowerMain() -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowNewSplash(width: f64, height: f64, rgba: &SRData, image_width: isize) -> *mut c_void);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowNewOverlay(width: f64, height: f64, rgba: &SRData, image_width: isize) -> *mut c_void);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetSplashImage(window: *mut c_void, rgba: &SRData, image_width: isize) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowNewWithOptions( x: f64, y: f64, width: f64, height: f64, title: SRString, decorated: bool, visible: bool)  -> *mut c_void);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetResizable(window: *mut c_void, resizable: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetSizeLimits(window: *mut c_void, min_width: f64, min_height: f64, max_width: f64, max_height: f64) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetClickThrough(window: *mut c_void, click_through: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowFocus(window: *mut c_void) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetAlwaysOnTop(window: *mut c_void, on_top: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetMaximized(window: *mut c_void, maximized: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetTransparent(window: *mut c_void, transparent: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetBackground(window: *mut c_void, red: f64, green: f64, blue: f64) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetParent(window: *mut c_void, parent: *mut c_void) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowNewParked( x: f64, y: f64, width: f64, height: f64, title: SRString)  -> *mut c_void);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowUnpark(window: *mut c_void, title: SRString) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowFadeOut(ctx: *mut c_void, window: *mut c_void, ret: *mut c_void) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetAllSpaces(window: *mut c_void, all_spaces: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetEvents(window: *mut c_void, ctx: *mut c_void, call: *mut c_void, free: *mut c_void) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetSize(window: *mut c_void, width: f64, height: f64) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetPosition(window: *mut c_void, x: f64, y: f64) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetSkipTaskbar(window: *mut c_void, skip: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetCursor(window: *mut c_void, kind: isize) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowInhibitShortcuts(window: *mut c_void, inhibit: bool) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowSetPreviewImage(window: *mut c_void, rgba: &SRData, width: isize) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_SetApplicationIcon(rgba: &SRData, width: isize) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_SetBadgeLabel(label: SRString) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_SetActivationPolicyMain(policy: isize) -> bool);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_TrayNewMain(id: u64, callback: *mut c_void) -> *mut c_void);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_TrayUpdateMain(tray: *mut c_void, rgba: &SRData, width: isize, tooltip: SRString, menu: SRString) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_TrayFreeMain(tray: *mut c_void) -> ());
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowBeginSheet(ctx: *mut c_void, window: *mut c_void, sheet: *mut c_void, ret: *mut c_void) -> ());
+#[cfg(target_os = "ios")]
+swift!(fn SwiftAppWindow_WindowSetVirtualKeyboardVisibleMain(window: *mut c_void, visible: bool) -> bool);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_CapturePreflight() -> bool);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_CaptureRequest() -> bool);
 
 #[repr(C)]
 struct SwiftSizeScale {
@@ -44,13 +163,37 @@ struct SwiftSizeScale {
 impl SwiftRet for SwiftSizeScale {}
 swift!(fn SwiftAppWindow_SurfaceSizeMain(surface: *mut c_void) -> SwiftSizeScale);
 
+#[repr(C)]
+struct SwiftDebugState {
+    width: f64,
+    height: f64,
+    scale: f64,
+    focused: bool,
+    visible: bool,
+    decorated: bool,
+    /// 0 normal, 1 zoomed, 2 miniaturized, 3 fullscreen.
+    state: u8,
+    /// The screen's `CGDirectDisplayID`, or 0 if it's offscreen or unknown.
+    display: u32,
+}
+impl SwiftRet for SwiftDebugState {}
+swift!(fn SwiftAppWindow_WindowDebugStateMain(window: *mut c_void) -> SwiftDebugState);
+swift!(fn SwiftAppWindow_WindowInputHandleMain(window: *mut c_void) -> *mut c_void);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowOuterPositionMain(window: *mut c_void, x: *mut f64, y: *mut f64) -> bool);
+#[cfg(target_os = "macos")]
+swift!(fn SwiftAppWindow_WindowNormalBoundsMain(window: *mut c_void, x: *mut f64, y: *mut f64, width: *mut f64, height: *mut f64) -> bool);
+
 pub fn is_main_thread() -> bool {
     unsafe { SwiftAppWindowIsMainThread() }
 }
 
-pub fn run_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
+pub fn run_main_thread<F: FnOnce() + Send + 'static>(
+    closure: F,
+) -> Result<(), crate::application::MainError> {
     std::thread::spawn(closure);
-    unsafe { SwiftAppWindowRunMainThread() }
+    unsafe { SwiftAppWindowRunMainThread() };
+    Ok(())
 }
 
 pub fn stop_main_thread() {
@@ -61,6 +204,99 @@ pub async fn alert(message: String) {
     todo!("alert not yet implemented for macOS: {}", message)
 }
 
+pub fn audio_hint_capabilities() -> AudioHintCapabilities {
+    AudioHintCapabilities {
+        //macOS doesn't duck other apps' audio; on iOS the session category decides, and we
+        //only choose between playing in the background or not
+        avoid_ducking: if cfg!(target_os = "ios") {
+            HintSupport::Unsupported
+        } else {
+            HintSupport::NotNeeded
+        },
+        background_audio: HintSupport::Supported,
+    }
+}
+
+pub async fn set_audio_hints(hints: AudioHints) -> Result<(), AudioHintError> {
+    crate::application::on_main_thread("set_audio_hints".to_string(), move || unsafe {
+        SwiftAppWindow_SetBackgroundAudio(hints.wants_background_audio())
+    })
+    .await;
+    Ok(())
+}
+
+pub fn performance_hint_capabilities() -> PerformanceHintCapabilities {
+    //iOS decides for itself, with thermal state and Low Power Mode
+    let support = if cfg!(target_os = "macos") {
+        HintSupport::Supported
+    } else {
+        HintSupport::Unsupported
+    };
+    PerformanceHintCapabilities {
+        prefer_discrete_gpu: support,
+        high_process_priority: support,
+        disable_power_throttling: support,
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub async fn set_performance_hints(hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    crate::application::on_main_thread("set_performance_hints".to_string(), move || unsafe {
+        SwiftAppWindow_SetPerformanceActivity(
+            hints.wants_high_process_priority() || hints.wants_disable_power_throttling(),
+            hints.wants_disable_power_throttling(),
+        );
+        SwiftAppWindow_SetPreferDiscreteGPU(hints.wants_prefer_discrete_gpu());
+    })
+    .await;
+    Ok(())
+}
+
+#[cfg(target_os = "ios")]
+pub async fn set_performance_hints(_hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    Ok(())
+}
+
+/// How often we look at the pasteboard while someone watches it.  Neither AppKit nor UIKit
+/// notify about changes made by other apps.
+const PASTEBOARD_POLL: Duration = Duration::from_millis(500);
+
+thread_local! {
+    /// The pasteboard's change count when we last looked, while anyone is watching.
+    static PASTEBOARD_WATCH: Cell<Option<isize>> = const { Cell::new(None) };
+    /// Whether a poll is scheduled, so watching again before it runs doesn't start another.
+    static PASTEBOARD_POLLING: Cell<bool> = const { Cell::new(false) };
+}
+
+fn poll_pasteboard() {
+    let Some(last) = PASTEBOARD_WATCH.get() else {
+        PASTEBOARD_POLLING.set(false);
+        return;
+    };
+    let count = unsafe { SwiftAppWindow_PasteboardChangeCount() };
+    if count != last {
+        PASTEBOARD_WATCH.set(Some(count));
+        crate::clipboard::notify();
+    }
+    on_main_thread_after(PASTEBOARD_POLL, poll_pasteboard);
+}
+
+/// Call on the main thread.
+pub fn watch_clipboard() -> Result<(), ClipboardError> {
+    if PASTEBOARD_WATCH.get().is_none() {
+        PASTEBOARD_WATCH.set(Some(unsafe { SwiftAppWindow_PasteboardChangeCount() }));
+    }
+    if !PASTEBOARD_POLLING.replace(true) {
+        on_main_thread_after(PASTEBOARD_POLL, poll_pasteboard);
+    }
+    Ok(())
+}
+
+/// Call on the main thread.
+pub fn unwatch_clipboard() {
+    PASTEBOARD_WATCH.set(None);
+}
+
 extern "C" fn on_main_thread_callback<F: FnOnce()>(ctx: *mut MainThreadClosure<F>) {
     let b: MainThreadClosure<F> = *unsafe { Box::from_raw(ctx) };
     (b.closure)();
@@ -72,6 +308,18 @@ pub fn on_main_thread<F: FnOnce()>(closure: F) {
     unsafe { SwiftAppWindow_OnMainThread(boxed_ptr, on_main_thread_callback::<F> as *mut c_void) }
 }
 
+pub fn on_main_thread_after<F: FnOnce() + Send + 'static>(delay: std::time::Duration, closure: F) {
+    let context = MainThreadClosure { closure };
+    let boxed_ptr = Box::into_raw(Box::new(context)) as *mut c_void;
+    unsafe {
+        SwiftAppWindow_OnMainThreadAfter(
+            delay.as_secs_f64(),
+            boxed_ptr,
+            on_main_thread_callback::<F> as *mut c_void,
+        )
+    }
+}
+
 struct MainThreadClosure<F> {
     closure: F,
 }
@@ -96,14 +344,322 @@ extern "C" fn recv_size(
     c.send((s, scale_factor));
 }
 
+/// `until_target` is how long until the frame is displayed, and `interval` the display's
+/// refresh interval, both in seconds.
+extern "C" fn recv_frame(ctx: *mut FrameCallback, until_target: f64, interval: f64) {
+    let frame = *unsafe { Box::from_raw(ctx) };
+    let now = Instant::now();
+    let target = now + Duration::from_secs_f64(until_target.max(0.0));
+    let interval = (interval > 0.0).then(|| Duration::from_secs_f64(interval));
+    frame(FrameTiming::new(now, Some(target), interval));
+}
+
+extern "C" fn recv_start_drag(ctx: *mut Sender<Result<(), DragError>>, result: i32) {
+    let c: Sender<Result<(), DragError>> = *unsafe { Box::from_raw(ctx) };
+    //see StartDragResult in Drag.swift
+    c.send(match result {
+        0 => Ok(()),
+        1 => Err(DragError::NoPointerGrab),
+        2 => Err(DragError::Unsupported),
+        other => Err(DragError::Platform(format!("unknown drag result {other}"))),
+    });
+}
+
+//NSDragOperation's bits
+const DRAG_OPERATION_COPY: isize = 1;
+const DRAG_OPERATION_LINK: isize = 2;
+const DRAG_OPERATION_MOVE: isize = 16;
+
+fn drag_operations(actions: DragActions) -> isize {
+    [
+        (DragAction::Copy, DRAG_OPERATION_COPY),
+        (DragAction::Move, DRAG_OPERATION_MOVE),
+        (DragAction::Link, DRAG_OPERATION_LINK),
+    ]
+    .into_iter()
+    .filter(|(action, _)| actions.contains(*action))
+    .fold(0, |operations, (_, operation)| operations | operation)
+}
+
+/// AppKit asks as the drag goes, so actions set mid-drag apply from then on.
+extern "C" fn recv_drag_operations(ctx: *const DragReporter) -> isize {
+    drag_operations(unsafe { &*ctx }.actions())
+}
+
+/// Called once per drag, including ones that didn't start.
+extern "C" fn recv_drag_ended(ctx: *mut DragReporter, operation: isize) {
+    let reporter: DragReporter = *unsafe { Box::from_raw(ctx) };
+    reporter.finish(if operation & DRAG_OPERATION_MOVE != 0 {
+        DragOutcome::Dropped(DragAction::Move)
+    } else if operation & DRAG_OPERATION_COPY != 0 {
+        DragOutcome::Dropped(DragAction::Copy)
+    } else if operation & DRAG_OPERATION_LINK != 0 {
+        DragOutcome::Dropped(DragAction::Link)
+    } else if operation == 0 {
+        DragOutcome::Cancelled
+    } else {
+        //generic, private or delete, which we never offer
+        DragOutcome::Unknown
+    });
+}
+
+#[cfg(target_os = "macos")]
+extern "C" fn recv_done(ctx: *mut Sender<()>) {
+    let c: Sender<()> = *unsafe { Box::from_raw(ctx) };
+    c.send(());
+}
+
+#[cfg(target_os = "macos")]
+extern "C" fn recv_pick_color(
+    ctx: *mut Sender<Result<Color, PickColorError>>,
+    result: i32,
+    red: f64,
+    green: f64,
+    blue: f64,
+) {
+    let c: Sender<Result<Color, PickColorError>> = *unsafe { Box::from_raw(ctx) };
+    //see PickColorResult in Dialog.swift
+    c.send(match result {
+        0 => Ok(Color::new(red, green, blue)),
+        1 => Err(PickColorError::Cancelled),
+        other => Err(PickColorError::Platform(format!(
+            "unknown color picker result {other}"
+        ))),
+    });
+}
+
+#[cfg(target_os = "macos")]
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    let (sender, fut) = r#continue::continuation();
+    let sender_box = Box::into_raw(Box::new(sender));
+    unsafe { SwiftAppWindow_PickColor(sender_box as *mut c_void, recv_pick_color as *mut c_void) }
+    fut.await
+}
+
+#[cfg(target_os = "ios")]
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    //UIColorPickerViewController has an eyedropper, but only inside its own sheet
+    Err(PickColorError::Unsupported)
+}
+
+extern "C" fn recv_post_notification(ctx: *mut Sender<Result<(), NotificationError>>, result: i32) {
+    let c: Sender<Result<(), NotificationError>> = *unsafe { Box::from_raw(ctx) };
+    //see PostNotificationResult in Notification.swift
+    c.send(match result {
+        0 => Ok(()),
+        1 => Err(NotificationError::Unsupported),
+        2 => Err(NotificationError::Denied),
+        3 => Err(NotificationError::Platform(
+            "UserNotifications couldn't post the notification".to_string(),
+        )),
+        other => Err(NotificationError::Platform(format!(
+            "unknown notification result {other}"
+        ))),
+    });
+}
+
+pub async fn post_notification(notification: Notification) -> Result<(), NotificationError> {
+    let (sender, fut) = r#continue::continuation();
+    let sender_box = Box::into_raw(Box::new(sender));
+    let (rgba, width) = match &notification.icon {
+        Some(icon) => (SRData::from(icon.rgba()), icon.width() as isize),
+        None => (SRData::from(&[][..]), 0),
+    };
+    unsafe {
+        SwiftAppWindow_PostNotification(
+            sender_box as *mut c_void,
+            SRString::from(notification.title.as_str()),
+            SRString::from(notification.body.as_str()),
+            &rgba,
+            width,
+            recv_post_notification as *mut c_void,
+        )
+    }
+    fut.await
+}
+
+/// See `PowerCallback` in Power.swift.
+extern "C" fn power_event(code: isize) {
+    let event = match code {
+        0 => PowerEvent::DisplayOff,
+        1 => PowerEvent::DisplayOn,
+        2 => PowerEvent::LowPowerEntered,
+        3 => PowerEvent::LowPowerExited,
+        _ => return,
+    };
+    crate::power::notify(event)
+}
+
+pub async fn watch_power() -> Result<(), PowerError> {
+    crate::application::on_main_thread("watch_power".to_string(), || unsafe {
+        SwiftAppWindow_WatchPowerMain(power_event as *mut c_void)
+    })
+    .await;
+    Ok(())
+}
+
+/// Call on the main thread.
+pub fn unwatch_power() {
+    unsafe { SwiftAppWindow_UnwatchPowerMain() }
+}
+
+#[cfg(target_os = "macos")]
+pub async fn capture_permission_state() -> CapturePermission {
+    if unsafe { SwiftAppWindow_CapturePreflight() } {
+        CapturePermission::Granted
+    } else {
+        CapturePermission::NotGranted
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub async fn request_capture_permission() -> CapturePermission {
+    //the prompt is UI
+    let granted =
+        crate::application::on_main_thread("request_capture_permission".to_string(), || unsafe {
+            SwiftAppWindow_CaptureRequest()
+        })
+        .await;
+    if granted {
+        CapturePermission::Granted
+    } else {
+        CapturePermission::NotGranted
+    }
+}
+
+//ReplayKit asks each time recording starts
+#[cfg(target_os = "ios")]
+pub async fn capture_permission_state() -> CapturePermission {
+    CapturePermission::AskEachTime
+}
+
+#[cfg(target_os = "ios")]
+pub async fn request_capture_permission() -> CapturePermission {
+    CapturePermission::AskEachTime
+}
+
+#[cfg(target_os = "macos")]
+pub async fn set_activation_policy(policy: ActivationPolicy) -> Result<(), ActivationPolicyError> {
+    //see NSApplication.ActivationPolicy
+    let raw: isize = match policy {
+        ActivationPolicy::Regular => 0,
+        ActivationPolicy::Accessory => 1,
+        ActivationPolicy::Prohibited => 2,
+    };
+    let ok =
+        crate::application::on_main_thread("set_activation_policy".to_string(), move || unsafe {
+            SwiftAppWindow_SetActivationPolicyMain(raw)
+        })
+        .await;
+    if ok {
+        Ok(())
+    } else {
+        Err(ActivationPolicyError::Platform(format!(
+            "AppKit refused activation policy {policy:?}"
+        )))
+    }
+}
+
+#[cfg(target_os = "ios")]
+pub async fn set_activation_policy(_policy: ActivationPolicy) -> Result<(), ActivationPolicyError> {
+    Err(ActivationPolicyError::Unsupported)
+}
+
+/// Returns whether the window should close.
+type CloseFn = Arc<dyn Fn() -> bool + Send + Sync>;
+
+extern "C" fn call_close(ctx: *const CloseFn) -> bool {
+    let f = unsafe { &*ctx };
+    f()
+}
+
+extern "C" fn free_close(ctx: *mut CloseFn) {
+    drop(unsafe { Box::from_raw(ctx) });
+}
+
+/// `kind` is WindowEvents.Kind in Window.swift.
+#[cfg(target_os = "macos")]
+extern "C" fn call_event(ctx: *const WindowEventSender, kind: isize, a: f64, b: f64) {
+    use crate::window::{WindowEvent, WindowStateFlags};
+    let events = unsafe { &*ctx };
+    let event = match kind {
+        0 => WindowEvent::Resized(Size::new(a, b)),
+        1 => WindowEvent::Moved(Position::new(a, b)),
+        2 => WindowEvent::Focused,
+        3 => WindowEvent::Unfocused,
+        4 => WindowEvent::Minimized,
+        5 => WindowEvent::Restored,
+        6 => WindowEvent::ScaleChanged(a),
+        7 => WindowEvent::CloseRequested,
+        8 => WindowEvent::Reconnected,
+        9 => {
+            //macOS has no tiling state to report
+            let bits = a as u32;
+            let flags = WindowStateFlags {
+                maximized: bits & 1 != 0,
+                fullscreen: bits & 2 != 0,
+                activated: bits & 4 != 0,
+                suspended: bits & 8 != 0,
+                ..Default::default()
+            };
+            return events.update_state(flags);
+        }
+        _ => return,
+    };
+    events.send(event)
+}
+
+#[cfg(target_os = "macos")]
+extern "C" fn free_event(ctx: *mut WindowEventSender) {
+    drop(unsafe { Box::from_raw(ctx) });
+}
+
+/// The numbering `SwiftAppWindow_WindowSetCursor` expects.
+#[cfg(target_os = "macos")]
+fn cursor_kind(cursor: Cursor) -> isize {
+    match cursor {
+        //AppKit has no busy cursor for apps to set; the system shows its own when we hang
+        Cursor::Arrow | Cursor::Wait => 0,
+        Cursor::Text => 1,
+        Cursor::Hand => 2,
+        Cursor::Crosshair => 3,
+        Cursor::NotAllowed => 4,
+        Cursor::ResizeHorizontal => 5,
+        Cursor::ResizeVertical => 6,
+        Cursor::Hidden => 7,
+    }
+}
+
 #[derive(Debug)]
 pub struct Window {
     imp: *mut c_void,
+    events: WindowEventSender,
+    /// The last cursor set, for `debug_state`.
+    cursor: Mutex<Cursor>,
 }
 //marked as Sendable in swift
 unsafe impl Send for Window {}
 unsafe impl Sync for Window {}
 impl Window {
+    fn from_imp(imp: *mut c_void) -> Self {
+        let events = WindowEventSender::default();
+        //freed by WindowEvents' deinit
+        #[cfg(target_os = "macos")]
+        unsafe {
+            SwiftAppWindow_WindowSetEvents(
+                imp,
+                Box::into_raw(Box::new(events.clone())) as *mut c_void,
+                call_event as *mut c_void,
+                free_event as *mut c_void,
+            )
+        };
+        Window {
+            imp,
+            events,
+            cursor: Mutex::new(Cursor::default()),
+        }
+    }
+
     pub async fn new(position: Position, size: Size, title: String) -> Self {
         let imp = unsafe {
             SwiftAppWindow_WindowNew(
@@ -114,8 +670,137 @@ impl Window {
                 SRString::from(title.as_str()),
             )
         };
-        Window { imp }
+        Window::from_imp(imp)
     }
+    #[cfg(target_os = "macos")]
+    pub async fn new_with_options(
+        position: Position,
+        size: Size,
+        title: String,
+        options: CreateOptions,
+    ) -> Self {
+        let imp = unsafe {
+            SwiftAppWindow_WindowNewWithOptions(
+                position.x(),
+                position.y(),
+                size.width(),
+                size.height(),
+                SRString::from(title.as_str()),
+                options.decorated,
+                options.visible,
+            )
+        };
+        Window::from_imp(imp)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn new_with_options(
+        position: Position,
+        size: Size,
+        title: String,
+        options: CreateOptions,
+    ) -> Self {
+        //windows have no decorations
+        let window = Self::new(position, size, title).await;
+        if !options.visible {
+            window.set_visible(false).await;
+        }
+        window
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn new_splash(size: Size, image: PreviewImage) -> Option<Self> {
+        let rgba = SRData::from(image.rgba());
+        let imp = unsafe {
+            SwiftAppWindow_WindowNewSplash(
+                size.width(),
+                size.height(),
+                &rgba,
+                image.width() as isize,
+            )
+        };
+        Some(Window::from_imp(imp))
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn new_splash(_size: Size, _image: PreviewImage) -> Option<Self> {
+        //the launch storyboard covers this
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn fade_out(self) {
+        let (sender, fut) = r#continue::continuation::<()>();
+        let sender_box = Box::into_raw(Box::new(sender));
+        unsafe {
+            SwiftAppWindow_WindowFadeOut(
+                sender_box as *mut c_void,
+                self.imp,
+                recv_done as *mut c_void,
+            )
+        };
+        fut.await
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn fade_out(self) {}
+
+    #[cfg(target_os = "macos")]
+    pub async fn new_overlay(size: Size, image: PreviewImage) -> Option<Self> {
+        let rgba = SRData::from(image.rgba());
+        let imp = unsafe {
+            SwiftAppWindow_WindowNewOverlay(
+                size.width(),
+                size.height(),
+                &rgba,
+                image.width() as isize,
+            )
+        };
+        Some(Window::from_imp(imp))
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn new_overlay(_size: Size, _image: PreviewImage) -> Option<Self> {
+        //windows fill the screen, so there is nowhere to float one
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_splash_image(&self, image: PreviewImage) {
+        let rgba = SRData::from(image.rgba());
+        unsafe { SwiftAppWindow_WindowSetSplashImage(self.imp, &rgba, image.width() as isize) };
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_splash_image(&self, _image: PreviewImage) {}
+
+    #[cfg(target_os = "macos")]
+    pub async fn new_parked(position: Position, size: Size, title: String) -> Option<Self> {
+        let imp = unsafe {
+            SwiftAppWindow_WindowNewParked(
+                position.x(),
+                position.y(),
+                size.width(),
+                size.height(),
+                SRString::from(title.as_str()),
+            )
+        };
+        Some(Window::from_imp(imp))
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn new_parked(_position: Position, _size: Size, _title: String) -> Option<Self> {
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn unpark(&self, title: String) {
+        unsafe { SwiftAppWindow_WindowUnpark(self.imp, SRString::from(title.as_str())) }
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn unpark(&self, _title: String) {}
+
     pub async fn default() -> Self {
         Self::new(
             Position::new(0.0, 0.0),
@@ -125,11 +810,11 @@ impl Window {
         .await
     }
 
-    pub async fn fullscreen(title: String) -> Result<Self, FullscreenError> {
+    pub async fn fullscreen(title: String, _output: Option<u32>) -> Result<Self, FullscreenError> {
         let imp = unsafe { SwiftAppWindow_WindowNewFullscreen(SRString::from(title.as_str())) };
-        Ok(Window { imp })
+        Ok(Window::from_imp(imp))
     }
-    pub async fn surface(&self) -> crate::surface::Surface {
+    pub async fn surface(&self) -> Surface {
         let (sender, fut) = r#continue::continuation();
 
         let sender_box = Box::into_raw(Box::new(sender));
@@ -141,9 +826,598 @@ impl Window {
             )
         };
 
-        let sys_surface = fut.await;
+        fut.await
+    }
+
+    pub async fn set_visible(&self, visible: bool) {
+        unsafe { SwiftAppWindow_WindowSetVisible(self.imp, visible) }
+    }
+
+    pub async fn set_canvas_behavior(&self, _behavior: CanvasBehavior) {
+        //only browsers have defaults to suppress
+    }
+
+    pub async fn set_close_behavior(&self, close_behavior: CloseBehavior) {
+        //see WindowDelegate.Behavior in Window.swift
+        let (behavior, f): (isize, CloseFn) = match close_behavior {
+            CloseBehavior::Destroy => (0, Arc::new(|| true)),
+            CloseBehavior::HideToTray => (1, Arc::new(|| false)),
+            CloseBehavior::Custom(f) => (
+                2,
+                Arc::new(move || {
+                    f();
+                    false
+                }),
+            ),
+            CloseBehavior::Ask(f) => (2, Arc::new(move || f() == CloseResponse::Close)),
+        };
+        //freed by the delegate's deinit
+        let ctx = Box::into_raw(Box::new(f));
+        unsafe {
+            SwiftAppWindow_WindowSetCloseBehavior(
+                self.imp,
+                behavior,
+                ctx as *mut c_void,
+                call_close as *mut c_void,
+                free_close as *mut c_void,
+            )
+        }
+    }
+
+    pub async fn show_window_menu(&self, position: Position) {
+        unsafe { SwiftAppWindow_WindowShowWindowMenu(self.imp, position.x(), position.y()) }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_size(&self, size: Size) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetSize(self.imp, size.width(), size.height()) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_size(&self, _size: Size) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_position(&self, position: Position) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetPosition(self.imp, position.x(), position.y()) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_position(&self, _position: Position) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn outer_position(&self) -> Option<Position> {
+        //the pointer is Sendable on the swift side
+        let imp = self.imp as usize;
+        crate::application::on_main_thread("Window::outer_position".into(), move || {
+            let (mut x, mut y) = (0.0, 0.0);
+            unsafe { SwiftAppWindow_WindowOuterPositionMain(imp as *mut c_void, &mut x, &mut y) }
+                .then(|| Position::new(x, y))
+        })
+        .await
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn outer_position(&self) -> Option<Position> {
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn normal_bounds(&self) -> Option<(Option<Position>, Size)> {
+        let imp = self.imp as usize;
+        crate::application::on_main_thread("Window::normal_bounds".into(), move || {
+            let (mut x, mut y, mut width, mut height) = (0.0, 0.0, 0.0, 0.0);
+            unsafe {
+                SwiftAppWindow_WindowNormalBoundsMain(
+                    imp as *mut c_void,
+                    &mut x,
+                    &mut y,
+                    &mut width,
+                    &mut height,
+                )
+            }
+            .then(|| (Some(Position::new(x, y)), Size::new(width, height)))
+        })
+        .await
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn normal_bounds(&self) -> Option<(Option<Position>, Size)> {
+        None
+    }
+
+    pub fn request_frame(&self, frame: FrameCallback) {
+        let ctx = Box::into_raw(Box::new(frame)) as *mut c_void;
+        unsafe { SwiftAppWindow_WindowRequestFrame(self.imp, ctx, recv_frame as *mut c_void) }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetAllSpaces(self.imp, visible) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        _visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_skip_taskbar(&self, skip: bool) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetSkipTaskbar(self.imp, skip) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_skip_taskbar(&self, _skip: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn inhibit_system_shortcuts(&self, inhibit: bool) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowInhibitShortcuts(self.imp, inhibit) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn inhibit_system_shortcuts(&self, _inhibit: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    //AppKit can only hide the cursor and disassociate it from the mouse, which isn't confinement
+    pub async fn confine_pointer(&self, _region: Option<Rect>) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn release_pointer(&self) -> Result<(), WindowSettingError> {
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_preview_image(
+        &self,
+        image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        let (rgba, width) = match &image {
+            Some(image) => (SRData::from(image.rgba()), image.width() as isize),
+            None => (SRData::from(&[][..]), 0),
+        };
+        unsafe { SwiftAppWindow_WindowSetPreviewImage(self.imp, &rgba, width) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_preview_image(
+        &self,
+        _image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    /// The Dock has one icon, the app's.
+    #[cfg(target_os = "macos")]
+    pub async fn set_icon(&self, icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        let (rgba, width) = match &icon {
+            Some(icon) => (SRData::from(icon.rgba()), icon.width() as isize),
+            None => (SRData::from(&[][..]), 0),
+        };
+        unsafe { SwiftAppWindow_SetApplicationIcon(&rgba, width) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_icon(&self, _icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        //alternate icons have to be in the bundle, and iOS asks the user first
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_badge(&self, label: Option<String>) -> Result<(), WindowSettingError> {
+        let label = label.unwrap_or_default();
+        unsafe { SwiftAppWindow_SetBadgeLabel(SRString::from(label.as_str())) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_badge(&self, _label: Option<String>) -> Result<(), WindowSettingError> {
+        //badges come from notifications, which need the user's permission
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_progress(&self, _progress: Option<f64>) -> Result<(), WindowSettingError> {
+        //the Dock only shows progress for downloads
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_cursor(&self, cursor: Cursor) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetCursor(self.imp, cursor_kind(cursor)) };
+        *self.cursor.lock().unwrap() = cursor;
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_cursor(&self, _cursor: Cursor) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        _visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        //the pointer is Sendable on the swift side
+        let imp = self.imp as usize;
+        let shown = crate::application::on_main_thread(
+            "Window::set_virtual_keyboard_visible".into(),
+            move || unsafe {
+                SwiftAppWindow_WindowSetVirtualKeyboardVisibleMain(imp as *mut c_void, visible)
+            },
+        )
+        .await;
+        if shown {
+            Ok(())
+        } else {
+            Err(WindowSettingError::Platform(
+                "the window couldn't change its first responder".to_string(),
+            ))
+        }
+    }
+
+    pub async fn set_decorations(&self, _mode: DecorationsMode) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_decoration_theme(
+        &self,
+        _theme: DecorationTheme,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_resize_border(&self, _width: f64) -> Result<(), WindowSettingError> {
+        //the system draws the frame
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn begin_sheet(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        self.start_sheet(sheet).await;
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn show_modal(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        //nobody waits for it to be dismissed
+        drop(self.start_sheet(sheet));
+        Ok(())
+    }
+
+    /// Puts `sheet` up, returning what finishes once it's dismissed.
+    #[cfg(target_os = "macos")]
+    fn start_sheet(&self, sheet: &Window) -> r#continue::Future<()> {
+        let (sender, fut) = r#continue::continuation::<()>();
+        let sender_box = Box::into_raw(Box::new(sender));
+        unsafe {
+            SwiftAppWindow_WindowBeginSheet(
+                sender_box as *mut c_void,
+                self.imp,
+                sheet.imp,
+                recv_done as *mut c_void,
+            )
+        };
+        fut
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn begin_sheet(&self, _sheet: &Window) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn show_modal(&self, _sheet: &Window) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
 
-        crate::surface::Surface { sys: sys_surface }
+    #[cfg(target_os = "macos")]
+    pub async fn set_resizable(&self, resizable: bool) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetResizable(self.imp, resizable) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_size_limits(
+        &self,
+        min: Option<Size>,
+        max: Option<Size>,
+    ) -> Result<(), WindowSettingError> {
+        //zero means no limit
+        let (min_width, min_height) = min.map_or((0.0, 0.0), |s| (s.width(), s.height()));
+        let (max_width, max_height) = max.map_or((0.0, 0.0), |s| (s.width(), s.height()));
+        unsafe {
+            SwiftAppWindow_WindowSetSizeLimits(
+                self.imp, min_width, min_height, max_width, max_height,
+            )
+        };
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_click_through(&self, click_through: bool) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetClickThrough(self.imp, click_through) };
+        Ok(())
+    }
+
+    #[cfg(all(target_os = "macos", feature = "accessibility"))]
+    pub async fn update_accessibility_tree(
+        &self,
+        update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        //the pointer is Sendable on the swift side
+        let imp = self.imp as usize;
+        let events = self.events.clone();
+        crate::application::on_main_thread("Window::update_accessibility_tree".into(), move || {
+            ax::update(imp, &events, update)
+        })
+        .await
+    }
+
+    #[cfg(all(target_os = "macos", feature = "accessibility"))]
+    pub async fn set_accessibility_action_handler(
+        &self,
+        on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> Result<(), WindowSettingError> {
+        let imp = self.imp as usize;
+        crate::application::on_main_thread(
+            "Window::set_accessibility_action_handler".into(),
+            move || ax::set_action_handler(imp, on_action),
+        )
+        .await;
+        Ok(())
+    }
+
+    #[cfg(all(target_os = "ios", feature = "accessibility"))]
+    pub async fn update_accessibility_tree(
+        &self,
+        _update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(all(target_os = "ios", feature = "accessibility"))]
+    pub async fn set_accessibility_action_handler(
+        &self,
+        _on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowFocus(self.imp) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn is_focused(&self) -> bool {
+        //the pointer is Sendable on the swift side
+        let imp = self.imp as usize;
+        crate::application::on_main_thread("Window::is_focused".into(), move || unsafe {
+            SwiftAppWindow_WindowDebugStateMain(imp as *mut c_void).focused
+        })
+        .await
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_always_on_top(&self, on_top: bool) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetAlwaysOnTop(self.imp, on_top) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_maximized(&self, maximized: bool) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetMaximized(self.imp, maximized) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_background(&self, color: Color) -> Result<(), WindowSettingError> {
+        let [red, green, blue] = color.to_rgb8().map(|c| c as f64 / 255.0);
+        unsafe { SwiftAppWindow_WindowSetBackground(self.imp, red, green, blue) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_transparent(&self, transparent: bool) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetTransparent(self.imp, transparent) };
+        Ok(())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn set_parent(&self, parent: &Window) -> Result<(), WindowSettingError> {
+        unsafe { SwiftAppWindow_WindowSetParent(self.imp, parent.imp) };
+        Ok(())
+    }
+
+    //the system sizes and stacks windows
+    #[cfg(target_os = "ios")]
+    pub async fn set_resizable(&self, _resizable: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_size_limits(
+        &self,
+        _min: Option<Size>,
+        _max: Option<Size>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_click_through(&self, _click_through: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_always_on_top(&self, _on_top: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_maximized(&self, _maximized: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_background(&self, _color: Color) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_transparent(&self, _transparent: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn set_parent(&self, _parent: &Window) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub fn event_sender(&self) -> WindowEventSender {
+        self.events.clone()
+    }
+
+    /// The NSWindow (or UIWindow) that input reports.
+    pub async fn input_handle(&self) -> Option<usize> {
+        //the pointer is Sendable on the swift side
+        let imp = self.imp as usize;
+        let handle =
+            crate::application::on_main_thread("Window::input_handle".into(), move || unsafe {
+                SwiftAppWindow_WindowInputHandleMain(imp as *mut c_void) as usize
+            })
+            .await;
+        (handle != 0).then_some(handle)
+    }
+
+    /// Input reports the `NSWindow` or `UIWindow`, so it doubles as the native handle.
+    pub async fn native_handle(&self) -> Option<NativeHandle> {
+        let window = NonNull::new(self.input_handle().await? as *mut c_void)?;
+        Some(if cfg!(target_os = "ios") {
+            NativeHandle::UiKit { ui_window: window }
+        } else {
+            NativeHandle::AppKit { ns_window: window }
+        })
+    }
+
+    pub async fn scale_factor(&self) -> f64 {
+        //the pointer is Sendable on the swift side
+        let imp = self.imp as usize;
+        crate::application::on_main_thread("Window::scale_factor".into(), move || unsafe {
+            SwiftAppWindow_WindowDebugStateMain(imp as *mut c_void).scale
+        })
+        .await
+    }
+
+    pub async fn debug_state(&self) -> WindowDebugState {
+        //the pointer is Sendable on the swift side
+        let imp = self.imp as usize;
+        let state =
+            crate::application::on_main_thread("Window::debug_state".into(), move || unsafe {
+                SwiftAppWindow_WindowDebugStateMain(imp as *mut c_void)
+            })
+            .await;
+        WindowDebugState {
+            backend: if cfg!(target_os = "ios") {
+                "uikit"
+            } else {
+                "appkit"
+            },
+            applied_size: Some(Size::new(state.width, state.height)),
+            proposed_size: None,
+            scale: Some(state.scale),
+            preferred_scale: None,
+            outputs: (state.display != 0)
+                .then_some(state.display)
+                .into_iter()
+                .collect(),
+            focused: Some(state.focused),
+            visible: Some(state.visible),
+            configured: None,
+            decorations: Some(if state.decorated {
+                DecorationsMode::Native
+            } else {
+                DecorationsMode::None
+            }),
+            buffer: None,
+            cursor: if cfg!(target_os = "macos") {
+                Some(*self.cursor.lock().unwrap())
+            } else {
+                None
+            },
+            state: Some(match state.state {
+                1 => WindowState::Maximized,
+                2 => WindowState::Minimized,
+                3 => WindowState::Fullscreen,
+                _ => WindowState::Normal,
+            }),
+        }
+    }
+
+    pub async fn start_drag(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+        reporter: DragReporter,
+    ) -> Result<(), DragError> {
+        //paths travel as their text/uri-list encoding; swift turns them back into file URLs
+        let payload = SRData::from(data.encoded().as_slice());
+        let (image, image_width) = match &image {
+            Some(image) => (SRData::from(image.rgba()), image.width() as isize),
+            None => (SRData::from(&[][..]), 0),
+        };
+        let (sender, fut) = r#continue::continuation();
+        let sender_box = Box::into_raw(Box::new(sender));
+        reporter.follow_actions();
+        let reporter_box = Box::into_raw(Box::new(reporter));
+        unsafe {
+            SwiftAppWindow_WindowStartDrag(
+                sender_box as *mut c_void,
+                self.imp,
+                SRString::from(data.mime()),
+                &payload,
+                &image,
+                image_width,
+                reporter_box as *mut c_void,
+                recv_drag_operations as *mut c_void,
+                recv_drag_ended as *mut c_void,
+                recv_start_drag as *mut c_void,
+            )
+        }
+        fut.await
     }
 }
 
@@ -153,6 +1427,9 @@ swift!(fn SwiftAppWindow_SurfaceRawHandle(surface: *mut c_void)  -> *mut c_void)
 
 swift!(fn SwiftAppWindow_SurfaceFree(surface: *mut c_void) -> ());
 swift!(fn SwiftAppWindow_SurfaceSizeUpdate(ctx: *mut c_void, surface: *mut c_void, notify: *mut c_void) -> ());
+swift!(fn SwiftAppWindow_SurfaceDamage(surface: *mut c_void, x: f64, y: f64, width: f64, height: f64) -> ());
+swift!(fn SwiftAppWindow_SurfaceRetain(surface: *mut c_void) -> ());
+swift!(fn SwiftAppWindow_SurfaceRequestFrame(surface: *mut c_void, ctx: *mut c_void, call: *mut c_void) -> ());
 
 extern "C" fn notify_size<F: Fn(Size)>(ctx: *const F, width: f64, height: f64) {
     let as_weak = unsafe { Weak::from_raw(ctx) };
@@ -163,11 +1440,121 @@ extern "C" fn notify_size<F: Fn(Size)>(ctx: *const F, width: f64, height: f64) {
     std::mem::forget(as_weak);
 }
 
+/// Our `NSStatusItem`, in its Swift wrapper.
+#[derive(Debug)]
+pub struct TrayIcon {
+    #[cfg(target_os = "macos")]
+    imp: *mut c_void,
+}
+
+//only used on the main thread
+unsafe impl Send for TrayIcon {}
+unsafe impl Sync for TrayIcon {}
+
+/// `item` is the index of the menu item chosen, or -1 for a click on the icon.
+#[cfg(target_os = "macos")]
+extern "C" fn tray_event(id: u64, item: isize) {
+    let event = match usize::try_from(item) {
+        Ok(item) => TrayEvent::MenuItem(item),
+        Err(_) => TrayEvent::Clicked,
+    };
+    crate::tray::notify(id, event)
+}
+
+/// Call on the main thread.
+#[cfg(target_os = "macos")]
+fn tray_update_main(imp: *mut c_void, state: &TrayState) {
+    let rgba = SRData::from(state.icon.rgba());
+    //a line per item; see Tray.swift
+    let menu = state
+        .menu
+        .iter()
+        .map(|item| match item {
+            TrayMenuItem::Item { label, enabled } => {
+                //labels are one line
+                let label = label.replace('\n', " ");
+                format!("{}{label}", if *enabled { '1' } else { '0' })
+            }
+            TrayMenuItem::Separator => "s".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    unsafe {
+        SwiftAppWindow_TrayUpdateMain(
+            imp,
+            &rgba,
+            state.icon.width() as isize,
+            SRString::from(state.tooltip.as_str()),
+            SRString::from(menu.as_str()),
+        )
+    }
+}
+
+impl TrayIcon {
+    #[cfg(target_os = "macos")]
+    pub async fn new(id: u64, state: TrayState) -> Result<Self, TrayError> {
+        crate::application::on_main_thread("TrayIcon::new".to_string(), move || {
+            let imp = unsafe { SwiftAppWindow_TrayNewMain(id, tray_event as *mut c_void) };
+            tray_update_main(imp, &state);
+            Ok(TrayIcon { imp })
+        })
+        .await
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn new(_id: u64, _state: TrayState) -> Result<Self, TrayError> {
+        Err(TrayError::Unsupported)
+    }
+
+    #[cfg(target_os = "macos")]
+    pub async fn update(&self, state: TrayState) -> Result<(), TrayError> {
+        let imp = self.imp as usize;
+        crate::application::on_main_thread("TrayIcon::update".to_string(), move || {
+            tray_update_main(imp as *mut c_void, &state);
+            Ok(())
+        })
+        .await
+    }
+
+    #[cfg(target_os = "ios")]
+    pub async fn update(&self, _state: TrayState) -> Result<(), TrayError> {
+        Err(TrayError::Unsupported)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        let imp = self.imp as usize;
+        crate::application::submit_to_main_thread("TrayIcon::drop".to_string(), move || unsafe {
+            SwiftAppWindow_TrayFreeMain(imp as *mut c_void)
+        });
+    }
+}
+
 pub struct Surface {
     imp: *mut c_void,
     update_size: Option<Arc<dyn Fn(Size)>>,
 }
 
+/// Our own reference to the Swift surface, so frame requesters can outlive it.
+struct SurfaceFrames(*mut c_void);
+
+//sendable in swift!
+unsafe impl Send for SurfaceFrames {}
+unsafe impl Sync for SurfaceFrames {}
+impl SurfaceFrames {
+    fn request(&self, frame: FrameCallback) {
+        let ctx = Box::into_raw(Box::new(frame)) as *mut c_void;
+        unsafe { SwiftAppWindow_SurfaceRequestFrame(self.0, ctx, recv_frame as *mut c_void) }
+    }
+}
+impl Drop for SurfaceFrames {
+    fn drop(&mut self) {
+        unsafe { SwiftAppWindow_SurfaceFree(self.0) }
+    }
+}
+
 //sendable in swift!
 unsafe impl Send for Surface {}
 unsafe impl Sync for Surface {}
@@ -202,12 +1589,20 @@ impl Surface {
     }
     pub fn raw_window_handle(&self) -> RawWindowHandle {
         let ptr = unsafe { SwiftAppWindow_SurfaceRawHandle(self.imp) };
-        RawWindowHandle::AppKit(AppKitWindowHandle::new(
+        #[cfg(target_os = "macos")]
+        return RawWindowHandle::AppKit(AppKitWindowHandle::new(
             NonNull::new(ptr as *mut _).unwrap(),
-        ))
+        ));
+        #[cfg(target_os = "ios")]
+        return RawWindowHandle::UiKit(UiKitWindowHandle::new(
+            NonNull::new(ptr as *mut _).unwrap(),
+        ));
     }
     pub fn raw_display_handle(&self) -> RawDisplayHandle {
-        RawDisplayHandle::AppKit(AppKitDisplayHandle::new())
+        #[cfg(target_os = "macos")]
+        return RawDisplayHandle::AppKit(AppKitDisplayHandle::new());
+        #[cfg(target_os = "ios")]
+        return RawDisplayHandle::UiKit(UiKitDisplayHandle::new());
     }
     /**
     Run the attached callback when size changes.
@@ -225,10 +1620,35 @@ impl Surface {
             )
         }
     }
+
+    pub fn frame_requester(&self) -> FrameRequester {
+        unsafe { SwiftAppWindow_SurfaceRetain(self.imp) }
+        let frames = SurfaceFrames(self.imp);
+        Arc::new(move |frame| frames.request(frame))
+    }
+
+    pub fn damage(&self, rects: &[Rect]) {
+        for rect in rects {
+            unsafe {
+                SwiftAppWindow_SurfaceDamage(
+                    self.imp,
+                    rect.origin().x(),
+                    rect.origin().y(),
+                    rect.size().width(),
+                    rect.size().height(),
+                )
+            }
+        }
+    }
 }
 
 impl Drop for Window {
     fn drop(&mut self) {
+        #[cfg(all(target_os = "macos", feature = "accessibility"))]
+        {
+            let imp = self.imp as usize;
+            on_main_thread(move || ax::forget(imp));
+        }
         unsafe {
             SwiftAppWindow_WindowFree(self.imp);
         }
@@ -241,3 +1661,63 @@ impl Debug for Surface {
         write!(f, "{:?}", self.imp)
     }
 }
+
+/// The Swift package calls back into the input module through these symbols, so they must link
+/// even when that module is compiled out.  Swift only calls most of them for input objects the
+/// module created, so they're never reached; windows report text regardless, which is dropped.
+#[cfg(not(feature = "input"))]
+mod input_stubs {
+    use std::ffi::c_void;
+
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_finish_mouse_event_context(_ctx: *mut c_void) {}
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_mouse_move(
+        _ctx: *const c_void,
+        _window: *mut c_void,
+        _window_pos_x: f64,
+        _window_pos_y: f64,
+        _window_width: f64,
+        _window_height: f64,
+    ) {
+    }
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_mouse_button(
+        _ctx: *const c_void,
+        _window: *mut c_void,
+        _button: u8,
+        _down: bool,
+        _clicks: u32,
+    ) {
+    }
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_mouse_scroll(
+        _ctx: *const c_void,
+        _window: *mut c_void,
+        _delta_x: f64,
+        _delta_y: f64,
+        _lines: bool,
+    ) {
+    }
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_key_notify_func(
+        _ctx: *mut c_void,
+        _window: *mut c_void,
+        _key_code: u16,
+        _down: bool,
+    ) {
+    }
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_finish_event_context(_ctx: *mut c_void) {}
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_text_commit(_text: *const std::ffi::c_char) {}
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_text_preedit(
+        _text: *const std::ffi::c_char,
+        _cursor_start: i64,
+        _cursor_end: i64,
+    ) {
+    }
+    #[unsafe(no_mangle)]
+    extern "C" fn raw_input_text_cancel() {}
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The app's accessibility tree, published over NSAccessibility with `accesskit_macos`.
+//!
+//! The adapter subclasses the window's content view when the app publishes its first tree.
+//! Until then the view keeps AppKit's defaults.
+
+use super::{SwiftAppWindow_WindowDebugStateMain, SwiftAppWindow_WindowInputHandleMain};
+use crate::window::accessibility::AccessibilityContent;
+use crate::window::{EventHandler, WindowEvent, WindowEventSender, WindowSettingError};
+use accesskit::{ActionRequest, TreeId, TreeUpdate};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::{Arc, Mutex};
+
+struct Accessibility {
+    content: Arc<Mutex<AccessibilityContent>>,
+    adapter: Option<accesskit_macos::SubclassingAdapter>,
+    /// Follows the window's focus, once there's an adapter.
+    _focus: Option<EventHandler>,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Accessibility {
+            //the app's tree is the window's whole tree
+            content: Arc::new(Mutex::new(AccessibilityContent::new(TreeId::ROOT))),
+            adapter: None,
+            _focus: None,
+        }
+    }
+}
+
+thread_local! {
+    /// By the window's Swift object.  Main thread only, like the adapters.
+    static WINDOWS: RefCell<HashMap<usize, Accessibility>> = RefCell::new(HashMap::new());
+}
+
+struct ContentTree(Arc<Mutex<AccessibilityContent>>);
+
+impl accesskit::ActivationHandler for ContentTree {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        self.0.lock().unwrap().full_update()
+    }
+}
+
+impl accesskit::ActionHandler for ContentTree {
+    fn do_action(&mut self, request: ActionRequest) {
+        AccessibilityContent::perform(&self.0, request)
+    }
+}
+
+/// Records `update` and passes it on, if anyone's listening.  Call on the main thread.
+pub(super) fn update(
+    imp: usize,
+    events: &WindowEventSender,
+    update: TreeUpdate,
+) -> Result<(), WindowSettingError> {
+    let queued = WINDOWS.with_borrow_mut(|windows| {
+        let ax = windows.entry(imp).or_default();
+        let update = ax.content.lock().unwrap().apply(update)?;
+        if ax.adapter.is_none() {
+            let window = unsafe { SwiftAppWindow_WindowInputHandleMain(imp as *mut c_void) };
+            if window.is_null() {
+                return Err(WindowSettingError::Unsupported);
+            }
+            let content = ContentTree(ax.content.clone());
+            let actions = ContentTree(ax.content.clone());
+            let mut adapter = unsafe {
+                accesskit_macos::SubclassingAdapter::for_window(window, content, actions)
+            };
+            let focused =
+                unsafe { SwiftAppWindow_WindowDebugStateMain(imp as *mut c_void) }.focused;
+            //inactive, so nothing to raise
+            _ = adapter.update_view_focus_state(focused);
+            ax.adapter = Some(adapter);
+            ax._focus = Some(events.handle(move |event| {
+                let focused = match event {
+                    WindowEvent::Focused => true,
+                    WindowEvent::Unfocused => false,
+                    _ => return,
+                };
+                super::on_main_thread(move || focus_changed(imp, focused));
+            }));
+        }
+        Ok(ax
+            .adapter
+            .as_mut()
+            .and_then(|a| a.update_if_active(|| update)))
+    })?;
+    //AppKit may ask for the tree again while raising
+    if let Some(queued) = queued {
+        queued.raise();
+    }
+    Ok(())
+}
+
+/// Call on the main thread.
+pub(super) fn set_action_handler(imp: usize, on_action: Box<dyn FnMut(ActionRequest) + Send>) {
+    WINDOWS.with_borrow_mut(|windows| {
+        let ax = windows.entry(imp).or_default();
+        ax.content.lock().unwrap().set_action_handler(on_action)
+    })
+}
+
+fn focus_changed(imp: usize, focused: bool) {
+    let queued = WINDOWS.with_borrow_mut(|windows| {
+        windows
+            .get_mut(&imp)?
+            .adapter
+            .as_mut()?
+            .update_view_focus_state(focused)
+    });
+    if let Some(queued) = queued {
+        queued.raise();
+    }
+}
+
+/// Drops the adapter, which puts the view's class back.  Call on the main thread.
+pub(super) fn forget(imp: usize) {
+    let ax = WINDOWS.with_borrow_mut(|windows| windows.remove(&imp));
+    drop(ax);
+}
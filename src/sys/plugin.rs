@@ -0,0 +1,445 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Forwards everything to the backend registered with [`crate::backend::register`].
+
+use crate::application::{
+    ActivationPolicy, ActivationPolicyError, CapturePermission, PerformanceHintCapabilities,
+    PerformanceHintError, PerformanceHints,
+};
+use crate::audio::{AudioHintCapabilities, AudioHintError, AudioHints, HintSupport};
+use crate::backend::{Backend, BackendSurface, BackendWindow, FrameRequester};
+use crate::clipboard::ClipboardError;
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::{Color, PickColorError};
+use crate::drag::{DragData, DragError, DragImage, DragReporter};
+use crate::notification::{Notification, NotificationError};
+use crate::power::PowerError;
+use crate::tray::{TrayError, TrayState};
+use crate::window::{
+    CanvasBehavior, CloseBehavior, CreateOptions, Cursor, DecorationTheme, DecorationsMode,
+    FrameCallback, IconData, NativeHandle, PreviewImage, WindowDebugState, WindowEventSender,
+    WindowSettingError,
+};
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+fn backend() -> &'static dyn Backend {
+    crate::backend::active().expect(
+        "No backend registered; call app_window::backend::register before application::main",
+    )
+}
+
+#[derive(Debug)]
+pub struct FullscreenError(String);
+
+impl Display for FullscreenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for FullscreenError {}
+
+#[derive(Debug)]
+pub struct Window {
+    imp: Box<dyn BackendWindow>,
+}
+
+impl Window {
+    pub async fn new(position: Position, size: Size, title: String) -> Self {
+        Window {
+            imp: backend().new_window(position, size, title).await,
+        }
+    }
+
+    pub async fn new_with_options(
+        position: Position,
+        size: Size,
+        title: String,
+        options: CreateOptions,
+    ) -> Self {
+        let window = Window::new(position, size, title).await;
+        //backends that can't are left decorated
+        if !options.decorated {
+            _ = window.imp.set_decorations(DecorationsMode::None).await;
+        }
+        if !options.visible {
+            window.imp.set_visible(false).await;
+        }
+        window
+    }
+
+    pub async fn fullscreen(title: String, _output: Option<u32>) -> Result<Self, FullscreenError> {
+        let imp = backend()
+            .new_fullscreen_window(title)
+            .await
+            .map_err(FullscreenError)?;
+        Ok(Window { imp })
+    }
+
+    pub async fn new_splash(_size: Size, _image: PreviewImage) -> Option<Self> {
+        None
+    }
+
+    pub async fn fade_out(self) {}
+
+    pub async fn new_overlay(_size: Size, _image: PreviewImage) -> Option<Self> {
+        None
+    }
+
+    pub async fn set_splash_image(&self, _image: PreviewImage) {}
+
+    pub async fn new_parked(_position: Position, _size: Size, _title: String) -> Option<Self> {
+        None
+    }
+
+    pub async fn unpark(&self, _title: String) {}
+
+    pub async fn default() -> Self {
+        Window::new(
+            Position::new(0.0, 0.0),
+            Size::new(800.0, 600.0),
+            "app_window".to_string(),
+        )
+        .await
+    }
+
+    pub async fn surface(&self) -> Surface {
+        Surface {
+            imp: self.imp.surface().await,
+        }
+    }
+
+    pub async fn set_visible(&self, visible: bool) {
+        self.imp.set_visible(visible).await
+    }
+
+    pub async fn set_canvas_behavior(&self, _behavior: CanvasBehavior) {
+        //only browsers have defaults to suppress
+    }
+
+    pub async fn set_close_behavior(&self, close_behavior: CloseBehavior) {
+        self.imp.set_close_behavior(close_behavior).await
+    }
+
+    pub async fn show_window_menu(&self, position: Position) {
+        self.imp.show_window_menu(position).await
+    }
+
+    pub async fn set_size(&self, size: Size) -> Result<(), WindowSettingError> {
+        self.imp.set_size(size).await
+    }
+
+    pub async fn set_position(&self, position: Position) -> Result<(), WindowSettingError> {
+        self.imp.set_position(position).await
+    }
+
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        self.imp.set_visible_on_all_workspaces(visible).await
+    }
+
+    pub async fn set_skip_taskbar(&self, skip: bool) -> Result<(), WindowSettingError> {
+        self.imp.set_skip_taskbar(skip).await
+    }
+
+    pub async fn inhibit_system_shortcuts(&self, inhibit: bool) -> Result<(), WindowSettingError> {
+        self.imp.inhibit_system_shortcuts(inhibit).await
+    }
+
+    pub async fn confine_pointer(&self, region: Option<Rect>) -> Result<(), WindowSettingError> {
+        self.imp.confine_pointer(region).await
+    }
+
+    pub async fn release_pointer(&self) -> Result<(), WindowSettingError> {
+        self.imp.release_pointer().await
+    }
+
+    pub async fn set_preview_image(
+        &self,
+        image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        self.imp.set_preview_image(image).await
+    }
+
+    pub async fn set_icon(&self, icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        self.imp.set_icon(icon).await
+    }
+
+    pub async fn set_badge(&self, label: Option<String>) -> Result<(), WindowSettingError> {
+        self.imp.set_badge(label).await
+    }
+
+    pub async fn set_progress(&self, progress: Option<f64>) -> Result<(), WindowSettingError> {
+        self.imp.set_progress(progress).await
+    }
+
+    pub async fn set_cursor(&self, cursor: Cursor) -> Result<(), WindowSettingError> {
+        self.imp.set_cursor(cursor).await
+    }
+
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        self.imp.set_virtual_keyboard_visible(visible).await
+    }
+
+    pub async fn set_decorations(&self, mode: DecorationsMode) -> Result<(), WindowSettingError> {
+        self.imp.set_decorations(mode).await
+    }
+
+    pub async fn set_decoration_theme(
+        &self,
+        theme: DecorationTheme,
+    ) -> Result<(), WindowSettingError> {
+        self.imp.set_decoration_theme(theme).await
+    }
+
+    pub async fn set_resize_border(&self, width: f64) -> Result<(), WindowSettingError> {
+        self.imp.set_resize_border(width).await
+    }
+
+    pub async fn begin_sheet(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        self.imp.begin_sheet(sheet.imp.as_ref()).await
+    }
+
+    pub async fn show_modal(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        self.imp.show_modal(sheet.imp.as_ref()).await
+    }
+
+    pub async fn set_resizable(&self, resizable: bool) -> Result<(), WindowSettingError> {
+        self.imp.set_resizable(resizable).await
+    }
+
+    pub async fn set_size_limits(
+        &self,
+        min: Option<Size>,
+        max: Option<Size>,
+    ) -> Result<(), WindowSettingError> {
+        self.imp.set_size_limits(min, max).await
+    }
+
+    pub async fn set_click_through(&self, click_through: bool) -> Result<(), WindowSettingError> {
+        self.imp.set_click_through(click_through).await
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn update_accessibility_tree(
+        &self,
+        update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        self.imp.update_accessibility_tree(update).await
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn set_accessibility_action_handler(
+        &self,
+        on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> Result<(), WindowSettingError> {
+        self.imp.set_accessibility_action_handler(on_action).await
+    }
+
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        self.imp.focus().await
+    }
+
+    pub async fn is_focused(&self) -> bool {
+        self.imp.is_focused().await
+    }
+
+    pub async fn set_always_on_top(&self, on_top: bool) -> Result<(), WindowSettingError> {
+        self.imp.set_always_on_top(on_top).await
+    }
+
+    pub async fn set_background(&self, color: Color) -> Result<(), WindowSettingError> {
+        self.imp.set_background(color).await
+    }
+
+    pub async fn set_maximized(&self, maximized: bool) -> Result<(), WindowSettingError> {
+        self.imp.set_maximized(maximized).await
+    }
+
+    pub async fn set_transparent(&self, transparent: bool) -> Result<(), WindowSettingError> {
+        self.imp.set_transparent(transparent).await
+    }
+
+    pub async fn set_parent(&self, parent: &Window) -> Result<(), WindowSettingError> {
+        self.imp.set_parent(parent.imp.as_ref()).await
+    }
+
+    pub async fn input_handle(&self) -> Option<usize> {
+        self.imp.input_handle().await
+    }
+
+    pub async fn native_handle(&self) -> Option<NativeHandle> {
+        self.imp.native_handle().await
+    }
+
+    pub fn event_sender(&self) -> WindowEventSender {
+        self.imp.event_sender()
+    }
+
+    pub async fn normal_bounds(&self) -> Option<(Option<Position>, Size)> {
+        None
+    }
+
+    pub async fn outer_position(&self) -> Option<Position> {
+        self.imp.outer_position().await
+    }
+
+    pub fn request_frame(&self, frame: FrameCallback) {
+        self.imp.request_frame(frame)
+    }
+
+    pub async fn scale_factor(&self) -> f64 {
+        self.imp.scale_factor().await
+    }
+
+    pub async fn debug_state(&self) -> WindowDebugState {
+        self.imp.debug_state().await
+    }
+
+    pub async fn start_drag(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+        reporter: DragReporter,
+    ) -> Result<(), DragError> {
+        self.imp
+            .start_drag_with_actions(data, image, reporter)
+            .await
+    }
+}
+
+#[derive(Debug)]
+pub struct Surface {
+    imp: Box<dyn BackendSurface>,
+}
+
+impl Surface {
+    pub async fn size_scale(&self) -> (Size, f64) {
+        self.imp.size_scale().await
+    }
+
+    pub fn size_main(&self) -> (Size, f64) {
+        self.imp.size_main()
+    }
+
+    pub fn raw_window_handle(&self) -> RawWindowHandle {
+        self.imp.raw_window_handle()
+    }
+
+    pub fn raw_display_handle(&self) -> RawDisplayHandle {
+        self.imp.raw_display_handle()
+    }
+
+    pub fn size_update<F: Fn(Size) + Send + 'static>(&mut self, update: F) {
+        self.imp.size_update(Box::new(update))
+    }
+
+    pub fn frame_requester(&self) -> FrameRequester {
+        self.imp.frame_requester()
+    }
+
+    pub fn damage(&self, rects: &[Rect]) {
+        self.imp.damage(rects)
+    }
+}
+
+pub fn is_main_thread() -> bool {
+    backend().is_main_thread()
+}
+
+pub fn run_main_thread<F: FnOnce() + Send + 'static>(
+    closure: F,
+) -> Result<(), crate::application::MainError> {
+    backend().run_main_thread(Box::new(closure));
+    Ok(())
+}
+
+pub fn on_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
+    backend().on_main_thread(Box::new(closure))
+}
+
+pub fn on_main_thread_after<F: FnOnce() + Send + 'static>(delay: std::time::Duration, closure: F) {
+    backend().on_main_thread_after(delay, Box::new(closure))
+}
+
+pub fn stop_main_thread() {
+    backend().stop_main_thread()
+}
+
+pub async fn alert(message: String) {
+    backend().alert(message).await
+}
+
+pub fn audio_hint_capabilities() -> AudioHintCapabilities {
+    AudioHintCapabilities {
+        avoid_ducking: HintSupport::Unsupported,
+        background_audio: HintSupport::Unsupported,
+    }
+}
+
+pub async fn set_audio_hints(_hints: AudioHints) -> Result<(), AudioHintError> {
+    Ok(())
+}
+
+pub fn performance_hint_capabilities() -> PerformanceHintCapabilities {
+    PerformanceHintCapabilities {
+        prefer_discrete_gpu: HintSupport::Unsupported,
+        high_process_priority: HintSupport::Unsupported,
+        disable_power_throttling: HintSupport::Unsupported,
+    }
+}
+
+pub async fn set_performance_hints(_hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    Ok(())
+}
+
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    Err(PickColorError::Unsupported)
+}
+
+pub async fn post_notification(_notification: Notification) -> Result<(), NotificationError> {
+    Err(NotificationError::Unsupported)
+}
+
+pub fn watch_clipboard() -> Result<(), ClipboardError> {
+    Err(ClipboardError::Unsupported)
+}
+
+pub fn unwatch_clipboard() {}
+
+pub async fn watch_power() -> Result<(), PowerError> {
+    Err(PowerError::Unsupported)
+}
+
+pub fn unwatch_power() {}
+
+#[derive(Debug)]
+pub struct TrayIcon(());
+
+impl TrayIcon {
+    pub async fn new(_id: u64, _state: TrayState) -> Result<Self, TrayError> {
+        Err(TrayError::Unsupported)
+    }
+
+    pub async fn update(&self, _state: TrayState) -> Result<(), TrayError> {
+        Err(TrayError::Unsupported)
+    }
+}
+
+pub async fn capture_permission_state() -> CapturePermission {
+    CapturePermission::Unavailable
+}
+
+pub async fn request_capture_permission() -> CapturePermission {
+    CapturePermission::Unavailable
+}
+
+pub async fn set_activation_policy(_policy: ActivationPolicy) -> Result<(), ActivationPolicyError> {
+    Err(ActivationPolicyError::Unsupported)
+}
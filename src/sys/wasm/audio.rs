@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Audio hints.  Browsers don't duck pages, and whether a background tab keeps playing is up
+//! to the browser's autoplay and throttling policy.
+
+use crate::audio::{AudioHintCapabilities, AudioHintError, AudioHints, HintSupport};
+
+pub fn audio_hint_capabilities() -> AudioHintCapabilities {
+    AudioHintCapabilities {
+        avoid_ducking: HintSupport::NotNeeded,
+        background_audio: HintSupport::Unsupported,
+    }
+}
+
+pub async fn set_audio_hints(_hints: AudioHints) -> Result<(), AudioHintError> {
+    Ok(())
+}
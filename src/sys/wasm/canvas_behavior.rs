@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Suppressing the browser's own handling of input on the canvas.
+//!
+//! The listeners are installed with the canvas, and check the current behavior on each
+//! event, so it can change at runtime without adding or removing them.
+
+use super::CanvasHolder;
+use super::slim::OrAbort;
+use crate::window::CanvasBehavior;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::{Event, HtmlCanvasElement};
+
+fn prevent_when(
+    canvas: &HtmlCanvasElement,
+    behavior: &Rc<Cell<CanvasBehavior>>,
+    event: &str,
+    when: fn(CanvasBehavior) -> bool,
+) {
+    let behavior = behavior.clone();
+    let listener = Closure::<dyn FnMut(Event)>::new(move |event: Event| {
+        if when(behavior.get()) {
+            event.prevent_default();
+        }
+    });
+    canvas
+        .add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
+        .or_abort("Can't add canvas listener");
+    listener.forget();
+}
+
+/// `behavior` is the window's, which the listeners read.
+pub(super) fn install_listeners(canvas: &HtmlCanvasElement, behavior: Rc<Cell<CanvasBehavior>>) {
+    prevent_when(
+        canvas,
+        &behavior,
+        "contextmenu",
+        CanvasBehavior::suppresses_context_menu,
+    );
+    //a selection that starts on the canvas would extend over the page as the pointer drags
+    prevent_when(
+        canvas,
+        &behavior,
+        "selectstart",
+        CanvasBehavior::prevents_text_selection,
+    );
+    //listeners on elements (unlike the document) aren't passive by default, so this works
+    prevent_when(
+        canvas,
+        &behavior,
+        "touchmove",
+        CanvasBehavior::prevents_touch_scroll,
+    );
+    apply_style(canvas, behavior.get());
+}
+
+/// Call on the main thread.
+pub(super) fn set(holder: &CanvasHolder, behavior: CanvasBehavior) {
+    holder.behavior.set(behavior);
+    if let Some(canvas) = &holder.canvas {
+        apply_style(canvas, behavior);
+    }
+}
+
+fn apply_style(canvas: &HtmlCanvasElement, behavior: CanvasBehavior) {
+    let style = canvas.style();
+    let user_select = if behavior.prevents_text_selection() {
+        "none"
+    } else {
+        ""
+    };
+    _ = style.set_property("user-select", user_select);
+    //browsers decide whether to scroll before touchmove fires, so say so up front too
+    let touch_action = if behavior.prevents_touch_scroll() {
+        "none"
+    } else {
+        ""
+    };
+    _ = style.set_property("touch-action", touch_action);
+}
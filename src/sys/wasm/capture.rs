@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Screen capture permission.  Browsers ask the user each time `getDisplayMedia` is called.
+
+use crate::application::CapturePermission;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen(inline_js = r#"
+export function hasDisplayMedia() {
+  return typeof navigator !== 'undefined'
+    && !!navigator.mediaDevices
+    && typeof navigator.mediaDevices.getDisplayMedia === 'function';
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = hasDisplayMedia)]
+    fn has_display_media() -> bool;
+}
+
+pub async fn capture_permission_state() -> CapturePermission {
+    //workers have a navigator, but no mediaDevices
+    let available = crate::application::on_main_thread(
+        "capture_permission_state".to_string(),
+        has_display_media,
+    )
+    .await;
+    if available {
+        CapturePermission::AskEachTime
+    } else {
+        CapturePermission::Unavailable
+    }
+}
+
+pub async fn request_capture_permission() -> CapturePermission {
+    capture_permission_state().await
+}
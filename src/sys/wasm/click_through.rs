@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Letting input through the canvas to the page below, with CSS `pointer-events`.
+
+use super::CanvasHolder;
+
+/// Call on the main thread.
+pub(super) fn set(holder: &CanvasHolder, click_through: bool) {
+    let pointer_events = if click_through { "none" } else { "" };
+    if let Some(canvas) = &holder.canvas {
+        _ = canvas
+            .style()
+            .set_property("pointer-events", pointer_events);
+    }
+}
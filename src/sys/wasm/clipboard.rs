@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Clipboard change notifications.
+//!
+//! Pages hear about their own `copy` and `cut`; only browsers with the `clipboardchange` event
+//! tell us about changes made elsewhere.
+
+use crate::clipboard::ClipboardError;
+use std::cell::RefCell;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::js_sys::Function;
+
+#[wasm_bindgen(inline_js = r#"
+export function watchClipboard(callback) {
+  if (typeof document === 'undefined') {
+    return null;
+  }
+  //copy and cut fire before the clipboard is written
+  const later = () => setTimeout(callback, 0);
+  const targets = [[document, 'copy'], [document, 'cut']];
+  if (typeof navigator !== 'undefined' && navigator.clipboard
+      && 'onclipboardchange' in navigator.clipboard) {
+    targets.push([navigator.clipboard, 'clipboardchange']);
+  }
+  for (const [target, type] of targets) {
+    target.addEventListener(type, later);
+  }
+  return () => {
+    for (const [target, type] of targets) {
+      target.removeEventListener(type, later);
+    }
+  };
+}
+"#)]
+extern "C" {
+    /// Calls `callback` on clipboard changes, and returns a function that stops, or `None` in
+    /// a worker.
+    #[wasm_bindgen(js_name = watchClipboard)]
+    fn watch(callback: &Closure<dyn FnMut()>) -> Option<Function>;
+}
+
+/// The callback, and the function that removes its listeners.
+struct Watch {
+    _callback: Closure<dyn FnMut()>,
+    stop: Function,
+}
+
+thread_local! {
+    static WATCH: RefCell<Option<Watch>> = const { RefCell::new(None) };
+}
+
+/// Call on the main thread.
+pub fn watch_clipboard() -> Result<(), ClipboardError> {
+    WATCH.with_borrow_mut(|watching| {
+        if watching.is_some() {
+            return Ok(());
+        }
+        let callback = Closure::<dyn FnMut()>::new(crate::clipboard::notify);
+        //workers have no page to copy from
+        let stop = watch(&callback).ok_or(ClipboardError::Unsupported)?;
+        *watching = Some(Watch {
+            _callback: callback,
+            stop,
+        });
+        Ok(())
+    })
+}
+
+/// Call on the main thread.
+pub fn unwatch_clipboard() {
+    if let Some(watch) = WATCH.take() {
+        _ = watch.stop.call0(&wasm_bindgen::JsValue::NULL);
+    }
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Asking before the page unloads, the closest a browser comes to closing the window.
+
+use super::slim::OrAbort;
+use crate::window::{CloseBehavior, CloseResponse};
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::BeforeUnloadEvent;
+
+type AskFn = Arc<dyn Fn() -> CloseResponse + Send + Sync>;
+
+thread_local! {
+    static ASK: RefCell<Option<AskFn>> = const { RefCell::new(None) };
+    static LISTENER_INSTALLED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn install_listener() {
+    let listener = Closure::<dyn FnMut(BeforeUnloadEvent)>::new(|event: BeforeUnloadEvent| {
+        //clone out, so the function can change the behavior itself
+        let Some(ask) = ASK.with_borrow(|ask| ask.clone()) else {
+            return;
+        };
+        if ask() == CloseResponse::Keep {
+            event.prevent_default();
+            //older browsers only prompt if this is set; none of them show the text
+            event.set_return_value("unsaved changes");
+        }
+    });
+    web_sys::window()
+        .or_abort("no window")
+        .add_event_listener_with_callback("beforeunload", listener.as_ref().unchecked_ref())
+        .or_abort("Can't add beforeunload listener");
+    listener.forget();
+}
+
+/// Call on the main thread.
+pub(super) fn set(behavior: CloseBehavior) {
+    let ask = match behavior {
+        CloseBehavior::Ask(f) => Some(f),
+        //browsers don't give the canvas a close button
+        _ => None,
+    };
+    //a beforeunload listener can keep the page out of the back/forward cache, so wait for one
+    if ask.is_some() && !LISTENER_INSTALLED.replace(true) {
+        install_listener();
+    }
+    ASK.replace(ask);
+}
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The cursor over the canvas, as its CSS `cursor`.
+
+use super::CanvasHolder;
+use crate::window::Cursor;
+
+fn css(cursor: Cursor) -> &'static str {
+    match cursor {
+        //the page's own cursor, which is normally the arrow
+        Cursor::Arrow => "",
+        Cursor::Text => "text",
+        Cursor::Hand => "pointer",
+        Cursor::Wait => "wait",
+        Cursor::Crosshair => "crosshair",
+        Cursor::NotAllowed => "not-allowed",
+        Cursor::ResizeHorizontal => "ew-resize",
+        Cursor::ResizeVertical => "ns-resize",
+        Cursor::Hidden => "none",
+    }
+}
+
+/// Call on the main thread.
+pub(super) fn set(holder: &CanvasHolder, cursor: Cursor) {
+    holder.cursor.set(cursor);
+    if let Some(canvas) = &holder.canvas {
+        _ = canvas.style().set_property("cursor", css(cursor));
+    }
+}
+
+/// Call on the main thread.
+pub(super) fn get(holder: &CanvasHolder) -> Cursor {
+    holder.cursor.get()
+}
+
+#[cfg(test)]
+mod test {
+    use super::css;
+    use crate::window::Cursor;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn arrow_is_the_pages_cursor() {
+        assert_eq!(css(Cursor::Arrow), "");
+        assert_eq!(css(Cursor::Hidden), "none");
+        assert_eq!(css(Cursor::Hand), "pointer");
+    }
+}
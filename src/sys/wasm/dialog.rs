@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The color picker, via the `EyeDropper` API.  Chromium-based browsers have it; elsewhere
+//! picking is unsupported.
+
+use crate::dialog::{Color, PickColorError};
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_futures::js_sys::Promise;
+
+#[wasm_bindgen(inline_js = r#"
+export function openEyeDropper() {
+  if (typeof EyeDropper === 'undefined') {
+    return null;
+  }
+  return new EyeDropper().open().then((result) => result.sRGBHex);
+}
+"#)]
+extern "C" {
+    /// Resolves with the picked `#rrggbb` color, or `None` without `EyeDropper`.
+    #[wasm_bindgen(js_name = openEyeDropper)]
+    fn open_eye_dropper() -> Option<Promise>;
+}
+
+fn error_from_js(error: JsValue) -> PickColorError {
+    let Some(error) = error.dyn_ref::<web_sys::js_sys::Error>() else {
+        return PickColorError::Platform(format!("{error:?}"));
+    };
+    //the user pressed Escape
+    if error.name() == "AbortError" {
+        PickColorError::Cancelled
+    } else {
+        PickColorError::Platform(error.message().into())
+    }
+}
+
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    let (sender, fut) = r#continue::continuation();
+    crate::application::on_main_thread("pick_color".to_string(), move || {
+        let Some(promise) = open_eye_dropper() else {
+            sender.send(Err(PickColorError::Unsupported));
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = match JsFuture::from(promise).await {
+                Ok(hex) => hex
+                    .as_string()
+                    .and_then(|hex| Color::from_hex(&hex))
+                    .ok_or_else(|| {
+                        PickColorError::Platform(format!("unexpected EyeDropper result {hex:?}"))
+                    }),
+                Err(e) => Err(error_from_js(e)),
+            };
+            sender.send(result);
+        });
+    })
+    .await;
+    fut.await
+}
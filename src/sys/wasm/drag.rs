@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Outgoing drag-and-drop via the canvas's `dragstart` event.
+//!
+//! Browsers only let a page start a drag from inside their own `dragstart` handler, so
+//! `start_drag` stashes the payload and makes the canvas draggable.  The browser's next drag
+//! gesture on the canvas picks it up; after that the canvas goes back to not being draggable.
+
+use super::Window;
+use super::slim::OrAbort;
+use crate::drag::{
+    DragAction, DragActions, DragData, DragError, DragImage, DragOutcome, DragReporter,
+};
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::js_sys;
+use web_sys::{
+    CanvasRenderingContext2d, DataTransfer, DragEvent, Element, File, FilePropertyBag,
+    HtmlCanvasElement, ImageData, window,
+};
+
+/// A drag waiting for the browser's `dragstart`.
+struct PendingDrag {
+    data: DragData,
+    image: Option<DragImage>,
+    reporter: DragReporter,
+}
+
+thread_local! {
+    static PENDING_DRAG: RefCell<Option<PendingDrag>> = const { RefCell::new(None) };
+    /// The drag the browser is running, until `dragend`.
+    static ACTIVE_DRAG: RefCell<Option<DragReporter>> = const { RefCell::new(None) };
+    /// Element passed to `setDragImage`; it has to stay in the DOM until the drag ends.
+    static DRAG_IMAGE_ELEMENT: RefCell<Option<Element>> = const { RefCell::new(None) };
+}
+
+pub(super) fn install_listeners(canvas: &HtmlCanvasElement) {
+    let dragstart = Closure::<dyn FnMut(DragEvent)>::new(on_dragstart);
+    canvas
+        .add_event_listener_with_callback("dragstart", dragstart.as_ref().unchecked_ref())
+        .or_abort("Can't add dragstart listener");
+    dragstart.forget();
+
+    let drag_canvas = canvas.clone();
+    let dragend = Closure::<dyn FnMut(DragEvent)>::new(move |event: DragEvent| {
+        drag_canvas.set_draggable(false);
+        if let Some(element) = DRAG_IMAGE_ELEMENT.take() {
+            element.remove();
+        }
+        if let Some(reporter) = ACTIVE_DRAG.take() {
+            let effect = event.data_transfer().map(|transfer| transfer.drop_effect());
+            reporter.finish(match effect.as_deref() {
+                Some("copy") => DragOutcome::Dropped(DragAction::Copy),
+                Some("move") => DragOutcome::Dropped(DragAction::Move),
+                Some("link") => DragOutcome::Dropped(DragAction::Link),
+                Some(_) => DragOutcome::Cancelled,
+                None => DragOutcome::Unknown,
+            });
+        }
+    });
+    canvas
+        .add_event_listener_with_callback("dragend", dragend.as_ref().unchecked_ref())
+        .or_abort("Can't add dragend listener");
+    dragend.forget();
+}
+
+fn on_dragstart(event: DragEvent) {
+    let Some(PendingDrag {
+        data,
+        image,
+        reporter,
+    }) = PENDING_DRAG.take()
+    else {
+        //nobody asked for a drag
+        event.prevent_default();
+        return;
+    };
+    let Some(transfer) = event.data_transfer() else {
+        return;
+    };
+    if let DragData::Bytes { mime, bytes } = data {
+        set_transfer_data(&transfer, &mime, bytes);
+    }
+    transfer.set_effect_allowed(effect_allowed(reporter.actions()));
+    ACTIVE_DRAG.replace(Some(reporter));
+    if let Some(image) = image
+        && let Some(element) = drag_image_element(&image)
+    {
+        transfer.set_drag_image(&element, 0, 0);
+        DRAG_IMAGE_ELEMENT.replace(Some(element));
+    }
+}
+
+/// The `effectAllowed` keyword for `actions`.  Browsers have no ask.
+fn effect_allowed(actions: DragActions) -> &'static str {
+    let copy = actions.contains(DragAction::Copy);
+    let moves = actions.contains(DragAction::Move);
+    let link = actions.contains(DragAction::Link);
+    match (copy, moves, link) {
+        (true, true, true) => "all",
+        (true, true, false) => "copyMove",
+        (true, false, true) => "copyLink",
+        (false, true, true) => "linkMove",
+        (true, false, false) => "copy",
+        (false, true, false) => "move",
+        (false, false, true) => "link",
+        (false, false, false) => "none",
+    }
+}
+
+fn set_transfer_data(transfer: &DataTransfer, mime: &str, bytes: Vec<u8>) {
+    match String::from_utf8(bytes) {
+        Ok(text) => {
+            if let Err(e) = transfer.set_data(mime, &text) {
+                logwise::warn_sync!("Can't set drag data: {e}", e = logwise::privacy::LogIt(e));
+            }
+        }
+        Err(e) => {
+            //DataTransfer only holds strings; binary payloads travel as a File
+            let array = js_sys::Uint8Array::from(e.as_bytes());
+            let parts = js_sys::Array::of1(&array);
+            let options = FilePropertyBag::new();
+            options.set_type(mime);
+            let added = File::new_with_u8_array_sequence_and_options(&parts, "data", &options)
+                .and_then(|file| transfer.items().add_with_file(&file));
+            if let Err(e) = added {
+                logwise::warn_sync!("Can't set drag data: {e}", e = logwise::privacy::LogIt(e));
+            }
+        }
+    }
+}
+
+fn drag_image_element(image: &DragImage) -> Option<Element> {
+    let document = window()?.document()?;
+    let canvas: HtmlCanvasElement = document.create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(image.width());
+    canvas.set_height(image.height());
+    //setDragImage wants something rendered, so park it offscreen
+    let style = canvas.style();
+    style.set_property("position", "fixed").ok()?;
+    style.set_property("left", "-10000px").ok()?;
+    style.set_property("top", "0").ok()?;
+    let context: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    //copy out of (possibly shared) wasm memory; ImageData rejects SharedArrayBuffer views
+    let pixels = js_sys::Uint8ClampedArray::new_with_length(image.rgba().len() as u32);
+    pixels.copy_from(image.rgba());
+    let image_data =
+        ImageData::new_with_js_u8_clamped_array_and_sh(&pixels, image.width(), image.height())
+            .ok()?;
+    context.put_image_data(&image_data, 0.0, 0.0).ok()?;
+    document.body()?.append_child(&canvas).ok()?;
+    Some(canvas.into())
+}
+
+impl Window {
+    pub async fn start_drag(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+        reporter: DragReporter,
+    ) -> Result<(), DragError> {
+        if matches!(data, DragData::Paths(_)) {
+            return Err(DragError::Unsupported);
+        }
+        let id = self.id;
+        crate::application::on_main_thread("Window::start_drag".to_string(), move || {
+            super::with_holder(id, |holder| {
+                //a virtual window has nothing to drag from
+                let canvas = holder.canvas.as_ref().ok_or(DragError::Unsupported)?;
+                let replaced = PENDING_DRAG.replace(Some(PendingDrag {
+                    data,
+                    image,
+                    reporter,
+                }));
+                //that one never started
+                if let Some(replaced) = replaced {
+                    replaced.reporter.finish(DragOutcome::Cancelled);
+                }
+                canvas.set_draggable(true);
+                Ok(())
+            })
+        })
+        .await
+    }
+}
@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Hosting the window in a canvas the page already has; see `WindowBuilder::canvas_selector`.
+//!
+//! The page lays such a canvas out, so it's sized by its element rather than the viewport, and
+//! a `ResizeObserver` on it stands in for the window's `resize` event.
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::js_sys::Function;
+use web_sys::{Document, HtmlCanvasElement};
+
+#[wasm_bindgen(inline_js = r#"
+export function observeResize(element, callback) {
+  if (typeof ResizeObserver === 'undefined') {
+    return null;
+  }
+  const observer = new ResizeObserver(() => callback());
+  observer.observe(element);
+  return () => observer.disconnect();
+}
+"#)]
+extern "C" {
+    /// Calls `callback` whenever `element` is resized, and returns a function that stops, or
+    /// `None` where there's no `ResizeObserver`.
+    #[wasm_bindgen(js_name = observeResize)]
+    fn observe_resize(element: &JsValue, callback: &Closure<dyn FnMut()>) -> Option<Function>;
+}
+
+/// The canvas `selector` matches, or why there isn't one.  Call on the main thread.
+pub(super) fn find(document: &Document, selector: &str) -> Result<HtmlCanvasElement, String> {
+    let element = document
+        .query_selector(selector)
+        .map_err(|e| format!("invalid selector {selector:?}: {e:?}"))?
+        .ok_or_else(|| format!("nothing matches {selector:?}"))?;
+    element
+        .dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| format!("{selector:?} isn't a canvas"))
+}
+
+/// Runs `on_resize` as `canvas` is resized, returning the function that stops.  Call on the
+/// main thread.
+pub(super) fn install(
+    canvas: &HtmlCanvasElement,
+    on_resize: &Closure<dyn FnMut()>,
+) -> Option<Function> {
+    let stop = observe_resize(canvas, on_resize);
+    if stop.is_none() {
+        logwise::warn_sync!("No ResizeObserver, so the canvas's size won't be followed");
+    }
+    stop
+}
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Window events.
+//!
+//! Each window has its own sender, for resizes of its canvas.  Focus, scale and the state
+//! flags belong to the page, so those go to every window.
+
+use super::CANVAS_HOLDERS;
+use super::slim::OrAbort;
+use crate::window::{WindowEvent, WindowStateFlags};
+use std::cell::Cell;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen(inline_js = r#"
+export function watchPixelRatio(callback) {
+  if (typeof window === 'undefined' || !window.matchMedia) {
+    return;
+  }
+  //the query matches the current ratio, so it changes when that does; then we ask again
+  const arm = () => {
+    window.matchMedia(`(resolution: ${window.devicePixelRatio}dppx)`).addEventListener(
+      'change',
+      () => {
+        callback(window.devicePixelRatio);
+        arm();
+      },
+      { once: true },
+    );
+  };
+  arm();
+}
+"#)]
+extern "C" {
+    /// Calls `callback` with `devicePixelRatio` each time it changes.
+    #[wasm_bindgen(js_name = watchPixelRatio)]
+    fn watch_pixel_ratio(callback: &Closure<dyn FnMut(f64)>);
+}
+
+thread_local! {
+    static INSTALLED: Cell<bool> = const { Cell::new(false) };
+    /// Whether the page has focus, per its last `focus` or `blur`.
+    static ACTIVATED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sends `event` to every window.  Call on the main thread.
+fn broadcast(event: WindowEvent) {
+    CANVAS_HOLDERS.with_borrow(|holders| {
+        for holder in holders.values() {
+            holder.events.send(event.clone());
+        }
+    });
+}
+
+/// Gives every window the page's state, which each sends on if it changed.  Call on the main
+/// thread.
+fn broadcast_state() {
+    let document = web_sys::window().and_then(|w| w.document());
+    let flags = WindowStateFlags {
+        fullscreen: document
+            .as_ref()
+            .and_then(|d| d.fullscreen_element())
+            .is_some(),
+        activated: ACTIVATED.get(),
+        suspended: document.is_some_and(|d| d.hidden()),
+        ..Default::default()
+    };
+    CANVAS_HOLDERS.with_borrow(|holders| {
+        for holder in holders.values() {
+            holder.events.update_state(flags);
+        }
+    });
+}
+
+fn send_on(window: &web_sys::Window, name: &str, event: WindowEvent) {
+    let listener = Closure::<dyn FnMut()>::new(move || {
+        match event {
+            WindowEvent::Focused => ACTIVATED.set(true),
+            WindowEvent::Unfocused => ACTIVATED.set(false),
+            _ => {}
+        }
+        broadcast(event.clone());
+        broadcast_state();
+    });
+    window
+        .add_event_listener_with_callback(name, listener.as_ref().unchecked_ref())
+        .or_abort("Can't add window listener");
+    listener.forget();
+}
+
+/// Only the first call does anything; the listeners serve every window.
+pub(super) fn install_listeners(window: &web_sys::Window) {
+    if INSTALLED.replace(true) {
+        return;
+    }
+    //the page, not the canvas: a canvas without a tabindex never takes focus itself
+    send_on(window, "focus", WindowEvent::Focused);
+    send_on(window, "blur", WindowEvent::Unfocused);
+    if let Some(document) = window.document() {
+        ACTIVATED.set(document.has_focus().unwrap_or(false));
+        let state_changed = Closure::<dyn FnMut()>::new(broadcast_state);
+        for name in ["fullscreenchange", "visibilitychange"] {
+            document
+                .add_event_listener_with_callback(name, state_changed.as_ref().unchecked_ref())
+                .or_abort("Can't add document listener");
+        }
+        state_changed.forget();
+    }
+    //moving to another display, or zooming the page
+    let scale_changed =
+        Closure::<dyn FnMut(f64)>::new(|scale| broadcast(WindowEvent::ScaleChanged(scale)));
+    watch_pixel_ratio(&scale_changed);
+    scale_changed.forget();
+}
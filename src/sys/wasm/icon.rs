@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The page's favicon, which stands in for the window's icon.
+//!
+//! If the page has its own `<link rel="icon">` we swap its `href` for a data URL, and put the
+//! original back when the icon is cleared; otherwise we add a link, and remove it again.
+
+use crate::window::{IconData, WindowSettingError};
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+use web_sys::js_sys;
+use web_sys::{CanvasRenderingContext2d, Document, Element, HtmlCanvasElement, ImageData};
+
+/// The link we changed, and its `href` before, or `None` if we added it.
+struct Favicon {
+    link: Element,
+    original_href: Option<String>,
+}
+
+thread_local! {
+    static FAVICON: RefCell<Option<Favicon>> = const { RefCell::new(None) };
+}
+
+fn platform_error(e: wasm_bindgen::JsValue) -> WindowSettingError {
+    WindowSettingError::Platform(format!("{e:?}"))
+}
+
+/// The icon as a PNG data URL.
+pub(super) fn data_url(document: &Document, icon: &IconData) -> Result<String, WindowSettingError> {
+    let canvas: HtmlCanvasElement = document
+        .create_element("canvas")
+        .map_err(platform_error)?
+        .dyn_into()
+        .map_err(|_| WindowSettingError::Platform("not a canvas".to_string()))?;
+    canvas.set_width(icon.width());
+    canvas.set_height(icon.height());
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")
+        .map_err(platform_error)?
+        .ok_or(WindowSettingError::Unsupported)?
+        .dyn_into()
+        .map_err(|_| WindowSettingError::Platform("not a 2d context".to_string()))?;
+    //copy out of (possibly shared) wasm memory; ImageData rejects SharedArrayBuffer views
+    let pixels = js_sys::Uint8ClampedArray::new_with_length(icon.rgba().len() as u32);
+    pixels.copy_from(icon.rgba());
+    let image_data =
+        ImageData::new_with_js_u8_clamped_array_and_sh(&pixels, icon.width(), icon.height())
+            .map_err(platform_error)?;
+    context
+        .put_image_data(&image_data, 0.0, 0.0)
+        .map_err(platform_error)?;
+    canvas.to_data_url().map_err(platform_error)
+}
+
+/// Call on the main thread.
+pub(super) fn set(icon: Option<IconData>) -> Result<(), WindowSettingError> {
+    //workers have no page to put an icon on
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return Err(WindowSettingError::Unsupported);
+    };
+    FAVICON.with_borrow_mut(|favicon| {
+        let Some(icon) = icon else {
+            if let Some(favicon) = favicon.take() {
+                match favicon.original_href {
+                    Some(href) => favicon
+                        .link
+                        .set_attribute("href", &href)
+                        .map_err(platform_error)?,
+                    None => favicon.link.remove(),
+                }
+            }
+            return Ok(());
+        };
+        let url = data_url(&document, &icon)?;
+        if favicon.is_none() {
+            let existing = document
+                .query_selector("link[rel~='icon']")
+                .map_err(platform_error)?;
+            *favicon = Some(match existing {
+                Some(link) => Favicon {
+                    original_href: Some(link.get_attribute("href").unwrap_or_default()),
+                    link,
+                },
+                None => {
+                    let link = document.create_element("link").map_err(platform_error)?;
+                    link.set_attribute("rel", "icon").map_err(platform_error)?;
+                    let head = document
+                        .query_selector("head")
+                        .map_err(platform_error)?
+                        .ok_or(WindowSettingError::Unsupported)?;
+                    head.append_child(&link).map_err(platform_error)?;
+                    Favicon {
+                        link,
+                        original_href: None,
+                    }
+                }
+            });
+        }
+        let favicon = favicon.as_ref().expect("favicon was just set");
+        favicon
+            .link
+            .set_attribute("href", &url)
+            .map_err(platform_error)
+    })
+}
@@ -0,0 +1,1160 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::application::DispatchSlice;
+use crate::application::time::Instant;
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::Color;
+use crate::window::{
+    CanvasBehavior, CloseBehavior, CreateOptions, Cursor, DecorationTheme, DecorationsMode,
+    FrameCallback, FrameRequester, FrameTiming, IconData, NativeHandle, PreviewImage,
+    WindowDebugState, WindowEvent, WindowEventSender, WindowSettingError, WindowState,
+};
+use logwise::Level;
+use logwise::context::Context;
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle, WebDisplayHandle, WebWindowHandle};
+use send_cells::send_cell::SendCell;
+use slim::OrAbort;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::js_sys::Promise;
+use web_sys::js_sys::{Function, TypeError};
+use web_sys::{HtmlCanvasElement, window};
+
+mod audio;
+mod canvas_behavior;
+mod capture;
+mod click_through;
+mod clipboard;
+mod close;
+mod cursor;
+mod dialog;
+mod drag;
+mod embed;
+mod events;
+mod icon;
+mod notification;
+mod shortcuts;
+mod slim;
+mod virtual_keyboard;
+
+pub use audio::{audio_hint_capabilities, set_audio_hints};
+pub use capture::{capture_permission_state, request_capture_permission};
+pub use clipboard::{unwatch_clipboard, watch_clipboard};
+pub use dialog::pick_color;
+pub use notification::post_notification;
+
+//pages can't see the displays or the battery; the page being hidden is WindowEvent::Occluded
+pub async fn watch_power() -> Result<(), crate::power::PowerError> {
+    Err(crate::power::PowerError::Unsupported)
+}
+
+pub fn unwatch_power() {}
+
+/// Pages have no tray, so icons are accepted and shown nowhere, which spares apps that run
+/// on the desktop too from special-casing the web.
+#[derive(Debug)]
+pub struct TrayIcon(());
+
+impl TrayIcon {
+    pub async fn new(
+        _id: u64,
+        _state: crate::tray::TrayState,
+    ) -> Result<Self, crate::tray::TrayError> {
+        Ok(TrayIcon(()))
+    }
+
+    pub async fn update(
+        &self,
+        _state: crate::tray::TrayState,
+    ) -> Result<(), crate::tray::TrayError> {
+        Ok(())
+    }
+}
+
+pub async fn set_activation_policy(
+    _policy: crate::application::ActivationPolicy,
+) -> Result<(), crate::application::ActivationPolicyError> {
+    Err(crate::application::ActivationPolicyError::Unsupported)
+}
+
+//the browser schedules the page, and WebGPU picks adapters by powerPreference
+pub fn performance_hint_capabilities() -> crate::application::PerformanceHintCapabilities {
+    use crate::audio::HintSupport;
+    crate::application::PerformanceHintCapabilities {
+        prefer_discrete_gpu: HintSupport::Unsupported,
+        high_process_priority: HintSupport::Unsupported,
+        disable_power_throttling: HintSupport::Unsupported,
+    }
+}
+
+pub async fn set_performance_hints(
+    _hints: crate::application::PerformanceHints,
+) -> Result<(), crate::application::PerformanceHintError> {
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct Window {
+    /// The key for our canvas in `CANVAS_HOLDERS`, and its `data-raw-handle`.
+    id: u32,
+    events: WindowEventSender,
+}
+
+thread_local! {
+    /// Each window's canvas, by id.
+    static CANVAS_HOLDERS: RefCell<HashMap<u32, CanvasHolder>> = RefCell::new(HashMap::new());
+    /// Starts at 1, the address input reports when it can't tell which canvas an event went
+    /// to, so that goes to the first window.
+    static NEXT_ID: Cell<u32> = const { Cell::new(1) };
+}
+
+/// Runs `f` with window `id`'s canvas.  Call on the main thread.
+fn with_holder<R>(id: u32, f: impl FnOnce(&CanvasHolder) -> R) -> R {
+    CANVAS_HOLDERS.with_borrow(|holders| f(holders.get(&id).or_abort("no canvas")))
+}
+
+type SizeCallback = dyn Fn(Size) + Send + 'static;
+type SharedSizeCallback = Arc<Mutex<Option<Box<SizeCallback>>>>;
+
+enum MainThreadEvent {
+    Execute(Box<dyn FnOnce() + Send + 'static>),
+}
+
+static MAIN_THREAD_SENDER: OnceLock<continue_stream::Sender<MainThreadEvent>> = OnceLock::new();
+
+/**
+Last known size and scale of the canvas, shared between the main thread and surfaces.
+
+The resize observer bumps `epoch` whenever the browser reports a change.  A cached value
+is only returned when it was recorded at the current epoch, so readers on other threads
+can skip the main-thread hop as long as nothing has been resized since.
+*/
+#[derive(Debug, Default)]
+struct SizeCache {
+    epoch: AtomicU64,
+    value: Mutex<Option<(u64, Size, f64)>>,
+}
+
+impl SizeCache {
+    fn invalidate(&self) {
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+
+    /// Returns the cached value if it is still current.
+    fn get(&self) -> Option<(Size, f64)> {
+        let epoch = self.epoch();
+        match *self.value.lock().unwrap() {
+            Some((cached_epoch, size, scale)) if cached_epoch == epoch => Some((size, scale)),
+            _ => None,
+        }
+    }
+
+    /// Records a value that was read at `epoch`.
+    ///
+    /// If a resize happened in the meantime the value is stored but is already stale.
+    fn store(&self, epoch: u64, size: Size, scale: f64) {
+        *self.value.lock().unwrap() = Some((epoch, size, scale));
+    }
+}
+
+/// Size of a virtual window that was never given one.
+const HEADLESS_SIZE: Size = Size::new(800.0, 600.0);
+
+fn read_size_scale_main(id: u32) -> (Size, f64) {
+    let (explicit, embedded) = CANVAS_HOLDERS.with_borrow(|holders| {
+        let holder = holders.get(&id);
+        (
+            holder.and_then(|h| h.size.get()),
+            holder.filter(|h| h.embedded).and_then(|h| h.canvas.clone()),
+        )
+    });
+    let Some(w) = window() else {
+        //no page to fill, so a virtual window keeps the size it was given
+        return (explicit.unwrap_or(HEADLESS_SIZE), 1.0);
+    };
+    let px = w.device_pixel_ratio();
+    if let Some(size) = explicit {
+        return (size, px);
+    }
+    //the page lays it out
+    if let Some(canvas) = embedded {
+        let size = Size::new(canvas.client_width() as f64, canvas.client_height() as f64);
+        return (size, px);
+    }
+    let width = w
+        .inner_width()
+        .or_abort("No width?")
+        .as_f64()
+        .or_abort("No width?");
+    let height = w
+        .inner_height()
+        .or_abort("No height?")
+        .as_f64()
+        .or_abort("No height?");
+
+    (Size::new(width, height), px)
+}
+
+/**
+A window in the page: a canvas, or a virtual window where there's no DOM.
+
+Node.js has no `window` or `document`, so [`CanvasHolder::new_headless`] stands in.  Nothing is
+drawn, but size and visibility are tracked and resizes are reported as usual, so app logic can
+run in tests and tooling without a browser.
+*/
+struct CanvasHolder {
+    handle: WebWindowHandle,
+    /// `None` for a virtual window.
+    canvas: Option<Rc<HtmlCanvasElement>>,
+    closure_box: SharedSizeCallback,
+    size_cache: Arc<SizeCache>,
+    /// Set by `set_size`; otherwise the canvas fills the viewport.
+    size: Cell<Option<Size>>,
+    /// Only tracked for a virtual window; a canvas's style says.
+    visible: Cell<bool>,
+    /// The page's own canvas, which it sizes; see `embed.rs`.
+    embedded: bool,
+    events: WindowEventSender,
+    cursor: Cell<Cursor>,
+    /// Read by the canvas's listeners as events arrive.
+    behavior: Rc<Cell<CanvasBehavior>>,
+    /// Follows resizes until we're dropped.
+    on_resize: Option<Closure<dyn FnMut()>>,
+    /// Disconnects the `ResizeObserver` on an embedded canvas.
+    stop_observing: Option<Function>,
+}
+impl CanvasHolder {
+    fn new_headless(size: Size) -> CanvasHolder {
+        CanvasHolder {
+            handle: WebWindowHandle::new(next_id()),
+            canvas: None,
+            closure_box: Arc::new(Mutex::new(None)),
+            size_cache: Arc::new(SizeCache::default()),
+            size: Cell::new(Some(size)),
+            visible: Cell::new(true),
+            embedded: false,
+            events: WindowEventSender::default(),
+            cursor: Cell::new(Cursor::Arrow),
+            behavior: Rc::new(Cell::new(CanvasBehavior::new())),
+            on_resize: None,
+            stop_observing: None,
+        }
+    }
+
+    fn id(&self) -> u32 {
+        self.handle.id
+    }
+
+    /// Whether this is a virtual window.  Call on the main thread.
+    fn is_headless() -> bool {
+        window().is_none()
+    }
+
+    /// Hosts the window in the canvas `selector` matches, or in a new one filling the viewport.
+    fn new_main(selector: Option<&str>) -> CanvasHolder {
+        let window = window().or_abort("Can't get window");
+        let document = window.document().or_abort("Can't get document");
+        let existing = selector.and_then(|selector| match embed::find(&document, selector) {
+            Ok(canvas) => Some(canvas),
+            Err(e) => {
+                logwise::warn_sync!(
+                    "Can't use the page's canvas, so making one: {e}",
+                    e = logwise::privacy::LogIt(&e)
+                );
+                None
+            }
+        });
+        let embedded = existing.is_some();
+        let canvas = existing.unwrap_or_else(|| Self::create_canvas(&document));
+        Self::with_canvas(canvas, embedded)
+    }
+
+    fn create_canvas(document: &web_sys::Document) -> HtmlCanvasElement {
+        use web_sys::wasm_bindgen::__rt::IntoJsResult;
+        let element = document
+            .create_element("canvas")
+            .or_abort("Can't create canvas");
+        let html_element = web_sys::HtmlElement::from(
+            element
+                .into_js_result()
+                .or_abort("Can't create html element"),
+        );
+
+        let style = html_element.style();
+        style
+            .set_property("width", "100vw")
+            .or_abort("Can't set width");
+        style
+            .set_property("height", "100vh")
+            .or_abort("Can't set height");
+
+        web_sys::HtmlCanvasElement::from(html_element.into_js_result().or_abort("Can't get canvas"))
+    }
+
+    fn with_canvas(canvas: HtmlCanvasElement, embedded: bool) -> CanvasHolder {
+        let id = next_id();
+        let closure_box: SharedSizeCallback = Arc::new(Mutex::new(None));
+        let move_closure_box = closure_box.clone();
+        let size_cache = Arc::new(SizeCache::default());
+        let move_size_cache = size_cache.clone();
+        let events = WindowEventSender::default();
+        let move_events = events.clone();
+        let window = window().or_abort("Can't get window");
+        let document = window.document().or_abort("Can't get document");
+        canvas
+            .set_attribute("data-raw-handle", &id.to_string())
+            .or_abort("Can't set data-raw-handle");
+        let canvas_rc = Rc::new(canvas);
+        let canvas_weak = Rc::downgrade(&canvas_rc);
+        let closure = Closure::<dyn FnMut()>::new(move || {
+            move_size_cache.invalidate();
+            match canvas_weak.upgrade() {
+                None => { /* deallocated? */ }
+                Some(canvas) => {
+                    let width = canvas.width();
+                    let height = canvas.height();
+                    if let Some(closure) = move_closure_box.lock().unwrap().as_ref() {
+                        closure(Size::new(width as f64, height as f64));
+                    }
+                }
+            }
+            move_events.send(WindowEvent::Resized(read_size_scale_main(id).0));
+        });
+
+        let stop_observing = if embedded {
+            embed::install(&canvas_rc, &closure)
+        } else {
+            window
+                .add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref())
+                .or_abort("Can't add resize listener");
+            None
+        };
+        let behavior = Rc::new(Cell::new(CanvasBehavior::new()));
+        drag::install_listeners(&canvas_rc);
+        canvas_behavior::install_listeners(&canvas_rc, behavior.clone());
+        events::install_listeners(&window);
+
+        if !embedded {
+            document
+                .body()
+                .unwrap()
+                .append_child(canvas_rc.as_ref())
+                .or_abort("Can't append canvas to body");
+        }
+        CanvasHolder {
+            handle: WebWindowHandle::new(id),
+            canvas: Some(canvas_rc),
+            closure_box,
+            size_cache,
+            size: Cell::new(None),
+            visible: Cell::new(true),
+            embedded,
+            events,
+            cursor: Cell::new(Cursor::Arrow),
+            behavior,
+            on_resize: Some(closure),
+            stop_observing,
+        }
+    }
+
+    /// Adds us to the registry, returning the window.  Call on the main thread.
+    fn register(self) -> Window {
+        let window = Window {
+            id: self.id(),
+            events: self.events.clone(),
+        };
+        CANVAS_HOLDERS.with_borrow_mut(|holders| holders.insert(window.id, self));
+        window
+    }
+}
+
+/// Stops following resizes, and takes away a canvas we made.
+impl Drop for CanvasHolder {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop_observing.take() {
+            _ = stop.call0(&JsValue::NULL);
+        } else if let Some(on_resize) = &self.on_resize
+            && let Some(window) = window()
+        {
+            _ = window
+                .remove_event_listener_with_callback("resize", on_resize.as_ref().unchecked_ref());
+        }
+        //the page's own canvas stays where the page put it
+        if let Some(canvas) = &self.canvas
+            && !self.embedded
+        {
+            canvas.remove();
+        }
+    }
+}
+
+/// Takes the canvas away with the window.
+impl Drop for Window {
+    fn drop(&mut self) {
+        let id = self.id;
+        on_main_thread(move || {
+            let holder = CANVAS_HOLDERS.with_borrow_mut(|holders| holders.remove(&id));
+            //outside the borrow, in case dropping it sends something
+            drop(holder);
+        });
+    }
+}
+
+fn next_id() -> u32 {
+    let id = NEXT_ID.get();
+    NEXT_ID.set(id + 1);
+    id
+}
+
+#[derive(Debug)]
+pub struct FullscreenError(String);
+
+impl Display for FullscreenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl Error for FullscreenError {}
+
+#[wasm_bindgen]
+extern "C" {
+    type Element2;
+    #[wasm_bindgen::prelude::wasm_bindgen(method,js_class="Element",js_name=requestFullscreen)]
+    fn request_fullscreen_2(this: &Element2) -> Promise;
+}
+
+impl Window {
+    pub async fn fullscreen(title: String, _output: Option<u32>) -> Result<Self, FullscreenError> {
+        let (sender, fut) = r#continue::continuation();
+        let sender_mutex = Arc::new(Mutex::new(Some(sender)));
+        let sender_mutex_error = sender_mutex.clone();
+        let main_thread_job =
+            crate::application::on_main_thread("Window::fullscreen".to_string(), move || {
+                if CanvasHolder::is_headless() {
+                    //there's no screen to fill
+                    let sender = sender_mutex
+                        .lock()
+                        .unwrap()
+                        .take()
+                        .or_abort("already sent?");
+                    sender.send(Err("No DOM, so no fullscreen".to_string()));
+                    return None;
+                }
+                let strong_closure = Closure::once(move |_| {
+                    let lock = sender_mutex
+                        .lock()
+                        .unwrap()
+                        .take()
+                        .or_abort("already sent?");
+                    lock.send(Ok(()));
+                });
+                let error_closure = Closure::once(move |a: JsValue| {
+                    let lock = sender_mutex_error
+                        .lock()
+                        .unwrap()
+                        .take()
+                        .or_abort("already sent?");
+                    let a_typeerror: TypeError = a.unchecked_into();
+                    let a_string = a_typeerror.to_string();
+
+                    lock.send(Err(ToString::to_string(&a_string)));
+                });
+                let window = window().or_abort("Can't get window");
+                let doc = window.document().or_abort("Can't get document");
+                let canvas = CanvasHolder::new_main(None);
+                let element = canvas.canvas.as_ref().or_abort("no canvas");
+                let as_element_2: &Element2 = element.as_ref().unchecked_ref();
+                doc.set_title(&title);
+                let promise = as_element_2.request_fullscreen_2();
+                drop(promise.then2(&strong_closure, &error_closure));
+                Some((
+                    SendCell::new((strong_closure, error_closure)),
+                    canvas.register(),
+                ))
+            });
+        let (closures, window) = main_thread_job.await.unzip();
+        logwise::warn_sync!("Waiting for fut...");
+        let fullscreen_result = fut.await;
+        //drop our closures
+        crate::application::on_main_thread("Drop fs".to_string(), move || {
+            drop(closures);
+        })
+        .await;
+        match (fullscreen_result, window) {
+            (Ok(..), Some(window)) => Ok(window),
+            //dropping the window takes its canvas away
+            (Ok(..), None) => Err(FullscreenError("No canvas".to_string())),
+            (Err(err), _) => Err(FullscreenError(err)),
+        }
+    }
+    pub async fn new(_position: Position, size: Size, title: String) -> Self {
+        Self::create(size, title, None).await
+    }
+
+    /// Makes the window, in the canvas `selector` matches if there's one.
+    async fn create(size: Size, title: String, selector: Option<String>) -> Self {
+        crate::application::on_main_thread("Window::new".to_string(), move || {
+            let Some(window) = window() else {
+                return CanvasHolder::new_headless(size).register();
+            };
+            let doc = window.document().or_abort("Can't get document");
+            doc.set_title(&title);
+            CanvasHolder::new_main(selector.as_deref()).register()
+        })
+        .await
+    }
+
+    pub async fn surface(&self) -> Surface {
+        let id = self.id;
+        crate::application::on_main_thread("surface".to_string(), move || {
+            with_holder(id, |canvas| Surface {
+                display_handle: canvas.handle,
+                closure_box: DebugWrapper(canvas.closure_box.clone()),
+                size_cache: canvas.size_cache.clone(),
+            })
+        })
+        .await
+    }
+    pub async fn set_visible(&self, visible: bool) {
+        let id = self.id;
+        crate::application::on_main_thread("Window::set_visible".to_string(), move || {
+            with_holder(id, |holder| {
+                holder.visible.set(visible);
+                if let Some(canvas) = &holder.canvas {
+                    let display = if visible { "" } else { "none" };
+                    _ = canvas.style().set_property("display", display);
+                }
+            })
+        })
+        .await
+    }
+    pub async fn set_canvas_behavior(&self, behavior: CanvasBehavior) {
+        let id = self.id;
+        crate::application::on_main_thread("Window::set_canvas_behavior".to_string(), move || {
+            with_holder(id, |holder| canvas_behavior::set(holder, behavior))
+        })
+        .await
+    }
+    pub async fn set_close_behavior(&self, close_behavior: CloseBehavior) {
+        crate::application::on_main_thread("Window::set_close_behavior".to_string(), move || {
+            close::set(close_behavior)
+        })
+        .await
+    }
+    pub async fn show_window_menu(&self, _position: Position) {
+        //browsers don't have a window menu
+    }
+    pub async fn set_size(&self, size: Size) -> Result<(), WindowSettingError> {
+        let id = self.id;
+        crate::application::on_main_thread("Window::set_size".to_string(), move || {
+            with_holder(id, |holder| {
+                if let Some(canvas) = &holder.canvas {
+                    let style = canvas.style();
+                    style
+                        .set_property("width", &format!("{}px", size.width()))
+                        .and_then(|_| style.set_property("height", &format!("{}px", size.height())))
+                        .map_err(|e| WindowSettingError::Platform(format!("{e:?}")))?;
+                }
+                holder.size.set(Some(size));
+                holder.size_cache.invalidate();
+                if let Some(closure) = holder.closure_box.lock().unwrap().as_ref() {
+                    closure(size);
+                }
+                holder.events.send(WindowEvent::Resized(size));
+                Ok(())
+            })
+        })
+        .await
+    }
+    pub async fn set_position(&self, _position: Position) -> Result<(), WindowSettingError> {
+        //the canvas sits in the page's layout
+        Err(WindowSettingError::Unsupported)
+    }
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        _visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        //the canvas lives in its tab
+        Err(WindowSettingError::Unsupported)
+    }
+    pub async fn set_skip_taskbar(&self, _skip: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+    pub async fn inhibit_system_shortcuts(&self, inhibit: bool) -> Result<(), WindowSettingError> {
+        shortcuts::inhibit_system_shortcuts(inhibit).await
+    }
+    //browsers only offer pointer lock, which hides the cursor
+    pub async fn confine_pointer(&self, _region: Option<Rect>) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+    pub async fn release_pointer(&self) -> Result<(), WindowSettingError> {
+        Ok(())
+    }
+    pub async fn set_preview_image(
+        &self,
+        _image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+    pub async fn set_icon(&self, icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        crate::application::on_main_thread("Window::set_icon".to_string(), move || icon::set(icon))
+            .await
+    }
+    //the Badging API is for installed apps, and only takes numbers
+    pub async fn set_badge(&self, _label: Option<String>) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+    pub async fn set_progress(&self, _progress: Option<f64>) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+    pub async fn set_cursor(&self, cursor: Cursor) -> Result<(), WindowSettingError> {
+        let id = self.id;
+        crate::application::on_main_thread("Window::set_cursor".to_string(), move || {
+            with_holder(id, |holder| cursor::set(holder, cursor))
+        })
+        .await;
+        Ok(())
+    }
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        crate::application::on_main_thread(
+            "Window::set_virtual_keyboard_visible".to_string(),
+            move || virtual_keyboard::set_visible(visible),
+        )
+        .await
+    }
+    pub async fn set_decorations(&self, _mode: DecorationsMode) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_decoration_theme(
+        &self,
+        _theme: DecorationTheme,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_resize_border(&self, _width: f64) -> Result<(), WindowSettingError> {
+        //the system draws the frame
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn begin_sheet(&self, _sheet: &Window) -> Result<(), WindowSettingError> {
+        //windows are canvases in the page, which has no notion of one being modal to another
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn show_modal(&self, _sheet: &Window) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    //the page lays out the canvas
+    pub async fn set_resizable(&self, _resizable: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_size_limits(
+        &self,
+        _min: Option<Size>,
+        _max: Option<Size>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_click_through(&self, click_through: bool) -> Result<(), WindowSettingError> {
+        let id = self.id;
+        crate::application::on_main_thread("Window::set_click_through".to_string(), move || {
+            with_holder(id, |holder| click_through::set(holder, click_through))
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_background(&self, color: Color) -> Result<(), WindowSettingError> {
+        let id = self.id;
+        crate::application::on_main_thread("Window::set_background".to_string(), move || {
+            with_holder(id, |holder| {
+                if let Some(canvas) = &holder.canvas {
+                    let [red, green, blue] = color.to_rgb8();
+                    _ = canvas
+                        .style()
+                        .set_property("background-color", &format!("rgb({red} {green} {blue})"));
+                }
+            })
+        })
+        .await;
+        Ok(())
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn update_accessibility_tree(
+        &self,
+        _update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn set_accessibility_action_handler(
+        &self,
+        _on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        crate::application::on_main_thread("Window::focus".to_string(), || {
+            //browsers only let pages take focus back from their own popups
+            let window = window().ok_or(WindowSettingError::Unsupported)?;
+            window
+                .focus()
+                .map_err(|e| WindowSettingError::Platform(format!("{e:?}")))
+        })
+        .await
+    }
+
+    pub async fn is_focused(&self) -> bool {
+        crate::application::on_main_thread("Window::is_focused".to_string(), || {
+            window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.has_focus().ok())
+                .unwrap_or(false)
+        })
+        .await
+    }
+
+    pub async fn set_always_on_top(&self, _on_top: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_maximized(&self, _maximized: bool) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_transparent(&self, _transparent: bool) -> Result<(), WindowSettingError> {
+        //the canvas is composited over the page already
+        Ok(())
+    }
+
+    pub async fn set_parent(&self, _parent: &Window) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    /// Input reports the canvas's id as the window, see `input::mouse`.
+    pub async fn input_handle(&self) -> Option<usize> {
+        Some(self.id as usize)
+    }
+
+    pub async fn native_handle(&self) -> Option<NativeHandle> {
+        let id = self.id;
+        crate::application::on_main_thread("Window::native_handle".into(), move || {
+            with_holder(id, |holder| {
+                //a virtual window has no canvas to find
+                holder.canvas.as_ref()?;
+                Some(NativeHandle::Web {
+                    raw_handle: holder.handle.id,
+                })
+            })
+        })
+        .await
+    }
+
+    pub fn event_sender(&self) -> WindowEventSender {
+        self.events.clone()
+    }
+    pub async fn normal_bounds(&self) -> Option<(Option<Position>, Size)> {
+        None
+    }
+
+    pub async fn outer_position(&self) -> Option<Position> {
+        None
+    }
+
+    pub fn request_frame(&self, frame: FrameCallback) {
+        request_frame(frame)
+    }
+
+    pub async fn scale_factor(&self) -> f64 {
+        let id = self.id;
+        crate::application::on_main_thread("Window::scale_factor".to_string(), move || {
+            read_size_scale_main(id).1
+        })
+        .await
+    }
+    pub async fn debug_state(&self) -> WindowDebugState {
+        let id = self.id;
+        crate::application::on_main_thread("Window::debug_state".to_string(), move || {
+            let (size, scale) = read_size_scale_main(id);
+            let (visible, cursor) = with_holder(id, |holder| {
+                let visible = match &holder.canvas {
+                    Some(canvas) => {
+                        canvas.style().get_property_value("display").as_deref() != Ok("none")
+                    }
+                    None => holder.visible.get(),
+                };
+                (visible, cursor::get(holder))
+            });
+            let document = window().and_then(|w| w.document());
+            let focused = document.as_ref().and_then(|d| d.has_focus().ok());
+            let state = match document.and_then(|d| d.fullscreen_element()) {
+                Some(_) => WindowState::Fullscreen,
+                None => WindowState::Normal,
+            };
+            WindowDebugState {
+                backend: if CanvasHolder::is_headless() {
+                    "headless"
+                } else {
+                    "web"
+                },
+                applied_size: Some(size),
+                proposed_size: None,
+                scale: Some(scale),
+                preferred_scale: None,
+                outputs: Vec::new(),
+                focused,
+                visible: Some(visible),
+                configured: None,
+                decorations: Some(DecorationsMode::None),
+                buffer: None,
+                cursor: Some(cursor),
+                state: Some(state),
+            }
+        })
+        .await
+    }
+    pub async fn new_splash(_size: Size, _image: PreviewImage) -> Option<Self> {
+        //the page is the splash screen
+        None
+    }
+
+    pub async fn fade_out(self) {}
+
+    pub async fn new_overlay(_size: Size, _image: PreviewImage) -> Option<Self> {
+        //the page is the splash screen
+        None
+    }
+
+    pub async fn set_splash_image(&self, _image: PreviewImage) {}
+
+    pub async fn new_with_options(
+        _position: Position,
+        size: Size,
+        title: String,
+        options: CreateOptions,
+    ) -> Self {
+        //a canvas has no decorations
+        let window = Window::create(size, title, options.canvas_selector).await;
+        if !options.visible {
+            window.set_visible(false).await;
+        }
+        window
+    }
+
+    pub async fn new_parked(_position: Position, _size: Size, _title: String) -> Option<Self> {
+        None
+    }
+
+    pub async fn unpark(&self, _title: String) {}
+
+    pub async fn default() -> Self {
+        Window::new(
+            Position::new(0.0, 0.0),
+            Size::new(800.0, 600.0),
+            String::from("app_window"),
+        )
+        .await
+    }
+}
+
+pub fn is_main_thread() -> bool {
+    //every dispatch asks, and the answer never changes for a thread
+    thread_local! {
+        static IS_MAIN_THREAD: Cell<Option<bool>> = const { Cell::new(None) };
+    }
+    IS_MAIN_THREAD.with(|cached| match cached.get() {
+        Some(is_main) => is_main,
+        None => {
+            let is_main = detect_main_thread();
+            cached.set(Some(is_main));
+            is_main
+        }
+    })
+}
+
+fn detect_main_thread() -> bool {
+    let g = web_sys::js_sys::global();
+
+    // Browser: main thread vs Web Worker
+    if g.dyn_ref::<web_sys::Window>().is_some() {
+        return true;
+    }
+    if g.dyn_ref::<web_sys::WorkerGlobalScope>().is_some() {
+        return false;
+    }
+
+    // Node: detect environment, then query worker_threads.isMainThread
+    #[cfg(not(feature = "wasm-slim"))]
+    if is_node_env(&g) {
+        return node_is_main_thread_cjs(); // sync, works when `require` is available
+    }
+
+    // Unknown host
+    #[cfg(feature = "wasm-slim")]
+    std::process::abort();
+    #[cfg(not(feature = "wasm-slim"))]
+    panic!("Unknown global object type: {:?}", g);
+}
+
+//wasm-slim is for browsers, and this is the only user of js-sys reflection
+#[cfg(not(feature = "wasm-slim"))]
+fn is_node_env(g: &wasm_bindgen::JsValue) -> bool {
+    // typeof process === 'object' && !!process?.versions?.node
+    if let Ok(process) = web_sys::js_sys::Reflect::get(g, &"process".into())
+        && !process.is_undefined()
+        && !process.is_null()
+        && let Ok(versions) = web_sys::js_sys::Reflect::get(&process, &"versions".into())
+        && let Ok(node) = web_sys::js_sys::Reflect::get(&versions, &"node".into())
+    {
+        return !node.is_undefined() && !node.is_null();
+    }
+    false
+}
+
+// --- Node (CommonJS): synchronous path ---
+// Uses `require('node:worker_threads').isMainThread` if `require` exists.
+#[cfg(not(feature = "wasm-slim"))]
+#[wasm_bindgen(inline_js = r#"
+export function nodeIsMainThreadCJS() {
+  try {
+    if (typeof require !== 'undefined') {
+      return require('node:worker_threads').isMainThread;
+    }
+  } catch (_) {}
+  // If require isn't available, caller can try the async ESM variant.
+  return true; // sensible default on main thread
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = nodeIsMainThreadCJS)]
+    fn node_is_main_thread_cjs() -> bool;
+}
+#[wasm_bindgen(inline_js = r#"
+export function yieldToEventLoop() {
+  return new Promise((resolve) => setTimeout(resolve, 0));
+}
+"#)]
+extern "C" {
+    /// Resolves from a new task, so input and rendering queued in the meantime run first.
+    #[wasm_bindgen(js_name = yieldToEventLoop)]
+    fn yield_to_event_loop() -> Promise;
+}
+
+#[wasm_bindgen(inline_js = r#"
+export function runAfter(f, ms) {
+  setTimeout(f, ms);
+}
+"#)]
+extern "C" {
+    /// Calls `f` from a task at least `ms` milliseconds from now.
+    #[wasm_bindgen(js_name = runAfter)]
+    fn run_after(f: JsValue, ms: f64);
+}
+
+pub fn run_main_thread<F: FnOnce() + Send + 'static>(
+    closure: F,
+) -> Result<(), crate::application::MainError> {
+    let (sender, receiver) = continue_stream::continuation();
+
+    let mut sent = false;
+    MAIN_THREAD_SENDER.get_or_init(|| {
+        sent = true;
+        sender
+    });
+    assert!(sent, "Don't call run_main_thread more than once");
+
+    let push_context = Context::current();
+    let push_context_2 = push_context.clone();
+
+    // logwise::info_sync!("wasm_thread WILL spawn");
+
+    wasm_safe_thread::spawn(|| {
+        // logwise::info_sync!("wasm_thread spawn");
+        let new_context = Context::new_task(
+            Some(push_context_2),
+            "app_window after MT context".to_string(),
+            Level::DebugInternal,
+            logwise::log_enabled!(Level::DebugInternal),
+        );
+        let new_id = new_context.context_id();
+        new_context.set_current();
+        closure();
+        Context::pop(new_id);
+    });
+
+    let event_loop_context = Context::new_task(
+        Some(Context::current()),
+        "main thread eventloop".to_string(),
+        Level::DebugInternal,
+        logwise::log_enabled!(Level::DebugInternal),
+    );
+    let apply_context = logwise::context::ApplyContext::new(event_loop_context, async move {
+        let mut slice = DispatchSlice::new();
+        loop {
+            // logwise::debuginternal_sync!("Waiting for main thread event");
+            let mut receive = std::pin::pin!(receiver.receive());
+            let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+            let event = match receive.as_mut().poll(&mut cx) {
+                std::task::Poll::Ready(event) => event,
+                std::task::Poll::Pending => {
+                    //the queue ran dry, so the next closure starts a fresh turn
+                    slice = DispatchSlice::new();
+                    receive.await
+                }
+            }
+            .or_abort("Can't receive event");
+            // logwise::debuginternal_sync!("Received main thread event");
+            match event {
+                MainThreadEvent::Execute(f) => f(),
+            }
+            //receiving from a ready queue never leaves the microtask queue, which would
+            //starve input and rendering
+            if slice.record_op() {
+                _ = wasm_bindgen_futures::JsFuture::from(yield_to_event_loop()).await;
+                slice = DispatchSlice::new();
+            }
+        }
+    });
+    wasm_bindgen_futures::spawn_local(apply_context);
+    Ok(())
+}
+
+pub fn on_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
+    if is_main_thread() {
+        closure()
+    } else {
+        let mt_sender = MAIN_THREAD_SENDER
+            .get()
+            .or_abort(crate::application::CALL_MAIN);
+        let boxed_closure = Box::new(closure) as Box<dyn FnOnce() + Send + 'static>;
+        // let perf = logwise::perfwarn_begin!("starting SEND task");
+
+        mt_sender.send(MainThreadEvent::Execute(boxed_closure));
+    }
+}
+
+pub fn on_main_thread_after<F: FnOnce() + Send + 'static>(delay: std::time::Duration, closure: F) {
+    on_main_thread(move || run_after(Closure::once_into_js(closure), delay.as_secs_f64() * 1000.0));
+}
+
+pub fn stop_main_thread() {
+    //nothing to do - handled by browsers
+}
+
+pub async fn alert(message: String) {
+    crate::application::on_main_thread("alert".to_string(), move || {
+        let Some(window) = window() else {
+            //nobody to show it to
+            logwise::warn_sync!(
+                "alert without a DOM: {message}",
+                message = logwise::privacy::LogIt(&message)
+            );
+            return;
+        };
+        window.alert_with_message(&message).or_abort("Alert failed");
+    })
+    .await
+}
+
+/// Calls `frame` on the browser's next animation frame.  Every canvas shares the page's.
+fn request_frame(frame: FrameCallback) {
+    on_main_thread(move || {
+        let fire = move || frame(FrameTiming::new(Instant::now(), None, None));
+        match window() {
+            Some(window) => {
+                let callback = Closure::once_into_js(move |_timestamp: f64| fire());
+                if let Err(e) = window.request_animation_frame(callback.unchecked_ref()) {
+                    logwise::warn_sync!(
+                        "requestAnimationFrame failed: {e}",
+                        e = logwise::privacy::LogIt(&e)
+                    );
+                }
+            }
+            //node has no frames to wait for; pace like a 60Hz display
+            None => run_after(Closure::once_into_js(fire), 1000.0 / 60.0),
+        }
+    });
+}
+
+#[derive(Clone)]
+struct DebugWrapper<T>(T);
+
+impl<T> Debug for DebugWrapper<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DebugWrapper")
+    }
+}
+#[derive(Debug)]
+pub struct Surface {
+    display_handle: WebWindowHandle,
+    closure_box: DebugWrapper<SharedSizeCallback>,
+    size_cache: Arc<SizeCache>,
+}
+impl Surface {
+    pub async fn size_scale(&self) -> (Size, f64) {
+        //render loops tend to ask every frame, so avoid the main-thread hop unless we've been resized
+        if let Some(cached) = self.size_cache.get() {
+            return cached;
+        }
+        let size_cache = self.size_cache.clone();
+        let id = self.display_handle.id;
+        crate::application::on_main_thread("size_scale".to_string(), move || {
+            let epoch = size_cache.epoch();
+            let (size, scale) = read_size_scale_main(id);
+            size_cache.store(epoch, size, scale);
+            (size, scale)
+        })
+        .await
+    }
+
+    pub fn size_main(&self) -> (Size, f64) {
+        let epoch = self.size_cache.epoch();
+        let (size, scale) = read_size_scale_main(self.display_handle.id);
+        self.size_cache.store(epoch, size, scale);
+        (size, scale)
+    }
+
+    pub fn raw_window_handle(&self) -> RawWindowHandle {
+        RawWindowHandle::Web(self.display_handle)
+    }
+    pub fn raw_display_handle(&self) -> RawDisplayHandle {
+        RawDisplayHandle::Web(WebDisplayHandle::new())
+    }
+    /**
+    Run the attached callback when size changes.
+    */
+    pub fn size_update<F: Fn(Size) + Send + 'static>(&mut self, update: F) {
+        self.closure_box.0.lock().unwrap().replace(Box::new(update));
+    }
+
+    pub fn frame_requester(&self) -> FrameRequester {
+        Arc::new(request_frame)
+    }
+
+    pub fn damage(&self, _rects: &[Rect]) {
+        //the browser composites the whole canvas
+    }
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Notifications, via the `Notification` API.  The first one asks for permission, which
+//! browsers only allow after the user has interacted with the page.
+
+use crate::notification::{Notification, NotificationError};
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_futures::js_sys::Promise;
+
+#[wasm_bindgen(inline_js = r#"
+export function postNotification(title, body, icon) {
+  if (typeof Notification === 'undefined') {
+    return null;
+  }
+  const show = () => {
+    new Notification(title, icon === undefined ? { body } : { body, icon });
+    return true;
+  };
+  if (Notification.permission === 'granted') {
+    return Promise.resolve(show());
+  }
+  if (Notification.permission === 'denied') {
+    return Promise.resolve(false);
+  }
+  return Notification.requestPermission().then((permission) =>
+    permission === 'granted' ? show() : false
+  );
+}
+"#)]
+extern "C" {
+    /// Resolves with whether the notification was shown, or `None` without `Notification`.
+    #[wasm_bindgen(js_name = postNotification)]
+    fn post(title: &str, body: &str, icon: Option<String>) -> Option<Promise>;
+}
+
+fn error_from_js(error: JsValue) -> NotificationError {
+    match error.dyn_ref::<web_sys::js_sys::Error>() {
+        //Chrome on Android only shows notifications from service workers
+        Some(error) if error.name() == "TypeError" => NotificationError::Unsupported,
+        Some(error) => NotificationError::Platform(error.message().into()),
+        None => NotificationError::Platform(format!("{error:?}")),
+    }
+}
+
+pub async fn post_notification(notification: Notification) -> Result<(), NotificationError> {
+    let (sender, fut) = r#continue::continuation();
+    crate::application::on_main_thread("post_notification".to_string(), move || {
+        let icon = notification.icon.as_ref().and_then(|icon| {
+            let document = web_sys::window()?.document()?;
+            super::icon::data_url(&document, icon).ok()
+        });
+        let Some(promise) = post(&notification.title, &notification.body, icon) else {
+            sender.send(Err(NotificationError::Unsupported));
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = match JsFuture::from(promise).await {
+                Ok(shown) if shown.as_bool() == Some(true) => Ok(()),
+                Ok(_) => Err(NotificationError::Denied),
+                Err(e) => Err(error_from_js(e)),
+            };
+            sender.send(result);
+        });
+    })
+    .await;
+    fut.await
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MPL-2.0
+//! System shortcut inhibition, via the Keyboard Lock API.  Chromium-based browsers have it,
+//! and only honor it in fullscreen.
+
+use crate::window::WindowSettingError;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_futures::js_sys::Promise;
+
+#[wasm_bindgen(inline_js = r#"
+export function lockKeyboard(lock) {
+  if (typeof navigator === 'undefined' || !navigator.keyboard
+      || typeof navigator.keyboard.lock !== 'function') {
+    return null;
+  }
+  if (lock) {
+    return navigator.keyboard.lock();
+  }
+  navigator.keyboard.unlock();
+  return Promise.resolve();
+}
+"#)]
+extern "C" {
+    /// Locks or unlocks every key, or `None` without the Keyboard Lock API.
+    #[wasm_bindgen(js_name = lockKeyboard)]
+    fn lock_keyboard(lock: bool) -> Option<Promise>;
+}
+
+fn error_from_js(error: JsValue) -> WindowSettingError {
+    match error.dyn_ref::<web_sys::js_sys::Error>() {
+        Some(error) => WindowSettingError::Platform(error.message().into()),
+        None => WindowSettingError::Platform(format!("{error:?}")),
+    }
+}
+
+pub async fn inhibit_system_shortcuts(inhibit: bool) -> Result<(), WindowSettingError> {
+    let (sender, fut) = r#continue::continuation();
+    crate::application::on_main_thread("inhibit_system_shortcuts".to_string(), move || {
+        let Some(promise) = lock_keyboard(inhibit) else {
+            sender.send(Err(WindowSettingError::Unsupported));
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = JsFuture::from(promise)
+                .await
+                .map(|_| ())
+                .map_err(error_from_js);
+            sender.send(result);
+        });
+    })
+    .await;
+    fut.await
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Support for the `wasm-slim` feature, which trades diagnostics for a smaller binary.
+
+/// `expect`, except with `wasm-slim` it aborts without a message.
+///
+/// `expect` formats the error with `Debug`, and for a `JsValue` that links in a lot of
+/// js-sys.  These failures mean the page is broken anyway.
+pub(super) trait OrAbort<T> {
+    fn or_abort(self, msg: &'static str) -> T;
+}
+
+impl<T> OrAbort<T> for Option<T> {
+    #[inline]
+    #[track_caller]
+    fn or_abort(self, msg: &'static str) -> T {
+        #[cfg(feature = "wasm-slim")]
+        {
+            let _ = msg;
+            self.unwrap_or_else(|| std::process::abort())
+        }
+        #[cfg(not(feature = "wasm-slim"))]
+        self.expect(msg)
+    }
+}
+
+impl<T, E: std::fmt::Debug> OrAbort<T> for Result<T, E> {
+    #[inline]
+    #[track_caller]
+    fn or_abort(self, msg: &'static str) -> T {
+        #[cfg(feature = "wasm-slim")]
+        {
+            let _ = msg;
+            self.unwrap_or_else(|_| std::process::abort())
+        }
+        #[cfg(not(feature = "wasm-slim"))]
+        self.expect(msg)
+    }
+}
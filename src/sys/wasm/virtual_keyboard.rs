@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The on-screen keyboard, which mobile browsers show while an editable element has focus.
+//!
+//! The canvas isn't editable, so we focus an invisible `<input>` instead.  Keys typed into it
+//! still reach the document's listeners, and browsers compose into it.
+
+use crate::window::WindowSettingError;
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+
+thread_local! {
+    static INPUT: RefCell<Option<HtmlInputElement>> = const { RefCell::new(None) };
+}
+
+fn create() -> HtmlInputElement {
+    let document = web_sys::window()
+        .expect("no global window exists")
+        .document()
+        .expect("no document on window");
+    let input: HtmlInputElement = document
+        .create_element("input")
+        .expect("Can't create input")
+        .dyn_into()
+        .expect("Not an input");
+    //invisible, but not display: none, which can't be focused
+    let style = input.style();
+    for (property, value) in [
+        ("position", "fixed"),
+        ("left", "0"),
+        ("top", "0"),
+        ("width", "1px"),
+        ("height", "1px"),
+        ("opacity", "0"),
+        ("pointer-events", "none"),
+    ] {
+        _ = style.set_property(property, value);
+    }
+    document
+        .body()
+        .expect("no body on document")
+        .append_child(&input)
+        .expect("Can't add input");
+    input
+}
+
+/// Call on the main thread.
+pub(super) fn set_visible(visible: bool) -> Result<(), WindowSettingError> {
+    let result = INPUT.with_borrow_mut(|input| {
+        if visible {
+            input.get_or_insert_with(create).focus()
+        } else {
+            input.as_ref().map_or(Ok(()), |input| input.blur())
+        }
+    });
+    result.map_err(|e| WindowSettingError::Platform(format!("{e:?}")))
+}
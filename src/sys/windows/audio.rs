@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Audio hints via the process's default WASAPI session.
+
+use crate::audio::{AudioHintCapabilities, AudioHintError, AudioHints, HintSupport};
+use windows::Win32::Media::Audio::{
+    IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator, MMDeviceEnumerator,
+    eConsole, eRender,
+};
+use windows::Win32::System::Com::{
+    CLSCTX_ALL, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+};
+use windows::core::Interface;
+
+pub fn audio_hint_capabilities() -> AudioHintCapabilities {
+    AudioHintCapabilities {
+        avoid_ducking: HintSupport::Supported,
+        //Windows doesn't throttle audio for unfocused windows
+        background_audio: HintSupport::NotNeeded,
+    }
+}
+
+fn set_ducking_preference(opt_out: bool) -> windows::core::Result<()> {
+    unsafe {
+        //S_FALSE (already initialized) and RPC_E_CHANGED_MODE both leave COM usable
+        _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)?;
+        let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)?;
+        //the default session for this process, which is where our streams play
+        let control = manager.GetAudioSessionControl(None, 0)?;
+        control
+            .cast::<IAudioSessionControl2>()?
+            .SetDuckingPreference(opt_out)
+    }
+}
+
+pub async fn set_audio_hints(hints: AudioHints) -> Result<(), AudioHintError> {
+    crate::application::on_main_thread("set_audio_hints".into(), move || {
+        set_ducking_preference(hints.wants_avoid_ducking())
+            .map_err(|e| AudioHintError::Platform(e.to_string()))
+    })
+    .await
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The app's accessibility tree, published over UI Automation with `accesskit_windows`.
+//!
+//! The adapter is made when the app publishes its first tree, and answers `WM_GETOBJECT` from
+//! then on.  Until then the window keeps what Windows gives every window.
+
+use super::HWND_IMPS;
+use crate::window::WindowSettingError;
+use crate::window::accessibility::AccessibilityContent;
+use accesskit::{ActionRequest, TreeId, TreeUpdate};
+use std::sync::{Arc, Mutex};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::UI::Input::KeyboardAndMouse::GetFocus;
+
+/// A window's part in `HwndImp`.
+pub(super) struct Accessibility {
+    content: Arc<Mutex<AccessibilityContent>>,
+    adapter: Option<accesskit_windows::Adapter>,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Accessibility {
+            //the app's tree is the window's whole tree
+            content: Arc::new(Mutex::new(AccessibilityContent::new(TreeId::ROOT))),
+            adapter: None,
+        }
+    }
+}
+
+struct ContentTree(Arc<Mutex<AccessibilityContent>>);
+
+impl accesskit::ActivationHandler for ContentTree {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        self.0.lock().unwrap().full_update()
+    }
+}
+
+impl accesskit::ActionHandler for ContentTree {
+    fn do_action(&mut self, request: ActionRequest) {
+        AccessibilityContent::perform(&self.0, request)
+    }
+}
+
+/// Records `update` and passes it on, if anyone's listening.  Call on the main thread.
+pub(super) fn update(hwnd: HWND, update: TreeUpdate) -> Result<(), WindowSettingError> {
+    let events = HWND_IMPS.with_borrow_mut(|c| {
+        let ax = &mut c.entry(hwnd.0).or_default().accessibility;
+        let update = ax.content.lock().unwrap().apply(update)?;
+        let content = ax.content.clone();
+        let adapter = ax.adapter.get_or_insert_with(|| {
+            let focused = unsafe { GetFocus() } == hwnd;
+            accesskit_windows::Adapter::new(hwnd, focused, ContentTree(content))
+        });
+        Ok(adapter.update_if_active(|| update))
+    })?;
+    //raising sends WM_GETOBJECT, which borrows HWND_IMPS
+    if let Some(events) = events {
+        events.raise();
+    }
+    Ok(())
+}
+
+/// Call on the main thread.
+pub(super) fn set_action_handler(hwnd: HWND, on_action: Box<dyn FnMut(ActionRequest) + Send>) {
+    HWND_IMPS.with_borrow_mut(|c| {
+        let ax = &mut c.entry(hwnd.0).or_default().accessibility;
+        ax.content.lock().unwrap().set_action_handler(on_action)
+    })
+}
+
+/// Answers `WM_GETOBJECT` once the app has published a tree.
+pub(super) fn get_object(hwnd: HWND, w_param: WPARAM, l_param: LPARAM) -> Option<LRESULT> {
+    let result = HWND_IMPS.with_borrow_mut(|c| {
+        let ax = &mut c.get_mut(&hwnd.0)?.accessibility;
+        let mut tree = ContentTree(ax.content.clone());
+        ax.adapter
+            .as_mut()?
+            .handle_wm_getobject(w_param, l_param, &mut tree)
+    });
+    //converting hands the provider to UI Automation, which may call back in
+    result.map(Into::into)
+}
+
+/// Call on `WM_SETFOCUS` and `WM_KILLFOCUS`.
+pub(super) fn focus_changed(hwnd: HWND, focused: bool) {
+    let events = HWND_IMPS.with_borrow_mut(|c| {
+        c.get_mut(&hwnd.0)?
+            .accessibility
+            .adapter
+            .as_mut()?
+            .update_window_focus_state(focused)
+    });
+    if let Some(events) = events {
+        events.raise();
+    }
+}
+
+/// Drops the adapter when the window is destroyed.
+pub(super) fn forget(hwnd: HWND) {
+    let adapter = HWND_IMPS.with_borrow_mut(|c| {
+        c.get_mut(&hwnd.0)
+            .and_then(|imp| imp.accessibility.adapter.take())
+    });
+    drop(adapter);
+}
@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Clipboard change notifications, via a message-only window registered with
+//! `AddClipboardFormatListener`.
+
+use crate::clipboard::ClipboardError;
+use std::cell::Cell;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::DataExchange::{
+    AddClipboardFormatListener, RemoveClipboardFormatListener,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, HWND_MESSAGE, RegisterClassExW,
+    WINDOW_EX_STYLE, WINDOW_STYLE, WM_CLIPBOARDUPDATE, WNDCLASSEXW,
+};
+use windows::core::{PCWSTR, w};
+
+const CLASS_NAME: PCWSTR = w!("app_window_clipboard_listener");
+
+thread_local! {
+    static CLASS_REGISTERED: Cell<bool> = const { Cell::new(false) };
+    /// The listening window, while anyone is watching.
+    static LISTENER: Cell<Option<HWND>> = const { Cell::new(None) };
+}
+
+extern "system" fn listener_proc(
+    hwnd: HWND,
+    msg: u32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if msg == WM_CLIPBOARDUPDATE {
+        crate::clipboard::notify();
+        return LRESULT(0);
+    }
+    unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }
+}
+
+fn create_listener() -> windows::core::Result<HWND> {
+    let instance = unsafe { GetModuleHandleW(PCWSTR::null()) }?;
+    if !CLASS_REGISTERED.replace(true) {
+        let class = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(listener_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        if unsafe { RegisterClassExW(&class) } == 0 {
+            CLASS_REGISTERED.set(false);
+            return Err(windows::core::Error::from_thread());
+        }
+    }
+    //message-only, so it never shows up anywhere
+    let hwnd = unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            CLASS_NAME,
+            PCWSTR::null(),
+            WINDOW_STYLE(0),
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            Some(instance.into()),
+            None,
+        )
+    }?;
+    if let Err(e) = unsafe { AddClipboardFormatListener(hwnd) } {
+        unsafe { _ = DestroyWindow(hwnd) };
+        return Err(e);
+    }
+    Ok(hwnd)
+}
+
+/// Call on the main thread.
+pub fn watch_clipboard() -> Result<(), ClipboardError> {
+    if LISTENER.get().is_some() {
+        return Ok(());
+    }
+    let hwnd = create_listener().map_err(|e| ClipboardError::Platform(e.to_string()))?;
+    LISTENER.set(Some(hwnd));
+    Ok(())
+}
+
+/// Call on the main thread.
+pub fn unwatch_clipboard() {
+    if let Some(hwnd) = LISTENER.take() {
+        unsafe {
+            _ = RemoveClipboardFormatListener(hwnd);
+            _ = DestroyWindow(hwnd);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Cursor confinement, with `ClipCursor`.
+//!
+//! The clip rectangle is global and in screen coordinates, so we reapply it whenever the window
+//! moves or resizes, and lift it while the window is in the background.
+
+use super::HWND_IMPS;
+use crate::coordinates::Rect;
+use crate::window::WindowSettingError;
+use windows::Win32::Foundation::{HWND, POINT, RECT};
+use windows::Win32::Graphics::Gdi::ClientToScreen;
+use windows::Win32::UI::WindowsAndMessaging::{ClipCursor, GetClientRect, GetForegroundWindow};
+
+/// Sets the confinement for `hwnd` and applies it.  `None` lifts it.
+pub(super) fn set(hwnd: HWND, confinement: Option<Option<Rect>>) -> Result<(), WindowSettingError> {
+    HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().confinement = confinement);
+    if confinement.is_some() {
+        let active = unsafe { GetForegroundWindow() } == hwnd;
+        apply(hwnd, active)
+    } else {
+        unsafe { ClipCursor(None) }.map_err(|e| WindowSettingError::Platform(e.to_string()))
+    }
+}
+
+/// Reapplies the confinement for `hwnd`, if it has one.  Call when it moves, resizes, or is
+/// activated or deactivated.
+pub(super) fn apply(hwnd: HWND, active: bool) -> Result<(), WindowSettingError> {
+    let Some(region) = HWND_IMPS.with_borrow(|c| c.get(&hwnd.0).and_then(|imp| imp.confinement))
+    else {
+        return Ok(());
+    };
+    let platform = |e: windows::core::Error| WindowSettingError::Platform(e.to_string());
+    if !active {
+        return unsafe { ClipCursor(None) }.map_err(platform);
+    }
+    let mut client = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut client) }.map_err(platform)?;
+    //clip the region, which is logical, to the client area
+    let scale = super::dpi::scale(hwnd);
+    let mut rect = match region {
+        Some(region) => RECT {
+            left: ((region.origin().x() * scale) as i32).max(client.left),
+            top: ((region.origin().y() * scale) as i32).max(client.top),
+            right: (((region.origin().x() + region.size().width()) * scale) as i32)
+                .min(client.right),
+            bottom: (((region.origin().y() + region.size().height()) * scale) as i32)
+                .min(client.bottom),
+        },
+        None => client,
+    };
+    let mut top_left = POINT {
+        x: rect.left,
+        y: rect.top,
+    };
+    let mut bottom_right = POINT {
+        x: rect.right,
+        y: rect.bottom,
+    };
+    unsafe {
+        _ = ClientToScreen(hwnd, &mut top_left);
+        _ = ClientToScreen(hwnd, &mut bottom_right);
+    }
+    rect = RECT {
+        left: top_left.x,
+        top: top_left.y,
+        right: bottom_right.x,
+        bottom: bottom_right.y,
+    };
+    unsafe { ClipCursor(Some(&rect)) }.map_err(platform)
+}
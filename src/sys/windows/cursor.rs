@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The cursor over the client area, with `WM_SETCURSOR`.
+//!
+//! Windows asks the window under the pointer for a cursor on every mouse move.  Left to
+//! `DefWindowProcW`, that's the class cursor (an arrow), so a cursor set any other way flickers
+//! back on the next move.  Instead we keep each window's cursor and answer with it.
+
+use super::HWND_IMPS;
+use crate::window::Cursor;
+use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT};
+use windows::Win32::Graphics::Gdi::ScreenToClient;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClientRect, GetCursorPos, HTCLIENT, IDC_ARROW, IDC_CROSS, IDC_HAND, IDC_IBEAM, IDC_NO,
+    IDC_SIZENS, IDC_SIZEWE, IDC_WAIT, LoadCursorW, SetCursor, WindowFromPoint,
+};
+use windows::core::PCWSTR;
+
+fn resource(cursor: Cursor) -> Option<PCWSTR> {
+    Some(match cursor {
+        Cursor::Arrow => IDC_ARROW,
+        Cursor::Text => IDC_IBEAM,
+        Cursor::Hand => IDC_HAND,
+        Cursor::Wait => IDC_WAIT,
+        Cursor::Crosshair => IDC_CROSS,
+        Cursor::NotAllowed => IDC_NO,
+        Cursor::ResizeHorizontal => IDC_SIZEWE,
+        Cursor::ResizeVertical => IDC_SIZENS,
+        Cursor::Hidden => return None,
+    })
+}
+
+fn show(cursor: Cursor) {
+    let handle = resource(cursor).and_then(|id| unsafe { LoadCursorW(None, id) }.ok());
+    //a null cursor hides it
+    unsafe { SetCursor(handle) };
+}
+
+/// The cursor set for `hwnd`.  Call on the main thread.
+pub(super) fn get(hwnd: HWND) -> Cursor {
+    HWND_IMPS.with_borrow(|c| c.get(&hwnd.0).map(|imp| imp.cursor).unwrap_or_default())
+}
+
+/// Sets the cursor for `hwnd`, and shows it now if the pointer is over the client area.  Call
+/// on the main thread.
+pub(super) fn set(hwnd: HWND, cursor: Cursor) {
+    HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().cursor = cursor);
+    //otherwise nothing asks until the pointer moves
+    let mut point = POINT::default();
+    if unsafe { GetCursorPos(&mut point) }.is_err() || unsafe { WindowFromPoint(point) } != hwnd {
+        return;
+    }
+    let mut client = RECT::default();
+    if unsafe { ScreenToClient(hwnd, &mut point) }.as_bool()
+        && unsafe { GetClientRect(hwnd, &mut client) }.is_ok()
+        && point.x >= client.left
+        && point.x < client.right
+        && point.y >= client.top
+        && point.y < client.bottom
+    {
+        show(cursor);
+    }
+}
+
+/// Handles `WM_SETCURSOR`.  Returns whether we set the cursor; if not, pass the message to
+/// `DefWindowProcW` so borders get their resize cursors.
+pub(super) fn on_set_cursor(hwnd: HWND, l_param: LPARAM) -> bool {
+    //LOWORD is the hit test
+    if (l_param.0 & 0xFFFF) as u32 != HTCLIENT {
+        return false;
+    }
+    show(get(hwnd));
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::{get, on_set_cursor, resource, set};
+    use crate::window::Cursor;
+    use windows::Win32::Foundation::{HWND, LPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{HTCAPTION, HTCLIENT, IDC_ARROW};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn hidden_has_no_resource() {
+        assert_eq!(resource(Cursor::Arrow), Some(IDC_ARROW));
+        assert_eq!(resource(Cursor::Hidden), None);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn cursor_is_kept_per_window() {
+        //never a real window, so the pointer can't be over it
+        let hwnd = HWND(0x5eed as *mut _);
+        let other = HWND(0x5eee as *mut _);
+        assert_eq!(get(hwnd), Cursor::Arrow);
+        set(hwnd, Cursor::Text);
+        assert_eq!(get(hwnd), Cursor::Text);
+        assert_eq!(get(other), Cursor::Arrow);
+        super::HWND_IMPS.with_borrow_mut(|c| c.remove(&hwnd.0));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn borders_keep_their_cursors() {
+        let hwnd = HWND(0x5eef as *mut _);
+        //WM_SETCURSOR's LOWORD is the hit test, and the HIWORD the mouse message
+        let message = 0x0200 << 16;
+        assert!(!on_set_cursor(hwnd, LPARAM(message | HTCAPTION as isize)));
+        assert!(on_set_cursor(hwnd, LPARAM(message | HTCLIENT as isize)));
+    }
+}
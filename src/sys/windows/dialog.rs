@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The color picker.  Windows has no system eyedropper, so we cover the screen with a nearly
+//! transparent window, wait for a click, and read that pixel back from the screen.
+
+use crate::dialog::{Color, PickColorError};
+use r#continue::Sender;
+use std::cell::RefCell;
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::Graphics::Dwm::DwmFlush;
+use windows::Win32::Graphics::Gdi::{
+    BLACK_BRUSH, GetDC, GetPixel, GetStockObject, HBRUSH, ReleaseDC,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_ESCAPE;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, GetCursorPos, GetSystemMetrics, IDC_CROSS,
+    LWA_ALPHA, LoadCursorW, RegisterClassExW, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
+    SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SW_HIDE, SW_SHOW, SetCursor, SetForegroundWindow,
+    SetLayeredWindowAttributes, ShowWindow, WM_KEYDOWN, WM_LBUTTONDOWN, WM_RBUTTONDOWN,
+    WM_SETCURSOR, WNDCLASSEXW, WS_EX_LAYERED, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP,
+};
+use windows::core::{PCWSTR, w};
+
+type PickSender = Sender<Result<Color, PickColorError>>;
+
+thread_local! {
+    /// The open picker, if any.
+    static PICKER: RefCell<Option<PickSender>> = const { RefCell::new(None) };
+    static CLASS_REGISTERED: RefCell<bool> = const { RefCell::new(false) };
+}
+
+const CLASS_NAME: PCWSTR = w!("app_window_color_picker");
+
+fn finish(hwnd: HWND, result: Result<Color, PickColorError>) {
+    unsafe { _ = DestroyWindow(hwnd) };
+    if let Some(sender) = PICKER.take() {
+        sender.send(result);
+    }
+}
+
+fn sample_cursor(hwnd: HWND) -> Result<Color, PickColorError> {
+    let mut point = POINT::default();
+    unsafe { GetCursorPos(&mut point) }.map_err(|e| PickColorError::Platform(e.to_string()))?;
+    //get the overlay out of the way, and let the compositor catch up, before reading the screen
+    unsafe {
+        _ = ShowWindow(hwnd, SW_HIDE);
+        _ = DwmFlush();
+    }
+    crate::application::record_platform_round_trip();
+    let dc = unsafe { GetDC(None) };
+    let pixel = unsafe { GetPixel(dc, point.x, point.y) };
+    unsafe { ReleaseDC(None, dc) };
+    //CLR_INVALID
+    if pixel.0 == 0xFFFF_FFFF {
+        return Err(PickColorError::Platform(
+            "can't read the screen at the cursor".to_string(),
+        ));
+    }
+    //COLORREF is 0x00BBGGRR
+    Ok(Color::from_rgb8(
+        (pixel.0 & 0xFF) as u8,
+        ((pixel.0 >> 8) & 0xFF) as u8,
+        ((pixel.0 >> 16) & 0xFF) as u8,
+    ))
+}
+
+extern "system" fn picker_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    match msg {
+        WM_LBUTTONDOWN => {
+            let result = sample_cursor(hwnd);
+            finish(hwnd, result);
+            LRESULT(0)
+        }
+        WM_RBUTTONDOWN => {
+            finish(hwnd, Err(PickColorError::Cancelled));
+            LRESULT(0)
+        }
+        WM_KEYDOWN if w_param.0 == VK_ESCAPE.0 as usize => {
+            finish(hwnd, Err(PickColorError::Cancelled));
+            LRESULT(0)
+        }
+        WM_SETCURSOR => {
+            if let Ok(cursor) = unsafe { LoadCursorW(None, IDC_CROSS) } {
+                unsafe { SetCursor(Some(cursor)) };
+            }
+            LRESULT(1)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) },
+    }
+}
+
+fn create_overlay() -> windows::core::Result<HWND> {
+    let instance = unsafe { GetModuleHandleW(PCWSTR::null()) }?;
+    if !CLASS_REGISTERED.replace(true) {
+        let class = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(picker_proc),
+            hInstance: instance.into(),
+            hbrBackground: HBRUSH(unsafe { GetStockObject(BLACK_BRUSH) }.0),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        if unsafe { RegisterClassExW(&class) } == 0 {
+            CLASS_REGISTERED.set(false);
+            return Err(windows::core::Error::from_thread());
+        }
+    }
+    //cover every monitor
+    let hwnd = unsafe {
+        CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW,
+            CLASS_NAME,
+            w!("Pick a color"),
+            WS_POPUP,
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+    }?;
+    //fully transparent windows don't receive clicks, so use the smallest visible alpha
+    if let Err(e) = unsafe { SetLayeredWindowAttributes(hwnd, COLORREF(0), 1, LWA_ALPHA) } {
+        unsafe { _ = DestroyWindow(hwnd) };
+        return Err(e);
+    }
+    unsafe {
+        _ = ShowWindow(hwnd, SW_SHOW);
+        _ = SetForegroundWindow(hwnd);
+    }
+    Ok(hwnd)
+}
+
+pub async fn pick_color() -> Result<Color, PickColorError> {
+    let (sender, fut) = r#continue::continuation();
+    crate::application::on_main_thread("pick_color".into(), move || {
+        if PICKER.with_borrow(Option::is_some) {
+            sender.send(Err(PickColorError::Platform(
+                "a color picker is already open".to_string(),
+            )));
+            return;
+        }
+        PICKER.replace(Some(sender));
+        if let Err(e) = create_overlay()
+            && let Some(sender) = PICKER.take()
+        {
+            sender.send(Err(PickColorError::Platform(e.to_string())));
+        }
+    })
+    .await;
+    fut.await
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Per-monitor DPI awareness.
+//!
+//! We opt into PerMonitorV2, so Windows stops bitmap-stretching our windows and reports their
+//! sizes in physical pixels.  Our `Size`s are logical, so they're converted here at the edges.
+//!
+//! Positions are logical too, at the scale of the monitor they're on: each monitor's rect on
+//! the virtual screen is divided by its own scale factor.  On a mixed-DPI desktop that leaves
+//! gaps or overlaps between monitors, but a point on a monitor maps to the same logical point
+//! and back, and a window's position and size always share a scale.
+
+use super::HWND_IMPS;
+use crate::coordinates::{Position, Size};
+use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, HDC, HMONITOR, MONITOR_DEFAULTTONEAREST, MonitorFromPoint,
+};
+use windows::Win32::UI::HiDpi::{
+    DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, GetDpiForMonitor, GetDpiForSystem, GetDpiForWindow,
+    MDT_EFFECTIVE_DPI, SetProcessDpiAwarenessContext,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClientRect, SWP_NOACTIVATE, SWP_NOZORDER, SetWindowPos,
+};
+
+/// Opts the process in.  Call before making any windows.
+pub(super) fn opt_in() {
+    //fails if a manifest or an earlier call already chose; either way, that choice stands
+    if let Err(e) =
+        unsafe { SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) }
+    {
+        logwise::debuginternal_sync!(
+            "DPI awareness already set: {e}",
+            e = logwise::privacy::LogIt(&e)
+        );
+    }
+}
+
+/// `hwnd`'s scale factor.
+pub(super) fn scale(hwnd: HWND) -> f64 {
+    match unsafe { GetDpiForWindow(hwnd) } {
+        //not a window (any more)
+        0 => 1.0,
+        dpi => dpi as f64 / 96.0,
+    }
+}
+
+/// The primary monitor's scale factor, for sizing a window before it exists.
+pub(super) fn system_scale() -> f64 {
+    let dpi = unsafe { GetDpiForSystem() };
+    dpi as f64 / 96.0
+}
+
+/// `monitor`'s scale factor, or the primary's if it won't say.
+fn monitor_scale(monitor: HMONITOR) -> f64 {
+    let (mut x, mut y) = (0, 0);
+    match unsafe { GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut x, &mut y) } {
+        Ok(()) if x != 0 => x as f64 / 96.0,
+        _ => system_scale(),
+    }
+}
+
+/// Every monitor's rect on the virtual screen, in physical pixels, and its scale factor.
+fn monitors() -> Vec<(RECT, f64)> {
+    unsafe extern "system" fn each(
+        monitor: HMONITOR,
+        _dc: HDC,
+        rect: *mut RECT,
+        monitors: LPARAM,
+    ) -> windows::core::BOOL {
+        let monitors = unsafe { &mut *(monitors.0 as *mut Vec<(RECT, f64)>) };
+        monitors.push((unsafe { *rect }, monitor_scale(monitor)));
+        true.into()
+    }
+    let mut monitors = Vec::new();
+    _ = unsafe {
+        EnumDisplayMonitors(
+            None,
+            None,
+            Some(each),
+            LPARAM(&mut monitors as *mut Vec<(RECT, f64)> as isize),
+        )
+    };
+    monitors
+}
+
+/// The scale factor of the monitor at `x`, `y` on the virtual screen, or the nearest one.
+pub(super) fn scale_at(x: i32, y: i32) -> f64 {
+    monitor_scale(unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST) })
+}
+
+/// The point `x`, `y` on the virtual screen, in logical pixels at its monitor's scale.
+pub(super) fn logical_position(x: i32, y: i32) -> Position {
+    let scale = scale_at(x, y);
+    Position::new(x as f64 / scale, y as f64 / scale)
+}
+
+/// `position`, on the virtual screen in physical pixels.  The inverse of
+/// [`logical_position`]; off every monitor, it's at the primary's scale.
+pub(super) fn physical_position(position: Position) -> (i32, i32) {
+    let scale = monitors()
+        .into_iter()
+        .find(|(rect, scale)| {
+            let (x, y) = (position.x() * scale, position.y() * scale);
+            (rect.left as f64..rect.right as f64).contains(&x)
+                && (rect.top as f64..rect.bottom as f64).contains(&y)
+        })
+        .map_or_else(system_scale, |(_, scale)| scale);
+    (
+        (position.x() * scale).round() as i32,
+        (position.y() * scale).round() as i32,
+    )
+}
+
+/// `width` by `height` physical pixels, in logical pixels at `scale`.
+pub(super) fn logical(width: i32, height: i32, scale: f64) -> Size {
+    Size::new(width as f64 / scale, height as f64 / scale)
+}
+
+/// `size`, in physical pixels at `scale`.
+pub(super) fn physical(size: Size, scale: f64) -> (i32, i32) {
+    (
+        (size.width() * scale).round() as i32,
+        (size.height() * scale).round() as i32,
+    )
+}
+
+/// Handles `WM_DPICHANGED`: moves to the rect Windows suggests, which keeps the logical size,
+/// and tells the surface even if that leaves the physical size alone.
+pub(super) fn changed(hwnd: HWND, l_param: LPARAM) {
+    let client_size = || {
+        let mut rect = RECT::default();
+        _ = unsafe { GetClientRect(hwnd, &mut rect) };
+        (rect.right, rect.bottom)
+    };
+    let before = client_size();
+    let suggested = unsafe { *(l_param.0 as *const RECT) };
+    _ = unsafe {
+        SetWindowPos(
+            hwnd,
+            None,
+            suggested.left,
+            suggested.top,
+            suggested.right - suggested.left,
+            suggested.bottom - suggested.top,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+        )
+    };
+    let after = client_size();
+    //otherwise WM_SIZE did it
+    if before == after {
+        let size = logical(after.0, after.1, scale(hwnd));
+        HWND_IMPS.with_borrow(|c| {
+            if let Some(f) = c.get(&hwnd.0).and_then(|imp| imp.size_notify.as_ref()) {
+                f(size)
+            }
+        });
+    }
+}
@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Outgoing drag-and-drop via OLE `DoDragDrop`.
+
+use super::Window;
+use crate::drag::{
+    DragAction, DragActions, DragData, DragError, DragImage, DragOutcome, DragReporter,
+};
+use std::cell::Cell;
+use std::path::PathBuf;
+use windows::Win32::Foundation::{
+    DATA_S_SAMEFORMATETC, DRAGDROP_S_CANCEL, DRAGDROP_S_DROP, DRAGDROP_S_USEDEFAULTCURSORS,
+    DV_E_FORMATETC, E_NOTIMPL, OLE_E_ADVISENOTSUPPORTED, POINT, S_OK,
+};
+use windows::Win32::System::Com::{
+    DATADIR_GET, DVASPECT_CONTENT, FORMATETC, IAdviseSink, IDataObject, IDataObject_Impl,
+    IEnumFORMATETC, IEnumSTATDATA, STGMEDIUM, STGMEDIUM_0, TYMED_HGLOBAL,
+};
+use windows::Win32::System::DataExchange::RegisterClipboardFormatW;
+use windows::Win32::System::Memory::{GMEM_MOVEABLE, GlobalAlloc, GlobalLock, GlobalUnlock};
+use windows::Win32::System::Ole::{
+    CF_HDROP, CF_UNICODETEXT, DROPEFFECT, DROPEFFECT_COPY, DROPEFFECT_LINK, DROPEFFECT_MOVE,
+    DoDragDrop, IDropSource, IDropSource_Impl, OleInitialize,
+};
+use windows::Win32::System::SystemServices::{MK_LBUTTON, MODIFIERKEYS_FLAGS};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetKeyState, VK_LBUTTON};
+use windows::Win32::UI::Shell::{DROPFILES, SHCreateStdEnumFmtEtc};
+use windows::core::{BOOL, HRESULT, HSTRING, Ref, implement};
+
+thread_local! {
+    static OLE_INITIALIZED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// OLE must be initialized on the thread that runs the drag loop.
+fn ensure_ole_initialized() -> Result<(), DragError> {
+    if !OLE_INITIALIZED.get() {
+        unsafe { OleInitialize(None) }.map_err(|e| DragError::Platform(e.to_string()))?;
+        OLE_INITIALIZED.set(true);
+    }
+    Ok(())
+}
+
+/// Registers (or looks up) the clipboard format named after a MIME type.
+fn registered_format(mime: &str) -> u16 {
+    let name: HSTRING = mime.into();
+    unsafe { RegisterClipboardFormatW(&name) as u16 }
+}
+
+fn utf16_nul(s: &str) -> Vec<u8> {
+    s.encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+/// Builds a `CF_HDROP` payload: a `DROPFILES` header followed by a double-nul-terminated
+/// list of wide paths.
+fn hdrop(paths: &[PathBuf]) -> Vec<u8> {
+    let header = DROPFILES {
+        pFiles: std::mem::size_of::<DROPFILES>() as u32,
+        pt: POINT::default(),
+        fNC: BOOL(0),
+        fWide: BOOL(1),
+    };
+    let header_bytes = unsafe {
+        std::slice::from_raw_parts(
+            &header as *const DROPFILES as *const u8,
+            std::mem::size_of::<DROPFILES>(),
+        )
+    };
+    let mut out = header_bytes.to_vec();
+    for path in paths {
+        out.extend(utf16_nul(&path.to_string_lossy()));
+    }
+    out.extend([0, 0]);
+    out
+}
+
+/// Every format we offer, paired with its `HGLOBAL` contents.
+fn formats_for(data: &DragData) -> Vec<(u16, Vec<u8>)> {
+    let mut formats = vec![(registered_format(data.mime()), data.encoded())];
+    match data {
+        DragData::Paths(paths) => formats.push((CF_HDROP.0, hdrop(paths))),
+        DragData::Bytes { mime, bytes } => {
+            if mime.starts_with("text/plain")
+                && let Ok(text) = std::str::from_utf8(bytes)
+            {
+                formats.push((CF_UNICODETEXT.0, utf16_nul(text)));
+            }
+        }
+    }
+    formats
+}
+
+fn formatetc(cf: u16) -> FORMATETC {
+    FORMATETC {
+        cfFormat: cf,
+        ptd: std::ptr::null_mut(),
+        dwAspect: DVASPECT_CONTENT.0,
+        lindex: -1,
+        tymed: TYMED_HGLOBAL.0 as u32,
+    }
+}
+
+#[implement(IDataObject)]
+struct DataObject {
+    formats: Vec<(u16, Vec<u8>)>,
+}
+
+impl DataObject {
+    fn find(&self, format: *const FORMATETC) -> Option<&[u8]> {
+        let format = unsafe { format.as_ref() }?;
+        if format.tymed & TYMED_HGLOBAL.0 as u32 == 0 {
+            return None;
+        }
+        self.formats
+            .iter()
+            .find(|(cf, _)| *cf == format.cfFormat)
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+}
+
+impl IDataObject_Impl for DataObject_Impl {
+    fn GetData(&self, pformatetcin: *const FORMATETC) -> windows::core::Result<STGMEDIUM> {
+        let bytes = self.find(pformatetcin).ok_or(DV_E_FORMATETC)?;
+        let global = unsafe { GlobalAlloc(GMEM_MOVEABLE, bytes.len()) }?;
+        unsafe {
+            let ptr = GlobalLock(global) as *mut u8;
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+            //returns an error once the lock count reaches zero, which is what we want
+            _ = GlobalUnlock(global);
+        }
+        Ok(STGMEDIUM {
+            tymed: TYMED_HGLOBAL.0 as u32,
+            u: STGMEDIUM_0 { hGlobal: global },
+            pUnkForRelease: std::mem::ManuallyDrop::new(None),
+        })
+    }
+
+    fn GetDataHere(
+        &self,
+        _pformatetc: *const FORMATETC,
+        _pmedium: *mut STGMEDIUM,
+    ) -> windows::core::Result<()> {
+        Err(E_NOTIMPL.into())
+    }
+
+    fn QueryGetData(&self, pformatetc: *const FORMATETC) -> HRESULT {
+        if self.find(pformatetc).is_some() {
+            S_OK
+        } else {
+            DV_E_FORMATETC
+        }
+    }
+
+    fn GetCanonicalFormatEtc(
+        &self,
+        _pformatectin: *const FORMATETC,
+        pformatetcout: *mut FORMATETC,
+    ) -> HRESULT {
+        if let Some(out) = unsafe { pformatetcout.as_mut() } {
+            out.ptd = std::ptr::null_mut();
+        }
+        DATA_S_SAMEFORMATETC
+    }
+
+    fn SetData(
+        &self,
+        _pformatetc: *const FORMATETC,
+        _pmedium: *const STGMEDIUM,
+        _frelease: BOOL,
+    ) -> windows::core::Result<()> {
+        Err(E_NOTIMPL.into())
+    }
+
+    fn EnumFormatEtc(&self, dwdirection: u32) -> windows::core::Result<IEnumFORMATETC> {
+        if dwdirection != DATADIR_GET.0 as u32 {
+            return Err(E_NOTIMPL.into());
+        }
+        let formats: Vec<FORMATETC> = self.formats.iter().map(|(cf, _)| formatetc(*cf)).collect();
+        unsafe { SHCreateStdEnumFmtEtc(&formats) }
+    }
+
+    fn DAdvise(
+        &self,
+        _pformatetc: *const FORMATETC,
+        _advf: u32,
+        _padvsink: Ref<IAdviseSink>,
+    ) -> windows::core::Result<u32> {
+        Err(OLE_E_ADVISENOTSUPPORTED.into())
+    }
+
+    fn DUnadvise(&self, _dwconnection: u32) -> windows::core::Result<()> {
+        Err(OLE_E_ADVISENOTSUPPORTED.into())
+    }
+
+    fn EnumDAdvise(&self) -> windows::core::Result<IEnumSTATDATA> {
+        Err(OLE_E_ADVISENOTSUPPORTED.into())
+    }
+}
+
+fn drop_effects(actions: DragActions) -> DROPEFFECT {
+    let mut effects = DROPEFFECT::default();
+    for (action, effect) in [
+        (DragAction::Copy, DROPEFFECT_COPY),
+        (DragAction::Move, DROPEFFECT_MOVE),
+        (DragAction::Link, DROPEFFECT_LINK),
+    ] {
+        if actions.contains(action) {
+            effects |= effect;
+        }
+    }
+    effects
+}
+
+/// The action in a single effect, as targets report them.
+fn drag_action(effect: DROPEFFECT) -> Option<DragAction> {
+    if effect.contains(DROPEFFECT_MOVE) {
+        Some(DragAction::Move)
+    } else if effect.contains(DROPEFFECT_COPY) {
+        Some(DragAction::Copy)
+    } else if effect.contains(DROPEFFECT_LINK) {
+        Some(DragAction::Link)
+    } else {
+        None
+    }
+}
+
+#[implement(IDropSource)]
+struct DropSource {
+    reporter: DragReporter,
+}
+
+impl IDropSource_Impl for DropSource_Impl {
+    fn QueryContinueDrag(&self, fescapepressed: BOOL, grfkeystate: MODIFIERKEYS_FLAGS) -> HRESULT {
+        if fescapepressed.as_bool() {
+            DRAGDROP_S_CANCEL
+        } else if grfkeystate.0 & MK_LBUTTON.0 == 0 {
+            DRAGDROP_S_DROP
+        } else {
+            S_OK
+        }
+    }
+
+    fn GiveFeedback(&self, dweffect: DROPEFFECT) -> HRESULT {
+        //the effect the target under the pointer would perform
+        self.reporter.set_action(drag_action(dweffect));
+        DRAGDROP_S_USEDEFAULTCURSORS
+    }
+}
+
+fn start_drag_main(data: DragData, reporter: DragReporter) -> Result<(), DragError> {
+    let left_down = unsafe { GetKeyState(VK_LBUTTON.0 as i32) } as u16 & 0x8000 != 0;
+    if !left_down {
+        return Err(DragError::NoPointerGrab);
+    }
+    ensure_ole_initialized()?;
+    let data_object: IDataObject = DataObject {
+        formats: formats_for(&data),
+    }
+    .into();
+    let effects = drop_effects(reporter.actions());
+    let drop_source: IDropSource = DropSource {
+        reporter: reporter.clone(),
+    }
+    .into();
+    let mut effect = DROPEFFECT::default();
+    //runs a modal loop until the drop completes
+    let hr = unsafe { DoDragDrop(&data_object, &drop_source, effects, &mut effect) };
+    if hr == DRAGDROP_S_DROP {
+        //targets that refuse the drop still report a drop, with no effect
+        reporter.finish(drag_action(effect).map_or(DragOutcome::Cancelled, DragOutcome::Dropped));
+        Ok(())
+    } else if hr == DRAGDROP_S_CANCEL {
+        reporter.finish(DragOutcome::Cancelled);
+        Ok(())
+    } else {
+        Err(DragError::Platform(
+            windows::core::Error::from(hr).to_string(),
+        ))
+    }
+}
+
+impl Window {
+    pub async fn start_drag(
+        &self,
+        data: DragData,
+        _image: Option<DragImage>,
+        reporter: DragReporter,
+    ) -> Result<(), DragError> {
+        crate::application::on_main_thread("Window::start_drag".into(), move || {
+            start_drag_main(data, reporter)
+        })
+        .await
+    }
+}
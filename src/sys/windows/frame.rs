@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Frame callbacks paced by DWM.
+//!
+//! `DwmFlush` blocks until the compositor's next pass, so one thread waits on it for every
+//! window's pending frames and hands them back to the main thread together.
+
+use crate::application::time::{Duration, Instant};
+use crate::window::{FrameCallback, FrameTiming};
+use std::sync::OnceLock;
+use std::sync::mpsc::{Sender, channel};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Graphics::Dwm::{DWM_TIMING_INFO, DwmFlush, DwmGetCompositionTimingInfo};
+
+static FRAMES: OnceLock<Sender<FrameCallback>> = OnceLock::new();
+
+/// How long a frame is when DWM can't say, as when composition is off on Windows 7.
+const FALLBACK_INTERVAL: Duration = Duration::from_micros(16_667);
+
+fn refresh_interval() -> Option<Duration> {
+    let mut info = DWM_TIMING_INFO {
+        cbSize: size_of::<DWM_TIMING_INFO>() as u32,
+        ..Default::default()
+    };
+    //since Windows 8.1 this must be the null window, and describes the whole desktop
+    unsafe { DwmGetCompositionTimingInfo(HWND::default(), &mut info) }.ok()?;
+    let rate = info.rateRefresh;
+    (rate.uiNumerator != 0)
+        .then(|| Duration::from_secs_f64(rate.uiDenominator as f64 / rate.uiNumerator as f64))
+}
+
+pub(super) fn request(frame: FrameCallback) {
+    FRAMES
+        .get_or_init(|| {
+            let (sender, receiver) = channel::<FrameCallback>();
+            std::thread::Builder::new()
+                .name("app_window frames".to_string())
+                .spawn(move || {
+                    while let Ok(first) = receiver.recv() {
+                        if unsafe { DwmFlush() }.is_err() {
+                            std::thread::sleep(FALLBACK_INTERVAL);
+                        }
+                        let timing = FrameTiming::new(Instant::now(), None, refresh_interval());
+                        let frames: Vec<_> =
+                            std::iter::once(first).chain(receiver.try_iter()).collect();
+                        crate::sys::on_main_thread(move || {
+                            for frame in frames {
+                                frame(timing);
+                            }
+                        });
+                    }
+                })
+                .expect("Can't spawn frame thread");
+            sender
+        })
+        .send(frame)
+        .expect("Frame thread stopped");
+}
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Window icons, and progress on the taskbar button.
+
+use super::HWND_IMPS;
+use crate::window::{IconData, WindowSettingError};
+use std::cell::OnceCell;
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BI_RGB, BITMAPINFO, BITMAPINFOHEADER, CreateBitmap, CreateDIBSection, DIB_RGB_COLORS,
+    DeleteObject,
+};
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+};
+use windows::Win32::UI::Shell::{ITaskbarList3, TBPF_NOPROGRESS, TaskbarList};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateIconIndirect, DestroyIcon, GetSystemMetrics, HICON, ICON_BIG, ICON_SMALL, ICONINFO,
+    SM_CXICON, SM_CXSMICON, SendMessageW, WM_SETICON,
+};
+
+thread_local! {
+    static TASKBAR: OnceCell<Option<ITaskbarList3>> = const { OnceCell::new() };
+}
+
+/// A `side` by `side` icon.  Unlike DWM's bitmaps, icons want straight alpha.
+pub(super) fn create(icon: &IconData, side: u32) -> windows::core::Result<HICON> {
+    let image = icon.square(side);
+    let info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: side as i32,
+            //negative for top-down
+            biHeight: -(side as i32),
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut bits = std::ptr::null_mut();
+    let color = unsafe { CreateDIBSection(None, &info, DIB_RGB_COLORS, &mut bits, None, 0) }?;
+    let pixels: Vec<u8> = image
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+        .collect();
+    unsafe { std::ptr::copy_nonoverlapping(pixels.as_ptr(), bits as *mut u8, pixels.len()) };
+    //with an alpha channel the mask is ignored, but CreateIconIndirect still wants one
+    let mask = unsafe { CreateBitmap(side as i32, side as i32, 1, 1, None) };
+    let icon = unsafe {
+        CreateIconIndirect(&ICONINFO {
+            fIcon: true.into(),
+            xHotspot: 0,
+            yHotspot: 0,
+            hbmMask: mask,
+            hbmColor: color,
+        })
+    };
+    //the icon has its own copies
+    unsafe {
+        _ = DeleteObject(color.into());
+        _ = DeleteObject(mask.into());
+    }
+    icon
+}
+
+/// Replaces the window's icons.  Call on the main thread.
+pub(super) fn set(hwnd: HWND, icon: Option<IconData>) -> Result<(), WindowSettingError> {
+    let icons = match &icon {
+        Some(icon) => {
+            let big = unsafe { GetSystemMetrics(SM_CXICON) }.max(1) as u32;
+            let small = unsafe { GetSystemMetrics(SM_CXSMICON) }.max(1) as u32;
+            let big = create(icon, big).map_err(|e| WindowSettingError::Platform(e.to_string()))?;
+            match create(icon, small) {
+                Ok(small) => Some((big, small)),
+                Err(e) => {
+                    _ = unsafe { DestroyIcon(big) };
+                    return Err(WindowSettingError::Platform(e.to_string()));
+                }
+            }
+        }
+        None => None,
+    };
+    let (big, small) = icons.unwrap_or_default();
+    for (kind, icon) in [(ICON_BIG, big), (ICON_SMALL, small)] {
+        //a null icon goes back to the class's
+        unsafe {
+            SendMessageW(
+                hwnd,
+                WM_SETICON,
+                Some(WPARAM(kind as usize)),
+                Some(LPARAM(icon.0 as isize)),
+            )
+        };
+    }
+    //the window doesn't own icons set this way, so free the ones it stopped using
+    let old = HWND_IMPS
+        .with_borrow_mut(|c| std::mem::replace(&mut c.entry(hwnd.0).or_default().icons, icons));
+    if let Some((big, small)) = old {
+        unsafe {
+            _ = DestroyIcon(big);
+            _ = DestroyIcon(small);
+        }
+    }
+    Ok(())
+}
+
+/// Frees the window's icons as it's destroyed.  Call on the main thread.
+pub(super) fn forget(hwnd: HWND) {
+    let icons = HWND_IMPS.with_borrow_mut(|c| c.get_mut(&hwnd.0).and_then(|imp| imp.icons.take()));
+    if let Some((big, small)) = icons {
+        unsafe {
+            _ = DestroyIcon(big);
+            _ = DestroyIcon(small);
+        }
+    }
+}
+
+fn taskbar() -> Option<ITaskbarList3> {
+    TASKBAR.with(|taskbar| {
+        taskbar
+            .get_or_init(|| unsafe {
+                //S_FALSE (already initialized) and RPC_E_CHANGED_MODE both leave COM usable
+                _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+                let taskbar: ITaskbarList3 =
+                    CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER).ok()?;
+                taskbar.HrInit().ok()?;
+                Some(taskbar)
+            })
+            .clone()
+    })
+}
+
+/// Shows `progress`, from 0 to 1, on the window's taskbar button.  Call on the main thread.
+pub(super) fn set_progress(hwnd: HWND, progress: Option<f64>) -> Result<(), WindowSettingError> {
+    //Explorer isn't running, say on Server Core
+    let taskbar = taskbar().ok_or(WindowSettingError::Unsupported)?;
+    let result = match progress {
+        //SetProgressValue also turns the bar on
+        Some(progress) => unsafe {
+            const TOTAL: u64 = 10_000;
+            taskbar.SetProgressValue(hwnd, (progress * TOTAL as f64) as u64, TOTAL)
+        },
+        None => unsafe { taskbar.SetProgressState(hwnd, TBPF_NOPROGRESS) },
+    };
+    result.map_err(|e| WindowSettingError::Platform(e.to_string()))
+}
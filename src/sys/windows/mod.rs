@@ -0,0 +1,1428 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::application::{
+    ActivationPolicy, ActivationPolicyError, CapturePermission, DispatchSlice,
+};
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::Color;
+use crate::window::{
+    CanvasBehavior, CloseBehavior, CloseResponse, CreateOptions, Cursor, DecorationTheme,
+    DecorationsMode, FrameCallback, FrameRequester, IconData, NativeHandle, PreviewImage,
+    TiledEdges, WindowDebugState, WindowEvent, WindowEventSender, WindowSettingError, WindowState,
+    WindowStateFlags,
+};
+use raw_window_handle::{
+    RawDisplayHandle, RawWindowHandle, Win32WindowHandle, WindowsDisplayHandle,
+};
+use send_cells::send_cell::SendCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::fmt::Display;
+use std::num::NonZero;
+use std::sync::Arc;
+use windows::Win32::Foundation::{
+    COLORREF, GetLastError, HINSTANCE, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM,
+};
+use windows::Win32::Graphics::Dwm::{DWMWA_CLOAKED, DwmGetWindowAttribute};
+use windows::Win32::Graphics::Gdi::{
+    ClientToScreen, CombineRgn, CreateRectRgn, DeleteObject, GetMonitorInfoW, HBRUSH, HMONITOR,
+    MONITOR_DEFAULTTONEAREST, MONITORINFO, MonitorFromWindow, RDW_INVALIDATE, RGN_OR, RedrawWindow,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::RemoteDesktop::{
+    NOTIFY_FOR_THIS_SESSION, WTSRegisterSessionNotification, WTSUnRegisterSessionNotification,
+};
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::Accessibility::{HWINEVENTHOOK, SetWinEventHook};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, EVENT_OBJECT_CLOAKED,
+    EVENT_OBJECT_UNCLOAKED, GWL_EXSTYLE, GWL_STYLE, GetClientRect, GetForegroundWindow,
+    GetMessageW, GetSystemMenu, GetSystemMetrics, GetWindowLongW, GetWindowPlacement,
+    GetWindowRect, HICON, HWND_NOTOPMOST, HWND_TOPMOST, IDC_ARROW, IsIconic, IsWindow,
+    IsWindowVisible, IsZoomed, LoadCursorW, MSG, OBJID_WINDOW, PM_NOREMOVE, PM_QS_INPUT,
+    PM_QS_PAINT, PM_REMOVE, PeekMessageW, PostMessageW, PostQuitMessage, PostThreadMessageW,
+    RegisterClassExW, SIZE_MINIMIZED, SM_CXSCREEN, SM_CYSCREEN, SW_HIDE, SW_RESTORE, SW_SHOW,
+    SW_SHOWMAXIMIZED, SW_SHOWNORMAL, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+    SWP_NOZORDER, SWP_SHOWWINDOW, SetForegroundWindow, SetWindowLongW, SetWindowPos,
+    SetWindowTextW, ShowWindow, TPM_RETURNCMD, TPM_RIGHTBUTTON, TrackPopupMenu, TranslateMessage,
+    WA_INACTIVE, WINDOW_EX_STYLE, WINDOW_STYLE, WINDOWPLACEMENT, WINEVENT_OUTOFCONTEXT,
+    WM_ACTIVATE, WM_CLOSE, WM_DESTROY, WM_DPICHANGED, WM_DWMSENDICONICLIVEPREVIEWBITMAP,
+    WM_DWMSENDICONICTHUMBNAIL, WM_ERASEBKGND, WM_GETMINMAXINFO, WM_MOVE, WM_PAINT, WM_QUIT,
+    WM_SETCURSOR, WM_SHOWWINDOW, WM_SIZE, WM_SYSCOMMAND, WM_USER, WM_WTSSESSION_CHANGE,
+    WNDCLASSEXW, WS_CAPTION, WS_EX_TOOLWINDOW, WS_OVERLAPPEDWINDOW, WS_POPUP, WTS_CONSOLE_CONNECT,
+    WTS_REMOTE_CONNECT,
+};
+#[cfg(feature = "accessibility")]
+use windows::Win32::UI::WindowsAndMessaging::{WM_GETOBJECT, WM_KILLFOCUS, WM_SETFOCUS};
+use windows::core::{HSTRING, PCWSTR, w};
+
+mod audio;
+#[cfg(feature = "accessibility")]
+mod ax;
+mod clipboard;
+mod confine;
+mod cursor;
+mod dialog;
+mod dpi;
+mod drag;
+mod frame;
+mod icon;
+mod notification;
+mod options;
+mod performance;
+mod power;
+mod preview;
+mod sheet;
+mod shortcuts;
+mod splash;
+mod timer;
+mod touch_keyboard;
+mod tray;
+
+pub use audio::{audio_hint_capabilities, set_audio_hints};
+pub use clipboard::{unwatch_clipboard, watch_clipboard};
+pub use dialog::pick_color;
+pub use notification::post_notification;
+pub use performance::{performance_hint_capabilities, set_performance_hints};
+pub use power::{unwatch_power, watch_power};
+pub use timer::on_main_thread_after;
+pub use tray::TrayIcon;
+
+//any desktop app may capture the screen
+pub async fn capture_permission_state() -> CapturePermission {
+    CapturePermission::NotRequired
+}
+
+pub async fn request_capture_permission() -> CapturePermission {
+    CapturePermission::NotRequired
+}
+
+const WM_RUN_FUNCTION: u32 = WM_USER;
+
+#[derive(Debug)]
+pub struct FullscreenError;
+
+impl Display for FullscreenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "FullscreenError")
+    }
+}
+impl std::error::Error for FullscreenError {}
+
+fn main_thread_id() -> u32 {
+    static mut MAIN_THREAD_ID: u32 = 0;
+    #[used]
+    #[allow(non_upper_case_globals)]
+    #[unsafe(link_section = ".CRT$XCU")]
+    static INIT_MAIN_THREAD_ID: unsafe fn() = {
+        unsafe fn initer() {
+            unsafe { MAIN_THREAD_ID = windows::Win32::System::Threading::GetCurrentThreadId() };
+        }
+        initer
+    };
+
+    unsafe { MAIN_THREAD_ID }
+}
+
+pub fn is_main_thread() -> bool {
+    //windows does not have a clear concept of a main thread but allows any thread to be in charge
+    //of a window.  However for compatibility we project a 'main thread-like' concept onto windows
+    let current_id = unsafe { windows::Win32::System::Threading::GetCurrentThreadId() };
+    current_id == main_thread_id()
+}
+
+struct WinClosure(Box<dyn FnOnce() + Send + 'static>);
+
+#[derive(Default)]
+struct HwndImp {
+    size_notify: Option<Box<dyn Fn(Size)>>,
+    close_behavior: CloseBehavior,
+    skip_taskbar: bool,
+    all_workspaces: bool,
+    preview: Option<PreviewImage>,
+    /// What a splash window paints.
+    placeholder: Option<PreviewImage>,
+    /// Wants system shortcuts while in the foreground; see `shortcuts.rs`.
+    inhibit_shortcuts: bool,
+    /// Keeps the cursor inside the window, or the given part of it; see `confine.rs`.
+    confinement: Option<Option<Rect>>,
+    /// So the next non-minimized `WM_SIZE` can report a restore.
+    minimized: bool,
+    /// Shown over the client area; see `cursor.rs`.
+    cursor: Cursor,
+    /// Set while this window is up as a sheet; see `sheet.rs`.
+    sheet: Option<sheet::Sheet>,
+    /// The big and small icons we gave the window; see `icon.rs`.
+    icons: Option<(HICON, HICON)>,
+    /// Kept above other apps' windows.
+    always_on_top: bool,
+    /// Smallest and largest outer sizes; see `options.rs`.
+    size_limits: (Option<Size>, Option<Size>),
+    /// Hidden, and maximized once shown; see `options.rs`.
+    maximize_on_show: bool,
+    /// Painted on `WM_ERASEBKGND`; see `options.rs`.
+    background: Option<COLORREF>,
+    /// The window we belong to, restored when a sheet ends; see `sheet.rs`.
+    owner: Option<HWND>,
+    /// The app's accessibility tree; see `ax.rs`.
+    #[cfg(feature = "accessibility")]
+    accessibility: ax::Accessibility,
+    events: WindowEventSender,
+}
+
+/// The window's event sender.  Call on the main thread.
+fn send_event(hwnd: HWND, event: WindowEvent) {
+    let events = HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().events.clone());
+    events.send(event)
+}
+/// The frame `hwnd` restores to from maximized, minimized or snapped, on the virtual screen.
+fn normal_rect(hwnd: HWND, monitor: HMONITOR) -> Option<RECT> {
+    let mut placement = WINDOWPLACEMENT {
+        length: std::mem::size_of::<WINDOWPLACEMENT>() as u32,
+        ..Default::default()
+    };
+    unsafe { GetWindowPlacement(hwnd, &mut placement) }.ok()?;
+    let mut normal = placement.rcNormalPosition;
+    //relative to the work area, except for tool windows
+    let ex_style = WINDOW_EX_STYLE(unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32);
+    if !ex_style.contains(WS_EX_TOOLWINDOW) {
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+            return None;
+        }
+        let (dx, dy) = (
+            info.rcWork.left - info.rcMonitor.left,
+            info.rcWork.top - info.rcMonitor.top,
+        );
+        normal.left += dx;
+        normal.right += dx;
+        normal.top += dy;
+        normal.bottom += dy;
+    }
+    Some(normal)
+}
+
+/// Edges of `hwnd` against `monitor`'s work area while it's snapped, which is when it's
+/// sized unlike the placement it restores to.
+fn snapped_edges(hwnd: HWND, monitor: HMONITOR) -> TiledEdges {
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    let mut rect = RECT::default();
+    if !unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool()
+        || unsafe { GetWindowRect(hwnd, &mut rect) }.is_err()
+    {
+        return TiledEdges::default();
+    }
+    let work = info.rcWork;
+    if normal_rect(hwnd, monitor).is_none_or(|normal| rect == normal) {
+        return TiledEdges::default();
+    }
+    //the frame's invisible resize borders hang past the work area, so compare loosely
+    TiledEdges {
+        left: rect.left <= work.left,
+        right: rect.right >= work.right,
+        top: rect.top <= work.top,
+        bottom: rect.bottom >= work.bottom,
+    }
+}
+
+/// Whether DWM hides `hwnd` though it isn't minimized, as on another virtual desktop.
+fn is_cloaked(hwnd: HWND) -> bool {
+    let mut cloaked = 0u32;
+    let got = unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut c_void,
+            size_of::<u32>() as u32,
+        )
+    };
+    got.is_ok() && cloaked != 0
+}
+
+unsafe extern "system" fn cloaking_changed(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    _id_child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    if id_object == OBJID_WINDOW.0 && HWND_IMPS.with_borrow(|c| c.contains_key(&hwnd.0)) {
+        update_state(hwnd, unsafe { GetForegroundWindow() } == hwnd);
+    }
+}
+
+/// Listens for our windows being cloaked and uncloaked, which no window message tells us.
+/// Call on the main thread.
+fn hook_cloaking() {
+    if CLOAKING_HOOKED.replace(true) {
+        return;
+    }
+    //out of context, so it's called from our message loop
+    let hook = unsafe {
+        SetWinEventHook(
+            EVENT_OBJECT_CLOAKED,
+            EVENT_OBJECT_UNCLOAKED,
+            None,
+            Some(cloaking_changed),
+            GetCurrentProcessId(),
+            0,
+            WINEVENT_OUTOFCONTEXT,
+        )
+    };
+    if hook.is_invalid() {
+        CLOAKING_HOOKED.set(false);
+    }
+}
+
+/// Sends `hwnd`'s [`WindowStateFlags`] if they changed.  `activated` is passed in, since
+/// during `WM_ACTIVATE` the foreground window hasn't changed yet.
+fn update_state(hwnd: HWND, activated: bool) {
+    let style = WINDOW_STYLE(unsafe { GetWindowLongW(hwnd, GWL_STYLE) } as u32);
+    let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+    let state = Window::state_main(hwnd, style, monitor);
+    let flags = WindowStateFlags {
+        maximized: state == WindowState::Maximized,
+        fullscreen: state == WindowState::Fullscreen,
+        tiled: match state {
+            WindowState::Normal => snapped_edges(hwnd, monitor),
+            _ => TiledEdges::default(),
+        },
+        activated,
+        suspended: state == WindowState::Minimized || is_cloaked(hwnd),
+    };
+    let events = HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().events.clone());
+    events.update_state(flags);
+}
+
+thread_local! {
+    static HWND_IMPS: RefCell<HashMap<*mut c_void /* hwnd */, HwndImp>> = RefCell::new(HashMap::new());
+    /// Set by an accessory activation policy; hides every window from the taskbar.
+    static HIDE_FROM_TASKBAR: Cell<bool> = const { Cell::new(false) };
+    /// Whether [`hook_cloaking`] has run.
+    static CLOAKING_HOOKED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Applies the extended styles that follow from the window's settings and the activation
+/// policy.  Call on the main thread.
+///
+/// Tool windows have no taskbar button, which both skipping the taskbar and (as an
+/// approximation) appearing on every desktop rely on.
+fn apply_window_settings(hwnd: HWND) -> Result<(), WindowSettingError> {
+    let (skip_taskbar, all_workspaces, always_on_top) = HWND_IMPS.with_borrow(|c| {
+        c.get(&hwnd.0)
+            .map(|imp| (imp.skip_taskbar, imp.all_workspaces, imp.always_on_top))
+            .unwrap_or_default()
+    });
+    let tool_window = skip_taskbar || all_workspaces || HIDE_FROM_TASKBAR.get();
+    let old_style = WINDOW_EX_STYLE(unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32);
+    let new_style = if tool_window {
+        old_style | WS_EX_TOOLWINDOW
+    } else {
+        old_style & !WS_EX_TOOLWINDOW
+    };
+    //the taskbar only notices the change when the window is shown again
+    let reshow = new_style != old_style && unsafe { IsWindowVisible(hwnd) }.as_bool();
+    if reshow {
+        unsafe { _ = ShowWindow(hwnd, SW_HIDE) };
+    }
+    unsafe { SetWindowLongW(hwnd, GWL_EXSTYLE, new_style.0 as i32) };
+    let insert_after = if all_workspaces || always_on_top {
+        HWND_TOPMOST
+    } else {
+        HWND_NOTOPMOST
+    };
+    let mut flags = SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_FRAMECHANGED;
+    if reshow {
+        flags |= SWP_SHOWWINDOW;
+    }
+    unsafe { SetWindowPos(hwnd, Some(insert_after), 0, 0, 0, 0, flags) }
+        .map_err(|e| WindowSettingError::Platform(e.to_string()))
+}
+
+pub async fn set_activation_policy(policy: ActivationPolicy) -> Result<(), ActivationPolicyError> {
+    crate::application::on_main_thread("set_activation_policy".into(), move || {
+        HIDE_FROM_TASKBAR.set(policy != ActivationPolicy::Regular);
+        let hwnds: Vec<*mut c_void> = HWND_IMPS.with_borrow(|c| c.keys().copied().collect());
+        for hwnd in hwnds.into_iter().map(HWND) {
+            //the map outlives destroyed windows
+            if unsafe { IsWindow(Some(hwnd)) }.as_bool() {
+                apply_window_settings(hwnd)
+                    .map_err(|e| ActivationPolicyError::Platform(e.to_string()))?;
+            }
+        }
+        Ok(())
+    })
+    .await
+}
+
+pub fn run_main_thread<F: FnOnce() + Send + 'static>(
+    closure: F,
+) -> Result<(), crate::application::MainError> {
+    //need to create a message queue first
+    let mut message = MSG::default();
+    _ = unsafe { PeekMessageW(&mut message, None, WM_USER, WM_USER, PM_NOREMOVE) }; //create a message queue
+    //we don't care about the return value of PeekMessageW, it simply tells us if messages are available or not
+
+    //now the queue is available so subsequent calls to PostMessageW will work
+    dpi::opt_in();
+    closure(); //I think it's ok to run inline on windows?
+    let mut slice = DispatchSlice::new();
+    loop {
+        let message_ret = unsafe { GetMessageW(&mut message, None, 0, 0) };
+        if message_ret.0 == 0 {
+            break;
+        } else if message_ret.0 == -1 {
+            panic!("GetMessageW failed");
+        }
+        match message.message {
+            WM_RUN_FUNCTION => {
+                let as_usize = message.wParam.0;
+                let winclosure = unsafe { Box::from_raw(as_usize as *mut WinClosure) };
+                winclosure.0();
+                //posted messages are retrieved ahead of input, so a burst of closures would
+                //starve it; once the budget is spent, let input and paint through first
+                if slice.record_op() {
+                    if !dispatch_input_and_paint() {
+                        break;
+                    }
+                    slice = DispatchSlice::new();
+                }
+            }
+            _ => {
+                slice = DispatchSlice::new();
+                unsafe {
+                    //ms code seems to ignore this return value in practice
+                    //see https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getmessage
+                    _ = TranslateMessage(&message);
+                    DispatchMessageW(&message);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Dispatches pending input and paint messages, skipping posted ones.
+///
+/// Returns `false` if the application is quitting.
+fn dispatch_input_and_paint() -> bool {
+    let mut message = MSG::default();
+    while unsafe {
+        PeekMessageW(
+            &mut message,
+            None,
+            0,
+            0,
+            PM_REMOVE | PM_QS_INPUT | PM_QS_PAINT,
+        )
+    }
+    .as_bool()
+    {
+        if message.message == WM_QUIT {
+            return false;
+        }
+        unsafe {
+            _ = TranslateMessage(&message);
+            DispatchMessageW(&message);
+        }
+    }
+    true
+}
+
+pub fn on_main_thread<F: FnOnce() + Send + 'static>(closure: F) {
+    let boxed_closure = Box::new(WinClosure(Box::new(closure)));
+    let closure_ptr = Box::into_raw(boxed_closure) as *mut ();
+    let as_usize = closure_ptr as usize;
+    unsafe {
+        PostThreadMessageW(
+            main_thread_id(),
+            WM_RUN_FUNCTION,
+            WPARAM(as_usize),
+            LPARAM(0),
+        )
+    }
+    .expect("PostThreadMessageW failed");
+}
+
+pub fn stop_main_thread() {
+    unsafe { PostQuitMessage(0) };
+}
+
+pub async fn alert(message: String) {
+    todo!("alert not yet implemented for Windows: {}", message)
+}
+
+#[derive(Debug)]
+pub struct Window {
+    hwnd: SendCell<HWND>,
+    events: WindowEventSender,
+}
+
+unsafe impl Send for Window {}
+unsafe impl Sync for Window {}
+
+extern "system" fn window_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    logwise::debuginternal_sync!(
+        "got msg hwnd {hwnd} msg {msg} w_param {w_param} l_param {l_param}",
+        hwnd = logwise::privacy::LogIt(&hwnd),
+        msg = msg,
+        w_param = logwise::privacy::LogIt(&w_param),
+        l_param = logwise::privacy::LogIt(&l_param)
+    );
+    #[cfg(feature = "input")]
+    if crate::input::window_proc(hwnd, msg, w_param, l_param) == LRESULT(0) {
+        return LRESULT(0);
+    }
+
+    match msg {
+        m if m == WM_SIZE => {
+            let width = (l_param.0 as u32 & 0xFFFF) as i32; // LOWORD(lParam)
+            let height = ((l_param.0 as u32 >> 16) & 0xFFFF) as i32; // HIWORD(lParam)
+            let size = dpi::logical(width, height, dpi::scale(hwnd));
+            let minimized = w_param.0 as u32 == SIZE_MINIMIZED;
+            let was_minimized = HWND_IMPS.with_borrow_mut(|c| {
+                let entry = c.entry(hwnd.0).or_default();
+                //minimizing reports a 0x0 client area, which isn't worth passing on
+                if !minimized && let Some(f) = entry.size_notify.as_ref() {
+                    f(size)
+                }
+                std::mem::replace(&mut entry.minimized, minimized)
+            });
+            match (was_minimized, minimized) {
+                (false, true) => send_event(hwnd, WindowEvent::Minimized),
+                (true, false) => send_event(hwnd, WindowEvent::Restored),
+                _ => {}
+            }
+            if !minimized {
+                send_event(hwnd, WindowEvent::Resized(size));
+            }
+            update_state(hwnd, unsafe { GetForegroundWindow() } == hwnd);
+            _ = confine::apply(hwnd, unsafe { GetForegroundWindow() } == hwnd);
+            LRESULT(0)
+        }
+        m if m == WM_MOVE => {
+            //lParam is the client area's origin; report the frame's, like set_position takes
+            let mut rect = RECT::default();
+            if unsafe { GetWindowRect(hwnd, &mut rect) }.is_ok() {
+                let position = dpi::logical_position(rect.left, rect.top);
+                send_event(hwnd, WindowEvent::Moved(position));
+            }
+            _ = confine::apply(hwnd, unsafe { GetForegroundWindow() } == hwnd);
+            unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }
+        }
+        m if m == WM_ACTIVATE => {
+            let active = (w_param.0 & 0xFFFF) as u32 != WA_INACTIVE;
+            let event = if active {
+                WindowEvent::Focused
+            } else {
+                WindowEvent::Unfocused
+            };
+            send_event(hwnd, event);
+            update_state(hwnd, active);
+            _ = confine::apply(hwnd, active);
+            unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }
+        }
+        m if m == WM_DPICHANGED => {
+            //LOWORD is the new horizontal DPI; it always matches the vertical
+            let scale = (w_param.0 & 0xFFFF) as f64 / 96.0;
+            dpi::changed(hwnd, l_param);
+            send_event(hwnd, WindowEvent::ScaleChanged(scale));
+            LRESULT(0)
+        }
+        m if m == WM_CLOSE => {
+            send_event(hwnd, WindowEvent::CloseRequested);
+            let close_behavior = HWND_IMPS.with_borrow(|c| {
+                c.get(&hwnd.0)
+                    .map(|imp| imp.close_behavior.clone())
+                    .unwrap_or_default()
+            });
+            match close_behavior {
+                CloseBehavior::Destroy => unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) },
+                CloseBehavior::HideToTray => {
+                    unsafe { _ = ShowWindow(hwnd, SW_HIDE) };
+                    LRESULT(0)
+                }
+                CloseBehavior::Custom(f) => {
+                    f();
+                    LRESULT(0)
+                }
+                CloseBehavior::Ask(f) => match f() {
+                    CloseResponse::Close => unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) },
+                    CloseResponse::Keep => LRESULT(0),
+                },
+            }
+        }
+        m if m == WM_SHOWWINDOW || m == WM_DESTROY => {
+            //WM_SHOWWINDOW's wParam is whether it's being shown
+            if m == WM_DESTROY || w_param.0 == 0 {
+                sheet::end(hwnd);
+            }
+            if m == WM_DESTROY {
+                icon::forget(hwnd);
+                #[cfg(feature = "accessibility")]
+                ax::forget(hwnd);
+            }
+            unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }
+        }
+        m if m == WM_WTSSESSION_CHANGE => {
+            //the user came back to this session, at the console or over Remote Desktop
+            if matches!(w_param.0 as u32, WTS_CONSOLE_CONNECT | WTS_REMOTE_CONNECT) {
+                send_event(hwnd, WindowEvent::Reconnected);
+            }
+            LRESULT(0)
+        }
+        #[cfg(feature = "accessibility")]
+        m if m == WM_GETOBJECT => ax::get_object(hwnd, w_param, l_param)
+            .unwrap_or_else(|| unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }),
+        #[cfg(feature = "accessibility")]
+        m if m == WM_SETFOCUS || m == WM_KILLFOCUS => {
+            ax::focus_changed(hwnd, m == WM_SETFOCUS);
+            unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }
+        }
+        m if m == WM_ERASEBKGND && options::erase_background(hwnd, w_param) => LRESULT(1),
+        m if m == WM_SETCURSOR && cursor::on_set_cursor(hwnd, l_param) => LRESULT(1),
+        m if m == WM_GETMINMAXINFO && options::min_max_info(hwnd, l_param) => LRESULT(0),
+        m if m == WM_PAINT && splash::paint(hwnd) => LRESULT(0),
+        m if m == WM_DWMSENDICONICTHUMBNAIL => preview::send_thumbnail(hwnd, l_param),
+        m if m == WM_DWMSENDICONICLIVEPREVIEWBITMAP => preview::send_live_preview(hwnd),
+        _ => unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) },
+    }
+}
+fn create_window_impl(
+    position: Position,
+    size: Size,
+    title: String,
+    style: WINDOW_STYLE,
+    show: bool,
+    events: WindowEventSender,
+) -> HWND {
+    let instance = unsafe { GetModuleHandleW(PCWSTR::null()) }.expect("Can't get module");
+    let cursor =
+        unsafe { LoadCursorW(Some(HINSTANCE::default()), IDC_ARROW) }.expect("Can't load cursor");
+    let winstr: HSTRING = title.into();
+    let class_name = w!("raw_input_debug_window");
+    let window_class = WNDCLASSEXW {
+        cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+        style: Default::default(),
+        lpfnWndProc: Some(window_proc),
+        cbClsExtra: 0,
+        cbWndExtra: 0,
+        hInstance: instance.into(),
+        hIcon: Default::default(),
+        hCursor: cursor,
+        hbrBackground: HBRUSH::default(),
+        lpszMenuName: PCWSTR::null(),
+        lpszClassName: class_name,
+        hIconSm: Default::default(),
+    };
+    let r = unsafe { RegisterClassExW(&window_class) };
+    assert_ne!(r, 0, "failed to register window class: {:?}", unsafe {
+        GetLastError()
+    });
+
+    let ex_style = if HIDE_FROM_TASKBAR.get() {
+        WS_EX_TOOLWINDOW
+    } else {
+        WINDOW_EX_STYLE(0)
+    };
+    //the window's monitor isn't known until it exists, so go by the one it's placed on
+    let (x, y) = dpi::physical_position(position);
+    let scale = dpi::scale_at(x, y);
+    let (width, height) = dpi::physical(size, scale);
+    let window = unsafe {
+        CreateWindowExW(
+            ex_style, //style
+            class_name, &winstr, style, x, y, //position
+            width, height, //size
+            None,   //parent
+            None,   //menu
+            None,   //instance
+            None,
+        )
+    }
+    .expect("failed to create window");
+    HWND_IMPS.with_borrow_mut(|c| c.entry(window.0).or_default().events = events);
+    //for WindowEvent::Reconnected; without it we just don't send that
+    _ = unsafe { WTSRegisterSessionNotification(window, NOTIFY_FOR_THIS_SESSION) };
+    hook_cloaking();
+    if show {
+        unsafe { _ = ShowWindow(window, SW_SHOWNORMAL) };
+    }
+    window
+}
+
+impl Window {
+    pub async fn new(position: Position, size: Size, title: String) -> Self {
+        let events = WindowEventSender::default();
+        let window_events = events.clone();
+        let window = crate::application::on_main_thread("Window::new".into(), move || {
+            let window = create_window_impl(
+                position,
+                size,
+                title,
+                WS_OVERLAPPEDWINDOW,
+                true,
+                window_events,
+            );
+            SendCell::new(window)
+        })
+        .await;
+
+        Window {
+            hwnd: window,
+            events,
+        }
+    }
+
+    pub async fn new_with_options(
+        position: Position,
+        size: Size,
+        title: String,
+        options: CreateOptions,
+    ) -> Self {
+        let events = WindowEventSender::default();
+        let window_events = events.clone();
+        let style = if options.decorated {
+            WS_OVERLAPPEDWINDOW
+        } else {
+            WS_POPUP
+        };
+        let window =
+            crate::application::on_main_thread("Window::new_with_options".into(), move || {
+                let window = create_window_impl(
+                    position,
+                    size,
+                    title,
+                    style,
+                    options.visible,
+                    window_events,
+                );
+                SendCell::new(window)
+            })
+            .await;
+
+        Window {
+            hwnd: window,
+            events,
+        }
+    }
+
+    pub async fn new_splash(size: Size, image: PreviewImage) -> Option<Self> {
+        Some(splash::new_splash(size, image).await)
+    }
+
+    pub async fn fade_out(self) {
+        splash::fade_out(&self).await
+    }
+
+    pub async fn new_overlay(size: Size, image: PreviewImage) -> Option<Self> {
+        Some(splash::new_overlay(size, image).await)
+    }
+
+    pub async fn set_splash_image(&self, image: PreviewImage) {
+        splash::set_image(self, image).await
+    }
+
+    pub async fn new_parked(position: Position, size: Size, title: String) -> Option<Self> {
+        let events = WindowEventSender::default();
+        let window_events = events.clone();
+        let window = crate::application::on_main_thread("Window::new_parked".into(), move || {
+            let window = create_window_impl(
+                position,
+                size,
+                title,
+                WS_OVERLAPPEDWINDOW,
+                false,
+                window_events,
+            );
+            SendCell::new(window)
+        })
+        .await;
+        Some(Window {
+            hwnd: window,
+            events,
+        })
+    }
+
+    pub async fn unpark(&self, title: String) {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::unpark".into(), move || {
+            let hwnd = *send_hwnd.get();
+            let title: HSTRING = title.into();
+            let command = if options::take_maximize_on_show(hwnd) {
+                SW_SHOWMAXIMIZED
+            } else {
+                SW_SHOWNORMAL
+            };
+            unsafe {
+                _ = SetWindowTextW(hwnd, &title);
+                _ = ShowWindow(hwnd, command);
+            }
+        })
+        .await
+    }
+
+    pub async fn default() -> Self {
+        Self::new(
+            Position::new(0.0, 0.0),
+            Size::new(800.0, 600.0),
+            "app_window".to_string(),
+        )
+        .await
+    }
+
+    pub async fn fullscreen(title: String, _output: Option<u32>) -> Result<Self, FullscreenError> {
+        let size = dpi::logical(
+            unsafe { GetSystemMetrics(SM_CXSCREEN) },
+            unsafe { GetSystemMetrics(SM_CYSCREEN) },
+            dpi::system_scale(),
+        );
+        let events = WindowEventSender::default();
+        let window_events = events.clone();
+        let window = crate::application::on_main_thread("Window::fullscreen".into(), move || {
+            let window = create_window_impl(
+                Position::new(0.0, 0.0),
+                size,
+                title,
+                WS_POPUP,
+                true,
+                window_events,
+            );
+            SendCell::new(window)
+        })
+        .await;
+
+        Ok(Window {
+            hwnd: window,
+            events,
+        })
+    }
+
+    pub async fn surface(&self) -> Surface {
+        let copy_hwnd = self.hwnd.copying();
+        Surface { imp: copy_hwnd }
+    }
+
+    pub async fn set_visible(&self, visible: bool) {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_visible".into(), move || {
+            let hwnd = *send_hwnd.get();
+            let command = if !visible {
+                SW_HIDE
+            } else if options::take_maximize_on_show(hwnd) {
+                SW_SHOWMAXIMIZED
+            } else {
+                SW_SHOW
+            };
+            unsafe { _ = ShowWindow(hwnd, command) };
+        })
+        .await
+    }
+
+    pub async fn set_canvas_behavior(&self, _behavior: CanvasBehavior) {
+        //only browsers have defaults to suppress
+    }
+
+    pub async fn set_close_behavior(&self, close_behavior: CloseBehavior) {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_close_behavior".into(), move || {
+            let hwnd = *send_hwnd.get();
+            HWND_IMPS.with_borrow_mut(|c| {
+                c.entry(hwnd.0).or_default().close_behavior = close_behavior;
+            });
+        })
+        .await
+    }
+
+    pub async fn show_window_menu(&self, position: Position) {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::show_window_menu".into(), move || {
+            let hwnd = *send_hwnd.get();
+            let scale = dpi::scale(hwnd);
+            let mut point = POINT {
+                x: (position.x() * scale) as i32,
+                y: (position.y() * scale) as i32,
+            };
+            unsafe {
+                _ = ClientToScreen(hwnd, &mut point);
+                let menu = GetSystemMenu(hwnd, false);
+                //TPM_RETURNCMD hands us the choice instead of posting WM_COMMAND
+                let command = TrackPopupMenu(
+                    menu,
+                    TPM_RETURNCMD | TPM_RIGHTBUTTON,
+                    point.x,
+                    point.y,
+                    None,
+                    hwnd,
+                    None,
+                );
+                if command.0 != 0 {
+                    _ = PostMessageW(
+                        Some(hwnd),
+                        WM_SYSCOMMAND,
+                        WPARAM(command.0 as usize),
+                        LPARAM(0),
+                    );
+                }
+            }
+        })
+        .await
+    }
+
+    pub async fn set_size(&self, size: Size) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_size".into(), move || {
+            //like CreateWindowExW, this is the outer size
+            let hwnd = *send_hwnd.get();
+            let (width, height) = dpi::physical(size, dpi::scale(hwnd));
+            unsafe {
+                SetWindowPos(
+                    hwnd,
+                    None,
+                    0,
+                    0,
+                    width,
+                    height,
+                    SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+                )
+            }
+            .map_err(|e| WindowSettingError::Platform(e.to_string()))
+        })
+        .await
+    }
+
+    pub async fn set_position(&self, position: Position) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_position".into(), move || {
+            let (x, y) = dpi::physical_position(position);
+            unsafe {
+                SetWindowPos(
+                    *send_hwnd.get(),
+                    None,
+                    x,
+                    y,
+                    0,
+                    0,
+                    SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+                )
+            }
+            .map_err(|e| WindowSettingError::Platform(e.to_string()))
+        })
+        .await
+    }
+
+    pub async fn outer_position(&self) -> Option<Position> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::outer_position".into(), move || {
+            //like WM_MOVE's events, the frame rather than the client area
+            let mut rect = RECT::default();
+            unsafe { GetWindowRect(*send_hwnd.get(), &mut rect) }
+                .ok()
+                .map(|()| dpi::logical_position(rect.left, rect.top))
+        })
+        .await
+    }
+
+    pub async fn normal_bounds(&self) -> Option<(Option<Position>, Size)> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::normal_bounds".into(), move || {
+            let hwnd = *send_hwnd.get();
+            let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+            let rect = normal_rect(hwnd, monitor)?;
+            //the outer size, like CreateWindowExW takes, at the scale create_window_impl uses
+            let scale = dpi::scale_at(rect.left, rect.top);
+            Some((
+                Some(dpi::logical_position(rect.left, rect.top)),
+                dpi::logical(rect.right - rect.left, rect.bottom - rect.top, scale),
+            ))
+        })
+        .await
+    }
+
+    pub fn request_frame(&self, frame: FrameCallback) {
+        frame::request(frame)
+    }
+
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread(
+            "Window::set_visible_on_all_workspaces".into(),
+            move || {
+                let hwnd = *send_hwnd.get();
+                //there's no public API for pinning to every virtual desktop; a topmost tool
+                //window is the closest we can get
+                HWND_IMPS.with_borrow_mut(|c| {
+                    c.entry(hwnd.0).or_default().all_workspaces = visible;
+                });
+                apply_window_settings(hwnd)
+            },
+        )
+        .await
+    }
+
+    pub async fn set_skip_taskbar(&self, skip: bool) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_skip_taskbar".into(), move || {
+            let hwnd = *send_hwnd.get();
+            HWND_IMPS.with_borrow_mut(|c| {
+                c.entry(hwnd.0).or_default().skip_taskbar = skip;
+            });
+            apply_window_settings(hwnd)
+        })
+        .await
+    }
+
+    pub async fn confine_pointer(&self, region: Option<Rect>) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::confine_pointer".into(), move || {
+            confine::set(*send_hwnd.get(), Some(region))
+        })
+        .await
+    }
+
+    pub async fn release_pointer(&self) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::release_pointer".into(), move || {
+            confine::set(*send_hwnd.get(), None)
+        })
+        .await
+    }
+
+    pub async fn inhibit_system_shortcuts(&self, inhibit: bool) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::inhibit_system_shortcuts".into(), move || {
+            let hwnd = *send_hwnd.get();
+            HWND_IMPS.with_borrow_mut(|c| {
+                c.entry(hwnd.0).or_default().inhibit_shortcuts = inhibit;
+            });
+            shortcuts::update_hook()
+        })
+        .await
+    }
+
+    pub async fn set_preview_image(
+        &self,
+        image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_preview_image".into(), move || {
+            preview::set(*send_hwnd.get(), image)
+        })
+        .await
+    }
+
+    pub async fn set_icon(&self, icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_icon".into(), move || {
+            icon::set(*send_hwnd.get(), icon)
+        })
+        .await
+    }
+
+    pub async fn set_badge(&self, _label: Option<String>) -> Result<(), WindowSettingError> {
+        //overlay icons could show one, but we'd have to draw the text
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_progress(&self, progress: Option<f64>) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_progress".into(), move || {
+            icon::set_progress(*send_hwnd.get(), progress)
+        })
+        .await
+    }
+
+    pub async fn set_cursor(&self, cursor: Cursor) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_cursor".into(), move || {
+            cursor::set(*send_hwnd.get(), cursor);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        //the touch keyboard is its own process, so this needn't be on the main thread
+        if visible {
+            touch_keyboard::show()
+        } else {
+            touch_keyboard::hide()
+        }
+    }
+
+    pub async fn set_decorations(&self, _mode: DecorationsMode) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_decoration_theme(
+        &self,
+        _theme: DecorationTheme,
+    ) -> Result<(), WindowSettingError> {
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn set_resize_border(&self, _width: f64) -> Result<(), WindowSettingError> {
+        //the system draws the frame
+        Err(WindowSettingError::Unsupported)
+    }
+
+    pub async fn begin_sheet(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        self.start_sheet(sheet).await.await;
+        Ok(())
+    }
+
+    pub async fn show_modal(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        //nobody waits for it to be dismissed
+        drop(self.start_sheet(sheet).await);
+        Ok(())
+    }
+
+    /// Puts `sheet` up, returning what finishes once it's dismissed.
+    async fn start_sheet(&self, sheet: &Window) -> r#continue::Future<()> {
+        let send_parent = self.hwnd.copying();
+        let send_sheet = sheet.hwnd.copying();
+        let (sender, fut) = r#continue::continuation();
+        crate::application::on_main_thread("Window::begin_sheet".into(), move || {
+            sheet::begin(*send_parent.get(), *send_sheet.get(), sender)
+        })
+        .await;
+        fut
+    }
+
+    pub async fn set_resizable(&self, resizable: bool) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_resizable".into(), move || {
+            options::set_resizable(*send_hwnd.get(), resizable)
+        })
+        .await
+    }
+
+    pub async fn set_size_limits(
+        &self,
+        min: Option<Size>,
+        max: Option<Size>,
+    ) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_size_limits".into(), move || {
+            let hwnd = *send_hwnd.get();
+            HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().size_limits = (min, max));
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_click_through(&self, click_through: bool) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_click_through".into(), move || {
+            options::set_click_through(*send_hwnd.get(), click_through)
+        })
+        .await
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn update_accessibility_tree(
+        &self,
+        update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::update_accessibility_tree".into(), move || {
+            ax::update(*send_hwnd.get(), update)
+        })
+        .await
+    }
+
+    #[cfg(feature = "accessibility")]
+    pub async fn set_accessibility_action_handler(
+        &self,
+        on_action: Box<dyn FnMut(accesskit::ActionRequest) + Send>,
+    ) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread(
+            "Window::set_accessibility_action_handler".into(),
+            move || ax::set_action_handler(*send_hwnd.get(), on_action),
+        )
+        .await;
+        Ok(())
+    }
+
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::focus".into(), move || {
+            let hwnd = *send_hwnd.get();
+            unsafe {
+                if IsIconic(hwnd).as_bool() {
+                    _ = ShowWindow(hwnd, SW_RESTORE);
+                }
+                //refused unless we're in the foreground already; then the taskbar button flashes
+                _ = SetForegroundWindow(hwnd);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn is_focused(&self) -> bool {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::is_focused".into(), move || {
+            *send_hwnd.get() == unsafe { GetForegroundWindow() }
+        })
+        .await
+    }
+
+    pub async fn set_always_on_top(&self, on_top: bool) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_always_on_top".into(), move || {
+            let hwnd = *send_hwnd.get();
+            HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().always_on_top = on_top);
+            apply_window_settings(hwnd)
+        })
+        .await
+    }
+
+    pub async fn set_maximized(&self, maximized: bool) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_maximized".into(), move || {
+            options::set_maximized(*send_hwnd.get(), maximized)
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_background(&self, color: Color) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_background".into(), move || {
+            options::set_background(*send_hwnd.get(), color)
+        })
+        .await;
+        Ok(())
+    }
+
+    pub async fn set_transparent(&self, transparent: bool) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::set_transparent".into(), move || {
+            options::set_transparent(*send_hwnd.get(), transparent)
+        })
+        .await
+    }
+
+    pub async fn set_parent(&self, parent: &Window) -> Result<(), WindowSettingError> {
+        let send_hwnd = self.hwnd.copying();
+        let send_parent = parent.hwnd.copying();
+        crate::application::on_main_thread("Window::set_parent".into(), move || {
+            sheet::set_parent(*send_hwnd.get(), *send_parent.get())
+        })
+        .await;
+        Ok(())
+    }
+
+    /// The HWND, which input reports.
+    pub async fn input_handle(&self) -> Option<usize> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::input_handle".into(), move || {
+            Some(send_hwnd.get().0 as usize)
+        })
+        .await
+    }
+
+    pub async fn native_handle(&self) -> Option<NativeHandle> {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::native_handle".into(), move || {
+            NonZero::new(send_hwnd.get().0 as isize).map(|hwnd| NativeHandle::Win32 { hwnd })
+        })
+        .await
+    }
+
+    pub fn event_sender(&self) -> WindowEventSender {
+        self.events.clone()
+    }
+
+    pub async fn scale_factor(&self) -> f64 {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::scale_factor".into(), move || {
+            Surface::size_imp(*send_hwnd.get()).1
+        })
+        .await
+    }
+
+    /// Fullscreen is a borderless window covering its monitor, as [`Window::fullscreen`] makes.
+    fn state_main(hwnd: HWND, style: WINDOW_STYLE, monitor: HMONITOR) -> WindowState {
+        if unsafe { IsIconic(hwnd) }.as_bool() {
+            return WindowState::Minimized;
+        }
+        if unsafe { IsZoomed(hwnd) }.as_bool() {
+            return WindowState::Maximized;
+        }
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        let mut rect = RECT::default();
+        if !style.contains(WS_CAPTION)
+            && unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool()
+            && unsafe { GetWindowRect(hwnd, &mut rect) }.is_ok()
+            && rect == info.rcMonitor
+        {
+            WindowState::Fullscreen
+        } else {
+            WindowState::Normal
+        }
+    }
+
+    pub async fn debug_state(&self) -> WindowDebugState {
+        let send_hwnd = self.hwnd.copying();
+        crate::application::on_main_thread("Window::debug_state".into(), move || {
+            let hwnd = *send_hwnd.get();
+            let (size, scale) = Surface::size_imp(hwnd);
+            let style = WINDOW_STYLE(unsafe { GetWindowLongW(hwnd, GWL_STYLE) } as u32);
+            let decorations = if style.contains(WS_CAPTION) {
+                DecorationsMode::Native
+            } else {
+                DecorationsMode::None
+            };
+            let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+            WindowDebugState {
+                backend: "win32",
+                applied_size: Some(size),
+                proposed_size: None,
+                scale: Some(scale),
+                preferred_scale: None,
+                //handles are pointers, but monitors are few and this is only for comparison
+                outputs: vec![monitor.0 as usize as u32],
+                focused: Some(unsafe { GetForegroundWindow() } == hwnd),
+                visible: Some(unsafe { IsWindowVisible(hwnd) }.as_bool()),
+                configured: None,
+                decorations: Some(decorations),
+                buffer: None,
+                cursor: Some(cursor::get(hwnd)),
+                state: Some(Self::state_main(hwnd, style, monitor)),
+            }
+        })
+        .await
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        let unsafe_hwnd = unsafe { *self.hwnd.get_unchecked() };
+        let unsafe_port_hwnd = send_cells::unsafe_send_cell::UnsafeSendCell::new(unsafe_hwnd);
+        logwise::debuginternal_sync!("Destroying window");
+        on_main_thread(move || {
+            _ = unsafe { WTSUnRegisterSessionNotification(*unsafe_port_hwnd.get()) };
+            unsafe { DestroyWindow(*unsafe_port_hwnd.get()) }.expect("Can't close window");
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct Surface {
+    imp: SendCell<HWND>,
+}
+
+unsafe impl Send for Surface {}
+unsafe impl Sync for Surface {}
+
+impl Surface {
+    fn size_imp(hwnd: HWND) -> (Size, f64) {
+        let mut rect = RECT::default();
+        //the window may be gone; a destroyed window has no size
+        if unsafe { GetClientRect(hwnd, &mut rect) }.is_err() {
+            return (Size::new(0.0, 0.0), 1.0);
+        }
+        let scale = dpi::scale(hwnd);
+        (dpi::logical(rect.right, rect.bottom, scale), scale)
+    }
+    pub async fn size_scale(&self) -> (Size, f64) {
+        let send_hwnd = self.imp.copying();
+        crate::application::on_main_thread("Surface::size_scale".into(), move || {
+            Self::size_imp(*send_hwnd.get())
+        })
+        .await
+    }
+    pub fn size_main(&self) -> (Size, f64) {
+        assert!(
+            crate::application::is_main_thread(),
+            "Call from main thread only"
+        );
+        Self::size_imp(*self.imp.get())
+    }
+
+    pub fn raw_window_handle(&self) -> RawWindowHandle {
+        //should be fine since we're just reading the value
+        let unsafe_hwnd: HWND = unsafe { *self.imp.get_unchecked() };
+        RawWindowHandle::Win32(Win32WindowHandle::new(
+            NonZero::new(unsafe_hwnd.0 as isize).expect("HWND is null"),
+        ))
+    }
+
+    pub fn raw_display_handle(&self) -> RawDisplayHandle {
+        RawDisplayHandle::Windows(WindowsDisplayHandle::new())
+    }
+
+    pub fn size_update<F: Fn(Size) + Send + 'static>(&mut self, _update: F) {
+        let move_hwnd = self.imp.copying();
+        on_main_thread(move || {
+            let hwnd = move_hwnd.get();
+            HWND_IMPS.with_borrow_mut(|c| {
+                let entry = c.entry(hwnd.0).or_default();
+                entry.size_notify = Some(Box::new(_update));
+            });
+        });
+    }
+
+    pub fn frame_requester(&self) -> FrameRequester {
+        Arc::new(frame::request)
+    }
+
+    pub fn damage(&self, rects: &[Rect]) {
+        let move_hwnd = self.imp.copying();
+        let rects = rects.to_vec();
+        on_main_thread(move || {
+            let hwnd = *move_hwnd.get();
+            unsafe {
+                let region = CreateRectRgn(0, 0, 0, 0);
+                for rect in rects {
+                    let part = CreateRectRgn(
+                        rect.origin().x().floor() as i32,
+                        rect.origin().y().floor() as i32,
+                        (rect.origin().x() + rect.size().width()).ceil() as i32,
+                        (rect.origin().y() + rect.size().height()).ceil() as i32,
+                    );
+                    CombineRgn(Some(region), Some(region), Some(part), RGN_OR);
+                    _ = DeleteObject(part.into());
+                }
+                _ = RedrawWindow(Some(hwnd), None, Some(region), RDW_INVALIDATE);
+                _ = DeleteObject(region.into());
+            }
+        });
+    }
+}
+
+impl Drop for Surface {
+    fn drop(&mut self) {
+        let move_hwnd = self.imp.copying();
+        //the window keeps its entry; only our callback goes
+        on_main_thread(move || {
+            let hwnd = move_hwnd.get();
+            HWND_IMPS.with_borrow_mut(|c| {
+                if let Some(entry) = c.get_mut(&hwnd.0) {
+                    entry.size_notify = None;
+                }
+            });
+        });
+    }
+}
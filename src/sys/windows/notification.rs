@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Notifications, as WinRT toasts.
+//!
+//! Toasts are shown on behalf of an AppUserModelID.  Packaged apps have one; others use the
+//! one they set with `SetCurrentProcessExplicitAppUserModelID`, or else the executable's name,
+//! which Windows shows as it is.
+
+use crate::notification::{Notification, NotificationError};
+use crate::window::IconData;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::UI::Notifications::{
+    NotificationSetting, ToastNotification, ToastNotificationManager, ToastNotifier,
+};
+use windows::Win32::Foundation::GENERIC_WRITE;
+use windows::Win32::Graphics::Imaging::{
+    CLSID_WICImagingFactory, GUID_ContainerFormatPng, GUID_WICPixelFormat32bppBGRA,
+    IWICBitmapFrameEncode, IWICImagingFactory, WICBitmapEncoderNoCache,
+};
+use windows::Win32::System::Com::StructuredStorage::IPropertyBag2;
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx, CoTaskMemFree,
+};
+use windows::Win32::UI::Shell::GetCurrentProcessExplicitAppUserModelID;
+use windows::core::HSTRING;
+
+fn platform(e: windows::core::Error) -> NotificationError {
+    NotificationError::Platform(e.to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn app_user_model_id() -> String {
+    if let Ok(id) = unsafe { GetCurrentProcessExplicitAppUserModelID() } {
+        let string = unsafe { id.to_string() };
+        unsafe { CoTaskMemFree(Some(id.0 as *const _)) };
+        if let Ok(id) = string {
+            return id;
+        }
+    }
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "app_window".to_string())
+}
+
+fn notifier() -> windows::core::Result<ToastNotifier> {
+    //packaged apps need no ID; the rest get "Element not found" without one
+    ToastNotificationManager::CreateToastNotifier().or_else(|_| {
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_user_model_id()))
+    })
+}
+
+/// Writes `icon` to a PNG in the temporary directory, as toasts only show images from files.
+/// The file stays, as Action Center shows the toast again later.
+fn write_icon(icon: &IconData) -> windows::core::Result<PathBuf> {
+    static NEXT_FILE: AtomicU32 = AtomicU32::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "app_window-{}-notification-{}.png",
+        std::process::id(),
+        NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+    ));
+    let bgra: Vec<u8> = icon
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+        .collect();
+    unsafe {
+        let factory: IWICImagingFactory =
+            CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)?;
+        let stream = factory.CreateStream()?;
+        stream.InitializeFromFilename(&HSTRING::from(path.as_os_str()), GENERIC_WRITE.0)?;
+        let encoder = factory.CreateEncoder(&GUID_ContainerFormatPng, std::ptr::null())?;
+        encoder.Initialize(&stream, WICBitmapEncoderNoCache)?;
+        let mut frame: Option<IWICBitmapFrameEncode> = None;
+        encoder.CreateNewFrame(&mut frame, std::ptr::null_mut())?;
+        let frame = frame.ok_or_else(windows::core::Error::empty)?;
+        frame.Initialize(None::<&IPropertyBag2>)?;
+        frame.SetSize(icon.width(), icon.height())?;
+        //the PNG encoder takes BGRA without converting it
+        let mut format = GUID_WICPixelFormat32bppBGRA;
+        frame.SetPixelFormat(&mut format)?;
+        frame.WritePixels(icon.height(), icon.width() * 4, &bgra)?;
+        frame.Commit()?;
+        encoder.Commit()?;
+    }
+    Ok(path)
+}
+
+/// Call on the main thread.
+fn show(notification: Notification) -> Result<(), NotificationError> {
+    //S_FALSE (already initialized) and RPC_E_CHANGED_MODE both leave COM usable
+    _ = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) };
+    //a notification without its icon beats none at all
+    let image = notification
+        .icon
+        .as_ref()
+        .and_then(|icon| write_icon(icon).ok())
+        .map(|path| {
+            format!(
+                r#"<image placement="appLogoOverride" src="{}"/>"#,
+                escape(&path.to_string_lossy())
+            )
+        })
+        .unwrap_or_default();
+    let xml = format!(
+        r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text>{image}</binding></visual></toast>"#,
+        escape(&notification.title),
+        escape(&notification.body),
+    );
+    let document = XmlDocument::new().map_err(platform)?;
+    document.LoadXml(&HSTRING::from(xml)).map_err(platform)?;
+    let toast = ToastNotification::CreateToastNotification(&document).map_err(platform)?;
+    let notifier = notifier().map_err(platform)?;
+    //turned off in Settings, by group policy, or for the whole machine
+    if notifier
+        .Setting()
+        .is_ok_and(|setting| setting != NotificationSetting::Enabled)
+    {
+        return Err(NotificationError::Denied);
+    }
+    notifier.Show(&toast).map_err(platform)
+}
+
+pub async fn post_notification(notification: Notification) -> Result<(), NotificationError> {
+    crate::application::on_main_thread("post_notification".to_string(), move || show(notification))
+        .await
+}
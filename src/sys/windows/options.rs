@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Options `WindowBuilder` sets once a window exists: resizing, size limits, maximizing,
+//! transparency, click-through and the background color.
+
+use super::HWND_IMPS;
+use crate::dialog::Color;
+use crate::window::WindowSettingError;
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, RECT, WPARAM};
+use windows::Win32::Graphics::Dwm::DwmExtendFrameIntoClientArea;
+use windows::Win32::Graphics::Gdi::{
+    CreateSolidBrush, DeleteObject, FillRect, HDC, InvalidateRect,
+};
+use windows::Win32::UI::Controls::MARGINS;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GWL_EXSTYLE, GWL_STYLE, GetClientRect, GetWindowLongW, IsWindowVisible, IsZoomed, LWA_ALPHA,
+    MINMAXINFO, SW_MAXIMIZE, SW_RESTORE, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+    SWP_NOZORDER, SetLayeredWindowAttributes, SetWindowLongW, SetWindowPos, ShowWindow,
+    WINDOW_EX_STYLE, WINDOW_STYLE, WS_EX_LAYERED, WS_EX_TRANSPARENT, WS_MAXIMIZEBOX, WS_THICKFRAME,
+};
+
+/// Adds or removes the sizing border and maximize button.  Call on the main thread.
+pub(super) fn set_resizable(hwnd: HWND, resizable: bool) -> Result<(), WindowSettingError> {
+    let old_style = WINDOW_STYLE(unsafe { GetWindowLongW(hwnd, GWL_STYLE) } as u32);
+    let new_style = if resizable {
+        old_style | WS_THICKFRAME | WS_MAXIMIZEBOX
+    } else {
+        old_style & !(WS_THICKFRAME | WS_MAXIMIZEBOX)
+    };
+    unsafe { SetWindowLongW(hwnd, GWL_STYLE, new_style.0 as i32) };
+    //the frame is cached until told otherwise
+    unsafe {
+        SetWindowPos(
+            hwnd,
+            None,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+        )
+    }
+    .map_err(|e| WindowSettingError::Platform(e.to_string()))
+}
+
+/// Answers `WM_GETMINMAXINFO` with the window's size limits.  Returns whether it has any.
+pub(super) fn min_max_info(hwnd: HWND, l_param: LPARAM) -> bool {
+    let Some((min, max)) = HWND_IMPS.with_borrow(|c| c.get(&hwnd.0).map(|imp| imp.size_limits))
+    else {
+        return false;
+    };
+    if min.is_none() && max.is_none() {
+        return false;
+    }
+    //outer sizes, like set_size
+    let info = unsafe { &mut *(l_param.0 as *mut MINMAXINFO) };
+    let scale = super::dpi::scale(hwnd);
+    if let Some(min) = min {
+        (info.ptMinTrackSize.x, info.ptMinTrackSize.y) = super::dpi::physical(min, scale);
+    }
+    if let Some(max) = max {
+        (info.ptMaxTrackSize.x, info.ptMaxTrackSize.y) = super::dpi::physical(max, scale);
+        info.ptMaxSize = info.ptMaxTrackSize;
+    }
+    true
+}
+
+/// Maximizes or restores the window, or for a hidden one, how it's next shown.  Call on the
+/// main thread.
+pub(super) fn set_maximized(hwnd: HWND, maximized: bool) {
+    //ShowWindow would show it
+    if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+        HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().maximize_on_show = maximized);
+        return;
+    }
+    if unsafe { IsZoomed(hwnd) }.as_bool() != maximized {
+        let command = if maximized { SW_MAXIMIZE } else { SW_RESTORE };
+        unsafe { _ = ShowWindow(hwnd, command) };
+    }
+}
+
+/// Whether the window should be shown maximized, forgetting the request.  Call on the main
+/// thread.
+pub(super) fn take_maximize_on_show(hwnd: HWND) -> bool {
+    HWND_IMPS.with_borrow_mut(|c| {
+        c.get_mut(&hwnd.0)
+            .is_some_and(|imp| std::mem::take(&mut imp.maximize_on_show))
+    })
+}
+
+/// Lets the window's alpha show what's behind it, by extending the DWM frame across all of it.
+/// Call on the main thread.
+pub(super) fn set_transparent(hwnd: HWND, transparent: bool) -> Result<(), WindowSettingError> {
+    let inset = if transparent { -1 } else { 0 };
+    let margins = MARGINS {
+        cxLeftWidth: inset,
+        cxRightWidth: inset,
+        cyTopHeight: inset,
+        cyBottomHeight: inset,
+    };
+    unsafe { DwmExtendFrameIntoClientArea(hwnd, &margins) }
+        .map_err(|e| WindowSettingError::Platform(e.to_string()))
+}
+
+/// Lets mouse input fall through to the windows below.  Call on the main thread.
+///
+/// `WS_EX_TRANSPARENT` only passes hit testing through on a layered window.
+pub(super) fn set_click_through(hwnd: HWND, click_through: bool) -> Result<(), WindowSettingError> {
+    let old_style = WINDOW_EX_STYLE(unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32);
+    let new_style = if click_through {
+        old_style | WS_EX_LAYERED | WS_EX_TRANSPARENT
+    } else {
+        old_style & !(WS_EX_LAYERED | WS_EX_TRANSPARENT)
+    };
+    unsafe { SetWindowLongW(hwnd, GWL_EXSTYLE, new_style.0 as i32) };
+    if !click_through {
+        return Ok(());
+    }
+    //a layered window isn't drawn until it has an opacity
+    unsafe { SetLayeredWindowAttributes(hwnd, COLORREF(0), 255, LWA_ALPHA) }
+        .map_err(|e| WindowSettingError::Platform(e.to_string()))
+}
+
+/// Paints the client area `color` until the app draws over it.  Call on the main thread.
+pub(super) fn set_background(hwnd: HWND, color: Color) {
+    let [red, green, blue] = color.to_rgb8();
+    //0x00bbggrr
+    let color = COLORREF(red as u32 | (green as u32) << 8 | (blue as u32) << 16);
+    HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().background = Some(color));
+    _ = unsafe { InvalidateRect(Some(hwnd), None, true) };
+}
+
+/// Handles `WM_ERASEBKGND`.  Returns whether we painted the background; otherwise nothing
+/// does, since the window class has no brush.
+pub(super) fn erase_background(hwnd: HWND, w_param: WPARAM) -> bool {
+    let Some(color) = HWND_IMPS.with_borrow(|c| c.get(&hwnd.0).and_then(|imp| imp.background))
+    else {
+        return false;
+    };
+    let mut rect = RECT::default();
+    if unsafe { GetClientRect(hwnd, &mut rect) }.is_err() {
+        return false;
+    }
+    let hdc = HDC(w_param.0 as *mut _);
+    unsafe {
+        let brush = CreateSolidBrush(color);
+        FillRect(hdc, &rect, brush);
+        _ = DeleteObject(brush.into());
+    }
+    true
+}
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Performance hints, via the process's priority class and power throttling state, and the
+//! per-app GPU preference that Settings > Display > Graphics writes.
+
+use crate::application::{PerformanceHintCapabilities, PerformanceHintError, PerformanceHints};
+use crate::audio::HintSupport;
+use std::sync::Mutex;
+use windows::Win32::System::Registry::{
+    HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ, RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW,
+};
+use windows::Win32::System::Threading::{
+    ABOVE_NORMAL_PRIORITY_CLASS, GetCurrentProcess, GetPriorityClass, HIGH_PRIORITY_CLASS,
+    NORMAL_PRIORITY_CLASS, PROCESS_CREATION_FLAGS, PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+    PROCESS_POWER_THROTTLING_EXECUTION_SPEED, PROCESS_POWER_THROTTLING_STATE,
+    ProcessPowerThrottling, REALTIME_PRIORITY_CLASS, SetPriorityClass, SetProcessInformation,
+};
+use windows::core::{HSTRING, w};
+
+/// What we changed, so we only undo that, and back to how it was.
+struct Applied {
+    hints: PerformanceHints,
+    /// The priority class before we raised it.
+    priority_class: u32,
+    /// Whether the GPU preference in the registry is one we wrote.
+    gpu_preference: bool,
+}
+
+static APPLIED: Mutex<Applied> = Mutex::new(Applied {
+    hints: PerformanceHints::new(),
+    priority_class: 0,
+    gpu_preference: false,
+});
+
+/// Values are named by executable path.
+const GPU_PREFERENCES: windows::core::PCWSTR =
+    w!("Software\\Microsoft\\DirectX\\UserGpuPreferences");
+
+pub fn performance_hint_capabilities() -> PerformanceHintCapabilities {
+    PerformanceHintCapabilities {
+        prefer_discrete_gpu: HintSupport::Supported,
+        high_process_priority: HintSupport::Supported,
+        disable_power_throttling: HintSupport::Supported,
+    }
+}
+
+fn set_power_throttling(disable: bool) -> windows::core::Result<()> {
+    let state = PROCESS_POWER_THROTTLING_STATE {
+        Version: PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+        //controlling the bit with it clear opts out; not controlling it lets Windows decide
+        ControlMask: if disable {
+            PROCESS_POWER_THROTTLING_EXECUTION_SPEED
+        } else {
+            0
+        },
+        StateMask: 0,
+    };
+    unsafe {
+        SetProcessInformation(
+            GetCurrentProcess(),
+            ProcessPowerThrottling,
+            &state as *const _ as *const _,
+            size_of::<PROCESS_POWER_THROTTLING_STATE>() as u32,
+        )
+    }
+}
+
+/// Asks for the high-performance GPU for this executable, unless the user already chose one.
+/// Returns whether we wrote a preference.
+///
+/// DXGI reads this when a factory is created, so it applies to renderers created afterwards.
+fn prefer_discrete_gpu(exe: &HSTRING) -> windows::core::Result<bool> {
+    let chosen = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            GPU_PREFERENCES,
+            exe,
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            None,
+        )
+    };
+    if chosen.is_ok() {
+        return Ok(false);
+    }
+    let value: Vec<u16> = "GpuPreference=2;".encode_utf16().chain([0]).collect();
+    unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            GPU_PREFERENCES,
+            exe,
+            REG_SZ.0,
+            Some(value.as_ptr().cast()),
+            (value.len() * size_of::<u16>()) as u32,
+        )
+    }
+    .ok()?;
+    Ok(true)
+}
+
+//all process-wide, so any thread will do, and threads spawned later get them too
+pub async fn set_performance_hints(hints: PerformanceHints) -> Result<(), PerformanceHintError> {
+    let platform_error = |e: windows::core::Error| PerformanceHintError::Platform(e.to_string());
+    let mut applied = APPLIED.lock().unwrap();
+    if hints.wants_prefer_discrete_gpu() != applied.hints.wants_prefer_discrete_gpu() {
+        let exe = std::env::current_exe().map_err(|e| {
+            PerformanceHintError::Platform(format!("can't find the executable: {e}"))
+        })?;
+        let exe = HSTRING::from(exe.as_os_str());
+        if hints.wants_prefer_discrete_gpu() {
+            applied.gpu_preference = prefer_discrete_gpu(&exe).map_err(platform_error)?;
+        } else if applied.gpu_preference {
+            unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, GPU_PREFERENCES, &exe) }
+                .ok()
+                .map_err(platform_error)?;
+            applied.gpu_preference = false;
+        }
+        applied.hints = applied
+            .hints
+            .prefer_discrete_gpu(hints.wants_prefer_discrete_gpu());
+    }
+    if hints.wants_high_process_priority() != applied.hints.wants_high_process_priority() {
+        let class = if hints.wants_high_process_priority() {
+            let current = unsafe { GetPriorityClass(GetCurrentProcess()) };
+            applied.priority_class = current;
+            //leave a higher class from whoever started us
+            let higher = [HIGH_PRIORITY_CLASS, REALTIME_PRIORITY_CLASS]
+                .iter()
+                .any(|class| class.0 == current);
+            (!higher).then_some(ABOVE_NORMAL_PRIORITY_CLASS)
+        } else if applied.priority_class == 0 {
+            //GetPriorityClass failed
+            Some(NORMAL_PRIORITY_CLASS)
+        } else {
+            Some(PROCESS_CREATION_FLAGS(applied.priority_class))
+        };
+        if let Some(class) = class {
+            unsafe { SetPriorityClass(GetCurrentProcess(), class) }.map_err(platform_error)?;
+        }
+        applied.hints = applied
+            .hints
+            .high_process_priority(hints.wants_high_process_priority());
+    }
+    if hints.wants_disable_power_throttling() != applied.hints.wants_disable_power_throttling() {
+        set_power_throttling(hints.wants_disable_power_throttling()).map_err(platform_error)?;
+        applied.hints = applied
+            .hints
+            .disable_power_throttling(hints.wants_disable_power_throttling());
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Power notifications, via a message-only window registered with
+//! `RegisterPowerSettingNotification`.
+//!
+//! Registering sends each setting's current value straight away, so we remember the last of
+//! each and only pass on changes, or a first value that isn't the usual one.
+
+use crate::power::{PowerError, PowerEvent};
+use std::cell::{Cell, RefCell};
+use std::thread::LocalKey;
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Power::{
+    HPOWERNOTIFY, POWERBROADCAST_SETTING, RegisterPowerSettingNotification,
+    UnregisterPowerSettingNotification,
+};
+use windows::Win32::System::SystemServices::{
+    GUID_CONSOLE_DISPLAY_STATE, GUID_POWER_SAVING_STATUS,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DEVICE_NOTIFY_WINDOW_HANDLE, DefWindowProcW, DestroyWindow, HWND_MESSAGE,
+    PBT_POWERSETTINGCHANGE, RegisterClassExW, WINDOW_EX_STYLE, WINDOW_STYLE, WM_POWERBROADCAST,
+    WNDCLASSEXW,
+};
+use windows::core::{PCWSTR, w};
+
+const CLASS_NAME: PCWSTR = w!("app_window_power_listener");
+
+struct Listener {
+    hwnd: HWND,
+    registrations: Vec<HPOWERNOTIFY>,
+}
+
+thread_local! {
+    static CLASS_REGISTERED: Cell<bool> = const { Cell::new(false) };
+    /// The listening window, while anyone is watching.
+    static LISTENER: RefCell<Option<Listener>> = const { RefCell::new(None) };
+    /// Whether the displays are on, once we've heard.
+    static DISPLAY_ON: Cell<Option<bool>> = const { Cell::new(None) };
+    /// Whether battery saver is on, once we've heard.
+    static SAVING: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Records `now`, and returns whether it's news: a change, or a first value other than
+/// `usual`.
+fn changed(last: &'static LocalKey<Cell<Option<bool>>>, now: bool, usual: bool) -> bool {
+    last.replace(Some(now)).unwrap_or(usual) != now
+}
+
+fn setting_changed(setting: &POWERBROADCAST_SETTING) {
+    if (setting.DataLength as usize) < size_of::<u32>() {
+        return;
+    }
+    let value = unsafe { std::ptr::read_unaligned(setting.Data.as_ptr() as *const u32) };
+    let event = if setting.PowerSetting == GUID_CONSOLE_DISPLAY_STATE {
+        //2 is dimmed, which can still be seen
+        let on = value != 0;
+        if !changed(&DISPLAY_ON, on, true) {
+            return;
+        }
+        if on {
+            PowerEvent::DisplayOn
+        } else {
+            PowerEvent::DisplayOff
+        }
+    } else if setting.PowerSetting == GUID_POWER_SAVING_STATUS {
+        let saving = value != 0;
+        if !changed(&SAVING, saving, false) {
+            return;
+        }
+        if saving {
+            PowerEvent::LowPowerEntered
+        } else {
+            PowerEvent::LowPowerExited
+        }
+    } else {
+        return;
+    };
+    crate::power::notify(event);
+}
+
+extern "system" fn listener_proc(
+    hwnd: HWND,
+    msg: u32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if msg == WM_POWERBROADCAST && w_param.0 as u32 == PBT_POWERSETTINGCHANGE {
+        setting_changed(unsafe { &*(l_param.0 as *const POWERBROADCAST_SETTING) });
+        return LRESULT(1);
+    }
+    unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }
+}
+
+fn create_listener() -> windows::core::Result<Listener> {
+    let instance = unsafe { GetModuleHandleW(PCWSTR::null()) }?;
+    if !CLASS_REGISTERED.replace(true) {
+        let class = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(listener_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        if unsafe { RegisterClassExW(&class) } == 0 {
+            CLASS_REGISTERED.set(false);
+            return Err(windows::core::Error::from_thread());
+        }
+    }
+    //message-only, so it never shows up anywhere; power settings are sent to it directly
+    let hwnd = unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            CLASS_NAME,
+            PCWSTR::null(),
+            WINDOW_STYLE(0),
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            Some(instance.into()),
+            None,
+        )
+    }?;
+    let mut registrations = Vec::new();
+    for setting in [GUID_CONSOLE_DISPLAY_STATE, GUID_POWER_SAVING_STATUS] {
+        match unsafe {
+            RegisterPowerSettingNotification(HANDLE(hwnd.0), &setting, DEVICE_NOTIFY_WINDOW_HANDLE)
+        } {
+            Ok(registration) => registrations.push(registration),
+            Err(e) => {
+                unsafe {
+                    for registration in registrations {
+                        _ = UnregisterPowerSettingNotification(registration);
+                    }
+                    _ = DestroyWindow(hwnd);
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(Listener {
+        hwnd,
+        registrations,
+    })
+}
+
+pub async fn watch_power() -> Result<(), PowerError> {
+    crate::application::on_main_thread("watch_power".to_string(), || {
+        if LISTENER.with_borrow(Option::is_some) {
+            return Ok(());
+        }
+        let listener = create_listener().map_err(|e| PowerError::Platform(e.to_string()))?;
+        LISTENER.replace(Some(listener));
+        Ok(())
+    })
+    .await
+}
+
+/// Call on the main thread.
+pub fn unwatch_power() {
+    if let Some(listener) = LISTENER.take() {
+        unsafe {
+            for registration in listener.registrations {
+                _ = UnregisterPowerSettingNotification(registration);
+            }
+            _ = DestroyWindow(listener.hwnd);
+        }
+        //the next watch hears the current state again
+        DISPLAY_ON.set(None);
+        SAVING.set(None);
+    }
+}
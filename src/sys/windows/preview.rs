@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Task switcher previews.  DWM captures windows itself, but GPU swapchains often come out
+//! blank, so windows with a preview image take over their iconic thumbnail and live preview.
+
+use super::{HWND_IMPS, Surface};
+use crate::window::{PreviewImage, WindowSettingError};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT};
+use windows::Win32::Graphics::Dwm::{
+    DWMWA_FORCE_ICONIC_REPRESENTATION, DWMWA_HAS_ICONIC_BITMAP, DwmInvalidateIconicBitmaps,
+    DwmSetIconicLivePreviewBitmap, DwmSetIconicThumbnail, DwmSetWindowAttribute,
+};
+use windows::Win32::Graphics::Gdi::{
+    BI_RGB, BITMAPINFO, BITMAPINFOHEADER, CreateDIBSection, DIB_RGB_COLORS, DeleteObject, HBITMAP,
+};
+use windows::core::BOOL;
+
+/// Replaces the window's preview.  Call on the main thread.
+pub(super) fn set(hwnd: HWND, image: Option<PreviewImage>) -> Result<(), WindowSettingError> {
+    let enabled = BOOL::from(image.is_some());
+    HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().preview = image);
+    for attribute in [DWMWA_FORCE_ICONIC_REPRESENTATION, DWMWA_HAS_ICONIC_BITMAP] {
+        unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                attribute,
+                &enabled as *const BOOL as *const _,
+                size_of::<BOOL>() as u32,
+            )
+        }
+        .map_err(|e| WindowSettingError::Platform(e.to_string()))?;
+    }
+    if enabled.as_bool() {
+        //DWM caches what we sent last; ask it to request fresh bitmaps
+        unsafe { DwmInvalidateIconicBitmaps(hwnd) }
+            .map_err(|e| WindowSettingError::Platform(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Handles `WM_DWMSENDICONICTHUMBNAIL`.
+pub(super) fn send_thumbnail(hwnd: HWND, l_param: LPARAM) -> LRESULT {
+    //the largest thumbnail DWM will show
+    let max_width = (l_param.0 as u32 >> 16) & 0xFFFF;
+    let max_height = l_param.0 as u32 & 0xFFFF;
+    send(hwnd, max_width, max_height, |bitmap| unsafe {
+        DwmSetIconicThumbnail(hwnd, bitmap, 0)
+    })
+}
+
+/// Handles `WM_DWMSENDICONICLIVEPREVIEWBITMAP`, the full-size preview shown when hovering a
+/// thumbnail.
+pub(super) fn send_live_preview(hwnd: HWND) -> LRESULT {
+    let (size, scale) = Surface::size_imp(hwnd);
+    let width = (size.width() * scale) as u32;
+    let height = (size.height() * scale) as u32;
+    send(hwnd, width, height, |bitmap| unsafe {
+        DwmSetIconicLivePreviewBitmap(hwnd, bitmap, None, 0)
+    })
+}
+
+fn send(
+    hwnd: HWND,
+    max_width: u32,
+    max_height: u32,
+    deliver: impl FnOnce(HBITMAP) -> windows::core::Result<()>,
+) -> LRESULT {
+    let image = HWND_IMPS.with_borrow(|c| {
+        c.get(&hwnd.0)
+            .and_then(|imp| imp.preview.as_ref())
+            .map(|preview| preview.fit_within(max_width.max(1), max_height.max(1)))
+    });
+    if let Some(image) = image {
+        match bitmap(&image) {
+            Ok(bitmap) => {
+                if let Err(e) = deliver(bitmap) {
+                    logwise::warn_sync!(
+                        "Can't send preview to DWM: {e}",
+                        e = logwise::privacy::LogIt(&e)
+                    );
+                }
+                unsafe { _ = DeleteObject(bitmap.into()) };
+            }
+            Err(e) => logwise::warn_sync!(
+                "Can't create preview bitmap: {e}",
+                e = logwise::privacy::LogIt(&e)
+            ),
+        }
+    }
+    LRESULT(0)
+}
+
+/// DWM wants a top-down, premultiplied 32bpp DIB.
+fn bitmap(image: &PreviewImage) -> windows::core::Result<HBITMAP> {
+    let info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: image.width() as i32,
+            //negative for top-down
+            biHeight: -(image.height() as i32),
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut bits = std::ptr::null_mut();
+    let bitmap = unsafe { CreateDIBSection(None, &info, DIB_RGB_COLORS, &mut bits, None, 0) }?;
+    let pixels = crate::drag::premultiplied_bgra(image.rgba());
+    unsafe { std::ptr::copy_nonoverlapping(pixels.as_ptr(), bits as *mut u8, pixels.len()) };
+    Ok(bitmap)
+}
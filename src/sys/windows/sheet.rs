@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Sheets, emulated as modal dialogs.
+//!
+//! Windows has no sheets, so we do what `DialogBox` does: the sheet becomes an owned window,
+//! which stays above its owner and minimizes with it, and the owner is disabled until the sheet
+//! is hidden or destroyed.
+
+use super::HWND_IMPS;
+use r#continue::Sender;
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::UI::Input::KeyboardAndMouse::EnableWindow;
+#[cfg(target_pointer_width = "64")]
+use windows::Win32::UI::WindowsAndMessaging::SetWindowLongPtrW;
+#[cfg(target_pointer_width = "32")]
+use windows::Win32::UI::WindowsAndMessaging::SetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    GWLP_HWNDPARENT, GetWindowRect, SW_SHOW, SWP_NOSIZE, SWP_NOZORDER, SetWindowPos, ShowWindow,
+};
+
+/// A sheet that's up, kept with the sheet's window.
+pub(super) struct Sheet {
+    parent: HWND,
+    done: Sender<()>,
+}
+
+fn set_owner(hwnd: HWND, owner: Option<HWND>) {
+    let owner = owner.map_or(0, |owner| owner.0 as isize);
+    //32-bit Windows only has the non-Ptr version
+    #[cfg(target_pointer_width = "64")]
+    unsafe {
+        SetWindowLongPtrW(hwnd, GWLP_HWNDPARENT, owner)
+    };
+    #[cfg(target_pointer_width = "32")]
+    unsafe {
+        SetWindowLongW(hwnd, GWLP_HWNDPARENT, owner as i32)
+    };
+}
+
+/// Makes `hwnd` belong to `owner`, outside of a sheet.  Call on the main thread.
+pub(super) fn set_parent(hwnd: HWND, owner: HWND) {
+    let in_sheet = HWND_IMPS.with_borrow_mut(|c| {
+        let imp = c.entry(hwnd.0).or_default();
+        imp.owner = Some(owner);
+        imp.sheet.is_some()
+    });
+    //a sheet's owner is set until it ends, which restores this one
+    if !in_sheet {
+        set_owner(hwnd, Some(owner));
+    }
+}
+
+/// Shows `sheet` over `parent`, and disables `parent` until [`end`].  Call on the main thread.
+pub(super) fn begin(parent: HWND, sheet: HWND, done: Sender<()>) {
+    //already up over something else
+    end(sheet);
+    set_owner(sheet, Some(parent));
+    let mut parent_rect = RECT::default();
+    let mut sheet_rect = RECT::default();
+    if unsafe { GetWindowRect(parent, &mut parent_rect) }.is_ok()
+        && unsafe { GetWindowRect(sheet, &mut sheet_rect) }.is_ok()
+    {
+        let x = parent_rect.left
+            + ((parent_rect.right - parent_rect.left) - (sheet_rect.right - sheet_rect.left)) / 2;
+        let y = parent_rect.top
+            + ((parent_rect.bottom - parent_rect.top) - (sheet_rect.bottom - sheet_rect.top)) / 2;
+        _ = unsafe { SetWindowPos(sheet, None, x, y, 0, 0, SWP_NOSIZE | SWP_NOZORDER) };
+    }
+    HWND_IMPS
+        .with_borrow_mut(|c| c.entry(sheet.0).or_default().sheet = Some(Sheet { parent, done }));
+    _ = unsafe { EnableWindow(parent, false) };
+    _ = unsafe { ShowWindow(sheet, SW_SHOW) };
+}
+
+/// Ends the sheet `hwnd`, if it's one.  Call as it's hidden or destroyed, before it goes, so
+/// activation passes back to the parent.
+pub(super) fn end(hwnd: HWND) {
+    let Some((sheet, owner)) = HWND_IMPS.with_borrow_mut(|c| {
+        c.get_mut(&hwnd.0)
+            .and_then(|imp| Some((imp.sheet.take()?, imp.owner)))
+    }) else {
+        return;
+    };
+    _ = unsafe { EnableWindow(sheet.parent, true) };
+    set_owner(hwnd, owner);
+    sheet.done.send(());
+}
@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Keeps system shortcuts away from the shell while an inhibiting window is in the foreground.
+//!
+//! Windows has no API for this, so we install a low-level keyboard hook, but only while some
+//! window asks for it.  The hook swallows the few combinations the shell acts on (the Windows
+//! key, Alt+Tab, Alt+Esc and Ctrl+Esc) and posts them to the window instead.  Everything
+//! else, including other apps' `RegisterHotKey` combinations, passes through untouched.
+//! Ctrl+Alt+Delete never reaches a hook.
+
+use super::HWND_IMPS;
+use crate::window::WindowSettingError;
+use std::cell::Cell;
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, VK_CONTROL, VK_ESCAPE, VK_LWIN, VK_RWIN, VK_TAB,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetForegroundWindow, HC_ACTION, HHOOK, KBDLLHOOKSTRUCT, LLKHF_ALTDOWN,
+    LLKHF_UP, PostMessageW, SetWindowsHookExW, UnhookWindowsHookEx, WH_KEYBOARD_LL, WM_KEYDOWN,
+    WM_KEYUP,
+};
+use windows::core::PCWSTR;
+
+thread_local! {
+    static HOOK: Cell<Option<HHOOK>> = const { Cell::new(None) };
+}
+
+fn is_system_shortcut(info: &KBDLLHOOKSTRUCT) -> bool {
+    let vk = info.vkCode as u16;
+    let alt = info.flags.0 & LLKHF_ALTDOWN.0 != 0;
+    let ctrl = unsafe { GetAsyncKeyState(VK_CONTROL.0 as i32) } < 0;
+    vk == VK_LWIN.0
+        || vk == VK_RWIN.0
+        || (alt && (vk == VK_TAB.0 || vk == VK_ESCAPE.0))
+        || (ctrl && vk == VK_ESCAPE.0)
+}
+
+extern "system" fn hook_proc(code: i32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let info = unsafe { &*(l_param.0 as *const KBDLLHOOKSTRUCT) };
+        let foreground = unsafe { GetForegroundWindow() };
+        let inhibited = HWND_IMPS.with_borrow(|c| {
+            c.get(&foreground.0)
+                .is_some_and(|imp| imp.inhibit_shortcuts)
+        });
+        if inhibited && is_system_shortcut(info) {
+            //swallowing the key hides it from the window too, so deliver it ourselves
+            let msg = if info.flags.0 & LLKHF_UP.0 != 0 {
+                WM_KEYUP
+            } else {
+                WM_KEYDOWN
+            };
+            unsafe {
+                _ = PostMessageW(
+                    Some(foreground),
+                    msg,
+                    WPARAM(info.vkCode as usize),
+                    LPARAM(0),
+                )
+            };
+            return LRESULT(1);
+        }
+    }
+    unsafe { CallNextHookEx(None, code, w_param, l_param) }
+}
+
+/// Installs the hook if any window inhibits shortcuts, and removes it otherwise.  Call on the
+/// main thread, whose message loop runs the hook.
+pub(super) fn update_hook() -> Result<(), WindowSettingError> {
+    let wanted = HWND_IMPS.with_borrow(|c| c.values().any(|imp| imp.inhibit_shortcuts));
+    match (wanted, HOOK.get()) {
+        (true, None) => {
+            let instance = unsafe { GetModuleHandleW(PCWSTR::null()) }
+                .map_err(|e| WindowSettingError::Platform(e.to_string()))?;
+            let hook = unsafe {
+                SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), Some(instance.into()), 0)
+            }
+            .map_err(|e| WindowSettingError::Platform(e.to_string()))?;
+            HOOK.set(Some(hook));
+        }
+        (false, Some(hook)) => {
+            HOOK.set(None);
+            unsafe { UnhookWindowsHookEx(hook) }
+                .map_err(|e| WindowSettingError::Platform(e.to_string()))?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
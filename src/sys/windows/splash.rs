@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Splash windows, painted with GDI so they show before any GPU work.
+
+use super::{HWND_IMPS, Window, apply_window_settings, create_window_impl};
+use crate::coordinates::{Position, Size};
+use crate::window::{PreviewImage, WindowEventSender};
+use send_cells::send_cell::SendCell;
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BeginPaint, DIB_RGB_COLORS, EndPaint, InvalidateRect,
+    PAINTSTRUCT, SRCCOPY, StretchDIBits,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    AW_BLEND, AW_HIDE, AnimateWindow, GetClientRect, GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN,
+    SW_SHOWNOACTIVATE, ShowWindow, WS_POPUP,
+};
+
+const FADE_MILLIS: u32 = 200;
+
+pub(super) async fn new_splash(size: Size, image: PreviewImage) -> Window {
+    let events = WindowEventSender::default();
+    let window_events = events.clone();
+    let hwnd = crate::application::on_main_thread("Window::new_splash".into(), move || {
+        //the screen's in physical pixels, and the size in logical ones
+        let scale = super::dpi::system_scale();
+        let position = super::dpi::logical_position(
+            ((unsafe { GetSystemMetrics(SM_CXSCREEN) } as f64 - size.width() * scale) / 2.0) as i32,
+            ((unsafe { GetSystemMetrics(SM_CYSCREEN) } as f64 - size.height() * scale) / 2.0)
+                as i32,
+        );
+        let hwnd = create_window_impl(position, size, String::new(), WS_POPUP, true, window_events);
+        //WM_PAINT waits for the queue to drain, so this lands before the first paint
+        HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().placeholder = Some(image));
+        SendCell::new(hwnd)
+    })
+    .await;
+    Window { hwnd, events }
+}
+
+/// A splash that stays above other windows without taking focus, for the diagnostic overlay.
+pub(super) async fn new_overlay(size: Size, image: PreviewImage) -> Window {
+    let events = WindowEventSender::default();
+    let window_events = events.clone();
+    let hwnd = crate::application::on_main_thread("Window::new_overlay".into(), move || {
+        let position = Position::new(10.0, 10.0);
+        let hwnd = create_window_impl(
+            position,
+            size,
+            String::new(),
+            WS_POPUP,
+            false,
+            window_events,
+        );
+        HWND_IMPS.with_borrow_mut(|c| {
+            let imp = c.entry(hwnd.0).or_default();
+            imp.placeholder = Some(image);
+            //a topmost tool window, as for every workspace
+            imp.skip_taskbar = true;
+            imp.all_workspaces = true;
+        });
+        _ = apply_window_settings(hwnd);
+        unsafe { _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE) };
+        SendCell::new(hwnd)
+    })
+    .await;
+    Window { hwnd, events }
+}
+
+pub(super) async fn set_image(window: &Window, image: PreviewImage) {
+    let send_hwnd = window.hwnd.copying();
+    crate::application::on_main_thread("Window::set_splash_image".into(), move || {
+        let hwnd = *send_hwnd.get();
+        HWND_IMPS.with_borrow_mut(|c| c.entry(hwnd.0).or_default().placeholder = Some(image));
+        unsafe { _ = InvalidateRect(Some(hwnd), None, false) };
+    })
+    .await
+}
+
+pub(super) async fn fade_out(window: &Window) {
+    let send_hwnd = window.hwnd.copying();
+    crate::application::on_main_thread("Window::fade_out".into(), move || {
+        //blocks the message loop for the length of the fade, but only once, at startup
+        unsafe { _ = AnimateWindow(*send_hwnd.get(), FADE_MILLIS, AW_BLEND | AW_HIDE) };
+    })
+    .await
+}
+
+/// Paints the placeholder, if the window has one.  Returns whether it did.
+pub(super) fn paint(hwnd: HWND) -> bool {
+    let image = HWND_IMPS.with_borrow(|c| c.get(&hwnd.0).and_then(|imp| imp.placeholder.clone()));
+    let Some(image) = image else {
+        return false;
+    };
+    let info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: image.width() as i32,
+            //negative for top-down
+            biHeight: -(image.height() as i32),
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let pixels = crate::drag::premultiplied_bgra(image.rgba());
+    let mut client = RECT::default();
+    let mut paint = PAINTSTRUCT::default();
+    unsafe {
+        _ = GetClientRect(hwnd, &mut client);
+        let dc = BeginPaint(hwnd, &mut paint);
+        StretchDIBits(
+            dc,
+            0,
+            0,
+            client.right - client.left,
+            client.bottom - client.top,
+            0,
+            0,
+            image.width() as i32,
+            image.height() as i32,
+            Some(pixels.as_ptr().cast()),
+            &info,
+            DIB_RGB_COLORS,
+            SRCCOPY,
+        );
+        _ = EndPaint(hwnd, &paint);
+    }
+    true
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Delayed closures on the main thread, as thread timers.
+//!
+//! A timer without a window fires through the message loop's `DispatchMessageW`, which calls
+//! its proc directly.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{KillTimer, SetTimer, USER_TIMER_MAXIMUM};
+
+thread_local! {
+    static TIMERS: RefCell<HashMap<usize, Box<dyn FnOnce()>>> = RefCell::new(HashMap::new());
+}
+
+extern "system" fn timer_proc(_hwnd: HWND, _msg: u32, id: usize, _time: u32) {
+    //thread timers repeat until killed
+    _ = unsafe { KillTimer(None, id) };
+    let closure = TIMERS.with_borrow_mut(|timers| timers.remove(&id));
+    if let Some(closure) = closure {
+        closure();
+    }
+}
+
+pub fn on_main_thread_after<F: FnOnce() + Send + 'static>(delay: Duration, closure: F) {
+    super::on_main_thread(move || {
+        //round up, so we don't fire early
+        let millis = delay
+            .as_nanos()
+            .div_ceil(1_000_000)
+            .min(USER_TIMER_MAXIMUM as u128) as u32;
+        let id = unsafe { SetTimer(None, 0, millis, Some(timer_proc)) };
+        if id == 0 {
+            logwise::error_sync!("SetTimer failed; running the timer now");
+            closure();
+            return;
+        }
+        TIMERS.with_borrow_mut(|timers| timers.insert(id, Box::new(closure)));
+    });
+}
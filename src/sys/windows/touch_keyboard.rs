@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The touch keyboard, which is `TabTip.exe`.
+//!
+//! Windows shows it by itself when a text control is focused in tablet mode, but our windows
+//! draw their own controls.  Starting TabTip shows it, and closing its window hides it.
+
+use crate::window::WindowSettingError;
+use std::path::PathBuf;
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{FindWindowW, PostMessageW, SC_CLOSE, WM_SYSCOMMAND};
+use windows::core::{PCWSTR, w};
+
+pub(super) fn show() -> Result<(), WindowSettingError> {
+    //TabTip is 64-bit, so a 32-bit process has to ask for the 64-bit Common Files
+    let common = std::env::var_os("CommonProgramW6432")
+        .or_else(|| std::env::var_os("CommonProgramFiles"))
+        .ok_or(WindowSettingError::Unsupported)?;
+    let tab_tip = PathBuf::from(common).join(r"microsoft shared\ink\TabTip.exe");
+    if !tab_tip.exists() {
+        return Err(WindowSettingError::Unsupported);
+    }
+    std::process::Command::new(tab_tip)
+        .spawn()
+        .map(drop)
+        .map_err(|e| WindowSettingError::Platform(e.to_string()))
+}
+
+pub(super) fn hide() -> Result<(), WindowSettingError> {
+    //if it isn't running, it isn't showing
+    let Ok(tab_tip) = (unsafe { FindWindowW(w!("IPTip_Main_Window"), PCWSTR::null()) }) else {
+        return Ok(());
+    };
+    unsafe {
+        PostMessageW(
+            Some(tab_tip),
+            WM_SYSCOMMAND,
+            WPARAM(SC_CLOSE as usize),
+            LPARAM(0),
+        )
+    }
+    .map_err(|e| WindowSettingError::Platform(e.to_string()))
+}
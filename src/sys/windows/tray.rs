@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Tray icons, with `Shell_NotifyIconW`.
+//!
+//! Each icon has a hidden window for the shell's callback messages.  It isn't message-only, so
+//! it hears `TaskbarCreated` when Explorer restarts and can add the icon again, and it can come
+//! to the foreground, which `TrackPopupMenu` needs for the menu to close when the user clicks
+//! elsewhere.
+
+use crate::tray::{TrayError, TrayEvent, TrayMenuItem, TrayState};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Shell::{
+    NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY, NOTIFYICONDATAW,
+    Shell_NotifyIconW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    AppendMenuW, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyIcon, DestroyMenu,
+    DestroyWindow, GetCursorPos, GetSystemMetrics, HICON, MF_GRAYED, MF_SEPARATOR, MF_STRING,
+    PostMessageW, RegisterClassExW, RegisterWindowMessageW, SM_CXSMICON, SetForegroundWindow,
+    TPM_RETURNCMD, TPM_RIGHTBUTTON, TrackPopupMenu, WINDOW_EX_STYLE, WM_APP, WM_LBUTTONUP, WM_NULL,
+    WM_RBUTTONUP, WNDCLASSEXW, WS_POPUP,
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+const CLASS_NAME: PCWSTR = w!("app_window_tray");
+/// What the shell sends our window about clicks on the icon.
+const WM_TRAY: u32 = WM_APP + 1;
+
+struct Entry {
+    id: u64,
+    icon: HICON,
+    tooltip: String,
+    menu: Vec<TrayMenuItem>,
+}
+
+thread_local! {
+    static CLASS_REGISTERED: Cell<bool> = const { Cell::new(false) };
+    /// Broadcast when Explorer starts, or restarts.
+    static TASKBAR_CREATED: Cell<u32> = const { Cell::new(0) };
+    /// Each icon, by its window.
+    static ICONS: RefCell<HashMap<isize, Entry>> = RefCell::new(HashMap::new());
+}
+
+fn platform(e: windows::core::Error) -> TrayError {
+    TrayError::Platform(e.to_string())
+}
+
+/// The icon's registration with the shell.  Each window has one icon, so its ID is always 0.
+fn notify_data(hwnd: HWND, entry: &Entry) -> NOTIFYICONDATAW {
+    let mut data = NOTIFYICONDATAW {
+        cbSize: size_of::<NOTIFYICONDATAW>() as u32,
+        hWnd: hwnd,
+        uID: 0,
+        uFlags: NIF_ICON | NIF_MESSAGE | NIF_TIP,
+        uCallbackMessage: WM_TRAY,
+        hIcon: entry.icon,
+        ..Default::default()
+    };
+    //leave room for the terminator
+    let tooltip: Vec<u16> = entry
+        .tooltip
+        .encode_utf16()
+        .take(data.szTip.len() - 1)
+        .collect();
+    data.szTip[..tooltip.len()].copy_from_slice(&tooltip);
+    data
+}
+
+fn create_icon(state: &TrayState) -> Result<HICON, TrayError> {
+    let side = unsafe { GetSystemMetrics(SM_CXSMICON) }.max(1) as u32;
+    super::icon::create(&state.icon, side).map_err(platform)
+}
+
+/// Shows `menu` at the pointer, and returns the index of the item chosen.
+fn show_menu(hwnd: HWND, menu: &[TrayMenuItem]) -> Option<usize> {
+    let popup = unsafe { CreatePopupMenu() }.ok()?;
+    for (index, item) in menu.iter().enumerate() {
+        match item {
+            TrayMenuItem::Item { label, enabled } => {
+                let flags = if *enabled {
+                    MF_STRING
+                } else {
+                    MF_STRING | MF_GRAYED
+                };
+                //commands count from 1, as 0 means nothing was chosen
+                let label = HSTRING::from(label.as_str());
+                unsafe { _ = AppendMenuW(popup, flags, index + 1, &label) };
+            }
+            TrayMenuItem::Separator => unsafe {
+                _ = AppendMenuW(popup, MF_SEPARATOR, 0, PCWSTR::null());
+            },
+        }
+    }
+    let mut point = POINT::default();
+    unsafe {
+        _ = GetCursorPos(&mut point);
+        _ = SetForegroundWindow(hwnd);
+    }
+    let chosen = unsafe {
+        TrackPopupMenu(
+            popup,
+            TPM_RETURNCMD | TPM_RIGHTBUTTON,
+            point.x,
+            point.y,
+            None,
+            hwnd,
+            None,
+        )
+    };
+    //so the menu opens again next time; see TrackPopupMenu's remarks
+    unsafe {
+        _ = PostMessageW(Some(hwnd), WM_NULL, WPARAM(0), LPARAM(0));
+        _ = DestroyMenu(popup);
+    }
+    (chosen.0 > 0).then(|| chosen.0 as usize - 1)
+}
+
+extern "system" fn tray_proc(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+    if msg == WM_TRAY {
+        let entry = ICONS.with_borrow(|icons| {
+            icons
+                .get(&(hwnd.0 as isize))
+                .map(|entry| (entry.id, entry.menu.clone()))
+        });
+        if let Some((id, menu)) = entry {
+            match l_param.0 as u32 {
+                WM_LBUTTONUP => crate::tray::notify(id, TrayEvent::Clicked),
+                WM_RBUTTONUP if !menu.is_empty() => {
+                    if let Some(index) = show_menu(hwnd, &menu) {
+                        crate::tray::notify(id, TrayEvent::MenuItem(index))
+                    }
+                }
+                _ => {}
+            }
+        }
+        return LRESULT(0);
+    }
+    if msg != 0 && msg == TASKBAR_CREATED.get() {
+        //a new Explorer doesn't know about us
+        ICONS.with_borrow(|icons| {
+            if let Some(entry) = icons.get(&(hwnd.0 as isize)) {
+                unsafe { _ = Shell_NotifyIconW(NIM_ADD, &notify_data(hwnd, entry)) };
+            }
+        });
+        return LRESULT(0);
+    }
+    unsafe { DefWindowProcW(hwnd, msg, w_param, l_param) }
+}
+
+/// A window that's never shown, for the shell to talk to.
+fn create_window() -> windows::core::Result<HWND> {
+    let instance = unsafe { GetModuleHandleW(PCWSTR::null()) }?;
+    if !CLASS_REGISTERED.replace(true) {
+        let class = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(tray_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        if unsafe { RegisterClassExW(&class) } == 0 {
+            CLASS_REGISTERED.set(false);
+            return Err(windows::core::Error::from_thread());
+        }
+        TASKBAR_CREATED.set(unsafe { RegisterWindowMessageW(w!("TaskbarCreated")) });
+    }
+    unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            CLASS_NAME,
+            PCWSTR::null(),
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+    }
+}
+
+/// Call on the main thread.
+fn add(id: u64, state: TrayState) -> Result<TrayIcon, TrayError> {
+    let hwnd = create_window().map_err(platform)?;
+    let icon = match create_icon(&state) {
+        Ok(icon) => icon,
+        Err(e) => {
+            unsafe { _ = DestroyWindow(hwnd) };
+            return Err(e);
+        }
+    };
+    let entry = Entry {
+        id,
+        icon,
+        tooltip: state.tooltip,
+        menu: state.menu,
+    };
+    if !unsafe { Shell_NotifyIconW(NIM_ADD, &notify_data(hwnd, &entry)) }.as_bool() {
+        unsafe {
+            _ = DestroyIcon(icon);
+            _ = DestroyWindow(hwnd);
+        }
+        return Err(TrayError::Platform(
+            "the shell didn't add the icon".to_string(),
+        ));
+    }
+    ICONS.with_borrow_mut(|icons| icons.insert(hwnd.0 as isize, entry));
+    Ok(TrayIcon {
+        hwnd: hwnd.0 as isize,
+    })
+}
+
+/// Call on the main thread.
+fn modify(hwnd: isize, state: TrayState) -> Result<(), TrayError> {
+    let icon = create_icon(&state)?;
+    ICONS.with_borrow_mut(|icons| {
+        let Some(entry) = icons.get_mut(&hwnd) else {
+            unsafe { _ = DestroyIcon(icon) };
+            return Ok(());
+        };
+        let old = std::mem::replace(&mut entry.icon, icon);
+        entry.tooltip = state.tooltip;
+        entry.menu = state.menu;
+        let hwnd = HWND(hwnd as *mut _);
+        let modified = unsafe { Shell_NotifyIconW(NIM_MODIFY, &notify_data(hwnd, entry)) };
+        //the shell has its own copy, or still shows the old one
+        unsafe { _ = DestroyIcon(if modified.as_bool() { old } else { icon }) };
+        if !modified.as_bool() {
+            entry.icon = old;
+            return Err(TrayError::Platform(
+                "the shell didn't update the icon".to_string(),
+            ));
+        }
+        Ok(())
+    })
+}
+
+/// Call on the main thread.
+fn remove(hwnd: isize) {
+    let Some(entry) = ICONS.with_borrow_mut(|icons| icons.remove(&hwnd)) else {
+        return;
+    };
+    let hwnd = HWND(hwnd as *mut _);
+    unsafe {
+        _ = Shell_NotifyIconW(NIM_DELETE, &notify_data(hwnd, &entry));
+        _ = DestroyIcon(entry.icon);
+        _ = DestroyWindow(hwnd);
+    }
+}
+
+/// Our hidden window, as an integer so the icon can be sent anywhere; it's only used on the
+/// main thread.
+#[derive(Debug)]
+pub struct TrayIcon {
+    hwnd: isize,
+}
+
+impl TrayIcon {
+    pub async fn new(id: u64, state: TrayState) -> Result<Self, TrayError> {
+        crate::application::on_main_thread("TrayIcon::new".to_string(), move || add(id, state))
+            .await
+    }
+
+    pub async fn update(&self, state: TrayState) -> Result<(), TrayError> {
+        let hwnd = self.hwnd;
+        crate::application::on_main_thread("TrayIcon::update".to_string(), move || {
+            modify(hwnd, state)
+        })
+        .await
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        let hwnd = self.hwnd;
+        crate::application::submit_to_main_thread("TrayIcon::drop".to_string(), move || {
+            remove(hwnd)
+        });
+    }
+}
@@ -101,5 +101,6 @@ where
             c();
             crate::sys::stop_main_thread();
         })
+        .expect("Failed to start the main thread")
     }
 }
@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::window::IconData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// An error showing or updating a tray icon.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TrayError {
+    /// The platform or desktop environment has no tray to show icons in.
+    #[error("tray icons are not supported here")]
+    Unsupported,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// An entry in a tray icon's menu.  See [`TrayIcon::set_menu`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TrayMenuItem {
+    /// A command.  Choosing it sends [`TrayEvent::MenuItem`] with the item's index in the menu.
+    Item {
+        /// The text shown.
+        label: String,
+        /// Whether it can be chosen.  Disabled items are shown dimmed.
+        enabled: bool,
+    },
+    /// A line between groups of items.
+    Separator,
+}
+
+impl TrayMenuItem {
+    /// An enabled item.
+    pub fn new(label: impl Into<String>) -> Self {
+        TrayMenuItem::Item {
+            label: label.into(),
+            enabled: true,
+        }
+    }
+
+    /// An item shown dimmed, which can't be chosen.
+    pub fn disabled(label: impl Into<String>) -> Self {
+        TrayMenuItem::Item {
+            label: label.into(),
+            enabled: false,
+        }
+    }
+}
+
+/// Something the user did to a tray icon.  See [`TrayIcon::new`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TrayEvent {
+    /// The icon was clicked, or otherwise activated.
+    Clicked,
+    /// An item of the icon's menu was chosen.  This is its index in the menu, counting
+    /// separators.
+    MenuItem(usize),
+}
+
+/// Everything a tray icon shows.  Backends get all of it with each change.
+#[derive(Debug, Clone)]
+pub(crate) struct TrayState {
+    pub icon: IconData,
+    pub tooltip: String,
+    pub menu: Vec<TrayMenuItem>,
+}
+
+type Handler = Arc<Mutex<dyn FnMut(TrayEvent) + Send>>;
+
+static HANDLERS: Mutex<Vec<(u64, Handler)>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Runs the handler of tray icon `id` with `event`, on the main thread.
+///
+/// Backends call this from wherever the platform tells them, which is often the main thread
+/// already.  The handler still runs from a later turn of it, so it can update or drop its own
+/// icon.
+#[cfg_attr(
+    not(any(
+        all(target_os = "linux", feature = "tray"),
+        target_os = "windows",
+        target_os = "macos"
+    )),
+    allow(dead_code)
+)]
+pub(crate) fn notify(id: u64, event: TrayEvent) {
+    let handler = HANDLERS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(other, _)| *other == id)
+        .map(|(_, handler)| handler.clone());
+    //the icon may be gone by now
+    if let Some(handler) = handler {
+        crate::application::submit_to_main_thread("tray::notify".to_string(), move || {
+            (handler.lock().unwrap())(event)
+        });
+    }
+}
+
+/// An icon in the system tray, or wherever the platform shows status items.
+///
+/// Tray icons suit apps that keep running in the background, like sync clients and
+/// chat apps: the icon shows the app is there, and its menu offers a few commands without a
+/// window.
+///
+/// Dropping the icon removes it.
+///
+/// # Platform behavior
+///
+/// * **macOS**: an `NSStatusItem` in the menu bar.  With a menu, a click opens the menu rather
+///   than sending [`TrayEvent::Clicked`].
+/// * **Windows**: `Shell_NotifyIconW` in the notification area.  A left click sends
+///   [`TrayEvent::Clicked`], and a right click opens the menu.  The icon comes back if Explorer
+///   restarts.
+/// * **Linux**: a `StatusNotifierItem` over D-Bus, with its menu as `com.canonical.dbusmenu`.
+///   KDE and most other desktops show these; GNOME needs the AppIndicator extension.  Needs the
+///   off-by-default `tray` feature.
+/// * **wasm32**: shows nothing, though creating and updating the icon succeed.
+/// * **iOS, Android**: returns [`TrayError::Unsupported`].
+#[derive(Debug)]
+#[must_use = "Dropping a tray icon removes it"]
+pub struct TrayIcon {
+    id: u64,
+    state: Mutex<TrayState>,
+    sys: crate::sys::TrayIcon,
+}
+
+impl TrayIcon {
+    /// Shows `icon` in the tray with `tooltip`, and no menu.
+    ///
+    /// `handler` runs on the main thread for each [`TrayEvent`], until the icon is dropped.
+    ///
+    /// # Errors
+    ///
+    /// * [`TrayError::Unsupported`] on platforms without a tray, or on Linux if no
+    ///   `StatusNotifierWatcher` is running.
+    /// * [`TrayError::Platform`] if the platform reported an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::tray::{TrayEvent, TrayIcon, TrayMenuItem};
+    /// use app_window::window::IconData;
+    ///
+    /// let icon = IconData::new(1, 1, vec![0x20, 0x80, 0xFF, 0xFF]).unwrap();
+    /// let tray = TrayIcon::new(icon, "Syncing".to_string(), |event| match event {
+    ///     TrayEvent::Clicked => println!("show the main window"),
+    ///     TrayEvent::MenuItem(0) => println!("pause"),
+    ///     TrayEvent::MenuItem(_) => println!("quit"),
+    ///     _ => {}
+    /// })
+    /// .await;
+    /// match tray {
+    ///     Ok(tray) => {
+    ///         let menu = vec![
+    ///             TrayMenuItem::new("Pause"),
+    ///             TrayMenuItem::Separator,
+    ///             TrayMenuItem::new("Quit"),
+    ///         ];
+    ///         //quit is the third item, counting the separator
+    ///         _ = tray.set_menu(menu).await;
+    ///         std::mem::forget(tray);
+    ///     }
+    ///     Err(e) => eprintln!("no tray icon: {e}"),
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`application::main()`](crate::application::main) has not been called.
+    pub async fn new<F: FnMut(TrayEvent) + Send + 'static>(
+        icon: IconData,
+        tooltip: String,
+        handler: F,
+    ) -> Result<Self, TrayError> {
+        assert!(
+            crate::application::is_main_thread_running(),
+            "{}",
+            crate::application::CALL_MAIN
+        );
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        HANDLERS
+            .lock()
+            .unwrap()
+            .push((id, Arc::new(Mutex::new(handler))));
+        let state = TrayState {
+            icon,
+            tooltip,
+            menu: Vec::new(),
+        };
+        match crate::sys::TrayIcon::new(id, state.clone()).await {
+            Ok(sys) => Ok(TrayIcon {
+                id,
+                state: Mutex::new(state),
+                sys,
+            }),
+            Err(e) => {
+                unregister(id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Replaces the icon's image.
+    pub async fn set_icon(&self, icon: IconData) -> Result<(), TrayError> {
+        self.update(|state| state.icon = icon).await
+    }
+
+    /// Replaces the text shown when hovering over the icon.
+    pub async fn set_tooltip(&self, tooltip: String) -> Result<(), TrayError> {
+        self.update(|state| state.tooltip = tooltip).await
+    }
+
+    /// Replaces the icon's menu.  An empty menu removes it.
+    pub async fn set_menu(&self, menu: Vec<TrayMenuItem>) -> Result<(), TrayError> {
+        self.update(|state| state.menu = menu).await
+    }
+
+    async fn update(&self, f: impl FnOnce(&mut TrayState)) -> Result<(), TrayError> {
+        let state = {
+            let mut state = self.state.lock().unwrap();
+            f(&mut state);
+            state.clone()
+        };
+        self.sys.update(state).await
+    }
+}
+
+fn unregister(id: u64) {
+    HANDLERS.lock().unwrap().retain(|(other, _)| *other != id);
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        //the sys icon removes itself
+        unregister(self.id);
+    }
+}
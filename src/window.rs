@@ -40,10 +40,41 @@
 //! ```
 
 use crate::application::CALL_MAIN;
-use crate::coordinates::{Position, Size};
+use crate::coordinates::{Position, Rect, Size};
+use crate::dialog::Color;
+use crate::drag::{DragActions, DragData, DragError, DragImage, DragSession};
 use crate::surface::Surface;
 use crate::sys;
+use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+#[cfg(all(
+    feature = "accessibility",
+    any(target_os = "linux", target_os = "windows", target_os = "macos")
+))]
+pub(crate) mod accessibility;
+mod busy;
+mod decoration;
+mod events;
+mod id;
+mod native;
+mod redraw;
+mod region;
+pub use busy::CursorGuard;
+pub use decoration::DecorationTheme;
+pub(crate) use events::EventHandler;
+pub use events::{
+    BackendError, TiledEdges, WindowEvent, WindowEventSender, WindowEvents, WindowStateFlags,
+};
+pub use id::WindowId;
+#[cfg(feature = "input")]
+pub(crate) use id::id_for_handle;
+pub use native::NativeHandle;
+pub use redraw::FrameTiming;
+pub(crate) use redraw::{FrameCallback, FrameRequester};
+pub use region::{DEFAULT_RESIZE_BORDER, MouseRegion, ResizeEdge};
 
 /// A cross-platform window.
 ///
@@ -87,14 +118,41 @@ use std::fmt::Display;
 /// - **Windows**: Uses Win32 APIs
 /// - **macOS**: Uses AppKit
 /// - **Linux**: Uses Wayland
-/// - **Web**: Creates a canvas element
+/// - **Web**: Creates a canvas element.  Each window has its own canvas, so several can share
+///   a page.
 ///
 /// See the [crate documentation](crate) for more details about platform-specific behavior.
 #[derive(Debug)]
 #[must_use = "Dropping a window will close it!"]
 pub struct Window {
-    sys: crate::sys::Window,
-    created_surface: bool,
+    //shared with busy cursor guards, which restore the cursor after we return them
+    sys: Arc<crate::sys::Window>,
+    /// The surface [`Window::surface`] hands out clones of, while any are alive.
+    surface: Weak<crate::surface::Shared>,
+    scale_override: ScaleOverride,
+    registration: id::Registration,
+    redraw: Arc<redraw::Redraw>,
+    cursor: Arc<Mutex<busy::CursorState>>,
+}
+
+/// A scale factor reported in place of the platform's, shared between a [`Window`] and its
+/// [`Surface`].  See [`Window::set_scale_override`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ScaleOverride(Arc<AtomicU64>);
+
+impl ScaleOverride {
+    //0.0 is never a valid scale, and its bits are 0
+    pub(crate) fn get(&self) -> Option<f64> {
+        match self.0.load(Ordering::Relaxed) {
+            0 => None,
+            bits => Some(f64::from_bits(bits)),
+        }
+    }
+
+    fn set(&self, scale: Option<f64>) {
+        self.0
+            .store(scale.map_or(0, f64::to_bits), Ordering::Relaxed);
+    }
 }
 
 /// An error that can occur when creating a fullscreen window.
@@ -104,7 +162,8 @@ pub struct Window {
 ///
 /// - **macOS**: May fail if fullscreen is not supported by the display
 /// - **Windows**: May fail if exclusive fullscreen mode cannot be acquired
-/// - **Linux**: May fail if the compositor doesn't support fullscreen
+/// - **Linux**: Fails if the requested output doesn't exist, or if the compositor declines to
+///   make the window fullscreen
 /// - **Web**: May fail if fullscreen permission is not granted
 #[derive(thiserror::Error, Debug)]
 pub struct FullscreenError(#[from] sys::FullscreenError);
@@ -115,6 +174,143 @@ impl Display for FullscreenError {
     }
 }
 
+/// An error changing a window setting.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WindowSettingError {
+    /// The platform or window system has no way to apply this setting.
+    #[error("not supported on this platform")]
+    Unsupported,
+    /// An image's pixel data doesn't match its dimensions.
+    #[error("image is not width * height RGBA8 pixels")]
+    InvalidImage,
+    /// An accessibility tree update can't be applied, such as a first one without a `tree`.
+    #[error("invalid accessibility tree update")]
+    InvalidTree,
+    /// The platform reported an error.
+    #[error("platform error: {0}")]
+    Platform(String),
+}
+
+/// A picture of a window's content, shown by task switchers.  See
+/// [`Window::set_preview_image`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewImage {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl PreviewImage {
+    /// Creates a preview from non-premultiplied RGBA8 pixels, in row-major order.
+    ///
+    /// Returns [`WindowSettingError::InvalidImage`] if `rgba` is not `width * height * 4`
+    /// bytes, or if either dimension is zero.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Result<Self, WindowSettingError> {
+        if width == 0 || height == 0 || rgba.len() != width as usize * height as usize * 4 {
+            return Err(WindowSettingError::InvalidImage);
+        }
+        Ok(PreviewImage {
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    /// Width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The RGBA8 pixels.
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Shrinks the image, keeping its aspect ratio, until it fits in `max_width` by
+    /// `max_height`.  Never enlarges it.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn fit_within(&self, max_width: u32, max_height: u32) -> PreviewImage {
+        let scale = (max_width as f64 / self.width as f64)
+            .min(max_height as f64 / self.height as f64)
+            .min(1.0);
+        let width = ((self.width as f64 * scale) as u32).max(1);
+        let height = ((self.height as f64 * scale) as u32).max(1);
+        self.resized(width, height)
+    }
+
+    /// Stretches the image to exactly `width` by `height`, which must not be zero.
+    #[cfg_attr(not(any(target_os = "linux", target_os = "windows")), allow(dead_code))]
+    pub(crate) fn resized(&self, width: u32, height: u32) -> PreviewImage {
+        if width == self.width && height == self.height {
+            return self.clone();
+        }
+        //nearest neighbor is plenty for thumbnails and placeholders
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            let source_y = (y as u64 * self.height as u64 / height as u64) as usize;
+            for x in 0..width {
+                let source_x = (x as u64 * self.width as u64 / width as u64) as usize;
+                let i = (source_y * self.width as usize + source_x) * 4;
+                rgba.extend_from_slice(&self.rgba[i..i + 4]);
+            }
+        }
+        PreviewImage {
+            width,
+            height,
+            rgba,
+        }
+    }
+}
+
+/// A window's icon, as shown in its title bar, the taskbar or Dock, and task switchers.  See
+/// [`Window::set_icon`].
+///
+/// Platforms scale icons to the sizes they show, so supply one large enough for the biggest;
+/// 256x256 is plenty.  Square images look best.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconData {
+    image: PreviewImage,
+}
+
+impl IconData {
+    /// Creates an icon from non-premultiplied RGBA8 pixels, in row-major order.
+    ///
+    /// Returns [`WindowSettingError::InvalidImage`] if `rgba` is not `width * height * 4`
+    /// bytes, or if either dimension is zero.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Result<Self, WindowSettingError> {
+        Ok(IconData {
+            image: PreviewImage::new(width, height, rgba)?,
+        })
+    }
+
+    /// Width of the icon in pixels.
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    /// Height of the icon in pixels.
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    /// The RGBA8 pixels.
+    pub fn rgba(&self) -> &[u8] {
+        self.image.rgba()
+    }
+
+    /// The icon stretched to a `side` by `side` square, for platforms that only take those.
+    #[cfg_attr(not(any(target_os = "linux", target_os = "windows")), allow(dead_code))]
+    pub(crate) fn square(&self, side: u32) -> PreviewImage {
+        self.image.resized(side, side)
+    }
+}
+
 impl Window {
     /// Creates a fullscreen window.
     ///
@@ -167,12 +363,64 @@ impl Window {
             "{}",
             CALL_MAIN
         );
-        let sys = crate::sys::Window::fullscreen(title).await?;
-        Ok(Window {
-            sys,
-            created_surface: false,
-        })
+        let sys = crate::sys::Window::fullscreen(title, None).await?;
+        Ok(Window::from_sys(sys).await)
+    }
+
+    /// Creates a fullscreen window on a particular output (monitor).
+    ///
+    /// `output` is an identifier from [`WindowSnapshot::outputs`] or [`WindowDebugState::outputs`],
+    /// such as the output an existing window is on.  Otherwise as [`Window::fullscreen`].
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux (Wayland)**: Fullscreen on that `wl_output`.  Fails if it's gone, for instance
+    ///   because the monitor was unplugged.
+    /// * **Other platforms**: `output` is ignored, and the window is fullscreen wherever
+    ///   [`Window::fullscreen`] would put it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FullscreenError`] if there's no such output, or fullscreen mode cannot be
+    /// established.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: app_window::window::Window) {
+    /// //a second fullscreen window on the same monitor as `window`
+    /// if let Some(&output) = window.snapshot().await.outputs.first() {
+    ///     match app_window::window::Window::fullscreen_on("Viewer".to_string(), output).await {
+    ///         Ok(viewer) => std::mem::forget(viewer),
+    ///         Err(e) => eprintln!("Failed to create fullscreen: {}", e),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`application::main()`](crate::application::main) has not been called.
+    pub async fn fullscreen_on(title: String, output: u32) -> Result<Self, FullscreenError> {
+        assert!(
+            crate::application::is_main_thread_running(),
+            "{}",
+            CALL_MAIN
+        );
+        let sys = crate::sys::Window::fullscreen(title, Some(output)).await?;
+        Ok(Window::from_sys(sys).await)
     }
+    async fn from_sys(sys: crate::sys::Window) -> Self {
+        Window {
+            registration: id::Registration::new(&sys).await,
+            sys: Arc::new(sys),
+            surface: Weak::new(),
+            scale_override: ScaleOverride::default(),
+            redraw: Arc::default(),
+            cursor: Arc::default(),
+        }
+    }
+
     /// Creates a new window with the specified position, size, and title.
     ///
     /// The window will be created at the given position with the specified dimensions.
@@ -215,8 +463,14 @@ impl Window {
     /// # Platform Notes
     ///
     /// - **macOS**: Position is from the bottom-left of the screen
+    /// - **Windows**: Position is from the top-left of the primary screen, in logical pixels at
+    ///   the scale of the monitor it's on, so it shares a scale with `size`
     /// - **Other platforms**: Position is from the top-left of the screen
     /// - **Web**: Position may be ignored by the browser
+    /// - **Node.js**: There's no DOM, so this creates a virtual window.  Nothing is drawn, but
+    ///   [`Window::set_size`] and [`Window::set_visible`] are tracked and resize events are
+    ///   sent, so app logic can be tested without a browser.  Input only arrives through the
+    ///   `conformance` feature's injection hooks.
     ///
     /// # Panics
     ///
@@ -226,16 +480,15 @@ impl Window {
             crate::application::is_main_thread_running(),
             "Call app_window::application::main"
         );
-        Window {
-            sys: crate::sys::Window::new(position, size, title).await,
-            created_surface: false,
-        }
+        Window::from_sys(crate::sys::Window::new(position, size, title).await).await
     }
 
-    /// Creates a [`Surface`] for this window.
+    /// Returns the [`Surface`] for this window, creating it if needed.
     ///
     /// A surface is required for rendering content to the window using graphics APIs
-    /// like wgpu. Only one surface can be created per window.
+    /// like wgpu.  A window has one surface: while any handle to it is alive, calling this again
+    /// returns a clone of it, as does [`Surface::clone`].  Once every handle is dropped, the next
+    /// call creates it afresh.
     ///
     /// # Example
     ///
@@ -267,72 +520,2643 @@ impl Window {
     ///
     /// Creating a surface may be expensive on some platforms. Applications that
     /// don't need to render content can skip creating a surface to save resources.
+    pub async fn surface(&mut self) -> Surface {
+        if let Some(shared) = self.surface.upgrade() {
+            return Surface { shared };
+        }
+        let surface = Surface::new(
+            self.sys.surface().await,
+            self.scale_override.clone(),
+            self.sys.event_sender(),
+        );
+        self.surface = Arc::downgrade(&surface.shared);
+        surface
+    }
+
+    /// Starts dragging `data` out of this window.
     ///
-    /// # Panics
+    /// Drags must begin while a mouse button is held down over the window, usually after the
+    /// pointer has moved a short distance from where the button was pressed.  The optional
+    /// `image` is displayed under the cursor during the drag.
     ///
-    /// Panics if a surface has already been created for this window.
-    pub async fn surface(&mut self) -> Surface {
-        assert!(!self.created_surface, "Surface already created");
-        self.created_surface = true;
-        self.sys.surface().await
+    /// This resolves once the drag has been handed off to the system.  Where the platform's
+    /// drag loop is modal (Windows), it resolves after the drop completes.
+    ///
+    /// See the [`drag`](crate::drag) module for platform details.
+    ///
+    /// # Errors
+    ///
+    /// * [`DragError::NoPointerGrab`] if no mouse button is currently pressed over the window
+    /// * [`DragError::Unsupported`] if the platform can't carry this data (e.g. file paths on the web)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::drag::DragData;
+    ///
+    /// let data = DragData::bytes("text/plain;charset=utf-8", "dragged text");
+    /// match window.start_drag(data, None).await {
+    ///     Ok(()) => println!("Drag started"),
+    ///     Err(e) => eprintln!("Can't drag: {e}"),
+    /// }
+    /// # }
+    /// ```
+    pub async fn start_drag(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+    ) -> Result<(), DragError> {
+        self.start_drag_with_actions(data, image, DragActions::COPY)
+            .await
+            .map(drop)
     }
 
-    /// Creates a new window with platform-appropriate default settings.
+    /// Like [`Window::start_drag`], but lets the target move or link the data as well as copy
+    /// it, and reports what it did.
     ///
-    /// This is the simplest way to create a window. The platform will choose
-    /// reasonable defaults for position, size, and other properties:
+    /// Targets pick one of `actions`, usually from the modifier keys the user holds (say,
+    /// Shift to move on Windows, or Command on macOS), and the returned [`DragSession`] says
+    /// which.  When it finishes with [`DragAction::Move`](crate::drag::DragAction::Move), the
+    /// target has the data, and the app should delete its own, as file managers do.
     ///
-    /// - **Position**: Typically centered or cascaded
-    /// - **Size**: A reasonable default (often 800x600 or similar)
-    /// - **Title**: Platform-specific default or empty
+    /// # Platform behavior
+    ///
+    /// * **Linux**: `wl_data_source.set_actions`, and the compositor's `action` events.
+    ///   [`DragAction::Ask`](crate::drag::DragAction::Ask) lets the target ask the user.
+    /// * **Windows**: the allowed `DROPEFFECT`s; the drag is over when this returns.  There's
+    ///   no ask, so it's ignored.
+    /// * **macOS**: the session's `NSDragOperation` mask, which
+    ///   [`DragSession::set_actions`] can change mid-drag.  The action is only known once the
+    ///   drag ends.
+    /// * **wasm32**: the `DataTransfer`'s `effectAllowed`, and its `dropEffect` at `dragend`.
+    /// * **iOS, Android**: returns [`DragError::Unsupported`], as `start_drag` does.
     ///
     /// # Example
     ///
     /// ```
-    /// #[cfg(target_arch = "wasm32")] {
-    ///     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::drag::{DragAction, DragActions, DragData, DragOutcome};
+    ///
+    /// let data = DragData::paths(["/tmp/report.pdf"]);
+    /// let actions = DragActions::COPY | DragActions::MOVE;
+    /// match window.start_drag_with_actions(data, None, actions).await {
+    ///     Ok(session) => {
+    ///         if session.finished().await == DragOutcome::Dropped(DragAction::Move) {
+    ///             println!("moved; remove it from the list");
+    ///         }
+    ///     }
+    ///     Err(e) => eprintln!("Can't drag: {e}"),
     /// }
-    /// use app_window::test_support::doctest_main;
-    /// use some_executor::task::{Configuration, Task};
+    /// # }
+    /// ```
+    pub async fn start_drag_with_actions(
+        &self,
+        data: DragData,
+        image: Option<DragImage>,
+        actions: DragActions,
+    ) -> Result<DragSession, DragError> {
+        let (session, reporter) = DragSession::new(actions);
+        self.sys.start_drag(data, image, reporter).await?;
+        Ok(session)
+    }
+
+    /// Shows the platform's standard window menu at `position`.
     ///
-    /// doctest_main(|| {
-    ///     Task::without_notifications(
-    ///         "doctest".to_string(),
-    ///         Configuration::default(),
-    ///         async {
-    ///             let _window = app_window::window::Window::default().await;
-    ///             println!("Window created with default settings");
-    ///             // Window closes when dropped
-    ///         },
-    ///     ).spawn_static_current();
+    /// This is the menu that normally appears when right-clicking a window's title bar
+    /// (move, resize, minimize, maximize, close, and so on).  Apps that draw their own title
+    /// bar can call this from their right-click handler so that behavior keeps working.
+    ///
+    /// `position` is relative to the window's content area, in the same units as
+    /// [`Surface::size_scale`](crate::surface::Surface::size_scale).
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: sends `xdg_toplevel.show_window_menu`.  Compositors only honor this while
+    ///   a mouse button is held down, so call it from the button press, not the release.
+    /// * **Windows**: tracks the window's system menu and runs the chosen `WM_SYSCOMMAND`.
+    /// * **macOS**: pops up a menu with Minimize, Zoom, and Close.
+    /// * **wasm32**: browsers have no window menu, so this does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::coordinates::Position;
+    ///
+    /// // the user right-clicked our custom title bar at (120, 12)
+    /// window.show_window_menu(Position::new(120.0, 12.0)).await;
+    /// # }
+    /// ```
+    pub async fn show_window_menu(&self, position: Position) {
+        self.sys.show_window_menu(position).await
+    }
+
+    /// Shows or hides the window.
+    ///
+    /// A hidden window keeps its surface and all of its state; showing it again puts it back
+    /// on screen.  This is how an app restores a window that was hidden by
+    /// [`CloseBehavior::HideToTray`].
+    ///
+    /// On Linux a hidden window is unmapped, so stop presenting frames to its surface until it
+    /// is shown again.  On wasm32 this toggles the canvas's CSS `display`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// window.set_visible(false).await;
+    /// // ...later, e.g. when the tray icon is clicked
+    /// window.set_visible(true).await;
+    /// # }
+    /// ```
+    pub async fn set_visible(&self, visible: bool) {
+        self.sys.set_visible(visible).await
+    }
+
+    /// Asks for the window to be brought forward and given keyboard focus.
+    ///
+    /// Window systems guard against apps stealing focus, so this is a request.  When it's
+    /// refused, the window is usually marked as wanting attention instead.  Watch
+    /// [`Window::events`] for [`WindowEvent::Focused`] to find out when it's granted.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: activates the app, then `makeKeyAndOrderFront`, restoring the window if
+    ///   it's minimized.
+    /// * **Windows**: `SetForegroundWindow`, restoring the window if it's minimized.  Unless the
+    ///   app is in the foreground already, Windows flashes its taskbar button instead.
+    /// * **Linux**: `xdg-activation`.  A request made while a mouse button is held on one of
+    ///   our windows usually takes focus; compositors may only mark the window as urgent
+    ///   otherwise.  Returns [`WindowSettingError::Unsupported`] where the compositor doesn't
+    ///   offer the protocol.
+    /// * **wasm32**: `window.focus()`.  Browsers ignore it unless this page opened the one
+    ///   that has focus.
+    /// * **iOS, Android**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// //the download finished
+    /// if let Err(e) = window.focus().await {
+    ///     eprintln!("can't ask for focus: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn focus(&self) -> Result<(), WindowSettingError> {
+        self.sys.focus().await
+    }
+
+    /// Whether the window has keyboard focus.
+    ///
+    /// [`Window::events`] reports when this changes, with [`WindowEvent::Focused`] and
+    /// [`WindowEvent::Unfocused`].
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS, iOS**: whether it's the key window.
+    /// * **Windows**: whether it's the foreground window.
+    /// * **Linux**: whether it has the seat's keyboard focus.
+    /// * **wasm32**: `document.hasFocus()`.
+    /// * **Android**: whether the activity's window has input focus.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// if !window.is_focused().await {
+    ///     //pause the game until the player comes back
+    /// }
+    /// # }
+    /// ```
+    pub async fn is_focused(&self) -> bool {
+        self.sys.is_focused().await
+    }
+
+    /// Changes which browser responses to input on the canvas are suppressed.  See
+    /// [`CanvasBehavior`]; this does nothing outside wasm32.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::CanvasBehavior;
+    ///
+    /// //a game's menu screen has selectable text, so only suppress the context menu there
+    /// let behavior = CanvasBehavior::new().suppress_context_menu(true);
+    /// window.set_canvas_behavior(behavior).await;
+    /// # }
+    /// ```
+    pub async fn set_canvas_behavior(&self, behavior: CanvasBehavior) {
+        self.sys.set_canvas_behavior(behavior).await
+    }
+
+    /// Calls `f` when the user asks to close the window, and closes it only if `f` returns
+    /// [`CloseResponse::Close`].
+    ///
+    /// Use this to prompt the user to save unsaved work.  This replaces any
+    /// [`CloseBehavior`] set on the builder; it's shorthand for [`CloseBehavior::Ask`].
+    ///
+    /// # Platform behavior
+    ///
+    /// On wasm32 `f` runs on the page's `beforeunload` event, and returning
+    /// [`CloseResponse::Keep`] has the browser ask the user whether to leave.  Browsers don't
+    /// let the page show its own prompt there.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::CloseResponse;
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// let unsaved = Arc::new(AtomicBool::new(true));
+    /// let check = unsaved.clone();
+    /// window
+    ///     .on_close_requested(move || {
+    ///         if check.load(Ordering::Relaxed) {
+    ///             //show a save prompt, and close once it's dismissed
+    ///             CloseResponse::Keep
+    ///         } else {
+    ///             CloseResponse::Close
+    ///         }
+    ///     })
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn on_close_requested<F: FnMut() -> CloseResponse + Send + 'static>(&self, f: F) {
+        let f = Mutex::new(f);
+        self.sys
+            .set_close_behavior(CloseBehavior::Ask(Arc::new(move || (f.lock().unwrap())())))
+            .await
+    }
+
+    /// Asks for the [`Window::on_redraw`] handler to run when the compositor is ready for the
+    /// next frame.
+    ///
+    /// Requests made before that frame arrives share it, so this is cheap to call whenever
+    /// something changes.  To animate, call it again from the handler; when nothing asks, no
+    /// frames arrive and the window costs nothing.  Windows that are hidden or fully covered
+    /// may get no frames until they're shown.
+    ///
+    /// Can be called from any thread.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS, iOS**: a display link on the window's display.
+    /// * **Windows**: the next DWM composition pass.
+    /// * **Linux**: a `wl_surface.frame` callback.
+    /// * **wasm32**: `requestAnimationFrame`.
+    /// * **Android**: the Choreographer's next frame.
+    pub fn request_redraw(&self) {
+        if self.redraw.request() {
+            let redraw = self.redraw.clone();
+            self.sys
+                .request_frame(Box::new(move |timing| redraw.fire(timing)));
+        }
+    }
+
+    /// Sets what runs, on the main thread, for each frame asked for with
+    /// [`Window::request_redraw`].  Replaces any earlier handler.
+    ///
+    /// This is the place to render, so GPU apps draw only when the compositor will show the
+    /// result, instead of free-running.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn example(window: &app_window::window::Window) {
+    /// window.on_redraw(|timing| {
+    ///     //render here, positioned for when the frame will be shown
+    ///     let _shown_at = timing.target().unwrap_or(timing.timestamp());
     /// });
+    /// //whenever the scene changes
+    /// window.request_redraw();
+    /// # }
     /// ```
+    pub fn on_redraw<F: FnMut(FrameTiming) + Send + 'static>(&self, f: F) {
+        self.redraw.set_handler(Box::new(f));
+    }
+
+    /// Returns an id for this window, unique for the life of the app.
     ///
-    /// # Panics
+    /// With several windows open, compare it against the window input was delivered to, such
+    /// as `app_window::input::keyboard::KeyEvent::window_id`, to route the
+    /// input to the right document.
     ///
-    /// Panics if [`application::main()`](crate::application::main) has not been called.
-    pub async fn default() -> Self {
-        assert!(
-            crate::application::is_main_thread_running(),
-            "Call app_window::application::run_main_thread"
-        );
-        Window {
-            sys: crate::sys::Window::default().await,
-            created_surface: false,
+    /// # Platform behavior
+    ///
+    /// Android's input can't be attributed to a window, so it never matches there.  On wasm32
+    /// pointer input goes to the canvas under it, and keys to the canvas last pressed in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(first: &app_window::window::Window, second: &app_window::window::Window) {
+    /// use app_window::input::keyboard::Keyboard;
+    ///
+    /// let keyboard = Keyboard::coalesced().await;
+    /// let mut events = keyboard.events();
+    /// let event = events.next().await;
+    /// if event.window_id() == Some(first.id()) {
+    ///     //first's document gets the key
+    /// } else if event.window_id() == Some(second.id()) {
+    ///     //second's does
+    /// }
+    /// # }
+    /// ```
+    pub fn id(&self) -> WindowId {
+        self.registration.id()
+    }
+
+    /// Returns the window's events: resizes, moves, focus changes, and so on.
+    ///
+    /// Each call starts a new [`WindowEvents`] that sees everything from then on.  Apps can
+    /// react to lifecycle changes here without platform code.
+    ///
+    /// # Platform behavior
+    ///
+    /// Each platform reports what it knows:
+    ///
+    /// | Event | macOS | Windows | Linux | wasm32 | iOS | Android |
+    /// |-------|-------|---------|-------|--------|-----|---------|
+    /// | [`Resized`](WindowEvent::Resized) | ✅ | ✅ | ✅ | ✅ | ❌ | ❌ |
+    /// | [`Moved`](WindowEvent::Moved) | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+    /// | [`Focused`](WindowEvent::Focused) / [`Unfocused`](WindowEvent::Unfocused) | ✅ | ✅ | ✅ | ✅ | ❌ | ✅ |
+    /// | [`Minimized`](WindowEvent::Minimized) / [`Restored`](WindowEvent::Restored) | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+    /// | [`ScaleChanged`](WindowEvent::ScaleChanged) | ✅ | ✅ | ✅ | ✅ | ❌ | ❌ |
+    /// | [`Occluded`](WindowEvent::Occluded) / [`Visible`](WindowEvent::Visible) | ✅ | ✅ | ✅ | ✅ | ❌ | ❌ |
+    /// | [`StateChanged`](WindowEvent::StateChanged) | ✅ | ✅ | ✅ | ✅ | ❌ | ❌ |
+    /// | [`CloseRequested`](WindowEvent::CloseRequested) | ✅ | ✅ | ✅ | ❌ | ❌ | ❌ |
+    ///
+    /// Wayland doesn't tell clients where their windows are or when they're minimized, but
+    /// [`WindowStateFlags::suspended`] covers what the compositor does say.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::WindowEvent;
+    ///
+    /// let mut events = window.events();
+    /// loop {
+    ///     match events.next().await {
+    ///         WindowEvent::Minimized => println!("pausing"),
+    ///         WindowEvent::Restored => println!("resuming"),
+    ///         _ => {}
+    ///     }
+    /// #   break;
+    /// }
+    /// # }
+    /// ```
+    pub fn events(&self) -> WindowEvents {
+        self.sys.event_sender().subscribe()
+    }
+
+    /// Returns the window's scale factor: how many physical pixels make up one logical pixel.
+    ///
+    /// This is the scale [`Surface::size_scale`] reports, without creating a surface.  If
+    /// [`Window::set_scale_override`] is in effect, this returns the override.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// let scale = window.scale_factor().await;
+    /// println!("{scale}x");
+    /// # }
+    /// ```
+    pub async fn scale_factor(&self) -> f64 {
+        match self.scale_override.get() {
+            Some(scale) => scale,
+            None => self.sys.scale_factor().await,
         }
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::window::Window;
+    /// Reports `scale` as this window's scale factor instead of the platform's, or goes back to
+    /// the platform's with `None`.
+    ///
+    /// The override applies to [`Window::scale_factor`] and to this window's [`Surface`], and
+    /// only to this window.  It's for apps that do their own DPI handling, or that zoom for
+    /// accessibility by rendering at a larger scale.  The platform itself isn't told, so the
+    /// window's logical size and the compositor's scaling are unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If `scale` isn't finite and positive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// //draw everything at 1.5x, whatever the display says
+    /// window.set_scale_override(Some(1.5));
+    /// assert_eq!(window.scale_factor().await, 1.5);
+    /// # }
+    /// ```
+    pub fn set_scale_override(&self, scale: Option<f64>) {
+        if let Some(scale) = scale {
+            assert!(
+                scale.is_finite() && scale > 0.0,
+                "scale override must be finite and positive"
+            );
+        }
+        self.scale_override.set(scale);
+    }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
-    #[test]
-    fn test_send() {
-        fn assert_send<T: Send>() {}
-        assert_send::<Window>();
-        fn assert_sync<T: Sync>() {}
-        assert_sync::<Window>();
+    /// Resizes the window.
+    ///
+    /// `size` means what it does in [`Window::new`].  The user, or a tiling window manager, may
+    /// resize the window again afterwards; watch [`Surface::size_update`] to follow along.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: sets the content size.
+    /// * **Windows**: sets the outer size, including the frame, like `CreateWindowExW`.
+    /// * **Linux**: clients choose their own size unless the compositor says otherwise, as it
+    ///   does for maximized or tiled windows.  The new size holds until then.
+    /// * **wasm32**: sizes the canvas, which otherwise fills the viewport.
+    /// * **iOS, Android**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::coordinates::Size;
+    ///
+    /// if let Err(e) = window.set_size(Size::new(1024.0, 768.0)).await {
+    ///     eprintln!("can't resize: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_size(&self, size: Size) -> Result<(), WindowSettingError> {
+        self.sys.set_size(size).await
+    }
+
+    /// Moves the window, in the same coordinates as [`Window::new`].
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: moves the window.
+    /// * **Windows**: moves the window.  The position is in logical pixels at the scale of the
+    ///   monitor it lands on; see [`Window::new`].
+    /// * **Linux**: Wayland doesn't let clients place their windows, so this returns
+    ///   [`WindowSettingError::Unsupported`].
+    /// * **iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    pub async fn set_position(&self, position: Position) -> Result<(), WindowSettingError> {
+        self.sys.set_position(position).await
+    }
+
+    /// Where the window is, in the same coordinates as [`Window::set_position`], or `None` if
+    /// the platform doesn't say.
+    ///
+    /// This is the frame's top-left corner, title bar included.  To follow the window as it
+    /// moves, watch for [`WindowEvent::Moved`] in [`Window::events`].
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: the frame's position.
+    /// * **Windows**: the frame's position, in logical pixels at the scale of the monitor it's
+    ///   on, like the window's size.
+    /// * **Linux**: Wayland doesn't tell clients where their windows are, so this is `None`.
+    ///   Position popups with the surface-relative coordinates the compositor understands.
+    /// * **iOS, Android, wasm32**: `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// if let Some(position) = window.outer_position().await {
+    ///     println!("window at {}, {}", position.x(), position.y());
+    /// }
+    /// # }
+    /// ```
+    pub async fn outer_position(&self) -> Option<Position> {
+        self.sys.outer_position().await
+    }
+
+    /// Keeps the window on screen when the user switches virtual desktops (Spaces, workspaces).
+    ///
+    /// This is for utility palettes, chat heads and similar windows that should follow the
+    /// user around.  Pass `false` to tie the window to a single desktop again.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: sets `NSWindowCollectionBehaviorCanJoinAllSpaces`.
+    /// * **Windows**: there is no public API to pin a window to every virtual desktop, so this
+    ///   approximates it by making the window a topmost tool window.  It stays above other
+    ///   windows and leaves the taskbar and Alt+Tab.
+    /// * **Linux**: Wayland clients can't control which workspaces they appear on; returns
+    ///   [`WindowSettingError::Unsupported`].
+    /// * **iOS, Android, wasm32**: there are no virtual desktops; returns
+    ///   [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// if let Err(e) = window.set_visible_on_all_workspaces(true).await {
+    ///     eprintln!("palette stays on this desktop: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_visible_on_all_workspaces(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        self.sys.set_visible_on_all_workspaces(visible).await
+    }
+
+    /// Hides the window's taskbar button, or shows it again.
+    ///
+    /// This is for auxiliary windows, such as palettes or popups, that shouldn't appear as
+    /// separate entries in the taskbar.  To hide the whole app, see
+    /// [`application::set_activation_policy`](crate::application::set_activation_policy).
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: makes the window a tool window (`WS_EX_TOOLWINDOW`), which also removes
+    ///   it from Alt+Tab.
+    /// * **macOS**: the dock shows apps rather than windows, so this removes the window from
+    ///   the Window menu and from Cmd+\` cycling instead.
+    /// * **Linux**: Wayland leaves the taskbar to the compositor; returns
+    ///   [`WindowSettingError::Unsupported`].
+    /// * **iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(palette: &app_window::window::Window) {
+    /// if let Err(e) = palette.set_skip_taskbar(true).await {
+    ///     eprintln!("palette stays in the taskbar: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_skip_taskbar(&self, skip: bool) -> Result<(), WindowSettingError> {
+        self.sys.set_skip_taskbar(skip).await
+    }
+
+    /// Asks the system to pass its own keyboard shortcuts, such as Alt+Tab or the Windows key,
+    /// to this window instead of acting on them.
+    ///
+    /// This is for fullscreen games and remote desktop clients, which want combinations the
+    /// system would otherwise swallow.  Pass `false` to give the shortcuts back.  Shortcuts
+    /// are only inhibited while the window (on macOS, the app) has keyboard focus.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: uses `zwp_keyboard_shortcuts_inhibit_manager_v1`.  The compositor decides
+    ///   whether to honor it and may ask the user first; compositors without the protocol
+    ///   return [`WindowSettingError::Unsupported`].
+    /// * **Windows**: installs a low-level keyboard hook, only while some window asks for this.
+    ///   It catches the Windows key, Alt+Tab, Alt+Esc and Ctrl+Esc and delivers them to the
+    ///   window as ordinary key events.  Other apps' hotkeys keep working, and Ctrl+Alt+Delete
+    ///   can't be caught at all.
+    /// * **macOS**: sets kiosk presentation options for the app: the Dock and menu bar are
+    ///   hidden and Cmd+Tab is disabled.  Mission Control and Cmd+Option+Esc still work.
+    /// * **wasm32**: uses the Keyboard Lock API, where the browser has it (Chromium).
+    ///   Browsers only honor it in fullscreen.
+    /// * **iOS, Android**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(game: &app_window::window::Window) {
+    /// if let Err(e) = game.inhibit_system_shortcuts(true).await {
+    ///     eprintln!("the system keeps its shortcuts: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn inhibit_system_shortcuts(&self, inhibit: bool) -> Result<(), WindowSettingError> {
+        self.sys.inhibit_system_shortcuts(inhibit).await
+    }
+
+    /// See `Mouse::confine_to`.
+    #[cfg_attr(not(feature = "input"), allow(dead_code))]
+    pub(crate) async fn confine_pointer(
+        &self,
+        region: Option<Rect>,
+    ) -> Result<(), WindowSettingError> {
+        self.sys.confine_pointer(region).await
+    }
+
+    #[cfg_attr(not(feature = "input"), allow(dead_code))]
+    pub(crate) async fn release_pointer(&self) -> Result<(), WindowSettingError> {
+        self.sys.release_pointer().await
+    }
+
+    /// Sets the picture task switchers show for this window, or `None` to go back to the
+    /// platform's own.
+    ///
+    /// Task switchers normally capture a window's content themselves, but for windows drawn
+    /// directly to a GPU [`Surface`] they may only find a blank frame.  Apps can render a
+    /// snapshot of their content and hand it over here; call it again whenever the content
+    /// changes enough to matter.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: supplies iconic thumbnails and live previews through DWM
+    ///   (`DwmSetIconicThumbnail` and `DwmSetIconicLivePreviewBitmap`), as shown by Alt+Tab and
+    ///   the taskbar.
+    /// * **macOS**: becomes the window's `miniwindowImage`, shown in the Dock while the window
+    ///   is minimized.  Mission Control captures the window itself.
+    /// * **Linux, iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::PreviewImage;
+    ///
+    /// //a real app would read back its last frame
+    /// let pixels = [0x20, 0x40, 0x80, 0xFF].repeat(64 * 48);
+    /// let preview = PreviewImage::new(64, 48, pixels).unwrap();
+    /// if let Err(e) = window.set_preview_image(Some(preview)).await {
+    ///     eprintln!("no custom preview: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_preview_image(
+        &self,
+        image: Option<PreviewImage>,
+    ) -> Result<(), WindowSettingError> {
+        self.sys.set_preview_image(image).await
+    }
+
+    /// Sets the window's icon, or `None` to go back to the app's.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: the window's big and small icons (`WM_SETICON`), shown in its title bar,
+    ///   the taskbar, and Alt+Tab.
+    /// * **macOS**: the Dock has one icon per app, so this sets the app's
+    ///   (`NSApplication.applicationIconImage`), whichever window it's called on.
+    /// * **Linux**: `xdg_toplevel_icon_v1`, a staging protocol.  Compositors without it take
+    ///   icons from the app's `.desktop` file, and this returns
+    ///   [`WindowSettingError::Unsupported`].
+    /// * **wasm32**: the page's favicon, a `<link rel="icon">` in its head.
+    /// * **iOS, Android**: icons come from the app bundle; returns
+    ///   [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::IconData;
+    ///
+    /// //a real app would decode its logo
+    /// let pixels = [0xE0, 0x60, 0x20, 0xFF].repeat(64 * 64);
+    /// let icon = IconData::new(64, 64, pixels).unwrap();
+    /// if let Err(e) = window.set_icon(Some(icon)).await {
+    ///     eprintln!("keeping the default icon: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_icon(&self, icon: Option<IconData>) -> Result<(), WindowSettingError> {
+        self.sys.set_icon(icon).await
+    }
+
+    /// Shows a short label over the app's icon, such as an unread count, or `None` to remove
+    /// it.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: the Dock tile's `badgeLabel`.  It belongs to the app, not the window, and
+    ///   an empty label removes it.
+    /// * **Windows, Linux, iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// let unread = 3;
+    /// if let Err(e) = window.set_badge(Some(unread.to_string())).await {
+    ///     eprintln!("no badges here: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_badge(&self, label: Option<String>) -> Result<(), WindowSettingError> {
+        self.sys.set_badge(label).await
+    }
+
+    /// Shows progress, from `0.0` to `1.0`, over the window's taskbar button, or `None` to
+    /// remove it.  Values outside that range are clamped.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: `ITaskbarList3::SetProgressValue`, the green bar across the taskbar
+    ///   button.
+    /// * **macOS, Linux, iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// for done in 0..=10 {
+    ///     //...copy a tenth of the files...
+    ///     _ = window.set_progress(Some(done as f64 / 10.0)).await;
+    /// }
+    /// _ = window.set_progress(None).await;
+    /// # }
+    /// ```
+    pub async fn set_progress(&self, progress: Option<f64>) -> Result<(), WindowSettingError> {
+        self.sys
+            .set_progress(progress.map(|p| p.clamp(0.0, 1.0)))
+            .await
+    }
+
+    /// Sets the mouse cursor shown while the pointer is over the window's content.
+    ///
+    /// The cursor stays until changed, including when the pointer leaves and comes back.
+    /// Over the title bar and borders the system shows its own cursors as usual.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: answers `WM_SETCURSOR` for the client area, so the cursor doesn't flicker
+    ///   back to the class cursor as the pointer moves.
+    /// * **macOS**: a cursor rect covering the content view.  AppKit has no busy cursor for
+    ///   apps, so [`Cursor::Wait`] shows the arrow.
+    /// * **Linux**: loaded from the cursor theme, with the `cursor-theme` feature.  Without it
+    ///   the compositor picks the cursor, and this returns
+    ///   [`WindowSettingError::Unsupported`].
+    /// * **wasm32**: the canvas's CSS `cursor`.
+    /// * **iOS, Android**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(editor: &app_window::window::Window) {
+    /// use app_window::window::Cursor;
+    ///
+    /// if let Err(e) = editor.set_cursor(Cursor::Text).await {
+    ///     eprintln!("keeping the default cursor: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_cursor(&self, cursor: Cursor) -> Result<(), WindowSettingError> {
+        let show = self.cursor.lock().unwrap().choose(cursor);
+        match show {
+            Some(cursor) => self.sys.set_cursor(cursor).await,
+            //shown once the window stops being busy
+            None => Ok(()),
+        }
+    }
+
+    /// Shows the busy cursor over the window's content until the returned guard is dropped.
+    ///
+    /// The previous cursor comes back when the guard goes away, whether the work finished,
+    /// panicked, or its future was cancelled.  [`CursorGuard::restore`] does the same, and waits
+    /// until the cursor is back.  Guards can overlap; the cursor comes back with the last one.
+    /// Cursors set with [`Self::set_cursor`] while busy are shown afterwards.
+    ///
+    /// # Platform behavior
+    ///
+    /// As for [`Self::set_cursor`] with [`Cursor::Wait`].  On macOS that's the arrow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// let busy = window.busy_cursor().await;
+    /// //...load the document...
+    /// if let Ok(busy) = busy {
+    ///     busy.restore().await;
+    /// }
+    /// # }
+    /// ```
+    pub async fn busy_cursor(&self) -> Result<CursorGuard, WindowSettingError> {
+        CursorGuard::begin(self.sys.clone(), self.cursor.clone()).await
+    }
+
+    /// Runs `work` with the busy cursor showing, and puts the previous cursor back afterwards.
+    ///
+    /// Where the platform can't show cursors the work runs all the same.  See
+    /// [`Self::busy_cursor`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// let pages = window
+    ///     .with_busy_cursor(async {
+    ///         //...paginate...
+    ///         42
+    ///     })
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn with_busy_cursor<R>(&self, work: impl Future<Output = R>) -> R {
+        let busy = self.busy_cursor().await;
+        let result = work.await;
+        if let Ok(busy) = busy {
+            busy.restore().await;
+        }
+        result
+    }
+
+    /// Shows or hides the on-screen keyboard, for typing into this window on touch devices.
+    ///
+    /// Apps that draw their own text fields call this as a field gains or loses focus.  Whether
+    /// one is needed can be guessed from
+    /// `app_window::input::keyboard::hardware_keyboard_present`.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: starts the touch keyboard (`TabTip.exe`), or closes it.  Windows shows it
+    ///   whether or not the window is focused.
+    /// * **iOS**: an invisible view that takes text becomes first responder.  Hardware key
+    ///   events still arrive as usual, but the keyboard's typing isn't reported yet.
+    /// * **Linux**: the compositor shows its on-screen keyboard, if it has one, while the
+    ///   window's `zwp_text_input_v3` is enabled, which it is by default while the window has
+    ///   focus.  Hiding it disables the text input, so input method text stops too until it's
+    ///   shown again.  Without the `input` feature, or where the compositor doesn't offer
+    ///   text input, this returns [`WindowSettingError::Unsupported`].
+    /// * **wasm32**: focuses an invisible `<input>`, since mobile browsers show the keyboard
+    ///   while an editable element has focus.  Browsers only do that for focus that comes from
+    ///   a user gesture, so call this from an input handler.
+    /// * **macOS, Android**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// //the user tapped a text field
+    /// if let Err(e) = window.set_virtual_keyboard_visible(true).await {
+    ///     eprintln!("no on-screen keyboard: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_virtual_keyboard_visible(
+        &self,
+        visible: bool,
+    ) -> Result<(), WindowSettingError> {
+        self.sys.set_virtual_keyboard_visible(visible).await
+    }
+
+    /// Sets how wide the window's resize borders are, in logical pixels; the default is
+    /// [`DEFAULT_RESIZE_BORDER`].  `0.0` leaves only the compositor's own ways to resize.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: while we draw the window's frame, dragging within this distance of an edge
+    ///   resizes the window.  See [`MouseRegion`].  Frames the compositor draws (see
+    ///   [`Self::set_decorations`]) have borders of their own.
+    /// * **Windows, macOS, iOS, Android, wasm32**: the system draws the frame and decides;
+    ///   returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// //a touch-friendly border
+    /// if let Err(e) = window.set_resize_border(20.0).await {
+    ///     eprintln!("the system decides: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_resize_border(&self, width: f64) -> Result<(), WindowSettingError> {
+        self.sys.set_resize_border(width).await
+    }
+
+    /// Chooses who draws the window's title bar and borders.
+    ///
+    /// [`Self::debug_state`] reports who ends up drawing them.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: new windows ask the compositor to draw them (`xdg-decoration`), and draw
+    ///   our own when it won't, as on GNOME.  [`DecorationsMode::Native`] returns
+    ///   [`WindowSettingError::Unsupported`] where the compositor doesn't offer that.  The
+    ///   compositor can still insist on its own; if it does, it draws them.
+    ///   [`DecorationsMode::ClientSide`] needs the `decorations` feature.
+    /// * **Windows, macOS, iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::DecorationsMode;
+    ///
+    /// //a game that draws its own frame
+    /// if let Err(e) = window.set_decorations(DecorationsMode::None).await {
+    ///     eprintln!("keeping the title bar: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_decorations(&self, mode: DecorationsMode) -> Result<(), WindowSettingError> {
+        self.sys.set_decorations(mode).await
+    }
+
+    /// Chooses how app_window draws the title bar buttons, where it draws them itself.
+    ///
+    /// The theme sets the title bar's height, the buttons' size and colors, or an image to
+    /// draw instead.  It applies whenever the window has [`DecorationsMode::ClientSide`]
+    /// decorations, including ones it falls back on later.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: the buttons are drawn at the window's scale, and redrawn when it changes.
+    ///   Needs the `decorations` feature.
+    /// * **Windows, macOS, iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::DecorationTheme;
+    ///
+    /// let mut theme = DecorationTheme::default();
+    /// theme.height = 32.0;
+    /// theme.button_width = 40.0;
+    /// if let Err(e) = window.set_decoration_theme(theme).await {
+    ///     eprintln!("the system draws the title bar: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn set_decoration_theme(
+        &self,
+        theme: DecorationTheme,
+    ) -> Result<(), WindowSettingError> {
+        self.sys.set_decoration_theme(theme).await
+    }
+
+    /// Shows `sheet` as a modal sheet attached to this window, and finishes once it's dismissed.
+    ///
+    /// Sheets suit settings panels and export dialogs: they belong to one window, which takes
+    /// no input while the sheet is up.  The sheet is dismissed by hiding it with
+    /// [`Window::set_visible`] (say, from its OK button) or by closing it; hiding leaves it
+    /// ready to show as a sheet again.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: `NSWindow.beginSheet`, so the sheet slides out of the window's title bar.
+    /// * **Windows**: emulated.  The sheet becomes an owned window centered over this one,
+    ///   which is disabled until the sheet is dismissed, as for a modal dialog.
+    /// * **Linux**: the sheet's toplevel gets this one as its parent, and is made modal with
+    ///   `xdg_dialog_v1` where the compositor offers it; elsewhere this window still takes
+    ///   input.  Compositors place the sheet themselves, usually centered over its parent.
+    /// * **iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(document: &app_window::window::Window) {
+    /// use app_window::coordinates::{Position, Size};
+    /// use app_window::window::Window;
+    ///
+    /// let position = Position::new(0.0, 0.0);
+    /// let settings = Window::new(position, Size::new(400.0, 300.0), "Settings".to_string()).await;
+    /// //the settings' OK button hides them, which ends the sheet
+    /// if let Err(e) = document.begin_sheet(&settings).await {
+    ///     eprintln!("no sheets here: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn begin_sheet(&self, sheet: &Window) -> Result<(), WindowSettingError> {
+        self.sys.begin_sheet(&sheet.sys).await
+    }
+
+    /// Publishes the accessibility tree for what the app draws in the window, or changes to it.
+    ///
+    /// Apps that draw their own UI, say with wgpu, use this so screen readers can see it.  The
+    /// tree follows [`accesskit`]'s rules: the first update needs
+    /// [`tree`](accesskit::TreeUpdate::tree), and later ones only the nodes that changed.  Use
+    /// [`TreeId::ROOT`](accesskit::TreeId::ROOT); the window puts the tree under its own node,
+    /// alongside anything it publishes itself.  Bounds are in logical pixels from the top-left
+    /// of the content area.  Handle what assistive technology asks for with
+    /// [`Window::on_accessibility_action`].
+    ///
+    /// Can be called from any thread, and is cheap to call when nobody's listening.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: published over AT-SPI with `accesskit_unix`, next to the title bar buttons
+    ///   we draw.
+    /// * **Windows**: published over UI Automation with `accesskit_windows`.  Until the first
+    ///   update, the window keeps the tree Windows gives it.
+    /// * **macOS**: published over NSAccessibility with `accesskit_macos`, as the content
+    ///   view's children.
+    /// * **iOS, Android, wasm32**: returns [`WindowSettingError::Unsupported`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::accesskit::{Node, NodeId, Role, Tree, TreeId, TreeUpdate};
+    ///
+    /// let mut button = Node::new(Role::Button);
+    /// button.set_label("Play");
+    /// let mut root = Node::new(Role::Group);
+    /// root.set_children(vec![NodeId(2)]);
+    /// let update = TreeUpdate {
+    ///     nodes: vec![(NodeId(1), root), (NodeId(2), button)],
+    ///     tree: Some(Tree::new(NodeId(1))),
+    ///     tree_id: TreeId::ROOT,
+    ///     focus: NodeId(2),
+    /// };
+    /// if let Err(e) = window.update_accessibility_tree(update).await {
+    ///     eprintln!("no accessibility tree: {e}");
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "accessibility")]
+    pub async fn update_accessibility_tree(
+        &self,
+        update: accesskit::TreeUpdate,
+    ) -> Result<(), WindowSettingError> {
+        self.sys.update_accessibility_tree(update).await
+    }
+
+    /// Sets what runs when assistive technology acts on the tree from
+    /// [`Window::update_accessibility_tree`], such as a screen reader clicking a button.
+    /// Replaces any earlier handler.
+    ///
+    /// Requests are addressed to [`TreeId::ROOT`](accesskit::TreeId::ROOT).  The handler may
+    /// run on any thread.
+    ///
+    /// # Platform behavior
+    ///
+    /// As for [`Window::update_accessibility_tree`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::accesskit::{Action, NodeId};
+    ///
+    /// let result = window
+    ///     .on_accessibility_action(|request| {
+    ///         if request.action == Action::Click && request.target_node == NodeId(2) {
+    ///             //play
+    ///         }
+    ///     })
+    ///     .await;
+    /// if let Err(e) = result {
+    ///     eprintln!("no accessibility actions: {e}");
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "accessibility")]
+    pub async fn on_accessibility_action<F: FnMut(accesskit::ActionRequest) + Send + 'static>(
+        &self,
+        f: F,
+    ) -> Result<(), WindowSettingError> {
+        self.sys.set_accessibility_action_handler(Box::new(f)).await
+    }
+
+    /// Returns the platform's own object for this window, for platform features app_window
+    /// doesn't wrap, such as menus or taskbar APIs.
+    ///
+    /// This is unsupported territory: app_window doesn't know what you do with the handle, and
+    /// may undo it or be confused by it.  See [`NativeHandle`] for how long the handle lives and
+    /// where it may be used.
+    ///
+    /// # Platform behavior
+    ///
+    /// | Platform | Handle |
+    /// |----------|--------|
+    /// | macOS | [`NativeHandle::AppKit`] |
+    /// | iOS | [`NativeHandle::UiKit`], or `None` before the scene connects |
+    /// | Windows | [`NativeHandle::Win32`] |
+    /// | Linux | [`NativeHandle::Wayland`], or `None` while reconnecting to the compositor |
+    /// | Web | [`NativeHandle::Web`], or `None` for a virtual window without a DOM |
+    /// | Android | `None` |
+    ///
+    /// Plugin backends return `None` unless they provide one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::NativeHandle;
+    ///
+    /// match window.native_handle().await {
+    ///     Some(NativeHandle::Win32 { hwnd }) => println!("HWND {hwnd}"),
+    ///     Some(other) => println!("{other:?}"),
+    ///     None => println!("no native handle"),
+    /// }
+    /// # }
+    /// ```
+    pub async fn native_handle(&self) -> Option<NativeHandle> {
+        self.sys.native_handle().await
+    }
+
+    /// Returns a [`WindowBuilder`], for setting options that [`Window::new`] doesn't take.
+    pub fn builder() -> WindowBuilder {
+        WindowBuilder::new()
+    }
+
+    /// Returns a snapshot of the window's internal state, for bug reports.
+    ///
+    /// The snapshot is what the backend currently believes about the window: its configured
+    /// size, scale, outputs, focus, and so on.  Fields a backend doesn't track are `None`.
+    /// With the `serde` feature, [`WindowDebugState`] implements `Serialize`, so it can be
+    /// attached to a report as JSON.
+    ///
+    /// The format is for humans and may change between releases; don't make decisions based
+    /// on it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// let state = window.debug_state().await;
+    /// eprintln!("window state: {state:#?}");
+    /// # }
+    /// ```
+    pub async fn debug_state(&self) -> WindowDebugState {
+        self.sys.debug_state().await
+    }
+
+    /// Returns the window's size, scale, state, focus and outputs, all as of the same moment.
+    ///
+    /// Reading [`Window::scale_factor`] and then [`Surface::size_scale`] can straddle a
+    /// configure from the window system and pair the old size with the new scale.  Every field
+    /// here comes from one read, made where the backend applies configures (under the window's
+    /// lock on Linux, on the main thread elsewhere), so a render thread can build a frame's
+    /// uniforms from it without torn values.  [`Window::set_scale_override`] applies.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: Wayland never says a window is minimized, so [`WindowState::Minimized`]
+    ///   isn't reported.  `outputs` are the `wl_output`s the surface has entered.
+    /// * **Windows, macOS**: `outputs` is the one monitor the window is mostly on.
+    /// * **iOS, Android, wasm32**: `outputs` is empty.  On wasm32 the state is
+    ///   [`WindowState::Fullscreen`] while the page is fullscreen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// let snapshot = window.snapshot().await;
+    /// let physical_width = snapshot.size.width() * snapshot.scale;
+    /// println!("{physical_width} pixels wide, {:?}", snapshot.state);
+    /// # }
+    /// ```
+    pub async fn snapshot(&self) -> WindowSnapshot {
+        let mut snapshot = WindowSnapshot::from(self.sys.debug_state().await);
+        if let Some(scale) = self.scale_override.get() {
+            snapshot.scale = scale;
+        }
+        snapshot
+    }
+
+    /// Returns the window's size, position and state, to restore with
+    /// [`WindowBuilder::geometry`] next launch.
+    ///
+    /// The size and position are the ones the window restores to, so a maximized or fullscreen
+    /// window saves the layout it had before, and comes back maximized or fullscreen over it.
+    /// See [`WindowGeometry`] for the coordinates.
+    ///
+    /// With the `serde` feature, [`WindowGeometry`] implements `Serialize` and `Deserialize`,
+    /// so it can be saved with the app's settings.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: the placement the window restores to (`GetWindowPlacement`).
+    /// * **macOS**: the frame from before the window was zoomed or went fullscreen.
+    /// * **Linux**: Wayland doesn't tell clients where their windows are, so `position` is
+    ///   `None` and the compositor places the restored window.  The size is the last one the
+    ///   window had while neither maximized nor fullscreen.
+    /// * **iOS, Android, wasm32**: windows fill what they can, so only `fullscreen` (on wasm32)
+    ///   is worth restoring.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(window: &app_window::window::Window) {
+    /// use app_window::window::WindowBuilder;
+    ///
+    /// let geometry = window.geometry().await;
+    /// //...save it, and next launch:
+    /// let restored = WindowBuilder::new().geometry(geometry).build().await;
+    /// # }
+    /// ```
+    pub async fn geometry(&self) -> WindowGeometry {
+        let snapshot = self.snapshot().await;
+        let (position, size) = match self.sys.normal_bounds().await {
+            Some(bounds) => bounds,
+            None => {
+                //minimized windows are parked somewhere off screen
+                let position = match snapshot.state {
+                    WindowState::Minimized => None,
+                    _ => self.outer_position().await,
+                };
+                (position, snapshot.size)
+            }
+        };
+        WindowGeometry {
+            size,
+            position,
+            maximized: snapshot.state == WindowState::Maximized,
+            fullscreen: snapshot.state == WindowState::Fullscreen,
+        }
+    }
+
+    /// Creates a new window with platform-appropriate default settings.
+    ///
+    /// This is the simplest way to create a window. The platform will choose
+    /// reasonable defaults for position, size, and other properties:
+    ///
+    /// - **Position**: Typically centered or cascaded
+    /// - **Size**: A reasonable default (often 800x600 or similar)
+    /// - **Title**: Platform-specific default or empty
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[cfg(target_arch = "wasm32")] {
+    ///     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+    /// }
+    /// use app_window::test_support::doctest_main;
+    /// use some_executor::task::{Configuration, Task};
+    ///
+    /// doctest_main(|| {
+    ///     Task::without_notifications(
+    ///         "doctest".to_string(),
+    ///         Configuration::default(),
+    ///         async {
+    ///             let _window = app_window::window::Window::default().await;
+    ///             println!("Window created with default settings");
+    ///             // Window closes when dropped
+    ///         },
+    ///     ).spawn_static_current();
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`application::main()`](crate::application::main) has not been called.
+    pub async fn default() -> Self {
+        assert!(
+            crate::application::is_main_thread_running(),
+            "Call app_window::application::run_main_thread"
+        );
+        Window::from_sys(crate::sys::Window::default().await).await
+    }
+}
+
+/// Which of the browser's own responses to input on the canvas to suppress.
+///
+/// By default a right-click on the canvas opens the browser's context menu, dragging across it
+/// selects the page around it, and touch drags scroll the page.  Games usually want none of
+/// that.  Everything is off by default.
+///
+/// Set this with [`WindowBuilder::suppress_context_menu`] and friends, or change it later with
+/// [`Window::set_canvas_behavior`].
+///
+/// # Platform behavior
+///
+/// Only wasm32 has a browser to suppress; elsewhere this is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CanvasBehavior {
+    suppress_context_menu: bool,
+    prevent_text_selection: bool,
+    prevent_touch_scroll: bool,
+}
+
+impl CanvasBehavior {
+    /// Leaves the browser's behavior alone.
+    pub const fn new() -> Self {
+        CanvasBehavior {
+            suppress_context_menu: false,
+            prevent_text_selection: false,
+            prevent_touch_scroll: false,
+        }
+    }
+
+    /// Stops right-clicks (and long presses) on the canvas from opening the context menu.
+    pub const fn suppress_context_menu(mut self, suppress: bool) -> Self {
+        self.suppress_context_menu = suppress;
+        self
+    }
+
+    /// Stops drags that start on the canvas from selecting text on the page.
+    pub const fn prevent_text_selection(mut self, prevent: bool) -> Self {
+        self.prevent_text_selection = prevent;
+        self
+    }
+
+    /// Stops touch drags on the canvas from scrolling or zooming the page.
+    pub const fn prevent_touch_scroll(mut self, prevent: bool) -> Self {
+        self.prevent_touch_scroll = prevent;
+        self
+    }
+
+    /// Whether the context menu is suppressed.
+    pub const fn suppresses_context_menu(self) -> bool {
+        self.suppress_context_menu
+    }
+
+    /// Whether text selection is prevented.
+    pub const fn prevents_text_selection(self) -> bool {
+        self.prevent_text_selection
+    }
+
+    /// Whether touch scrolling is prevented.
+    pub const fn prevents_touch_scroll(self) -> bool {
+        self.prevent_touch_scroll
+    }
+}
+
+/// The mouse cursor shown over a window's content.  See [`Window::set_cursor`].
+///
+/// These are the platform's own cursors, so they match the rest of the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum Cursor {
+    /// The ordinary pointer.  This is the default.
+    #[default]
+    Arrow,
+    /// An I-beam, for text that can be selected or edited.
+    Text,
+    /// A pointing hand, for links and buttons.
+    Hand,
+    /// The busy cursor.
+    Wait,
+    /// Crosshairs, for precise selection.
+    Crosshair,
+    /// The action isn't allowed here.
+    NotAllowed,
+    /// Resizing left and right.
+    ResizeHorizontal,
+    /// Resizing up and down.
+    ResizeVertical,
+    /// No cursor at all.
+    Hidden,
+}
+
+/// What happens when the user asks to close a window.
+///
+/// "Asking to close" means clicking the title bar's close button, pressing the platform's close
+/// shortcut (Alt+F4, Cmd+W), or choosing Close from the window menu.  Dropping a [`Window`]
+/// always closes it regardless of this setting.
+///
+/// Set this with [`WindowBuilder::close_behavior`].
+///
+/// # Platform behavior
+///
+/// On wasm32 there is no close button, so the behavior is never triggered, except that
+/// [`CloseBehavior::Ask`] runs when the page is about to unload.
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum CloseBehavior {
+    /// Close the window.  This is the default.
+    ///
+    /// The underlying platform window goes away, but the [`Window`] value stays alive until
+    /// you drop it.
+    #[default]
+    Destroy,
+    /// Hide the window instead of closing it.
+    ///
+    /// Intended for apps that keep running with a tray or status icon.  Bring the window back
+    /// with [`Window::set_visible`].
+    HideToTray,
+    /// Leave the window open and call this function instead.
+    ///
+    /// The function runs on the main thread.  It's up to you to hide or drop the window.
+    Custom(Arc<dyn Fn() + Send + Sync>),
+    /// Call this function, and close the window if it returns [`CloseResponse::Close`].
+    ///
+    /// The function runs on the main thread.  See [`Window::on_close_requested`].
+    Ask(Arc<dyn Fn() -> CloseResponse + Send + Sync>),
+}
+
+/// Whether to go ahead with closing a window; see [`Window::on_close_requested`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CloseResponse {
+    /// Close the window.
+    Close,
+    /// Leave the window open.
+    Keep,
+}
+
+impl std::fmt::Debug for CloseBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseBehavior::Destroy => write!(f, "Destroy"),
+            CloseBehavior::HideToTray => write!(f, "HideToTray"),
+            CloseBehavior::Custom(_) => write!(f, "Custom(..)"),
+            CloseBehavior::Ask(_) => write!(f, "Ask(..)"),
+        }
+    }
+}
+
+/// A snapshot of a window's internal state.  See [`Window::debug_state`].
+///
+/// Sizes are in logical pixels.  Fields are `None` when the backend doesn't track them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct WindowDebugState {
+    /// The backend that produced the snapshot, e.g. `"wayland"` or `"win32"`.
+    pub backend: &'static str,
+    /// The size the window is currently drawn at.
+    pub applied_size: Option<Size>,
+    /// A size the window system asked for that hasn't been applied yet.
+    pub proposed_size: Option<Size>,
+    /// The scale factor buffers are drawn at.
+    pub scale: Option<f64>,
+    /// The scale factor the window system prefers, if it said so directly.
+    pub preferred_scale: Option<f64>,
+    /// Identifiers of the outputs (monitors) the window is on.  An identifier lasts while its
+    /// output stays connected; see [`Window::fullscreen_on`].
+    pub outputs: Vec<u32>,
+    /// Whether the window has keyboard focus.
+    pub focused: Option<bool>,
+    /// Whether the window is shown.  See [`Window::set_visible`].
+    pub visible: Option<bool>,
+    /// Whether the window system has configured the window at least once.
+    pub configured: Option<bool>,
+    /// Who draws the window's title bar and borders.
+    pub decorations: Option<DecorationsMode>,
+    /// The state of the placeholder buffer app_window draws before the app presents.
+    pub buffer: Option<BufferStatus>,
+    /// The cursor shown over the window's content.  See [`Window::set_cursor`].
+    pub cursor: Option<Cursor>,
+    /// Whether the window is maximized, minimized or fullscreen.
+    pub state: Option<WindowState>,
+}
+
+impl WindowDebugState {
+    /// Creates a snapshot for `backend` with every other field unknown.
+    ///
+    /// Out-of-tree backends start from this and fill in what they track.
+    pub fn new(backend: &'static str) -> Self {
+        WindowDebugState {
+            backend,
+            applied_size: None,
+            proposed_size: None,
+            scale: None,
+            preferred_scale: None,
+            outputs: Vec::new(),
+            focused: None,
+            visible: None,
+            configured: None,
+            decorations: None,
+            buffer: None,
+            cursor: None,
+            state: None,
+        }
+    }
+}
+
+/// Whether a window is maximized, minimized or fullscreen.  See [`Window::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum WindowState {
+    /// None of the others.
+    #[default]
+    Normal,
+    /// Filling the screen, apart from the system's panels.
+    Maximized,
+    /// Minimized to the taskbar or dock.
+    Minimized,
+    /// Covering the whole screen.
+    Fullscreen,
+}
+
+/// A window's size, scale, state, focus and outputs as of one moment.  See
+/// [`Window::snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct WindowSnapshot {
+    /// The content area's size in logical pixels, or zero before the window system has sized
+    /// the window.
+    pub size: Size,
+    /// How many physical pixels make up one logical pixel.
+    pub scale: f64,
+    /// Whether the window is maximized, minimized or fullscreen.
+    pub state: WindowState,
+    /// Whether the window has keyboard focus.
+    pub focused: bool,
+    /// Identifiers of the outputs (monitors) the window is on, in no particular order.  An
+    /// identifier lasts while its output stays connected; see [`Window::fullscreen_on`].
+    pub outputs: Vec<u32>,
+}
+
+/// Fills in what the backend didn't track: zero size, a scale of 1, [`WindowState::Normal`]
+/// and no focus.
+impl From<WindowDebugState> for WindowSnapshot {
+    fn from(state: WindowDebugState) -> Self {
+        WindowSnapshot {
+            size: state
+                .applied_size
+                .or(state.proposed_size)
+                .unwrap_or(Size::new(0.0, 0.0)),
+            scale: state.scale.unwrap_or(1.0),
+            state: state.state.unwrap_or_default(),
+            focused: state.focused.unwrap_or(false),
+            outputs: state.outputs,
+        }
+    }
+}
+
+/// A window's size, position and state, for restoring its layout between launches.  See
+/// [`Window::geometry`] and [`WindowBuilder::geometry`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct WindowGeometry {
+    /// The size the window restores to, in logical pixels, measured as [`Window::new`] takes
+    /// it: the content area, except on Windows, where it's the frame's outer size.
+    pub size: Size,
+    /// Where the frame's top-left corner goes when the window restores, if the platform says,
+    /// in the coordinates of [`Window::outer_position`].
+    pub position: Option<Position>,
+    /// Whether the window was maximized.
+    pub maximized: bool,
+    /// Whether the window was fullscreen.
+    pub fullscreen: bool,
+}
+
+impl WindowGeometry {
+    /// Creates geometry for an ordinary window of `size`, placed by the platform.
+    pub fn new(size: Size) -> Self {
+        WindowGeometry {
+            size,
+            position: None,
+            maximized: false,
+            fullscreen: false,
+        }
+    }
+}
+
+/// Who draws a window's title bar and borders.  See [`Window::set_decorations`] and
+/// [`WindowDebugState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum DecorationsMode {
+    /// The window system draws them.
+    Native,
+    /// app_window draws them itself.
+    ClientSide,
+    /// The window has none.
+    None,
+}
+
+/// The state of a window's placeholder buffer.  See [`WindowDebugState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum BufferStatus {
+    /// app_window holds an unattached buffer of this size, in physical pixels.
+    Held {
+        /// Width in physical pixels.
+        width: u32,
+        /// Height in physical pixels.
+        height: u32,
+    },
+    /// app_window holds no buffer: the window system is using it, or it was never allocated.
+    NotHeld,
+}
+
+/// Options that [`WindowBuilder`]s fall back on, set app-wide with
+/// [`application::set_window_defaults`](crate::application::set_window_defaults) or for a
+/// class of windows with
+/// [`application::set_window_class_defaults`](crate::application::set_window_class_defaults).
+///
+/// Each option is inherited separately.  A window takes an option from its builder if it was
+/// set there, then from its class's defaults, then from the app's defaults, then from
+/// [`Window::default`].  The canvas behavior options are one option for this purpose: setting
+/// any of them on a builder replaces the whole [`CanvasBehavior`].
+///
+/// Defaults are read when a window is built, so changing them doesn't affect windows that
+/// already exist.
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::application;
+/// use app_window::coordinates::Size;
+/// use app_window::window::{CloseBehavior, WindowBuilder, WindowDefaults};
+///
+/// application::set_window_defaults(WindowDefaults::new().title("Sketch"));
+/// application::set_window_class_defaults(
+///     "palette",
+///     WindowDefaults::new()
+///         .size(Size::new(240.0, 480.0))
+///         .close_behavior(CloseBehavior::HideToTray),
+/// );
+/// //titled "Sketch", 240x480, hides when closed
+/// let colors = WindowBuilder::new().class("palette").build().await;
+/// //titled "Brushes", otherwise the same
+/// let brushes = WindowBuilder::new().class("palette").title("Brushes").build().await;
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WindowDefaults {
+    size: Option<Size>,
+    title: Option<String>,
+    close_behavior: Option<CloseBehavior>,
+    canvas_behavior: Option<CanvasBehavior>,
+    decorations: Option<bool>,
+    resizable: Option<bool>,
+    transparent: Option<bool>,
+    always_on_top: Option<bool>,
+    min_size: Option<Size>,
+    max_size: Option<Size>,
+    click_through: Option<bool>,
+    icon: Option<IconData>,
+    background: Option<Color>,
+}
+
+impl WindowDefaults {
+    /// Defaults that set nothing, so every option is inherited.
+    pub const fn new() -> Self {
+        WindowDefaults {
+            size: None,
+            title: None,
+            close_behavior: None,
+            canvas_behavior: None,
+            decorations: None,
+            resizable: None,
+            transparent: None,
+            always_on_top: None,
+            min_size: None,
+            max_size: None,
+            click_through: None,
+            icon: None,
+            background: None,
+        }
+    }
+
+    /// Sets the initial size, in logical pixels.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Sets the title.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets what happens when the user asks to close the window.  See [`CloseBehavior`].
+    pub fn close_behavior(mut self, close_behavior: CloseBehavior) -> Self {
+        self.close_behavior = Some(close_behavior);
+        self
+    }
+
+    /// Sets which of the browser's responses to input on the canvas to suppress.  See
+    /// [`CanvasBehavior`].
+    pub fn canvas_behavior(mut self, canvas_behavior: CanvasBehavior) -> Self {
+        self.canvas_behavior = Some(canvas_behavior);
+        self
+    }
+
+    /// Sets whether the window has a title bar and borders.  See
+    /// [`WindowBuilder::decorations`].
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.decorations = Some(decorations);
+        self
+    }
+
+    /// Sets whether the user can resize the window.  See [`WindowBuilder::resizable`].
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = Some(resizable);
+        self
+    }
+
+    /// Sets whether what's behind the window shows through where it draws transparent
+    /// pixels.  See [`WindowBuilder::transparent`].
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = Some(transparent);
+        self
+    }
+
+    /// Sets whether the window floats above other apps' windows.  See
+    /// [`WindowBuilder::always_on_top`].
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = Some(always_on_top);
+        self
+    }
+
+    /// Sets the smallest size the user can resize the window to.  See
+    /// [`WindowBuilder::min_size`].
+    pub fn min_size(mut self, size: Size) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    /// Sets the largest size the user can resize the window to.  See
+    /// [`WindowBuilder::max_size`].
+    pub fn max_size(mut self, size: Size) -> Self {
+        self.max_size = Some(size);
+        self
+    }
+
+    /// Sets whether mouse and touch input pass through the window.  See
+    /// [`WindowBuilder::click_through`].
+    pub fn click_through(mut self, click_through: bool) -> Self {
+        self.click_through = Some(click_through);
+        self
+    }
+
+    /// Sets the window's icon.  See [`WindowBuilder::icon`].
+    pub fn icon(mut self, icon: IconData) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Sets the color the window shows until the app presents a frame.  See
+    /// [`WindowBuilder::background`].
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// These options, with any that aren't set taken from `parent`.
+    fn inherit(self, parent: &WindowDefaults) -> Self {
+        WindowDefaults {
+            size: self.size.or(parent.size),
+            title: self.title.or_else(|| parent.title.clone()),
+            close_behavior: self
+                .close_behavior
+                .or_else(|| parent.close_behavior.clone()),
+            canvas_behavior: self.canvas_behavior.or(parent.canvas_behavior),
+            decorations: self.decorations.or(parent.decorations),
+            resizable: self.resizable.or(parent.resizable),
+            transparent: self.transparent.or(parent.transparent),
+            always_on_top: self.always_on_top.or(parent.always_on_top),
+            min_size: self.min_size.or(parent.min_size),
+            max_size: self.max_size.or(parent.max_size),
+            click_through: self.click_through.or(parent.click_through),
+            icon: self.icon.or_else(|| parent.icon.clone()),
+            background: self.background.or(parent.background),
+        }
+    }
+}
+
+/// The app's defaults, and each class's.
+#[derive(Debug)]
+struct DefaultsRegistry {
+    app: WindowDefaults,
+    classes: BTreeMap<String, WindowDefaults>,
+}
+
+static WINDOW_DEFAULTS: Mutex<DefaultsRegistry> = Mutex::new(DefaultsRegistry {
+    app: WindowDefaults::new(),
+    classes: BTreeMap::new(),
+});
+
+pub(crate) fn set_window_defaults(defaults: WindowDefaults) {
+    WINDOW_DEFAULTS.lock().unwrap().app = defaults;
+}
+
+pub(crate) fn set_window_class_defaults(class: String, defaults: WindowDefaults) {
+    WINDOW_DEFAULTS
+        .lock()
+        .unwrap()
+        .classes
+        .insert(class, defaults);
+}
+
+/// Configures a [`Window`] before creating it.
+///
+/// `WindowBuilder` is the way to set options that [`Window::new`] doesn't take.  Unset
+/// options come from the [`WindowDefaults`] for the builder's [`class`](Self::class) and the
+/// app, and otherwise keep the same defaults as [`Window::default`].
+///
+/// # Example
+///
+/// ```
+/// # async fn example() {
+/// use app_window::coordinates::Size;
+/// use app_window::window::{CloseBehavior, WindowBuilder};
+///
+/// let window = WindowBuilder::new()
+///     .title("Tray App")
+///     .size(Size::new(400.0, 300.0))
+///     .close_behavior(CloseBehavior::HideToTray)
+///     .build()
+///     .await;
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowBuilder {
+    position: Position,
+    /// Only the options set on this builder; see [`WindowBuilder::resolve`].
+    options: WindowDefaults,
+    class: Option<String>,
+    from_pool: bool,
+    parent: Option<Arc<crate::sys::Window>>,
+    /// Shown as a sheet on `parent`; see [`WindowBuilder::modal_to`].
+    modal: bool,
+    canvas_selector: Option<String>,
+    visible: bool,
+    fullscreen: bool,
+    maximized: bool,
+}
+
+/// How [`WindowBuilder::build`] asks a backend to create a window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CreateOptions {
+    pub decorated: bool,
+    pub visible: bool,
+    /// wasm32: the page's canvas to use; see [`WindowBuilder::canvas_selector`].
+    pub canvas_selector: Option<String>,
+}
+
+/// A builder's options, with unset ones inherited; see [`WindowBuilder::resolve`].
+#[derive(Debug)]
+struct ResolvedOptions {
+    size: Size,
+    title: String,
+    close_behavior: CloseBehavior,
+    canvas_behavior: CanvasBehavior,
+    decorations: bool,
+    resizable: bool,
+    transparent: bool,
+    always_on_top: bool,
+    min_size: Option<Size>,
+    max_size: Option<Size>,
+    click_through: bool,
+    icon: Option<IconData>,
+    background: Option<Color>,
+}
+
+/// A hidden window created ahead of time by [`WindowBuilder::prewarm`].
+#[derive(Debug)]
+struct PooledWindow {
+    position: Position,
+    size: Size,
+    sys: crate::sys::Window,
+}
+
+static WINDOW_POOL: Mutex<Vec<PooledWindow>> = Mutex::new(Vec::new());
+
+impl Default for WindowBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WindowBuilder {
+    /// Creates a builder with default options.
+    pub fn new() -> Self {
+        WindowBuilder {
+            position: Position::new(0.0, 0.0),
+            options: WindowDefaults::new(),
+            class: None,
+            from_pool: false,
+            parent: None,
+            modal: false,
+            canvas_selector: None,
+            visible: true,
+            fullscreen: false,
+            maximized: false,
+        }
+    }
+
+    /// Makes the window one of a class, so it takes unset options from the defaults set with
+    /// [`application::set_window_class_defaults`](crate::application::set_window_class_defaults)
+    /// before the app's.  A class without defaults is the same as no class.
+    pub fn class(mut self, class: impl Into<String>) -> Self {
+        self.class = Some(class.into());
+        self
+    }
+
+    /// This builder's options, with unset ones inherited as described on [`WindowDefaults`].
+    fn resolve(&self) -> ResolvedOptions {
+        let registry = WINDOW_DEFAULTS.lock().unwrap();
+        let class = self
+            .class
+            .as_ref()
+            .and_then(|class| registry.classes.get(class))
+            .cloned()
+            .unwrap_or_default();
+        let options = self.options.clone().inherit(&class.inherit(&registry.app));
+        ResolvedOptions {
+            size: options.size.unwrap_or(Size::new(800.0, 600.0)),
+            title: options.title.unwrap_or_else(|| "app_window".to_string()),
+            close_behavior: options.close_behavior.unwrap_or_default(),
+            canvas_behavior: options.canvas_behavior.unwrap_or_default(),
+            decorations: options.decorations.unwrap_or(true),
+            resizable: options.resizable.unwrap_or(true),
+            transparent: options.transparent.unwrap_or(false),
+            always_on_top: options.always_on_top.unwrap_or(false),
+            min_size: options.min_size,
+            max_size: options.max_size,
+            click_through: options.click_through.unwrap_or(false),
+            icon: options.icon,
+            background: options.background,
+        }
+    }
+
+    /// Sets the initial position of the window in screen coordinates.
+    pub fn position(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Sets the initial size of the window in logical pixels.
+    pub fn size(mut self, size: Size) -> Self {
+        self.options.size = Some(size);
+        self
+    }
+
+    /// Sets the window title.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.options.title = Some(title.into());
+        self
+    }
+
+    /// Sets what happens when the user asks to close the window.  See [`CloseBehavior`].
+    pub fn close_behavior(mut self, close_behavior: CloseBehavior) -> Self {
+        self.options.close_behavior = Some(close_behavior);
+        self
+    }
+
+    fn canvas_behavior(&mut self) -> &mut CanvasBehavior {
+        self.options
+            .canvas_behavior
+            .get_or_insert_with(CanvasBehavior::new)
+    }
+
+    /// Stops right-clicks on the canvas from opening the browser's context menu.  See
+    /// [`CanvasBehavior`].
+    pub fn suppress_context_menu(mut self, suppress: bool) -> Self {
+        let behavior = self.canvas_behavior();
+        *behavior = behavior.suppress_context_menu(suppress);
+        self
+    }
+
+    /// Stops drags on the canvas from selecting the page around it.  See [`CanvasBehavior`].
+    pub fn prevent_text_selection(mut self, prevent: bool) -> Self {
+        let behavior = self.canvas_behavior();
+        *behavior = behavior.prevent_text_selection(prevent);
+        self
+    }
+
+    /// Stops touch drags on the canvas from scrolling the page.  See [`CanvasBehavior`].
+    pub fn prevent_touch_scroll(mut self, prevent: bool) -> Self {
+        let behavior = self.canvas_behavior();
+        *behavior = behavior.prevent_touch_scroll(prevent);
+        self
+    }
+
+    /// Hosts the window in a canvas the page already has, matched by a CSS `selector` such as
+    /// `"#my-canvas"`, instead of a new canvas filling the viewport.
+    ///
+    /// This is for embedding in a page with its own layout.  The page sizes the canvas, and
+    /// the window follows it: [`Surface::size_update`] and [`WindowEvent::Resized`] report its
+    /// resizes.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **wasm32**: the first element matching `selector`.  If nothing matches, or it isn't a
+    ///   canvas, a warning is logged and a canvas filling the viewport is made as usual.
+    /// * **Other platforms**: this does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::window::WindowBuilder;
+    ///
+    /// let window = WindowBuilder::new()
+    ///     .canvas_selector("#game")
+    ///     .build()
+    ///     .await;
+    /// # }
+    /// ```
+    pub fn canvas_selector(mut self, selector: impl Into<String>) -> Self {
+        self.canvas_selector = Some(selector.into());
+        self
+    }
+
+    /// Sets whether the window has a title bar and borders; the default is `true`.
+    ///
+    /// Games and splash screens that draw everything themselves can turn them off.  Without a
+    /// title bar the user has nothing to move or close the window by, so the app provides its
+    /// own ways, if it wants them.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: our decor isn't drawn, and the compositor is asked not to draw its own.
+    /// * **Windows**: a `WS_POPUP` window.
+    /// * **macOS**: an `NSWindowStyleMaskBorderless` window, which can still take the keyboard.
+    /// * **iOS, Android, wasm32**: windows have no decorations, so this does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::window::WindowBuilder;
+    ///
+    /// let game = WindowBuilder::new().title("Asteroids").decorations(false).build().await;
+    /// # }
+    /// ```
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.options.decorations = Some(decorations);
+        self
+    }
+
+    /// Sets whether the user can resize the window; the default is `true`.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: the window's minimum and maximum size are pinned to its size, which
+    ///   compositors honor by not resizing it, and the resize border is turned off.
+    /// * **Windows**: the window has no sizing border or maximize button.
+    /// * **macOS**: the window lacks `NSWindowStyleMaskResizable`.
+    /// * **iOS, Android, wasm32**: the system sizes windows, so this does nothing.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.options.resizable = Some(resizable);
+        self
+    }
+
+    /// Sets the smallest size, in logical pixels, that the user can resize the window to.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: `xdg_toplevel.set_min_size`, which compositors may ignore.
+    /// * **Windows**: the outer size, like [`Window::set_size`].
+    /// * **macOS**: the content size.
+    /// * **iOS, Android, wasm32**: the system sizes windows, so this does nothing.
+    pub fn min_size(mut self, size: Size) -> Self {
+        self.options.min_size = Some(size);
+        self
+    }
+
+    /// Sets the largest size, in logical pixels, that the user can resize the window to.
+    ///
+    /// This also limits maximizing.  Platforms treat it as they do
+    /// [`min_size`](Self::min_size).
+    pub fn max_size(mut self, size: Size) -> Self {
+        self.options.max_size = Some(size);
+        self
+    }
+
+    /// Lets what's behind the window show through where it draws transparent pixels; the
+    /// default is `false`.
+    ///
+    /// This makes the window able to be transparent.  To actually see through it, configure the
+    /// surface with an alpha mode other than opaque and draw pixels with alpha below one.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: Wayland composites every surface with its alpha, so there's nothing to do.
+    /// * **Windows**: the DWM frame is extended across the whole window.
+    /// * **macOS**: the window is made non-opaque with a clear background.
+    /// * **wasm32**: the canvas is already composited over the page.
+    /// * **iOS, Android**: this does nothing.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.options.transparent = Some(transparent);
+        self
+    }
+
+    /// Sets the window's icon; see [`Window::set_icon`].  By default windows show the app's
+    /// icon.
+    pub fn icon(mut self, icon: IconData) -> Self {
+        self.options.icon = Some(icon);
+        self
+    }
+
+    /// Sets the color the window shows until the app presents its first frame, so it doesn't
+    /// flash the system's default before the app draws.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: the window is filled with `color` until the app presents.
+    /// * **Windows**: the window erases to `color`.
+    /// * **macOS**: the window's background color, unless it's [`transparent`](Self::transparent).
+    /// * **wasm32**: the canvas's CSS `background-color`.
+    /// * **iOS, Android**: this does nothing.
+    pub fn background(mut self, color: Color) -> Self {
+        self.options.background = Some(color);
+        self
+    }
+
+    /// Lets mouse and touch input pass through the window to whatever is below it; the default
+    /// is `false`.
+    ///
+    /// Overlays and HUDs combine this with [`transparent`](Self::transparent) and
+    /// [`always_on_top`](Self::always_on_top), so they can be seen over other apps without
+    /// getting in the way.  The window gets no pointer input, so give the user another way to
+    /// close it.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: the surface's input region is empty.
+    /// * **Windows**: a `WS_EX_LAYERED | WS_EX_TRANSPARENT` window.
+    /// * **macOS**: the window ignores mouse events.
+    /// * **wasm32**: the canvas has `pointer-events: none`, so input goes to the page below.
+    /// * **iOS, Android**: this does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::window::WindowBuilder;
+    ///
+    /// let hud = WindowBuilder::new()
+    ///     .title("FPS")
+    ///     .decorations(false)
+    ///     .transparent(true)
+    ///     .always_on_top(true)
+    ///     .click_through(true)
+    ///     .build()
+    ///     .await;
+    /// # }
+    /// ```
+    pub fn click_through(mut self, click_through: bool) -> Self {
+        self.options.click_through = Some(click_through);
+        self
+    }
+
+    /// Keeps the window above other apps' windows, as for palettes and picture-in-picture; the
+    /// default is `false`.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Windows**: a topmost window.
+    /// * **macOS**: a window at the floating level.
+    /// * **Linux**: xdg-shell leaves stacking to the compositor, so this does nothing.
+    /// * **iOS, Android, wasm32**: this does nothing.
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.options.always_on_top = Some(always_on_top);
+        self
+    }
+
+    /// Makes the window belong to `parent`, so it stays above it, as for tool palettes and
+    /// inspectors.  Unlike [`Window::begin_sheet`], `parent` stays usable.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: `xdg_toplevel.set_parent`.  Compositors keep the window above `parent`, and
+    ///   may hide it when `parent` is minimized.
+    /// * **Windows**: an owned window, which stays above its owner and minimizes with it.
+    /// * **macOS**: a child window, which also moves with `parent`.
+    /// * **iOS, Android, wasm32**: this does nothing.
+    pub fn parent(mut self, parent: &Window) -> Self {
+        self.parent = Some(parent.sys.clone());
+        self
+    }
+
+    /// Makes the window a modal child of `parent`, as for preferences and dialogs: it belongs
+    /// to `parent` as with [`parent`](Self::parent), and is shown as a sheet on it, as with
+    /// [`Window::begin_sheet`], so `parent` takes no input until it's dismissed.
+    ///
+    /// Hiding or closing the window dismisses it.  It stays `parent`'s child, and can be made
+    /// modal again with [`Window::begin_sheet`].  A window built [`visible(false)`](Self::visible)
+    /// isn't shown, so it's only a child until then.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **macOS**: `NSWindow.beginSheet`, so the window slides out of `parent`'s title bar.
+    /// * **Windows**: an owned window centered over `parent`, which is disabled with
+    ///   `EnableWindow` until the window is dismissed.
+    /// * **Linux**: `xdg_toplevel.set_parent`, made modal with `xdg_dialog_v1` where the
+    ///   compositor offers it.
+    /// * **iOS, Android, wasm32**: the window is shown as usual.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example(document: &app_window::window::Window) {
+    /// use app_window::coordinates::Size;
+    /// use app_window::window::WindowBuilder;
+    ///
+    /// let preferences = WindowBuilder::new()
+    ///     .title("Preferences")
+    ///     .size(Size::new(400.0, 300.0))
+    ///     .modal_to(document)
+    ///     .build()
+    ///     .await;
+    /// # }
+    /// ```
+    pub fn modal_to(mut self, parent: &Window) -> Self {
+        self.parent = Some(parent.sys.clone());
+        self.modal = true;
+        self
+    }
+
+    /// Sets whether the window is shown once it's built; the default is `true`.
+    ///
+    /// A window built hidden can be set up, then shown with [`Window::set_visible`].
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// Makes the window fullscreen, like [`Window::fullscreen`]; the default is `false`.
+    ///
+    /// The position, size and decorations are ignored.  If the platform can't make a
+    /// fullscreen window, [`build`](Self::build) creates an ordinary one instead.
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Makes the window start maximized; the default is `false`.
+    ///
+    /// The size is still what the window returns to when the user restores it.
+    ///
+    /// # Platform behavior
+    ///
+    /// * **Linux**: `xdg_toplevel.set_maximized`, before the window is first shown.
+    /// * **Windows**: shown with `SW_SHOWMAXIMIZED`.
+    /// * **macOS**: zoomed once shown.
+    /// * **iOS, Android, wasm32**: windows already fill what they can, so this does nothing.
+    pub fn maximized(mut self, maximized: bool) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    /// Restores a window's layout from [`Window::geometry`], setting the size, position,
+    /// [`maximized`](Self::maximized) and [`fullscreen`](Self::fullscreen) options at once.
+    ///
+    /// Without a saved position the builder's position is kept.  A saved position isn't
+    /// checked against the screens, so a window saved on a monitor that's since been unplugged
+    /// may open where the platform puts it, or off screen.
+    pub fn geometry(mut self, geometry: WindowGeometry) -> Self {
+        //saved before the window system sized it
+        if geometry.size.width() > 0.0 && geometry.size.height() > 0.0 {
+            self.options.size = Some(geometry.size);
+        }
+        if let Some(position) = geometry.position {
+            self.position = position;
+        }
+        self.maximized = geometry.maximized;
+        self.fullscreen = geometry.fullscreen;
+        self
+    }
+
+    /// Takes the window from the pool filled by [`WindowBuilder::prewarm`], if there's one
+    /// with this builder's position and size.  Otherwise [`build`](Self::build) creates a new
+    /// window as usual.  Pooled windows are decorated and shown, so builders without
+    /// [`decorations`](Self::decorations), [`visible`](Self::visible) or with
+    /// [`fullscreen`](Self::fullscreen) or [`modal_to`](Self::modal_to) always create a new
+    /// window.
+    pub fn from_pool(mut self) -> Self {
+        self.from_pool = true;
+        self
+    }
+
+    /// Creates hidden windows with this builder's position and size, so that a later
+    /// [`from_pool`](Self::from_pool) build can show one at once.
+    ///
+    /// Creating a window costs a round trip to the window system, which can take hundreds of
+    /// milliseconds on a slow compositor.  Apps that open windows on user action, such as
+    /// detachable panels, can pay that cost up front.  This tops the pool up to `count`
+    /// windows of this position and size; call it again after claiming one to stay warm.
+    /// The title and close behavior are applied when a window is claimed.
+    ///
+    /// Pooling only helps on Linux, Windows and macOS.  Elsewhere this does nothing.
+    ///
+    /// See also [`application::prewarm_windows`](crate::application::prewarm_windows).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn example() {
+    /// use app_window::coordinates::Size;
+    /// use app_window::window::WindowBuilder;
+    ///
+    /// let panel = WindowBuilder::new().size(Size::new(320.0, 480.0));
+    /// panel.prewarm(2).await;
+    /// //...later, when the user detaches a panel
+    /// let window = panel.clone().title("Layers").from_pool().build().await;
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`application::main()`](crate::application::main) has not been called.
+    pub async fn prewarm(&self, count: usize) {
+        assert!(
+            crate::application::is_main_thread_running(),
+            "Call app_window::application::main"
+        );
+        let ResolvedOptions { size, title, .. } = self.resolve();
+        let pooled = WINDOW_POOL
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|w| w.position == self.position && w.size == size)
+            .count();
+        for _ in pooled..count {
+            let Some(sys) =
+                crate::sys::Window::new_parked(self.position, size, title.clone()).await
+            else {
+                return;
+            };
+            WINDOW_POOL.lock().unwrap().push(PooledWindow {
+                position: self.position,
+                size,
+                sys,
+            });
+        }
+    }
+
+    /// Removes a pooled window of this position and `size` from the pool.
+    fn claim(&self, size: Size) -> Option<crate::sys::Window> {
+        let mut pool = WINDOW_POOL.lock().unwrap();
+        let index = pool
+            .iter()
+            .position(|w| w.position == self.position && w.size == size)?;
+        Some(pool.swap_remove(index).sys)
+    }
+
+    /// Creates the window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`application::main()`](crate::application::main) has not been called.
+    pub async fn build(self) -> Window {
+        assert!(
+            crate::application::is_main_thread_running(),
+            "{}",
+            CALL_MAIN
+        );
+        let options = self.resolve();
+        //options that need the window are applied while it's hidden, so it never shows without them
+        let setup = !options.resizable
+            || options.transparent
+            || options.always_on_top
+            || options.click_through
+            || options.icon.is_some()
+            || options.background.is_some()
+            || options.min_size.is_some()
+            || options.max_size.is_some()
+            || self.parent.is_some()
+            || self.maximized;
+        let fullscreen = if self.fullscreen {
+            match crate::sys::Window::fullscreen(options.title.clone(), None).await {
+                Ok(sys) => Some(sys),
+                Err(e) => {
+                    logwise::warn_sync!(
+                        "Can't create a fullscreen window: {e}",
+                        e = logwise::privacy::LogIt(&e)
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let is_fullscreen = fullscreen.is_some();
+        let claimed = if self.from_pool
+            && options.decorations
+            && self.visible
+            && !self.modal
+            && !is_fullscreen
+        {
+            self.claim(options.size)
+        } else {
+            None
+        };
+        let pooled = claimed.is_some();
+        let sys = match (fullscreen, claimed) {
+            (Some(sys), _) | (None, Some(sys)) => sys,
+            (None, None) => {
+                let create = CreateOptions {
+                    decorated: options.decorations,
+                    visible: self.visible && !setup,
+                    canvas_selector: self.canvas_selector.clone(),
+                };
+                crate::sys::Window::new_with_options(
+                    self.position,
+                    options.size,
+                    options.title.clone(),
+                    create,
+                )
+                .await
+            }
+        };
+        if is_fullscreen && !self.visible {
+            sys.set_visible(false).await;
+        }
+        if setup {
+            self.apply_setup(&sys, &options).await;
+        }
+        if pooled {
+            sys.unpark(options.title).await;
+        } else if setup && self.visible && !is_fullscreen && !self.show_modal(&sys).await {
+            sys.set_visible(true).await;
+        }
+        let window = Window::from_sys(sys).await;
+        if !matches!(options.close_behavior, CloseBehavior::Destroy) {
+            window.sys.set_close_behavior(options.close_behavior).await;
+        }
+        if options.canvas_behavior != CanvasBehavior::new() {
+            window
+                .sys
+                .set_canvas_behavior(options.canvas_behavior)
+                .await;
+        }
+        window
+    }
+
+    /// Shows the window as a sheet on its parent, if it's [`modal_to`](Self::modal_to) one.
+    /// Returns whether it did.
+    async fn show_modal(&self, sys: &crate::sys::Window) -> bool {
+        let Some(parent) = self.parent.as_ref().filter(|_| self.modal) else {
+            return false;
+        };
+        match parent.show_modal(sys).await {
+            Ok(()) => true,
+            Err(WindowSettingError::Unsupported) => false,
+            Err(e) => {
+                logwise::warn_sync!(
+                    "Can't show the window modally: {e}",
+                    e = logwise::privacy::LogIt(&e)
+                );
+                false
+            }
+        }
+    }
+
+    /// Applies the options that can only be set on a window that exists.  Platforms that lack
+    /// one just go without.
+    async fn apply_setup(&self, sys: &crate::sys::Window, options: &ResolvedOptions) {
+        fn check(option: &str, result: Result<(), WindowSettingError>) {
+            match result {
+                Ok(()) | Err(WindowSettingError::Unsupported) => {}
+                Err(e) => logwise::warn_sync!(
+                    "Can't apply {option}: {e}",
+                    option = logwise::privacy::LogIt(&option),
+                    e = logwise::privacy::LogIt(&e)
+                ),
+            }
+        }
+        if !options.resizable {
+            check("resizable", sys.set_resizable(false).await);
+        }
+        if options.min_size.is_some() || options.max_size.is_some() {
+            check(
+                "size limits",
+                sys.set_size_limits(options.min_size, options.max_size)
+                    .await,
+            );
+        }
+        if let Some(color) = options.background {
+            check("background", sys.set_background(color).await);
+        }
+        if options.transparent {
+            check("transparent", sys.set_transparent(true).await);
+        }
+        if let Some(icon) = &options.icon {
+            check("icon", sys.set_icon(Some(icon.clone())).await);
+        }
+        if options.always_on_top {
+            check("always_on_top", sys.set_always_on_top(true).await);
+        }
+        if options.click_through {
+            check("click_through", sys.set_click_through(true).await);
+        }
+        if let Some(parent) = &self.parent {
+            check("parent", sys.set_parent(parent).await);
+        }
+        if self.maximized {
+            check("maximized", sys.set_maximized(true).await);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::coordinates::{Position, Size};
+    use crate::dialog::Color;
+    use crate::window::{
+        CloseBehavior, IconData, PreviewImage, ScaleOverride, Window, WindowBuilder,
+        WindowDefaults, WindowGeometry, WindowSettingError,
+    };
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn test_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Window>();
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Window>();
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn preview_fits_within_bounds() {
+        assert_eq!(
+            PreviewImage::new(2, 2, vec![0; 4]),
+            Err(WindowSettingError::InvalidImage)
+        );
+        let pixels = (0..4 * 2).flat_map(|i| [i as u8, 0, 0, 255]).collect();
+        let image = PreviewImage::new(4, 2, pixels).unwrap();
+        let small = image.fit_within(2, 2);
+        assert_eq!((small.width(), small.height()), (2, 1));
+        assert_eq!(small.rgba(), &[0, 0, 0, 255, 2, 0, 0, 255]);
+        assert_eq!(image.fit_within(100, 100), image);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn icon_stretches_to_square() {
+        assert_eq!(
+            IconData::new(0, 0, Vec::new()),
+            Err(WindowSettingError::InvalidImage)
+        );
+        let icon = IconData::new(2, 1, [1, 0, 0, 255, 2, 0, 0, 255].to_vec()).unwrap();
+        let square = icon.square(2);
+        assert_eq!((square.width(), square.height()), (2, 2));
+        assert_eq!(square.rgba(), [1, 0, 0, 255, 2, 0, 0, 255].repeat(2));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn defaults_inherit_each_option() {
+        let app = WindowDefaults::new()
+            .title("App")
+            .size(Size::new(100.0, 100.0))
+            .decorations(false)
+            .resizable(false);
+        let class = WindowDefaults::new()
+            .size(Size::new(200.0, 200.0))
+            .close_behavior(CloseBehavior::HideToTray)
+            .min_size(Size::new(100.0, 50.0));
+        let window = WindowDefaults::new()
+            .title("Palette")
+            .inherit(&class.inherit(&app));
+        assert_eq!(window.title.as_deref(), Some("Palette"));
+        assert_eq!(window.size, Some(Size::new(200.0, 200.0)));
+        assert!(matches!(
+            window.close_behavior,
+            Some(CloseBehavior::HideToTray)
+        ));
+        assert_eq!(window.canvas_behavior, None);
+        assert_eq!(window.decorations, Some(false));
+        assert_eq!(window.resizable, Some(false));
+        assert_eq!(window.min_size, Some(Size::new(100.0, 50.0)));
+        assert_eq!(window.max_size, None);
+        assert_eq!(window.click_through, None);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn defaults_inherit_icon_and_background() {
+        let icon = IconData::new(1, 1, vec![0, 0, 0, 255]).unwrap();
+        let app = WindowDefaults::new()
+            .icon(icon.clone())
+            .background(Color::new(0.0, 0.0, 0.0));
+        let dark = Color::new(0.1, 0.1, 0.1);
+        super::set_window_class_defaults(
+            "test::icon_and_background".to_string(),
+            WindowDefaults::new().background(dark),
+        );
+        let window = WindowDefaults::new().inherit(&app);
+        assert_eq!(window.icon.as_ref(), Some(&icon));
+        assert_eq!(window.background, Some(Color::new(0.0, 0.0, 0.0)));
+
+        let options = WindowBuilder::new()
+            .class("test::icon_and_background")
+            .resolve();
+        assert_eq!(options.background, Some(dark));
+        assert_eq!(options.icon, None);
+        let options = WindowBuilder::new()
+            .class("test::icon_and_background")
+            .icon(icon.clone())
+            .resolve();
+        assert_eq!(options.icon, Some(icon));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn geometry_restores_what_was_saved() {
+        let mut saved = WindowGeometry::new(Size::new(640.0, 480.0));
+        saved.position = Some(Position::new(30.0, 40.0));
+        saved.maximized = true;
+        let builder = WindowBuilder::new().geometry(saved);
+        assert_eq!(builder.options.size, Some(Size::new(640.0, 480.0)));
+        assert_eq!(builder.position, Position::new(30.0, 40.0));
+        assert!(builder.maximized);
+        assert!(!builder.fullscreen);
+
+        //an unsized window keeps the builder's size and position
+        let builder = WindowBuilder::new()
+            .size(Size::new(200.0, 100.0))
+            .position(Position::new(5.0, 5.0))
+            .geometry(WindowGeometry::new(Size::new(0.0, 0.0)));
+        assert_eq!(builder.options.size, Some(Size::new(200.0, 100.0)));
+        assert_eq!(builder.position, Position::new(5.0, 5.0));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn scale_override_is_shared() {
+        let window_side = ScaleOverride::default();
+        let surface_side = window_side.clone();
+        assert_eq!(surface_side.get(), None);
+        window_side.set(Some(1.25));
+        assert_eq!(surface_side.get(), Some(1.25));
+        window_side.set(None);
+        assert_eq!(surface_side.get(), None);
     }
 }
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The tree the app publishes for what it draws; see
+//! [`Window::update_accessibility_tree`](super::Window::update_accessibility_tree).
+//!
+//! Assistive technology can start listening at any time, and then needs the whole tree, so
+//! backends keep what the app sent here and hand it to their adapter when asked.
+
+use crate::window::WindowSettingError;
+use accesskit::{ActionRequest, NodeId, TreeId, TreeUpdate};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Receives actions on the app's tree; see `Window::on_accessibility_action`.
+pub(crate) type ActionFn = Arc<Mutex<Box<dyn FnMut(ActionRequest) + Send>>>;
+
+pub(crate) struct AccessibilityContent {
+    /// What the adapter knows the app's tree as: the root, or a subtree of the backend's own.
+    tree_id: TreeId,
+    nodes: HashMap<NodeId, accesskit::Node>,
+    tree: Option<accesskit::Tree>,
+    focus: Option<NodeId>,
+    /// `nodes.len()` when we last dropped nodes the app removed.
+    pruned_len: usize,
+    on_action: Option<ActionFn>,
+}
+
+impl std::fmt::Debug for AccessibilityContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessibilityContent")
+            .field("tree_id", &self.tree_id)
+            .field("nodes", &self.nodes.len())
+            .field("tree", &self.tree)
+            .field("focus", &self.focus)
+            .field("on_action", &self.on_action.is_some())
+            .finish()
+    }
+}
+
+impl AccessibilityContent {
+    /// Content the adapter knows as `tree_id`.
+    pub fn new(tree_id: TreeId) -> Self {
+        AccessibilityContent {
+            tree_id,
+            nodes: HashMap::new(),
+            tree: None,
+            focus: None,
+            pruned_len: 0,
+            on_action: None,
+        }
+    }
+
+    /// Whether the app has published a tree, for backends that graft it under their own.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub fn is_published(&self) -> bool {
+        self.tree.is_some()
+    }
+
+    pub fn set_action_handler(&mut self, on_action: Box<dyn FnMut(ActionRequest) + Send>) {
+        self.on_action = Some(Arc::new(Mutex::new(on_action)));
+    }
+
+    /// Passes `request` to the app's handler, addressed to its tree as the app knows it.
+    ///
+    /// The handler is called without `content` locked, so it can update the tree.
+    pub fn perform(content: &Mutex<Self>, mut request: ActionRequest) {
+        let on_action = content.lock().unwrap().on_action.clone();
+        if let Some(on_action) = on_action {
+            request.target_tree = TreeId::ROOT;
+            (on_action.lock().unwrap())(request);
+        }
+    }
+
+    /// Records `update`, and returns it addressed to the adapter's tree.
+    pub fn apply(&mut self, mut update: TreeUpdate) -> Result<TreeUpdate, WindowSettingError> {
+        if let Some(tree) = &update.tree {
+            self.tree = Some(tree.clone());
+        } else if self.tree.is_none() {
+            return Err(WindowSettingError::InvalidTree);
+        }
+        for (id, node) in &update.nodes {
+            self.nodes.insert(*id, node.clone());
+        }
+        self.focus = Some(update.focus);
+        //forget nodes the app removed, now and then
+        if self.nodes.len() > 2 * self.pruned_len.max(64) {
+            self.forget_removed();
+        }
+        update.tree_id = self.tree_id;
+        Ok(update)
+    }
+
+    /// Drops nodes the app removed from the tree.
+    pub fn forget_removed(&mut self) {
+        self.nodes = self.reachable().into_iter().collect();
+        self.pruned_len = self.nodes.len();
+    }
+
+    /// The whole tree, for an adapter that just became active.
+    pub fn full_update(&self) -> Option<TreeUpdate> {
+        let tree = self.tree.clone()?;
+        let focus = self.focus.unwrap_or(tree.root);
+        Some(TreeUpdate {
+            nodes: self.reachable(),
+            tree: Some(tree),
+            tree_id: self.tree_id,
+            focus,
+        })
+    }
+
+    /// Nodes still in the tree; removing a child only updates its parent.
+    fn reachable(&self) -> Vec<(NodeId, accesskit::Node)> {
+        let Some(tree) = &self.tree else {
+            return Vec::new();
+        };
+        let mut nodes = Vec::new();
+        let mut pending = vec![tree.root];
+        while let Some(id) = pending.pop() {
+            if let Some(node) = self.nodes.get(&id) {
+                pending.extend_from_slice(node.children());
+                nodes.push((id, node.clone()));
+            }
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AccessibilityContent;
+    use crate::window::WindowSettingError;
+    use accesskit::{Action, ActionRequest, Node, NodeId, Role, Tree, TreeId, TreeUpdate, Uuid};
+    use std::sync::{Arc, Mutex};
+
+    const SUBTREE: TreeId = TreeId(Uuid::from_u128(1));
+
+    fn group(children: Vec<NodeId>) -> Node {
+        let mut node = Node::new(Role::Group);
+        node.set_children(children);
+        node
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn full_update_has_only_the_current_tree() {
+        let mut content = AccessibilityContent::new(SUBTREE);
+        let first = TreeUpdate {
+            nodes: vec![(NodeId(2), Node::new(Role::Button))],
+            tree: None,
+            tree_id: TreeId::ROOT,
+            focus: NodeId(2),
+        };
+        assert!(matches!(
+            content.apply(first),
+            Err(WindowSettingError::InvalidTree)
+        ));
+
+        let update = content
+            .apply(TreeUpdate {
+                nodes: vec![
+                    (NodeId(1), group(vec![NodeId(2)])),
+                    (NodeId(2), Node::new(Role::Button)),
+                ],
+                tree: Some(Tree::new(NodeId(1))),
+                tree_id: TreeId::ROOT,
+                focus: NodeId(2),
+            })
+            .unwrap();
+        assert_eq!(update.tree_id, SUBTREE);
+        //replace the button with a label
+        content
+            .apply(TreeUpdate {
+                nodes: vec![
+                    (NodeId(1), group(vec![NodeId(3)])),
+                    (NodeId(3), Node::new(Role::Label)),
+                ],
+                tree: None,
+                tree_id: TreeId::ROOT,
+                focus: NodeId(1),
+            })
+            .unwrap();
+
+        let full = content.full_update().unwrap();
+        let mut ids: Vec<_> = full.nodes.iter().map(|(id, _)| *id).collect();
+        ids.sort();
+        assert_eq!(ids, [NodeId(1), NodeId(3)]);
+        assert_eq!(full.focus, NodeId(1));
+        assert_eq!(full.tree_id, SUBTREE);
+        content.forget_removed();
+        assert_eq!(content.nodes.len(), 2);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn actions_are_addressed_to_the_apps_tree() {
+        let content = Mutex::new(AccessibilityContent::new(SUBTREE));
+        let request = ActionRequest {
+            action: Action::Click,
+            target_tree: SUBTREE,
+            target_node: NodeId(2),
+            data: None,
+        };
+        //nobody's listening yet
+        AccessibilityContent::perform(&content, request.clone());
+        let seen: Arc<Mutex<Vec<ActionRequest>>> = Arc::default();
+        let move_seen = seen.clone();
+        content
+            .lock()
+            .unwrap()
+            .set_action_handler(Box::new(move |request| {
+                move_seen.lock().unwrap().push(request)
+            }));
+        AccessibilityContent::perform(&content, request);
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].target_tree, TreeId::ROOT);
+        assert_eq!(seen[0].target_node, NodeId(2));
+    }
+}
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The busy cursor, for [`Window::busy_cursor`](super::Window::busy_cursor).
+//!
+//! Guards can overlap, so we count them: the wait cursor goes up with the first and the
+//! cursor the app chose comes back with the last.  [`Window::set_cursor`](super::Window::set_cursor)
+//! calls made in between are remembered, and shown once the window stops being busy.
+
+use super::{Cursor, WindowSettingError};
+use std::sync::{Arc, Mutex};
+
+/// The cursor the app chose for a window, and how many busy guards are over it.
+#[derive(Debug, Default)]
+pub(crate) struct CursorState {
+    chosen: Cursor,
+    busy: usize,
+}
+
+impl CursorState {
+    /// Records the app's cursor.  Returns it if it should be shown now.
+    pub(crate) fn choose(&mut self, cursor: Cursor) -> Option<Cursor> {
+        self.chosen = cursor;
+        (self.busy == 0).then_some(cursor)
+    }
+
+    /// Returns `true` for the first guard, which puts up the wait cursor.
+    fn begin(&mut self) -> bool {
+        self.busy += 1;
+        self.busy == 1
+    }
+
+    /// Returns the cursor to put back once the last guard ends.
+    fn end(&mut self) -> Option<Cursor> {
+        self.busy -= 1;
+        (self.busy == 0).then_some(self.chosen)
+    }
+}
+
+/// Shows the busy cursor over a window until dropped.  See
+/// [`Window::busy_cursor`](super::Window::busy_cursor).
+#[derive(Debug)]
+#[must_use = "the cursor is restored as soon as the guard is dropped"]
+pub struct CursorGuard {
+    sys: Arc<crate::sys::Window>,
+    state: Arc<Mutex<CursorState>>,
+    ended: bool,
+}
+
+impl CursorGuard {
+    pub(crate) async fn begin(
+        sys: Arc<crate::sys::Window>,
+        state: Arc<Mutex<CursorState>>,
+    ) -> Result<Self, WindowSettingError> {
+        let first = state.lock().unwrap().begin();
+        if first && let Err(e) = sys.set_cursor(Cursor::Wait).await {
+            state.lock().unwrap().end();
+            return Err(e);
+        }
+        Ok(CursorGuard {
+            sys,
+            state,
+            ended: false,
+        })
+    }
+
+    /// Ends the busy cursor, waiting until the previous cursor is back.
+    ///
+    /// Dropping the guard does the same without waiting.
+    pub async fn restore(mut self) {
+        self.ended = true;
+        let restore = self.state.lock().unwrap().end();
+        if let Some(cursor) = restore {
+            //it was shown once, so it can be shown again
+            _ = self.sys.set_cursor(cursor).await;
+        }
+    }
+}
+
+impl Drop for CursorGuard {
+    fn drop(&mut self) {
+        if self.ended {
+            return;
+        }
+        let Some(cursor) = self.state.lock().unwrap().end() else {
+            return;
+        };
+        //after a panic or a cancelled future nobody is left to await this, so the main thread does
+        if !crate::application::is_main_thread_running() {
+            return;
+        }
+        let sys = self.sys.clone();
+        crate::application::submit_to_main_thread("CursorGuard::drop".to_string(), move || {
+            crate::executor::already_on_main_thread_submit(
+                "CursorGuard::drop".to_string(),
+                async move {
+                    _ = sys.set_cursor(cursor).await;
+                },
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CursorState;
+    use crate::window::Cursor;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn overlapping_guards_restore_the_latest_choice() {
+        let mut state = CursorState::default();
+        assert_eq!(state.choose(Cursor::Text), Some(Cursor::Text));
+        assert!(state.begin());
+        assert!(!state.begin());
+        //chosen while busy, so shown later
+        assert_eq!(state.choose(Cursor::Hand), None);
+        assert_eq!(state.end(), None);
+        assert_eq!(state.end(), Some(Cursor::Hand));
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The look of the title bar buttons app_window draws itself, for
+//! [`Window::set_decoration_theme`](super::Window::set_decoration_theme).
+//!
+//! The buttons are drawn from lines rather than a bitmap or a font, so they're drawn afresh at
+//! each scale the window is shown at and stay sharp on high-DPI displays.
+#![cfg_attr(
+    not(all(target_os = "linux", feature = "decorations")),
+    allow(dead_code)
+)]
+
+use super::PreviewImage;
+
+/// How app_window draws the title bar buttons of a window it decorates itself.  See
+/// [`Window::set_decoration_theme`](super::Window::set_decoration_theme).
+///
+/// The buttons sit at the top right of the window: minimize, maximize, then close.  Sizes are
+/// in logical pixels, and rounded to whole ones.  Colors are non-premultiplied RGBA.
+///
+/// # Example
+///
+/// ```
+/// use app_window::window::DecorationTheme;
+///
+/// //bigger buttons for a touch screen, on a dark bar
+/// let mut theme = DecorationTheme::default();
+/// theme.height = 40.0;
+/// theme.button_width = 48.0;
+/// theme.background = [0x20, 0x20, 0x20, 0xFF];
+/// theme.glyph = [0xFF, 0xFF, 0xFF, 0xFF];
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct DecorationTheme {
+    /// Height of the title bar.  Dragging it, away from the buttons, moves the window.
+    pub height: f64,
+    /// Width of each button.
+    pub button_width: f64,
+    /// Drawn behind the buttons.
+    pub background: [u8; 4],
+    /// The minimize and maximize glyphs.
+    pub glyph: [u8; 4],
+    /// The close glyph.
+    pub close_glyph: [u8; 4],
+    /// How thick the glyphs' lines are.
+    pub stroke: f64,
+    /// Replaces the drawn buttons: minimize, maximize and close side by side, stretched over
+    /// all three.  Supply it at the largest scale you expect, as it's only ever scaled.
+    pub button_strip: Option<PreviewImage>,
+}
+
+impl Default for DecorationTheme {
+    /// The buttons app_window has always drawn: 25 pixels square, on nothing.
+    fn default() -> Self {
+        DecorationTheme {
+            height: 25.0,
+            button_width: 25.0,
+            background: [0, 0, 0, 0],
+            glyph: [91, 104, 205, 154],
+            close_glyph: [203, 45, 45, 118],
+            stroke: 1.0,
+            button_strip: None,
+        }
+    }
+}
+
+type Point = (f64, f64);
+
+impl DecorationTheme {
+    /// Height of the title bar in whole logical pixels.
+    pub(crate) fn titlebar_height(&self) -> f64 {
+        self.height.round().max(1.0)
+    }
+
+    /// Width of one button in whole logical pixels.
+    pub(crate) fn button_width(&self) -> f64 {
+        self.button_width.round().max(1.0)
+    }
+
+    /// The three buttons, `scale` pixels to the logical pixel.
+    pub(crate) fn render(&self, scale: u32) -> PreviewImage {
+        let width = (self.button_width() * 3.0) as u32 * scale;
+        let height = self.titlebar_height() as u32 * scale;
+        if let Some(strip) = &self.button_strip {
+            return strip.resized(width, height);
+        }
+        let button = width as f64 / 3.0;
+        let side = button.min(height as f64) * 0.4;
+        let glyphs: [Vec<(Point, Point)>; 3] = std::array::from_fn(|index| {
+            let center_x = button * (index as f64 + 0.5);
+            let center_y = height as f64 / 2.0;
+            let (left, right) = (center_x - side / 2.0, center_x + side / 2.0);
+            let (top, bottom) = (center_y - side / 2.0, center_y + side / 2.0);
+            match index {
+                0 => vec![((left, bottom), (right, bottom))],
+                1 => vec![
+                    ((left, top), (right, top)),
+                    ((right, top), (right, bottom)),
+                    ((right, bottom), (left, bottom)),
+                    ((left, bottom), (left, top)),
+                ],
+                _ => vec![
+                    ((left, top), (right, bottom)),
+                    ((right, top), (left, bottom)),
+                ],
+            }
+        });
+        let half_stroke = self.stroke * scale as f64 / 2.0;
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let point = (x as f64 + 0.5, y as f64 + 0.5);
+                let index = ((point.0 / button) as usize).min(2);
+                let distance = glyphs[index]
+                    .iter()
+                    .map(|&(a, b)| distance_to_segment(point, a, b))
+                    .fold(f64::INFINITY, f64::min);
+                //a pixel's worth of antialiasing at the line's edge
+                let coverage = (half_stroke + 0.5 - distance).clamp(0.0, 1.0);
+                let color = if index == 2 {
+                    self.close_glyph
+                } else {
+                    self.glyph
+                };
+                rgba.extend_from_slice(&over(color, coverage, self.background));
+            }
+        }
+        PreviewImage {
+            width,
+            height,
+            rgba,
+        }
+    }
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
+
+/// `top`, with its alpha scaled by `coverage`, composited over `bottom`.
+fn over(top: [u8; 4], coverage: f64, bottom: [u8; 4]) -> [u8; 4] {
+    let top_alpha = top[3] as f64 / 255.0 * coverage;
+    let bottom_alpha = bottom[3] as f64 / 255.0 * (1.0 - top_alpha);
+    let alpha = top_alpha + bottom_alpha;
+    if alpha == 0.0 {
+        return [0, 0, 0, 0];
+    }
+    let channel =
+        |i: usize| ((top[i] as f64 * top_alpha + bottom[i] as f64 * bottom_alpha) / alpha).round();
+    [
+        channel(0) as u8,
+        channel(1) as u8,
+        channel(2) as u8,
+        (alpha * 255.0).round() as u8,
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::DecorationTheme;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn renders_at_scale() {
+        let theme = DecorationTheme {
+            background: [0, 0, 0, 0xFF],
+            ..Default::default()
+        };
+        let image = theme.render(2);
+        assert_eq!((image.width(), image.height()), (150, 50));
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * image.width() + x) * 4) as usize;
+            image.rgba()[i..i + 4].to_vec()
+        };
+        //the middle of the close button's cross, and a corner of the bar
+        assert_eq!(pixel(125, 25), [94, 21, 21, 0xFF]);
+        assert_eq!(pixel(0, 0), [0, 0, 0, 0xFF]);
+    }
+}
@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Lifecycle events for one window; see [`Window::events`](super::Window::events).
+
+use crate::coordinates::{Position, Size};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Poll, Waker};
+
+/// Most events a [`WindowEvents`] holds before dropping the oldest.
+const WINDOW_EVENT_CAPACITY: usize = 256;
+
+/// Something that happened to a window; see [`Window::events`](super::Window::events).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum WindowEvent {
+    /// The window's content area changed size.  The size is in logical pixels, like
+    /// [`Surface::size_update`](crate::surface::Surface::size_update).
+    Resized(Size),
+    /// The window moved.  The position is its top-left corner on the desktop, in the same
+    /// coordinates as [`Window::outer_position`](super::Window::outer_position).
+    Moved(Position),
+    /// The window gained keyboard focus.
+    Focused,
+    /// The window lost keyboard focus.
+    Unfocused,
+    /// The window was minimized.
+    Minimized,
+    /// The window came back from being minimized.
+    Restored,
+    /// The window's scale factor changed, e.g. because it moved to another display.
+    ScaleChanged(f64),
+    /// Nothing of the window can be seen: it's minimized, covered by other windows, on
+    /// another virtual desktop, or its page is in a background tab.  Renderers can stop
+    /// drawing until [`WindowEvent::Visible`].
+    ///
+    /// Sent when [`WindowStateFlags::suspended`] turns on, right after the
+    /// [`WindowEvent::StateChanged`] that says so.
+    Occluded,
+    /// The window can be seen again after [`WindowEvent::Occluded`].
+    Visible,
+    /// The window system changed what it says about the window's state.  Sent once when a
+    /// backend first knows the state, then whenever it changes.  See [`WindowStateFlags`].
+    StateChanged(WindowStateFlags),
+    /// The user asked to close the window.  What happens next is up to its
+    /// [`CloseBehavior`](super::CloseBehavior).
+    CloseRequested,
+    /// The window system failed in a way that affects the window.
+    ///
+    /// On Linux this is sent when the Wayland connection fails, because of a protocol error or
+    /// because the compositor exited.  The connection can't be used after that, so the window
+    /// is lost, but the app keeps running, and [`WindowEvent::Reconnected`] follows if a
+    /// compositor comes back.
+    BackendError(BackendError),
+    /// The window system is back after an interruption.  Anything the app made from the
+    /// window's surface, like a GPU swapchain, may need to be made again.
+    ///
+    /// * On Linux, the compositor came back after a [`WindowEvent::BackendError`] lost the
+    ///   window.  The window was recreated with its size, visibility and cursor, but the old
+    ///   [`Surface`](crate::surface::Surface) is dead; get a new one from
+    ///   [`Window::surface`](super::Window::surface).  Confined pointers and inhibited
+    ///   shortcuts have to be asked for again.
+    /// * On Windows, the user's session reconnected, at the console or over Remote Desktop.
+    /// * On macOS, the Mac woke from sleep.
+    Reconnected,
+}
+
+/// Which edges of a window are against something, such as a screen edge or another tiled
+/// window.  See [`WindowStateFlags::tiled`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct TiledEdges {
+    /// The left edge.
+    pub left: bool,
+    /// The right edge.
+    pub right: bool,
+    /// The top edge.
+    pub top: bool,
+    /// The bottom edge.
+    pub bottom: bool,
+}
+
+impl TiledEdges {
+    /// Whether any edge is tiled.
+    pub fn any(&self) -> bool {
+        self.left || self.right || self.top || self.bottom
+    }
+}
+
+/// What the window system says about a window, for [`WindowEvent::StateChanged`].
+///
+/// Renderers can stop drawing while `suspended`, and apps drawing their own decorations can
+/// square off and drop the shadow on `tiled` edges.  Flags a platform doesn't report stay
+/// `false`:
+///
+/// | Flag | Linux | Windows | macOS | wasm32 |
+/// |------|-------|---------|-------|--------|
+/// | `maximized` | `xdg_toplevel` state | `IsZoomed` | `isZoomed` | Never |
+/// | `fullscreen` | `xdg_toplevel` state | Borderless over the monitor | Fullscreen space | Page fullscreen |
+/// | `tiled` | `xdg_toplevel` states | Snapped, against the work area's edges | Never | Never |
+/// | `activated` | `xdg_toplevel` state | Foreground | Key window | Page focused |
+/// | `suspended` | `xdg_toplevel` state | Minimized or cloaked | Minimized or occluded | Page hidden |
+///
+/// Windows are cloaked while on another virtual desktop.  Linux compositors choose when to
+/// suspend a window; those without the state never do.
+///
+/// iOS and Android don't send these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct WindowStateFlags {
+    /// Filling the screen, apart from the system's panels.
+    pub maximized: bool,
+    /// Covering the whole screen.
+    pub fullscreen: bool,
+    /// Edges that are against something, so shouldn't look floating.
+    pub tiled: TiledEdges,
+    /// Drawn as the active window, which usually means it has focus.
+    pub activated: bool,
+    /// Not visible to the user, so not worth drawing: minimized, hidden behind other windows,
+    /// or on a sleeping screen.
+    pub suspended: bool,
+}
+
+/// What went wrong, for [`WindowEvent::BackendError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendError {
+    message: String,
+    window_lost: bool,
+}
+
+impl BackendError {
+    /// Describes a failure.  Backends send these; see [`WindowEventSender`].
+    pub fn new(message: impl Into<String>, window_lost: bool) -> Self {
+        BackendError {
+            message: message.into(),
+            window_lost,
+        }
+    }
+
+    /// The failure, as the platform described it.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Whether the window is gone: it no longer shows anything or gets input.  On Linux it
+    /// comes back with [`WindowEvent::Reconnected`] if the compositor does; otherwise drop the
+    /// [`Window`](super::Window) and create another to carry on.
+    pub fn window_lost(&self) -> bool {
+        self.window_lost
+    }
+}
+
+#[derive(Debug, Default)]
+struct WindowEventQueue {
+    events: VecDeque<WindowEvent>,
+    waker: Option<Waker>,
+}
+
+/// The events for one window, in order.
+///
+/// If the events aren't read, the oldest are dropped once 256 are waiting.
+///
+/// Created by [`Window::events`](super::Window::events).
+#[derive(Debug)]
+pub struct WindowEvents {
+    queue: Arc<Mutex<WindowEventQueue>>,
+}
+
+impl WindowEvents {
+    /// Waits for the next event.
+    pub async fn next(&mut self) -> WindowEvent {
+        std::future::poll_fn(|cx| {
+            let mut queue = self.queue.lock().unwrap();
+            match queue.events.pop_front() {
+                Some(event) => Poll::Ready(event),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Returns the next event, if one is waiting.
+    pub fn try_next(&mut self) -> Option<WindowEvent> {
+        self.queue.lock().unwrap().events.pop_front()
+    }
+}
+
+type HandlerFn = dyn Fn(&WindowEvent) + Send + Sync;
+
+/// Called with each of a window's events as it's sent, until dropped.  See
+/// [`WindowEventSender::handle`].
+pub(crate) struct EventHandler {
+    _handler: Arc<HandlerFn>,
+}
+
+impl Debug for EventHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EventHandler")
+    }
+}
+
+/// Delivers a window's events to each of its [`WindowEvents`].
+///
+/// Backends hold one per window and call [`send`](Self::send) as things happen.  Clones send
+/// to the same listeners.
+#[derive(Clone, Default)]
+pub struct WindowEventSender {
+    listeners: Arc<Mutex<Vec<Weak<Mutex<WindowEventQueue>>>>>,
+    handlers: Arc<Mutex<Vec<Weak<HandlerFn>>>>,
+    /// The last [`WindowEvent::StateChanged`] sent.
+    state: Arc<Mutex<Option<WindowStateFlags>>>,
+}
+
+impl Debug for WindowEventSender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowEventSender")
+            .field("listeners", &self.listeners.lock().unwrap().len())
+            .field("handlers", &self.handlers.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl WindowEventSender {
+    /// Delivers `event` to every live [`WindowEvents`] for this window, and calls its handlers.
+    pub fn send(&self, event: WindowEvent) {
+        //handlers may send or handle, so call them unlocked
+        let handlers: Vec<_> = {
+            let mut handlers = self.handlers.lock().unwrap();
+            handlers.retain(|handler| handler.strong_count() > 0);
+            handlers.iter().filter_map(Weak::upgrade).collect()
+        };
+        for handler in handlers {
+            handler(&event);
+        }
+        self.listeners.lock().unwrap().retain(|listener| {
+            let Some(queue) = listener.upgrade() else {
+                return false;
+            };
+            let mut queue = queue.lock().unwrap();
+            if queue.events.len() == WINDOW_EVENT_CAPACITY {
+                queue.events.pop_front();
+            }
+            queue.events.push_back(event.clone());
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+            true
+        });
+    }
+
+    /// Sends [`WindowEvent::StateChanged`] if `flags` differ from the last it sent, so backends
+    /// can call this whenever the state might have changed.
+    /// Also sends [`WindowEvent::Occluded`] or [`WindowEvent::Visible`] when `suspended` changes.
+    /// Windows start out visible, so a first state that isn't suspended sends neither.
+    pub fn update_state(&self, flags: WindowStateFlags) {
+        let old = self.state.lock().unwrap().replace(flags);
+        if old == Some(flags) {
+            return;
+        }
+        self.send(WindowEvent::StateChanged(flags));
+        let was_suspended = old.is_some_and(|old| old.suspended);
+        if flags.suspended != was_suspended {
+            self.send(if flags.suspended {
+                WindowEvent::Occluded
+            } else {
+                WindowEvent::Visible
+            });
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> WindowEvents {
+        let queue = Arc::new(Mutex::new(WindowEventQueue::default()));
+        self.listeners.lock().unwrap().push(Arc::downgrade(&queue));
+        WindowEvents { queue }
+    }
+
+    /// Calls `handler` with each event from now on, on whatever thread sends it, until the
+    /// returned [`EventHandler`] is dropped.
+    pub(crate) fn handle(
+        &self,
+        handler: impl Fn(&WindowEvent) + Send + Sync + 'static,
+    ) -> EventHandler {
+        let handler: Arc<HandlerFn> = Arc::new(handler);
+        self.handlers.lock().unwrap().push(Arc::downgrade(&handler));
+        EventHandler { _handler: handler }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WindowEvent, WindowEventSender, WindowStateFlags};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn each_listener_gets_every_event() {
+        let sender = WindowEventSender::default();
+        let mut first = sender.subscribe();
+        let mut second = sender.subscribe();
+        sender.send(WindowEvent::Focused);
+        assert_eq!(second.try_next(), Some(WindowEvent::Focused));
+        sender.send(WindowEvent::CloseRequested);
+        assert_eq!(first.try_next(), Some(WindowEvent::Focused));
+        assert_eq!(first.try_next(), Some(WindowEvent::CloseRequested));
+        assert_eq!(first.try_next(), None);
+        assert_eq!(second.try_next(), Some(WindowEvent::CloseRequested));
+
+        drop(second);
+        sender.send(WindowEvent::Unfocused);
+        assert_eq!(sender.listeners.lock().unwrap().len(), 1);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn state_changes_are_sent_once() {
+        let sender = WindowEventSender::default();
+        let mut events = sender.subscribe();
+        let mut flags = WindowStateFlags::default();
+        sender.update_state(flags);
+        sender.update_state(flags);
+        flags.tiled.left = true;
+        sender.update_state(flags);
+        assert_eq!(
+            events.try_next(),
+            Some(WindowEvent::StateChanged(WindowStateFlags::default()))
+        );
+        assert_eq!(events.try_next(), Some(WindowEvent::StateChanged(flags)));
+        assert_eq!(events.try_next(), None);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn suspension_sends_occlusion() {
+        let sender = WindowEventSender::default();
+        let mut events = sender.subscribe();
+        let mut flags = WindowStateFlags::default();
+        sender.update_state(flags);
+        flags.suspended = true;
+        sender.update_state(flags);
+        flags.activated = true;
+        sender.update_state(flags);
+        flags.suspended = false;
+        sender.update_state(flags);
+        let occlusion: Vec<_> = std::iter::from_fn(|| events.try_next())
+            .filter(|event| !matches!(event, WindowEvent::StateChanged(_)))
+            .collect();
+        assert_eq!(occlusion, [WindowEvent::Occluded, WindowEvent::Visible]);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn handlers_run_until_dropped() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let sender = WindowEventSender::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        let handler = sender.handle(move |_| {
+            counted.fetch_add(1, Ordering::Relaxed);
+        });
+        sender.send(WindowEvent::Focused);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        drop(handler);
+        sender.send(WindowEvent::Focused);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert!(sender.handlers.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Telling windows apart, including which one input went to; see
+//! [`Window::id`](super::Window::id).
+//!
+//! Input reports the platform's handle for the window (see `crate::input::Window`), which
+//! apps can't match to a [`Window`](super::Window).  So each window registers its handle
+//! here, and input looks it up.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Identifies a [`Window`](super::Window).
+///
+/// Ids are never reused, even after the window closes, so they're safe to keep as keys, e.g.
+/// to route input to the right document.  Input says which window it went to with the same
+/// id; see `app_window::input::Window::id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WindowId(u64);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Platform handles, with the window each belongs to.  Newest last.
+static HANDLES: Mutex<Vec<(usize, WindowId)>> = Mutex::new(Vec::new());
+
+/// A window's id, with its handle registered until this is dropped.
+#[derive(Debug)]
+pub(super) struct Registration {
+    id: WindowId,
+}
+
+impl Registration {
+    pub(super) async fn new(sys: &crate::sys::Window) -> Self {
+        let id = WindowId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        if let Some(handle) = sys.input_handle().await {
+            HANDLES.lock().unwrap().push((handle, id));
+        }
+        Registration { id }
+    }
+
+    pub(super) fn id(&self) -> WindowId {
+        self.id
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        HANDLES.lock().unwrap().retain(|(_, id)| *id != self.id);
+    }
+}
+
+/// The window input reported by `handle` went to.
+#[cfg_attr(not(feature = "input"), allow(dead_code))]
+pub(crate) fn id_for_handle(handle: usize) -> Option<WindowId> {
+    //platforms reuse handles; the newest window wins
+    HANDLES
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|(h, _)| *h == handle)
+        .map(|(_, id)| *id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HANDLES, WindowId, id_for_handle};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn newest_window_wins() {
+        //not a real handle, so no window registers it
+        let handle = usize::MAX - 1;
+        assert_eq!(id_for_handle(handle), None);
+        HANDLES
+            .lock()
+            .unwrap()
+            .push((handle, WindowId(u64::MAX - 1)));
+        HANDLES.lock().unwrap().push((handle, WindowId(u64::MAX)));
+        assert_eq!(id_for_handle(handle), Some(WindowId(u64::MAX)));
+        HANDLES.lock().unwrap().retain(|(h, _)| *h != handle);
+    }
+}
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The platform's own window objects, for [`Window::native_handle`](super::Window::native_handle).
+//!
+//! This is an escape hatch for platform features app_window doesn't wrap, such as menus,
+//! taskbar APIs or custom sheets.  What you do with a handle is between you and the platform:
+//! app_window doesn't know about it, and changes it makes may undo yours, or yours may confuse
+//! it.  The shape of [`NativeHandle`] is stable; the behavior of anything done through it
+//! isn't.
+
+use std::ffi::c_void;
+use std::num::NonZero;
+use std::ptr::NonNull;
+
+/// A window's platform object.
+///
+/// Pointers are borrowed from the [`Window`](super::Window): they're valid while it's open,
+/// and must not be released, closed or destroyed.  Use them on the thread the platform
+/// requires, usually the main thread (see
+/// [`application::on_main_thread`](crate::application::on_main_thread)).
+///
+/// Unlike [`Surface`](crate::surface::Surface)'s raw handles, which name the drawable area for
+/// graphics APIs, these name the window itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NativeHandle {
+    /// macOS: the `NSWindow *`.
+    AppKit {
+        /// The `NSWindow *`.
+        ns_window: NonNull<c_void>,
+    },
+    /// iOS: the `UIWindow *`.
+    UiKit {
+        /// The `UIWindow *`.
+        ui_window: NonNull<c_void>,
+    },
+    /// Windows: the top-level `HWND`.
+    Win32 {
+        /// The `HWND`.
+        hwnd: NonZero<isize>,
+    },
+    /// Linux: libwayland proxies for the window.
+    ///
+    /// Objects made from these belong to `display`'s connection; if the compositor restarts,
+    /// the window is rebuilt on a new connection and the old handle is dead.
+    Wayland {
+        /// The `wl_display *` the window belongs to.
+        display: NonNull<c_void>,
+        /// The window's `wl_surface *`.
+        wl_surface: NonNull<c_void>,
+        /// The window's `xdg_toplevel *`.
+        xdg_toplevel: NonNull<c_void>,
+    },
+    /// Web: the window's canvas, which carries a `data-raw-handle` attribute with this value,
+    /// as in [`raw_window_handle::WebWindowHandle`].
+    Web {
+        /// Find the canvas with `canvas[data-raw-handle="<raw_handle>"]`.
+        raw_handle: u32,
+    },
+}
+
+//it's only an address; using it is what the platform restricts to a thread, and that's unsafe
+unsafe impl Send for NativeHandle {}
+unsafe impl Sync for NativeHandle {}
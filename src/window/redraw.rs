@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Redraws paced by the display, for [`Window::request_redraw`](super::Window::request_redraw).
+//!
+//! A request asks the platform for one callback when the compositor is ready for the next
+//! frame; requests made before it arrives share it.  Apps that animate request another frame
+//! from the callback, and stop asking when nothing changes, so an idle window costs nothing.
+
+use crate::application::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// What the platform said about a frame, passed to
+/// [`Window::on_redraw`](super::Window::on_redraw).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTiming {
+    timestamp: Instant,
+    target: Option<Instant>,
+    refresh_interval: Option<Duration>,
+}
+
+impl FrameTiming {
+    pub(crate) fn new(
+        timestamp: Instant,
+        target: Option<Instant>,
+        refresh_interval: Option<Duration>,
+    ) -> Self {
+        FrameTiming {
+            timestamp,
+            target,
+            refresh_interval,
+        }
+    }
+
+    /// When the platform said the frame could start.
+    pub fn timestamp(&self) -> Instant {
+        self.timestamp
+    }
+
+    /// When the frame is expected to reach the display, where the platform says.
+    ///
+    /// Animations look smoothest positioned for this time rather than [`Self::timestamp`].
+    pub fn target(&self) -> Option<Instant> {
+        self.target
+    }
+
+    /// The display's time between frames, where the platform says.
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        self.refresh_interval
+    }
+}
+
+/// A callback the platform calls, on the main thread, once the frame is due.
+pub(crate) type FrameCallback = Box<dyn FnOnce(FrameTiming) + Send>;
+
+/// Requests frames for a surface from any thread, and outlives the borrow it came from.
+pub(crate) type FrameRequester = Arc<dyn Fn(FrameCallback) + Send + Sync>;
+
+type Handler = Box<dyn FnMut(FrameTiming) + Send>;
+
+/// A window's redraw handler, and whether a frame is on its way.
+#[derive(Default)]
+pub(crate) struct Redraw {
+    requested: AtomicBool,
+    handler: Mutex<Option<Handler>>,
+}
+
+impl std::fmt::Debug for Redraw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Redraw")
+            .field("requested", &self.requested.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Redraw {
+    pub(crate) fn set_handler(&self, handler: Handler) {
+        *self.handler.lock().unwrap() = Some(handler);
+    }
+
+    /// Marks a frame as requested.  Returns `false` if one already was, so the platform has
+    /// already been asked.
+    pub(crate) fn request(&self) -> bool {
+        !self.requested.swap(true, Ordering::AcqRel)
+    }
+
+    /// Runs the handler for a frame.
+    pub(crate) fn fire(&self, timing: FrameTiming) {
+        //cleared first, so the handler can ask for the next frame
+        self.requested.store(false, Ordering::Release);
+        //taken out while it runs, so it can replace itself without deadlocking
+        let Some(mut handler) = self.handler.lock().unwrap().take() else {
+            return;
+        };
+        handler(timing);
+        let mut slot = self.handler.lock().unwrap();
+        if slot.is_none() {
+            *slot = Some(handler);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FrameTiming, Redraw};
+    use crate::application::time::Instant;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn requests_coalesce_until_the_frame() {
+        let redraw = Arc::new(Redraw::default());
+        let frames = Arc::new(AtomicUsize::new(0));
+        let counted = frames.clone();
+        let inner = redraw.clone();
+        redraw.set_handler(Box::new(move |_| {
+            counted.fetch_add(1, Ordering::Relaxed);
+            //the next frame can be asked for from the handler
+            assert!(inner.request());
+        }));
+        assert!(redraw.request());
+        assert!(!redraw.request());
+        redraw.fire(FrameTiming::new(Instant::now(), None, None));
+        assert_eq!(frames.load(Ordering::Relaxed), 1);
+        assert!(!redraw.request());
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Which part of a window a position falls in, such as a resize border.
+//!
+//! Where app_window draws a window's frame itself (Linux), it uses these to decide what a click
+//! does.  Apps that draw their own title bar or borders can use the same definitions.
+
+use crate::coordinates::{Position, Size};
+
+/// How wide resize borders are unless set with
+/// [`Window::set_resize_border`](super::Window::set_resize_border), in logical pixels.
+pub const DEFAULT_RESIZE_BORDER: f64 = 10.0;
+
+/// An edge or corner of a window, which resizes it when dragged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ResizeEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ResizeEdge {
+    /// The edge or corner of a window of `size` that `position` is on, if it's within
+    /// `border` of one.  Positions are in the window's coordinates, with the origin at the
+    /// top left of its content.
+    pub fn from_position(size: Size, position: Position, border: f64) -> Option<Self> {
+        let top = position.y() < border;
+        let bottom = size.height() - position.y() < border;
+        let left = position.x() < border;
+        let right = size.width() - position.x() < border;
+        match (top, bottom, left, right) {
+            (true, _, true, _) => Some(ResizeEdge::TopLeft),
+            (true, _, _, true) => Some(ResizeEdge::TopRight),
+            (_, true, true, _) => Some(ResizeEdge::BottomLeft),
+            (_, true, _, true) => Some(ResizeEdge::BottomRight),
+            (true, ..) => Some(ResizeEdge::Top),
+            (_, true, ..) => Some(ResizeEdge::Bottom),
+            (_, _, true, _) => Some(ResizeEdge::Left),
+            (.., true) => Some(ResizeEdge::Right),
+            _ => None,
+        }
+    }
+}
+
+/// The part of a window a position falls in.
+///
+/// [`MouseRegion::from_position`] only finds borders.  The title bar and its buttons are up to
+/// whoever draws them: app_window's own decorations on Linux, or the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MouseRegion {
+    /// The window's content.
+    Client,
+    /// A title bar, which moves the window when dragged.
+    Titlebar,
+    /// The title bar's close button.
+    CloseButton,
+    /// The title bar's maximize button.
+    MaximizeButton,
+    /// The title bar's minimize button.
+    MinimizeButton,
+    /// A border, which resizes the window when dragged.
+    Resize(ResizeEdge),
+}
+
+impl MouseRegion {
+    /// The region of a window of `size` that `position` falls in, with resize borders
+    /// `border` wide.  This is [`MouseRegion::Resize`] or [`MouseRegion::Client`].
+    pub fn from_position(size: Size, position: Position, border: f64) -> Self {
+        match ResizeEdge::from_position(size, position, border) {
+            Some(edge) => MouseRegion::Resize(edge),
+            None => MouseRegion::Client,
+        }
+    }
+}
+
+/// With [`DEFAULT_RESIZE_BORDER`].
+impl From<(Position, Size)> for MouseRegion {
+    fn from((position, size): (Position, Size)) -> Self {
+        MouseRegion::from_position(size, position, DEFAULT_RESIZE_BORDER)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MouseRegion, ResizeEdge};
+    use crate::coordinates::{Position, Size};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn corners_win_over_edges() {
+        let size = Size::new(100.0, 50.0);
+        let region = |x, y| MouseRegion::from_position(size, Position::new(x, y), 10.0);
+        assert_eq!(region(50.0, 25.0), MouseRegion::Client);
+        assert_eq!(region(2.0, 2.0), MouseRegion::Resize(ResizeEdge::TopLeft));
+        assert_eq!(
+            region(95.0, 45.0),
+            MouseRegion::Resize(ResizeEdge::BottomRight)
+        );
+        assert_eq!(region(50.0, 45.0), MouseRegion::Resize(ResizeEdge::Bottom));
+        assert_eq!(region(2.0, 25.0), MouseRegion::Resize(ResizeEdge::Left));
+        assert_eq!(
+            MouseRegion::from((Position::new(95.0, 25.0), size)),
+            MouseRegion::Resize(ResizeEdge::Right)
+        );
+    }
+}
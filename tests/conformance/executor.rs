@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Main thread and executor semantics.
+
+use crate::{Outcome, ensure};
+use app_window::application::{self, DispatchBudget};
+use app_window::main_thread_cell::MainThreadCell;
+use some_executor::Priority;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// `on_main_thread` runs its closure on the main thread and hands back the result.
+pub async fn on_main_thread() -> Outcome {
+    ensure!(
+        !application::is_main_thread(),
+        "the suite should start off the main thread"
+    );
+    let (on_main, value) =
+        application::on_main_thread("conformance::on_main_thread".to_string(), || {
+            (application::is_main_thread(), 42)
+        })
+        .await;
+    ensure!(on_main, "closure didn't run on the main thread");
+    ensure!(value == 42, "got {value} back instead of 42");
+    Outcome::Pass
+}
+
+/// A panicking closure doesn't stop the main thread from running the next one.
+pub async fn panic_contained() -> Outcome {
+    if cfg!(target_arch = "wasm32") {
+        return Outcome::Skip("panics abort on wasm32".to_string());
+    }
+    application::submit_to_main_thread("conformance::panic_contained".to_string(), || {
+        panic!("conformance: this panic is expected")
+    });
+    let value =
+        application::on_main_thread("conformance::panic_contained_after".to_string(), || 7).await;
+    ensure!(value == 7, "got {value} back instead of 7");
+    Outcome::Pass
+}
+
+/// Closures submitted from one thread run in submission order.
+pub async fn submit_order() -> Outcome {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    for i in 0..16 {
+        let order = order.clone();
+        application::submit_to_main_thread("conformance::submit_order".to_string(), move || {
+            order.lock().unwrap().push(i)
+        });
+    }
+    //submissions are FIFO, so once this runs everything before it has too
+    application::on_main_thread("conformance::submit_order_fence".to_string(), || ()).await;
+    let order = order.lock().unwrap();
+    ensure!(
+        *order == (0..16).collect::<Vec<_>>(),
+        "closures ran out of order: {order:?}"
+    );
+    Outcome::Pass
+}
+
+/// Prioritized closures run most urgent first, and cancelled ones don't run.
+pub async fn priorities() -> Outcome {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let move_order = order.clone();
+    //submitted from the main thread, so none can start until we return
+    let cancelled = application::on_main_thread("conformance::priorities".to_string(), move || {
+        let submit = |i, priority| {
+            let order = move_order.clone();
+            application::submit_to_main_thread_with_priority(
+                "conformance::priorities_work".to_string(),
+                priority,
+                move || order.lock().unwrap().push(i),
+            )
+        };
+        submit(0, Priority::Background);
+        let dropped = submit(1, Priority::Background);
+        submit(2, Priority::Utility);
+        submit(3, Priority::UserInteractive);
+        dropped.cancel()
+    })
+    .await;
+    ensure!(cancelled, "couldn't cancel work that hadn't started");
+    //each submission posted a dispatch before this one
+    application::on_main_thread("conformance::priorities_fence".to_string(), || ()).await;
+    let order = order.lock().unwrap();
+    ensure!(*order == [3, 2, 0], "closures ran as {order:?}");
+    Outcome::Pass
+}
+
+/// Futures sent to the main thread executor run to completion, including across awaits.
+pub async fn on_main_thread_async() -> Outcome {
+    let value = app_window::executor::on_main_thread_async(
+        "conformance::on_main_thread_async".to_string(),
+        async {
+            let first = application::is_main_thread();
+            //nested main thread work from inside the main thread executor
+            let inner = app_window::executor::on_main_thread_async(
+                "conformance::on_main_thread_async_inner".to_string(),
+                async { 2 },
+            )
+            .await;
+            (first, inner)
+        },
+    )
+    .await;
+    ensure!(value.0, "future didn't run on the main thread");
+    ensure!(value.1 == 2, "nested future returned {}", value.1);
+    Outcome::Pass
+}
+
+/// A `MainThreadCell` can be created and read from a background thread.
+pub async fn main_thread_cell() -> Outcome {
+    let cell = MainThreadCell::new_on_main_thread(|| async { 21 }).await;
+    let doubled = cell.with(|value| *value * 2).await;
+    ensure!(doubled == 42, "got {doubled} from the cell");
+    Outcome::Pass
+}
+
+/// A flood of closures from several threads all runs, in per-thread order, while the event
+/// loop keeps yielding to the platform between small dispatch budgets.
+pub async fn dispatch_stress() -> Outcome {
+    const THREADS: usize = 4;
+    const PER_THREAD: usize = 5_000;
+    let prior = application::dispatch_budget();
+    application::set_dispatch_budget(DispatchBudget::new(8, Duration::from_micros(500)));
+
+    let seen = Arc::new(Mutex::new(vec![Vec::new(); THREADS]));
+    let submitters: Vec<_> = (0..THREADS)
+        .map(|thread| {
+            let seen = seen.clone();
+            std::thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    let seen = seen.clone();
+                    application::submit_to_main_thread(
+                        "conformance::dispatch_stress".to_string(),
+                        move || seen.lock().unwrap()[thread].push(i),
+                    );
+                }
+            })
+        })
+        .collect();
+    for submitter in submitters {
+        submitter.join().unwrap();
+    }
+    //every submitter is done, so once this runs everything before it has too
+    application::on_main_thread("conformance::dispatch_stress_fence".to_string(), || ()).await;
+    application::set_dispatch_budget(prior);
+
+    let seen = seen.lock().unwrap();
+    for (thread, order) in seen.iter().enumerate() {
+        ensure!(
+            order.len() == PER_THREAD,
+            "thread {thread}: {} of {PER_THREAD} closures ran",
+            order.len()
+        );
+        ensure!(
+            order.iter().enumerate().all(|(i, &v)| i == v),
+            "thread {thread}: closures ran out of order"
+        );
+    }
+    Outcome::Pass
+}
+
+/// `run_chunked` runs every unit on the main thread, in order, over more than one chunk.
+pub async fn run_chunked() -> Outcome {
+    let chunks = Arc::new(Mutex::new(0));
+    let chunks_counter = chunks.clone();
+    let work = (0..2_000).map(|i| {
+        //slow enough that a 1ms budget needs several chunks
+        std::thread::sleep(Duration::from_micros(5));
+        (application::is_main_thread(), i)
+    });
+    let results = app_window::executor::run_chunked(
+        "conformance::run_chunked".to_string(),
+        work,
+        Duration::from_millis(1),
+        move |_| *chunks_counter.lock().unwrap() += 1,
+    )
+    .await;
+    ensure!(results.len() == 2_000, "got {} results", results.len());
+    ensure!(
+        results.iter().all(|(on_main, _)| *on_main),
+        "work ran off the main thread"
+    );
+    ensure!(
+        results.iter().enumerate().all(|(i, (_, v))| i == *v),
+        "results are out of order"
+    );
+    let chunks = *chunks.lock().unwrap();
+    ensure!(chunks > 1, "all work ran in {chunks} chunk");
+    Outcome::Pass
+}
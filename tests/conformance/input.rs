@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Keyboard and mouse state tracking, driven by injected events.
+
+use crate::{Outcome, ensure};
+use app_window::input::keyboard::Keyboard;
+use app_window::input::keyboard::key::KeyboardKey;
+use app_window::input::mouse::{MOUSE_BUTTON_LEFT, MOUSE_BUTTON_RIGHT, Mouse};
+
+pub async fn keyboard() -> Outcome {
+    let keyboard = Keyboard::coalesced().await;
+    keyboard.inject_key(KeyboardKey::A, true);
+    ensure!(keyboard.is_pressed(KeyboardKey::A), "A isn't pressed");
+    ensure!(
+        !keyboard.is_pressed(KeyboardKey::B),
+        "pressing A also pressed B"
+    );
+    keyboard.inject_key(KeyboardKey::A, false);
+    ensure!(
+        !keyboard.is_pressed(KeyboardKey::A),
+        "A is still pressed after release"
+    );
+    Outcome::Pass
+}
+
+pub async fn mouse() -> Outcome {
+    let mut mouse = Mouse::coalesced().await;
+    mouse.inject_button(MOUSE_BUTTON_LEFT, true);
+    ensure!(
+        mouse.button_state(MOUSE_BUTTON_LEFT),
+        "left button isn't pressed"
+    );
+    ensure!(
+        !mouse.button_state(MOUSE_BUTTON_RIGHT),
+        "pressing left also pressed right"
+    );
+    mouse.inject_button(MOUSE_BUTTON_LEFT, false);
+    ensure!(
+        !mouse.button_state(MOUSE_BUTTON_LEFT),
+        "left button is still pressed after release"
+    );
+    //scroll deltas accumulate until read
+    mouse.inject_scroll(1.0, 2.0);
+    mouse.inject_scroll(0.5, -1.0);
+    let delta = mouse.load_clear_scroll_delta();
+    ensure!(delta == (1.5, 1.0), "accumulated scroll was {delta:?}");
+    let delta = mouse.load_clear_scroll_delta();
+    ensure!(delta == (0.0, 0.0), "scroll wasn't cleared, got {delta:?}");
+    Outcome::Pass
+}
@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Conformance suite for app_window backends.
+//!
+//! Exercises the public API (window lifecycle, sizing, surface handles, input state and executor
+//! semantics) against whichever backend the target selects.  New backends should pass this
+//! before they're merged.
+//!
+//! Results are written to stdout as JSON lines, one object per check:
+//!
+//! ```text
+//! {"backend":"linux","test":"window::lifecycle","status":"pass","message":null}
+//! ```
+//!
+//! followed by a summary object.  `status` is one of `pass`, `fail` or `skip`.  Human-readable
+//! progress goes to stderr.  The process exits nonzero if any check fails.
+//!
+//! Run with: `cargo test --features conformance --test conformance`
+//!
+//! The suite needs a real windowing environment (a Wayland compositor on Linux, a logged-in
+//! session on macOS and Windows).  It doesn't run on wasm32, where the browser test runner can't
+//! collect its output.
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+
+mod executor;
+mod input;
+mod surface;
+mod window;
+
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// How long the whole suite may run before we assume a check is hung.
+const WATCHDOG: Duration = Duration::from_secs(120);
+
+/// The outcome of a single check.
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    Skip(String),
+}
+
+/// Fails the current check with a message unless `cond` holds.
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            return $crate::Outcome::Fail(format!($($arg)+));
+        }
+    };
+}
+
+#[derive(Default)]
+struct Report {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+impl Report {
+    async fn run(&mut self, name: &str, check: impl Future<Output = Outcome>) {
+        eprint!("{name} ... ");
+        let start = Instant::now();
+        let outcome = check.await;
+        let elapsed = start.elapsed();
+        let (status, message) = match outcome {
+            Outcome::Pass => {
+                self.passed += 1;
+                ("pass", None)
+            }
+            Outcome::Fail(message) => {
+                self.failed += 1;
+                ("fail", Some(message))
+            }
+            Outcome::Skip(message) => {
+                self.skipped += 1;
+                ("skip", Some(message))
+            }
+        };
+        match &message {
+            Some(message) => eprintln!("{status}: {message}"),
+            None => eprintln!("{status}"),
+        }
+        println!(
+            "{{\"backend\":{},\"test\":{},\"status\":\"{status}\",\"message\":{},\"elapsed_ms\":{}}}",
+            json_string(backend()),
+            json_string(name),
+            message.as_deref().map_or("null".to_string(), json_string),
+            elapsed.as_millis()
+        );
+    }
+
+    fn finish(&self) -> ! {
+        println!(
+            "{{\"backend\":{},\"summary\":{{\"passed\":{},\"failed\":{},\"skipped\":{}}}}}",
+            json_string(backend()),
+            self.passed,
+            self.failed,
+            self.skipped
+        );
+        eprintln!(
+            "\n{} passed, {} failed, {} skipped",
+            self.passed, self.failed, self.skipped
+        );
+        std::process::exit(if self.failed == 0 { 0 } else { 1 })
+    }
+}
+
+/// Name of the backend under test.
+pub fn backend() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "linux"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "ios") {
+        "ios"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "android") {
+        "android"
+    } else if cfg!(target_arch = "wasm32") {
+        "wasm"
+    } else {
+        "unknown"
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+async fn run_all() {
+    let mut report = Report::default();
+
+    report
+        .run("executor::on_main_thread", executor::on_main_thread())
+        .await;
+    report
+        .run("executor::submit_order", executor::submit_order())
+        .await;
+    report
+        .run("executor::panic_contained", executor::panic_contained())
+        .await;
+    report
+        .run("executor::priorities", executor::priorities())
+        .await;
+    report
+        .run(
+            "executor::on_main_thread_async",
+            executor::on_main_thread_async(),
+        )
+        .await;
+    report
+        .run("executor::main_thread_cell", executor::main_thread_cell())
+        .await;
+    report
+        .run("executor::dispatch_stress", executor::dispatch_stress())
+        .await;
+    report
+        .run("executor::run_chunked", executor::run_chunked())
+        .await;
+
+    report.run("window::lifecycle", window::lifecycle()).await;
+    report.run("window::builder", window::builder()).await;
+    report.run("window::visibility", window::visibility()).await;
+    report.run("window::resize", window::resize()).await;
+    report
+        .run("window::debug_state", window::debug_state())
+        .await;
+    report.run("window::events", window::events()).await;
+    report.run("window::cursor", window::cursor()).await;
+    report
+        .run("window::geometry_round_trip", window::geometry_round_trip())
+        .await;
+
+    report
+        .run("surface::size_scale", surface::size_scale())
+        .await;
+    report.run("surface::handles", surface::handles()).await;
+    report.run("surface::repeated", surface::repeated()).await;
+
+    report.run("input::keyboard", input::keyboard()).await;
+    report.run("input::mouse", input::mouse()).await;
+
+    report.finish();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use some_executor::task::{Configuration, Task};
+
+    std::thread::Builder::new()
+        .name("conformance watchdog".to_string())
+        .spawn(|| {
+            std::thread::sleep(WATCHDOG);
+            eprintln!("conformance suite timed out after {WATCHDOG:?}");
+            std::process::exit(2);
+        })
+        .unwrap();
+
+    app_window::application::main(|| {
+        std::thread::spawn(|| {
+            let t = Task::without_notifications(
+                "conformance".to_string(),
+                Configuration::default(),
+                run_all(),
+            );
+            t.spawn_static_current();
+        });
+    });
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
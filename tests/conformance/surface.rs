@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Surface sizing and raw handles.
+
+use crate::{Outcome, ensure};
+use app_window::window::Window;
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+
+/// `size_scale` and `size_main` agree and report a usable scale.
+pub async fn size_scale() -> Outcome {
+    let mut window = Window::default().await;
+    let surface = window.surface().await;
+    let (size, scale) = surface.size_scale().await;
+    ensure!(
+        size.width() > 0.0 && size.height() > 0.0,
+        "surface has an empty size {size:?}"
+    );
+    ensure!(scale.is_finite() && scale > 0.0, "bad scale factor {scale}");
+    let (main_size, main_scale) =
+        app_window::application::on_main_thread("conformance::size_main".to_string(), move || {
+            surface.size_main()
+        })
+        .await;
+    ensure!(
+        main_size == size && main_scale == scale,
+        "size_main reported {main_size:?}@{main_scale} but size_scale reported {size:?}@{scale}"
+    );
+    Outcome::Pass
+}
+
+/// The raw handles are the kind the backend is documented to produce.
+pub async fn handles() -> Outcome {
+    let mut window = Window::default().await;
+    let surface = window.surface().await;
+    let window_handle = surface.raw_window_handle();
+    let display_handle = surface.raw_display_handle();
+    let expected = match (window_handle, display_handle) {
+        (RawWindowHandle::Wayland(_), RawDisplayHandle::Wayland(_)) => "linux",
+        (RawWindowHandle::AppKit(_), RawDisplayHandle::AppKit(_)) => "macos",
+        (RawWindowHandle::Win32(_), RawDisplayHandle::Windows(_)) => "windows",
+        (RawWindowHandle::Web(_), RawDisplayHandle::Web(_)) => "wasm",
+        (window_handle, display_handle) => {
+            return Outcome::Fail(format!(
+                "unexpected handles {window_handle:?} / {display_handle:?}"
+            ));
+        }
+    };
+    ensure!(
+        expected == crate::backend(),
+        "{} backend produced {expected} handles",
+        crate::backend()
+    );
+    Outcome::Pass
+}
+
+/// Asking for the surface again hands out the same one.
+pub async fn repeated() -> Outcome {
+    let mut window = Window::default().await;
+    let first = window.surface().await;
+    let second = window.surface().await;
+    ensure!(
+        first.raw_window_handle() == second.raw_window_handle(),
+        "second surface has a different handle"
+    );
+    drop((first, second));
+    //every handle is gone, so this makes it afresh
+    let third = window.surface().await;
+    let (size, _) = third.size_scale().await;
+    ensure!(
+        size.width() > 0.0 && size.height() > 0.0,
+        "recreated surface has an empty size {size:?}"
+    );
+    Outcome::Pass
+}
@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Window creation, visibility and sizing.
+
+use crate::{Outcome, ensure};
+use app_window::coordinates::{Position, Size};
+use app_window::window::{Cursor, Window, WindowBuilder, WindowEvent, WindowGeometry};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Windows can be created and dropped repeatedly.
+pub async fn lifecycle() -> Outcome {
+    for i in 0..3 {
+        let window = Window::new(
+            Position::new(0.0, 0.0),
+            Size::new(320.0, 240.0),
+            format!("conformance lifecycle {i}"),
+        )
+        .await;
+        drop(window);
+    }
+    let window = Window::default().await;
+    drop(window);
+    Outcome::Pass
+}
+
+/// `WindowBuilder` creates a window with the requested size.
+pub async fn builder() -> Outcome {
+    let mut window = WindowBuilder::new()
+        .size(Size::new(400.0, 300.0))
+        .title("conformance builder")
+        .build()
+        .await;
+    let surface = window.surface().await;
+    let (size, _) = surface.size_scale().await;
+    ensure!(
+        size.width() > 0.0 && size.height() > 0.0,
+        "window has an empty size {size:?}"
+    );
+    Outcome::Pass
+}
+
+/// Hiding and showing a window doesn't break it.
+pub async fn visibility() -> Outcome {
+    let mut window = Window::default().await;
+    window.set_visible(false).await;
+    window.set_visible(true).await;
+    let surface = window.surface().await;
+    let (size, _) = surface.size_scale().await;
+    ensure!(
+        size.width() > 0.0 && size.height() > 0.0,
+        "window has an empty size {size:?} after being shown again"
+    );
+    Outcome::Pass
+}
+
+/// Size updates can be subscribed to, and any reported sizes are sane.
+///
+/// There's no API to resize a window programmatically, so this only sees resizes the window
+/// system makes on its own (e.g. tiling compositors).
+pub async fn resize() -> Outcome {
+    let mut window = Window::new(
+        Position::new(0.0, 0.0),
+        Size::new(640.0, 480.0),
+        "conformance resize".to_string(),
+    )
+    .await;
+    let mut surface = window.surface().await;
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    let move_updates = updates.clone();
+    surface.size_update(move |size| move_updates.lock().unwrap().push(size));
+    std::thread::sleep(Duration::from_millis(500));
+    let updates = updates.lock().unwrap();
+    if let Some(bad) = updates
+        .iter()
+        .find(|size| !(size.width() > 0.0 && size.height() > 0.0))
+    {
+        return Outcome::Fail(format!("size_update reported {bad:?}"));
+    }
+    if updates.is_empty() {
+        return Outcome::Skip("the window system didn't resize the window".to_string());
+    }
+    Outcome::Pass
+}
+
+/// `debug_state` names the backend and tracks visibility, where the backend reports it.
+pub async fn debug_state() -> Outcome {
+    let window = Window::default().await;
+    let state = window.debug_state().await;
+    ensure!(!state.backend.is_empty(), "debug_state has no backend name");
+    if let Some(size) = state.applied_size {
+        ensure!(
+            size.width() >= 0.0 && size.height() >= 0.0,
+            "debug_state reports a negative size {size:?}"
+        );
+    }
+    if state.visible.is_none() {
+        return Outcome::Skip(format!("{} doesn't report visibility", state.backend));
+    }
+    window.set_visible(false).await;
+    //some backends hide asynchronously
+    let mut visible = true;
+    for _ in 0..20 {
+        visible = window.debug_state().await.visible == Some(true);
+        if !visible {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    window.set_visible(true).await;
+    ensure!(!visible, "debug_state reports a hidden window as visible");
+    Outcome::Pass
+}
+
+/// `set_size` shows up in the window's events as a resize.
+pub async fn events() -> Outcome {
+    let window = Window::default().await;
+    let mut events = window.events();
+    if window.set_size(Size::new(500.0, 400.0)).await.is_err() {
+        return Outcome::Skip("the backend can't resize windows".to_string());
+    }
+    //some backends resize asynchronously
+    for _ in 0..20 {
+        while let Some(event) = events.try_next() {
+            if let WindowEvent::Resized(size) = event {
+                ensure!(
+                    size.width() > 0.0 && size.height() > 0.0,
+                    "events reported a resize to {size:?}"
+                );
+                return Outcome::Pass;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Outcome::Skip("no resize event after set_size".to_string())
+}
+
+/// Each window keeps the cursor it was given.
+pub async fn cursor() -> Outcome {
+    let first = Window::default().await;
+    let second = Window::default().await;
+    if first.set_cursor(Cursor::Text).await.is_err() {
+        return Outcome::Skip("the backend can't set cursors".to_string());
+    }
+    ensure!(
+        second.set_cursor(Cursor::Crosshair).await.is_ok(),
+        "set_cursor worked for one window but not another"
+    );
+    let (first_cursor, second_cursor) = (
+        first.debug_state().await.cursor,
+        second.debug_state().await.cursor,
+    );
+    if first_cursor.is_none() {
+        return Outcome::Skip("debug_state doesn't report the cursor".to_string());
+    }
+    ensure!(
+        first_cursor == Some(Cursor::Text) && second_cursor == Some(Cursor::Crosshair),
+        "windows report cursors {first_cursor:?} and {second_cursor:?}"
+    );
+    Outcome::Pass
+}
+
+/// Whether `a` and `b` match, give or take rounding to physical pixels.
+fn same_geometry(a: &WindowGeometry, b: &WindowGeometry) -> bool {
+    let near = |a: f64, b: f64| (a - b).abs() <= 1.0;
+    let positions = match (a.position, b.position) {
+        (Some(a), Some(b)) => near(a.x(), b.x()) && near(a.y(), b.y()),
+        (a, b) => a.is_none() && b.is_none(),
+    };
+    positions
+        && near(a.size.width(), b.size.width())
+        && near(a.size.height(), b.size.height())
+        && a.maximized == b.maximized
+        && a.fullscreen == b.fullscreen
+}
+
+/// A window restored from `geometry` saves the same geometry, including a maximized window's
+/// normal bounds.
+pub async fn geometry_round_trip() -> Outcome {
+    let requested = Size::new(500.0, 400.0);
+    let window = WindowBuilder::new()
+        .size(requested)
+        .position(Position::new(120.0, 140.0))
+        .title("conformance geometry")
+        .build()
+        .await;
+    //some backends size asynchronously
+    std::thread::sleep(Duration::from_millis(300));
+    let saved = window.geometry().await;
+    drop(window);
+    if saved.size != requested {
+        return Outcome::Skip(format!(
+            "the window system sized the window {:?} rather than {requested:?}",
+            saved.size
+        ));
+    }
+
+    let window = WindowBuilder::new()
+        .geometry(saved)
+        .title("conformance geometry restored")
+        .build()
+        .await;
+    std::thread::sleep(Duration::from_millis(300));
+    let restored = window.geometry().await;
+    drop(window);
+    ensure!(
+        same_geometry(&saved, &restored),
+        "saved {saved:?}, but the restored window saves {restored:?}"
+    );
+
+    let mut maximized = saved;
+    maximized.maximized = true;
+    let window = WindowBuilder::new()
+        .geometry(maximized)
+        .title("conformance geometry maximized")
+        .build()
+        .await;
+    std::thread::sleep(Duration::from_millis(300));
+    let restored = window.geometry().await;
+    if !restored.maximized {
+        return Outcome::Skip("the window system didn't maximize the window".to_string());
+    }
+    ensure!(
+        same_geometry(&maximized, &restored),
+        "saved {maximized:?}, but the maximized window saves {restored:?}"
+    );
+    Outcome::Pass
+}
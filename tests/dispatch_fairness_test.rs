@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Floods the main thread with closures and checks that frames still arrive.
+//!
+//! Without a dispatch budget, the main loop would drain the whole flood before giving the
+//! window system a turn, so an animating window would get no frames until it was done.
+//!
+//! Needs a display.  Run with: `cargo test --test dispatch_fairness_test`
+
+use app_window::application::{self, DispatchBudget};
+use app_window::window::WindowBuilder;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Enough closures to keep the main thread busy for a few seconds.
+const FLOOD: usize = 100_000;
+/// How long each closure holds the main thread.
+const CLOSURE_COST: Duration = Duration::from_micros(20);
+/// Frames we expect while flooded; a 60Hz display shows well over this in a second.
+const MIN_FRAMES: usize = 10;
+
+fn main() {
+    application::main(|| {
+        std::thread::Builder::new()
+            .name("dispatch_fairness_test".to_string())
+            .spawn(|| {
+                let passed = test_executors::sleep_on(frames_arrive_while_flooded());
+                std::process::exit(if passed { 0 } else { 1 });
+            })
+            .unwrap();
+    });
+}
+
+async fn frames_arrive_while_flooded() -> bool {
+    application::set_dispatch_budget(DispatchBudget::new(16, Duration::from_millis(2)));
+    let window = Arc::new(
+        WindowBuilder::new()
+            .title("dispatch_fairness_test")
+            .build()
+            .await,
+    );
+    let frames = Arc::new(AtomicUsize::new(0));
+    let flooding = Arc::new(AtomicBool::new(false));
+
+    let move_window = Arc::downgrade(&window);
+    let move_frames = frames.clone();
+    let move_flooding = flooding.clone();
+    window.on_redraw(move |_| {
+        if move_flooding.load(Ordering::Relaxed) {
+            move_frames.fetch_add(1, Ordering::Relaxed);
+        }
+        //keep animating
+        if let Some(window) = move_window.upgrade() {
+            window.request_redraw();
+        }
+    });
+    window.request_redraw();
+
+    let ran = Arc::new(AtomicUsize::new(0));
+    flooding.store(true, Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..FLOOD {
+        let ran = ran.clone();
+        application::submit_to_main_thread("dispatch_fairness_test".to_string(), move || {
+            let spin = Instant::now();
+            while spin.elapsed() < CLOSURE_COST {}
+            ran.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    //the last closure runs after all the others
+    application::on_main_thread("dispatch_fairness_test::done".to_string(), || ()).await;
+    flooding.store(false, Ordering::Relaxed);
+    let elapsed = start.elapsed();
+
+    let frames = frames.load(Ordering::Relaxed);
+    let ran = ran.load(Ordering::Relaxed);
+    println!("{ran} closures in {elapsed:?}, with {frames} frames");
+    if ran != FLOOD {
+        println!("FAIL: only {ran} of {FLOOD} closures ran");
+        return false;
+    }
+    if frames < MIN_FRAMES {
+        println!("FAIL: expected at least {MIN_FRAMES} frames while flooded");
+        return false;
+    }
+    println!("PASS");
+    true
+}
@@ -38,19 +38,16 @@ fn main() {
         thread::Builder::new()
             .name("executor_bug_tests".to_string())
             .spawn(|| {
-                let mut test_results = Vec::new();
-
-                // Test 1: Basic nested submission
-                test_results.push(("nested_submission", test_nested_main_thread_submit_bug()));
-
-                // Test 2: Multiple nested levels
-                test_results.push(("deep_nested", test_deep_nested_submissions()));
-
-                // Test 3: Debug output pattern
-                test_results.push(("debug_pattern", test_debug_output_pattern()));
-
-                // Test 4: Concurrent submissions
-                test_results.push(("concurrent", test_concurrent_submissions()));
+                let test_results = vec![
+                    // Test 1: Basic nested submission
+                    ("nested_submission", test_nested_main_thread_submit_bug()),
+                    // Test 2: Multiple nested levels
+                    ("deep_nested", test_deep_nested_submissions()),
+                    // Test 3: Debug output pattern
+                    ("debug_pattern", test_debug_output_pattern()),
+                    // Test 4: Concurrent submissions
+                    ("concurrent", test_concurrent_submissions()),
+                ];
 
                 // Report results
                 let passed = test_results.iter().filter(|(_, result)| *result).count();
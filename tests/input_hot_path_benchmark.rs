@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Benchmark for the per-event cost of recording mouse and keyboard input.
+//!
+//! Gaming mice report at up to 8kHz, which leaves 125µs per event for everything the app
+//! does.  This injects a burst of motion, button and key events while another thread polls
+//! the state, as a render loop would, and checks that recording an event takes under a
+//! tenth of that budget.
+//!
+//! Run with: `cargo test --features conformance --test input_hot_path_benchmark`
+logwise::declare_logging_domain!();
+
+use app_window::input::keyboard::Keyboard;
+use app_window::input::keyboard::key::KeyboardKey;
+use app_window::input::mouse::{MOUSE_BUTTON_LEFT, Mouse};
+use some_executor::task::{Configuration, Task};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const EVENTS: usize = 1_000_000;
+/// Time between reports from an 8kHz mouse.
+const REPORT_INTERVAL: Duration = Duration::from_micros(125);
+const REQUIRED_HEADROOM: f64 = 10.0;
+
+fn main() {
+    logwise::warn_sync!("=== Input Hot Path Benchmark ===");
+
+    app_window::application::main(|| {
+        thread::spawn(|| {
+            let t = Task::without_notifications(
+                "input_hot_path_benchmark".to_string(),
+                Configuration::default(),
+                async {
+                    let ok = run_benchmark().await;
+                    std::process::exit(if ok { 0 } else { 1 });
+                },
+            );
+            t.spawn_static_current();
+        });
+    });
+}
+
+async fn run_benchmark() -> bool {
+    let mouse = Arc::new(Mouse::coalesced().await);
+    let keyboard = Keyboard::coalesced().await;
+
+    let done = Arc::new(AtomicBool::new(false));
+    let reader = {
+        let mouse = mouse.clone();
+        let done = done.clone();
+        thread::spawn(move || {
+            let mut reads = 0usize;
+            while !done.load(Ordering::Relaxed) {
+                std::hint::black_box(mouse.window_pos());
+                std::hint::black_box(mouse.button_state(MOUSE_BUTTON_LEFT));
+                reads += 1;
+            }
+            reads
+        })
+    };
+
+    let start = Instant::now();
+    for i in 0..EVENTS {
+        let v = i as f64;
+        mouse.inject_position(v, v, 1920.0, 1080.0);
+        if i % 16 == 0 {
+            mouse.inject_button(MOUSE_BUTTON_LEFT, i % 32 == 0);
+            keyboard.inject_key(KeyboardKey::Space, i % 32 == 0);
+        }
+    }
+    let elapsed = start.elapsed();
+    done.store(true, Ordering::Relaxed);
+    let reads = reader.join().unwrap();
+
+    let per_event = elapsed / EVENTS as u32;
+    let headroom = REPORT_INTERVAL.as_secs_f64() / per_event.as_secs_f64().max(1e-12);
+    logwise::warn_sync!(
+        "{events} events in {elapsed}, {reads} concurrent reads",
+        events = EVENTS,
+        elapsed = format!("{elapsed:?}"),
+        reads = reads
+    );
+    logwise::warn_sync!(
+        "Per event: {per_event}, headroom at 8kHz: {headroom}x",
+        per_event = format!("{per_event:?}"),
+        headroom = format!("{headroom:.0}")
+    );
+    if headroom < REQUIRED_HEADROOM {
+        logwise::error_sync!(
+            "Headroom {headroom}x is below the required {required}x",
+            headroom = format!("{headroom:.1}"),
+            required = REQUIRED_HEADROOM
+        );
+        return false;
+    }
+    true
+}